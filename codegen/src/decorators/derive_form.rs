@@ -1,19 +1,22 @@
 #![allow(unused_imports)] // FIXME: Why is this coming from quote_tokens?
 
 use std::mem::transmute;
+use std::str::FromStr;
 
 use syntax::ext::base::{Annotatable, ExtCtxt};
 use syntax::print::pprust::{stmt_to_string};
-use syntax::ast::{ItemKind, Expr, MetaItem, Mutability, VariantData, Ident};
+use syntax::ast::{ItemKind, Expr, MetaItem, Mutability, VariantData, Ident, LitKind};
+use syntax::ast::{StructField, Ty, TyKind};
 use syntax::codemap::Span;
 use syntax::ext::build::AstBuilder;
+use syntax::parse::parse_expr_from_source_str;
 use syntax::ptr::P;
 
 use syntax_ext::deriving::generic::MethodDef;
 use syntax_ext::deriving::generic::{StaticStruct, Substructure, TraitDef, ty};
 use syntax_ext::deriving::generic::combine_substructure as c_s;
 
-use utils::strip_ty_lifetimes;
+use utils::{strip_ty_lifetimes, MetaItemExt};
 
 static ONLY_STRUCTS_ERR: &'static str = "`FromForm` can only be derived for \
     structures with named fields.";
@@ -49,6 +52,249 @@ fn get_struct_lifetime(ecx: &mut ExtCtxt, item: &Annotatable, span: Span)
     }
 }
 
+// Returns a `&'lifetime str` type.
+fn str_ty(lifetime: Option<&'static str>) -> ty::Ty<'static> {
+    ty::Ptr(
+        Box::new(ty::Literal(ty::Path::new_local("str"))),
+        ty::Borrowed(lifetime, Mutability::Immutable)
+    )
+}
+
+// Returns a `Result<Self, error_type>` type.
+fn result_ty(error_type: &ty::Ty<'static>) -> ty::Ty<'static> {
+    ty::Ty::Literal(
+        ty::Path {
+            path: vec!["std", "result", "Result"],
+            lifetime: None,
+            params: vec![
+                Box::new(ty::Ty::Self_),
+                Box::new(error_type.clone())
+            ],
+            global: true,
+        }
+    )
+}
+
+// Returns the key syntax of the structure, from its `#[form(syntax = "..")]`
+// attribute, if any: `true` for brackets and `false` for dots.
+fn get_brackets_syntax(ecx: &mut ExtCtxt, item: &Annotatable) -> bool {
+    let attrs = match *item {
+        Annotatable::Item(ref item) => &item.attrs,
+        _ => return false
+    };
+
+    let mut brackets = false;
+    for attr in attrs.iter().filter(|attr| attr.check_name("form")) {
+        let params = attr.meta_item_list().unwrap_or(&[]);
+        for param in params {
+            let syntax = match param.name_value() {
+                Some((name, lit)) if &*name.as_str() == "syntax" => match lit.node {
+                    LitKind::Str(ref s, _) => Some(s.as_str().to_string()),
+                    _ => None
+                },
+                _ => None
+            };
+
+            match syntax.as_ref().map(|s| s.as_str()) {
+                Some("dots") => brackets = false,
+                Some("brackets") => brackets = true,
+                _ => {
+                    ecx.struct_span_err(param.span, "unknown `form` parameter")
+                        .help(r#"the syntax of the keys of nested fields is set via
+                              `syntax = "dots"` or `syntax = "brackets"`"#)
+                        .emit();
+                }
+            }
+        }
+    }
+
+    brackets
+}
+
+// The default value of a structure field that's missing from the form.
+#[derive(Debug)]
+enum FieldDefault {
+    // From `default = "value"`: the field is parsed as if the form had
+    // `value` as the field's value.
+    Value(String),
+    // From `default`: the field's type's `Default` value.
+    Trait,
+}
+
+// The parameters of the `form` attributes of a structure field.
+struct FieldAttrs {
+    // The name of the form field, from `field = "..."` or the identifier.
+    name: String,
+    // Whether the field is never read from the form, via `skip`.
+    skip: bool,
+    // The value of the field when it's missing, from `default`.
+    default: Option<FieldDefault>,
+    // The validators of the field's value, from each `validate = "expr"`.
+    validators: Vec<P<Expr>>,
+}
+
+// Returns the parameters of the `#[form(..)]` attributes of `field`.
+fn get_field_attrs(ecx: &mut ExtCtxt, field: &StructField, ident: Ident) -> FieldAttrs {
+    let mut attrs = FieldAttrs {
+        name: ident.to_string(),
+        skip: false,
+        default: None,
+        validators: vec![]
+    };
+
+    for attr in field.attrs.iter().filter(|attr| attr.check_name("form")) {
+        let params = attr.meta_item_list().unwrap_or(&[]);
+        for param in params {
+            if let Some((key, lit)) = param.name_value() {
+                match &*key.as_str() {
+                    "field" => match lit.node {
+                        LitKind::Str(ref s, _) if !s.as_str().is_empty() => {
+                            attrs.name = s.as_str().to_string();
+                        }
+                        _ => {
+                            ecx.span_err(lit.span, "`field` must be a non-empty string");
+                        }
+                    },
+                    "default" => match lit.node {
+                        LitKind::Str(ref s, _) => {
+                            let value = s.as_str().to_string();
+                            if let Err(expected) = check_default(&field.ty, &value) {
+                                let msg = format!("`{}` is not a valid `{}`", value, expected);
+                                ecx.struct_span_err(lit.span, &msg)
+                                    .help("the default is parsed like a value in a form")
+                                    .emit();
+                            }
+
+                            attrs.default = Some(FieldDefault::Value(value));
+                        }
+                        _ => {
+                            ecx.struct_span_err(lit.span, "`default` must be a string")
+                                .help(r#"the default is parsed like a value in a form, as
+                                      in `default = "10"`"#)
+                                .emit();
+                        }
+                    },
+                    "validate" => match lit.node {
+                        LitKind::Str(ref s, _) => {
+                            let source = s.as_str().to_string();
+                            match parse_expr_from_source_str("<validate>".into(), source,
+                                                             ecx.cfg(), ecx.parse_sess) {
+                                Ok(expr) => attrs.validators.push(expr),
+                                Err(mut e) => {
+                                    e.cancel();
+                                    ecx.span_err(lit.span,
+                                                 "`validate` must be a valid expression");
+                                }
+                            }
+                        }
+                        _ => {
+                            ecx.struct_span_err(lit.span, "`validate` must be a string")
+                                .help(r#"a validator is an expression in a string, as in
+                                      `validate = "range(1..100)"`"#)
+                                .emit();
+                        }
+                    },
+                    _ => {
+                        ecx.span_err(param.span, "unknown `form` parameter");
+                    }
+                }
+
+                continue;
+            }
+
+            match param.meta_item().map(|mi| (mi.is_word(), mi.name.as_str())) {
+                Some((true, ref name)) if &**name == "skip" => attrs.skip = true,
+                Some((true, ref name)) if &**name == "default" => {
+                    attrs.default = Some(FieldDefault::Trait);
+                }
+                _ => {
+                    ecx.struct_span_err(param.span, "unknown `form` parameter")
+                        .help(r#"a field is renamed via `field = "name"`, skipped via
+                              `skip`, and given a default via `default`"#)
+                        .emit();
+                }
+            }
+        }
+    }
+
+    attrs
+}
+
+// Checks that `value`, the `default` of a field of type `ty`, parses as a form
+// value of that type, returning the name of the type if it doesn't. Only `bool`
+// and the primitive types parsed via `FromStr` are checked here; a default for
+// a field of another type that doesn't parse fails parsing with `BadDefault`.
+fn check_default(ty: &Ty, value: &str) -> Result<(), &'static str> {
+    let name = match ty.node {
+        TyKind::Path(None, ref path) => match path.segments.last() {
+            Some(segment) => segment.identifier.name.as_str(),
+            None => return Ok(())
+        },
+        _ => return Ok(())
+    };
+
+    fn parses<T: FromStr>(value: &str) -> bool {
+        value.parse::<T>().is_ok()
+    }
+
+    let (valid, expected) = match &*name {
+        "bool" => match &*value.to_lowercase() {
+            "on" | "true" | "yes" | "1" | "off" | "false" | "no" | "0" => (true, "bool"),
+            _ => (false, "bool")
+        },
+        "f32" => (parses::<f32>(value), "f32"),
+        "f64" => (parses::<f64>(value), "f64"),
+        "isize" => (parses::<isize>(value), "isize"),
+        "i8" => (parses::<i8>(value), "i8"),
+        "i16" => (parses::<i16>(value), "i16"),
+        "i32" => (parses::<i32>(value), "i32"),
+        "i64" => (parses::<i64>(value), "i64"),
+        "usize" => (parses::<usize>(value), "usize"),
+        "u8" => (parses::<u8>(value), "u8"),
+        "u16" => (parses::<u16>(value), "u16"),
+        "u32" => (parses::<u32>(value), "u32"),
+        "u64" => (parses::<u64>(value), "u64"),
+        _ => return Ok(())
+    };
+
+    if valid { Ok(()) } else { Err(expected) }
+}
+
+// Returns the definition of a method of `FromForm` named `name` that parses a
+// form string by delegating to `FromFormItems`, failing on unexpected fields
+// only if `strict` is `true`. Keys use the brackets syntax if `brackets`. The
+// method fails with the first of the errors `FromFormItems` reports.
+fn from_form_method(name: &'static str, lifetime: Option<&'static str>,
+                    error_type: &ty::Ty<'static>, strict: bool, brackets: bool)
+                    -> MethodDef<'static> {
+    MethodDef {
+        name: name,
+        generics: ty::LifetimeBounds::empty(),
+        explicit_self: None,
+        args: vec![str_ty(lifetime)],
+        ret_ty: result_ty(error_type),
+        attributes: vec![],
+        is_unsafe: false,
+        combine_substructure: c_s(Box::new(move |cx: &mut ExtCtxt, span: Span,
+                                                 substr: &Substructure| {
+            let arg = &substr.nonself_args[0];
+            let strict = cx.expr_bool(span, strict);
+            let syntax = if brackets {
+                quote_expr!(cx, ::rocket::request::FormSyntax::Brackets)
+            } else {
+                quote_expr!(cx, ::rocket::request::FormSyntax::Dots)
+            };
+
+            quote_expr!(cx,
+                <Self as ::rocket::request::FromFormItems>::from_form_items(
+                    ::rocket::request::FormItems($arg).collect(), $strict, $syntax)
+                    .map_err(::rocket::request::FormErrors::into_first)
+            )
+        })),
+        unify_fieldless_variants: false,
+    }
+}
+
 // TODO: Use proper logging to emit the error messages.
 pub fn from_form_derive(ecx: &mut ExtCtxt, span: Span, meta_item: &MetaItem,
           annotated: &Annotatable, push: &mut FnMut(Annotatable)) {
@@ -61,8 +307,12 @@ pub fn from_form_derive(ecx: &mut ExtCtxt, span: Span, meta_item: &MetaItem,
             })
     };
 
-    // The error type in the derived implementation.
-    let error_type = ty::Ty::Literal(ty::Path::new(vec!["rocket", "Error"]));
+    // The syntax of the keys of nested fields.
+    let brackets = get_brackets_syntax(ecx, annotated);
+
+    // The error types in the derived implementations.
+    let error_type = ty::Ty::Literal(ty::Path::new(vec!["rocket", "request", "FormError"]));
+    let errors_type = ty::Ty::Literal(ty::Path::new(vec!["rocket", "request", "FormErrors"]));
 
     let trait_def = TraitDef {
         is_unsafe: false,
@@ -76,55 +326,121 @@ pub fn from_form_derive(ecx: &mut ExtCtxt, span: Span, meta_item: &MetaItem,
             global: true,
         },
         additional_bounds: Vec::new(),
-        generics: trait_generics,
+        generics: trait_generics.clone(),
+        methods: vec![
+            from_form_method("from_form_string", lifetime_var, &error_type, true, brackets),
+            from_form_method("from_form_string_lenient", lifetime_var, &error_type, false,
+                             brackets),
+        ],
+        associated_types: vec![
+            (Ident::from_str("Error"), error_type.clone())
+        ],
+    };
+
+    trait_def.expand(ecx, meta_item, annotated, push);
+
+    // The items are a `Vec<(&'f str, &'f str)>`.
+    let items_type = ty::Ty::Literal(ty::Path {
+        path: vec!["std", "vec", "Vec"],
+        lifetime: None,
+        params: vec![Box::new(ty::Tuple(vec![str_ty(lifetime_var), str_ty(lifetime_var)]))],
+        global: true,
+    });
+
+    let items_trait_def = TraitDef {
+        is_unsafe: false,
+        supports_unions: false,
+        span: span,
+        attributes: Vec::new(),
+        path: ty::Path {
+            path: vec!["rocket", "request", "FromFormItems"],
+            lifetime: lifetime_var,
+            params: vec![],
+            global: true,
+        },
+        additional_bounds: Vec::new(),
+        generics: trait_generics.clone(),
         methods: vec![
             MethodDef {
-                name: "from_form_string",
+                name: "from_form_items",
                 generics: ty::LifetimeBounds::empty(),
                 explicit_self: None,
                 args: vec![
-                    ty::Ptr(
-                        Box::new(ty::Literal(ty::Path::new_local("str"))),
-                        ty::Borrowed(lifetime_var, Mutability::Immutable)
-                    )
+                    items_type,
+                    ty::Literal(ty::Path::new_local("bool")),
+                    ty::Literal(ty::Path::new(vec!["rocket", "request", "FormSyntax"])),
                 ],
-                ret_ty: ty::Ty::Literal(
-                    ty::Path {
-                        path: vec!["std", "result", "Result"],
-                        lifetime: None,
-                        params: vec![
-                            Box::new(ty::Ty::Self_),
-                            Box::new(error_type.clone())
-                        ],
-                        global: true,
-                    }
-                ),
+                ret_ty: result_ty(&errors_type),
                 attributes: vec![],
                 is_unsafe: false,
                 combine_substructure: c_s(Box::new(from_form_substructure)),
                 unify_fieldless_variants: false,
+            },
+            MethodDef {
+                name: "form_syntax",
+                generics: ty::LifetimeBounds::empty(),
+                explicit_self: None,
+                args: vec![],
+                ret_ty: ty::Literal(ty::Path::new(vec!["rocket", "request", "FormSyntax"])),
+                attributes: vec![],
+                is_unsafe: false,
+                combine_substructure: c_s(Box::new(move |cx: &mut ExtCtxt, _: Span,
+                                                         _: &Substructure| {
+                    if brackets {
+                        quote_expr!(cx, ::rocket::request::FormSyntax::Brackets)
+                    } else {
+                        quote_expr!(cx, ::rocket::request::FormSyntax::Dots)
+                    }
+                })),
+                unify_fieldless_variants: false,
             }
         ],
-        associated_types: vec![
-            (Ident::from_str("Error"), error_type.clone())
-        ],
+        associated_types: vec![],
     };
 
-    trait_def.expand(ecx, meta_item, annotated, push);
+    items_trait_def.expand(ecx, meta_item, annotated, push);
+
+    // The structure can be a field of another structure deriving `FromForm`.
+    let field_trait_def = TraitDef {
+        is_unsafe: false,
+        supports_unions: false,
+        span: span,
+        attributes: Vec::new(),
+        path: ty::Path {
+            path: vec!["rocket", "request", "FormField"],
+            lifetime: lifetime_var,
+            params: vec![],
+            global: true,
+        },
+        additional_bounds: Vec::new(),
+        generics: trait_generics,
+        methods: vec![],
+        associated_types: vec![],
+    };
+
+    field_trait_def.expand(ecx, meta_item, annotated, push);
 }
 
-fn from_form_substructure(cx: &mut ExtCtxt, trait_span: Span, substr: &Substructure) -> P<Expr> {
+// Generates the body of `FromFormItems::from_form_items`, which parses the
+// structure from a vector of form items whose keys use its `syntax` argument,
+// ignoring unexpected fields if its `strict` argument is `false`. The items for
+// each field, including those with keys like `field.rest` and `field[0]`, are
+// collected and parsed together, as a value, a nested form, or a collection,
+// depending on the field's type, and then checked by the field's validators.
+// Every error is collected, so that all of them are reported at once.
+fn from_form_substructure(cx: &mut ExtCtxt, trait_span: Span, substr: &Substructure)
+        -> P<Expr> {
     // Check that we specified the methods to the argument correctly.
-    const EXPECTED_ARGS: usize = 1;
-    let arg = if substr.nonself_args.len() == EXPECTED_ARGS {
-        &substr.nonself_args[0]
+    const EXPECTED_ARGS: usize = 3;
+    let (items, strict, syntax) = if substr.nonself_args.len() == EXPECTED_ARGS {
+        (&substr.nonself_args[0], &substr.nonself_args[1], &substr.nonself_args[2])
     } else {
-        let msg = format!("incorrect number of arguments in `from_form_string`: \
+        let msg = format!("incorrect number of arguments in `from_form_items`: \
             expected {}, found {}", EXPECTED_ARGS, substr.nonself_args.len());
         cx.span_bug(trait_span, msg.as_str());
     };
 
-    debug!("argument is: {:?}", arg);
+    debug!("arguments are: {:?}, {:?}, {:?}", items, strict, syntax);
 
     // Ensure the the fields are from a 'StaticStruct' and extract them.
     let fields = match *substr.fields {
@@ -135,115 +451,250 @@ fn from_form_substructure(cx: &mut ExtCtxt, trait_span: Span, substr: &Substruct
         _ => cx.span_bug(trait_span, "impossible substructure in `from_form`")
     };
 
-    // Create a vector of (ident, type) pairs, one for each field in struct.
+    // Create a vector of (ident, form field name, type, default) tuples, one
+    // for each field in the struct that's parsed, and a vector of the
+    // identifiers of the skipped fields that are always their `Default` value.
+    // A skipped field with a `default = "value"` is parsed from its value, but
+    // isn't in the form: it's in `fields_and_types` but not in `field_names`.
     let mut fields_and_types = vec![];
+    let mut field_names = vec![];
+    let mut skipped_fields = vec![];
     for field in fields {
         let ident = match field.ident {
             Some(ident) => ident,
             None => cx.span_fatal(trait_span, ONLY_STRUCTS_ERR)
         };
 
+        let FieldAttrs { name, skip, default, validators } = get_field_attrs(cx, field, ident);
+        if skip {
+            match default {
+                Some(FieldDefault::Value(_)) => { /* parsed from the default */ }
+                _ => {
+                    skipped_fields.push(ident);
+                    continue;
+                }
+            }
+        } else {
+            if field_names.contains(&name) {
+                cx.span_err(field.span, &format!("duplicate form field `{}`", name));
+            }
+
+            field_names.push(name.clone());
+        }
+
         let stripped_ty = strip_ty_lifetimes(field.ty.clone());
-        fields_and_types.push((ident, stripped_ty));
+        fields_and_types.push((ident, name, stripped_ty, default, validators));
     }
 
     debug!("Fields and types: {:?}", fields_and_types);
     let mut stmts = Vec::new();
 
-    // The thing to do when we wish to exit with an error.
-    let return_err_stmt = quote_tokens!(cx,
-        return Err(::rocket::Error::BadParse)
-    );
+    // Fail to compile if a field's type can't be parsed from a form.
+    for &(_, _, ref ty, _, _) in &fields_and_types {
+        stmts.push(quote_stmt!(cx,
+            ::rocket::request::assert_form_field::<$ty>();
+        ).unwrap());
+    }
 
     // Generate the let bindings for parameters that will be unwrapped and
     // placed into the final struct. They start out as `None` and are changed
     // to Some when a parse completes, or some default value if the parse was
     // unsuccessful and default() returns Some.
-    for &(ref ident, ref ty) in &fields_and_types {
+    for &(ref ident, _, ref ty, _, _) in &fields_and_types {
         stmts.push(quote_stmt!(cx,
             let mut $ident: ::std::option::Option<$ty> = None;
         ).unwrap());
     }
 
-    // Generating an arm for each struct field. This matches against the key and
-    // tries to parse the value according to the type.
-    let mut arms = vec![];
-    for &(ref ident, _) in &fields_and_types {
-        let ident_string = ident.to_string();
-        let id_str = ident_string.as_str();
-        arms.push(quote_tokens!(cx,
-            $id_str => {
-                $ident = match ::rocket::request::FromFormValue::from_form_value(v) {
-                    Ok(v) => Some(v),
-                    Err(e) => {
-                        println!("    => Error parsing form val '{}': {:?}",
-                                 $id_str, e);
-                        $return_err_stmt
-                    }
-                };
-            },
-        ));
-    }
+    // The names of the fields, used to recognize the items for each field, and
+    // those items, as (field, rest of key, value), collected while iterating.
+    let field_names: Vec<_> = field_names.iter().map(|name| {
+        let id_str = name.as_str();
+        quote_tokens!(cx, $id_str,)
+    }).collect();
+
+    stmts.push(quote_stmt!(cx,
+        let __rocket_fields: &[&str] = &[$field_names];
+    ).unwrap());
+
+    stmts.push(quote_stmt!(cx,
+        let mut __rocket_items = vec![];
+    ).unwrap());
+
+    // The errors, and the names of the fields that failed to parse, which
+    // aren't also reported as missing.
+    stmts.push(quote_stmt!(cx,
+        let mut __rocket_errors = ::rocket::request::FormErrors::new();
+    ).unwrap());
 
-    // The actual match statement. Iterate through all of the fields in the form
-    // and use the $arms generated above.
     stmts.push(quote_stmt!(cx,
-        for (k, v) in ::rocket::request::FormItems($arg) {
+        #[allow(unused_mut)]
+        let mut __rocket_failed: Vec<&str> = vec![];
+    ).unwrap());
+
+    // The actual loop. Iterate through all of the items in the form, splitting
+    // each key into a field name and the rest of the key, as in `tags` and
+    // `[0]`, and collect the items for each field. A key for a field that isn't
+    // in the structure fails parsing if `strict`, and is otherwise let go by.
+    stmts.push(quote_stmt!(cx,
+        for (k, v) in $items {
+            let (name, rest) = ::rocket::request::split_form_key(k, $syntax);
+
+            if __rocket_fields.contains(&name) {
+                __rocket_items.push((name, rest, v));
+                continue;
+            }
+
             match k {
-                $arms
                 field if field == "_method" => {
                     /* This is a Rocket-specific field. If the user hasn't asked
                      * for it, just let it go by without error. This should stay
                      * in sync with Rocket::preprocess. */
                 }
-                _ => {
+                _ if $strict => {
                     println!("    => {}={} has no matching field in struct.",
                              k, v);
-                    $return_err_stmt
+                    __rocket_errors.push(::rocket::request::FormError::Unexpected {
+                        field: k.to_string()
+                    });
+                }
+                _ => {
+                    println!("    => Ignoring {}={}: no matching field in struct.",
+                             k, v);
                 }
            };
        }
     ).unwrap());
 
-    // This looks complicated but just generates the boolean condition checking
-    // that each parameter actually is Some() or has a default value.
-    let mut failure_conditions = vec![];
+    // Parse each field from its items as a value, nested form, or collection.
+    // A field with a `default = "value"` and no items is parsed from `value`; if
+    // that fails, the structure, not the form, is at fault: the error is a
+    // `BadDefault`, never blamed on the client.
+    // A parsed value is then passed to each of the field's validators.
+    for &(ref ident, ref name, ref ty, ref default, ref validators) in &fields_and_types {
+        let id_str = name.as_str();
+        let default_items = match *default {
+            Some(FieldDefault::Value(ref value)) => {
+                let value = value.as_str();
+                quote_tokens!(cx,
+                    let __rocket_is_default = items.is_empty();
+                    let items = if __rocket_is_default { vec![("", $value)] } else { items };
+                )
+            }
+            _ => quote_tokens!(cx, let __rocket_is_default = false;)
+        };
 
-    // Start with `false` in case there are no fields.
-    failure_conditions.push(quote_tokens!(cx, false));
+        let validations: Vec<_> = validators.iter().map(|validator| quote_tokens!(cx,
+            {
+                #[allow(unused_imports)]
+                use ::rocket::request::validate::*;
+                let __rocket_validator = $validator;
+                let __rocket_result =
+                    <_ as ::rocket::request::validate::Validator<$ty>>::validate(
+                        &__rocket_validator, __rocket_value);
+                if let Err(message) = __rocket_result {
+                    println!("    => Form field '{}' is invalid.", $id_str);
+                    __rocket_errors.push(::rocket::request::FormError::Invalid {
+                        field: $id_str.to_string(),
+                        message: message
+                    });
+                }
+            }
+        )).collect();
 
-    for &(ref ident, ref ty) in (&fields_and_types).iter() {
-        // Pushing an "||" (or) between every condition.
-        failure_conditions.push(quote_tokens!(cx, ||));
+        stmts.push(quote_stmt!(cx,
+            {
+                let items: Vec<_> = __rocket_items.iter()
+                    .filter(|&&(field, _, _)| field == $id_str)
+                    .map(|&(_, rest, value)| (rest, value))
+                    .collect();
 
-        failure_conditions.push(quote_tokens!(cx,
-            if $ident.is_none() &&
-                <$ty as ::rocket::request::FromFormValue>::default().is_none() {
-                println!("    => '{}' did not parse.", stringify!($ident));
-                true
-            } else { false }
-        ));
+                $default_items
+
+                if !items.is_empty() {
+                    let result = ::rocket::request::from_form_field::<$ty>(items, $strict,
+                                                                          $syntax,
+                                                                          stringify!($ty));
+                    match result {
+                        Ok(Some(v)) => $ident = Some(v),
+                        Ok(None) => {
+                            println!("    => Ignoring items for '{}'.", $id_str);
+                        }
+                        Err(_) if __rocket_is_default => {
+                            println!("    => Default of form field '{}' is invalid.", $id_str);
+                            __rocket_errors.push(::rocket::request::FormError::BadDefault {
+                                field: $id_str.to_string(),
+                                expected: stringify!($ty)
+                            });
+                            __rocket_failed.push($id_str);
+                        }
+                        Err(e) => {
+                            println!("    => Error parsing form field '{}'.", $id_str);
+                            __rocket_errors.append(e.prefixed($id_str));
+                            __rocket_failed.push($id_str);
+                        }
+                    }
+                }
+
+                if let Some(ref __rocket_value) = $ident {
+                    $validations
+                }
+            }
+        ).unwrap());
+    }
+
+    // Check that each parameter that parsed actually is Some() or has a default
+    // value, recording an error for each that isn't. Fields with a `default`
+    // without a value always have one.
+    for &(ref ident, ref name, ref ty, ref default, _) in &fields_and_types {
+        if let Some(FieldDefault::Trait) = *default {
+            continue;
+        }
+
+        let id_str = name.as_str();
+        stmts.push(quote_stmt!(cx,
+            if $ident.is_none() && !__rocket_failed.contains(&$id_str) &&
+                ::rocket::request::form_field_default::<$ty>($strict).is_none() {
+                println!("    => '{}' did not parse.", $id_str);
+                __rocket_errors.push(::rocket::request::FormError::Missing {
+                    field: $id_str.to_string(),
+                    expected: stringify!($ty)
+                });
+            }
+        ).unwrap());
     }
 
     // The fields of the struct, which are just the let bindings declared above
-    // or the default value.
+    // or the default value: the type's `Default` value for fields with a
+    // `default` without a value, and `form_field_default` otherwise. Skipped
+    // fields without a default value are always the `Default` value.
+    stmts.push(quote_stmt!(cx,
+        if !__rocket_errors.is_empty() {
+            return Err(__rocket_errors);
+        }
+    ).unwrap());
+
     let mut result_fields = vec![];
-    for &(ref ident, ref ty) in &fields_and_types {
+    for &(ref ident, _, ref ty, ref default, _) in &fields_and_types {
+        let default = match *default {
+            Some(FieldDefault::Trait) => quote_expr!(cx, ::std::default::Default::default()),
+            _ => quote_expr!(cx, ::rocket::request::form_field_default::<$ty>($strict).unwrap())
+        };
+
+        result_fields.push(quote_tokens!(cx,
+            $ident: $ident.unwrap_or_else(|| $default),
+        ));
+    }
+
+    for ident in &skipped_fields {
         result_fields.push(quote_tokens!(cx,
-            $ident: $ident.unwrap_or_else(||
-                <$ty as ::rocket::request::FromFormValue>::default().unwrap()
-            ),
+            $ident: ::std::default::Default::default(),
         ));
     }
 
-    // The final block: check the error conditions, and if all is well, return
-    // the structure.
+    // The final block: all is well, so return the structure.
     let self_ident = substr.type_ident;
     let final_block = quote_block!(cx, {
-        if $failure_conditions {
-            $return_err_stmt;
-        }
-
         return Ok($self_ident {
             $result_fields
         });
@@ -0,0 +1,305 @@
+use std::str::FromStr;
+
+use syntax::ast::{ItemKind, MetaItem, VariantData, Attribute, LitKind, Generics, StructField};
+use syntax::codemap::Span;
+use syntax::ext::base::{Annotatable, ExtCtxt};
+use syntax::parse::parse_item_from_source_str;
+use syntax::print::pprust::{ty_to_string, generics_to_string};
+
+use rocket::http::{ContentType, Status};
+use utils::{emit_item, MetaItemExt};
+
+static ONLY_ITEMS_ERR: &'static str = "`Responder` can only be derived for structures \
+    and enums.";
+
+// The parameters of the `response` attribute of an item or variant.
+#[derive(Debug, Clone, Default)]
+struct ResponseAttrs {
+    // The status to set, from `status = 404`.
+    status: Option<u16>,
+    // The Content-Type to set, from `content_type = "application/json"`.
+    content_type: Option<String>,
+}
+
+impl ResponseAttrs {
+    // Returns `self` with the parameters it doesn't set taken from `defaults`.
+    fn or(self, defaults: &ResponseAttrs) -> ResponseAttrs {
+        ResponseAttrs {
+            status: self.status.or(defaults.status),
+            content_type: self.content_type.or_else(|| defaults.content_type.clone()),
+        }
+    }
+}
+
+// How a field contributes to the response.
+#[derive(Debug, PartialEq)]
+enum FieldKind {
+    // The field is the inner responder: the field without a `header`.
+    Responder,
+    // From `header = "Name"`: the field's `Display` value is the value of the
+    // header `Name`.
+    NamedHeader(String),
+    // From `header`: the field is converted `Into<Header>`.
+    Header,
+}
+
+// Returns the parameters of the `#[response(..)]` attributes in `attrs`, which
+// are those of an item or variant.
+fn get_response_attrs(ecx: &mut ExtCtxt, attrs: &[Attribute]) -> ResponseAttrs {
+    let mut response = ResponseAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.check_name("response")) {
+        let params = attr.meta_item_list().unwrap_or(&[]);
+        for param in params {
+            let (name, lit) = match param.name_value() {
+                Some((name, lit)) => (name.as_str().to_string(), lit),
+                None => {
+                    ecx.struct_span_err(param.span, "unknown `response` parameter")
+                        .help(r#"the status and Content-Type of a response are set via
+                              `status = 404` and `content_type = "text/plain"`"#)
+                        .emit();
+                    continue;
+                }
+            };
+
+            match (name.as_str(), &lit.node) {
+                ("status", &LitKind::Int(n, _)) => {
+                    if n < 100 || n > 599 {
+                        ecx.span_err(lit.span, "status must be >= 100 and <= 599");
+                    } else {
+                        if Status::from_code(n as u16).is_none() {
+                            ecx.span_warn(lit.span, "status code is unknown");
+                        }
+
+                        response.status = Some(n as u16);
+                    }
+                }
+                ("status", _) => ecx.span_err(lit.span, "`status` must be an integer"),
+                ("content_type", &LitKind::Str(ref s, _)) => {
+                    match ContentType::from_str(&s.as_str()) {
+                        Ok(ct) => {
+                            if !ct.is_known() {
+                                let msg = format!("'{}' is not a known content-type", s);
+                                ecx.span_warn(lit.span, &msg);
+                            }
+
+                            response.content_type = Some(s.as_str().to_string());
+                        }
+                        Err(_) => ecx.span_err(lit.span, "malformed content-type")
+                    }
+                }
+                ("content_type", _) => {
+                    ecx.struct_span_err(lit.span, r#"`content_type` must be a "content/type""#)
+                        .help(r#"the Content-Type is a string, as in
+                              `content_type = "application/json"`"#)
+                        .emit();
+                }
+                _ => ecx.span_err(param.span, "unknown `response` parameter")
+            }
+        }
+    }
+
+    response
+}
+
+// Returns how `field` contributes to the response, from its `#[response(..)]`
+// attributes.
+fn get_field_kind(ecx: &mut ExtCtxt, field: &StructField) -> FieldKind {
+    let mut kind = FieldKind::Responder;
+    for attr in field.attrs.iter().filter(|attr| attr.check_name("response")) {
+        let params = attr.meta_item_list().unwrap_or(&[]);
+        for param in params {
+            if let Some((name, lit)) = param.name_value() {
+                match (&*name.as_str(), &lit.node) {
+                    ("header", &LitKind::Str(ref s, _)) if !s.as_str().is_empty() => {
+                        kind = FieldKind::NamedHeader(s.as_str().to_string());
+                    }
+                    ("header", _) => {
+                        ecx.span_err(lit.span, "`header` must be a non-empty string");
+                    }
+                    _ => ecx.span_err(param.span, "unknown `response` parameter")
+                }
+
+                continue;
+            }
+
+            match param.meta_item().map(|mi| (mi.is_word(), mi.name.as_str())) {
+                Some((true, ref name)) if &**name == "header" => kind = FieldKind::Header,
+                _ => {
+                    ecx.struct_span_err(param.span, "unknown `response` parameter")
+                        .help(r#"a field is sent as a header via `header = "Name"`, or
+                              via `header` if it converts into a `Header`"#)
+                        .emit();
+                }
+            }
+        }
+    }
+
+    kind
+}
+
+// Returns the source of a match arm, for the structure or variant with the
+// path `path` and fields `data`, that builds the response.
+fn arm_source(ecx: &mut ExtCtxt, path: &str, data: &VariantData, attrs: &ResponseAttrs,
+              inner_tys: &mut Vec<String>) -> String {
+    let mut bindings = vec![];
+    let mut responder = None;
+    let mut headers = vec![];
+    for (i, field) in data.fields().iter().enumerate() {
+        let binding = format!("__rocket_field_{}", i);
+        bindings.push(match field.ident {
+            Some(ident) => format!("{}: {}", ident, binding),
+            None => binding.clone()
+        });
+
+        match get_field_kind(ecx, field) {
+            FieldKind::Responder if responder.is_some() => {
+                ecx.struct_span_err(field.span, "only one field may be the inner responder")
+                    .help(r#"every other field must be sent as a header, via
+                          `#[response(header = "Name")]` or `#[response(header)]`"#)
+                    .emit();
+            }
+            FieldKind::Responder => {
+                inner_tys.push(ty_to_string(&field.ty));
+                responder = Some(binding);
+            }
+            FieldKind::NamedHeader(name) => headers.push(format!(
+                "::rocket::http::Header::new({:?}, ::std::string::ToString::to_string(&{}))",
+                name, binding)),
+            FieldKind::Header => headers.push(binding),
+        }
+    }
+
+    let pattern = match *data {
+        VariantData::Struct(..) => format!("{} {{ {} }}", path, bindings.join(", ")),
+        VariantData::Tuple(..) => format!("{}({})", path, bindings.join(", ")),
+        VariantData::Unit(..) => path.to_string(),
+    };
+
+    let mut body = match responder {
+        Some(binding) => format!(
+            "let mut __rocket_response = ::rocket::response::Responder::respond({})?;\n",
+            binding),
+        None => "let mut __rocket_response = ::rocket::Response::new();\n".to_string()
+    };
+
+    if let Some(status) = attrs.status {
+        body.push_str(&format!(
+            "__rocket_response.set_status(::rocket::http::Status::raw({}));\n", status));
+    }
+
+    // The Content-Type was validated above, so parsing it can't fail.
+    if let Some(ref content_type) = attrs.content_type {
+        headers.insert(0, format!(
+            "<::rocket::http::ContentType as ::std::str::FromStr>::from_str({:?}).unwrap()",
+            content_type));
+    }
+
+    for header in headers {
+        body.push_str(&format!(
+            "::rocket::response::set_derived_header(&mut __rocket_response, {})?;\n",
+            header));
+    }
+
+    format!("{} => {{\n{}Ok(__rocket_response)\n}}\n", pattern, body)
+}
+
+// Returns the source of the generics of the implementation and of the type,
+// and the lifetime of the `Responder` implementation: the item's lifetime, if
+// it has one, and `'static` otherwise.
+fn generics_source(ecx: &mut ExtCtxt, span: Span, generics: &Generics)
+        -> (String, String, String) {
+    if !generics.where_clause.predicates.is_empty() {
+        ecx.span_err(span, "`Responder` cannot be derived for items with a `where` clause");
+    }
+
+    let lifetime = match generics.lifetimes.len() {
+        0 => "'static".to_string(),
+        1 => generics.lifetimes[0].lifetime.name.to_string(),
+        _ => {
+            ecx.span_err(span, "cannot have more than one lifetime parameter when \
+                deriving `Responder`");
+            "'static".to_string()
+        }
+    };
+
+    let mut params: Vec<String> = generics.lifetimes.iter()
+        .map(|def| def.lifetime.name.to_string())
+        .collect();
+
+    params.extend(generics.ty_params.iter().map(|param| param.ident.to_string()));
+    let ty_generics = match params.is_empty() {
+        true => String::new(),
+        false => format!("<{}>", params.join(", "))
+    };
+
+    (generics_to_string(generics), ty_generics, lifetime)
+}
+
+pub fn responder_derive(ecx: &mut ExtCtxt, span: Span, _: &MetaItem,
+                        annotated: &Annotatable, push: &mut FnMut(Annotatable)) {
+    let item = match *annotated {
+        Annotatable::Item(ref item) => item,
+        _ => ecx.span_fatal(span, ONLY_ITEMS_ERR)
+    };
+
+    let name = item.ident.to_string();
+    let item_attrs = get_response_attrs(ecx, &item.attrs);
+    let mut inner_tys = vec![];
+    let (arms, generics) = match item.node {
+        ItemKind::Struct(ref data, ref generics) => {
+            let arm = arm_source(ecx, &name, data, &item_attrs, &mut inner_tys);
+            (arm, generics)
+        }
+        ItemKind::Enum(ref def, ref generics) => {
+            let mut arms = String::new();
+            for variant in &def.variants {
+                let attrs = get_response_attrs(ecx, &variant.node.attrs).or(&item_attrs);
+                let path = format!("{}::{}", name, variant.node.name);
+                arms.push_str(&arm_source(ecx, &path, &variant.node.data, &attrs,
+                                          &mut inner_tys));
+            }
+
+            (arms, generics)
+        }
+        _ => ecx.span_fatal(span, ONLY_ITEMS_ERR)
+    };
+
+    // Inner responders whose types use the item's type parameters are only
+    // responders if the parameters are suitable, so require that they are.
+    let (impl_generics, ty_generics, lifetime) = generics_source(ecx, item.span, generics);
+    let where_clause = match generics.ty_params.is_empty() || inner_tys.is_empty() {
+        true => String::new(),
+        false => {
+            let bounds: Vec<_> = inner_tys.iter()
+                .map(|ty| format!("{}: ::rocket::response::Responder<{}>", ty, lifetime))
+                .collect();
+
+            format!("where {}", bounds.join(", "))
+        }
+    };
+
+    let source = format!("
+        impl{impl_generics} ::rocket::response::Responder<{lifetime}>
+            for {name}{ty_generics} {where_clause}
+        {{
+            #[allow(unused_mut)]
+            fn respond(self) -> ::rocket::response::Result<{lifetime}> {{
+                match self {{
+                    {arms}
+                }}
+            }}
+        }}",
+        impl_generics = impl_generics, lifetime = lifetime, name = name,
+        ty_generics = ty_generics, where_clause = where_clause, arms = arms);
+
+    let result = parse_item_from_source_str("<derive_Responder>".into(), source,
+                                            ecx.cfg(), ecx.parse_sess);
+    match result {
+        Ok(Some(item)) => emit_item(push, item),
+        Ok(None) => ecx.span_bug(span, "empty `Responder` implementation"),
+        Err(mut e) => {
+            e.emit();
+            ecx.span_bug(span, "malformed `Responder` implementation");
+        }
+    }
+}
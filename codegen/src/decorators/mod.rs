@@ -1,8 +1,10 @@
 mod route;
 mod error;
 mod derive_form;
+mod derive_responder;
 
 pub use self::route::*;
 pub use self::error::*;
 pub use self::derive_form::*;
+pub use self::derive_responder::*;
 
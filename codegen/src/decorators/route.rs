@@ -1,7 +1,8 @@
 use std::collections::HashSet;
 use std::fmt::Display;
 
-use ::{ROUTE_STRUCT_PREFIX, ROUTE_FN_PREFIX, PARAM_PREFIX};
+use ::{ROUTE_STRUCT_PREFIX, ROUTE_FN_PREFIX, ROUTE_STATE_FN_PREFIX, PARAM_PREFIX};
+use ::ROUTE_PARAMS_FN_PREFIX;
 use utils::{emit_item, span, sep_by_tok, option_as_expr, strip_ty_lifetimes};
 use utils::{SpanExt, IdentExt, ArgExt};
 use parser::{Param, RouteParams};
@@ -35,14 +36,15 @@ fn content_type_to_expr(ecx: &ExtCtxt, ct: Option<ContentType>) -> Option<P<Expr
 }
 
 trait RouteGenerateExt {
-    fn gen_form(&self, &ExtCtxt, Option<&Spanned<Ident>>, P<Expr>) -> Option<Stmt>;
     fn missing_declared_err<T: Display>(&self, ecx: &ExtCtxt, arg: &Spanned<T>);
 
     fn generate_data_statement(&self, ecx: &ExtCtxt) -> Option<Stmt>;
     fn generate_query_statement(&self, ecx: &ExtCtxt) -> Option<Stmt>;
     fn generate_param_statements(&self, ecx: &ExtCtxt) -> Vec<Stmt>;
+    fn generate_state_statements(&self, ecx: &ExtCtxt) -> Vec<Stmt>;
+    fn generate_param_check_statements(&self, ecx: &ExtCtxt) -> Vec<Stmt>;
     fn generate_fn_arguments(&self, ecx: &ExtCtxt) -> Vec<TokenTree>;
-    fn explode(&self, ecx: &ExtCtxt) -> (&String, Path, P<Expr>, P<Expr>);
+    fn explode(&self, ecx: &ExtCtxt) -> (&String, Path, P<Expr>, P<Expr>, P<Expr>, P<Expr>);
 }
 
 impl RouteGenerateExt for RouteParams {
@@ -53,28 +55,6 @@ impl RouteGenerateExt for RouteParams {
         ecx.span_err(fn_span, "...but isn't in the function signature.");
     }
 
-    fn gen_form(&self, ecx: &ExtCtxt, param: Option<&Spanned<Ident>>,
-                form_string: P<Expr>) -> Option<Stmt> {
-        let arg = param.and_then(|p| self.annotated_fn.find_input(&p.node.name));
-        if param.is_none() {
-            return None;
-        } else if arg.is_none() {
-            self.missing_declared_err(ecx, &param.unwrap());
-            return None;
-        }
-
-        let arg = arg.unwrap();
-        let name = arg.ident().expect("form param identifier").prepend(PARAM_PREFIX);
-        let ty = strip_ty_lifetimes(arg.ty.clone());
-        Some(quote_stmt!(ecx,
-            let $name: $ty =
-                match ::rocket::request::FromForm::from_form_string($form_string) {
-                    Ok(v) => v,
-                    Err(_) => return ::rocket::Outcome::Forward(_data)
-                };
-        ).expect("form statement"))
-    }
-
     fn generate_data_statement(&self, ecx: &ExtCtxt) -> Option<Stmt> {
         let param = self.data_param.as_ref().map(|p| &p.value);
         let arg = param.and_then(|p| self.annotated_fn.find_input(&p.node.name));
@@ -94,7 +74,8 @@ impl RouteGenerateExt for RouteParams {
                     ::rocket::Outcome::Success(d) => d,
                     ::rocket::Outcome::Forward(d) =>
                         return ::rocket::Outcome::Forward(d),
-                    ::rocket::Outcome::Failure((code, _)) => {
+                    ::rocket::Outcome::Failure((code, e)) => {
+                        _req.set_guard_failure(code, e);
                         return ::rocket::Outcome::Failure(code);
                     }
                 };
@@ -103,14 +84,30 @@ impl RouteGenerateExt for RouteParams {
 
     fn generate_query_statement(&self, ecx: &ExtCtxt) -> Option<Stmt> {
         let param = self.query_param.as_ref();
-        let expr = quote_expr!(ecx,
-           match _req.uri().query() {
-               Some(query) => query,
-               None => return ::rocket::Outcome::Forward(_data)
-           }
-        );
-
-        self.gen_form(ecx, param, expr)
+        let arg = param.and_then(|p| self.annotated_fn.find_input(&p.node.name));
+        if param.is_none() {
+            return None;
+        } else if arg.is_none() {
+            self.missing_declared_err(ecx, &param.unwrap());
+            return None;
+        }
+
+        // A missing query string is parsed as the type's default form, if any.
+        let arg = arg.unwrap();
+        let name = arg.ident().expect("query param identifier").prepend(PARAM_PREFIX);
+        let ty = strip_ty_lifetimes(arg.ty.clone());
+        Some(quote_stmt!(ecx,
+            let $name: $ty = match _req.uri().query() {
+                Some(query) => match ::rocket::request::FromForm::from_form_string(query) {
+                    Ok(v) => v,
+                    Err(_) => return ::rocket::Outcome::Forward(_data)
+                },
+                None => match <$ty as ::rocket::request::FromForm>::default_form() {
+                    Some(v) => v,
+                    None => return ::rocket::Outcome::Forward(_data)
+                }
+            };
+        ).expect("query statement"))
     }
 
     // TODO: Add some kind of logging facility in Rocket to get be able to log
@@ -190,7 +187,8 @@ impl RouteGenerateExt for RouteParams {
                     ::rocket::outcome::Outcome::Success(v) => v,
                     ::rocket::outcome::Outcome::Forward(_) =>
                         return ::rocket::Outcome::forward(_data),
-                    ::rocket::outcome::Outcome::Failure((code, _)) => {
+                    ::rocket::outcome::Outcome::Failure((code, e)) => {
+                        _req.set_guard_failure(code, e);
                         return ::rocket::Outcome::Failure(code)
                     },
                 };
@@ -200,6 +198,60 @@ impl RouteGenerateExt for RouteParams {
         fn_param_statements
     }
 
+    fn generate_state_statements(&self, ecx: &ExtCtxt) -> Vec<Stmt> {
+        // Record the managed state type of every argument that has one. Only
+        // `State<T>` arguments do; for all other types, `state_type` is `None`.
+        self.annotated_fn.decl().inputs.iter().map(|arg| {
+            let ty = strip_ty_lifetimes(arg.ty.clone());
+            quote_stmt!(ecx,
+                if let Some(id) = <$ty as ::rocket::StateType>::state_type() {
+                    state.push((id, stringify!($ty)));
+                }
+            ).expect("state statement")
+        }).collect()
+    }
+
+    fn generate_param_check_statements(&self, ecx: &ExtCtxt) -> Vec<Stmt> {
+        // Check that every declared path parameter parses, without keeping the
+        // values. Undeclared parameters are reported by the handler function.
+        let mut check_statements = vec![];
+        for (i, param) in self.path_params(ecx).enumerate() {
+            let ty = match self.annotated_fn.find_input(&param.ident().name) {
+                Some(arg) => strip_ty_lifetimes(arg.ty.clone()),
+                None => continue
+            };
+
+            let name = param.ident();
+            let expr = match param {
+                Param::Single(_) => quote_expr!(ecx, match _req.get_param_str($i) {
+                    Some(s) => <$ty as ::rocket::request::FromParam>::from_param(s)
+                        .is_ok(),
+                    None => false
+                }),
+                Param::Many(_) => {
+                    let d = URI::new(self.path.node.as_str()).segments().enumerate()
+                        .filter(|&(_, s)| s.starts_with("<"))
+                        .map((&|(d, _)| d))
+                        .next().expect("segment when segment is iterated");
+
+                    quote_expr!(ecx, match _req.get_raw_segments($d) {
+                        Some(s) => <$ty as ::rocket::request::FromSegments>::from_segments(s)
+                            .is_ok(),
+                        None => false
+                    })
+                },
+            };
+
+            check_statements.push(quote_stmt!(ecx,
+                if !$expr {
+                    return Err(stringify!($name));
+                }
+            ).expect("param check statement"));
+        }
+
+        check_statements
+    }
+
     fn generate_fn_arguments(&self, ecx: &ExtCtxt) -> Vec<TokenTree> {
         let args = self.annotated_fn.decl().inputs.iter()
             .filter_map(|a| a.ident())
@@ -209,14 +261,16 @@ impl RouteGenerateExt for RouteParams {
         sep_by_tok(ecx, &args, token::Comma)
     }
 
-    fn explode(&self, ecx: &ExtCtxt) -> (&String, Path, P<Expr>, P<Expr>) {
+    fn explode(&self, ecx: &ExtCtxt) -> (&String, Path, P<Expr>, P<Expr>, P<Expr>, P<Expr>) {
         let path = &self.path.node;
         let method = method_to_path(ecx, self.method.node);
         let format = self.format.as_ref().map(|kv| kv.value().clone());
         let content_type = option_as_expr(ecx, &content_type_to_expr(ecx, format));
         let rank = option_as_expr(ecx, &self.rank);
+        let feature = option_as_expr(ecx, &self.feature);
+        let canonical = option_as_expr(ecx, &self.canonical);
 
-        (path, method, content_type, rank)
+        (path, method, content_type, rank, feature, canonical)
     }
 }
 
@@ -235,6 +289,8 @@ fn generic_route_decorator(known_method: Option<Spanned<Method>>,
     let query_statement = route.generate_query_statement(ecx);
     let data_statement = route.generate_data_statement(ecx);
     let fn_arguments = route.generate_fn_arguments(ecx);
+    let state_statements = route.generate_state_statements(ecx);
+    let param_check_statements = route.generate_param_check_statements(ecx);
 
     // Generate and emit the wrapping function with the Rocket handler signature.
     let user_fn_name = route.annotated_fn.ident();
@@ -250,10 +306,40 @@ fn generic_route_decorator(known_method: Option<Spanned<Method>>,
          }
     ).unwrap());
 
+    // Generate and emit the function that returns the managed state the route
+    // requests, which is checked when Rocket is launched.
+    let route_state_fn_name = user_fn_name.prepend(ROUTE_STATE_FN_PREFIX);
+    emit_item(push, quote_item!(ecx,
+        #[allow(unused_mut)]
+        fn $route_state_fn_name()
+                -> ::std::vec::Vec<(::std::any::TypeId, &'static str)> {
+            let mut state = ::std::vec::Vec::new();
+            $state_statements
+            state
+        }
+    ).unwrap());
+
+    // Generate and emit the function that checks that the route's path
+    // parameters parse, which is used to explain how requests are routed.
+    let route_params_fn_name = user_fn_name.prepend(ROUTE_PARAMS_FN_PREFIX);
+    emit_item(push, quote_item!(ecx,
+        #[allow(unused_variables)]
+        fn $route_params_fn_name(_req: &::rocket::Request)
+                -> ::std::result::Result<(), &'static str> {
+            $param_check_statements
+            Ok(())
+        }
+    ).unwrap());
+
     // Generate and emit the static route info that uses the just generated
     // function as its handler. A proper Rocket route will be created from this.
     let struct_name = user_fn_name.prepend(ROUTE_STRUCT_PREFIX);
-    let (path, method, content_type, rank) = route.explode(ecx);
+    let (path, method, content_type, rank, feature, canonical) = route.explode(ecx);
+    let param_docs: Vec<_> = route.param_docs.iter().map(|&(ref name, ref description)| {
+        let (name, description) = (name.as_str(), description.as_str());
+        quote_tokens!(ecx, ($name, $description),)
+    }).collect();
+
     emit_item(push, quote_item!(ecx,
         #[allow(non_upper_case_globals)]
         pub static $struct_name: ::rocket::StaticRouteInfo =
@@ -263,6 +349,11 @@ fn generic_route_decorator(known_method: Option<Spanned<Method>>,
                 handler: $route_fn_name,
                 format: $content_type,
                 rank: $rank,
+                feature: $feature,
+                canonical: $canonical,
+                state: $route_state_fn_name,
+                params: $route_params_fn_name,
+                param_docs: &[$param_docs],
             };
     ).unwrap());
 }
@@ -33,15 +33,22 @@
 //! kv_param := 'rank' '=' INTEGER
 //!           | 'format' '=' STRING
 //!           | 'data' '=' DYNAMIC_PARAM
+//!           | 'feature' '=' STRING
+//!           | 'canonical' '=' STRING
 //!
 //! INTEGER := isize, as defined by Rust
 //! STRING := UTF-8 string literal, as defined by Rust
 //! IDENT := Valid identifier, as defined by Rust
 //!
 //! URI_SEG := Valid HTTP URI Segment
-//! DYNAMIC_PARAM := '<' IDENT '..'? '>' (string literal)
+//! DYNAMIC_PARAM := '<' IDENT (':' MATCHER | '..')? '>' (string literal)
+//! MATCHER := IDENT | '[' '^'? CHARACTER_CLASS ']' ('+' | '*' | '{' n (',' m?)? '}')?
 //! </pre>
 //!
+//! A `MATCHER` names a segment matcher registered via `Rocket::matcher` or
+//! built-in, or is an inline character class pattern. Routes only match
+//! requests whose segments the matchers accept.
+//!
 //! Note that the **route** attribute takes a method as its first argument,
 //! while the remaining do not. That is, **route** looks like:
 //!
@@ -51,6 +58,18 @@
 //!
 //!     #[get("/hello")]
 //!
+//! A function with a route attribute may also be annotated with any number of
+//! **param** attributes, which describe the function's arguments:
+//!
+//! <pre>
+//! param := 'name' '=' STRING ',' 'description' '=' STRING
+//! </pre>
+//!
+//! The descriptions are captured into the route's
+//! [param_docs](/rocket/struct.Route.html#structfield.param_docs), as are
+//! those in lines of the function's doc comment of the form ``* `name` -
+//! description``. A **param** attribute takes precedence over a doc comment.
+//!
 //! The syntax for the **error** attribute is:
 //!
 //! <pre>
@@ -62,6 +81,56 @@
 //! This crate implements the following custom derives:
 //!
 //!   * **FromForm**
+//!   * **Responder**
+//!
+//! A structure deriving **FromForm** may be annotated with the **form**
+//! attribute, whose syntax is:
+//!
+//! <pre>
+//! form := 'syntax' '=' ('"dots"' | '"brackets"')
+//! </pre>
+//!
+//! The fields of the structure may also be annotated with the **form**
+//! attribute, whose syntax on a field is:
+//!
+//! <pre>
+//! form := 'field' '=' STRING_LIT
+//!       | 'skip'
+//!       | 'default' ('=' STRING_LIT)?
+//!       | 'validate' '=' STRING_LIT
+//! </pre>
+//!
+//! A field with a **field** parameter is read from the form field with the
+//! given name instead of its own. When a field with a **default** parameter is
+//! missing from the form, it's parsed as if the form had the given string as
+//! its value, or set to its type's `Default` value if no string is given. A
+//! **skip** field is never read from the form and is instead set to its
+//! **default**, if it has one, or its type's `Default` value. Each
+//! **validate** parameter is an expression, in a string, that evaluates to a
+//! [Validator](/rocket/request/validate/trait.Validator.html) of the field's
+//! type; the field's parsed value is checked by each of its validators.
+//!
+//! A structure or enum deriving **Responder**, and each variant of an enum
+//! deriving it, may be annotated with the **response** attribute, whose syntax
+//! is:
+//!
+//! <pre>
+//! response := 'status' '=' INTEGER (',' 'content_type' '=' STRING_LIT)?
+//!           | 'content_type' '=' STRING_LIT
+//! </pre>
+//!
+//! The fields may also be annotated with the **response** attribute, whose
+//! syntax on a field is:
+//!
+//! <pre>
+//! response := 'header' ('=' STRING_LIT)?
+//! </pre>
+//!
+//! The one field without a **header** parameter is the inner responder, whose
+//! response has its status and Content-Type replaced by those given, if any,
+//! and each **header** field added as a header: named by the given string and
+//! set to the field's `Display` value, or converted `Into<Header>` if no name
+//! is given. See [Responder](/rocket/response/trait.Responder.html) for more.
 //!
 //! ## Procedural Macros
 //!
@@ -110,6 +179,7 @@ use std::env;
 use rustc_plugin::Registry;
 use syntax::ext::base::SyntaxExtension;
 use syntax::symbol::Symbol;
+use syntax::feature_gate::AttributeType;
 
 const DEBUG_ENV_VAR: &'static str = "ROCKET_CODEGEN_DEBUG";
 
@@ -117,6 +187,8 @@ const PARAM_PREFIX: &'static str = "rocket_param_";
 const ROUTE_STRUCT_PREFIX: &'static str = "static_rocket_route_info_for_";
 const CATCH_STRUCT_PREFIX: &'static str = "static_rocket_catch_info_for_";
 const ROUTE_FN_PREFIX: &'static str = "rocket_route_fn_";
+const ROUTE_STATE_FN_PREFIX: &'static str = "rocket_route_state_fn_";
+const ROUTE_PARAMS_FN_PREFIX: &'static str = "rocket_route_params_fn_";
 const CATCH_FN_PREFIX: &'static str = "rocket_catch_fn_";
 
 macro_rules! register_decorators {
@@ -137,9 +209,13 @@ pub fn plugin_registrar(reg: &mut Registry) {
 
     reg.register_macro("routes", macros::routes);
     reg.register_macro("errors", macros::errors);
+    reg.register_attribute("form".to_string(), AttributeType::Whitelisted);
+    reg.register_attribute("param".to_string(), AttributeType::Whitelisted);
+    reg.register_attribute("response".to_string(), AttributeType::Whitelisted);
 
     register_decorators!(reg,
         "derive_FromForm" => from_form_derive,
+        "derive_Responder" => responder_derive,
 
         "error" => error_decorator,
         "route" => route_decorator,
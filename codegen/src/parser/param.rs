@@ -63,13 +63,18 @@ impl<'s, 'a, 'c> Iterator for ParamIter<'s, 'a, 'c> {
             return err(self.ctxt, self.span, "malformed parameter list");
         }
 
-        // Calculate the parameter's ident.
+        // Calculate the parameter's ident, stripping any segment matcher.
         let full_param = &self.string[(start + 1)..end];
         let (is_many, param) = match full_param.ends_with("..") {
             true => (true, &full_param[..(full_param.len() - 2)]),
             false => (false, full_param)
         };
 
+        let (param, matcher) = match param.find(':') {
+            Some(i) => (&param[..i], Some(&param[(i + 1)..])),
+            None => (param, None)
+        };
+
         let mut param_span = self.span;
         param_span.lo = self.span.lo + BytePos(start as u32);
         param_span.hi = self.span.lo + BytePos((end + 1) as u32);
@@ -85,6 +90,10 @@ impl<'s, 'a, 'c> Iterator for ParamIter<'s, 'a, 'c> {
             err(self.ctxt, param_span, "parameter names must be valid identifiers")
         } else if param.starts_with("_") {
             err(self.ctxt, param_span, "parameters cannot be ignored")
+        } else if matcher == Some("") {
+            err(self.ctxt, param_span, "segment matchers cannot be empty")
+        } else if is_many && matcher.is_some() {
+            err(self.ctxt, param_span, "trailing '..' params cannot have segment matchers")
         } else if is_many && !self.string.is_empty() {
             let sp = self.span.shorten_to(self.string.len());
             self.ctxt.struct_span_err(sp, "text after a trailing '..' param")
@@ -4,11 +4,13 @@ use std::collections::HashSet;
 use syntax::ast::*;
 use syntax::ext::base::{ExtCtxt, Annotatable};
 use syntax::codemap::{Span, Spanned, dummy_spanned};
+use syntax::symbol::Symbol;
 
-use utils::{span, MetaItemExt, SpanExt, is_valid_ident};
+use utils::{span, MetaItemExt, SpanExt, ArgExt, is_valid_ident};
 use super::{Function, ParamIter};
 use super::keyvalue::KVSpanned;
 use rocket::http::{Method, ContentType};
+use rocket::canonical::Canonical;
 
 /// This structure represents the parsed `route` attribute.
 ///
@@ -25,6 +27,9 @@ pub struct RouteParams {
     pub query_param: Option<Spanned<Ident>>,
     pub format: Option<KVSpanned<ContentType>>,
     pub rank: Option<KVSpanned<isize>>,
+    pub feature: Option<KVSpanned<String>>,
+    pub canonical: Option<KVSpanned<String>>,
+    pub param_docs: Vec<(String, String)>,
 }
 
 impl RouteParams {
@@ -73,7 +78,8 @@ impl RouteParams {
 
         // Parse all of the optional parameters.
         let mut seen_keys = HashSet::new();
-        let (mut rank, mut data, mut format) = Default::default();
+        let (mut rank, mut data, mut format, mut feature) = Default::default();
+        let mut canonical = None;
         for param in &attr_params[1..] {
             let kv_opt = kv_from_nested(&param);
             if kv_opt.is_none() {
@@ -86,6 +92,8 @@ impl RouteParams {
                 "rank" => rank = parse_opt(ecx, &kv, parse_rank),
                 "data" => data = parse_opt(ecx, &kv, parse_data),
                 "format" => format = parse_opt(ecx, &kv, parse_format),
+                "feature" => feature = parse_opt(ecx, &kv, parse_feature),
+                "canonical" => canonical = parse_opt(ecx, &kv, parse_canonical),
                 _ => {
                     let msg = format!("'{}' is not a known parameter", kv.key());
                     ecx.span_err(kv.span, &msg);
@@ -113,6 +121,8 @@ impl RouteParams {
             }
         }
 
+        let param_docs = parse_param_docs(ecx, &function, annotated);
+
         RouteParams {
             method: method,
             path: path,
@@ -120,6 +130,9 @@ impl RouteParams {
             query_param: query,
             format: format,
             rank: rank,
+            feature: feature,
+            canonical: canonical,
+            param_docs: param_docs,
             annotated_fn: function,
         }
     }
@@ -288,3 +301,135 @@ fn parse_format(ecx: &ExtCtxt, kv: &KVSpanned<LitKind>) -> ContentType {
 
     ContentType::Any
 }
+
+fn parse_feature(ecx: &ExtCtxt, kv: &KVSpanned<LitKind>) -> String {
+    if let LitKind::Str(ref s, _) = *kv.value() {
+        if !s.as_str().is_empty() {
+            return s.as_str().to_string();
+        }
+    }
+
+    ecx.struct_span_err(kv.span, r#"`feature` must be a non-empty string"#)
+        .help(r#"feature, if specified, must be a key-value pair where
+              the key is `feature` and the value is the name of the feature
+              flag that enables the route. e.g: feature = "new_checkout""#)
+        .emit();
+
+    String::new()
+}
+
+fn parse_canonical(ecx: &ExtCtxt, kv: &KVSpanned<LitKind>) -> String {
+    if let LitKind::Str(ref s, _) = *kv.value() {
+        let string = s.as_str().to_string();
+        if let Err(e) = string.parse::<Canonical>() {
+            ecx.struct_span_err(kv.value.span, &format!("malformed canonical URL options: {}", e))
+                .help("the options are `lowercase`, `no_trailing_slash`, `link`, \
+                       and `host = name[:port]`")
+                .emit();
+        }
+
+        return string;
+    }
+
+    ecx.struct_span_err(kv.span, r#"`canonical` must be a string of options"#)
+        .help(r#"canonical, if specified, must be a key-value pair where
+              the key is `canonical` and the value is a comma-separated list
+              of options. e.g: canonical = "lowercase, no_trailing_slash""#)
+        .emit();
+
+    String::new()
+}
+
+/// Splits a line of a doc comment of the form ``* `name` - description`` or
+/// ``* `name`: description`` into the name and the description.
+fn split_param_doc(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_left_matches('/').trim();
+    if !line.starts_with("* `") && !line.starts_with("- `") {
+        return None;
+    }
+
+    let line = &line[3..];
+    let end = match line.find('`') {
+        Some(end) => end,
+        None => return None
+    };
+
+    let rest = line[(end + 1)..].trim_left();
+    if !rest.starts_with('-') && !rest.starts_with(':') {
+        return None;
+    }
+
+    Some((&line[..end], rest[1..].trim()))
+}
+
+/// Returns the descriptions of the handler's arguments as (name, description)
+/// pairs, in the order of the arguments. Descriptions are read from the
+/// handler's `#[param(name = "..", description = "..")]` attributes and from
+/// the lines of its doc comment of the form ``* `name` - description``, with
+/// an attribute taking precedence over a doc comment.
+fn parse_param_docs(ecx: &ExtCtxt, function: &Function, annotated: &Annotatable)
+        -> Vec<(String, String)> {
+    let attrs = match *annotated {
+        Annotatable::Item(ref item) => &item.attrs,
+        _ => return vec![]
+    };
+
+    let mut docs: Vec<(String, String)> = vec![];
+    for attr in attrs.iter().filter(|attr| attr.is_sugared_doc) {
+        let line = match attr.value_str() {
+            Some(line) => line.as_str().to_string(),
+            None => continue
+        };
+
+        if let Some((name, description)) = split_param_doc(&line) {
+            if function.find_input(&Symbol::intern(name)).is_some() {
+                docs.push((name.to_string(), description.to_string()));
+            }
+        }
+    }
+
+    for attr in attrs.iter().filter(|attr| attr.check_name("param")) {
+        let (mut name, mut description) = (None, None);
+        for param in attr.meta_item_list().unwrap_or(&[]) {
+            match param.name_value() {
+                Some((key, &Lit { node: LitKind::Str(ref s, _), .. })) => {
+                    match &*key.as_str() {
+                        "name" => name = Some(s.as_str().to_string()),
+                        "description" => description = Some(s.as_str().to_string()),
+                        _ => ecx.span_err(param.span, "unknown `param` parameter")
+                    }
+                }
+                _ => ecx.span_err(param.span, "expected key = string")
+            }
+        }
+
+        let (name, description) = match (name, description) {
+            (Some(name), Some(description)) => (name, description),
+            _ => {
+                ecx.struct_span_err(attr.span, "`param` requires a name and a description")
+                    .help(r#"e.g: #[param(name = "id", description = "The post's ID.")]"#)
+                    .emit();
+                continue;
+            }
+        };
+
+        if function.find_input(&Symbol::intern(&name)).is_none() {
+            let msg = format!("the handler has no argument named `{}`", name);
+            ecx.span_err(attr.span, &msg);
+            continue;
+        }
+
+        docs.retain(|&(ref documented, _)| documented != &name);
+        docs.push((name, description));
+    }
+
+    let mut ordered = vec![];
+    for arg_name in function.decl().inputs.iter().filter_map(|arg| arg.name()) {
+        let arg_name = arg_name.as_str();
+        if let Some(i) = docs.iter().position(|&(ref name, _)| *name == *arg_name) {
+            ordered.push(docs.remove(i));
+        }
+    }
+
+    ordered
+}
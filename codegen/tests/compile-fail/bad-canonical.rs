@@ -0,0 +1,15 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[get("/", canonical = "uppercase")] //~ ERROR malformed canonical URL options
+fn one() -> &'static str { "hi" }
+
+#[get("/", canonical = "lowercase, host = not a host")] //~ ERROR malformed canonical URL options
+fn two() -> &'static str { "hi" }
+
+#[get("/", canonical = 10)] //~ ERROR string of options
+fn three() -> &'static str { "hi" }
+
+fn main() {  }
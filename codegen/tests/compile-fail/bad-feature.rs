@@ -0,0 +1,12 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[get("/", feature = "")] //~ ERROR non-empty string
+fn one() -> &'static str { "hi" }
+
+#[get("/", feature = 10)] //~ ERROR non-empty string
+fn two() -> &'static str { "hi" }
+
+fn main() {  }
@@ -0,0 +1,49 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[derive(FromForm)]
+struct One {
+    #[form(field = "")] //~ ERROR non-empty string
+    a: usize,
+}
+
+#[derive(FromForm)]
+struct Two {
+    #[form(field = 10)] //~ ERROR non-empty string
+    a: usize,
+}
+
+#[derive(FromForm)]
+struct Three {
+    #[form(rename = "b")] //~ ERROR unknown `form` parameter
+    a: usize,
+}
+
+#[derive(FromForm)]
+struct Four {
+    #[form(field = "b")]
+    a: usize,
+    b: usize, //~ ERROR duplicate form field
+}
+
+#[derive(FromForm)]
+struct Five {
+    #[form(validate = "range(1..")] //~ ERROR valid expression
+    a: usize,
+}
+
+#[derive(FromForm)]
+struct Six {
+    #[form(default = "ten")] //~ ERROR not a valid `usize`
+    a: usize,
+}
+
+#[derive(FromForm)]
+struct Seven {
+    #[form(default = "maybe")] //~ ERROR not a valid `bool`
+    a: bool,
+}
+
+fn main() {  }
@@ -0,0 +1,13 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+struct Color(u8, u8, u8);
+
+#[derive(FromForm)] //~ ERROR the trait bound `Color: rocket::request::FormField
+struct Paint {
+    color: Color,
+}
+
+fn main() {  }
@@ -0,0 +1,18 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[get("/<id>")]
+#[param(name = "name", description = "The name.")] //~ ERROR no argument named `name`
+fn one(id: usize) -> String { id.to_string() }
+
+#[get("/<id>")]
+#[param(name = "id")] //~ ERROR requires a name and a description
+fn two(id: usize) -> String { id.to_string() }
+
+#[get("/<id>")]
+#[param(name = "id", summary = "The ID.")] //~ ERROR unknown `param` parameter
+fn three(id: usize) -> String { id.to_string() }
+
+fn main() {  }
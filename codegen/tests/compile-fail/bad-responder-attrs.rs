@@ -0,0 +1,41 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[derive(Responder)]
+#[response(status = 99)] //~ ERROR status must be >= 100 and <= 599
+struct One(String);
+
+#[derive(Responder)]
+#[response(status = "404")] //~ ERROR `status` must be an integer
+struct Two(String);
+
+#[derive(Responder)]
+#[response(content_type = "json")] //~ ERROR malformed content-type
+struct Three(String);
+
+#[derive(Responder)]
+#[response(code = 404)] //~ ERROR unknown `response` parameter
+struct Four(String);
+
+#[derive(Responder)]
+struct Five {
+    body: String,
+    other: String, //~ ERROR only one field may be the inner responder
+}
+
+#[derive(Responder)]
+struct Six {
+    body: String,
+    #[response(header = "")] //~ ERROR non-empty string
+    name: String,
+}
+
+#[derive(Responder)]
+enum Seven {
+    #[response(status = 404, reason = "gone")] //~ ERROR unknown `response` parameter
+    A(String),
+}
+
+fn main() {  }
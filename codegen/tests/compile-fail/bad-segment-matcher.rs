@@ -0,0 +1,10 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+#[get("/<name:>")] //~ ERROR segment matchers cannot be empty
+fn get(name: &str) -> &'static str { "hi" }
+
+#[get("/<path:uint..>")] //~ ERROR cannot have segment matchers
+fn get1(path: ::std::path::PathBuf) -> &'static str { "hi" }
+
+fn main() {  }
@@ -3,7 +3,9 @@
 
 extern crate rocket;
 
-use rocket::request::{FromForm, FromFormValue};
+use std::collections::HashMap;
+
+use rocket::request::{FromForm, FromFormValue, FormError};
 
 #[derive(Debug, PartialEq, FromForm)]
 struct TodoTask {
@@ -53,6 +55,25 @@ struct ManualMethod<'r> {
     done: bool
 }
 
+#[derive(Debug, PartialEq, FromForm)]
+struct Address<'r> {
+    street: &'r str,
+    zip: usize,
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Customer<'r> {
+    name: String,
+    address: Address<'r>,
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Order<'r> {
+    tags: Vec<&'r str>,
+    rows: Vec<Address<'r>>,
+    quantities: HashMap<String, usize>,
+}
+
 fn main() {
     // Same number of arguments: simple case.
     let task = TodoTask::from_form_string("description=Hello&completed=on");
@@ -63,7 +84,34 @@ fn main() {
 
     // Argument in string but not in form.
     let task = TodoTask::from_form_string("other=a&description=Hello&completed=on");
-    assert!(task.is_err());
+    assert_eq!(task, Err(FormError::Unexpected { field: "other".to_string() }));
+
+    // Unless the form is parsed leniently.
+    let task = TodoTask::from_form_string_lenient("other=a&description=Hello&completed=on");
+    assert_eq!(task, Ok(TodoTask {
+        description: "Hello".to_string(),
+        completed: true
+    }));
+
+    let task = TodoTask::from_form_string_lenient("other=a&completed=on");
+    assert_eq!(task, Err(FormError::Missing {
+        field: "description".to_string(),
+        expected: "String"
+    }));
+
+    // Missing and unparseable arguments are reported with the field.
+    let task = TodoTask::from_form_string("completed=on");
+    assert_eq!(task, Err(FormError::Missing {
+        field: "description".to_string(),
+        expected: "String"
+    }));
+
+    let input = FormInput::from_form_string("number=ten");
+    assert_eq!(input, Err(FormError::BadValue {
+        field: "number".to_string(),
+        value: "ten".to_string(),
+        expected: "usize"
+    }));
 
     // Ensure _method isn't required.
     let task = TodoTask::from_form_string("_method=patch&description=Hello&completed=off");
@@ -72,6 +120,32 @@ fn main() {
         completed: false
     }));
 
+    // Unchecked checkboxes aren't submitted at all: they're missing when
+    // parsing strictly, and `false` when parsing leniently.
+    let task = TodoTask::from_form_string("description=Hello");
+    assert_eq!(task, Err(FormError::Missing {
+        field: "completed".to_string(),
+        expected: "bool"
+    }));
+
+    let task = TodoTask::from_form_string_lenient("description=Hello&other=a");
+    assert_eq!(task, Ok(TodoTask {
+        description: "Hello".to_string(),
+        completed: false
+    }));
+
+    for &(value, completed) in &[("yes", true), ("TRUE", true), ("1", true), ("no", false)] {
+        let task = TodoTask::from_form_string(&format!("description=Hi&completed={}", value));
+        assert_eq!(task.map(|task| task.completed), Ok(completed));
+    }
+
+    let task = TodoTask::from_form_string("description=Hi&completed=maybe");
+    assert_eq!(task, Err(FormError::BadValue {
+        field: "completed".to_string(),
+        value: "maybe".to_string(),
+        expected: "bool"
+    }));
+
     let form_string = &[
         "password=testing", "checkbox=off", "checkbox=on", "number=10",
         "checkbox=off", "textarea=", "select=a", "radio=c",
@@ -106,4 +180,71 @@ fn main() {
         _method: None,
         done: true
     }));
+
+    // Nested forms are parsed from prefixed keys.
+    let customer = Customer::from_form_string("address.zip=12345&name=Bob&address.street=Main");
+    assert_eq!(customer, Ok(Customer {
+        name: "Bob".to_string(),
+        address: Address { street: "Main", zip: 12345 }
+    }));
+
+    // Errors in nested forms are reported with the full key.
+    let customer = Customer::from_form_string("name=Bob&address.street=Main&address.zip=x");
+    assert_eq!(customer, Err(FormError::BadValue {
+        field: "address.zip".to_string(),
+        value: "x".to_string(),
+        expected: "usize"
+    }));
+
+    let customer = Customer::from_form_string("name=Bob&address.zip=1&address.city=X");
+    assert_eq!(customer, Err(FormError::Unexpected { field: "address.city".to_string() }));
+
+    let customer = Customer::from_form_string("name=Bob");
+    assert_eq!(customer, Err(FormError::Missing {
+        field: "address".to_string(),
+        expected: "Address"
+    }));
+
+    // Keys nested under value fields are unexpected.
+    let task = TodoTask::from_form_string("description=Hi&description.x=1&completed=on");
+    assert_eq!(task, Err(FormError::Unexpected { field: "description.x".to_string() }));
+
+    // Leniency extends to nested forms.
+    let customer = Customer::from_form_string_lenient(
+        "name=Bob&address.street=Main&address.zip=1&address.city=X&name.x=1");
+    assert_eq!(customer, Ok(Customer {
+        name: "Bob".to_string(),
+        address: Address { street: "Main", zip: 1 }
+    }));
+
+    // Collections gather repeated, indexed, and keyed items.
+    let form_string = &[
+        "tags=a", "rows[1].street=B", "tags=b", "rows[0].street=A", "quantities[x]=1",
+        "rows[1].zip=2", "rows%5B0%5D.zip=1", "quantities%5By%5D=2",
+    ].join("&");
+
+    let order = Order::from_form_string(&form_string).unwrap();
+    assert_eq!(order.tags, vec!["a", "b"]);
+    assert_eq!(order.rows, vec![
+        Address { street: "A", zip: 1 },
+        Address { street: "B", zip: 2 },
+    ]);
+    assert_eq!(order.quantities.len(), 2);
+    assert_eq!(order.quantities["x"], 1);
+    assert_eq!(order.quantities["y"], 2);
+
+    // Missing collections are empty.
+    let order = Order::from_form_string("").unwrap();
+    assert!(order.tags.is_empty() && order.rows.is_empty() && order.quantities.is_empty());
+
+    // Errors in collections are reported with the full key.
+    let order = Order::from_form_string("rows[3].street=A&rows[3].zip=x");
+    assert_eq!(order, Err(FormError::BadValue {
+        field: "rows[3].zip".to_string(),
+        value: "x".to_string(),
+        expected: "usize"
+    }));
+
+    let order = Order::from_form_string("quantities[x]=many");
+    assert_eq!(order.unwrap_err().field(), "quantities[x]");
 }
@@ -0,0 +1,82 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::collections::HashMap;
+
+use rocket::request::{FromForm, FormError};
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Address<'r> {
+    street: &'r str,
+    zip: usize,
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct User<'r> {
+    name: &'r str,
+    emails: Vec<&'r str>,
+    address: Address<'r>,
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+#[form(syntax = "brackets")]
+struct Signup<'r> {
+    user: User<'r>,
+    rows: Vec<Address<'r>>,
+    attrs: HashMap<String, &'r str>,
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+#[form(syntax = "dots")]
+struct Customer<'r> {
+    address: Address<'r>,
+}
+
+fn main() {
+    // Nested forms and collections are parsed from bracketed keys.
+    let form_string = &[
+        "user[name]=Bob", "user[emails][]=a", "user[address][street]=Main",
+        "user%5Bemails%5D%5B%5D=b", "user[address][zip]=1", "rows[0][street]=A",
+        "rows[0][zip]=2", "attrs[color]=red",
+    ].join("&");
+
+    let signup = Signup::from_form_string(form_string).unwrap();
+    assert_eq!(signup.user, User {
+        name: "Bob",
+        emails: vec!["a", "b"],
+        address: Address { street: "Main", zip: 1 }
+    });
+    assert_eq!(signup.rows, vec![Address { street: "A", zip: 2 }]);
+    assert_eq!(signup.attrs["color"], "red");
+
+    // Errors are reported with the full bracketed key.
+    let signup = Signup::from_form_string(
+        "user[name]=Bob&user[address][street]=Main&user[address][zip]=x");
+    assert_eq!(signup, Err(FormError::BadValue {
+        field: "user[address][zip]".to_string(),
+        value: "x".to_string(),
+        expected: "usize"
+    }));
+
+    let signup = Signup::from_form_string("rows[0][street]=A&rows[0][zip]=x");
+    assert_eq!(signup.unwrap_err().field(), "rows[0][zip]");
+
+    // Dotted keys are unexpected with brackets, unless parsed leniently.
+    let signup = Signup::from_form_string("user.name=Bob");
+    assert_eq!(signup, Err(FormError::Unexpected { field: "user.name".to_string() }));
+
+    let signup = Signup::from_form_string_lenient("user.name=Bob");
+    assert_eq!(signup.unwrap_err(), FormError::Missing {
+        field: "user".to_string(),
+        expected: "User"
+    });
+
+    // And bracketed keys are unexpected with dots.
+    let customer = Customer::from_form_string("address.street=Main&address.zip=1");
+    assert_eq!(customer, Ok(Customer { address: Address { street: "Main", zip: 1 } }));
+
+    let customer = Customer::from_form_string("address[street]=Main&address[zip]=1");
+    assert_eq!(customer.unwrap_err().field(), "address[street]");
+}
@@ -0,0 +1,96 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::net::IpAddr;
+
+use rocket::request::{FromForm, FormError};
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Address {
+    street: String,
+    #[form(default = "Earth")]
+    planet: String,
+}
+
+#[derive(Debug, PartialEq, Default)]
+struct Token(usize);
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Settings<'r> {
+    name: &'r str,
+    #[form(default = "10")]
+    limit: usize,
+    #[form(default = "light")]
+    theme: &'r str,
+    #[form(default = "true")]
+    notify: bool,
+    #[form(default)]
+    newsletter: bool,
+    #[form(default)]
+    tags: Vec<String>,
+    #[form(field = "mod", default = "2.5")]
+    modifier: f32,
+    address: Address,
+    #[form(skip, default = "7")]
+    version: u8,
+    #[form(skip)]
+    token: Token,
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Server {
+    #[form(default = "localhost")]
+    host: IpAddr,
+}
+
+fn main() {
+    // Missing fields with defaults take them.
+    let settings = Settings::from_form_string("name=Bob&address.street=Main").unwrap();
+    assert_eq!(settings, Settings {
+        name: "Bob",
+        limit: 10,
+        theme: "light",
+        notify: true,
+        newsletter: false,
+        tags: vec![],
+        modifier: 2.5,
+        address: Address { street: "Main".into(), planet: "Earth".into() },
+        version: 7,
+        token: Token(0),
+    });
+
+    // Fields that are present are parsed as usual.
+    let form_string = &[
+        "name=Bob", "limit=5", "theme=dark", "notify=off", "newsletter=on",
+        "tags=a", "mod=1", "address.street=Main", "address.planet=Mars"
+    ].join("&");
+
+    let settings = Settings::from_form_string(form_string).unwrap();
+    assert_eq!((settings.limit, settings.theme, settings.notify), (5, "dark", false));
+    assert_eq!((settings.newsletter, settings.modifier), (true, 1.0));
+    assert_eq!(settings.tags, vec!["a".to_string()]);
+    assert_eq!(settings.address.planet, "Mars");
+
+    // Defaults don't apply to values that fail to parse, or to other fields.
+    let settings = Settings::from_form_string("name=Bob&limit=x&address.street=Main");
+    assert_eq!(settings.unwrap_err().field(), "limit");
+
+    let settings = Settings::from_form_string("limit=5&address.street=Main");
+    assert_eq!(settings.unwrap_err().field(), "name");
+
+    // Skipped fields can't be set, even with a default.
+    let settings = Settings::from_form_string("name=Bob&address.street=Main&version=1");
+    assert_eq!(settings, Err(FormError::Unexpected { field: "version".to_string() }));
+
+    // An invalid default is reported as such, only when it's used.
+    let server = Server::from_form_string("");
+    assert_eq!(server, Err(FormError::BadDefault {
+        field: "host".to_string(),
+        expected: "IpAddr"
+    }));
+
+    let server = Server::from_form_string("host=127.0.0.1").unwrap();
+    assert_eq!(server.host, "127.0.0.1".parse::<IpAddr>().unwrap());
+}
@@ -0,0 +1,60 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::request::{FromForm, FormError};
+
+#[derive(Debug, PartialEq, Default)]
+struct Session {
+    id: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Address<'r> {
+    #[form(field = "street-name")]
+    street: &'r str,
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Account<'r> {
+    #[form(field = "type")]
+    kind: &'r str,
+    #[form(field = "mod")]
+    module: Option<usize>,
+    #[form(field = "home")]
+    address: Address<'r>,
+    #[form(skip)]
+    admin: bool,
+    #[form(skip)]
+    session: Session,
+}
+
+fn main() {
+    let account = Account::from_form_string("type=a&mod=2&home.street-name=Main");
+    assert_eq!(account, Ok(Account {
+        kind: "a",
+        module: Some(2),
+        address: Address { street: "Main" },
+        admin: false,
+        session: Session::default(),
+    }));
+
+    // Errors are reported with the names of the form fields.
+    let account = Account::from_form_string("mod=2&home.street-name=Main");
+    assert_eq!(account.unwrap_err().field(), "type");
+
+    let account = Account::from_form_string("type=a&mod=x&home.street-name=Main");
+    assert_eq!(account.unwrap_err().field(), "mod");
+
+    // The names of the structure's fields are not form fields.
+    let account = Account::from_form_string("kind=a&home.street-name=Main");
+    assert_eq!(account, Err(FormError::Unexpected { field: "kind".to_string() }));
+
+    // Skipped fields can't be set from the form.
+    let account = Account::from_form_string("type=a&home.street-name=Main&admin=true");
+    assert_eq!(account, Err(FormError::Unexpected { field: "admin".to_string() }));
+
+    let account = Account::from_form_string_lenient("type=a&home.street-name=Main&admin=true");
+    assert_eq!(account.map(|a| a.admin), Ok(false));
+}
@@ -0,0 +1,76 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::request::{FromForm, FormError, FormErrors, Validated};
+
+fn not_admin(name: &&str) -> Result<(), String> {
+    match *name {
+        "admin" => Err("is reserved".to_string()),
+        _ => Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Address {
+    #[form(validate = "len(5..6)")]
+    zip: String,
+}
+
+#[derive(Debug, PartialEq, FromForm)]
+struct Signup<'r> {
+    #[form(validate = "len(1..8)", validate = "not_admin")]
+    name: &'r str,
+    #[form(validate = "range(18..150)")]
+    age: u8,
+    #[form(validate = "range(0.0..5.0)")]
+    rating: Option<f32>,
+    #[form(validate = "len(0..3)")]
+    tags: Vec<String>,
+    address: Address,
+}
+
+fn invalid(field: &str, message: &str) -> FormError {
+    FormError::Invalid { field: field.to_string(), message: message.to_string() }
+}
+
+fn main() {
+    let signup = Signup::from_form_string("name=bob&age=42&address.zip=12345");
+    assert_eq!(signup, Ok(Signup {
+        name: "bob",
+        age: 42,
+        rating: None,
+        tags: vec![],
+        address: Address { zip: "12345".to_string() }
+    }));
+
+    let signup = Signup::from_form_string("name=admin&age=42&address.zip=12345");
+    assert_eq!(signup, Err(invalid("name", "is reserved")));
+
+    let signup = Signup::from_form_string("name=bob&age=12&rating=7&address.zip=1");
+    assert_eq!(signup, Err(invalid("age", "must be at least 18 and less than 150")));
+
+    // All of the errors are reported by `Validated`, in field order, with
+    // unexpected fields first and missing fields last.
+    let form = "x=1&name=&age=ten&rating=7&tags=a&tags=b&tags=c&address.zip=1";
+    let errors = Validated::<Signup>::from_form_string(form).unwrap().into_result().unwrap_err();
+    let fields: Vec<_> = errors.iter().map(|e| e.field()).collect();
+    assert_eq!(fields, vec!["x", "name", "age", "rating", "tags", "address.zip"]);
+    assert_eq!(errors.for_field("age"), vec![&FormError::BadValue {
+        field: "age".to_string(),
+        value: "ten".to_string(),
+        expected: "u8"
+    }]);
+
+    let errors = Validated::<Signup>::from_form_string("name=a").unwrap().0.unwrap_err();
+    let mut expected = FormErrors::new();
+    expected.push(FormError::Missing { field: "age".to_string(), expected: "u8" });
+    expected.push(FormError::Missing { field: "address".to_string(), expected: "Address" });
+    assert_eq!(errors, expected);
+
+    // A lenient `Validated` ignores unexpected fields.
+    let form = "x=1&name=a&age=20&address.zip=54321";
+    let signup = Validated::<Signup>::from_form_string_lenient(form);
+    assert!(signup.unwrap().0.is_ok());
+}
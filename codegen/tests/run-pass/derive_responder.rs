@@ -0,0 +1,90 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::response::{Responder, Response};
+use rocket::http::{Status, ContentType};
+
+#[derive(Responder)]
+#[response(content_type = "text/plain")]
+enum ApiError<'a> {
+    #[response(status = 404)]
+    NotFound(&'a str),
+    #[response(status = 400, content_type = "application/json")]
+    Invalid(String),
+    #[response(status = 503)]
+    Unavailable {
+        message: String,
+        #[response(header = "Retry-After")]
+        retry_after: u32,
+    },
+    #[response(status = 401)]
+    Unauthorized,
+}
+
+#[derive(Responder)]
+#[response(status = 201)]
+struct Created<R> {
+    #[response(header = "Location")]
+    location: String,
+    body: Option<R>,
+    #[response(header)]
+    format: ContentType,
+}
+
+#[derive(Responder)]
+struct Tagged(String, #[response(header = "X-Tag")] &'static str);
+
+fn header(response: &Response, name: &str) -> Option<String> {
+    response.header_values(name).next().map(|v| v.to_string())
+}
+
+fn body(response: &mut Response) -> Option<String> {
+    response.body().and_then(|b| b.into_string())
+}
+
+fn main() {
+    let mut response = ApiError::NotFound("nope").respond().unwrap();
+    assert_eq!(response.status(), Status::NotFound);
+    assert_eq!(header(&response, "Content-Type"), Some("text/plain".into()));
+    assert_eq!(body(&mut response), Some("nope".into()));
+
+    let response = ApiError::Invalid("{}".into()).respond().unwrap();
+    assert_eq!(response.status(), Status::BadRequest);
+    assert_eq!(header(&response, "Content-Type"), Some("application/json".into()));
+
+    let error = ApiError::Unavailable { message: "later".into(), retry_after: 30 };
+    let response = error.respond().unwrap();
+    assert_eq!(response.status(), Status::ServiceUnavailable);
+    assert_eq!(header(&response, "Retry-After"), Some("30".into()));
+
+    let mut response = ApiError::Unauthorized.respond().unwrap();
+    assert_eq!(response.status(), Status::Unauthorized);
+    assert_eq!(body(&mut response), None);
+
+    let location = "/items/1".to_string();
+    let created = Created { location: location, body: Some("hi"), format: ContentType::JSON };
+    let mut response = created.respond().unwrap();
+    assert_eq!(response.status(), Status::Created);
+    assert_eq!(header(&response, "Location"), Some("/items/1".into()));
+    assert_eq!(header(&response, "Content-Type"), Some("application/json".into()));
+    assert_eq!(body(&mut response), Some("hi".into()));
+
+    // The inner responder's failure is the wrapper's failure.
+    let created: Created<&str> = Created {
+        location: "/".into(),
+        body: None,
+        format: ContentType::JSON
+    };
+
+    assert_eq!(created.respond().err(), Some(Status::NotFound));
+
+    // Invalid header values fail the response.
+    let response = Tagged("hi".into(), "a\r\nb").respond();
+    assert_eq!(response.err(), Some(Status::InternalServerError));
+
+    let response = Tagged("hi".into(), "v1").respond().unwrap();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(header(&response, "X-Tag"), Some("v1".into()));
+}
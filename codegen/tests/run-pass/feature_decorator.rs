@@ -0,0 +1,20 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[get("/", feature = "beta")]
+fn beta() -> &'static str { "beta" }
+
+#[get("/", rank = 2)]
+fn stable() -> &'static str { "stable" }
+
+#[post("/", feature = "beta", rank = 3)]
+fn post_beta() -> &'static str { "beta" }
+
+fn main() {
+    let routes = routes![beta, stable, post_beta];
+    assert_eq!(routes[0].feature, Some("beta".to_string()));
+    assert_eq!(routes[1].feature, None);
+    assert_eq!(routes[2].feature, Some("beta".to_string()));
+}
@@ -0,0 +1,39 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+/// Returns a post.
+///
+/// * `id` - The post's ID.
+/// * `lang`: The language to render the post in.
+/// * `other` - Not a parameter.
+#[get("/posts/<id>?<lang>")]
+fn post(id: usize, lang: &str) -> String { format!("{} {}", id, lang) }
+
+/// Creates a post.
+///
+/// * `title` - Overridden by the attribute.
+#[post("/posts/<title>", data = "<body>")]
+#[param(name = "body", description = "The post's body.")]
+#[param(name = "title", description = "The post's title.")]
+fn create(title: &str, body: String) -> String { format!("{}: {}", title, body) }
+
+#[get("/")]
+fn index() -> &'static str { "index" }
+
+fn main() {
+    let routes = routes![post, create, index];
+    assert_eq!(routes[0].param_docs, vec![
+        ("id", "The post's ID."),
+        ("lang", "The language to render the post in.")
+    ]);
+
+    assert_eq!(routes[1].param_docs, vec![
+        ("title", "The post's title."),
+        ("body", "The post's body.")
+    ]);
+
+    assert_eq!(routes[1].param_description("body"), Some("The post's body."));
+    assert!(routes[2].param_docs.is_empty());
+}
@@ -0,0 +1,11 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[get("/test/<one:uint>/<two:[a-z-]+>/<three:[0-9]{2,4}>")]
+fn get(one: usize, two: &str, three: isize) -> &'static str { "hi" }
+
+fn main() {
+    let _ = routes![get];
+}
@@ -0,0 +1,328 @@
+use std::io::{self, Read, BufRead};
+use std::marker::PhantomData;
+use std::fmt;
+
+use rocket::outcome::Outcome;
+use rocket::request::Request;
+use rocket::data::{self, Data, FromData};
+use rocket::response::{self, Responder, Response};
+use rocket::http::ContentType;
+use rocket::config::ByteUnit;
+
+use super::serde::{Serialize, Deserialize};
+use super::serde_json;
+use super::JsonError;
+
+/// Newline-delimited JSON: a sequence of JSON values, one per line, streamed
+/// to or from the client.
+///
+/// `JsonLines` wraps an iterator. As a responder, it streams the JSON
+/// serialization of each item of the iterator on its own line, as it's
+/// produced, so arbitrarily large exports can be sent without collecting them
+/// in memory. As a data guard, `JsonLines<JsonItems<T>>` deserializes the
+/// lines of the request body one at a time, as they're iterated over.
+///
+/// # Responding
+///
+/// Return a `JsonLines<I>`, where `I` can be turned into an iterator of items
+/// implementing `Serialize`. The response has a Content-Type of
+/// `application/x-ndjson` and is sent with chunked transfer encoding; each line
+/// is flushed to the client as soon as it's serialized. If an item fails to
+/// serialize, the error is logged and the response ends abruptly.
+///
+/// ```rust,ignore
+/// #[get("/users/export")]
+/// fn export(db: State<Database>) -> JsonLines<UserIter> {
+///     JsonLines(db.users())
+/// }
+/// ```
+///
+/// # Receiving
+///
+/// Add a `data` parameter of type `JsonLines<JsonItems<T>>`, where `T`
+/// implements `Deserialize`. The guard forwards unless the request's
+/// Content-Type is `application/x-ndjson`, `application/x-jsonlines`, or
+/// `application/jsonl`. Iterating over it yields a `Result<T, JsonError>` for
+/// each non-blank line of the body; the line numbers in errors are those of the
+/// body. A line that isn't valid JSON yields an `Err` and iteration moves on to
+/// the next line. A line longer than the **json** limit, described in
+/// [Limits](/rocket/config/struct.Limits.html), yields a
+/// `JsonError::TooLarge`, as does a failure to read the body, and ends
+/// iteration.
+///
+/// ```rust,ignore
+/// #[post("/users/import", data = "<users>")]
+/// fn import(users: JsonLines<JsonItems<User>>) -> String {
+///     let (mut imported, mut failed) = (0, 0);
+///     for user in users {
+///         match user {
+///             Ok(user) => { create(user); imported += 1 }
+///             Err(_) => failed += 1
+///         }
+///     }
+///
+///     format!("imported {}, failed {}", imported, failed)
+/// }
+/// ```
+#[derive(Debug)]
+pub struct JsonLines<I>(pub I);
+
+impl<I> JsonLines<I> {
+    /// Consumes the wrapper and returns the wrapped iterator.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use rocket_contrib::JsonLines;
+    /// let lines = JsonLines(vec![1, 2, 3]);
+    /// assert_eq!(lines.into_inner(), vec![1, 2, 3]);
+    /// ```
+    #[inline(always)]
+    pub fn into_inner(self) -> I {
+        self.0
+    }
+}
+
+impl<I: Iterator> Iterator for JsonLines<I> {
+    type Item = I::Item;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<I::Item> {
+        self.0.next()
+    }
+}
+
+/// A reader of the newline-delimited JSON serialization of the items of an
+/// iterator, serializing one item per read.
+struct LinesReader<I> {
+    items: I,
+    line: Vec<u8>,
+    position: usize,
+}
+
+impl<I: Iterator> Read for LinesReader<I> where I::Item: Serialize {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position == self.line.len() {
+            let item = match self.items.next() {
+                Some(item) => item,
+                None => return Ok(0)
+            };
+
+            self.line = serde_json::to_vec(&item).map_err(|e| {
+                error_!("JSON line failed to serialize: {:?}", e);
+                io::Error::new(io::ErrorKind::InvalidData, e)
+            })?;
+
+            self.line.push(b'\n');
+            self.position = 0;
+        }
+
+        let n = (&self.line[self.position..]).read(buf)?;
+        self.position += n;
+        Ok(n)
+    }
+}
+
+/// Streams each item of the wrapped iterator as a line of JSON with a
+/// Content-Type of `application/x-ndjson`, using chunked transfer encoding.
+impl<'r, I> Responder<'r> for JsonLines<I>
+    where I: IntoIterator, I::IntoIter: 'r, I::Item: Serialize
+{
+    fn respond(self) -> response::Result<'r> {
+        let reader = LinesReader { items: self.0.into_iter(), line: vec![], position: 0 };
+        Response::build()
+            .header(ContentType::new("application", "x-ndjson"))
+            .flushed_body(reader, 4096)
+            .ok()
+    }
+}
+
+/// An iterator over the values of the lines of a newline-delimited JSON
+/// request body, deserialized as they're read. See
+/// [JsonLines](struct.JsonLines.html).
+pub struct JsonItems<T> {
+    reader: Box<BufRead>,
+    limit: ByteUnit,
+    line: usize,
+    done: bool,
+    _item: PhantomData<T>,
+}
+
+impl<T> JsonItems<T> {
+    /// Returns an iterator over the values of the lines read from `reader`,
+    /// each of which may be at most `limit` bytes long.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # extern crate rocket;
+    /// # extern crate rocket_contrib;
+    /// # fn main() {
+    /// use std::io::Cursor;
+    /// use rocket::config::ByteUnit;
+    /// use rocket_contrib::JsonItems;
+    ///
+    /// let body = Cursor::new("1\n\n[2]\n3\n");
+    /// let items: Vec<_> = JsonItems::<usize>::new(body, ByteUnit::new(16)).collect();
+    /// assert_eq!(items[0].as_ref().ok(), Some(&1));
+    /// assert_eq!(items[1].as_ref().err().and_then(|e| e.location()).map(|l| l.0), Some(3));
+    /// assert_eq!(items[2].as_ref().ok(), Some(&3));
+    /// # }
+    /// ```
+    pub fn new<R: BufRead + 'static>(reader: R, limit: ByteUnit) -> JsonItems<T> {
+        JsonItems {
+            reader: Box::new(reader),
+            limit: limit,
+            line: 0,
+            done: false,
+            _item: PhantomData,
+        }
+    }
+
+    /// Returns the number of lines read so far.
+    #[inline(always)]
+    pub fn lines_read(&self) -> usize {
+        self.line
+    }
+}
+
+impl<T: Deserialize> Iterator for JsonItems<T> {
+    type Item = Result<T, JsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            // Read one byte past the limit to find out if the limit is exceeded.
+            let mut line = vec![];
+            let limit = self.limit.as_u64();
+            if let Err(e) = self.reader.by_ref().take(limit + 1).read_until(b'\n', &mut line) {
+                self.done = true;
+                return Some(Err(JsonError::Io(e)));
+            }
+
+            if line.is_empty() {
+                self.done = true;
+                return None;
+            }
+
+            self.line += 1;
+            if line.len() as u64 > limit && !line.ends_with(b"\n") {
+                self.done = true;
+                return Some(Err(JsonError::TooLarge(self.limit)));
+            }
+
+            if line.iter().all(|&b| b == b' ' || b == b'\t' || b == b'\r' || b == b'\n') {
+                continue;
+            }
+
+            // Errors are located within the line; locate them in the body.
+            let number = self.line;
+            return Some(serde_json::from_slice(&line).map_err(|e| {
+                match JsonError::from(e) {
+                    JsonError::Syntax { message, column, .. } => {
+                        JsonError::Syntax { message: message, line: number, column: column }
+                    }
+                    JsonError::Data { message, field, column, .. } => JsonError::Data {
+                        message: message,
+                        field: field,
+                        line: number,
+                        column: column
+                    },
+                    error => error
+                }
+            }));
+        }
+
+        None
+    }
+}
+
+impl<T> fmt::Debug for JsonItems<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("JsonItems")
+            .field("limit", &self.limit)
+            .field("line", &self.line)
+            .field("done", &self.done)
+            .finish()
+    }
+}
+
+/// Returns `true` if `content_type` is one of the media types used for
+/// newline-delimited JSON.
+fn is_json_lines(content_type: &ContentType) -> bool {
+    content_type.ttype == "application" && match &*content_type.subtype {
+        "x-ndjson" | "x-jsonlines" | "jsonl" => true,
+        _ => false
+    }
+}
+
+/// Forwards unless the request's Content-Type is that of newline-delimited
+/// JSON. Otherwise always succeeds: errors are reported by the iterator.
+impl<T: Deserialize> FromData for JsonLines<JsonItems<T>> {
+    type Error = ();
+
+    fn from_data(request: &Request, data: Data) -> data::Outcome<Self, ()> {
+        if !is_json_lines(&request.content_type()) {
+            warn_!("Content-Type is not newline-delimited JSON.");
+            return Outcome::Forward(data);
+        }
+
+        let limit = request.limits().get("json");
+        Outcome::Success(JsonLines(JsonItems::new(data.open(), limit)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Cursor, Read};
+
+    use rocket::config::ByteUnit;
+    use super::{JsonItems, LinesReader};
+    use super::super::JsonError;
+
+    #[test]
+    fn test_lines_reader() {
+        let items = vec![vec![1, 2], vec![], vec![3]];
+        let mut reader = LinesReader { items: items.into_iter(), line: vec![], position: 0 };
+
+        let mut buf = [0; 4];
+        assert_eq!(reader.read(&mut buf).unwrap(), 4);
+        assert_eq!(&buf, b"[1,2");
+
+        let mut rest = String::new();
+        reader.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "]\n[]\n[3]\n");
+    }
+
+    #[test]
+    fn test_items() {
+        let body = Cursor::new("{\"a\": 1}\r\n  \n{\"a\": }\n[1,2,3]\n");
+        let mut items = JsonItems::<Vec<usize>>::new(body, ByteUnit::new(32));
+
+        match items.next() {
+            Some(Err(JsonError::Data { line: 1, .. })) => {  },
+            other => panic!("unexpected item: {:?}", other)
+        }
+
+        match items.next() {
+            Some(Err(JsonError::Syntax { line: 3, .. })) => {  },
+            other => panic!("unexpected item: {:?}", other)
+        }
+
+        assert_eq!(items.next().map(|r| r.ok()), Some(Some(vec![1, 2, 3])));
+        assert!(items.next().is_none());
+        assert_eq!(items.lines_read(), 4);
+    }
+
+    #[test]
+    fn test_items_limit() {
+        let body = Cursor::new("[1,2]\n[1,2,3,4]\n[1]\n");
+        let mut items = JsonItems::<Vec<usize>>::new(body, ByteUnit::new(6));
+
+        assert_eq!(items.next().map(|r| r.ok()), Some(Some(vec![1, 2])));
+        match items.next() {
+            Some(Err(JsonError::TooLarge(_))) => {  },
+            other => panic!("unexpected item: {:?}", other)
+        }
+
+        assert!(items.next().is_none());
+    }
+}
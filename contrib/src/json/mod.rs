@@ -2,16 +2,22 @@ extern crate serde;
 extern crate serde_json;
 
 use std::ops::{Deref, DerefMut};
-use std::io::Read;
+use std::io::{self, Read};
+use std::fmt;
 
 use rocket::outcome::Outcome;
 use rocket::request::Request;
-use rocket::data::{self, Data, FromData};
+use rocket::data::{self, Data, FromData, Charset};
 use rocket::response::{self, Responder, content};
 use rocket::http::Status;
+use rocket::config::ByteUnit;
 
 use self::serde::{Serialize, Deserialize};
-use self::serde_json::error::Error as SerdeError;
+use self::serde_json::error::{Error as SerdeError, ErrorCode};
+
+mod lines;
+
+pub use self::lines::{JsonLines, JsonItems};
 
 /// The JSON type, which implements `FromData` and `Responder`. This type allows
 /// you to trivially consume and respond with JSON in your Rocket application.
@@ -33,6 +39,13 @@ use self::serde_json::error::Error as SerdeError;
 /// doesn't specify "application/json" as its first `Content-Type:` header
 /// parameter will not be routed to this handler.
 ///
+/// The body is decoded from the charset declared by its Content-Type, as
+/// described in [Charset](/rocket/data/enum.Charset.html), and a leading UTF-8
+/// byte order mark is ignored. If the body can't be parsed, the request fails
+/// with a status of `400 Bad Request`. To handle the failure instead, use a
+/// `Result<JSON<T>, JsonError>`; the [JsonError](enum.JsonError.html) locates
+/// the problem in the body.
+///
 /// If you're responding with JSON data, return a `JSON<T>` type, where `T`
 /// implements `Serialize` from [Serde](https://github.com/serde-rs/json). The
 /// content type of the response is set to `application/json` automatically.
@@ -64,25 +77,164 @@ impl<T> JSON<T> {
     }
 }
 
-/// Maximum size of JSON is 1MB.
-/// TODO: Determine this size from some configuration parameter.
-const MAX_SIZE: u64 = 1048576;
-
 impl<T: Deserialize> FromData for JSON<T> {
-    type Error = SerdeError;
+    type Error = JsonError;
 
-    fn from_data(request: &Request, data: Data) -> data::Outcome<Self, SerdeError> {
+    fn from_data(request: &Request, data: Data) -> data::Outcome<Self, JsonError> {
         if !request.content_type().is_json() {
             error_!("Content-Type is not JSON.");
             return Outcome::Forward(data);
         }
 
-        let reader = data.open().take(MAX_SIZE);
-        match serde_json::from_reader(reader).map(|val| JSON(val)) {
+        let limit = request.limits().get("json");
+
+        // Read one byte past the limit to find out if the limit is exceeded.
+        let mut body = vec![];
+        if let Err(e) = data.open().take(limit.as_u64() + 1).read_to_end(&mut body) {
+            let error = JsonError::Io(e);
+            error_!("Couldn't read JSON body: {}", error);
+            return Outcome::Failure((Status::BadRequest, error));
+        }
+
+        if body.len() as u64 > limit.as_u64() {
+            let error = JsonError::TooLarge(limit);
+            error_!("Couldn't parse JSON body: {}", error);
+            return Outcome::Failure((Status::PayloadTooLarge, error));
+        }
+
+        // Decode the body from its charset, removing any byte order mark.
+        let decoded = Charset::of(&request.content_type()).and_then(|c| c.decode(body));
+        let body = match decoded {
+            Ok(body) => body,
+            Err(e) => {
+                let error = JsonError::Io(e);
+                error_!("Couldn't read JSON body: {}", error);
+                return Outcome::Failure((Status::BadRequest, error));
+            }
+        };
+
+        match serde_json::from_str(&body).map(|val| JSON(val)) {
             Ok(value) => Outcome::Success(value),
             Err(e) => {
-                error_!("Couldn't parse JSON body: {:?}", e);
-                Outcome::Failure((Status::BadRequest, e))
+                let error = JsonError::from(e);
+                error_!("Couldn't parse JSON body: {}", error);
+                Outcome::Failure((Status::BadRequest, error))
+            }
+        }
+    }
+}
+
+/// The error returned by the [JSON](struct.JSON.html) data guard when the
+/// request body couldn't be read or parsed.
+///
+/// Errors in the body carry the line and column, both starting at 1, at which
+/// the error was detected. Handlers can receive the error by accepting a
+/// `Result<JSON<T>, JsonError>`; when the guard fails instead, the error is
+/// recorded as the request's
+/// [guard_failure](/rocket/struct.Request.html#method.guard_failure) so that
+/// error catchers can retrieve it.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[post("/users", format = "application/json", data = "<user>")]
+/// fn new_user(user: Result<JSON<User>, JsonError>) -> status::Custom<String> {
+///     match user {
+///         Ok(user) => status::Custom(Status::Created, create(user.unwrap())),
+///         Err(e @ JsonError::Data { .. }) => {
+///             status::Custom(Status::UnprocessableEntity, e.to_string())
+///         }
+///         Err(e) => status::Custom(Status::BadRequest, e.to_string())
+///     }
+/// }
+/// ```
+///
+/// A catcher can report the same error for routes that accept a `JSON<T>`:
+///
+/// ```rust,ignore
+/// #[error(400)]
+/// fn bad_request(req: &Request) -> String {
+///     let failure = req.guard_failure();
+///     match failure.as_ref().and_then(|f| f.downcast_ref::<JsonError>()) {
+///         Some(e) => format!("{} (field: {:?})", e, e.field()),
+///         None => "Bad request.".to_string()
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub enum JsonError {
+    /// Reading the body failed. This includes bodies that aren't UTF-8.
+    Io(io::Error),
+    /// The body isn't valid JSON.
+    Syntax {
+        message: String,
+        line: usize,
+        column: usize
+    },
+    /// The body is valid JSON, but it doesn't describe a value of the expected
+    /// type. When the problem is a single field, such as a missing or unknown
+    /// field, `field` is its name.
+    Data {
+        message: String,
+        field: Option<String>,
+        line: usize,
+        column: usize
+    },
+    /// The body exceeds the **json** limit, the field, described in
+    /// [Limits](/rocket/config/struct.Limits.html). Requests with such bodies
+    /// are answered with `413 Payload Too Large`.
+    TooLarge(ByteUnit),
+}
+
+impl JsonError {
+    /// Returns the line and column at which the error was detected, if the
+    /// error is in the body.
+    pub fn location(&self) -> Option<(usize, usize)> {
+        match *self {
+            JsonError::Io(_) | JsonError::TooLarge(_) => None,
+            JsonError::Syntax { line, column, .. } => Some((line, column)),
+            JsonError::Data { line, column, .. } => Some((line, column)),
+        }
+    }
+
+    /// Returns the name of the field that caused the error, if known.
+    pub fn field(&self) -> Option<&str> {
+        match *self {
+            JsonError::Data { field: Some(ref field), .. } => Some(field),
+            _ => None
+        }
+    }
+}
+
+impl From<SerdeError> for JsonError {
+    fn from(error: SerdeError) -> JsonError {
+        let (code, line, column) = match error {
+            SerdeError::Syntax(code, line, column) => (code, line, column),
+            SerdeError::Io(e) => return JsonError::Io(e),
+            e => return JsonError::Io(io::Error::new(io::ErrorKind::InvalidData, e)),
+        };
+
+        let message = format!("{:?}", code);
+        let field = match code {
+            ErrorCode::MissingField(field) => Some(field.to_string()),
+            ErrorCode::UnknownField(field) => Some(field),
+            ErrorCode::Custom(_) | ErrorCode::InvalidType(_) | ErrorCode::InvalidValue(_)
+                | ErrorCode::InvalidLength(_) | ErrorCode::UnknownVariant(_) => None,
+            _ => return JsonError::Syntax { message: message, line: line, column: column }
+        };
+
+        JsonError::Data { message: message, field: field, line: line, column: column }
+    }
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            JsonError::Io(ref e) => write!(f, "failed to read JSON: {}", e),
+            JsonError::TooLarge(limit) => write!(f, "JSON exceeds the limit of {}", limit),
+            JsonError::Syntax { ref message, line, column } |
+                JsonError::Data { ref message, line, column, .. } => {
+                write!(f, "{} at line {} column {}", message, line, column)
             }
         }
     }
@@ -11,6 +11,14 @@
 //! an asterisk next to the features that are enabled by default:
 //!
 //! * [json*](struct.JSON.html)
+//! * [json*](struct.JsonLines.html) (newline-delimited)
+//! * [query](struct.Query.html)
+//! * [well_known](struct.WellKnown.html)
+//! * [tokens](struct.Token.html)
+//! * [tokens](struct.CsrfConfig.html) (CSRF protection)
+//! * [tus](struct.Tus.html)
+//! * [mirror](struct.Mirror.html)
+//! * [sentry](struct.Sentry.html)
 //! * [handlebars_templates](struct.Template.html)
 //! * [tera_templates](struct.Template.html)
 //!
@@ -43,8 +51,47 @@ mod json;
 #[cfg(feature = "templates")]
 mod templates;
 
+#[cfg(feature = "query")]
+mod query;
+
+#[cfg(feature = "well_known")]
+mod well_known;
+
+#[cfg(feature = "tokens")]
+mod token;
+
+#[cfg(feature = "tus")]
+mod tus;
+
+#[cfg(feature = "mirror")]
+mod mirror;
+
+#[cfg(feature = "sentry")]
+mod sentry;
+
 #[cfg(feature = "json")]
-pub use json::JSON;
+pub use json::{JSON, JsonError, JsonLines, JsonItems};
 
 #[cfg(feature = "templates")]
 pub use templates::Template;
+
+#[cfg(feature = "query")]
+pub use query::{Query, QueryError};
+
+#[cfg(feature = "well_known")]
+pub use well_known::WellKnown;
+
+#[cfg(feature = "tokens")]
+pub use token::{Token, TokenKey, TokenPurpose, TokenError};
+
+#[cfg(feature = "tokens")]
+pub use token::{CsrfConfig, CsrfGroup, AllRoutes, CsrfToken, CsrfHeader, CsrfForm, CsrfError};
+
+#[cfg(feature = "tus")]
+pub use tus::{Tus, TusUpload};
+
+#[cfg(feature = "mirror")]
+pub use mirror::Mirror;
+
+#[cfg(feature = "sentry")]
+pub use sentry::Sentry;
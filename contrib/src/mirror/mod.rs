@@ -0,0 +1,404 @@
+extern crate hyper;
+
+use std::ascii::AsciiExt;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{sync_channel, SyncSender, Receiver, TrySendError};
+use std::thread;
+use std::time::Duration;
+
+use rocket::{Request, Data};
+
+use self::hyper::Client;
+use self::hyper::header::Headers;
+use self::hyper::method::Method;
+
+/// Headers that describe a single connection or the framing of a body, and so
+/// aren't copied into mirrored requests.
+const HOP_BY_HOP: &'static [&'static str] = &[
+    "Connection", "Keep-Alive", "Proxy-Authenticate", "Proxy-Authorization", "TE",
+    "Trailer", "Transfer-Encoding", "Upgrade", "Content-Length", "Host"
+];
+
+/// A copy of an incoming request, to be sent to the shadow upstream.
+#[derive(Debug, Clone, PartialEq)]
+struct Shadow {
+    method: String,
+    uri: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Shadow {
+    /// Copies `request`, with the body `body`, unless the body is incomplete
+    /// or longer than `limit` bytes.
+    fn capture(request: &Request, body: &[u8], complete: bool, limit: usize)
+            -> Option<Shadow> {
+        if !complete || body.len() > limit {
+            return None;
+        }
+
+        let headers = request.headers().iter()
+            .filter(|h| !HOP_BY_HOP.iter().any(|name| h.name.eq_ignore_ascii_case(name)))
+            .map(|h| (h.name.to_string(), h.value.to_string()))
+            .collect();
+
+        Some(Shadow {
+            method: request.method().to_string(),
+            uri: request.uri().as_str().to_string(),
+            headers: headers,
+            body: body.to_vec(),
+        })
+    }
+
+    /// Sends `self` to `upstream` and discards the response.
+    fn send(self, client: &Client, upstream: &str) -> Result<(), String> {
+        let method: Method = self.method.parse().map_err(|e| format!("{}", e))?;
+        let url = format!("{}{}", upstream.trim_right_matches('/'), self.uri);
+
+        let mut headers = Headers::new();
+        for (name, value) in self.headers {
+            let mut values = headers.get_raw(&name)
+                .map(|values| values.to_vec())
+                .unwrap_or_else(Vec::new);
+
+            values.push(value.into_bytes());
+            headers.set_raw(name, values);
+        }
+
+        let mut response = client.request(method, &*url)
+            .headers(headers)
+            .body(&*self.body)
+            .send()
+            .map_err(|e| format!("{}", e))?;
+
+        // Reading the response to its end lets the connection be reused.
+        io::copy(&mut response, &mut io::sink()).map_err(|e| format!("{}", e))?;
+        debug!("Mirrored {} {} to {}: {}.", self.method, self.uri, upstream, response.status);
+        Ok(())
+    }
+}
+
+/// Mirrors a sample of incoming requests to a shadow upstream, such as a new
+/// backend being tested with production traffic.
+///
+/// A `Mirror` is attached to an application as a request hook, via
+/// `Rocket::on_request`: each request satisfying the hook's
+/// [Predicate](/rocket/hook/struct.Predicate.html) is counted, and a
+/// percentage of them, evenly spaced, is copied and sent to the upstream in
+/// the background. The copy has the request's method, URI, headers, and body;
+/// hop-by-hop headers, such as `Connection` and `Transfer-Encoding`, and the
+/// `Host` header are left out. The upstream's responses are read and
+/// discarded.
+///
+/// Mirroring never affects the real response. Copies are queued for a pool of
+/// worker threads, and when the queue is full, because the upstream is slow or
+/// down, new copies are dropped instead of waiting. Requests are sent with a
+/// timeout, and failures are logged and otherwise ignored.
+///
+/// # Bodies
+///
+/// Bodies are copied from the request's
+/// [peek buffer](/rocket/struct.Data.html#method.peek), which is read before
+/// the request is routed and holds up to 4KiB of the body. A request is only
+/// mirrored if its whole body is in the peek buffer and is no longer than the
+/// [body limit](#method.body_limit), so that the upstream never sees a
+/// truncated body. Requests without bodies are always eligible.
+///
+/// # Example
+///
+/// Mirror 10% of the requests under `/api` to a shadow deployment:
+///
+/// ```rust
+/// # extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use rocket::hook::Predicate;
+/// use rocket_contrib::Mirror;
+///
+/// # fn main() {
+/// let mirror = Mirror::new("http://shadow.internal:8000").sample(10);
+///
+/// # if false {
+/// rocket::ignite()
+///     .on_request(Predicate::path("/api"), move |req, data| mirror.mirror(req, data))
+///     .launch()
+/// # }
+/// # }
+/// ```
+pub struct Mirror {
+    upstream: String,
+    percent: u8,
+    body_limit: usize,
+    queue: usize,
+    workers: usize,
+    timeout: Duration,
+    seen: AtomicUsize,
+    sender: Mutex<Option<SyncSender<Shadow>>>,
+}
+
+impl Mirror {
+    /// Creates a new `Mirror` that sends copies of every request to
+    /// `upstream`, the base URL of the shadow server, such as
+    /// `http://shadow.internal:8000`. The request's URI is appended to it.
+    ///
+    /// By default, bodies of up to 4KiB are mirrored, up to 128 copies are
+    /// queued for 2 worker threads, and requests to the upstream time out
+    /// after 5 seconds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::Mirror;
+    ///
+    /// # #[allow(unused_variables)]
+    /// let mirror = Mirror::new("http://10.0.0.7:8000");
+    /// ```
+    pub fn new<S: Into<String>>(upstream: S) -> Mirror {
+        Mirror {
+            upstream: upstream.into(),
+            percent: 100,
+            body_limit: 4096,
+            queue: 128,
+            workers: 2,
+            timeout: Duration::from_secs(5),
+            seen: AtomicUsize::new(0),
+            sender: Mutex::new(None),
+        }
+    }
+
+    /// Sets the percentage of requests that are mirrored to `percent`, which
+    /// is capped at 100. A `percent` of `0` disables mirroring.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::Mirror;
+    ///
+    /// # #[allow(unused_variables)]
+    /// let mirror = Mirror::new("http://10.0.0.7:8000").sample(5);
+    /// ```
+    #[inline]
+    pub fn sample(mut self, percent: u8) -> Mirror {
+        self.percent = ::std::cmp::min(percent, 100);
+        self
+    }
+
+    /// Sets the largest body, in bytes, that is mirrored. Requests with
+    /// larger bodies aren't mirrored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::Mirror;
+    ///
+    /// # #[allow(unused_variables)]
+    /// let mirror = Mirror::new("http://10.0.0.7:8000").body_limit(1024);
+    /// ```
+    #[inline]
+    pub fn body_limit(mut self, bytes: usize) -> Mirror {
+        self.body_limit = bytes;
+        self
+    }
+
+    /// Sets the number of copies that may wait to be sent to `queue`. Copies
+    /// made while the queue is full are dropped.
+    #[inline]
+    pub fn queue(mut self, queue: usize) -> Mirror {
+        self.queue = queue;
+        self
+    }
+
+    /// Sets the number of threads that send copies to the upstream to
+    /// `workers`, which is at least 1.
+    #[inline]
+    pub fn workers(mut self, workers: usize) -> Mirror {
+        self.workers = ::std::cmp::max(workers, 1);
+        self
+    }
+
+    /// Sets the read and write timeout of requests to the upstream.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Mirror {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Counts `request` and, if it's sampled and its body is eligible, queues
+    /// a copy of it to be sent to the upstream. This is the request hook.
+    ///
+    /// The worker threads are started when the first copy is queued.
+    pub fn mirror(&self, request: &Request, data: &Data) {
+        if !self.sampled() {
+            return;
+        }
+
+        let shadow = match Shadow::capture(request, data.peek(), data.peek_complete(),
+                                           self.body_limit) {
+            Some(shadow) => shadow,
+            None => {
+                debug!("Not mirroring {}: its body isn't buffered in full.", request);
+                return;
+            }
+        };
+
+        let mut sender = self.sender.lock().expect("mirror sender lock");
+        if sender.is_none() {
+            *sender = Some(self.spawn_workers());
+        }
+
+        match sender.as_ref().map(|sender| sender.try_send(shadow)) {
+            Some(Ok(())) | None => {  }
+            Some(Err(TrySendError::Full(shadow))) => {
+                warn_!("Mirror queue is full: dropping {} {}.", shadow.method, shadow.uri);
+            }
+            Some(Err(TrySendError::Disconnected(_))) => {
+                error_!("Mirror workers have stopped: requests are no longer mirrored.");
+            }
+        }
+    }
+
+    /// Returns `true` if the next request should be mirrored. Mirrored
+    /// requests are evenly spaced: with a 10% sample, every tenth request is.
+    fn sampled(&self) -> bool {
+        let n = self.seen.fetch_add(1, Ordering::Relaxed) as u64;
+        let percent = self.percent as u64;
+        (n * percent) / 100 != ((n + 1) * percent) / 100
+    }
+
+    /// Starts the worker threads and returns the sender of their queue.
+    fn spawn_workers(&self) -> SyncSender<Shadow> {
+        let (sender, receiver) = sync_channel(self.queue);
+        let receiver = Arc::new(Mutex::new(receiver));
+        for i in 0..self.workers {
+            let receiver = receiver.clone();
+            let (upstream, timeout) = (self.upstream.clone(), self.timeout);
+            let spawned = thread::Builder::new()
+                .name(format!("rocket-mirror-{}", i))
+                .spawn(move || work(receiver, upstream, timeout));
+
+            if let Err(e) = spawned {
+                error_!("Failed to start a mirror worker: {}.", e);
+            }
+        }
+
+        sender
+    }
+}
+
+/// Sends the copies received on `receiver` to `upstream` until the `Mirror`
+/// is dropped.
+fn work(receiver: Arc<Mutex<Receiver<Shadow>>>, upstream: String, timeout: Duration) {
+    let mut client = Client::new();
+    client.set_read_timeout(Some(timeout));
+    client.set_write_timeout(Some(timeout));
+
+    loop {
+        let shadow = match receiver.lock().map(|receiver| receiver.recv()) {
+            Ok(Ok(shadow)) => shadow,
+            _ => return
+        };
+
+        let (method, uri) = (shadow.method.clone(), shadow.uri.clone());
+        if let Err(e) = shadow.send(&client, &upstream) {
+            warn_!("Failed to mirror {} {} to {}: {}.", method, uri, upstream, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Read, BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    use super::{Mirror, Shadow};
+    use rocket::Request;
+    use rocket::http::{Method, Header};
+
+    fn mirrored(mirror: &Mirror, requests: usize) -> usize {
+        (0..requests).filter(|_| mirror.sampled()).count()
+    }
+
+    #[test]
+    fn test_sampling() {
+        assert_eq!(mirrored(&Mirror::new("").sample(10), 100), 10);
+        assert_eq!(mirrored(&Mirror::new("").sample(25), 1000), 250);
+        assert_eq!(mirrored(&Mirror::new("").sample(0), 100), 0);
+        assert_eq!(mirrored(&Mirror::new("").sample(200), 100), 100);
+        assert_eq!(mirrored(&Mirror::new(""), 7), 7);
+
+        let mirror = Mirror::new("").sample(50);
+        let picks: Vec<_> = (0..4).map(|_| mirror.sampled()).collect();
+        assert_eq!(picks, vec![false, true, false, true]);
+    }
+
+    #[test]
+    fn test_capture() {
+        let mut request = Request::new(Method::Post, "/api/items?x=1");
+        request.add_header(Header::new("Content-Type", "text/plain"));
+        request.add_header(Header::new("X-Trace", "a"));
+        request.add_header(Header::new("X-Trace", "b"));
+        request.add_header(Header::new("Connection", "keep-alive"));
+        request.add_header(Header::new("host", "rocket.rs"));
+        request.add_header(Header::new("Transfer-Encoding", "chunked"));
+
+        let shadow = Shadow::capture(&request, b"hello", true, 5).unwrap();
+        assert_eq!(shadow.method, "POST");
+        assert_eq!(shadow.uri, "/api/items?x=1");
+        assert_eq!(shadow.body, b"hello".to_vec());
+
+        let mut headers = shadow.headers.clone();
+        headers.sort();
+        assert_eq!(headers, vec![
+            ("Content-Type".to_string(), "text/plain".to_string()),
+            ("X-Trace".to_string(), "a".to_string()),
+            ("X-Trace".to_string(), "b".to_string()),
+        ]);
+
+        assert!(Shadow::capture(&request, b"hello", true, 4).is_none());
+        assert!(Shadow::capture(&request, b"hello", false, 4096).is_none());
+        assert!(Shadow::capture(&request, b"", true, 0).is_some());
+    }
+
+    #[test]
+    fn test_send() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let upstream = format!("http://{}/", listener.local_addr().unwrap());
+        let server = ::std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut head = vec![];
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+
+                head.push(line.trim().to_string());
+            }
+
+            let mut body = [0; 5];
+            reader.read_exact(&mut body).unwrap();
+            (&stream).write_all(b"HTTP/1.1 204 No Content\r\n\r\n").unwrap();
+            (head, body)
+        });
+
+        let mut request = Request::new(Method::Put, "/items/1");
+        request.add_header(Header::new("X-Trace", "abc"));
+        let shadow = Shadow::capture(&request, b"hello", true, 4096).unwrap();
+
+        let mut client = super::hyper::Client::new();
+        client.set_read_timeout(Some(Duration::from_secs(5)));
+        shadow.send(&client, &upstream).unwrap();
+
+        let (head, body) = server.join().unwrap();
+        assert_eq!(head[0], "PUT /items/1 HTTP/1.1");
+        assert!(head.iter().any(|line| line == "X-Trace: abc"));
+        assert!(head.iter().any(|line| line == "Content-Length: 5"));
+        assert_eq!(&body, b"hello");
+    }
+}
@@ -0,0 +1,592 @@
+extern crate serde;
+
+use std::ops::{Deref, DerefMut};
+use std::error::Error;
+use std::{fmt, vec, slice};
+
+use rocket::request::{self, Request, FromRequest, FromQuery, FormItems, FromFormValue};
+use rocket::request::{FormSyntax, split_form_key};
+
+use self::serde::Deserialize;
+use self::serde::de::{self, Deserializer, Visitor, SeqVisitor, MapVisitor, EnumVisitor};
+
+/// The `Query` type, which implements `FromQuery` and `FromRequest`. This type
+/// allows you to deserialize deeply structured query strings into nested Serde
+/// types.
+///
+/// Unlike `FromForm`, which maps each key in a query string directly to a
+/// field, `Query` interprets brackets in keys as nesting. This makes it
+/// possible to express query grammars such as the one used by JSON:API:
+///
+///   * `filter[status]=open` sets the `status` field of the `filter` map or
+///     structure to `"open"`.
+///   * `sort[]=-created&sort[]=title` appends `"-created"` and `"title"` to the
+///     `sort` sequence.
+///   * `ids[1]=b&ids[0]=a` sets the `ids` sequence to `["a", "b"]`.
+///   * `tag=a&tag=b` sets the `tag` sequence to `["a", "b"]`.
+///
+/// Keys are split into their bracketed segments just as Rocket splits the keys
+/// of forms with the brackets syntax, so brackets may be percent-encoded, but
+/// the name before the first bracket can't contain a `.` or percent-encoded
+/// characters. Keys and values are percent-decoded, and `+` is decoded to a
+/// space. Values are parsed into the type requested by the target structure; a
+/// single value is accepted where a sequence is expected. Enums are not
+/// supported, and keys may be nested at most 32 levels deep.
+///
+/// As a request guard, `Query` behaves exactly like
+/// [rocket::request::Query](/rocket/request/struct.Query.html): if the query
+/// string is missing, it is treated as empty, and if the query string cannot be
+/// deserialized into `T`, the request fails with a status of `400 Bad Request`.
+/// Use `Option<Query<T>>` or `Result<Query<T>, QueryError>` to handle the
+/// failure in the route instead.
+///
+/// ```rust,ignore
+/// #[derive(Deserialize)]
+/// struct Filter {
+///     status: Option<String>,
+///     author: Option<usize>,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Params {
+///     filter: Filter,
+///     sort: Vec<String>,
+/// }
+///
+/// // GET /articles?filter[status]=open&filter[author]=7&sort[]=-created
+/// #[get("/articles")]
+/// fn articles(params: Query<Params>) -> String {
+///     ...
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Query<T>(pub T);
+
+impl<T> Query<T> {
+    /// Consumes the `Query` wrapper and returns the wrapped item.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use rocket_contrib::Query;
+    /// let query = Query(vec!["a".to_string()]);
+    /// assert_eq!(query.unwrap(), vec!["a".to_string()]);
+    /// ```
+    pub fn unwrap(self) -> T {
+        self.0
+    }
+}
+
+/// Parses and deserializes the query string `string` into a `T`.
+fn from_str<T: Deserialize>(string: &str) -> Result<T, QueryError> {
+    let node = parse(string)?;
+    T::deserialize(&mut NodeDeserializer(&node))
+}
+
+impl<'q, T: Deserialize> FromQuery<'q> for Query<T> {
+    type Error = QueryError;
+
+    fn from_query(query: &'q str) -> Result<Self, QueryError> {
+        from_str(query).map(Query)
+    }
+}
+
+impl<'a, 'r, T: Deserialize> FromRequest<'a, 'r> for Query<T> {
+    type Error = QueryError;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, QueryError> {
+        request::Query::<Query<T>>::from_request(request).map(|query| query.into_inner())
+    }
+}
+
+impl<T> Deref for Query<T> {
+    type Target = T;
+
+    fn deref<'a>(&'a self) -> &'a T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Query<T> {
+    fn deref_mut<'a>(&'a mut self) -> &'a mut T {
+        &mut self.0
+    }
+}
+
+/// The error returned when a query string can't be parsed or deserialized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryError {
+    message: String
+}
+
+impl QueryError {
+    fn new<S: Into<String>>(message: S) -> QueryError {
+        QueryError { message: message.into() }
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for QueryError {
+    fn description(&self) -> &str {
+        &self.message
+    }
+}
+
+impl de::Error for QueryError {
+    fn custom<T: Into<String>>(message: T) -> QueryError {
+        QueryError::new(message)
+    }
+
+    fn end_of_stream() -> QueryError {
+        QueryError::new("unexpected end of query string")
+    }
+}
+
+/// The maximum number of bracketed components in a key. Nodes are built and
+/// deserialized recursively, so deeper keys could overflow the stack.
+const MAX_DEPTH: usize = 32;
+
+/// One component of a bracketed key: `[name]` or `[]`.
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Key(String),
+    Push,
+}
+
+/// The tree of values described by a query string.
+#[derive(Debug, PartialEq)]
+enum Node {
+    Leaf(String),
+    Seq(Vec<Node>),
+    Map(Vec<(String, Node)>),
+}
+
+/// Decodes a percent-encoded key or value, translating `+` into a space.
+fn decode(string: &str) -> Result<String, QueryError> {
+    String::from_form_value(string)
+        .map_err(|s| QueryError::new(format!("invalid percent-encoding in '{}'", s)))
+}
+
+/// Splits the segment in the leading brackets of `rest` off of it, as in `b`
+/// and `[]` for `[b][]`. Returns `None` if `rest` doesn't start with brackets.
+fn split_brackets(rest: &str) -> Option<(&str, &str)> {
+    // Without leading brackets, `split_form_key` splits off a name instead,
+    // consuming nothing but the name.
+    let (segment, remainder) = split_form_key(rest, FormSyntax::Brackets);
+    match segment.len() + remainder.len() < rest.len() {
+        true => Some((segment, remainder)),
+        false => None
+    }
+}
+
+/// Splits a raw key like `a[b][]` into its decoded segments: `[a, b, <push>]`.
+/// Fails if the key is nested more than `MAX_DEPTH` levels deep.
+fn segments(key: &str) -> Result<Vec<Segment>, QueryError> {
+    let (base, mut rest) = split_form_key(key, FormSyntax::Brackets);
+    if base.is_empty() || split_brackets(key).is_some() {
+        return Err(QueryError::new(format!("missing name in key '{}'", key)));
+    }
+
+    let mut segments = vec![Segment::Key(decode(base)?)];
+    while !rest.is_empty() {
+        if segments.len() > MAX_DEPTH {
+            return Err(QueryError::new(format!("key '{}' is nested too deeply", key)));
+        }
+
+        let (segment, remainder) = match split_brackets(rest) {
+            Some(split) => split,
+            None => return Err(QueryError::new(format!("malformed key '{}'", key)))
+        };
+
+        match segment {
+            "" => segments.push(Segment::Push),
+            name => segments.push(Segment::Key(decode(name)?)),
+        }
+
+        rest = remainder;
+    }
+
+    Ok(segments)
+}
+
+impl Node {
+    /// Creates the tree for a value at `path` relative to a new node.
+    fn new_at(path: &[Segment], value: String) -> Node {
+        match path.split_first() {
+            None => Node::Leaf(value),
+            Some((&Segment::Push, rest)) => Node::Seq(vec![Node::new_at(rest, value)]),
+            Some((&Segment::Key(ref key), rest)) => {
+                Node::Map(vec![(key.clone(), Node::new_at(rest, value))])
+            }
+        }
+    }
+
+    /// Inserts `value` at `path` relative to `self`.
+    fn insert(&mut self, path: &[Segment], value: String) -> Result<(), QueryError> {
+        let (segment, rest) = match path.split_first() {
+            Some(split) => split,
+            None => return self.push_value(value),
+        };
+
+        match (segment, self) {
+            (&Segment::Push, &mut Node::Seq(ref mut items)) => {
+                items.push(Node::new_at(rest, value));
+                Ok(())
+            }
+            (&Segment::Key(ref key), &mut Node::Map(ref mut entries)) => {
+                let position = entries.iter().position(|&(ref k, _)| k == key);
+                match position {
+                    Some(i) => entries[i].1.insert(rest, value),
+                    None => {
+                        entries.push((key.clone(), Node::new_at(rest, value)));
+                        Ok(())
+                    }
+                }
+            }
+            _ => Err(QueryError::new("conflicting structure for the same key"))
+        }
+    }
+
+    /// Adds a repeated value to `self`, turning a single value into a sequence.
+    fn push_value(&mut self, value: String) -> Result<(), QueryError> {
+        let repeated = match *self {
+            Node::Leaf(ref existing) => Node::Seq(vec![
+                Node::Leaf(existing.clone()), Node::Leaf(value)
+            ]),
+            Node::Seq(ref mut items) => {
+                items.push(Node::Leaf(value));
+                return Ok(());
+            }
+            Node::Map(_) => {
+                return Err(QueryError::new("conflicting structure for the same key"));
+            }
+        };
+
+        *self = repeated;
+        Ok(())
+    }
+}
+
+/// Parses the query string `string` into a tree of values.
+fn parse(string: &str) -> Result<Node, QueryError> {
+    let mut root = Node::Map(vec![]);
+    for (key, value) in FormItems(string) {
+        let path = segments(key)?;
+        root.insert(&path, decode(value)?)?;
+    }
+
+    Ok(root)
+}
+
+struct NodeDeserializer<'a>(&'a Node);
+
+impl<'a> NodeDeserializer<'a> {
+    fn leaf(&self) -> Result<&'a str, QueryError> {
+        match *self.0 {
+            Node::Leaf(ref value) => Ok(value),
+            Node::Seq(_) => Err(QueryError::new("expected a single value, found many")),
+            Node::Map(_) => Err(QueryError::new("expected a single value, found a map")),
+        }
+    }
+
+    /// Returns the items of `self` as a sequence. Maps with integer keys are
+    /// treated as sequences ordered by their keys.
+    fn items(&self) -> Result<Vec<&'a Node>, QueryError> {
+        match *self.0 {
+            Node::Leaf(_) => Ok(vec![self.0]),
+            Node::Seq(ref items) => Ok(items.iter().collect()),
+            Node::Map(ref entries) => {
+                let mut indexed = Vec::with_capacity(entries.len());
+                for &(ref key, ref value) in entries {
+                    match key.parse::<usize>() {
+                        Ok(i) => indexed.push((i, value)),
+                        Err(_) => return Err(QueryError::new("expected a sequence, found a map"))
+                    }
+                }
+
+                indexed.sort_by_key(|&(i, _)| i);
+                Ok(indexed.into_iter().map(|(_, value)| value).collect())
+            }
+        }
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $ty:ident, $visit:ident;)*) => ($(
+        fn $method<V: Visitor>(&mut self, mut visitor: V) -> Result<V::Value, QueryError> {
+            let value = self.leaf()?;
+            match value.parse::<$ty>() {
+                Ok(parsed) => visitor.$visit(parsed),
+                Err(_) => Err(QueryError::new(format!("'{}' is not a valid {}",
+                                                      value, stringify!($ty))))
+            }
+        }
+    )*)
+}
+
+impl<'a> Deserializer for NodeDeserializer<'a> {
+    type Error = QueryError;
+
+    fn deserialize<V: Visitor>(&mut self, mut visitor: V) -> Result<V::Value, QueryError> {
+        let node = self.0;
+        match *node {
+            Node::Leaf(ref value) => visitor.visit_str(value),
+            Node::Seq(_) => self.deserialize_seq(visitor),
+            Node::Map(_) => self.deserialize_map(visitor),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_usize => usize, visit_usize;
+        deserialize_u8 => u8, visit_u8;
+        deserialize_u16 => u16, visit_u16;
+        deserialize_u32 => u32, visit_u32;
+        deserialize_u64 => u64, visit_u64;
+        deserialize_isize => isize, visit_isize;
+        deserialize_i8 => i8, visit_i8;
+        deserialize_i16 => i16, visit_i16;
+        deserialize_i32 => i32, visit_i32;
+        deserialize_i64 => i64, visit_i64;
+        deserialize_f32 => f32, visit_f32;
+        deserialize_f64 => f64, visit_f64;
+        deserialize_char => char, visit_char;
+    }
+
+    fn deserialize_bool<V: Visitor>(&mut self, mut visitor: V) -> Result<V::Value, QueryError> {
+        let value = self.leaf()?;
+        match <bool as FromFormValue>::from_form_value(value) {
+            Ok(parsed) => visitor.visit_bool(parsed),
+            Err(_) => Err(QueryError::new(format!("'{}' is not a valid bool", value)))
+        }
+    }
+
+    fn deserialize_str<V: Visitor>(&mut self, mut visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_str(self.leaf()?)
+    }
+
+    fn deserialize_string<V: Visitor>(&mut self, mut visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_string(self.leaf()?.to_string())
+    }
+
+    fn deserialize_bytes<V: Visitor>(&mut self, mut visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_bytes(self.leaf()?.as_bytes())
+    }
+
+    fn deserialize_unit<V: Visitor>(&mut self, mut visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_option<V: Visitor>(&mut self, mut visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_seq<V: Visitor>(&mut self, mut visitor: V) -> Result<V::Value, QueryError> {
+        let items = self.items()?;
+        visitor.visit_seq(SeqDeserializer { iter: items.into_iter() })
+    }
+
+    fn deserialize_seq_fixed_size<V: Visitor>(&mut self, _: usize, visitor: V)
+            -> Result<V::Value, QueryError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor>(&mut self, _: usize, visitor: V)
+            -> Result<V::Value, QueryError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor>(&mut self, mut visitor: V) -> Result<V::Value, QueryError> {
+        match *self.0 {
+            Node::Map(ref entries) => visitor.visit_map(MapDeserializer {
+                iter: entries.iter(),
+                value: None
+            }),
+            _ => Err(QueryError::new("expected a map"))
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor>(&mut self, _: &'static str, visitor: V)
+            -> Result<V::Value, QueryError> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor>(&mut self, _: &'static str, mut visitor: V)
+            -> Result<V::Value, QueryError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor>(&mut self, _: &'static str, _: usize, visitor: V)
+            -> Result<V::Value, QueryError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor>(&mut self,
+                                      _: &'static str,
+                                      _: &'static [&'static str],
+                                      visitor: V) -> Result<V::Value, QueryError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_struct_field<V: Visitor>(&mut self, visitor: V)
+            -> Result<V::Value, QueryError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_enum<V: EnumVisitor>(&mut self,
+                                        name: &'static str,
+                                        _: &'static [&'static str],
+                                        _: V) -> Result<V::Value, QueryError> {
+        Err(QueryError::new(format!("enum '{}' can't be deserialized from a query", name)))
+    }
+
+    fn deserialize_ignored_any<V: Visitor>(&mut self, mut visitor: V)
+            -> Result<V::Value, QueryError> {
+        visitor.visit_unit()
+    }
+}
+
+struct SeqDeserializer<'a> {
+    iter: vec::IntoIter<&'a Node>,
+}
+
+impl<'a> SeqVisitor for SeqDeserializer<'a> {
+    type Error = QueryError;
+
+    fn visit<T: Deserialize>(&mut self) -> Result<Option<T>, QueryError> {
+        match self.iter.next() {
+            Some(node) => T::deserialize(&mut NodeDeserializer(node)).map(Some),
+            None => Ok(None)
+        }
+    }
+
+    fn end(&mut self) -> Result<(), QueryError> {
+        match self.iter.len() {
+            0 => Ok(()),
+            n => Err(QueryError::new(format!("{} unexpected trailing values", n)))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.iter.len(), Some(self.iter.len()))
+    }
+}
+
+struct MapDeserializer<'a> {
+    iter: slice::Iter<'a, (String, Node)>,
+    value: Option<&'a Node>,
+}
+
+impl<'a> MapVisitor for MapDeserializer<'a> {
+    type Error = QueryError;
+
+    fn visit_key<K: Deserialize>(&mut self) -> Result<Option<K>, QueryError> {
+        match self.iter.next() {
+            Some(&(ref key, ref value)) => {
+                self.value = Some(value);
+                let key = Node::Leaf(key.clone());
+                K::deserialize(&mut NodeDeserializer(&key)).map(Some)
+            }
+            None => Ok(None)
+        }
+    }
+
+    fn visit_value<V: Deserialize>(&mut self) -> Result<V, QueryError> {
+        match self.value.take() {
+            Some(node) => V::deserialize(&mut NodeDeserializer(node)),
+            None => Err(QueryError::new("map value requested before its key"))
+        }
+    }
+
+    fn end(&mut self) -> Result<(), QueryError> {
+        Ok(())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.iter.len(), Some(self.iter.len()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, segments, from_str, Node, Segment, MAX_DEPTH};
+    use std::collections::{BTreeMap, HashMap};
+
+    fn leaf(value: &str) -> Node {
+        Node::Leaf(value.to_string())
+    }
+
+    #[test]
+    fn test_segments() {
+        assert_eq!(segments("a").unwrap(), vec![Segment::Key("a".into())]);
+        assert_eq!(segments("a[b][]").unwrap(), vec![
+            Segment::Key("a".into()), Segment::Key("b".into()), Segment::Push
+        ]);
+
+        assert_eq!(segments("a%5Bb+c%5d[%21]").unwrap(), vec![
+            Segment::Key("a".into()), Segment::Key("b c".into()), Segment::Key("!".into())
+        ]);
+
+        assert!(segments("").is_err());
+        assert!(segments("[a]").is_err());
+        assert!(segments("a[b").is_err());
+        assert!(segments("a[b]c").is_err());
+        assert!(segments("a.b").is_err());
+        assert!(segments("a%20b").is_err());
+
+        let brackets: String = (0..MAX_DEPTH).map(|_| "[x]").collect();
+        let deep = format!("a{}", brackets);
+        assert_eq!(segments(&deep).unwrap().len(), MAX_DEPTH + 1);
+        assert!(segments(&format!("{}[x]", deep)).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        let tree = parse("filter[status]=open&sort[]=-created&sort[]=title&q=a+b%21")
+            .unwrap();
+
+        assert_eq!(tree, Node::Map(vec![
+            ("filter".into(), Node::Map(vec![("status".into(), leaf("open"))])),
+            ("sort".into(), Node::Seq(vec![leaf("-created"), leaf("title")])),
+            ("q".into(), leaf("a b!")),
+        ]));
+
+        assert_eq!(parse("").unwrap(), Node::Map(vec![]));
+        assert_eq!(parse("a=1&a=2&a=3").unwrap(), Node::Map(vec![
+            ("a".into(), Node::Seq(vec![leaf("1"), leaf("2"), leaf("3")]))
+        ]));
+    }
+
+    #[test]
+    fn test_parse_conflicts() {
+        assert!(parse("a=1&a[b]=2").is_err());
+        assert!(parse("a[b]=1&a=2").is_err());
+        assert!(parse("a[]=1&a[b]=2").is_err());
+        assert!(parse("a=%ZZ").is_err());
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let map: HashMap<String, HashMap<String, u32>> =
+            from_str("page[size]=10&page[number]=2").unwrap();
+        assert_eq!(map["page"]["size"], 10);
+        assert_eq!(map["page"]["number"], 2);
+
+        let map: BTreeMap<String, Vec<String>> =
+            from_str("sort[]=-created&one=x&ids[1]=b&ids[0]=a").unwrap();
+        assert_eq!(map["sort"], vec!["-created".to_string()]);
+        assert_eq!(map["one"], vec!["x".to_string()]);
+        assert_eq!(map["ids"], vec!["a".to_string(), "b".to_string()]);
+
+        let map: HashMap<String, Option<bool>> = from_str("a=on&b=false").unwrap();
+        assert_eq!(map["a"], Some(true));
+        assert_eq!(map["b"], Some(false));
+
+        assert!(from_str::<HashMap<String, u8>>("a=256").is_err());
+        assert!(from_str::<HashMap<String, String>>("a=1&a=2").is_err());
+        assert!(from_str::<HashMap<String, Vec<u8>>>("a[x]=1").is_err());
+    }
+}
@@ -0,0 +1,218 @@
+use super::serde::Serialize;
+use super::serde_json::{self, Value};
+
+use rocket::Request;
+use rocket::http::{Status, AcceptLanguage};
+
+use super::{Template, TEMPLATES};
+
+/// The directory, relative to `template_dir`, holding error page templates.
+const ERROR_DIR: &'static str = "errors";
+
+/// Returns the names of the templates that may render the error page for
+/// status `code`, from most to least specific.
+fn bases(code: u16) -> Vec<String> {
+    vec![
+        format!("{}/{}", ERROR_DIR, code),
+        format!("{}/{}xx", ERROR_DIR, code / 100),
+        format!("{}/default", ERROR_DIR),
+    ]
+}
+
+/// Returns the locales of the localized variants of the template named `base`
+/// among `names`, in alphabetical order. The template named `base.de` is the
+/// `de` variant of `base`.
+fn locales<'a, I>(names: I, base: &str) -> Vec<&'a str>
+    where I: Iterator<Item = &'a str>
+{
+    let mut locales: Vec<_> = names
+        .filter(|name| name.starts_with(base) && name[base.len()..].starts_with('.'))
+        .map(|name| &name[(base.len() + 1)..])
+        .filter(|locale| !locale.is_empty() && !locale.contains('/'))
+        .collect();
+
+    locales.sort();
+    locales
+}
+
+/// Chooses the template, and its locale, to render the error page for status
+/// `code` from the templates named `names`. `accept` is `None` when the client
+/// didn't state its language preferences.
+fn select<'a>(names: &[&'a str], code: u16, accept: Option<&AcceptLanguage>)
+    -> Option<(String, Option<&'a str>)>
+{
+    for base in bases(code) {
+        let exists = names.contains(&base.as_str());
+        let locales = locales(names.iter().map(|name| *name), &base);
+        let locale = match accept {
+            Some(accept) => accept.negotiate(&locales),
+            None if !exists => AcceptLanguage::any().negotiate(&locales),
+            None => None
+        };
+
+        match locale {
+            Some(locale) => return Some((format!("{}.{}", base, locale), Some(locale))),
+            None if exists => return Some((base, None)),
+            None => continue
+        }
+    }
+
+    None
+}
+
+impl Template {
+    /// Renders the error page for status `status` in response to `request`.
+    /// This is equivalent to [error_with](#method.error_with) with an empty
+    /// context.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #![feature(plugin)]
+    /// # #![plugin(rocket_codegen)]
+    /// # extern crate rocket;
+    /// # extern crate rocket_contrib;
+    /// use rocket::Request;
+    /// use rocket::http::Status;
+    /// use rocket_contrib::Template;
+    ///
+    /// #[error(404)]
+    /// fn not_found(request: &Request) -> Template {
+    ///     Template::error(Status::NotFound, request)
+    /// }
+    /// # fn main() { }
+    /// ```
+    pub fn error(status: Status, request: &Request) -> Template {
+        Template::error_with(status, request, &())
+    }
+
+    /// Renders the error page for status `status` in response to `request`
+    /// with the context `context`, choosing the template by status and by the
+    /// client's preferred language.
+    ///
+    /// Error page templates live in the `errors` directory of `template_dir`
+    /// and are named by status code, optionally followed by a locale. For a
+    /// `404` error, the first of the following templates that exists is
+    /// rendered, where `{lang}` is the locale, among those with a template,
+    /// that best matches the request's `Accept-Language` header:
+    ///
+    ///   1. `errors/404.{lang}`, such as `errors/404.en.html.tera`
+    ///   2. `errors/404`
+    ///   3. `errors/4xx.{lang}`
+    ///   4. `errors/4xx`
+    ///   5. `errors/default.{lang}`
+    ///   6. `errors/default`
+    ///
+    /// A more specific status thus wins over a better matching locale. When
+    /// the request has no `Accept-Language` header, the template without a
+    /// locale is preferred, falling back to the alphabetically first locale.
+    /// The response varies on `Accept-Language`.
+    ///
+    /// If `context` serializes to an object, the keys `status`, `reason`,
+    /// `uri`, and `lang` are added to it unless already present; otherwise,
+    /// the context consists of just those keys. `lang` is the locale of the
+    /// chosen template, or `null` if it has none. If no template exists, an
+    /// error is logged and the template fails to render.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #![feature(plugin)]
+    /// # #![plugin(rocket_codegen)]
+    /// # extern crate rocket;
+    /// # extern crate rocket_contrib;
+    /// use std::collections::HashMap;
+    ///
+    /// use rocket::Request;
+    /// use rocket::http::Status;
+    /// use rocket_contrib::Template;
+    ///
+    /// #[error(500)]
+    /// fn internal_error(request: &Request) -> Template {
+    ///     let mut context = HashMap::new();
+    ///     context.insert("support", "support@example.com");
+    ///     Template::error_with(Status::InternalServerError, request, &context)
+    /// }
+    /// # fn main() { }
+    /// ```
+    pub fn error_with<T: Serialize>(status: Status, request: &Request, context: &T)
+        -> Template
+    {
+        let accept = if request.headers().contains("Accept-Language") {
+            request.guard::<AcceptLanguage>().succeeded()
+        } else {
+            None
+        };
+
+        request.vary_on("Accept-Language");
+        let names: Vec<&str> = TEMPLATES.keys().map(|name| name.as_str()).collect();
+        let (name, lang) = match select(&names, status.code, accept.as_ref()) {
+            Some(selected) => selected,
+            None => {
+                error_!("No error page template for status {}.", status.code);
+                info_!("Searched for '{}' and its fallbacks.", bases(status.code)[0]);
+                return Template::new(None, None, None);
+            }
+        };
+
+        let mut context = match serde_json::to_value(context) {
+            value @ Value::Object(_) => value,
+            _ => Value::Object(Default::default())
+        };
+
+        if let Value::Object(ref mut map) = context {
+            let entries = vec![
+                ("status", serde_json::to_value(&status.code)),
+                ("reason", serde_json::to_value(&status.reason)),
+                ("uri", serde_json::to_value(&request.uri().as_str())),
+                ("lang", lang.map_or(Value::Null, |lang| serde_json::to_value(&lang))),
+            ];
+
+            for (key, value) in entries {
+                if !map.contains_key(key) {
+                    map.insert(key.to_string(), value);
+                }
+            }
+        }
+
+        Template::render(name, &context)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::select;
+    use rocket::http::AcceptLanguage;
+
+    const NAMES: &'static [&'static str] = &[
+        "errors/404.en", "errors/404.de", "errors/4xx", "errors/4xx.fr",
+        "errors/default.en", "errors/5xx.en/partial", "index",
+    ];
+
+    fn selected(code: u16, accept: Option<&str>) -> Option<(String, Option<&'static str>)> {
+        let accept = accept.map(|a| AcceptLanguage::from_str(a).unwrap());
+        select(NAMES, code, accept.as_ref())
+    }
+
+    #[test]
+    fn test_select_by_status_then_locale() {
+        assert_eq!(selected(404, Some("de-CH, en;q=0.5")),
+                   Some(("errors/404.de".into(), Some("de"))));
+        assert_eq!(selected(404, Some("fr")), Some(("errors/4xx.fr".into(), Some("fr"))));
+        assert_eq!(selected(404, Some("it")), Some(("errors/4xx".into(), None)));
+        assert_eq!(selected(410, Some("fr, en;q=0.5")),
+                   Some(("errors/4xx.fr".into(), Some("fr"))));
+        assert_eq!(selected(500, Some("ja")), None);
+        assert_eq!(selected(500, Some("en-US")),
+                   Some(("errors/default.en".into(), Some("en"))));
+    }
+
+    #[test]
+    fn test_select_without_preferences() {
+        assert_eq!(selected(404, None), Some(("errors/404.de".into(), Some("de"))));
+        assert_eq!(selected(418, None), Some(("errors/4xx".into(), None)));
+        assert_eq!(selected(503, None), Some(("errors/default.en".into(), Some("en"))));
+    }
+}
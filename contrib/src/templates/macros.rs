@@ -31,7 +31,8 @@ macro_rules! render_set {
                 -> Option<Template> {
             if info.extension == $engine::EXT {
                 let rendered = $engine::render(name, info, c);
-                return Some(Template(rendered, info.data_type.clone()));
+                let (data_type, path) = (info.data_type.clone(), info.full_path.clone());
+                return Some(Template::new(rendered, data_type, Some(path)));
             }
 
             None
@@ -9,17 +9,21 @@ mod tera_templates;
 mod handlebars_templates;
 
 #[macro_use] mod macros;
+mod errors;
 
 use self::serde::Serialize;
 use self::glob::glob;
 
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::fs;
 use std::fmt;
 
 use rocket::config;
-use rocket::response::{self, Content, Responder};
-use rocket::http::{ContentType, Status};
+use rocket::response::{self, Content, Responder, Response};
+use rocket::http::{ContentType, Status, ETag, LastModified};
 
 /// The Template type implements generic support for template rendering in
 /// Rocket.
@@ -84,9 +88,39 @@ use rocket::http::{ContentType, Status};
 ///     Template::render("index", &context)
 /// }
 /// ```
-// Fields are: (optionally rendered template, template extension)
+///
+/// # Error Pages
+///
+/// Error catchers can render error page templates selected by status and by
+/// the client's preferred language, such as `errors/404.en.html.tera`, via
+/// [error](#method.error) and [error_with](#method.error_with).
+///
+/// # Caching
+///
+/// Responses carry a strong `ETag` computed from the rendered template, so
+/// identical renders share a tag. Pages that depend only on the template file
+/// can also carry a `Last-Modified` header with the file's modification time;
+/// see [with_last_modified](#method.with_last_modified). To answer conditional
+/// requests with `304 Not Modified`, attach the
+/// [conditional](/rocket/hook/fn.conditional.html) response hook:
+///
+/// ```rust,ignore
+/// rocket::ignite()
+///     .mount("/", routes![index])
+///     .on_response(Predicate::any(), hook::conditional)
+///     .launch()
+/// ```
 #[derive(Debug)]
-pub struct Template(Option<String>, Option<String>);
+pub struct Template {
+    /// The rendered template, if rendering succeeded.
+    rendered: Option<String>,
+    /// The extension before the engine extension in the template, if any.
+    data_type: Option<String>,
+    /// The complete path to the template file, if it was found.
+    path: Option<PathBuf>,
+    /// Whether to add a `Last-Modified` header from the file's mtime.
+    last_modified: bool,
+}
 
 #[derive(Debug)]
 pub struct TemplateInfo {
@@ -119,6 +153,16 @@ lazy_static! {
 }
 
 impl Template {
+    fn new(rendered: Option<String>, data_type: Option<String>, path: Option<PathBuf>)
+            -> Template {
+        Template {
+            rendered: rendered,
+            data_type: data_type,
+            path: path,
+            last_modified: false
+        }
+    }
+
     /// Render the template named `name` with the context `context`. The
     /// `context` can be of any type that implements `Serialize`. This is
     /// typically a `HashMap` or a custom `struct`.
@@ -145,7 +189,7 @@ impl Template {
             error_!("Template '{}' does not exist.", name);
             info_!("Known templates: {}", names.join(","));
             info_!("Searched in '{}'.", *TEMPLATE_DIR);
-            return Template(None, None);
+            return Template::new(None, None, None);
         }
 
         // Keep this set in-sync with the `engine_set` invocation.
@@ -156,35 +200,83 @@ impl Template {
 
         unreachable!("A template extension was discovered but not rendered.")
     }
+
+    /// Adds a `Last-Modified` header with the modification time of the
+    /// template file to the response. This is only correct when the rendered
+    /// page depends on nothing but the template, since changes to the context
+    /// don't change the file's modification time.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use rocket_contrib::Template;
+    ///
+    /// let context: HashMap<String, String> = HashMap::new();
+    /// let template = Template::render("about", &context).with_last_modified();
+    /// ```
+    #[inline(always)]
+    pub fn with_last_modified(mut self) -> Template {
+        self.last_modified = true;
+        self
+    }
 }
 
 /// Returns a response with the Content-Type derived from the template's
-/// extension and a fixed-size body containing the rendered template. If
+/// extension, a strong `ETag` computed from the rendered template, and a
+/// fixed-size body containing the rendered template. If requested via
+/// `with_last_modified`, a `Last-Modified` header is added as well. If
 /// rendering fails, an `Err` of `Status::InternalServerError` is returned.
 impl Responder<'static> for Template {
     fn respond(self) -> response::Result<'static> {
-        let content_type = match self.1 {
+        let content_type = match self.data_type {
             Some(ref ext) => ContentType::from_extension(ext),
             None => ContentType::HTML
         };
 
-        match self.0 {
-            Some(render) => Content(content_type, render).respond(),
-            None => Err(Status::InternalServerError)
+        let render = match self.rendered {
+            Some(render) => render,
+            None => return Err(Status::InternalServerError)
+        };
+
+        let etag = content_etag(&render);
+        let mut response = Response::build_from(Content(content_type, render).respond()?)
+            .header(etag)
+            .finalize();
+
+        if self.last_modified {
+            let modified = self.path.as_ref()
+                .and_then(|path| fs::metadata(path).and_then(|m| m.modified()).ok());
+
+            match modified {
+                Some(time) => { response.set_header(LastModified(time)); }
+                None => warn_!("Unable to read the template's modification time.")
+            }
         }
+
+        Ok(response)
     }
 }
 
 /// Renders `self`. If the template cannot be rendered, nothing is written.
 impl fmt::Display for Template {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self.0 {
+        match self.rendered {
             Some(ref render) => render.fmt(f),
             None => Ok(())
         }
     }
 }
 
+/// Returns a strong entity tag for the rendered template `render`. The hash is
+/// keyed identically in every process, so all instances of an application
+/// agree on the tag.
+fn content_etag(render: &str) -> ETag {
+    let mut hasher = DefaultHasher::new();
+    render.hash(&mut hasher);
+    ETag::strong(format!("{:016x}", hasher.finish()))
+}
+
 /// Removes the file path's extension or does nothing if there is none.
 fn remove_extension<P: AsRef<Path>>(path: P) -> PathBuf {
     let path = path.as_ref();
@@ -282,6 +374,14 @@ mod tests {
         assert_eq!(data_type, Some("html".to_owned()));
     }
 
+    #[test]
+    fn content_etag_is_stable() {
+        assert_eq!(content_etag("<h1>Hi</h1>"), content_etag("<h1>Hi</h1>"));
+        assert!(content_etag("<h1>Hi</h1>") != content_etag("<h1>Hi!</h1>"));
+        assert!(!content_etag("").is_weak());
+        assert_eq!(content_etag("").tag().len(), 16);
+    }
+
     #[test]
     fn template_path_doc_examples() {
         assert_eq!(relative_path_to_name("index.html.hbs"), "index");
@@ -0,0 +1,666 @@
+//! Stateless CSRF protection via signed double-submit cookies.
+
+use std::marker::PhantomData;
+use std::str::from_utf8;
+use std::time::{Duration, SystemTime};
+use std::fmt;
+
+use rocket::outcome::Outcome;
+use rocket::request::{self, Request, FromRequest, FormItems};
+use rocket::data::{self, Data, FromData};
+use rocket::http::{Status, Cookie, RawStr};
+use rocket::State;
+
+use super::ring::constant_time;
+use super::ring::rand::{SecureRandom, SystemRandom};
+use super::{TokenKey, TokenPurpose, TokenError, encode};
+
+/// A group of routes sharing a CSRF configuration, such as a site's admin
+/// interface.
+///
+/// Each group has its own cookie, named after the group, and its own
+/// [CsrfConfig](struct.CsrfConfig.html), which the application manages. The
+/// group's name is bound into the signature of its tokens, so a token issued
+/// for one group is never valid for another. Most applications only need the
+/// default group, [AllRoutes](struct.AllRoutes.html).
+///
+/// # Example
+///
+/// ```rust
+/// use rocket_contrib::CsrfGroup;
+///
+/// struct Admin;
+///
+/// impl CsrfGroup for Admin {
+///     fn name() -> &'static str { "admin_csrf" }
+/// }
+/// ```
+pub trait CsrfGroup: Send + Sync + 'static {
+    /// Returns the name of the group, which is also the name of its cookie.
+    /// Names must be distinct and valid cookie names.
+    fn name() -> &'static str;
+}
+
+/// The default CSRF group, with a cookie named `csrf`.
+#[derive(Debug)]
+pub struct AllRoutes;
+
+impl CsrfGroup for AllRoutes {
+    fn name() -> &'static str { "csrf" }
+}
+
+/// The token purpose of the CSRF tokens of group `G`.
+struct GroupPurpose<G>(PhantomData<G>);
+
+impl<G: CsrfGroup> TokenPurpose for GroupPurpose<G> {
+    fn purpose() -> &'static str { G::name() }
+}
+
+/// The configuration of the CSRF protection of the routes in group `G`.
+///
+/// Protection is stateless: no token is stored on the server. Instead, the
+/// [CsrfToken](struct.CsrfToken.html) request guard issues a random token,
+/// signed with the configuration's key, and sets it as the group's cookie.
+/// The application echoes the token back in every unsafe request, either in a
+/// form field or in a header, and the validator guards,
+/// [CsrfForm](struct.CsrfForm.html) and [CsrfHeader](struct.CsrfHeader.html),
+/// check that the echoed token is the cookie's token and that the token was
+/// signed with the key and hasn't expired. Another site can cause a browser to
+/// send the cookie but can't read it, so it can't echo the token.
+///
+/// Tokens aren't bound to the client, so the signature only keeps attackers
+/// from forging tokens; any token this configuration issued is valid for any
+/// client. An attacker who can set cookies for the site, such as from a
+/// sibling subdomain, can obtain a valid token, plant it in a victim's browser,
+/// and echo it back. Double-submit cookies don't protect against such
+/// attackers; don't rely on them when untrusted parties control subdomains of
+/// the site.
+///
+/// The configuration must be managed by the application, via
+/// `Rocket::manage`, for the guards of group `G` to work. The key should be
+/// dedicated to CSRF protection; see [TokenKey](struct.TokenKey.html).
+///
+/// # Defaults
+///
+/// By default, tokens are valid for twelve hours, the cookie's path is `/`,
+/// the cookie isn't restricted to HTTPS, tokens are echoed in the `_csrf` form
+/// field or the `X-CSRF-Token` header, and no paths are exempt.
+///
+/// # Example
+///
+/// Protect the whole application, but give the admin interface short-lived
+/// tokens of its own:
+///
+/// ```rust
+/// # extern crate rocket;
+/// # extern crate rocket_contrib;
+/// use std::time::Duration;
+/// use rocket_contrib::{CsrfConfig, CsrfGroup, AllRoutes, TokenKey};
+///
+/// struct Admin;
+///
+/// impl CsrfGroup for Admin {
+///     fn name() -> &'static str { "admin_csrf" }
+/// }
+///
+/// # fn main() {
+/// # let (key, admin_key) = ([0; 32], [1; 32]);
+/// let config = CsrfConfig::<AllRoutes>::new(TokenKey::new(&key))
+///     .exempt("/webhooks");
+///
+/// let admin_config = CsrfConfig::<Admin>::new(TokenKey::new(&admin_key))
+///     .path("/admin")
+///     .secure(true)
+///     .valid_for(Duration::from_secs(15 * 60));
+///
+/// # if false {
+/// rocket::ignite()
+///     .manage(config)
+///     .manage(admin_config)
+///     .launch()
+/// # }
+/// # }
+/// ```
+pub struct CsrfConfig<G: CsrfGroup = AllRoutes> {
+    key: TokenKey,
+    valid_for: Duration,
+    path: String,
+    secure: bool,
+    field: String,
+    header: String,
+    exempt: Vec<String>,
+    _group: PhantomData<G>,
+}
+
+impl<G: CsrfGroup> CsrfConfig<G> {
+    /// Returns the default configuration for group `G`, signing tokens with
+    /// `key`.
+    pub fn new(key: TokenKey) -> CsrfConfig<G> {
+        CsrfConfig {
+            key: key,
+            valid_for: Duration::from_secs(12 * 60 * 60),
+            path: "/".to_string(),
+            secure: false,
+            field: "_csrf".to_string(),
+            header: "X-CSRF-Token".to_string(),
+            exempt: vec![],
+            _group: PhantomData,
+        }
+    }
+
+    /// Sets how long issued tokens, and their cookies, are valid for.
+    pub fn valid_for(mut self, valid_for: Duration) -> Self {
+        self.valid_for = valid_for;
+        self
+    }
+
+    /// Sets the path of the cookie, restricting it to the group's routes.
+    pub fn path<S: Into<String>>(mut self, path: S) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Sets whether the cookie is only sent over HTTPS.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Sets the name of the form field tokens are echoed in.
+    pub fn field<S: Into<String>>(mut self, field: S) -> Self {
+        self.field = field.into();
+        self
+    }
+
+    /// Sets the name of the header tokens are echoed in.
+    pub fn header<S: Into<String>>(mut self, header: S) -> Self {
+        self.header = header.into();
+        self
+    }
+
+    /// Exempts requests to `path`, and to the paths below it, from
+    /// validation, as for webhooks authenticated by other means. Paths are
+    /// matched by whole segments: exempting `/hooks` exempts `/hooks/github`
+    /// but not `/hooksmith`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::{CsrfConfig, AllRoutes, TokenKey};
+    ///
+    /// # let key = [0; 32];
+    /// let config = CsrfConfig::<AllRoutes>::new(TokenKey::new(&key)).exempt("/hooks");
+    /// assert!(config.is_exempt("/hooks/github"));
+    /// assert!(!config.is_exempt("/hooksmith"));
+    /// ```
+    pub fn exempt<S: Into<String>>(mut self, path: S) -> Self {
+        let mut path = path.into();
+        while path.len() > 1 && path.ends_with('/') {
+            path.pop();
+        }
+
+        self.exempt.push(path);
+        self
+    }
+
+    /// Returns `true` if requests to `path` are exempt from validation.
+    pub fn is_exempt(&self, path: &str) -> bool {
+        self.exempt.iter().any(|prefix| {
+            prefix == "/" || path == prefix
+                || (path.starts_with(prefix.as_str())
+                    && path.as_bytes()[prefix.len()] == b'/')
+        })
+    }
+
+    /// Issues a new token: a random nonce, signed with the key, that expires
+    /// after the configured duration.
+    pub fn issue(&self) -> Result<String, CsrfError> {
+        let mut nonce = [0; 16];
+        SystemRandom::new().fill(&mut nonce).map_err(|_| CsrfError::NoRandomness)?;
+        Ok(self.key.issue::<GroupPurpose<G>>(&encode(&nonce), self.valid_for))
+    }
+
+    /// Verifies that `submitted`, the token echoed by a request, is `cookie`,
+    /// the token in the request's cookie, and that the token was issued with
+    /// this configuration's key for group `G` and hasn't expired.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket_contrib::{CsrfConfig, CsrfError, AllRoutes, TokenKey};
+    ///
+    /// # let key = [0; 32];
+    /// let config = CsrfConfig::<AllRoutes>::new(TokenKey::new(&key));
+    /// let token = config.issue().unwrap();
+    /// assert!(config.verify(&token, &token).is_ok());
+    ///
+    /// let other = config.issue().unwrap();
+    /// assert_eq!(config.verify(&token, &other), Err(CsrfError::Mismatch));
+    /// ```
+    pub fn verify(&self, cookie: &str, submitted: &str) -> Result<SystemTime, CsrfError> {
+        self.verify_token(cookie, submitted)
+    }
+
+    /// Implements [verify](#method.verify) for any data guard error type.
+    fn verify_token<E>(&self, cookie: &str, submitted: &str)
+            -> Result<SystemTime, CsrfError<E>> {
+        let equal = constant_time::verify_slices_are_equal(cookie.as_bytes(),
+                                                          submitted.as_bytes());
+        if equal.is_err() {
+            return Err(CsrfError::Mismatch);
+        }
+
+        self.key.verify::<GroupPurpose<G>>(cookie)
+            .map(|(_, expires)| expires)
+            .map_err(CsrfError::Invalid)
+    }
+
+    /// Returns the cookie that carries `token`.
+    fn cookie(&self, token: String) -> Cookie {
+        let mut cookie = Cookie::new(G::name().to_string(), token);
+        cookie.path = Some(self.path.clone());
+        cookie.max_age = Some(self.valid_for.as_secs());
+        cookie.secure = self.secure;
+        cookie
+    }
+
+    /// Checks the token `submitted` by `request` against its cookie, unless
+    /// the request's path is exempt.
+    fn check<E>(&self, request: &Request, submitted: Option<String>)
+            -> Result<(), CsrfError<E>> {
+        if self.is_exempt(request.uri().path()) {
+            return Ok(());
+        }
+
+        let cookie = request.cookies().find(G::name()).ok_or(CsrfError::MissingCookie)?;
+        let submitted = submitted.ok_or(CsrfError::MissingToken)?;
+        self.verify_token(&cookie.value, &submitted).map(|_| ())
+    }
+
+    /// Returns the token echoed in `request`'s header, if there is one.
+    fn header_token(&self, request: &Request) -> Option<String> {
+        request.headers().get_one(&self.header).map(|token| token.trim().to_string())
+    }
+
+    /// Returns the token echoed in the form field of `data`, if the request
+    /// has a form body and the field is in its peeked portion.
+    fn form_token(&self, request: &Request, data: &Data) -> Option<String> {
+        if !request.content_type().is_form() {
+            return None;
+        }
+
+        let peek = data.peek();
+        let form = match from_utf8(peek) {
+            Ok(form) => form,
+            Err(e) => from_utf8(&peek[..e.valid_up_to()]).unwrap_or("")
+        };
+
+        FormItems(form).find(|&(key, _)| key == self.field)
+            .and_then(|(_, value)| RawStr::from_str(value).url_decode().ok())
+    }
+}
+
+impl<G: CsrfGroup> fmt::Debug for CsrfConfig<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CsrfConfig")
+            .field("group", &G::name())
+            .field("valid_for", &self.valid_for)
+            .field("path", &self.path)
+            .field("secure", &self.secure)
+            .field("field", &self.field)
+            .field("header", &self.header)
+            .field("exempt", &self.exempt)
+            .finish()
+    }
+}
+
+/// The reason CSRF protection failed.
+///
+/// `E` is the error of the data guard wrapped by
+/// [CsrfForm](struct.CsrfForm.html); it's `()` for the other guards.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CsrfError<E = ()> {
+    /// The application doesn't manage a `CsrfConfig` for the group.
+    MissingConfig,
+    /// No random nonce could be generated for a new token.
+    NoRandomness,
+    /// The request has no CSRF cookie.
+    MissingCookie,
+    /// The request doesn't echo a token.
+    MissingToken,
+    /// The echoed token isn't the cookie's token.
+    Mismatch,
+    /// The cookie's token is malformed, forged, or expired.
+    Invalid(TokenError),
+    /// The token was valid, but the wrapped data guard failed.
+    Data(E),
+}
+
+impl<E> CsrfError<E> {
+    /// Returns the status the guards fail with for this error.
+    fn status(&self) -> Status {
+        match *self {
+            CsrfError::MissingConfig | CsrfError::NoRandomness => Status::InternalServerError,
+            _ => Status::Forbidden
+        }
+    }
+}
+
+impl<E: fmt::Debug> fmt::Display for CsrfError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CsrfError::MissingConfig => f.write_str("no CSRF configuration is managed"),
+            CsrfError::NoRandomness => f.write_str("no random nonce could be generated"),
+            CsrfError::MissingCookie => f.write_str("the CSRF cookie is missing"),
+            CsrfError::MissingToken => f.write_str("the CSRF token is missing"),
+            CsrfError::Mismatch => f.write_str("the CSRF token doesn't match the cookie"),
+            CsrfError::Invalid(ref e) => write!(f, "the CSRF cookie is invalid: {}", e),
+            CsrfError::Data(ref e) => write!(f, "the data guard failed: {:?}", e),
+        }
+    }
+}
+
+/// Returns the managed configuration of group `G`.
+fn config<'r, G: CsrfGroup, E>(request: &Request<'r>)
+        -> Result<&'r CsrfConfig<G>, CsrfError<E>> {
+    match State::<CsrfConfig<G>>::from_request(request) {
+        Outcome::Success(config) => Ok(config.inner()),
+        _ => {
+            error_!("No CSRF configuration is managed for group '{}'.", G::name());
+            Err(CsrfError::MissingConfig)
+        }
+    }
+}
+
+/// Request guard for the CSRF token of group `G`, to be echoed by the forms
+/// and scripts of the response.
+///
+/// If the request's cookie for `G` holds a valid token with more than half of
+/// its lifetime left, the guard returns it. Otherwise, the guard issues a new
+/// token and sets it as the cookie of the response. The guard fails with a
+/// status of `500 Internal Server Error` if the application doesn't manage a
+/// [CsrfConfig](struct.CsrfConfig.html) for `G`.
+///
+/// The cookie isn't `HttpOnly`, so scripts can read the token from it and
+/// echo it in the configured header.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[get("/profile")]
+/// fn edit_profile(csrf: CsrfToken) -> Html<String> {
+///     Html(format!("<form method=\"post\" action=\"/profile\">{}
+///                   <input name=\"bio\"> <button>Save</button></form>",
+///                  csrf.hidden_field()))
+/// }
+/// ```
+pub struct CsrfToken<'r, G: CsrfGroup = AllRoutes> {
+    token: String,
+    config: &'r CsrfConfig<G>,
+}
+
+impl<'r, G: CsrfGroup> CsrfToken<'r, G> {
+    /// Returns the token.
+    #[inline(always)]
+    pub fn value(&self) -> &str {
+        &self.token
+    }
+
+    /// Returns the name of the form field to echo the token in.
+    #[inline(always)]
+    pub fn field(&self) -> &str {
+        &self.config.field
+    }
+
+    /// Returns the name of the header to echo the token in.
+    #[inline(always)]
+    pub fn header(&self) -> &str {
+        &self.config.header
+    }
+
+    /// Returns a hidden HTML `input` element that echoes the token in a form.
+    /// For the token to be found, the element must be among the first
+    /// fields of the form; see [CsrfForm](struct.CsrfForm.html).
+    pub fn hidden_field(&self) -> String {
+        format!("<input type=\"hidden\" name=\"{}\" value=\"{}\">", self.field(), self.token)
+    }
+}
+
+impl<'r, G: CsrfGroup> fmt::Debug for CsrfToken<'r, G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CsrfToken")
+            .field("group", &G::name())
+            .field("token", &self.token)
+            .finish()
+    }
+}
+
+impl<'a, 'r, G: CsrfGroup> FromRequest<'a, 'r> for CsrfToken<'r, G> {
+    type Error = CsrfError;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, CsrfError> {
+        let config = match config::<G, ()>(request) {
+            Ok(config) => config,
+            Err(e) => return Outcome::Failure((e.status(), e))
+        };
+
+        if let Some(cookie) = request.cookies().find(G::name()) {
+            let renew_at = SystemTime::now() + config.valid_for / 2;
+            match config.key.verify::<GroupPurpose<G>>(&cookie.value) {
+                Ok((_, expires)) if expires > renew_at => {
+                    return Outcome::Success(CsrfToken { token: cookie.value, config: config });
+                }
+                _ => {  }
+            }
+        }
+
+        match config.issue() {
+            Ok(token) => {
+                request.cookies().add(config.cookie(token.clone()));
+                Outcome::Success(CsrfToken { token: token, config: config })
+            }
+            Err(e) => {
+                error_!("Failed to issue a CSRF token: {}.", e);
+                Outcome::Failure((e.status(), e))
+            }
+        }
+    }
+}
+
+/// Request guard that validates the CSRF token echoed in a header, for
+/// requests from scripts and requests without a form body, such as `DELETE`
+/// requests.
+///
+/// The guard succeeds if the request's path is exempt in group `G`'s
+/// [CsrfConfig](struct.CsrfConfig.html) or if the configured header, by
+/// default `X-CSRF-Token`, holds the token in the group's cookie and the token
+/// is valid. Otherwise, it fails with a status of `403 Forbidden`, or `500
+/// Internal Server Error` if no `CsrfConfig` is managed for `G`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[delete("/posts/<id>")]
+/// fn delete(id: usize, _csrf: CsrfHeader) -> &'static str {
+///     remove_post(id);
+///     "Deleted."
+/// }
+/// ```
+pub struct CsrfHeader<G: CsrfGroup = AllRoutes>(PhantomData<G>);
+
+impl<G: CsrfGroup> fmt::Debug for CsrfHeader<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CsrfHeader({})", G::name())
+    }
+}
+
+impl<'a, 'r, G: CsrfGroup> FromRequest<'a, 'r> for CsrfHeader<G> {
+    type Error = CsrfError;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, CsrfError> {
+        let result = config::<G, ()>(request).and_then(|config| {
+            config.check(request, config.header_token(request))
+        });
+
+        match result {
+            Ok(()) => Outcome::Success(CsrfHeader(PhantomData)),
+            Err(e) => {
+                error_!("CSRF validation failed: {}.", e);
+                Outcome::Failure((e.status(), e))
+            }
+        }
+    }
+}
+
+/// Data guard that validates the CSRF token echoed in a form field or header
+/// before running the data guard `T`.
+///
+/// The token is read from the configured header, by default `X-CSRF-Token`,
+/// or else from the configured field, by default `_csrf`, of a
+/// `application/x-www-form-urlencoded` body. The field is only looked for in
+/// the body's peeked portion, its first 4KiB, so it should come first in the
+/// form. If the request's path is exempt in group `G`'s
+/// [CsrfConfig](struct.CsrfConfig.html), or the token is the one in the
+/// group's cookie and is valid, the guard runs `T`, failing with
+/// `CsrfError::Data` if `T` fails and forwarding if `T` forwards. Otherwise,
+/// it fails with a status of `403 Forbidden`, or `500 Internal Server Error`
+/// if no `CsrfConfig` is managed for `G`.
+///
+/// Note that `Form<T>` parses strictly: `T` must have a field for the token,
+/// such as `_csrf: String`, or `LenientForm<T>` must be used instead.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[derive(FromForm)]
+/// struct Profile {
+///     bio: String,
+/// }
+///
+/// #[post("/profile", data = "<profile>")]
+/// fn update(profile: CsrfForm<LenientForm<Profile>>) -> Redirect {
+///     save_bio(&profile.into_inner().get().bio);
+///     Redirect::to("/profile")
+/// }
+/// ```
+pub struct CsrfForm<T, G: CsrfGroup = AllRoutes>(T, PhantomData<G>);
+
+impl<T, G: CsrfGroup> CsrfForm<T, G> {
+    /// Consumes the guard and returns the value of the wrapped data guard.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: fmt::Debug, G: CsrfGroup> fmt::Debug for CsrfForm<T, G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("CsrfForm").field(&self.0).finish()
+    }
+}
+
+impl<T, G: CsrfGroup> ::std::ops::Deref for CsrfForm<T, G> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: FromData, G: CsrfGroup> FromData for CsrfForm<T, G> {
+    type Error = CsrfError<T::Error>;
+
+    fn from_data(request: &Request, data: Data) -> data::Outcome<Self, Self::Error> {
+        let result = config::<G, T::Error>(request).and_then(|config| {
+            let token = config.header_token(request)
+                .or_else(|| config.form_token(request, &data));
+
+            config.check(request, token)
+        });
+
+        if let Err(e) = result {
+            error_!("CSRF validation failed: {}.", e);
+            return Outcome::Failure((e.status(), e));
+        }
+
+        match T::from_data(request, data) {
+            Outcome::Success(value) => Outcome::Success(CsrfForm(value, PhantomData)),
+            Outcome::Failure((status, e)) => Outcome::Failure((status, CsrfError::Data(e))),
+            Outcome::Forward(data) => Outcome::Forward(data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use rocket::Request;
+    use rocket::http::{Cookie, Method, Header};
+    use super::{CsrfConfig, CsrfGroup, CsrfError, AllRoutes, GroupPurpose};
+    use super::super::{TokenKey, TokenError};
+
+    struct Admin;
+
+    impl CsrfGroup for Admin {
+        fn name() -> &'static str { "admin_csrf" }
+    }
+
+    fn key() -> TokenKey {
+        TokenKey::new(b"an example key that is 32 bytes.")
+    }
+
+    #[test]
+    fn test_verify() {
+        let config = CsrfConfig::<AllRoutes>::new(key());
+        let token = config.issue().unwrap();
+        assert!(token != config.issue().unwrap());
+        assert!(config.verify(&token, &token).is_ok());
+        assert_eq!(config.verify(&token, "nope"), Err(CsrfError::Mismatch));
+
+        // Tokens are bound to their group and key.
+        let admin = CsrfConfig::<Admin>::new(key());
+        let error = Err(CsrfError::Invalid(TokenError::BadSignature));
+        assert_eq!(admin.verify(&token, &token), error);
+
+        let other_key = TokenKey::new(b"another key which is 32 bytes...");
+        let forged = CsrfConfig::<AllRoutes>::new(other_key).issue().unwrap();
+        assert_eq!(config.verify(&forged, &forged), error);
+
+        let expired = config.key.issue::<GroupPurpose<AllRoutes>>("x", Duration::from_secs(0));
+        let error = Err(CsrfError::Invalid(TokenError::Expired));
+        assert_eq!(config.verify(&expired, &expired), error);
+    }
+
+    #[test]
+    fn test_check() {
+        let config = CsrfConfig::<AllRoutes>::new(key()).exempt("/hooks/");
+        let token = config.issue().unwrap();
+
+        let mut request = Request::new(Method::Post, "/profile");
+        let result: Result<(), CsrfError> = config.check(&request, Some(token.clone()));
+        assert_eq!(result, Err(CsrfError::MissingCookie));
+
+        request.cookies().add(Cookie::new("csrf".into(), token.clone()));
+        assert_eq!(config.check::<()>(&request, None), Err(CsrfError::MissingToken));
+        assert_eq!(config.check::<()>(&request, Some("x".into())), Err(CsrfError::Mismatch));
+        assert_eq!(config.check::<()>(&request, Some(token.clone())), Ok(()));
+
+        request.add_header(Header::new("X-CSRF-Token", token.clone()));
+        assert_eq!(config.header_token(&request), Some(token));
+
+        let request = Request::new(Method::Post, "/hooks/github");
+        assert_eq!(config.check::<()>(&request, None), Ok(()));
+    }
+
+    #[test]
+    fn test_cookie() {
+        let config = CsrfConfig::<Admin>::new(key()).path("/admin").secure(true);
+        let cookie = config.cookie("token".into());
+        assert_eq!(cookie.name, "admin_csrf");
+        assert_eq!(cookie.path, Some("/admin".into()));
+        assert_eq!(cookie.max_age, Some(12 * 60 * 60));
+        assert!(cookie.secure && !cookie.httponly);
+    }
+}
@@ -0,0 +1,355 @@
+extern crate ring;
+
+use std::cmp::min;
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::fmt;
+
+use rocket::outcome::Outcome;
+use rocket::request::{self, Request, FromRequest, FormItems};
+use rocket::http::{Status, RawStr};
+use rocket::http::base64;
+use rocket::State;
+
+use self::ring::{digest, hmac};
+
+mod csrf;
+
+pub use self::csrf::{CsrfConfig, CsrfGroup, AllRoutes, CsrfToken, CsrfHeader, CsrfForm, CsrfError};
+
+/// The purpose of a [Token](struct.Token.html), such as verifying an email
+/// address or resetting a password.
+///
+/// The purpose is bound into a token's signature, so a token issued for one
+/// purpose is never valid for another.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket_contrib::TokenPurpose;
+///
+/// struct PasswordReset;
+///
+/// impl TokenPurpose for PasswordReset {
+///     fn purpose() -> &'static str { "password-reset" }
+/// }
+/// ```
+pub trait TokenPurpose {
+    /// Returns the name of the purpose. Names must be distinct.
+    fn purpose() -> &'static str;
+}
+
+/// The secret key tokens are signed with, using HMAC-SHA256.
+///
+/// The key must be managed by the application, via `Rocket::manage`, for the
+/// [Token](struct.Token.html) request guard to validate tokens. The key should
+/// be at least 256 bits of random data and kept secret: anyone with the key
+/// can issue tokens.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rocket_contrib::{TokenKey, TokenPurpose};
+///
+/// struct VerifyEmail;
+///
+/// impl TokenPurpose for VerifyEmail {
+///     fn purpose() -> &'static str { "verify-email" }
+/// }
+///
+/// let key = TokenKey::from_base64("MTIzNDU2Nzg5MDEyMzQ1Njc4OTAxMjM0NTY3ODkwMTI=").unwrap();
+/// let token = key.issue::<VerifyEmail>("bob@example.com", Duration::from_secs(3600));
+/// assert_eq!(key.verify::<VerifyEmail>(&token).unwrap().0, "bob@example.com");
+/// ```
+pub struct TokenKey {
+    key: hmac::SigningKey
+}
+
+impl TokenKey {
+    /// Creates a new key from the secret bytes `key`.
+    pub fn new(key: &[u8]) -> TokenKey {
+        TokenKey { key: hmac::SigningKey::new(&digest::SHA256, key) }
+    }
+
+    /// Creates a new key from the base64 encoded secret `key`, as might be
+    /// stored in the configuration. Returns an `Err` if `key` isn't valid
+    /// base64 or is shorter than 256 bits.
+    pub fn from_base64(key: &str) -> Result<TokenKey, &'static str> {
+        let key = base64::decode(key.trim())?;
+        if key.len() < 32 {
+            return Err("Token keys must be at least 256 bits.");
+        }
+
+        Ok(TokenKey::new(&key))
+    }
+
+    /// Returns the message signed for a token for purpose `P` with `payload`.
+    fn message<P: TokenPurpose>(payload: &[u8]) -> Vec<u8> {
+        let mut message = P::purpose().as_bytes().to_vec();
+        message.push(b'\n');
+        message.extend_from_slice(payload);
+        message
+    }
+
+    /// Issues a token for purpose `P` identifying `subject`, such as a user ID
+    /// or email address, that expires `valid_for` from now. The token consists
+    /// only of URL-safe characters and can be embedded in a link as is. Tokens
+    /// expire in 2106 at the latest, however long `valid_for` is.
+    pub fn issue<P: TokenPurpose>(&self, subject: &str, valid_for: Duration) -> String {
+        let expires = min(now().saturating_add(valid_for.as_secs()), MAX_EXPIRES);
+        let payload = format!("{}:{}", expires, subject);
+        let signature = hmac::sign(&self.key, &TokenKey::message::<P>(payload.as_bytes()));
+        format!("{}.{}", encode(payload.as_bytes()), encode(signature.as_ref()))
+    }
+
+    /// Verifies that `token` was issued with this key for purpose `P` and
+    /// hasn't expired. On success, returns the token's subject and the time at
+    /// which it expires.
+    pub fn verify<P: TokenPurpose>(&self, token: &str)
+            -> Result<(String, SystemTime), TokenError> {
+        let mut parts = token.trim().splitn(2, '.');
+        let (payload, signature) = match (parts.next(), parts.next()) {
+            (Some(payload), Some(signature)) => (payload, signature),
+            _ => return Err(TokenError::Malformed)
+        };
+
+        let payload = decode(payload).ok_or(TokenError::Malformed)?;
+        let signature = decode(signature).ok_or(TokenError::Malformed)?;
+        let message = TokenKey::message::<P>(&payload);
+        if hmac::verify_with_own_key(&self.key, &message, &signature).is_err() {
+            return Err(TokenError::BadSignature);
+        }
+
+        let payload = String::from_utf8(payload).map_err(|_| TokenError::Malformed)?;
+        let mut parts = payload.splitn(2, ':');
+        let (expires, subject) = match (parts.next(), parts.next()) {
+            (Some(expires), Some(subject)) => (expires, subject),
+            _ => return Err(TokenError::Malformed)
+        };
+
+        let expires: u64 = expires.parse().map_err(|_| TokenError::Malformed)?;
+        if expires > MAX_EXPIRES {
+            return Err(TokenError::Malformed);
+        } else if now() >= expires {
+            return Err(TokenError::Expired);
+        }
+
+        Ok((subject.to_string(), UNIX_EPOCH + Duration::from_secs(expires)))
+    }
+}
+
+impl fmt::Debug for TokenKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("TokenKey(..)")
+    }
+}
+
+/// The latest time, in seconds since the epoch, at which a token may expire.
+/// Later times overflow the `SystemTime` of some platforms.
+const MAX_EXPIRES: u64 = ::std::u32::MAX as u64;
+
+/// Returns the number of seconds since the epoch.
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Encodes `bytes` as unpadded, URL-safe base64.
+fn encode(bytes: &[u8]) -> String {
+    base64::encode(bytes).trim_right_matches('=').chars().map(|c| match c {
+        '+' => '-',
+        '/' => '_',
+        c => c
+    }).collect()
+}
+
+/// Decodes unpadded, URL-safe base64.
+fn decode(string: &str) -> Option<Vec<u8>> {
+    let standard: String = string.chars().map(|c| match c {
+        '-' => '+',
+        '_' => '/',
+        '+' | '/' => '!',
+        c => c
+    }).collect();
+
+    base64::decode(&standard).ok()
+}
+
+/// The reason a token is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenError {
+    /// The token isn't structured like a token.
+    Malformed,
+    /// The token wasn't issued with the key for the purpose.
+    BadSignature,
+    /// The token has expired.
+    Expired,
+    /// The application doesn't manage a `TokenKey`.
+    MissingKey,
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TokenError::Malformed => f.write_str("the token is malformed"),
+            TokenError::BadSignature => f.write_str("the token's signature is invalid"),
+            TokenError::Expired => f.write_str("the token has expired"),
+            TokenError::MissingKey => f.write_str("no token key is managed"),
+        }
+    }
+}
+
+/// Request guard for a valid token for purpose `P`, issued via
+/// [TokenKey::issue](struct.TokenKey.html#method.issue).
+///
+/// The token is read from the `token` query parameter, so a link such as
+/// `/verify?token=...` can be sent by email. If there is no `token` parameter,
+/// the guard forwards. If the token is malformed, expired, or wasn't issued
+/// for `P` with the application's managed [TokenKey](struct.TokenKey.html), the
+/// guard fails with a status of `403 Forbidden`. If no `TokenKey` is managed,
+/// the guard fails with a status of `500 Internal Server Error`.
+///
+/// A token remains valid until it expires. To make a token usable only once,
+/// include state that using the token changes, such as a hash of the user's
+/// current password for a password reset, in its subject, and check it.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// struct VerifyEmail;
+///
+/// impl TokenPurpose for VerifyEmail {
+///     fn purpose() -> &'static str { "verify-email" }
+/// }
+///
+/// #[get("/verify")]
+/// fn verify(token: Token<VerifyEmail>) -> String {
+///     mark_verified(token.subject());
+///     format!("Thanks for verifying {}!", token.subject())
+/// }
+///
+/// fn main() {
+///     rocket::ignite()
+///         .mount("/", routes![verify])
+///         .manage(TokenKey::from_base64(SECRET).unwrap())
+///         .launch()
+/// }
+/// ```
+pub struct Token<P: TokenPurpose> {
+    subject: String,
+    expires: SystemTime,
+    _purpose: PhantomData<P>,
+}
+
+impl<P: TokenPurpose> Token<P> {
+    /// Returns the subject the token was issued for.
+    #[inline(always)]
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Returns the time at which the token expires.
+    #[inline(always)]
+    pub fn expires(&self) -> SystemTime {
+        self.expires
+    }
+}
+
+impl<P: TokenPurpose> fmt::Debug for Token<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Token")
+            .field("purpose", &P::purpose())
+            .field("subject", &self.subject)
+            .field("expires", &self.expires)
+            .finish()
+    }
+}
+
+impl<'a, 'r, P: TokenPurpose> FromRequest<'a, 'r> for Token<P> {
+    type Error = TokenError;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, TokenError> {
+        let token = request.uri().query()
+            .and_then(|query| FormItems(query).find(|&(key, _)| key == "token"))
+            .and_then(|(_, value)| RawStr::from_str(value).url_decode().ok());
+
+        let token = match token {
+            Some(token) => token,
+            None => return Outcome::Forward(())
+        };
+
+        let key = match State::<TokenKey>::from_request(request) {
+            Outcome::Success(key) => key,
+            _ => return Outcome::Failure((Status::InternalServerError, TokenError::MissingKey))
+        };
+
+        match key.verify::<P>(&token) {
+            Ok((subject, expires)) => Outcome::Success(Token {
+                subject: subject,
+                expires: expires,
+                _purpose: PhantomData
+            }),
+            Err(e) => {
+                error_!("Invalid '{}' token: {}.", P::purpose(), e);
+                Outcome::Failure((Status::Forbidden, e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+    use super::{TokenKey, TokenPurpose, TokenError, MAX_EXPIRES, encode, decode};
+
+    struct VerifyEmail;
+    struct ResetPassword;
+
+    impl TokenPurpose for VerifyEmail {
+        fn purpose() -> &'static str { "verify-email" }
+    }
+
+    impl TokenPurpose for ResetPassword {
+        fn purpose() -> &'static str { "reset-password" }
+    }
+
+    #[test]
+    fn test_url_safe_base64() {
+        let bytes = [0xfb, 0xff, 0xbf, 0x01];
+        assert_eq!(encode(&bytes), "-_-_AQ");
+        assert_eq!(decode("-_-_AQ"), Some(bytes.to_vec()));
+        assert_eq!(decode("+/+/AQ"), None);
+    }
+
+    #[test]
+    fn test_issue_and_verify() {
+        let key = TokenKey::new(b"an example key that is 32 bytes.");
+        let token = key.issue::<VerifyEmail>("user:42", Duration::from_secs(60));
+        assert!(token.chars().all(|c| c.is_alphanumeric() || "-_.".contains(c)));
+
+        let (subject, _) = key.verify::<VerifyEmail>(&token).unwrap();
+        assert_eq!(subject, "user:42");
+
+        // Tokens are bound to their purpose and key.
+        assert_eq!(key.verify::<ResetPassword>(&token), Err(TokenError::BadSignature));
+        let other = TokenKey::new(b"another key which is 32 bytes...");
+        assert_eq!(other.verify::<VerifyEmail>(&token), Err(TokenError::BadSignature));
+
+        // Tampering is detected.
+        let forged = format!("A{}", token);
+        assert!(key.verify::<VerifyEmail>(&forged).is_err());
+        assert_eq!(key.verify::<VerifyEmail>("nonsense"), Err(TokenError::Malformed));
+    }
+
+    #[test]
+    fn test_expiry() {
+        let key = TokenKey::new(b"an example key that is 32 bytes.");
+        let token = key.issue::<VerifyEmail>("bob", Duration::from_secs(0));
+        assert_eq!(key.verify::<VerifyEmail>(&token), Err(TokenError::Expired));
+
+        // Absurdly long validity is capped rather than overflowing.
+        let token = key.issue::<VerifyEmail>("bob", Duration::from_secs(::std::u64::MAX));
+        let (_, expires) = key.verify::<VerifyEmail>(&token).unwrap();
+        assert_eq!(expires, UNIX_EPOCH + Duration::from_secs(MAX_EXPIRES));
+    }
+}
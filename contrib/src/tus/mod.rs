@@ -0,0 +1,589 @@
+extern crate ring;
+
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::time::{Duration, SystemTime};
+use std::fmt;
+
+use rocket::{Route, Request, Response, Data, State};
+use rocket::handler::Outcome;
+use rocket::request::TempFile;
+use rocket::response::ResponseBuilder;
+use rocket::outcome::Outcome as GuardOutcome;
+use rocket::http::{Status, Method, ContentType, Expires};
+use rocket::http::base64;
+
+use self::ring::rand::{SecureRandom, SystemRandom};
+
+/// The version of the tus protocol implemented.
+const TUS_VERSION: &'static str = "1.0.0";
+
+/// The protocol extensions implemented.
+const TUS_EXTENSIONS: &'static str = "creation,expiration,termination";
+
+/// An upload in progress.
+struct Upload {
+    file: TempFile,
+    length: u64,
+    metadata: String,
+    expires: SystemTime,
+}
+
+/// Resumable uploads via the [tus](https://tus.io) protocol, version 1.0.0.
+///
+/// Clients on flaky connections, such as mobile apps, upload large files in
+/// pieces with tus: they create an upload of a known length, append data to it
+/// with `PATCH` requests, and, when a request fails partway, ask for the
+/// upload's offset with a `HEAD` request and resume from there. Any tus client
+/// library can be used. The `creation`, `expiration`, and `termination`
+/// extensions are implemented.
+///
+/// A `Tus` keeps track of the uploads in progress. It must be managed by the
+/// application, via `Rocket::manage`, and the routes returned by
+/// [routes](#method.routes) mounted at the URL clients upload to. The routes
+/// answer the following requests, where `/files` is the mount point:
+///
+///   * `OPTIONS /files`: describes the server's tus support.
+///   * `POST /files`: creates an upload of `Upload-Length` bytes, with the
+///     optional `Upload-Metadata`, and answers with its URL, `/files/<id>`, in
+///     the `Location` header.
+///   * `HEAD /files/<id>`: answers with the upload's offset, the number of
+///     bytes received so far.
+///   * `PATCH /files/<id>`: appends the body to the upload, which must be
+///     sent as `application/offset+octet-stream` with the upload's current
+///     offset in `Upload-Offset`.
+///   * `DELETE /files/<id>`: cancels the upload.
+///
+/// While a `PATCH` for an upload is being received, `HEAD` and `PATCH`
+/// requests for the same upload are answered with `423 Locked`, so a client
+/// resuming after its connection dropped retries until the server notices.
+///
+/// Each upload is streamed into a [TempFile](/rocket/request/struct.TempFile.html)
+/// as it's received. Uploads are limited to the size of the **file** limit
+/// described in [Limits](/rocket/config/struct.Limits.html), which is
+/// advertised to clients as `Tus-Max-Size`. Uploads expire, and are deleted,
+/// once no data has been appended to them for the duration set via
+/// [expire_after](#method.expire_after), an hour by default. Uploads are kept
+/// in memory, so they don't survive a restart of the application.
+///
+/// Once an upload is complete, the application claims it via
+/// [take](#method.take), typically from a route the client calls with the
+/// upload's ID once it's done, and moves the file wherever it belongs.
+///
+/// # Usage
+///
+/// To use, add the `tus` feature to the `rocket_contrib` dependencies section
+/// of your `Cargo.toml`:
+///
+/// ```toml,ignore
+/// [dependencies.rocket_contrib]
+/// version = "*"
+/// default-features = false
+/// features = ["tus"]
+/// ```
+///
+/// Then, manage a `Tus` and mount its routes:
+///
+/// ```rust,ignore
+/// use rocket_contrib::Tus;
+///
+/// #[post("/videos/<id>")]
+/// fn publish(id: &str, tus: State<Tus>) -> io::Result<Option<String>> {
+///     let upload = match tus.take(id) {
+///         Some(upload) => upload,
+///         None => return Ok(None)
+///     };
+///
+///     let title = upload.metadata("filename").unwrap_or("untitled").to_string();
+///     upload.into_file().persist_to(format!("/srv/videos/{}", id))?;
+///     Ok(Some(format!("Published '{}'.", title)))
+/// }
+///
+/// fn main() {
+///     rocket::ignite()
+///         .manage(Tus::new().expire_after(Duration::from_secs(24 * 60 * 60)))
+///         .mount("/files", Tus::routes())
+///         .mount("/", routes![publish])
+///         .launch();
+/// }
+/// ```
+pub struct Tus {
+    uploads: Mutex<HashMap<String, Arc<Mutex<Upload>>>>,
+    expire_after: Duration,
+}
+
+impl Tus {
+    /// Returns a `Tus` with no uploads whose uploads expire after an hour.
+    pub fn new() -> Tus {
+        Tus { uploads: Mutex::new(HashMap::new()), expire_after: Duration::from_secs(60 * 60) }
+    }
+
+    /// Sets how long an upload is kept after data was last appended to it.
+    pub fn expire_after(mut self, expire_after: Duration) -> Self {
+        self.expire_after = expire_after;
+        self
+    }
+
+    /// Returns the routes that implement the protocol. These should be
+    /// mounted at the URL clients upload to.
+    pub fn routes() -> Vec<Route> {
+        vec![
+            Route::new(Method::Options, "/", options),
+            Route::new(Method::Post, "/", create),
+            Route::new(Method::Head, "/<id>", head),
+            Route::new(Method::Patch, "/<id>", patch),
+            Route::new(Method::Delete, "/<id>", terminate),
+        ]
+    }
+
+    /// Creates an empty upload of `length` bytes with the `Upload-Metadata`
+    /// `metadata`, returning its ID.
+    fn create(&self, length: u64, metadata: &str) -> io::Result<String> {
+        let fields = parse_metadata(metadata)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "bad metadata"))?;
+
+        let file_name = fields.get("filename").or_else(|| fields.get("name"));
+        let content_type = fields.get("filetype").or_else(|| fields.get("type"))
+            .and_then(|string| string.parse::<ContentType>().ok());
+
+        let file = TempFile::new(file_name.map(|name| name.as_str()), content_type)?;
+        let mut nonce = [0; 16];
+        SystemRandom::new().fill(&mut nonce)
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "couldn't generate an ID"))?;
+
+        let id: String = nonce.iter().map(|b| format!("{:02x}", b)).collect();
+        let upload = Upload {
+            file: file,
+            length: length,
+            metadata: metadata.to_string(),
+            expires: SystemTime::now() + self.expire_after,
+        };
+
+        self.remove_expired();
+        let mut uploads = self.uploads.lock().expect("tus uploads lock");
+        uploads.insert(id.clone(), Arc::new(Mutex::new(upload)));
+        Ok(id)
+    }
+
+    /// Returns the upload with ID `id`, or the status to answer requests for
+    /// it with: `404 Not Found` if there's no such upload, and `410 Gone` if
+    /// it has expired.
+    fn upload(&self, id: &str) -> Result<Arc<Mutex<Upload>>, Status> {
+        let mut uploads = self.uploads.lock().expect("tus uploads lock");
+        let expired = match uploads.get(id) {
+            Some(upload) => is_expired(upload, SystemTime::now()),
+            None => return Err(Status::NotFound)
+        };
+
+        if expired {
+            uploads.remove(id);
+            return Err(Status::Gone);
+        }
+
+        Ok(uploads[id].clone())
+    }
+
+    /// Deletes the uploads that have expired, returning how many there were.
+    /// Expired uploads are also deleted whenever an upload is created.
+    pub fn remove_expired(&self) -> usize {
+        let now = SystemTime::now();
+        let mut uploads = self.uploads.lock().expect("tus uploads lock");
+        let expired: Vec<String> = uploads.iter()
+            .filter(|&(_, upload)| is_expired(upload, now))
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for id in &expired {
+            uploads.remove(id);
+        }
+
+        expired.len()
+    }
+
+    /// Returns `true` if the upload with ID `id` exists and has received all
+    /// of its data.
+    pub fn is_complete(&self, id: &str) -> bool {
+        match self.upload(id) {
+            Ok(upload) => {
+                let upload = upload.lock().expect("tus upload lock");
+                upload.file.len() == upload.length
+            }
+            Err(_) => false
+        }
+    }
+
+    /// Removes the complete upload with ID `id` and returns it, handing its
+    /// file over to the application. Returns `None` if there's no such upload
+    /// or it isn't complete.
+    pub fn take(&self, id: &str) -> Option<TusUpload> {
+        if !self.is_complete(id) {
+            return None;
+        }
+
+        let upload = match self.uploads.lock().expect("tus uploads lock").remove(id) {
+            Some(upload) => upload,
+            None => return None
+        };
+
+        let upload = match Arc::try_unwrap(upload) {
+            Ok(upload) => upload.into_inner().expect("tus upload lock"),
+            Err(upload) => {
+                // A request for the upload is still being handled.
+                self.uploads.lock().expect("tus uploads lock").insert(id.to_string(), upload);
+                return None;
+            }
+        };
+
+        Some(TusUpload {
+            id: id.to_string(),
+            metadata: parse_metadata(&upload.metadata).unwrap_or(HashMap::new()),
+            file: upload.file,
+        })
+    }
+}
+
+impl fmt::Debug for Tus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let uploads = self.uploads.lock().expect("tus uploads lock").len();
+        f.debug_struct("Tus")
+            .field("uploads", &uploads)
+            .field("expire_after", &self.expire_after)
+            .finish()
+    }
+}
+
+/// A complete upload, claimed via [Tus::take](struct.Tus.html#method.take).
+#[derive(Debug)]
+pub struct TusUpload {
+    id: String,
+    file: TempFile,
+    metadata: HashMap<String, String>,
+}
+
+impl TusUpload {
+    /// Returns the upload's ID.
+    #[inline(always)]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the value of the metadata field `key` sent by the client when
+    /// creating the upload, if there is one. The value is untrusted.
+    pub fn metadata(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(|value| value.as_str())
+    }
+
+    /// Returns the uploaded file. Its name and Content-Type are taken from
+    /// the `filename` and `filetype` metadata fields, if the client sent them.
+    #[inline(always)]
+    pub fn file(&self) -> &TempFile {
+        &self.file
+    }
+
+    /// Consumes the upload and returns the uploaded file, which is deleted
+    /// when dropped unless it's persisted.
+    #[inline(always)]
+    pub fn into_file(self) -> TempFile {
+        self.file
+    }
+}
+
+/// Returns `true` if `upload` expired before `now`. An upload that's locked,
+/// because a request for it is being handled, hasn't expired; checking
+/// doesn't wait for the lock, so the uploads aren't held up meanwhile.
+fn is_expired(upload: &Mutex<Upload>, now: SystemTime) -> bool {
+    match upload.try_lock() {
+        Ok(upload) => upload.expires <= now,
+        Err(_) => false
+    }
+}
+
+/// Locks `upload` for a request, or returns the response to give if another
+/// request holds the lock. A `PATCH` holds the lock while it reads its data,
+/// which can take until the read times out when its client has disconnected;
+/// the client's requests to resume are answered with `423 Locked` meanwhile
+/// instead of waiting.
+fn lock<'r>(upload: &Mutex<Upload>) -> Result<MutexGuard<Upload>, Outcome<'r>> {
+    match upload.try_lock() {
+        Ok(upload) => Ok(upload),
+        Err(TryLockError::WouldBlock) => {
+            warn_!("Upload is locked by another request.");
+            Err(Outcome::of(response(Status::Locked).finalize()))
+        }
+        Err(TryLockError::Poisoned(_)) => panic!("tus upload lock poisoned")
+    }
+}
+
+/// Parses an `Upload-Metadata` header: comma-separated pairs of a key and an
+/// optional, base64 encoded value. Values that aren't UTF-8 are decoded
+/// lossily. Returns `None` if the header is malformed.
+fn parse_metadata(header: &str) -> Option<HashMap<String, String>> {
+    let mut metadata = HashMap::new();
+    for pair in header.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        let mut parts = pair.split(' ').filter(|p| !p.is_empty());
+        let (key, value) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(key), value, None) => (key, value),
+            _ => return None
+        };
+
+        let value = match value.map(base64::decode) {
+            Some(Ok(bytes)) => String::from_utf8_lossy(&bytes).into_owned(),
+            Some(Err(_)) => return None,
+            None => String::new()
+        };
+
+        metadata.insert(key.to_string(), value);
+    }
+
+    Some(metadata)
+}
+
+/// Returns a response builder with `status` and the `Tus-Resumable` header.
+fn response(status: Status) -> ResponseBuilder<'static> {
+    let mut builder = Response::build();
+    builder.status(status).raw_header("Tus-Resumable", TUS_VERSION);
+    builder
+}
+
+/// Returns the value of the header `name` as a `u64`, if it's present and
+/// valid.
+fn u64_header(request: &Request, name: &str) -> Option<u64> {
+    request.headers().get_one(name).and_then(|value| value.trim().parse().ok())
+}
+
+/// Returns the managed `Tus`, or the response to give if there isn't one or
+/// the request is for another version of the protocol.
+fn tus<'r>(request: &'r Request) -> Result<&'r Tus, Outcome<'r>> {
+    if request.headers().get_one("Tus-Resumable") != Some(TUS_VERSION) {
+        warn_!("Request isn't for tus version {}.", TUS_VERSION);
+        let response = response(Status::PreconditionFailed)
+            .raw_header("Tus-Version", TUS_VERSION)
+            .finalize();
+
+        return Err(Outcome::of(response));
+    }
+
+    match request.guard::<State<Tus>>() {
+        GuardOutcome::Success(tus) => Ok(tus.inner()),
+        _ => {
+            error_!("Resumable uploads require a managed `Tus`.");
+            Err(Outcome::failure(Status::InternalServerError))
+        }
+    }
+}
+
+fn options<'r>(request: &'r Request, _: Data) -> Outcome<'r> {
+    let max_size = request.limits().get("file").as_u64();
+    Outcome::of(response(Status::NoContent)
+        .raw_header("Tus-Version", TUS_VERSION)
+        .raw_header("Tus-Extension", TUS_EXTENSIONS)
+        .raw_header("Tus-Max-Size", max_size.to_string())
+        .finalize())
+}
+
+fn create<'r>(request: &'r Request, _: Data) -> Outcome<'r> {
+    let tus = match tus(request) {
+        Ok(tus) => tus,
+        Err(outcome) => return outcome
+    };
+
+    let length = match u64_header(request, "Upload-Length") {
+        Some(length) => length,
+        None => {
+            warn_!("Upload has no valid Upload-Length.");
+            return Outcome::of(response(Status::BadRequest).finalize());
+        }
+    };
+
+    let max_size = request.limits().get("file");
+    if length > max_size.as_u64() {
+        warn_!("Upload of {} bytes exceeds the file limit of {}.", length, max_size);
+        return Outcome::of(response(Status::PayloadTooLarge).finalize());
+    }
+
+    let metadata = request.headers().get_one("Upload-Metadata").unwrap_or("");
+    let id = match tus.create(length, metadata) {
+        Ok(id) => id,
+        Err(ref e) if e.kind() == io::ErrorKind::InvalidInput => {
+            warn_!("Upload has malformed Upload-Metadata.");
+            return Outcome::of(response(Status::BadRequest).finalize());
+        }
+        Err(e) => {
+            error_!("Failed to create upload: {:?}", e);
+            return Outcome::failure(Status::InternalServerError);
+        }
+    };
+
+    let location = format!("{}/{}", request.uri().path().trim_right_matches('/'), id);
+    let expires = SystemTime::now() + tus.expire_after;
+    Outcome::of(response(Status::Created)
+        .raw_header("Location", location)
+        .raw_header("Upload-Expires", Expires(expires).to_string())
+        .finalize())
+}
+
+fn head<'r>(request: &'r Request, _: Data) -> Outcome<'r> {
+    let tus = match tus(request) {
+        Ok(tus) => tus,
+        Err(outcome) => return outcome
+    };
+
+    let upload = match tus.upload(request.get_param_str(0).unwrap_or("")) {
+        Ok(upload) => upload,
+        Err(status) => return Outcome::of(response(status).finalize())
+    };
+
+    let upload = match lock(&upload) {
+        Ok(upload) => upload,
+        Err(outcome) => return outcome
+    };
+
+    let mut builder = response(Status::Ok);
+    builder.raw_header("Upload-Offset", upload.file.len().to_string())
+        .raw_header("Upload-Length", upload.length.to_string())
+        .raw_header("Upload-Expires", Expires(upload.expires).to_string())
+        .raw_header("Cache-Control", "no-store");
+
+    if !upload.metadata.is_empty() {
+        builder.raw_header("Upload-Metadata", upload.metadata.clone());
+    }
+
+    Outcome::of(builder.finalize())
+}
+
+fn patch<'r>(request: &'r Request, data: Data) -> Outcome<'r> {
+    let tus = match tus(request) {
+        Ok(tus) => tus,
+        Err(outcome) => return outcome
+    };
+
+    let upload = match tus.upload(request.get_param_str(0).unwrap_or("")) {
+        Ok(upload) => upload,
+        Err(status) => return Outcome::of(response(status).finalize())
+    };
+
+    let content_type = request.content_type();
+    if content_type.ttype != "application" || content_type.subtype != "offset+octet-stream" {
+        warn_!("Upload data isn't application/offset+octet-stream.");
+        return Outcome::of(response(Status::UnsupportedMediaType).finalize());
+    }
+
+    let offset = match u64_header(request, "Upload-Offset") {
+        Some(offset) => offset,
+        None => {
+            warn_!("Upload data has no valid Upload-Offset.");
+            return Outcome::of(response(Status::BadRequest).finalize());
+        }
+    };
+
+    let mut upload = match lock(&upload) {
+        Ok(upload) => upload,
+        Err(outcome) => return outcome
+    };
+
+    if offset != upload.file.len() {
+        warn_!("Upload-Offset {} doesn't match offset {}.", offset, upload.file.len());
+        return Outcome::of(response(Status::Conflict).finalize());
+    }
+
+    let remaining = upload.length - offset;
+    if u64_header(request, "Content-Length").map_or(false, |len| len > remaining) {
+        warn_!("Upload data exceeds the {} bytes remaining.", remaining);
+        return Outcome::of(response(Status::PayloadTooLarge).finalize());
+    }
+
+    // Data received before a failure is kept so the client can resume.
+    let result = upload.file.append(data.open().take(remaining));
+    upload.expires = SystemTime::now() + tus.expire_after;
+    let status = match result {
+        Ok(_) => Status::NoContent,
+        Err(e) => {
+            error_!("Failed to append upload data: {:?}", e);
+            Status::InternalServerError
+        }
+    };
+
+    Outcome::of(response(status)
+        .raw_header("Upload-Offset", upload.file.len().to_string())
+        .raw_header("Upload-Expires", Expires(upload.expires).to_string())
+        .finalize())
+}
+
+fn terminate<'r>(request: &'r Request, _: Data) -> Outcome<'r> {
+    let tus = match tus(request) {
+        Ok(tus) => tus,
+        Err(outcome) => return outcome
+    };
+
+    let id = request.get_param_str(0).unwrap_or("");
+    match tus.uploads.lock().expect("tus uploads lock").remove(id) {
+        Some(_) => Outcome::of(response(Status::NoContent).finalize()),
+        None => Outcome::of(response(Status::NotFound).finalize())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+    use std::time::Duration;
+
+    use super::{Tus, lock, parse_metadata};
+
+    #[test]
+    fn test_parse_metadata() {
+        let header = "filename d29ybGRfZG9taW5hdGlvbl9wbGFuLnBkZg==,is_confidential";
+        let metadata = parse_metadata(header).unwrap();
+        assert_eq!(metadata["filename"], "world_domination_plan.pdf");
+        assert_eq!(metadata["is_confidential"], "");
+
+        assert_eq!(parse_metadata("").unwrap().len(), 0);
+        assert!(parse_metadata("key !!!").is_none());
+        assert!(parse_metadata("key dmFsdWU= extra").is_none());
+    }
+
+    #[test]
+    fn test_upload_lifecycle() {
+        let tus = Tus::new();
+        let id = tus.create(11, "filename aGVsbG8udHh0").unwrap();
+        assert!(!tus.is_complete(&id));
+        assert!(tus.take(&id).is_none());
+
+        {
+            let upload = tus.upload(&id).unwrap();
+            let mut upload = upload.lock().unwrap();
+            upload.file.append(&b"hello"[..]).unwrap();
+            upload.file.append(&b" world"[..]).unwrap();
+        }
+
+        assert!(tus.is_complete(&id));
+        let upload = tus.take(&id).unwrap();
+        assert_eq!(upload.file().file_name(), Some("hello.txt"));
+        assert_eq!(upload.metadata("filename"), Some("hello.txt"));
+
+        let mut contents = String::new();
+        upload.file().open().unwrap().read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "hello world");
+        assert!(tus.take(&id).is_none());
+    }
+
+    #[test]
+    fn test_locked_upload() {
+        let tus = Tus::new();
+        let id = tus.create(5, "").unwrap();
+        let upload = tus.upload(&id).unwrap();
+
+        let guard = lock(&upload).ok().unwrap();
+        assert!(lock(&upload).is_err());
+        drop(guard);
+        assert!(lock(&upload).is_ok());
+    }
+
+    #[test]
+    fn test_expiry() {
+        let tus = Tus::new().expire_after(Duration::from_secs(0));
+        let id = tus.create(5, "").unwrap();
+        assert_eq!(tus.remove_expired(), 1);
+        assert!(tus.upload(&id).is_err());
+    }
+}
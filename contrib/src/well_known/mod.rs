@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use rocket::{Route, Request, Data};
+use rocket::config;
+use rocket::handler::Outcome;
+use rocket::http::{ContentType, Method};
+use rocket::response::{self, Content, Redirect, Responder};
+
+/// The name of the configuration table from which documents are read.
+const CONFIG_TABLE: &'static str = "well_known";
+
+/// A document served under `/.well-known/`.
+#[derive(Debug, Clone, PartialEq)]
+enum Document {
+    Body(ContentType, String),
+    Redirect(String),
+}
+
+impl<'r> Responder<'r> for Document {
+    fn respond(self) -> response::Result<'r> {
+        match self {
+            Document::Body(content_type, body) => Content(content_type, body).respond(),
+            Document::Redirect(uri) => Redirect::to(&uri).respond(),
+        }
+    }
+}
+
+lazy_static! {
+    static ref DOCUMENTS: RwLock<HashMap<String, Document>> = RwLock::new(HashMap::new());
+}
+
+/// Serves documents under `/.well-known/`, such as `security.txt`,
+/// `webfinger`, `change-password`, and `assetlinks.json`.
+///
+/// Documents can be registered in code, via
+/// [register](#method.register) and [register_redirect](#method.register_redirect),
+/// or in the configuration, via the `well_known` table. Documents registered in
+/// code take precedence over those in the configuration. The routes returned
+/// by [routes](#method.routes) serve the documents; requests for unknown
+/// documents are forwarded.
+///
+/// # Configuration
+///
+/// Each key in the `well_known` table is the name of a document, and each value
+/// is its body. The content type is chosen from the name: `webfinger` and
+/// `host-meta.json` are served as `application/jrd+json`, names with a known
+/// extension are served with the corresponding content type, and all others
+/// are served as plain text. `change-password`, which names the page at which
+/// users can change their password, is served as a redirect to its value.
+///
+/// ```toml
+/// [global.well_known]
+/// "security.txt" = "Contact: mailto:security@example.com"
+/// "change-password" = "https://example.com/account/password"
+/// ```
+///
+/// # Usage
+///
+/// To use, add the `well_known` feature to the `rocket_contrib` dependencies
+/// section of your `Cargo.toml`:
+///
+/// ```toml,ignore
+/// [dependencies.rocket_contrib]
+/// version = "*"
+/// default-features = false
+/// features = ["well_known"]
+/// ```
+///
+/// Then, mount the routes at the root:
+///
+/// ```rust,ignore
+/// use rocket::http::ContentType;
+/// use rocket_contrib::WellKnown;
+///
+/// fn main() {
+///     WellKnown::register("assetlinks.json", ContentType::JSON, r#"[{"relation": []}]"#);
+///
+///     rocket::ignite()
+///         .mount("/", WellKnown::routes())
+///         .launch();
+/// }
+/// ```
+pub struct WellKnown;
+
+impl WellKnown {
+    /// Registers the document `name`, to be served at `/.well-known/name` with
+    /// content type `content_type` and body `body`. Replaces any previously
+    /// registered document with the same name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` contains a `/`.
+    pub fn register<S, B>(name: S, content_type: ContentType, body: B)
+        where S: Into<String>, B: Into<String>
+    {
+        WellKnown::insert(name.into(), Document::Body(content_type, body.into()));
+    }
+
+    /// Registers the document `name`, to be served at `/.well-known/name` as
+    /// a redirect to `uri`. Replaces any previously registered document with
+    /// the same name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` contains a `/`.
+    pub fn register_redirect<S, U>(name: S, uri: U)
+        where S: Into<String>, U: Into<String>
+    {
+        WellKnown::insert(name.into(), Document::Redirect(uri.into()));
+    }
+
+    /// Returns the routes that serve the registered and configured documents.
+    /// These should be mounted at `/`.
+    pub fn routes() -> Vec<Route> {
+        vec![Route::new(Method::Get, "/.well-known/<name>", handler)]
+    }
+
+    fn insert(name: String, document: Document) {
+        if name.contains('/') {
+            error_!("Bad well-known document name: '{}'.", name);
+            panic!("Well-known document names cannot contain '/'.")
+        }
+
+        DOCUMENTS.write().expect("well-known documents lock").insert(name, document);
+    }
+}
+
+/// Returns the content type to serve the configured document `name` with.
+fn content_type_for(name: &str) -> ContentType {
+    match name {
+        "webfinger" | "host-meta.json" => ContentType::new("application", "jrd+json"),
+        _ => match name.rfind('.').map(|i| ContentType::from_extension(&name[(i + 1)..])) {
+            Some(ref ct) if !ct.is_any() => ct.clone(),
+            _ => ContentType::Plain,
+        }
+    }
+}
+
+/// Looks up the configured document `name`.
+fn configured(name: &str) -> Option<Document> {
+    let config = match config::active() {
+        Some(config) => config,
+        None => return None,
+    };
+
+    let table = match config.get_table(CONFIG_TABLE) {
+        Ok(table) => table,
+        Err(ref e) if e.is_not_found() => return None,
+        Err(e) => {
+            e.pretty_print();
+            return None;
+        }
+    };
+
+    let value = match table.get(name) {
+        Some(value) => value,
+        None => return None,
+    };
+
+    match value.as_str() {
+        Some(body) if name == "change-password" => Some(Document::Redirect(body.into())),
+        Some(body) => Some(Document::Body(content_type_for(name), body.into())),
+        None => {
+            warn_!("Well-known document '{}' is not a string. Ignoring.", name);
+            None
+        }
+    }
+}
+
+/// Looks up the document `name`, preferring registered documents.
+fn lookup(name: &str) -> Option<Document> {
+    let registered = DOCUMENTS.read().expect("well-known documents lock").get(name).cloned();
+    registered.or_else(|| configured(name))
+}
+
+fn handler<'r>(request: &'r Request, data: Data) -> Outcome<'r> {
+    let name: &str = match request.get_param(0) {
+        Ok(name) => name,
+        Err(_) => return Outcome::forward(data),
+    };
+
+    match lookup(name) {
+        Some(document) => Outcome::of(document),
+        None => Outcome::forward(data),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{WellKnown, Document, lookup, content_type_for};
+    use rocket::http::ContentType;
+
+    #[test]
+    fn test_content_types() {
+        let jrd = ContentType::new("application", "jrd+json");
+        assert_eq!(content_type_for("security.txt"), ContentType::Plain);
+        assert_eq!(content_type_for("assetlinks.json"), ContentType::JSON);
+        assert_eq!(content_type_for("webfinger"), jrd);
+        assert_eq!(content_type_for("host-meta.json"), jrd);
+        assert_eq!(content_type_for("nodeinfo"), ContentType::Plain);
+        assert_eq!(content_type_for("thing.unknown"), ContentType::Plain);
+    }
+
+    #[test]
+    fn test_registered_documents() {
+        WellKnown::register("security.txt", ContentType::Plain, "Contact: mailto:a@b.c");
+        WellKnown::register_redirect("change-password", "/account/password");
+
+        assert_eq!(lookup("security.txt"),
+                   Some(Document::Body(ContentType::Plain, "Contact: mailto:a@b.c".into())));
+        assert_eq!(lookup("change-password"),
+                   Some(Document::Redirect("/account/password".into())));
+        assert_eq!(lookup("unknown"), None);
+
+        WellKnown::register("security.txt", ContentType::Plain, "Contact: x");
+        assert_eq!(lookup("security.txt"),
+                   Some(Document::Body(ContentType::Plain, "Contact: x".into())));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bad_name() {
+        WellKnown::register("a/b", ContentType::Plain, "");
+    }
+}
@@ -6,7 +6,7 @@ extern crate serde_json;
 #[macro_use] extern crate serde_derive;
 
 use rocket::{Request, Error};
-use rocket::http::ContentType;
+use rocket::http::{Accept, ContentType};
 use rocket::response::content::JSON;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,21 +18,22 @@ struct Person {
 // This shows how to manually serialize some JSON, but in a real application,
 // we'd use the JSON contrib type.
 #[get("/<name>/<age>", format = "application/json")]
-fn hello(content_type: ContentType, name: String, age: i8) -> JSON<String> {
+fn hello(accept: Accept, name: String, age: i8) -> JSON<String> {
     let person = Person {
         name: name,
         age: age,
     };
 
-    println!("ContentType: {}", content_type);
+    println!("Accept: {}", accept);
     JSON(serde_json::to_string(&person).unwrap())
 }
 
 #[error(404)]
 fn not_found(_: Error, request: &Request) -> String {
-    if !request.content_type().is_json() {
-        format!("<p>This server only supports JSON requests, not '{}'.</p>",
-                request.content_type())
+    let accepts_json = request.accept().map_or(true, |a| a.accepts(&ContentType::JSON));
+    if !accepts_json {
+        format!("<p>This server only serves JSON, not '{}'.</p>",
+                request.accept().map_or(String::new(), |a| a.to_string()))
     } else {
         format!("<p>Sorry, '{}' is not a valid path!</p>
                     <p>Try visiting /hello/&lt;name&gt;/&lt;age&gt; instead.</p>",
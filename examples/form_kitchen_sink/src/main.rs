@@ -3,7 +3,7 @@
 
 extern crate rocket;
 
-use rocket::request::{Form, FromFormValue};
+use rocket::request::{Form, FromFormValue, FormError, FormDataError};
 use rocket::response::NamedFile;
 use std::io;
 
@@ -39,11 +39,14 @@ struct FormInput {
 }
 
 #[post("/", data = "<sink>")]
-fn sink(sink: Result<Form<FormInput>, Option<String>>) -> String {
+fn sink(sink: Result<Form<FormInput>, FormDataError<FormError>>) -> String {
     match sink {
         Ok(form) => format!("{:?}", form.get()),
-        Err(Some(f)) => format!("Invalid form input: {}", f),
-        Err(None) => format!("Form input was invalid UTF8."),
+        Err(FormDataError::Parse(f, e)) => format!("Invalid form input ({}): {}", e, f),
+        Err(FormDataError::Io(_)) => format!("Form input was invalid UTF8."),
+        Err(FormDataError::TooLarge(limit)) => format!("Form input exceeds {}.", limit),
+        Err(FormDataError::Malformed(reason)) => format!("Malformed form input: {}.", reason),
+        Err(FormDataError::TooMany(limit)) => format!("Form input exceeds the {} limit.", limit),
     }
 }
 
@@ -0,0 +1,32 @@
+#![no_main]
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+#[macro_use] extern crate libfuzzer_sys;
+extern crate rocket;
+
+use std::str;
+
+use rocket::http::RawStr;
+use rocket::request::{FromForm, FromFormValue, FormItems};
+
+#[derive(FromForm)]
+struct Fields {
+    name: String,
+    age: Option<u16>,
+    accepted: bool,
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(string) = str::from_utf8(data) {
+        for (key, value) in FormItems(string) {
+            let _ = RawStr::from_str(key).url_decode();
+            let _ = String::from_form_value(value);
+            let _ = bool::from_form_value(value);
+            let _ = u64::from_form_value(value);
+        }
+
+        let _ = Fields::from_form_string(string);
+        let _ = Fields::from_form_string_lenient(string);
+    }
+});
@@ -0,0 +1,42 @@
+#![no_main]
+
+#[macro_use] extern crate libfuzzer_sys;
+extern crate rocket;
+
+use std::str::{self, FromStr};
+
+use rocket::Request;
+use rocket::http::{ContentType, Host, ETag, Range, AcceptLanguage, Authorization};
+use rocket::http::{Date, ContentLength, Allow, Digest, ContentMd5, Method};
+
+fn parse_all(value: &str) {
+    let _ = ContentType::from_str(value);
+    let _ = Host::parse(value);
+    let _ = ETag::from_str(value);
+    let _ = ETag::parse_list(value);
+    let _ = Range::from_str(value).map(|range| range.resolve(1024));
+    let _ = AcceptLanguage::from_str(value);
+    let _ = AcceptLanguage::parse_lossy(value).negotiate(&["en", "en-US", "fr"]);
+    let _ = Authorization::from_str(value);
+    let _ = Date::from_str(value);
+    let _ = ContentLength::from_str(value);
+    let _ = Allow::from_str(value);
+    let _ = Digest::from_str(value).map(|digest| digest.verify(value.as_bytes()));
+    let _ = ContentMd5::from_str(value);
+    let _ = Method::from_str(value);
+}
+
+fuzz_target!(|data: &[u8]| {
+    // Parse the input as a full request head, then as individual values.
+    if let Ok(request) = Request::parse(data) {
+        for header in request.headers().iter() {
+            parse_all(&header.value);
+        }
+
+        let _ = request.content_type();
+    }
+
+    if let Ok(value) = str::from_utf8(data) {
+        parse_all(value);
+    }
+});
@@ -0,0 +1,27 @@
+#![no_main]
+
+#[macro_use] extern crate libfuzzer_sys;
+extern crate rocket;
+
+use std::str;
+
+use rocket::http::RawStr;
+use rocket::http::uri::URI;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = URI::percent_decode(data);
+    let _ = URI::percent_decode_lossy(data);
+
+    if let Ok(string) = str::from_utf8(data) {
+        let uri = URI::new(string);
+        let _ = uri.segment_count();
+        for segment in uri.segments() {
+            let _ = RawStr::from_str(segment).url_decode();
+        }
+
+        let _ = uri.path();
+        let _ = uri.query().map(|q| RawStr::from_str(q).url_decode());
+        let _ = uri.fragment();
+        let _ = uri.to_string();
+    }
+});
@@ -0,0 +1,275 @@
+//! A bounded pool of threads for blocking work.
+//!
+//! Rocket handles requests on a fixed pool of worker threads. A handler that
+//! does CPU-heavy or otherwise blocking work, such as resizing an image or
+//! hashing a password with bcrypt, occupies its worker for as long as the work
+//! takes; a handful of such requests can leave no workers for the rest. This
+//! module offloads that work to a separate, bounded pool so that at most a
+//! fixed number of blocking tasks run at once.
+//!
+//! The pool has a fixed number of threads and a bounded queue of tasks waiting
+//! for a thread. When the queue is full, [spawn](fn.spawn.html) waits for room,
+//! applying backpressure to the handlers submitting work, while
+//! [try_spawn](fn.try_spawn.html) fails immediately so that handlers can shed
+//! load instead, for instance by responding with `503 Service Unavailable`.
+//!
+//! The global pool used by `spawn` and `try_spawn` is created on first use and
+//! sized by the `blocking_threads` and `blocking_queue` configuration
+//! parameters. Independent pools can be created with [Pool](struct.Pool.html).
+//!
+//! # Example
+//!
+//! ```rust
+//! # #![feature(plugin)]
+//! # #![plugin(rocket_codegen)]
+//! # extern crate rocket;
+//! use rocket::blocking;
+//! use rocket::http::Status;
+//!
+//! fn expensive_hash(password: String) -> String {
+//!     /* hash the password slowly... */
+//! # password
+//! }
+//!
+//! #[post("/hash/<password>")]
+//! fn hash(password: String) -> Result<String, Status> {
+//!     let task = blocking::try_spawn(move || expensive_hash(password))
+//!         .map_err(|_| Status::ServiceUnavailable)?;
+//!
+//!     task.join().map_err(|_| Status::InternalServerError)
+//! }
+//! # fn main() { }
+//! ```
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, Once, ONCE_INIT};
+use std::sync::mpsc::{self, SyncSender, Receiver, TrySendError};
+use std::thread;
+use std::fmt;
+
+use config;
+
+/// A task queued for a thread in the pool.
+type Task = Box<FnMut() + Send>;
+
+/// The error returned by [try_spawn](fn.try_spawn.html) when the pool's queue
+/// is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Busy;
+
+impl fmt::Display for Busy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the blocking pool's queue is full")
+    }
+}
+
+/// A handle to a task running in a blocking pool.
+pub struct Handle<T> {
+    receiver: Receiver<thread::Result<T>>,
+}
+
+impl<T> Handle<T> {
+    /// Waits for the task to finish and returns its result. If the task
+    /// panicked, the panic's payload is returned as an `Err`.
+    pub fn join(self) -> thread::Result<T> {
+        match self.receiver.recv() {
+            Ok(result) => result,
+            Err(_) => Err(Box::new("the blocking task was dropped") as Box<Any + Send>)
+        }
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Handle { .. }")
+    }
+}
+
+/// A bounded pool of threads for blocking work.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::blocking::Pool;
+///
+/// let pool = Pool::new(2, 8);
+/// let tasks: Vec<_> = (1..5).map(|n| pool.spawn(move || n * n)).collect();
+/// let squares: Vec<_> = tasks.into_iter().map(|t| t.join().unwrap()).collect();
+/// assert_eq!(squares, vec![1, 4, 9, 16]);
+/// ```
+pub struct Pool {
+    threads: usize,
+    sender: Mutex<SyncSender<Task>>,
+}
+
+impl Pool {
+    /// Creates a pool with `threads` threads and room for `queue` tasks to wait
+    /// for a thread. With a `queue` of `0`, tasks are only accepted when a
+    /// thread is free to take them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is `0`.
+    pub fn new(threads: usize, queue: usize) -> Pool {
+        assert!(threads > 0, "a blocking pool needs at least one thread");
+
+        let (sender, receiver) = mpsc::sync_channel::<Task>(queue);
+        let receiver = Arc::new(Mutex::new(receiver));
+        for i in 0..threads {
+            let receiver = receiver.clone();
+            thread::Builder::new()
+                .name(format!("rocket-blocking-{}", i))
+                .spawn(move || loop {
+                    let task = match receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        Err(_) => return
+                    };
+
+                    match task {
+                        Ok(mut task) => task(),
+                        Err(_) => return
+                    }
+                })
+                .expect("spawn blocking pool thread");
+        }
+
+        Pool { threads: threads, sender: Mutex::new(sender) }
+    }
+
+    /// Returns the number of threads in the pool.
+    #[inline(always)]
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Wraps `f` into a task that sends its result to the returned handle.
+    fn task<F, T>(f: F) -> (Task, Handle<T>)
+        where F: FnOnce() -> T + Send + 'static, T: Send + 'static
+    {
+        let (sender, receiver) = mpsc::channel();
+        let mut f = Some(f);
+        let task: Task = Box::new(move || {
+            if let Some(f) = f.take() {
+                let _ = sender.send(panic::catch_unwind(AssertUnwindSafe(f)));
+            }
+        });
+
+        (task, Handle { receiver: receiver })
+    }
+
+    /// Returns a sender to the pool's queue. Senders are cloned so that the
+    /// lock isn't held while waiting for room in the queue.
+    fn sender(&self) -> SyncSender<Task> {
+        self.sender.lock().expect("blocking pool sender lock").clone()
+    }
+
+    /// Runs `f` on a thread in the pool, first waiting for room in the queue
+    /// if it is full, and returns a handle to its result.
+    pub fn spawn<F, T>(&self, f: F) -> Handle<T>
+        where F: FnOnce() -> T + Send + 'static, T: Send + 'static
+    {
+        let (task, handle) = Pool::task(f);
+        // The threads only exit once the sender is dropped, so this succeeds.
+        let _ = self.sender().send(task);
+        handle
+    }
+
+    /// Runs `f` on a thread in the pool and returns a handle to its result,
+    /// unless the queue is full, in which case `Busy` is returned immediately.
+    pub fn try_spawn<F, T>(&self, f: F) -> Result<Handle<T>, Busy>
+        where F: FnOnce() -> T + Send + 'static, T: Send + 'static
+    {
+        let (task, handle) = Pool::task(f);
+        match self.sender().try_send(task) {
+            Ok(()) | Err(TrySendError::Disconnected(_)) => Ok(handle),
+            Err(TrySendError::Full(_)) => Err(Busy)
+        }
+    }
+}
+
+impl fmt::Debug for Pool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Pool").field("threads", &self.threads).finish()
+    }
+}
+
+static INIT: Once = ONCE_INIT;
+static mut POOL: Option<Pool> = None;
+
+/// Returns the global pool, creating it from the active configuration on
+/// first use.
+fn global() -> &'static Pool {
+    INIT.call_once(|| {
+        let (threads, queue) = config::active()
+            .map_or((4, 64), |c| (c.blocking_threads, c.blocking_queue));
+
+        unsafe { POOL = Some(Pool::new(threads, queue)); }
+    });
+
+    unsafe { POOL.as_ref().expect("initialized blocking pool") }
+}
+
+/// Runs `f` on a thread in the global blocking pool, first waiting for room in
+/// the pool's queue if it is full, and returns a handle to its result.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::blocking;
+///
+/// let task = blocking::spawn(|| (1..1001).sum::<u64>());
+/// assert_eq!(task.join().unwrap(), 500500);
+/// ```
+pub fn spawn<F, T>(f: F) -> Handle<T>
+    where F: FnOnce() -> T + Send + 'static, T: Send + 'static
+{
+    global().spawn(f)
+}
+
+/// Runs `f` on a thread in the global blocking pool and returns a handle to its
+/// result, unless the pool's queue is full, in which case `Busy` is returned
+/// immediately.
+pub fn try_spawn<F, T>(f: F) -> Result<Handle<T>, Busy>
+    where F: FnOnce() -> T + Send + 'static, T: Send + 'static
+{
+    global().try_spawn(f)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Barrier};
+    use std::sync::mpsc;
+    use super::{Pool, Busy};
+
+    #[test]
+    fn test_panics_are_returned() {
+        let pool = Pool::new(1, 1);
+        assert!(pool.spawn(|| panic!("oh no")).join().is_err());
+        assert_eq!(pool.spawn(|| 1 + 1).join().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_backpressure() {
+        let pool = Pool::new(1, 1);
+        let started = Arc::new(Barrier::new(2));
+        let (release, released) = mpsc::channel::<()>();
+
+        // Occupy the only thread, then fill the queue.
+        let barrier = started.clone();
+        let running = pool.spawn(move || {
+            barrier.wait();
+            released.recv().unwrap();
+            1
+        });
+
+        started.wait();
+        let queued = pool.try_spawn(|| 2).unwrap();
+        assert_eq!(pool.try_spawn(|| 3).unwrap_err(), Busy);
+
+        release.send(()).unwrap();
+        assert_eq!(running.join().unwrap(), 1);
+        assert_eq!(queued.join().unwrap(), 2);
+        assert_eq!(pool.spawn(|| 4).join().unwrap(), 4);
+    }
+}
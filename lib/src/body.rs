@@ -0,0 +1,240 @@
+//! A trait shared by request and response bodies, and adapters over it.
+//!
+//! The body of an incoming request, [Data](/rocket/data/struct.Data.html), and
+//! the body of an outgoing response,
+//! [Body](/rocket/response/enum.Body.html), both implement
+//! [MessageBody](trait.MessageBody.html). Code that transforms the bytes of a
+//! body, such as compression, checksumming, or recording, can thus be written
+//! once, as an adapter over any `MessageBody`, and applied to bodies flowing in
+//! either direction. Adapters are themselves message bodies, so they compose.
+//!
+//! An adapted request body is read via
+//! [MessageBody::open](trait.MessageBody.html#tymethod.open). An adapted
+//! response body is set on a response via
+//! [Response::set_message_body](/rocket/struct.Response.html#method.set_message_body).
+//!
+//! # Example
+//!
+//! Count the bytes of a body, whichever way it's flowing:
+//!
+//! ```rust
+//! use std::io::{Cursor, Read};
+//! use rocket::body::MessageBody;
+//! use rocket::response::Body;
+//!
+//! fn count<B: MessageBody>(body: B) -> u64 {
+//!     let mut count = 0;
+//!     {
+//!         let mut reader = body.inspect(|bytes: &[u8]| count += bytes.len() as u64).open();
+//!         let _ = reader.read_to_end(&mut vec![]);
+//!     }
+//!
+//!     count
+//! }
+//!
+//! assert_eq!(count(Body::Sized(Cursor::new("hello"), 5)), 5);
+//! ```
+
+use std::io::{self, Read};
+
+use config::ByteUnit;
+
+/// The body of an HTTP message: a request's
+/// [Data](/rocket/data/struct.Data.html) or a response's
+/// [Body](/rocket/response/enum.Body.html).
+///
+/// A body is read by consuming it via [open](#tymethod.open), which returns a
+/// reader for its bytes. Before it's opened, a body may know its length; it
+/// reports it via [length_hint](#tymethod.length_hint). The provided methods
+/// wrap a body in one of this module's adapters.
+pub trait MessageBody {
+    /// The reader returned by [open](#tymethod.open).
+    type Reader: Read;
+
+    /// Returns the number of bytes in the body, if it's known.
+    fn length_hint(&self) -> Option<u64>;
+
+    /// Consumes the body and returns a reader for its bytes.
+    fn open(self) -> Self::Reader;
+
+    /// Returns the body, failing reads with an error of kind `InvalidData`
+    /// once more than `limit` bytes have been read.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{Cursor, Read, ErrorKind};
+    /// use rocket::body::MessageBody;
+    /// use rocket::response::Body;
+    /// use rocket::config::ByteUnit;
+    ///
+    /// let body = Body::Sized(Cursor::new("hello"), 5).limit(ByteUnit::new(3));
+    /// let error = body.open().read_to_end(&mut vec![]).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::InvalidData);
+    /// ```
+    fn limit(self, limit: ByteUnit) -> Limited<Self> where Self: Sized {
+        Limited { body: self, limit: limit }
+    }
+
+    /// Returns the body, calling `f` with the bytes of each read as they're
+    /// read. Checksumming or recording a body is a matter of inspecting it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{Cursor, Read};
+    /// use rocket::body::MessageBody;
+    /// use rocket::response::Body;
+    ///
+    /// let mut recorded = vec![];
+    /// let body = Body::Sized(Cursor::new("hello"), 5);
+    /// body.inspect(|bytes: &[u8]| recorded.extend_from_slice(bytes))
+    ///     .open()
+    ///     .read_to_end(&mut vec![])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(recorded, b"hello");
+    /// ```
+    fn inspect<F: FnMut(&[u8])>(self, f: F) -> Inspect<Self, F> where Self: Sized {
+        Inspect { body: self, f: f }
+    }
+}
+
+/// A body that fails reads once more than a limit of bytes have been read.
+///
+/// Returned by [MessageBody::limit](trait.MessageBody.html#method.limit).
+#[derive(Debug)]
+pub struct Limited<B> {
+    body: B,
+    limit: ByteUnit,
+}
+
+impl<B: MessageBody> MessageBody for Limited<B> {
+    type Reader = LimitedReader<B::Reader>;
+
+    #[inline(always)]
+    fn length_hint(&self) -> Option<u64> {
+        // A body longer than the limit fails before its end: its length, as
+        // read, isn't known.
+        self.body.length_hint().and_then(|len| {
+            if len <= self.limit.as_u64() { Some(len) } else { None }
+        })
+    }
+
+    fn open(self) -> LimitedReader<B::Reader> {
+        LimitedReader { reader: self.body.open(), limit: self.limit, read: 0 }
+    }
+}
+
+/// The reader of a [Limited](struct.Limited.html) body.
+#[derive(Debug)]
+pub struct LimitedReader<R> {
+    reader: R,
+    limit: ByteUnit,
+    read: u64,
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        self.read += n as u64;
+        if self.read > self.limit.as_u64() {
+            let message = format!("data exceeds the limit of {}", self.limit);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        }
+
+        Ok(n)
+    }
+}
+
+/// A body whose bytes are passed to a function as they're read.
+///
+/// Returned by [MessageBody::inspect](trait.MessageBody.html#method.inspect).
+pub struct Inspect<B, F> {
+    body: B,
+    f: F,
+}
+
+impl<B: MessageBody, F: FnMut(&[u8])> MessageBody for Inspect<B, F> {
+    type Reader = InspectReader<B::Reader, F>;
+
+    #[inline(always)]
+    fn length_hint(&self) -> Option<u64> {
+        self.body.length_hint()
+    }
+
+    fn open(self) -> InspectReader<B::Reader, F> {
+        InspectReader { reader: self.body.open(), f: self.f }
+    }
+}
+
+/// The reader of an [Inspect](struct.Inspect.html) body.
+pub struct InspectReader<R, F> {
+    reader: R,
+    f: F,
+}
+
+impl<R: Read, F: FnMut(&[u8])> Read for InspectReader<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        (self.f)(&buf[..n]);
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Cursor, Read, ErrorKind};
+
+    use super::MessageBody;
+    use data::Data;
+    use response::{Body, Response};
+    use config::ByteUnit;
+
+    fn read<B: MessageBody>(body: B) -> Result<Vec<u8>, ErrorKind> {
+        let mut bytes = vec![];
+        body.open().read_to_end(&mut bytes).map(|_| bytes).map_err(|e| e.kind())
+    }
+
+    #[test]
+    fn length_hints() {
+        assert_eq!(Data::new(b"hello".to_vec()).length_hint(), Some(5));
+        assert_eq!(Body::Sized(Cursor::new("hello"), 5).length_hint(), Some(5));
+        assert_eq!(Body::Chunked(Cursor::new("hello"), 4096).length_hint(), None);
+
+        let limited = Body::Sized(Cursor::new("hello"), 5).limit(ByteUnit::new(5));
+        assert_eq!(limited.length_hint(), Some(5));
+
+        let limited = Body::Sized(Cursor::new("hello"), 5).limit(ByteUnit::new(1));
+        assert_eq!(limited.length_hint(), None);
+    }
+
+    #[test]
+    fn adapters_apply_to_requests_and_responses() {
+        let limit = ByteUnit::new(5);
+        assert_eq!(read(Data::new(b"hello".to_vec()).limit(limit)), Ok(b"hello".to_vec()));
+        assert_eq!(read(Data::new(b"hello!".to_vec()).limit(limit)), Err(ErrorKind::InvalidData));
+
+        let body = Body::Chunked(Cursor::new("hello!"), 4096);
+        assert_eq!(read(body.limit(limit)), Err(ErrorKind::InvalidData));
+
+        let mut count = 0;
+        let data = Data::new(b"hello".to_vec());
+        assert_eq!(read(data.inspect(|b: &[u8]| count += b.len())), Ok(b"hello".to_vec()));
+        assert_eq!(count, 5);
+    }
+
+    #[test]
+    fn adapted_bodies_can_be_responded_with() {
+        let mut recorded = vec![];
+        {
+            let mut response = Response::new();
+            let body = Body::Sized(Cursor::new("hello"), 5);
+            response.set_message_body(body.inspect(|b: &[u8]| recorded.extend_from_slice(b)));
+            assert!(response.body().map_or(false, |b| b.is_sized()));
+            assert_eq!(response.body().and_then(|b| b.into_string()), Some("hello".into()));
+        }
+
+        assert_eq!(recorded, b"hello");
+    }
+}
@@ -0,0 +1,371 @@
+//! Canonical URLs for routes.
+//!
+//! A route can declare the canonical form of the URLs it's reached at via the
+//! `canonical` route attribute parameter. Requests for a non-canonical variant
+//! of such a URL are answered with a permanent redirect to the canonical URL
+//! before being routed, so that every resource is reached at exactly one URL.
+//! The parameter's value is a comma-separated list of options:
+//!
+//!   * **lowercase**
+//!
+//!     The path is lowercase. Requests for paths with uppercase letters are
+//!     redirected to the lowercase path, even if they don't otherwise match
+//!     the route. Percent-encoded characters are left as they are.
+//!
+//!   * **no_trailing_slash**
+//!
+//!     The path has no trailing slash, so `/about/` is redirected to `/about`.
+//!     The root path, `/`, is always canonical.
+//!
+//!   * **host = _name_[:_port_]**
+//!
+//!     Requests must be addressed to the host _name_ and, if it's given, the
+//!     _port_. Requests addressed to any other host, via the `Host` header,
+//!     are redirected to the same path on the canonical host.
+//!
+//!   * **link**
+//!
+//!     Successful responses from the route carry a `Link` header pointing at
+//!     the canonical URL, as in `Link: </about>; rel="canonical"`. The URL is
+//!     absolute if the route has a canonical host.
+//!
+//! Regardless of the options, a path that starts with more than one slash is
+//! redirected to the same path with a single leading slash. Such paths match
+//! routes as if they had one, but a `Location` of `//evil.com` would send the
+//! client to another host.
+//!
+//! `GET` and `HEAD` requests are redirected with `301 Moved Permanently` and
+//! other requests with `308 Permanent Redirect`, which preserves the method
+//! and body. The query string is kept as it is.
+//!
+//! A [Canonical](struct.Canonical.html) can also be set on a manually created
+//! [Route](/rocket/struct.Route.html) via its `canonical` field.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #![feature(plugin)]
+//! # #![plugin(rocket_codegen)]
+//! # extern crate rocket;
+//! #
+//! // `/About/` and `/ABOUT` are redirected to `/about`.
+//! #[get("/about", canonical = "lowercase, no_trailing_slash, link")]
+//! fn about() -> &'static str {
+//!     "About us."
+//! }
+//!
+//! // Requests to other hosts are redirected to `http://www.rocket.rs/`.
+//! #[get("/", canonical = "host = www.rocket.rs")]
+//! fn index() -> &'static str {
+//!     "Welcome!"
+//! }
+//! # fn main() { }
+//! ```
+
+use std::ascii::AsciiExt;
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use request::Request;
+use http::{Header, Host};
+
+/// The canonical form of a route's URLs. See the
+/// [canonical](/rocket/canonical/index.html) module for more.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Canonical {
+    lowercase: bool,
+    no_trailing_slash: bool,
+    host: Option<Host<'static>>,
+    link: bool,
+}
+
+impl Canonical {
+    /// Returns a `Canonical` that enforces nothing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::canonical::Canonical;
+    ///
+    /// let canonical = Canonical::new().lowercase().no_trailing_slash();
+    /// assert_eq!(canonical.to_string(), "lowercase, no_trailing_slash");
+    /// ```
+    #[inline]
+    pub fn new() -> Canonical {
+        Canonical { lowercase: false, no_trailing_slash: false, host: None, link: false }
+    }
+
+    /// Requires the path to be lowercase.
+    #[inline]
+    pub fn lowercase(mut self) -> Self {
+        self.lowercase = true;
+        self
+    }
+
+    /// Requires the path to have no trailing slash.
+    #[inline]
+    pub fn no_trailing_slash(mut self) -> Self {
+        self.no_trailing_slash = true;
+        self
+    }
+
+    /// Requires requests to be addressed to `host`. If `host` has no port,
+    /// requests to any port of the host are canonical.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::canonical::Canonical;
+    /// use rocket::http::Host;
+    ///
+    /// let canonical = Canonical::new().host(Host::new("www.rocket.rs", None));
+    /// assert_eq!(canonical.to_string(), "host = www.rocket.rs");
+    /// ```
+    #[inline]
+    pub fn host(mut self, host: Host<'static>) -> Self {
+        self.host = Some(host);
+        self
+    }
+
+    /// Adds a `Link` header pointing at the canonical URL to successful
+    /// responses.
+    #[inline]
+    pub fn link(mut self) -> Self {
+        self.link = true;
+        self
+    }
+
+    /// Returns the canonical form of `path`. Leading slashes are always
+    /// collapsed into one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::canonical::Canonical;
+    ///
+    /// let canonical = Canonical::new().lowercase().no_trailing_slash();
+    /// assert_eq!(canonical.canonical_path("/About/Us%2F/"), "/about/us%2F");
+    /// assert_eq!(canonical.canonical_path("//Evil.com/"), "/evil.com");
+    /// assert_eq!(canonical.canonical_path("/"), "/");
+    /// ```
+    pub fn canonical_path<'p>(&self, mut path: &'p str) -> Cow<'p, str> {
+        if path.starts_with("//") {
+            path = &path[(path.len() - path.trim_left_matches('/').len() - 1)..];
+        }
+
+        if self.no_trailing_slash && path.len() > 1 && path.ends_with('/') {
+            path = match path.trim_right_matches('/') {
+                "" => "/",
+                trimmed => trimmed
+            };
+        }
+
+        if self.lowercase && path.bytes().any(|b| b >= b'A' && b <= b'Z') {
+            return Cow::Owned(lowercase_unescaped(path));
+        }
+
+        Cow::Borrowed(path)
+    }
+
+    /// Returns the location to redirect `request` to if it isn't for a
+    /// canonical URL, and `None` if it is. This should only be used internally
+    /// by `Rocket`.
+    #[doc(hidden)]
+    pub fn redirect_location(&self, request: &Request) -> Option<String> {
+        let uri = request.uri();
+        let path = self.canonical_path(uri.path());
+        let wrong_host = match (self.host.as_ref(), request.host()) {
+            (Some(canonical), Some(host)) => !host.matches(canonical),
+            _ => false
+        };
+
+        if !wrong_host && path == uri.path() {
+            return None;
+        }
+
+        // Browsers treat `/\` like `//`, so a relative location starting with
+        // it would also point at another host.
+        if !wrong_host && path.starts_with("/\\") {
+            warn_!("Refusing to redirect to '{}': it names another host.", path);
+            return None;
+        }
+
+        let query = uri.query().map(|q| format!("?{}", q)).unwrap_or(String::new());
+        Some(match wrong_host {
+            true => format!("{}{}", self.url(request, &path), query),
+            false => format!("{}{}", path, query)
+        })
+    }
+
+    /// Returns the `Link` header to add to a successful response to `request`,
+    /// if `self` asks for one. This should only be used internally by
+    /// `Rocket`.
+    #[doc(hidden)]
+    pub fn link_header(&self, request: &Request) -> Option<Header<'static>> {
+        if !self.link {
+            return None;
+        }
+
+        let url = self.url(request, &self.canonical_path(request.uri().path()));
+        Some(Header::new("Link", format!("<{}>; rel=\"canonical\"", url)))
+    }
+
+    /// Returns the canonical URL of `path`: absolute, with the scheme that
+    /// `request` was made over, if there's a canonical host, and `path`
+    /// otherwise.
+    fn url(&self, request: &Request, path: &str) -> String {
+        match self.host {
+            Some(ref host) => format!("{}://{}{}", request.scheme(), host, path),
+            None => path.to_string()
+        }
+    }
+}
+
+/// Returns `path` with its ASCII letters lowercased, except for those in
+/// percent-encoded characters.
+fn lowercase_unescaped(path: &str) -> String {
+    let mut lowered = String::with_capacity(path.len());
+    let mut escaped = 0;
+    for c in path.chars() {
+        if escaped > 0 {
+            escaped -= 1;
+            lowered.push(c);
+        } else {
+            if c == '%' {
+                escaped = 2;
+            }
+
+            lowered.push(c.to_ascii_lowercase());
+        }
+    }
+
+    lowered
+}
+
+impl Default for Canonical {
+    #[inline]
+    fn default() -> Canonical {
+        Canonical::new()
+    }
+}
+
+impl FromStr for Canonical {
+    type Err = String;
+
+    /// Parses a comma-separated list of the options described in the
+    /// [canonical](/rocket/canonical/index.html) module.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::canonical::Canonical;
+    /// use rocket::http::Host;
+    ///
+    /// let canonical: Canonical = "lowercase, host = rocket.rs:8000".parse().unwrap();
+    /// let host = Host::new("rocket.rs", Some(8000));
+    /// assert_eq!(canonical, Canonical::new().lowercase().host(host));
+    ///
+    /// assert!("uppercase".parse::<Canonical>().is_err());
+    /// ```
+    fn from_str(string: &str) -> Result<Canonical, String> {
+        let mut canonical = Canonical::new();
+        for option in string.split(',').map(|o| o.trim()).filter(|o| !o.is_empty()) {
+            let mut parts = option.splitn(2, '=').map(|p| p.trim());
+            match (parts.next(), parts.next()) {
+                (Some("lowercase"), None) => canonical.lowercase = true,
+                (Some("no_trailing_slash"), None) => canonical.no_trailing_slash = true,
+                (Some("link"), None) => canonical.link = true,
+                (Some("host"), Some(host)) => match Host::parse(host) {
+                    Ok(host) => canonical.host = Some(host.into_owned()),
+                    Err(e) => return Err(format!("invalid host '{}': {}", host, e))
+                },
+                _ => return Err(format!("unknown option '{}'", option))
+            }
+        }
+
+        Ok(canonical)
+    }
+}
+
+impl fmt::Display for Canonical {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut options = vec![];
+        if self.lowercase {
+            options.push("lowercase".to_string());
+        }
+
+        if self.no_trailing_slash {
+            options.push("no_trailing_slash".to_string());
+        }
+
+        if let Some(ref host) = self.host {
+            options.push(format!("host = {}", host));
+        }
+
+        if self.link {
+            options.push("link".to_string());
+        }
+
+        write!(f, "{}", options.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Canonical;
+    use request::Request;
+    use http::{Header, Method};
+
+    fn location(canonical: &str, uri: &str, host: Option<&str>) -> Option<String> {
+        let mut request = Request::new(Method::Get, uri.to_string());
+        if let Some(host) = host {
+            request.add_header(Header::new("Host", host.to_string()));
+        }
+
+        canonical.parse::<Canonical>().unwrap().redirect_location(&request)
+    }
+
+    #[test]
+    fn test_canonical_paths() {
+        let canonical = Canonical::new().lowercase().no_trailing_slash();
+        assert_eq!(canonical.canonical_path("/a/b"), "/a/b");
+        assert_eq!(canonical.canonical_path("/A/B//"), "/a/b");
+        assert_eq!(canonical.canonical_path("///"), "/");
+        assert_eq!(canonical.canonical_path("//evil.com"), "/evil.com");
+        assert_eq!(Canonical::new().canonical_path("///evil.com/"), "/evil.com/");
+        assert_eq!(canonical.canonical_path("/%C3%A9T%c3%a9"), "/%C3%A9t%c3%a9");
+        assert_eq!(Canonical::new().canonical_path("/A/"), "/A/");
+    }
+
+    #[test]
+    fn test_redirect_locations() {
+        assert_eq!(location("lowercase", "/about", None), None);
+        assert_eq!(location("lowercase", "/About?x=Y", None), Some("/about?x=Y".into()));
+        assert_eq!(location("no_trailing_slash", "/about/", None), Some("/about".into()));
+        assert_eq!(location("host = rocket.rs", "/a", Some("Rocket.rs:80")), None);
+        assert_eq!(location("host = rocket.rs", "/a", None), None);
+        assert_eq!(location("host = rocket.rs:8000", "/a?b", Some("rocket.rs")),
+                   Some("http://rocket.rs:8000/a?b".into()));
+        assert_eq!(location("lowercase, host = rocket.rs", "/A", Some("www.rocket.rs")),
+                   Some("http://rocket.rs/a".into()));
+    }
+
+    #[test]
+    fn test_no_open_redirects() {
+        assert_eq!(location("no_trailing_slash", "//evil.com/", None), Some("/evil.com".into()));
+        assert_eq!(location("lowercase", "//EVIL.com/", None), Some("/evil.com/".into()));
+        assert_eq!(location("lowercase", "/\\EVIL.com/", None), None);
+        assert_eq!(location("link", "//evil.com", None), Some("/evil.com".into()));
+    }
+
+    #[test]
+    fn test_parse_display() {
+        let canonical: Canonical = " link,lowercase , host=rocket.rs ".parse().unwrap();
+        assert_eq!(canonical.to_string(), "lowercase, host = rocket.rs, link");
+        assert_eq!("".parse::<Canonical>(), Ok(Canonical::new()));
+        assert!("host".parse::<Canonical>().is_err());
+        assert!("host = bad host".parse::<Canonical>().is_err());
+        assert!("lowercase = yes".parse::<Canonical>().is_err());
+    }
+}
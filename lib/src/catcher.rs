@@ -26,9 +26,9 @@ use term_painter::Color::*;
 /// Rocket has many built-in, pre-registered default catchers. In particular,
 /// Rocket has catchers for all of the following status codes: 400, 401, 402,
 /// 403, 404, 405, 406, 407, 408, 409, 410, 411, 412, 413, 414, 415, 416, 417,
-/// 418, 421, 426, 428, 429, 431, 451, 500, 501, 503, and 510. As such, catchers
-/// only need to be registered if an error needs to be handled in a custom
-/// fashion.
+/// 418, 421, 422, 426, 428, 429, 431, 451, 500, 501, 503, and 510. As such,
+/// catchers only need to be registered if an error needs to be handled in a
+/// custom fashion.
 ///
 /// # Code Generation
 ///
@@ -219,6 +219,8 @@ pub mod defaults {
                 teapot.", handle_418,
             421, "Misdirected Request", "The server cannot produce a response for this
                 request.", handle_421,
+            422, "Unprocessable Entity", "The request was well-formed but was unable to
+                be followed due to semantic errors.", handle_422,
             426, "Upgrade Required", "Switching to the protocol in the Upgrade header
                 field is required.", handle_426,
             428, "Precondition Required", "The server requires the request to be
@@ -1,4 +1,6 @@
-use handler::{Handler, ErrorHandler};
+use std::any::TypeId;
+
+use handler::{Handler, ErrorHandler, ParamCheck};
 use http::{Method, ContentType};
 
 pub struct StaticRouteInfo {
@@ -7,6 +9,11 @@ pub struct StaticRouteInfo {
     pub format: Option<ContentType>,
     pub handler: Handler,
     pub rank: Option<isize>,
+    pub feature: Option<&'static str>,
+    pub canonical: Option<&'static str>,
+    pub state: fn() -> Vec<(TypeId, &'static str)>,
+    pub params: ParamCheck,
+    pub param_docs: &'static [(&'static str, &'static str)],
 }
 
 pub struct StaticCatchInfo {
@@ -1,14 +1,19 @@
 use std::collections::HashMap;
-use std::net::ToSocketAddrs;
+use std::net::{IpAddr, ToSocketAddrs};
 use std::path::Path;
 use std::sync::RwLock;
+use std::time::{Duration, SystemTime};
+use std::io::Read;
+use std::fs::{self, File};
 use std::fmt;
 
 use config::Environment::*;
-use config::{self, Environment, ConfigError};
+use config::{self, Environment, ConfigError, RocketConfig, RedirectRule, ByteUnit, Limits};
+use config::units;
 
 use logger::LoggingLevel;
-use toml::Value;
+use http::{Host, Status};
+use toml::{Value, Table};
 
 /// The core configuration structure.
 pub struct Config {
@@ -20,6 +25,23 @@ pub struct Config {
     pub log_level: LoggingLevel,
     /// The environment that this configuration corresponds to.
     pub env: Environment,
+    /// The hosts, with optional ports, that requests may be addressed to. If
+    /// empty, requests to any host are accepted.
+    pub hosts: Vec<String>,
+    /// The addresses of the proxies whose forwarding headers are trusted.
+    pub trusted_proxies: Vec<IpAddr>,
+    /// The redirects applied before requests are routed.
+    pub redirects: Vec<RedirectRule>,
+    /// The limits on the sizes of request bodies read by data guards.
+    pub limits: Limits,
+    /// How long to wait for more of a request's body before giving up, or
+    /// `None` to wait indefinitely.
+    pub read_timeout: Option<Duration>,
+    /// The number of threads in the pool for blocking work.
+    pub blocking_threads: usize,
+    /// The number of blocking tasks that may wait for a thread before spawning
+    /// more blocks.
+    pub blocking_queue: usize,
     session_key: RwLock<Option<String>>,
     extras: HashMap<String, Value>,
     filepath: String,
@@ -52,6 +74,13 @@ impl Config {
                     log_level: LoggingLevel::Normal,
                     session_key: RwLock::new(None),
                     extras: HashMap::new(),
+                    hosts: vec![],
+                    trusted_proxies: vec![],
+                    redirects: vec![],
+                    limits: Limits::default(),
+                    read_timeout: Some(Duration::from_secs(5)),
+                    blocking_threads: 4,
+                    blocking_queue: 64,
                     env: env,
                     filepath: filepath.to_string(),
                 }
@@ -63,6 +92,13 @@ impl Config {
                     log_level: LoggingLevel::Normal,
                     session_key: RwLock::new(None),
                     extras: HashMap::new(),
+                    hosts: vec![],
+                    trusted_proxies: vec![],
+                    redirects: vec![],
+                    limits: Limits::default(),
+                    read_timeout: Some(Duration::from_secs(5)),
+                    blocking_threads: 4,
+                    blocking_queue: 64,
                     env: env,
                     filepath: filepath.to_string(),
                 }
@@ -74,6 +110,13 @@ impl Config {
                     log_level: LoggingLevel::Critical,
                     session_key: RwLock::new(None),
                     extras: HashMap::new(),
+                    hosts: vec![],
+                    trusted_proxies: vec![],
+                    redirects: vec![],
+                    limits: Limits::default(),
+                    read_timeout: Some(Duration::from_secs(5)),
+                    blocking_threads: 4,
+                    blocking_queue: 64,
                     env: env,
                     filepath: filepath.to_string(),
                 }
@@ -89,10 +132,36 @@ impl Config {
         ConfigError::BadType(id, expect, val.type_str(), self.filepath.clone())
     }
 
+    /// Parses `val`, the value of the entry `name`, as a byte size: either an
+    /// integer number of bytes or a string such as `"5MiB"`.
+    fn parse_bytes(&self, name: &str, val: &Value) -> config::Result<ByteUnit> {
+        let expect = "a byte size, such as 4096, \"512kb\", or \"5MiB\"";
+        match *val {
+            Value::Integer(n) if n >= 0 => Ok(ByteUnit::new(n as u64)),
+            Value::String(ref s) => s.parse().map_err(|_| self.bad_type(name, val, expect)),
+            _ => Err(self.bad_type(name, val, expect))
+        }
+    }
+
+    /// Parses `val`, the value of the entry `name`, as a duration: either an
+    /// integer number of seconds or a string such as `"30s"` or `"2m"`.
+    fn parse_duration(&self, name: &str, val: &Value) -> config::Result<Duration> {
+        let expect = "a duration, such as 30, \"500ms\", \"30s\", or \"2m\"";
+        match *val {
+            Value::Integer(n) if n >= 0 => Ok(Duration::from_secs(n as u64)),
+            Value::String(ref s) => {
+                units::parse_duration(s).map_err(|_| self.bad_type(name, val, expect))
+            }
+            _ => Err(self.bad_type(name, val, expect))
+        }
+    }
+
     /// Sets the configuration `val` for the `name` entry. If the `name` is one
-    /// of "address", "port", "session_key", or "log" (the "default" values),
-    /// the appropriate value in the `self` Config structure is set. Otherwise,
-    /// the value is stored as an `extra`.
+    /// of "address", "port", "session_key", "log", "hosts", "trusted_proxies",
+    /// "redirects", "limits", "read_timeout", "blocking_threads", or
+    /// "blocking_queue" (the "default" values), the
+    /// appropriate value in the `self` Config structure is set. Otherwise, the
+    /// value is stored as an `extra`.
     ///
     /// For each of the default values, the following `Value` variant is
     /// expected. If a different variant is supplied, a `BadType` `Err` is
@@ -102,6 +171,13 @@ impl Config {
     ///   * **port**: Integer
     ///   * **session_key**: String (192-bit base64)
     ///   * **log**: String
+    ///   * **hosts**: Array of Strings
+    ///   * **trusted_proxies**: Array of Strings (IP addresses)
+    ///   * **redirects**: Table of Strings or Tables (redirect rules)
+    ///   * **limits**: Table of Integers or Strings (byte sizes)
+    ///   * **read_timeout**: Integer or String (duration)
+    ///   * **blocking_threads**: Integer (positive)
+    ///   * **blocking_queue**: Integer
     ///
     pub fn set(&mut self, name: &str, val: &Value) -> config::Result<()> {
         if name == "address" {
@@ -134,6 +210,88 @@ impl Config {
                 Err(_) => return Err(self.bad_type(name, val,
                                 "log level ('normal', 'critical', 'debug')"))
             };
+        } else if name == "hosts" {
+            let array = parse!(self, name, val, as_slice, "an array of hosts")?;
+            let mut hosts = Vec::with_capacity(array.len());
+            for value in array {
+                match value.as_str().map(Host::parse) {
+                    Some(Ok(host)) => hosts.push(host.to_string()),
+                    _ => return Err(self.bad_type(name, val, "an array of hosts"))
+                }
+            }
+
+            self.hosts = hosts;
+        } else if name == "trusted_proxies" {
+            let expect = "an array of IP addresses";
+            let array = parse!(self, name, val, as_slice, expect)?;
+            let mut proxies = Vec::with_capacity(array.len());
+            for value in array {
+                match value.as_str().map(|s| s.parse::<IpAddr>()) {
+                    Some(Ok(address)) => proxies.push(address),
+                    _ => return Err(self.bad_type(name, val, expect))
+                }
+            }
+
+            self.trusted_proxies = proxies;
+        } else if name == "redirects" {
+            let expect = "a table of redirects";
+            let table = parse!(self, name, val, as_table, expect)?;
+            let mut redirects = Vec::with_capacity(table.len());
+            for (from, value) in table {
+                let (to, code) = match *value {
+                    Value::String(ref to) => (Some(to.as_str()), Some(301)),
+                    Value::Table(ref rule) => {
+                        let to = rule.get("to").and_then(|v| v.as_str());
+                        let code = match rule.get("status") {
+                            Some(status) => status.as_integer(),
+                            None => Some(301)
+                        };
+
+                        (to, code)
+                    }
+                    _ => (None, None)
+                };
+
+                let status = code.and_then(|code| Status::from_code(code as u16));
+                match (to, status) {
+                    (Some(to), Some(status)) => match RedirectRule::new(from.as_str(), to, status) {
+                        Ok(rule) => redirects.push(rule),
+                        Err(_) => return Err(self.bad_type(name, val, expect))
+                    },
+                    _ => return Err(self.bad_type(name, val, expect))
+                }
+            }
+
+            self.redirects = redirects;
+        } else if name == "limits" {
+            let table = parse!(self, name, val, as_table, "a table of byte sizes")?;
+            let mut limits = Limits::default();
+            for (kind, value) in table {
+                let limit = self.parse_bytes(&format!("{}.{}", name, kind), value)?;
+                limits = limits.limit(kind.as_str(), limit);
+            }
+
+            self.limits = limits;
+        } else if name == "read_timeout" {
+            let timeout = self.parse_duration(name, val)?;
+            self.read_timeout = match timeout == Duration::from_secs(0) {
+                true => None,
+                false => Some(timeout)
+            };
+        } else if name == "blocking_threads" {
+            let threads = parse!(self, name, val, as_integer, "an integer")?;
+            if threads < 1 {
+                return Err(self.bad_type(name, val, "a positive integer"));
+            }
+
+            self.blocking_threads = threads as usize;
+        } else if name == "blocking_queue" {
+            let queue = parse!(self, name, val, as_integer, "an integer")?;
+            if queue < 0 {
+                return Err(self.bad_type(name, val, "an unsigned integer"));
+            }
+
+            self.blocking_queue = queue as usize;
         } else {
             self.extras.insert(name.into(), val.clone());
         }
@@ -212,6 +370,111 @@ impl Config {
         parse!(self, name, value, as_float, "a float")
     }
 
+    /// Attempts to retrieve the extra named `name` as a byte size. The extra
+    /// may be an integer number of bytes or a string with a unit, as described
+    /// in [ByteUnit](struct.ByteUnit.html). If an extra with that name doesn't
+    /// exist, returns an `Err` of `NotFound`. If an extra with that name does
+    /// exist but is not a byte size, returns a `BadType` error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment, Value};
+    ///
+    /// let config = Config::default_for(Environment::Staging, "/custom").unwrap()
+    ///     .extra("avatar_limit", &Value::String("5MiB".into()));
+    ///
+    /// assert_eq!(config.get_bytes("avatar_limit").unwrap().as_u64(), 5 * 1024 * 1024);
+    /// ```
+    pub fn get_bytes(&self, name: &str) -> config::Result<ByteUnit> {
+        let value = self.extras.get(name).ok_or_else(|| ConfigError::NotFound)?;
+        self.parse_bytes(name, value)
+    }
+
+    /// Attempts to retrieve the extra named `name` as a duration. The extra may
+    /// be an integer number of seconds or a string with one of the units `ms`,
+    /// `s`, `m`, or `h`, such as `"30s"` or `"2m"`. If an extra with that name
+    /// doesn't exist, returns an `Err` of `NotFound`. If an extra with that
+    /// name does exist but is not a duration, returns a `BadType` error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket::config::{Config, Environment, Value};
+    ///
+    /// let config = Config::default_for(Environment::Staging, "/custom").unwrap()
+    ///     .extra("db_timeout", &Value::String("2m".into()));
+    ///
+    /// assert_eq!(config.get_duration("db_timeout").unwrap(), Duration::from_secs(120));
+    /// ```
+    pub fn get_duration(&self, name: &str) -> config::Result<Duration> {
+        let value = self.extras.get(name).ok_or_else(|| ConfigError::NotFound)?;
+        self.parse_duration(name, value)
+    }
+
+    /// Attempts to retrieve the extra named `name` as a table. If an extra
+    /// with that name doesn't exist, returns an `Err` of `NotFound`. If an
+    /// extra with that name does exist but is not a table, returns a `BadType`
+    /// error.
+    pub fn get_table<'a>(&'a self, name: &str) -> config::Result<&'a Table> {
+        let value = self.extras.get(name).ok_or_else(|| ConfigError::NotFound)?;
+        parse!(self, name, value, as_table, "a table")
+    }
+
+    /// Re-reads the configuration file that `self` was read from and updates
+    /// the parameters named in `names` to their current values for `self`'s
+    /// environment. Returns the names of the parameters whose values changed.
+    ///
+    /// Only the "log" parameter and extras can be reloaded; the names of other
+    /// parameters are ignored.
+    #[doc(hidden)]
+    pub fn reload(&mut self, names: &[String]) -> config::Result<Vec<String>> {
+        let mut contents = String::new();
+        File::open(&self.filepath)
+            .and_then(|mut file| file.read_to_string(&mut contents))
+            .map_err(|_| ConfigError::IOError)?;
+
+        let rocket_config = RocketConfig::parse(contents, &self.filepath)?;
+        let new = rocket_config.get(self.env);
+
+        let mut changed = vec![];
+        for name in names {
+            let did_change = match name.as_str() {
+                "log" => replace_if_changed(&mut self.log_level, &new.log_level),
+                "address" | "port" | "session_key" | "hosts" | "trusted_proxies"
+                    | "redirects" | "limits" | "read_timeout" | "blocking_threads"
+                    | "blocking_queue" => false,
+                _ => {
+                    let new_value = new.extras.get(name);
+                    if self.extras.get(name) == new_value {
+                        false
+                    } else {
+                        match new_value {
+                            Some(value) => self.extras.insert(name.clone(), value.clone()),
+                            None => self.extras.remove(name),
+                        };
+
+                        true
+                    }
+                }
+            };
+
+            if did_change {
+                changed.push(name.clone());
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// Returns the last modification time of the configuration file that
+    /// `self` was read from, if it can be determined.
+    #[doc(hidden)]
+    pub fn modified(&self) -> Option<SystemTime> {
+        fs::metadata(&self.filepath).and_then(|m| m.modified()).ok()
+    }
+
     /// Returns the path at which the configuration file for `self` is stored.
     /// For instance, if the configuration file is at `/tmp/Rocket.toml`, the
     /// path `/tmp` is returned.
@@ -250,6 +513,60 @@ impl Config {
         self
     }
 
+    /// Sets the `hosts` in `self` to `var` and returns the structure.
+    #[inline(always)]
+    pub fn hosts(mut self, var: Vec<String>) -> Self {
+        self.hosts = var;
+        self
+    }
+
+    /// Sets the `trusted_proxies` in `self` to `var` and returns the structure.
+    #[inline(always)]
+    pub fn trusted_proxies(mut self, var: Vec<IpAddr>) -> Self {
+        self.trusted_proxies = var;
+        self
+    }
+
+    /// Sets the `redirects` in `self` to `var` and returns the structure.
+    #[inline(always)]
+    pub fn redirects(mut self, var: Vec<RedirectRule>) -> Self {
+        self.redirects = var;
+        self
+    }
+
+    /// Sets the `limits` in `self` to `var` and returns the structure.
+    #[inline(always)]
+    pub fn limits(mut self, var: Limits) -> Self {
+        self.limits = var;
+        self
+    }
+
+    /// Sets the `read_timeout` in `self` to `var` and returns the structure.
+    /// A timeout of zero means no timeout.
+    #[inline(always)]
+    pub fn read_timeout(mut self, var: Duration) -> Self {
+        self.read_timeout = match var == Duration::from_secs(0) {
+            true => None,
+            false => Some(var)
+        };
+
+        self
+    }
+
+    /// Sets the `blocking_threads` in `self` to `var` and returns the structure.
+    #[inline(always)]
+    pub fn blocking_threads(mut self, var: usize) -> Self {
+        self.blocking_threads = var;
+        self
+    }
+
+    /// Sets the `blocking_queue` in `self` to `var` and returns the structure.
+    #[inline(always)]
+    pub fn blocking_queue(mut self, var: usize) -> Self {
+        self.blocking_queue = var;
+        self
+    }
+
     /// Sets the `env` in `self` to `var` and returns the structure.
     #[inline(always)]
     pub fn env(mut self, var: Environment) -> Self {
@@ -266,6 +583,16 @@ impl Config {
     }
 }
 
+/// Sets `old` to `new` if they differ, returning `true` if they did.
+fn replace_if_changed<T: PartialEq + Clone>(old: &mut T, new: &T) -> bool {
+    if *old == *new {
+        return false;
+    }
+
+    *old = new.clone();
+    true
+}
+
 impl fmt::Debug for Config {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Config[{}] {{ address: {}, port: {}, log_level: {:?} }}",
@@ -273,6 +600,28 @@ impl fmt::Debug for Config {
     }
 }
 
+impl Clone for Config {
+    fn clone(&self) -> Config {
+        let session_key = self.session_key.read().expect("couldn't lock session key");
+        Config {
+            address: self.address.clone(),
+            port: self.port,
+            log_level: self.log_level,
+            env: self.env,
+            hosts: self.hosts.clone(),
+            trusted_proxies: self.trusted_proxies.clone(),
+            redirects: self.redirects.clone(),
+            limits: self.limits.clone(),
+            read_timeout: self.read_timeout,
+            blocking_threads: self.blocking_threads,
+            blocking_queue: self.blocking_queue,
+            session_key: RwLock::new(session_key.clone()),
+            extras: self.extras.clone(),
+            filepath: self.filepath.clone(),
+        }
+    }
+}
+
 impl PartialEq for Config {
     fn eq(&self, other: &Config) -> bool {
         &*self.session_key.read().unwrap() == &*other.session_key.read().unwrap()
@@ -280,6 +629,13 @@ impl PartialEq for Config {
             && self.port == other.port
             && self.log_level == other.log_level
             && self.env == other.env
+            && self.hosts == other.hosts
+            && self.trusted_proxies == other.trusted_proxies
+            && self.redirects == other.redirects
+            && self.limits == other.limits
+            && self.read_timeout == other.read_timeout
+            && self.blocking_threads == other.blocking_threads
+            && self.blocking_queue == other.blocking_queue
             && self.extras == other.extras
             && self.filepath == other.filepath
     }
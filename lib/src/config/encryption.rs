@@ -0,0 +1,253 @@
+use std::sync::{Mutex, Once, ONCE_INIT};
+use std::sync::atomic::{AtomicBool, ATOMIC_BOOL_INIT, Ordering};
+
+use super::{ConfigError, Result};
+use toml::{Table, Value};
+
+/// The prefix that marks a string value in a configuration file as encrypted.
+pub const ENCRYPTED_PREFIX: &'static str = "enc:";
+
+/// The environment variable from which the built-in decryptor reads its key.
+pub const KEY_ENV: &'static str = "ROCKET_CONFIG_KEY";
+
+/// A decryptor of encrypted configuration values.
+///
+/// String values in the configuration file that begin with `enc:` are
+/// encrypted. When the configuration file is read, the remainder of each such
+/// value is passed to the active decryptor, and the decrypted value is used in
+/// its place. A decryptor can fetch keys from anywhere, such as from a key
+/// management service, and is installed via
+/// [set_decryptor](fn.set_decryptor.html).
+///
+/// `Decryptor` is implemented for all `Fn(&str) -> Result<String, String>`
+/// closures that are `Send + Sync`.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::config::{self, Decryptor};
+///
+/// struct Kms;
+///
+/// impl Decryptor for Kms {
+///     fn decrypt(&self, ciphertext: &str) -> Result<String, String> {
+///         // Ask the key management service to decrypt `ciphertext`.
+///         # Ok(ciphertext.to_string())
+///     }
+/// }
+///
+/// config::set_decryptor(Kms);
+/// ```
+pub trait Decryptor: Send + Sync {
+    /// Decrypts `ciphertext`, the part of an encrypted value after `enc:`,
+    /// returning the plaintext or a description of why decryption failed.
+    fn decrypt(&self, ciphertext: &str) -> ::std::result::Result<String, String>;
+}
+
+impl<F> Decryptor for F
+    where F: Fn(&str) -> ::std::result::Result<String, String> + Send + Sync
+{
+    fn decrypt(&self, ciphertext: &str) -> ::std::result::Result<String, String> {
+        self(ciphertext)
+    }
+}
+
+type Installed = Mutex<Option<Box<Decryptor>>>;
+
+static INIT: Once = ONCE_INIT;
+static mut DECRYPTOR: *const Installed = 0 as *const Installed;
+static INITIALIZED: AtomicBool = ATOMIC_BOOL_INIT;
+
+/// Returns the installed decryptor, if any, initializing its slot once.
+fn installed() -> &'static Installed {
+    unsafe {
+        INIT.call_once(|| DECRYPTOR = Box::into_raw(Box::new(Mutex::new(None))));
+        &*DECRYPTOR
+    }
+}
+
+/// Sets the decryptor used to decrypt encrypted values in the configuration
+/// file, replacing the built-in decryptor. See [Decryptor](trait.Decryptor.html)
+/// for details.
+///
+/// # Panics
+///
+/// Panics if the configuration has already been read, as happens when
+/// [rocket::ignite](/rocket/fn.ignite.html) is called. Call this function
+/// before igniting.
+pub fn set_decryptor<D: Decryptor + 'static>(decryptor: D) {
+    let mut installed = installed().lock().expect("decryptor lock");
+    if INITIALIZED.load(Ordering::SeqCst) {
+        error_!("The configuration has already been read.");
+        panic!("set_decryptor() must be called before rocket::ignite().");
+    }
+
+    *installed = Some(Box::new(decryptor));
+}
+
+/// Marks the configuration as read. After this, the decryptor can't change.
+#[doc(hidden)]
+pub fn mark_initialized() {
+    let _installed = installed().lock().expect("decryptor lock");
+    INITIALIZED.store(true, Ordering::SeqCst);
+}
+
+/// Decrypts `ciphertext` with the installed decryptor, or with the built-in
+/// decryptor if none has been installed.
+fn decrypt(ciphertext: &str) -> ::std::result::Result<String, String> {
+    match *installed().lock().expect("decryptor lock") {
+        Some(ref decryptor) => decryptor.decrypt(ciphertext),
+        None => builtin::decrypt(ciphertext)
+    }
+}
+
+/// Decrypts every encrypted string in `value`, recursing into arrays and
+/// tables. `name` is the dotted path to `value`, used in errors.
+fn decrypt_value(value: &mut Value, name: &str, filename: &str) -> Result<()> {
+    let plaintext = match *value {
+        Value::String(ref s) if s.starts_with(ENCRYPTED_PREFIX) => {
+            decrypt(&s[ENCRYPTED_PREFIX.len()..]).map_err(|reason| {
+                ConfigError::BadEncryptedValue(name.into(), reason, filename.into())
+            })?
+        }
+        Value::Array(ref mut values) => {
+            for value in values.iter_mut() {
+                decrypt_value(value, name, filename)?;
+            }
+
+            return Ok(());
+        }
+        Value::Table(ref mut table) => return decrypt_table(table, name, filename),
+        _ => return Ok(())
+    };
+
+    *value = Value::String(plaintext);
+    Ok(())
+}
+
+/// Decrypts every encrypted string in `table`, whose dotted path is `prefix`.
+#[doc(hidden)]
+pub fn decrypt_table(table: &mut Table, prefix: &str, filename: &str) -> Result<()> {
+    for (key, value) in table.iter_mut() {
+        let name = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+
+        decrypt_value(value, &name, filename)?;
+    }
+
+    Ok(())
+}
+
+/// The built-in decryptor: ChaCha20-Poly1305 with the base64 encoded 256-bit
+/// key in the `ROCKET_CONFIG_KEY` environment variable. Ciphertexts are the
+/// base64 encoding of the 96-bit nonce followed by the sealed plaintext.
+#[cfg(feature = "encrypted_config")]
+mod builtin {
+    use std::env;
+
+    use ring::aead;
+    use ring::rand::{SecureRandom, SystemRandom};
+
+    use http::base64;
+    use super::KEY_ENV;
+
+    const NONCE_LEN: usize = 12;
+
+    fn key() -> Result<Vec<u8>, String> {
+        let key = env::var(KEY_ENV)
+            .map_err(|_| format!("the {} environment variable isn't set", KEY_ENV))?;
+
+        base64::decode(key.trim())
+            .map_err(|e| format!("the {} environment variable is bad: {}", KEY_ENV, e))
+    }
+
+    pub fn decrypt(ciphertext: &str) -> Result<String, String> {
+        let key = aead::OpeningKey::new(&aead::CHACHA20_POLY1305, &key()?)
+            .map_err(|_| "the decryption key must be 256 bits".to_string())?;
+
+        let mut data = base64::decode(ciphertext).map_err(|e| e.to_string())?;
+        if data.len() < NONCE_LEN {
+            return Err("the ciphertext is too short".into());
+        }
+
+        let (nonce, sealed) = data.split_at_mut(NONCE_LEN);
+        let plaintext = aead::open_in_place(&key, nonce, &[], 0, sealed)
+            .map_err(|_| "the value couldn't be decrypted with the key".to_string())?;
+
+        String::from_utf8(plaintext.to_vec())
+            .map_err(|_| "the decrypted value isn't UTF-8".to_string())
+    }
+
+    pub fn encrypt(key: &[u8], plaintext: &str) -> Result<String, String> {
+        let key = aead::SealingKey::new(&aead::CHACHA20_POLY1305, key)
+            .map_err(|_| "the encryption key must be 256 bits".to_string())?;
+
+        let tag_len = aead::CHACHA20_POLY1305.tag_len();
+        let mut data = vec![0; NONCE_LEN + plaintext.len() + tag_len];
+        SystemRandom::new().fill(&mut data[..NONCE_LEN])
+            .map_err(|_| "couldn't generate a nonce".to_string())?;
+
+        let len = {
+            let (nonce, in_out) = data.split_at_mut(NONCE_LEN);
+            in_out[..plaintext.len()].copy_from_slice(plaintext.as_bytes());
+            aead::seal_in_place(&key, nonce, &[], in_out, tag_len)
+                .map_err(|_| "couldn't encrypt the value".to_string())?
+        };
+
+        data.truncate(NONCE_LEN + len);
+        Ok(format!("{}{}", super::ENCRYPTED_PREFIX, base64::encode(&data)))
+    }
+}
+
+#[cfg(not(feature = "encrypted_config"))]
+mod builtin {
+    pub fn decrypt(_: &str) -> Result<String, String> {
+        Err("no decryptor is installed; enable the `encrypted_config` feature \
+            or call `config::set_decryptor`".into())
+    }
+}
+
+/// Encrypts `plaintext` with the built-in decryptor's cipher under the 256-bit
+/// key `key`, returning a value, including the `enc:` prefix, suitable for use
+/// in a configuration file.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::config;
+///
+/// let key = [7u8; 32];
+/// let value = config::encrypt(&key, "hunter2").unwrap();
+/// assert!(value.starts_with("enc:"));
+/// ```
+#[cfg(feature = "encrypted_config")]
+pub fn encrypt(key: &[u8], plaintext: &str) -> ::std::result::Result<String, String> {
+    builtin::encrypt(key, plaintext)
+}
+
+#[cfg(all(test, feature = "encrypted_config"))]
+mod test {
+    use std::env;
+
+    use super::{builtin, encrypt, KEY_ENV, ENCRYPTED_PREFIX};
+    use config::test::ENV_LOCK;
+    use http::base64;
+
+    #[test]
+    fn test_round_trip() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        let key = [42u8; 32];
+        env::set_var(KEY_ENV, base64::encode(&key));
+
+        let value = encrypt(&key, "s3cr3t").unwrap();
+        assert_eq!(builtin::decrypt(&value[ENCRYPTED_PREFIX.len()..]), Ok("s3cr3t".into()));
+
+        let other = encrypt(&[1u8; 32], "s3cr3t").unwrap();
+        assert!(builtin::decrypt(&other[ENCRYPTED_PREFIX.len()..]).is_err());
+        assert!(builtin::decrypt("AAAA").is_err());
+    }
+}
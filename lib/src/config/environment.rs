@@ -9,6 +9,29 @@ use self::Environment::*;
 pub const CONFIG_ENV: &'static str = "ROCKET_ENV";
 
 /// An enum corresponding to the valid configuration environments.
+///
+/// The environment an application is running in is available to handlers via
+/// the `Environment` request guard and to the application via
+/// [Rocket::environment](/rocket/struct.Rocket.html#method.environment).
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::config::Environment;
+///
+/// #[get("/debug")]
+/// fn debug(env: Environment) -> Option<&'static str> {
+///     if env.is_dev() {
+///         Some("Debugging information.")
+///     } else {
+///         None
+///     }
+/// }
+/// # fn main() { }
+/// ```
 #[derive(Hash, PartialEq, Eq, Debug, Clone, Copy)]
 pub enum Environment {
     /// The development environment.
@@ -42,6 +65,51 @@ impl Environment {
     pub fn all() -> [Environment; 3] {
         [Development, Staging, Production]
     }
+
+    /// Returns `true` if `self` is `Environment::Development`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Environment;
+    ///
+    /// assert!(Environment::Development.is_dev());
+    /// assert!(!Environment::Production.is_dev());
+    /// ```
+    #[inline(always)]
+    pub fn is_dev(self) -> bool {
+        self == Development
+    }
+
+    /// Returns `true` if `self` is `Environment::Staging`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Environment;
+    ///
+    /// assert!(Environment::Staging.is_stage());
+    /// assert!(!Environment::Development.is_stage());
+    /// ```
+    #[inline(always)]
+    pub fn is_stage(self) -> bool {
+        self == Staging
+    }
+
+    /// Returns `true` if `self` is `Environment::Production`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::Environment;
+    ///
+    /// assert!(Environment::Production.is_prod());
+    /// assert!(!Environment::Staging.is_prod());
+    /// ```
+    #[inline(always)]
+    pub fn is_prod(self) -> bool {
+        self == Production
+    }
 }
 
 impl FromStr for Environment {
@@ -41,6 +41,10 @@ pub enum ConfigError {
     ///
     /// Parameters: (entry_name, expected_type, actual_type, filename)
     BadType(String, &'static str, &'static str, String),
+    /// An encrypted value could not be decrypted.
+    ///
+    /// Parameters: (entry_name, reason, filename)
+    BadEncryptedValue(String, String, String),
     /// There was a TOML parsing error.
     ///
     /// Parameters: (toml_source_string, filename, error_list)
@@ -77,6 +81,11 @@ impl ConfigError {
                 info_!("expected value to be {}, but found {}",
                        White.paint(expected), White.paint(actual));
             }
+            BadEncryptedValue(ref name, ref reason, ref filename) => {
+                error!("'{}' key could not be decrypted", name);
+                info_!("in {}", White.paint(filename));
+                info_!("{}", reason);
+            }
             ParseError(ref source, ref filename, ref errors) => {
                 for error in errors {
                     let (lo, hi) = error.byte_range;
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use config::ByteUnit;
+
+/// The limits on the sizes of request bodies read by data guards, by kind of
+/// data.
+///
+/// Each limit has a name, which is the kind of data it applies to. Rocket's
+/// data guards use the following limits, whose default values are shown:
+///
+///   * **forms**: `32KiB`, for URL-encoded forms read by `Form` and
+///     `LenientForm` and for the text fields of multipart forms
+///   * **file**: `1MiB`, for each file uploaded in a multipart form or via
+///     `rocket_contrib::Tus`
+///   * **parts**: `256`, for the number of parts, text fields and files
+///     alike, in a multipart form
+///   * **files**: `16`, for the number of files uploaded in a multipart form
+///   * **json**: `1MiB`, for JSON read by `rocket_contrib::JSON`
+///   * **default**: `1MiB`, for any other kind of data, including text read
+///     by `String`, which uses a **string** limit if one is set
+///
+/// The **parts** and **files** limits are counts rather than sizes, so they
+/// should be given as plain integers.
+///
+/// Libraries may look up limits with other names; if there is no limit with a
+/// name, the **default** limit applies. Data guards answer requests whose
+/// bodies exceed their limit with `413 Payload Too Large`. Custom data guards
+/// retrieve the active limits via
+/// [Request::limits](/rocket/struct.Request.html#method.limits), and handlers
+/// and request guards via the `&Limits` request guard.
+///
+/// Limits are configured via the `limits` table in `Rocket.toml`, where only
+/// the limits that differ from the defaults need to be listed:
+///
+/// ```toml
+/// [production.limits]
+/// forms = "64KiB"
+/// file = "1GiB"
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::config::{Limits, ByteUnit};
+///
+/// let limits = Limits::default()
+///     .limit("json", ByteUnit::new(64 * 1024))
+///     .limit("msgpack", ByteUnit::new(16 * 1024));
+///
+/// assert_eq!(limits.get("json"), ByteUnit::new(64 * 1024));
+/// assert_eq!(limits.get("msgpack"), ByteUnit::new(16 * 1024));
+/// assert_eq!(limits.get("forms"), ByteUnit::new(32 * 1024));
+/// assert_eq!(limits.get("xml"), limits.get("default"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Limits {
+    limits: HashMap<String, ByteUnit>,
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits { limits: HashMap::new() }
+            .limit("forms", ByteUnit::new(32 * 1024))
+            .limit("file", ByteUnit::new(1024 * 1024))
+            .limit("parts", ByteUnit::new(256))
+            .limit("files", ByteUnit::new(16))
+            .limit("json", ByteUnit::new(1024 * 1024))
+            .limit("default", ByteUnit::new(1024 * 1024))
+    }
+}
+
+impl Limits {
+    /// Sets the limit named `name` to `limit`, replacing any existing limit
+    /// with that name, and returns the structure.
+    pub fn limit<S: Into<String>>(mut self, name: S, limit: ByteUnit) -> Limits {
+        self.limits.insert(name.into(), limit);
+        self
+    }
+
+    /// Returns the limit named `name`, or the **default** limit if there is no
+    /// limit with that name.
+    pub fn get(&self, name: &str) -> ByteUnit {
+        match self.limits.get(name).or_else(|| self.limits.get("default")) {
+            Some(limit) => *limit,
+            None => ByteUnit::new(1024 * 1024)
+        }
+    }
+}
+
+impl fmt::Display for Limits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut limits: Vec<_> = self.limits.iter().collect();
+        limits.sort();
+        for (i, &(name, limit)) in limits.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+
+            write!(f, "{} = {}", name, limit)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Limits;
+    use config::ByteUnit;
+
+    #[test]
+    fn test_display() {
+        let limits = Limits::default().limit("file", ByteUnit::new(1 << 30));
+        assert_eq!(limits.to_string(),
+                   "default = 1MiB, file = 1GiB, files = 16B, forms = 32KiB, json = 1MiB, \
+                    parts = 256B");
+    }
+}
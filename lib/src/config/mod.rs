@@ -40,6 +40,40 @@
 //!   * **session_key**: _[string]_ a 192-bit base64 encoded string (32
 //!     characters) to use as the session key
 //!     * example: `"VheMwXIBygSmOlZAhuWl2B+zgvTN3WW5"`
+//!   * **hosts**: _[array]_ the hosts, with optional ports, that requests may
+//!     be addressed to via the `Host` header; if empty or missing, any host is
+//!     accepted
+//!     * example: `["rocket.rs", "www.rocket.rs:8000"]`
+//!   * **trusted_proxies**: _[array]_ the IP addresses of the proxies whose
+//!     `Forwarded` and `X-Forwarded-*` headers are trusted when determining
+//!     the client's address and scheme; if empty or missing, no proxy is
+//!     trusted
+//!     * example: `["127.0.0.1", "::1"]`
+//!   * **redirects**: _[table]_ redirects applied before requests are routed,
+//!     mapping source paths to destinations; a destination is either a string,
+//!     redirected to with `301 Moved Permanently`, or a table with a `to`
+//!     string and a `status` integer; see
+//!     [RedirectRule](struct.RedirectRule.html) for the syntax of paths
+//!     * example: a `[development.redirects]` table containing `"/old" =
+//!       "/new"` and `"/blog/<p..>" = { to = "/posts/<p..>", status = 302 }`
+//!   * **limits**: _[table]_ the maximum sizes of request bodies read by data
+//!     guards, by kind of data, as integers in bytes or as strings with one of
+//!     the units described in [ByteUnit](struct.ByteUnit.html); the kinds used
+//!     by Rocket and their defaults are listed in [Limits](struct.Limits.html)
+//!     * example: a `[development.limits]` table containing `forms =
+//!       "64KiB"`, `json = 65536`, and `file = "1GiB"`
+//!   * **read_timeout**: _[integer or string]_ how long to wait for more of a
+//!     request's body before giving up, in seconds or as a string with one of
+//!     the units `ms`, `s`, `m`, or `h`; `0` means no timeout; defaults to
+//!     `"5s"`
+//!     * examples: `10`, `"500ms"`, `"30s"`, `"2m"`
+//!   * **blocking_threads**: _[integer]_ the number of threads that run work
+//!     handed to [blocking::spawn](/rocket/blocking/fn.spawn.html); defaults
+//!     to `4`
+//!     * example: `8`
+//!   * **blocking_queue**: _[integer]_ how many blocking tasks may wait for a
+//!     thread before `blocking::spawn` waits for room; defaults to `64`
+//!     * example: `256`
 //!
 //! ### Rocket.toml
 //!
@@ -92,6 +126,29 @@
 //! address = "0.0.0.0"
 //! ```
 //!
+//! ## Encrypted Values
+//!
+//! Secrets, such as database passwords, can be stored in `Rocket.toml` in
+//! encrypted form so that the file can be committed safely. Any string value
+//! that begins with `enc:` is decrypted when the configuration file is read,
+//! and the decrypted value is used in its place. If a value can't be
+//! decrypted, the application aborts at launch.
+//!
+//! ```toml
+//! [production]
+//! db_password = "enc:6u1IK4o5ZCEU8Zq0Q0mKr2oZc6mVbKkNaHaL3w=="
+//! ```
+//!
+//! With the `encrypted_config` feature enabled, values are decrypted with
+//! ChaCha20-Poly1305 using the base64 encoded 256-bit key in the
+//! `ROCKET_CONFIG_KEY` environment variable, and [encrypt](fn.encrypt.html)
+//! produces encrypted values. Alternatively, keys can be kept elsewhere, such
+//! as in a key management service, by installing a
+//! [Decryptor](trait.Decryptor.html) via
+//! [set_decryptor](fn.set_decryptor.html) before calling `rocket::ignite`.
+//! The `encrypted_config` feature depends on `ring` 0.13, which requires a
+//! newer nightly than the rest of Rocket; see the changelog.
+//!
 //! ## Retrieving Configuration Parameters
 //!
 //! Configuration parameters for the currently active configuration environment
@@ -119,28 +176,53 @@
 //! ```
 //!
 //! Libraries should always use a default if a parameter is not defined.
+//!
+//! ## Hot Reloading
+//!
+//! Some parameters can be changed without restarting the application. The
+//! `log` parameter as well as any extra can be marked as hot-reloadable via
+//! [Rocket::hot_reload](/rocket/struct.Rocket.html#method.hot_reload). Once
+//! the application has launched, the `Rocket.toml` file is checked for changes
+//! periodically, and, on Unix, whenever the process receives `SIGHUP`. When it
+//! changes, the marked parameters are reloaded, requests that arrive afterwards
+//! see the reloaded values via their configuration, and the functions
+//! registered via
+//! [Rocket::on_reload](/rocket/struct.Rocket.html#method.on_reload) are called
+//! with the reloaded configuration.
 
 mod error;
 mod environment;
 mod config;
+mod reload;
+mod encryption;
+mod redirect;
+mod units;
+mod limits;
 
 use std::sync::{Once, ONCE_INIT};
 use std::fs::{self, File};
 use std::collections::HashMap;
 use std::io::Read;
 use std::path::PathBuf;
-use std::process;
 use std::env;
 
 pub use self::error::{ConfigError, ParsingError};
 pub use self::environment::Environment;
 pub use self::config::Config;
+pub use self::reload::ReloadHandler;
+pub use self::redirect::RedirectRule;
+pub use self::units::ByteUnit;
+pub use self::limits::Limits;
+pub use self::encryption::{Decryptor, set_decryptor};
+#[cfg(feature = "encrypted_config")] pub use self::encryption::encrypt;
+#[doc(hidden)] pub use self::reload::{watch, LiveConfig};
 use self::Environment::*;
 
 use toml;
 pub use toml::{Array, Table, Value};
 
 use logger::{self, LoggingLevel};
+use shutdown::ShutdownReason;
 
 static INIT: Once = ONCE_INIT;
 static mut CONFIG: Option<RocketConfig> = None;
@@ -210,7 +292,7 @@ impl RocketConfig {
     fn parse(src: String, filename: &str) -> Result<RocketConfig> {
         // Parse the source as TOML, if possible.
         let mut parser = toml::Parser::new(&src);
-        let toml = parser.parse().ok_or_else(|| ConfigError::ParseError(
+        let mut toml = parser.parse().ok_or_else(|| ConfigError::ParseError(
             src.clone(), filename.into(),
             parser.errors.iter().map(|error| ParsingError {
                 byte_range: (error.lo, error.hi),
@@ -220,6 +302,9 @@ impl RocketConfig {
             }).collect()
         ))?;
 
+        // Replace encrypted values with their decryptions.
+        encryption::decrypt_table(&mut toml, "", filename)?;
+
         // Create a config with the defaults; set the env to the active one.
         let mut config = RocketConfig::active_default(filename)?;
 
@@ -300,7 +385,8 @@ impl RocketConfig {
 ///
 /// # Panics
 ///
-/// If there is a problem, prints a nice error message and bails.
+/// If there is a problem, prints a nice error message and exits the process with
+/// the exit code of `ShutdownReason::Config`.
 #[doc(hidden)]
 pub fn init() -> (&'static Config, bool) {
     let mut this_init = false;
@@ -315,17 +401,18 @@ pub fn init() -> (&'static Config, bool) {
 }
 
 unsafe fn private_init() {
+    encryption::mark_initialized();
     let bail = |e: ConfigError| -> ! {
         logger::init(LoggingLevel::Debug);
         e.pretty_print();
-        process::exit(1)
+        ShutdownReason::Config(format!("{:?}", e)).exit()
     };
 
     use self::ConfigError::*;
     let config = RocketConfig::read().unwrap_or_else(|e| {
         match e {
             ParseError(..) | BadEntry(..) | BadEnv(..) | BadType(..)
-                | BadFilePath(..) => bail(e),
+                | BadFilePath(..) | BadEncryptedValue(..) => bail(e),
             IOError | BadCWD => warn!("Failed reading Rocket.toml. Using defaults."),
             NotFound => { /* try using the default below */ }
         }
@@ -355,8 +442,11 @@ pub fn active() -> Option<&'static Config> {
 mod test {
     use std::env;
     use std::sync::Mutex;
+    use std::time::Duration;
 
-    use super::{RocketConfig, ConfigError, GLOBAL_ENV_NAME};
+    use super::{RocketConfig, ConfigError, RedirectRule, ByteUnit, Limits};
+    use super::GLOBAL_ENV_NAME;
+    use super::set_decryptor;
     use super::environment::{Environment, CONFIG_ENV};
     use super::Environment::*;
     use super::config::Config;
@@ -364,11 +454,12 @@ mod test {
 
     use ::toml::Value;
     use ::logger::LoggingLevel;
+    use ::http::Status;
 
     const TEST_CONFIG_FILENAME: &'static str = "/tmp/testing/Rocket.toml";
 
     lazy_static! {
-        static ref ENV_LOCK: Mutex<usize> = Mutex::new(0);
+        pub static ref ENV_LOCK: Mutex<usize> = Mutex::new(0);
     }
 
     macro_rules! check_config {
@@ -689,6 +780,250 @@ mod test {
         "#.to_string(), TEST_CONFIG_FILENAME).is_err());
     }
 
+    #[test]
+    fn test_good_hosts() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::remove_var(CONFIG_ENV);
+
+        check_config!(RocketConfig::parse(r#"
+                          [dev]
+                          hosts = ["rocket.rs", "www.rocket.rs:8000"]
+                      "#.to_string(), TEST_CONFIG_FILENAME), {
+                          default_config(Development).hosts(vec![
+                              "rocket.rs".into(), "www.rocket.rs:8000".into()
+                          ])
+                      });
+
+        check_config!(RocketConfig::parse(r#"
+                          [dev]
+                          hosts = []
+                      "#.to_string(), TEST_CONFIG_FILENAME), {
+                          default_config(Development)
+                      });
+    }
+
+    #[test]
+    fn test_bad_hosts() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::remove_var(CONFIG_ENV);
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            hosts = "rocket.rs"
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            hosts = [1, 2]
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            hosts = ["rocket.rs/path"]
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            hosts = ["rocket.rs:http"]
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+    }
+
+    #[test]
+    fn test_good_trusted_proxies() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::remove_var(CONFIG_ENV);
+
+        check_config!(RocketConfig::parse(r#"
+                          [dev]
+                          trusted_proxies = ["10.0.0.1", "::1"]
+                      "#.to_string(), TEST_CONFIG_FILENAME), {
+                          default_config(Development).trusted_proxies(vec![
+                              "10.0.0.1".parse().unwrap(), "::1".parse().unwrap()
+                          ])
+                      });
+    }
+
+    #[test]
+    fn test_bad_trusted_proxies() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::remove_var(CONFIG_ENV);
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            trusted_proxies = "10.0.0.1"
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            trusted_proxies = ["10.0.0.1:80"]
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            trusted_proxies = ["proxy.local"]
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+    }
+
+    #[test]
+    fn test_good_redirects() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::remove_var(CONFIG_ENV);
+
+        check_config!(RocketConfig::parse(r#"
+                          [dev.redirects]
+                          "/old" = "/new"
+                          "/blog/<p..>" = { to = "/posts/<p..>", status = 302 }
+                      "#.to_string(), TEST_CONFIG_FILENAME), {
+                          default_config(Development).redirects(vec![
+                              RedirectRule::new("/blog/<p..>", "/posts/<p..>",
+                                                Status::Found).unwrap(),
+                              RedirectRule::new("/old", "/new",
+                                                Status::MovedPermanently).unwrap(),
+                          ])
+                      });
+    }
+
+    #[test]
+    fn test_bad_redirects() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::remove_var(CONFIG_ENV);
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            redirects = ["/old", "/new"]
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev.redirects]
+            "old" = "/new"
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev.redirects]
+            "/old" = { to = "/new", status = 200 }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev.redirects]
+            "/old" = { status = 301 }
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+    }
+
+    #[test]
+    fn test_good_limits_and_timeouts() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::remove_var(CONFIG_ENV);
+
+        check_config!(RocketConfig::parse(r#"
+                          [dev]
+                          read_timeout = "2m"
+
+                          [dev.limits]
+                          forms = "512kb"
+                      "#.to_string(), TEST_CONFIG_FILENAME), {
+                          default_config(Development)
+                              .limits(Limits::default().limit("forms", ByteUnit::new(512_000)))
+                              .read_timeout(Duration::from_secs(120))
+                      });
+
+        check_config!(RocketConfig::parse(r#"
+                          [dev]
+                          read_timeout = 10
+                          limits = { json = 1024, msgpack = "2KiB" }
+                      "#.to_string(), TEST_CONFIG_FILENAME), {
+                          default_config(Development)
+                              .limits(Limits::default()
+                                  .limit("json", ByteUnit::new(1024))
+                                  .limit("msgpack", ByteUnit::new(2048)))
+                              .read_timeout(Duration::from_secs(10))
+                      });
+
+        check_config!(RocketConfig::parse(r#"
+                          [dev]
+                          read_timeout = "500ms"
+
+                          [dev.limits]
+                          file = "1GiB"
+                          default = "5MiB"
+                      "#.to_string(), TEST_CONFIG_FILENAME), {
+                          default_config(Development)
+                              .limits(Limits::default()
+                                  .limit("file", ByteUnit::new(1024 * 1024 * 1024))
+                                  .limit("default", ByteUnit::new(5 * 1024 * 1024)))
+                              .read_timeout(Duration::from_millis(500))
+                      });
+
+        let config = RocketConfig::parse(r#"
+                          [dev]
+                          read_timeout = "0s"
+                      "#.to_string(), TEST_CONFIG_FILENAME).unwrap();
+        assert_eq!(config.active().read_timeout, None);
+
+        check_config!(RocketConfig::parse(r#"
+                          [dev]
+                          blocking_threads = 8
+                          blocking_queue = 0
+                      "#.to_string(), TEST_CONFIG_FILENAME), {
+                          default_config(Development)
+                              .blocking_threads(8)
+                              .blocking_queue(0)
+                      });
+    }
+
+    #[test]
+    fn test_bad_limits_and_timeouts() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::remove_var(CONFIG_ENV);
+
+        assert!(RocketConfig::parse(r#"
+            [dev.limits]
+            forms = "5 parsecs"
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev.limits]
+            forms = -1
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            limits = "1MiB"
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            read_timeout = "2 weeks"
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            read_timeout = true
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            read_timeout = -1
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            blocking_threads = 0
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+
+        assert!(RocketConfig::parse(r#"
+            [dev]
+            blocking_queue = -1
+        "#.to_string(), TEST_CONFIG_FILENAME).is_err());
+    }
+
     #[test]
     fn test_bad_toml() {
         // Take the lock so changing the environment doesn't cause races.
@@ -710,6 +1045,49 @@ mod test {
         "#.to_string(), TEST_CONFIG_FILENAME).is_err());
     }
 
+    #[test]
+    fn test_encrypted_values() {
+        // Take the lock so changing the environment doesn't cause races.
+        let _env_lock = ENV_LOCK.lock().unwrap();
+        env::remove_var(CONFIG_ENV);
+
+        set_decryptor(|ciphertext: &str| match ciphertext {
+            "bad" => Err("bad ciphertext".to_string()),
+            _ => Ok(ciphertext.chars().rev().collect())
+        });
+
+        let parsed = RocketConfig::parse(r#"
+            [dev]
+            port = 8000
+            password = "enc:2retnuh"
+            plain = "2retnuh"
+            list = ["enc:a", "b", "enc:dc"]
+            table = { secret = "enc:terces" }
+        "#.to_string(), TEST_CONFIG_FILENAME).expect("parsed");
+
+        let config = parsed.get(Development);
+        assert_eq!(config.get_str("password"), Ok("hunter2"));
+        assert_eq!(config.get_str("plain"), Ok("2retnuh"));
+
+        let list: Vec<_> = config.extras()
+            .find(|&(name, _)| name == "list")
+            .and_then(|(_, value)| value.as_slice())
+            .map(|values| values.iter().map(|v| v.as_str().unwrap()).collect())
+            .unwrap();
+        assert_eq!(list, vec!["a", "b", "cd"]);
+
+        let table = config.get_table("table").unwrap();
+        assert_eq!(table.get("secret").and_then(|v| v.as_str()), Some("secret"));
+
+        let err = RocketConfig::parse(r#"
+            [dev]
+            password = "enc:bad"
+        "#.to_string(), TEST_CONFIG_FILENAME).unwrap_err();
+
+        assert_eq!(err, ConfigError::BadEncryptedValue("dev.password".into(),
+            "bad ciphertext".into(), TEST_CONFIG_FILENAME.into()));
+    }
+
     #[test]
     fn test_global_overrides() {
         // Take the lock so changing the environment doesn't cause races.
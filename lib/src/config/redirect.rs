@@ -0,0 +1,266 @@
+use std::fmt;
+
+use http::{Status, StatusClass};
+use http::uri::URI;
+
+/// A declarative redirect from one path to another, applied before requests
+/// are routed.
+///
+/// The source path may contain dynamic segments: `<name>` matches exactly one
+/// segment, and `<name..>`, which must be last, matches all remaining
+/// segments, including none. The destination may refer to the dynamic
+/// segments of the source by name; each reference is replaced with the
+/// segment(s) it matched. If the destination has no query string, the
+/// request's query string, if any, is appended.
+///
+/// Redirect rules are added via the `redirects` configuration parameter or
+/// [Rocket::redirect](/rocket/struct.Rocket.html#method.redirect).
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::config::RedirectRule;
+/// use rocket::http::Status;
+/// use rocket::http::uri::URI;
+///
+/// let rule = RedirectRule::new("/blog/<year>/<rest..>", "/posts/<rest..>?y=<year>",
+///                              Status::MovedPermanently).unwrap();
+///
+/// let uri = URI::new("/blog/2016/launch/notes");
+/// assert_eq!(rule.apply(&uri), Some("/posts/launch/notes?y=2016".to_string()));
+/// assert_eq!(rule.apply(&URI::new("/blog")), None);
+///
+/// let rule = RedirectRule::new("/docs/<path..>", "/guide/<path..>",
+///                              Status::Found).unwrap();
+///
+/// let uri = URI::new("/docs/config?lang=en");
+/// assert_eq!(rule.apply(&uri), Some("/guide/config?lang=en".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RedirectRule {
+    from: String,
+    to: String,
+    status: Status,
+}
+
+/// A segment of the source path of a redirect rule.
+enum Segment<'a> {
+    Static(&'a str),
+    Single(&'a str),
+    Multi(&'a str),
+}
+
+/// Parses the segments of the source path `from`.
+fn parse_segments(from: &str) -> Result<Vec<Segment>, &'static str> {
+    if !from.starts_with('/') {
+        return Err("Redirect sources must be absolute paths.");
+    }
+
+    let mut segments = vec![];
+    for segment in from.split('/').filter(|s| !s.is_empty()) {
+        if let Some(&Segment::Multi(_)) = segments.last() {
+            return Err("A `<name..>` segment must be the last segment.");
+        }
+
+        if !segment.starts_with('<') {
+            if segment.contains('<') || segment.contains('>') {
+                return Err("Dynamic segments must span an entire segment.");
+            }
+
+            segments.push(Segment::Static(segment));
+            continue;
+        }
+
+        if !segment.ends_with('>') {
+            return Err("Dynamic segments must end with `>`.");
+        }
+
+        let name = &segment[1..(segment.len() - 1)];
+        let multi = name.ends_with("..");
+        let name = if multi { &name[..(name.len() - 2)] } else { name };
+
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err("Dynamic segment names must be nonempty identifiers.");
+        }
+
+        segments.push(if multi { Segment::Multi(name) } else { Segment::Single(name) });
+    }
+
+    Ok(segments)
+}
+
+impl RedirectRule {
+    /// Creates a new rule redirecting requests to paths matching `from` to
+    /// `to` with status `status`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` describing the problem if `from` isn't an absolute
+    /// path, if one of its dynamic segments is malformed, if `to` refers to a
+    /// dynamic segment that `from` doesn't have, or if `status` isn't a
+    /// redirection status (`3xx`) other than `304 Not Modified`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::config::RedirectRule;
+    /// use rocket::http::Status;
+    ///
+    /// assert!(RedirectRule::new("/old", "/new", Status::MovedPermanently).is_ok());
+    /// assert!(RedirectRule::new("old", "/new", Status::Found).is_err());
+    /// assert!(RedirectRule::new("/<a..>/b", "/", Status::Found).is_err());
+    /// assert!(RedirectRule::new("/<a>", "/<b>", Status::Found).is_err());
+    /// assert!(RedirectRule::new("/a", "/b", Status::Ok).is_err());
+    /// ```
+    pub fn new<F, T>(from: F, to: T, status: Status) -> Result<RedirectRule, &'static str>
+        where F: Into<String>, T: Into<String>
+    {
+        let (from, to) = (from.into(), to.into());
+        if status.class() != StatusClass::Redirection || status == Status::NotModified {
+            return Err("Redirects must have a redirection status.");
+        }
+
+        {
+            let segments = parse_segments(&from)?;
+            let mut rest = to.as_str();
+            while let Some(start) = rest.find('<') {
+                let end = match rest[start..].find('>') {
+                    Some(i) => start + i,
+                    None => return Err("Unterminated dynamic segment in destination."),
+                };
+
+                let name = rest[(start + 1)..end].trim_right_matches("..");
+                let defined = segments.iter().any(|segment| match *segment {
+                    Segment::Single(n) | Segment::Multi(n) => n == name,
+                    Segment::Static(_) => false
+                });
+
+                if !defined {
+                    return Err("The destination refers to an undefined dynamic segment.");
+                }
+
+                rest = &rest[(end + 1)..];
+            }
+        }
+
+        Ok(RedirectRule { from: from, to: to, status: status })
+    }
+
+    /// Returns the source path of `self`.
+    #[inline(always)]
+    pub fn from(&self) -> &str {
+        &self.from
+    }
+
+    /// Returns the destination of `self`.
+    #[inline(always)]
+    pub fn to(&self) -> &str {
+        &self.to
+    }
+
+    /// Returns the status of the redirect.
+    #[inline(always)]
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Returns the destination `uri` is redirected to if `uri`'s path matches
+    /// the source path of `self`, and `None` otherwise.
+    pub fn apply(&self, uri: &URI) -> Option<String> {
+        let segments = match parse_segments(&self.from) {
+            Ok(segments) => segments,
+            Err(_) => return None
+        };
+
+        let mut params: Vec<(&str, String)> = vec![];
+        let mut path = uri.segments();
+        let mut rest_matched = false;
+        for segment in &segments {
+            match *segment {
+                Segment::Static(s) => {
+                    if path.next() != Some(s) {
+                        return None;
+                    }
+                }
+                Segment::Single(name) => match path.next() {
+                    Some(s) => params.push((name, s.to_string())),
+                    None => return None
+                },
+                Segment::Multi(name) => {
+                    let rest: Vec<_> = path.by_ref().collect();
+                    params.push((name, rest.join("/")));
+                    rest_matched = true;
+                }
+            }
+        }
+
+        if !rest_matched && path.next().is_some() {
+            return None;
+        }
+
+        let mut destination = self.to.clone();
+        for &(name, ref value) in &params {
+            destination = destination.replace(&format!("<{}..>", name), value)
+                .replace(&format!("<{}>", name), value);
+        }
+
+        if let (false, Some(query)) = (destination.contains('?'), uri.query()) {
+            destination.push('?');
+            destination.push_str(query);
+        }
+
+        Some(destination)
+    }
+}
+
+impl fmt::Display for RedirectRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} => {} ({})", self.from, self.to, self.status.code)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RedirectRule;
+    use http::Status;
+    use http::uri::URI;
+
+    fn apply(from: &str, to: &str, uri: &str) -> Option<String> {
+        let rule = RedirectRule::new(from, to, Status::Found).unwrap();
+        rule.apply(&URI::new(uri))
+    }
+
+    #[test]
+    fn test_static_rules() {
+        assert_eq!(apply("/a/b", "/c", "/a/b"), Some("/c".to_string()));
+        assert_eq!(apply("/a/b", "/c", "/a//b/"), Some("/c".to_string()));
+        assert_eq!(apply("/a/b", "/c", "/a/b/c"), None);
+        assert_eq!(apply("/a/b", "/c", "/a"), None);
+        assert_eq!(apply("/", "/home", "/"), Some("/home".to_string()));
+        assert_eq!(apply("/", "/home", "/x"), None);
+    }
+
+    #[test]
+    fn test_dynamic_rules() {
+        assert_eq!(apply("/u/<id>", "/users/<id>", "/u/7"), Some("/users/7".to_string()));
+        assert_eq!(apply("/u/<id>", "/users/<id>", "/u"), None);
+        assert_eq!(apply("/u/<id>", "/users/<id>", "/u/7/x"), None);
+        assert_eq!(apply("/s/<p..>", "/static/<p..>", "/s"), Some("/static/".to_string()));
+        assert_eq!(apply("/s/<p..>", "/static/<p..>", "/s/a/b"),
+                   Some("/static/a/b".to_string()));
+        assert_eq!(apply("/s/<p..>", "https://cdn.example.com/<p..>", "/s/a?v=1"),
+                   Some("https://cdn.example.com/a?v=1".to_string()));
+        assert_eq!(apply("/s/<p..>", "/x?y=1", "/s/a?v=1"), Some("/x?y=1".to_string()));
+    }
+
+    #[test]
+    fn test_bad_rules() {
+        assert!(RedirectRule::new("/<a", "/", Status::Found).is_err());
+        assert!(RedirectRule::new("/a<b>", "/", Status::Found).is_err());
+        assert!(RedirectRule::new("/<>", "/", Status::Found).is_err());
+        assert!(RedirectRule::new("/<a>", "/<a..>", Status::Found).is_ok());
+        assert!(RedirectRule::new("/<a>", "/<a", Status::Found).is_err());
+        assert!(RedirectRule::new("/a", "/b", Status::NotModified).is_err());
+        assert!(RedirectRule::new("/a", "/b", Status::PermanentRedirect).is_ok());
+    }
+}
@@ -0,0 +1,127 @@
+use std::time::Duration;
+use std::thread;
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, ATOMIC_BOOL_INIT, Ordering};
+
+use logger;
+use config::Config;
+
+/// How often, in milliseconds, the configuration file is checked for changes.
+const RELOAD_INTERVAL_MS: u64 = 2000;
+
+/// The type of a function called after hot-reloadable configuration parameters
+/// have changed. The function is passed the reloaded configuration and the
+/// names of the parameters that changed.
+pub type ReloadHandler = Box<Fn(&Config, &[String]) + Send + Sync>;
+
+/// The most recently reloaded configuration, shared between the thread that
+/// watches the configuration file and the application, so that requests are
+/// handled under the reloaded configuration. This should only be used
+/// internally by `Rocket`.
+#[doc(hidden)]
+#[derive(Clone, Default)]
+pub struct LiveConfig(Arc<RwLock<Option<Arc<Config>>>>);
+
+impl LiveConfig {
+    #[doc(hidden)]
+    pub fn new() -> LiveConfig {
+        LiveConfig::default()
+    }
+
+    /// Returns the most recently reloaded configuration, if there is one.
+    #[doc(hidden)]
+    pub fn get(&self) -> Option<Arc<Config>> {
+        self.0.read().expect("live config lock").clone()
+    }
+
+    /// Makes `config` the configuration new requests are handled under.
+    /// Requests in flight keep the configuration being replaced alive until
+    /// they complete.
+    fn set(&self, config: Config) {
+        *self.0.write().expect("live config lock") = Some(Arc::new(config));
+    }
+}
+
+/// Set when the process receives `SIGHUP`, asking for the configuration to be
+/// reloaded.
+static HANGUP: AtomicBool = ATOMIC_BOOL_INIT;
+
+#[cfg(unix)]
+fn handle_hangups() {
+    use libc::{c_int, sighandler_t, signal, SIGHUP};
+
+    extern "C" fn record(_: c_int) {
+        HANGUP.store(true, Ordering::SeqCst);
+    }
+
+    unsafe { signal(SIGHUP, record as extern "C" fn(c_int) as sighandler_t); }
+}
+
+#[cfg(not(unix))]
+fn handle_hangups() {
+    /* Signals are only handled on Unix. */
+}
+
+/// Spawns a thread that watches the configuration file for `config` for
+/// changes. When the file changes, or when the process receives `SIGHUP`, the
+/// parameters in `names` are reloaded. If any of them changed, the logging
+/// level is updated if necessary, the reloaded configuration is published to
+/// `live`, and each of the `handlers` is called.
+#[doc(hidden)]
+pub fn watch(mut config: Config,
+             names: Vec<String>,
+             handlers: Vec<ReloadHandler>,
+             live: LiveConfig) {
+    handle_hangups();
+    thread::spawn(move || {
+        let mut last_modified = config.modified();
+        loop {
+            thread::sleep(Duration::from_millis(RELOAD_INTERVAL_MS));
+
+            let hangup = HANGUP.swap(false, Ordering::SeqCst);
+            let modified = config.modified();
+            if modified == last_modified && !hangup {
+                continue;
+            }
+
+            last_modified = modified;
+            match config.reload(&names) {
+                Ok(ref changed) if changed.is_empty() => { /* nothing to do */ }
+                Ok(changed) => {
+                    info!("🔧  Reloaded configuration: {}.", changed.join(", "));
+                    if changed.iter().any(|name| name == "log") {
+                        logger::set_level(config.log_level);
+                    }
+
+                    live.set(config.clone());
+                    for handler in handlers.iter() {
+                        handler(&config, &changed[..]);
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to reload configuration. Keeping old values.");
+                    e.pretty_print();
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::LiveConfig;
+    use config::{Config, Environment};
+
+    #[test]
+    fn test_live_config() {
+        let live = LiveConfig::new();
+        assert!(live.get().is_none());
+
+        let config = Config::default_for(Environment::Development, "/tmp/Rocket.toml")
+            .unwrap()
+            .port(9001);
+
+        live.clone().set(config);
+        assert_eq!(live.get().map(|config| config.port), Some(9001));
+    }
+}
@@ -0,0 +1,158 @@
+use std::ascii::AsciiExt;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// A number of bytes, as used for size limits.
+///
+/// A `ByteUnit` can be parsed from a string consisting of an integer and an
+/// optional, case-insensitive unit. Decimal units are multiples of 1000 and
+/// binary units are multiples of 1024:
+///
+///   * `b` (or no unit): bytes
+///   * `kb`, `mb`, `gb`: kilobytes, megabytes, gigabytes
+///   * `kib`, `mib`, `gib`: kibibytes, mebibytes, gibibytes
+///
+/// In configuration files, a size may also be given as an integer number of
+/// bytes.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::config::ByteUnit;
+///
+/// assert_eq!("512kb".parse::<ByteUnit>().unwrap().as_u64(), 512_000);
+/// assert_eq!("5MiB".parse::<ByteUnit>().unwrap().as_u64(), 5 * 1024 * 1024);
+/// assert_eq!("100".parse::<ByteUnit>().unwrap(), ByteUnit::new(100));
+/// assert!("5 parsecs".parse::<ByteUnit>().is_err());
+///
+/// assert_eq!(ByteUnit::new(32 * 1024).to_string(), "32KiB");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteUnit(u64);
+
+impl ByteUnit {
+    /// Returns a `ByteUnit` of `bytes` bytes.
+    #[inline(always)]
+    pub fn new(bytes: u64) -> ByteUnit {
+        ByteUnit(bytes)
+    }
+
+    /// Returns the number of bytes in `self`.
+    #[inline(always)]
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Splits `string` into its leading integer and the trimmed remainder.
+fn split_number(string: &str) -> Result<(u64, &str), &'static str> {
+    let string = string.trim();
+    let end = string.find(|c: char| !c.is_digit(10)).unwrap_or(string.len());
+    if end == 0 {
+        return Err("expected a number followed by an optional unit");
+    }
+
+    let number = string[..end].parse().map_err(|_| "the number is too large")?;
+    Ok((number, string[end..].trim()))
+}
+
+impl FromStr for ByteUnit {
+    type Err = &'static str;
+
+    fn from_str(string: &str) -> Result<ByteUnit, &'static str> {
+        let (number, unit) = split_number(string)?;
+        let multiplier: u64 = match &*unit.to_ascii_lowercase() {
+            "" | "b" => 1,
+            "kb" => 1_000,
+            "kib" => 1 << 10,
+            "mb" => 1_000_000,
+            "mib" => 1 << 20,
+            "gb" => 1_000_000_000,
+            "gib" => 1 << 30,
+            _ => return Err("unknown unit; expected one of b, kb, kib, mb, mib, gb, gib")
+        };
+
+        number.checked_mul(multiplier).map(ByteUnit).ok_or("the size is too large")
+    }
+}
+
+impl fmt::Display for ByteUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let units = [(1 << 30, "GiB"), (1 << 20, "MiB"), (1 << 10, "KiB")];
+        for &(size, unit) in &units {
+            if self.0 != 0 && self.0 % size == 0 {
+                return write!(f, "{}{}", self.0 / size, unit);
+            }
+        }
+
+        write!(f, "{}B", self.0)
+    }
+}
+
+/// Parses a duration from a string consisting of an integer and an optional,
+/// case-insensitive unit: `ms`, `s` (the default), `m`, or `h`.
+pub fn parse_duration(string: &str) -> Result<Duration, &'static str> {
+    let (number, unit) = split_number(string)?;
+    let (multiplier, millis): (u64, bool) = match &*unit.to_ascii_lowercase() {
+        "ms" => (1, true),
+        "" | "s" => (1, false),
+        "m" => (60, false),
+        "h" => (60 * 60, false),
+        _ => return Err("unknown unit; expected one of ms, s, m, h")
+    };
+
+    match number.checked_mul(multiplier) {
+        Some(n) if millis => Ok(Duration::from_millis(n)),
+        Some(n) => Ok(Duration::from_secs(n)),
+        None => Err("the duration is too long")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    use super::{ByteUnit, parse_duration};
+
+    #[test]
+    fn test_byte_units() {
+        assert_eq!("0".parse(), Ok(ByteUnit::new(0)));
+        assert_eq!("42b".parse(), Ok(ByteUnit::new(42)));
+        assert_eq!("512kb".parse(), Ok(ByteUnit::new(512_000)));
+        assert_eq!("512 KiB".parse(), Ok(ByteUnit::new(512 * 1024)));
+        assert_eq!("5MiB".parse(), Ok(ByteUnit::new(5 << 20)));
+        assert_eq!(" 2gb ".parse(), Ok(ByteUnit::new(2_000_000_000)));
+        assert_eq!("1GIB".parse(), Ok(ByteUnit::new(1 << 30)));
+
+        assert!("".parse::<ByteUnit>().is_err());
+        assert!("MiB".parse::<ByteUnit>().is_err());
+        assert!("-5MiB".parse::<ByteUnit>().is_err());
+        assert!("1.5MiB".parse::<ByteUnit>().is_err());
+        assert!("5TiB".parse::<ByteUnit>().is_err());
+        assert!("18446744073709551615kb".parse::<ByteUnit>().is_err());
+    }
+
+    #[test]
+    fn test_byte_unit_display() {
+        assert_eq!(ByteUnit::new(0).to_string(), "0B");
+        assert_eq!(ByteUnit::new(1000).to_string(), "1000B");
+        assert_eq!(ByteUnit::new(1536).to_string(), "1536B");
+        assert_eq!(ByteUnit::new(2048).to_string(), "2KiB");
+        assert_eq!(ByteUnit::new(5 << 20).to_string(), "5MiB");
+        assert_eq!(ByteUnit::new(3 << 30).to_string(), "3GiB");
+    }
+
+    #[test]
+    fn test_durations() {
+        assert_eq!(parse_duration("30"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration("1 H"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("s").is_err());
+        assert!(parse_duration("2 fortnights").is_err());
+        assert!(parse_duration("1.5s").is_err());
+    }
+}
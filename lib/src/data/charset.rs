@@ -0,0 +1,129 @@
+use std::ascii::AsciiExt;
+use std::io;
+
+use http::ContentType;
+
+/// The UTF-8 byte order mark.
+const BOM: &'static [u8] = b"\xEF\xBB\xBF";
+
+/// A character encoding of text request data.
+///
+/// The charset of request data is declared by the `charset` parameter of its
+/// Content-Type, and is UTF-8 if none is declared. Rocket's text data guards,
+/// `String`, [Form](/rocket/request/struct.Form.html), and
+/// [LenientForm](/rocket/request/struct.LenientForm.html), decode data via
+/// its `Charset`, so data in any supported charset is received as a UTF-8
+/// `String`. Custom data guards for text do the same via
+/// [of](#method.of) and [decode](#method.decode).
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::data::Charset;
+/// use rocket::http::ContentType;
+///
+/// let content_type = ContentType::with_params("text", "plain", Some("charset=latin1"));
+/// let charset = Charset::of(&content_type).unwrap();
+/// assert_eq!(charset, Charset::Latin1);
+/// assert_eq!(charset.decode(b"caf\xE9".to_vec()).unwrap(), "café");
+///
+/// let with_bom = b"\xEF\xBB\xBFcaf\xC3\xA9".to_vec();
+/// assert_eq!(Charset::Utf8.decode(with_bom).unwrap(), "café");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Charset {
+    /// UTF-8, which includes US-ASCII. A leading byte order mark, which some
+    /// clients send, is removed when decoding.
+    Utf8,
+    /// ISO-8859-1, or Latin-1, in which each byte is the character with the
+    /// byte's value as its code point.
+    Latin1,
+}
+
+impl Charset {
+    /// Returns the charset named `name`, compared case-insensitively, or
+    /// `None` if the charset isn't supported.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::data::Charset;
+    ///
+    /// assert_eq!(Charset::from_name("UTF-8"), Some(Charset::Utf8));
+    /// assert_eq!(Charset::from_name("iso-8859-1"), Some(Charset::Latin1));
+    /// assert_eq!(Charset::from_name("shift_jis"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Charset> {
+        let name = name.to_ascii_lowercase();
+        match name.as_str() {
+            "utf-8" | "utf8" | "us-ascii" | "ascii" => Some(Charset::Utf8),
+            "iso-8859-1" | "iso8859-1" | "iso_8859-1" | "latin1" | "latin-1" | "l1" => {
+                Some(Charset::Latin1)
+            }
+            _ => None
+        }
+    }
+
+    /// Returns the charset declared by the `charset` parameter of
+    /// `content_type`, or `Utf8` if it doesn't declare one. If the declared
+    /// charset isn't supported, returns an error of kind `InvalidData`.
+    pub fn of(content_type: &ContentType) -> io::Result<Charset> {
+        match content_type.param("charset") {
+            Some(name) => Charset::from_name(name).ok_or_else(|| {
+                let message = format!("unsupported charset '{}'", name);
+                io::Error::new(io::ErrorKind::InvalidData, message)
+            }),
+            None => Ok(Charset::Utf8)
+        }
+    }
+
+    /// Decodes `bytes`, encoded in this charset, into a `String`. If `bytes`
+    /// aren't valid in this charset, returns an error of kind `InvalidData`.
+    pub fn decode(self, mut bytes: Vec<u8>) -> io::Result<String> {
+        match self {
+            Charset::Utf8 => {
+                if bytes.starts_with(BOM) {
+                    bytes.drain(..BOM.len());
+                }
+
+                String::from_utf8(bytes).map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidData, "data is not valid UTF-8")
+                })
+            }
+            Charset::Latin1 => Ok(bytes.into_iter().map(|b| b as char).collect()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::ErrorKind;
+    use std::str::FromStr;
+
+    use super::Charset;
+    use http::ContentType;
+
+    fn charset_of(content_type: &str) -> Result<Charset, ErrorKind> {
+        Charset::of(&ContentType::from_str(content_type).unwrap()).map_err(|e| e.kind())
+    }
+
+    #[test]
+    fn test_charset_of() {
+        assert_eq!(charset_of("text/plain"), Ok(Charset::Utf8));
+        assert_eq!(charset_of("text/plain; charset=UTF-8"), Ok(Charset::Utf8));
+        assert_eq!(charset_of("text/plain; charset=\"ISO-8859-1\""), Ok(Charset::Latin1));
+        assert_eq!(charset_of("application/json; Charset=latin1"), Ok(Charset::Latin1));
+        assert_eq!(charset_of("text/plain; charset=koi8-r"), Err(ErrorKind::InvalidData));
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(Charset::Utf8.decode(b"\xEF\xBB\xBFhi".to_vec()).unwrap(), "hi");
+        assert_eq!(Charset::Utf8.decode(b"hi\xEF\xBB\xBF".to_vec()).unwrap(), "hi\u{feff}");
+        assert_eq!(Charset::Utf8.decode(b"\xEF\xBB".to_vec()).unwrap_err().kind(),
+                   ErrorKind::InvalidData);
+
+        assert_eq!(Charset::Latin1.decode(b"\xA3\xFF".to_vec()).unwrap(), "£ÿ");
+        assert_eq!(Charset::Latin1.decode(vec![]).unwrap(), "");
+    }
+}
@@ -1,16 +1,21 @@
 use std::io::{self, BufRead, Read, Write, Cursor, BufReader};
+use std::cmp::min;
 use std::path::Path;
 use std::fs::File;
 use std::time::Duration;
 use std::mem::transmute;
 
 use super::data_stream::{DataStream, StreamReader, kill_stream};
+use super::persist;
 
 use ext::ReadExt;
+use config::ByteUnit;
+use http::Status;
+use body::MessageBody;
 
-use http::hyper::h1::HttpReader;
-use http::hyper::buffer;
-use http::hyper::h1::HttpReader::*;
+use hyper::http::h1::HttpReader;
+use hyper::buffer;
+use hyper::http::h1::HttpReader::*;
 use http::hyper::net::{HttpStream, NetworkStream};
 
 pub type BodyReader<'a, 'b> =
@@ -43,7 +48,19 @@ pub type BodyReader<'a, 'b> =
 ///
 /// The `peek` method returns a slice containing at most 4096 bytes of buffered
 /// body data. This enables partially or fully reading from a `Data` object
-/// without consuming the `Data` object.
+/// without consuming the `Data` object. The [fill_peek](#method.fill_peek)
+/// method reads more of the body into the `peek` buffer when the first 4096
+/// bytes aren't enough, leaving the rest of the body to be streamed.
+///
+/// # Chunked Bodies
+///
+/// Bodies sent with `Transfer-Encoding: chunked`, as many clients do when
+/// streaming uploads, are decoded as they're read, so `Data` yields the same
+/// bytes whether or not the request had a `Content-Length`. Since the length
+/// of a chunked body isn't known up front, data guards should bound how much
+/// they read, as the built-in guards do with the configured
+/// [limits](/rocket/config/struct.Limits.html), or use
+/// [stream_to_limited](#method.stream_to_limited).
 pub struct Data {
     buffer: Vec<u8>,
     is_done: bool,
@@ -61,7 +78,11 @@ impl Data {
     /// including that in the `peek` buffer. The method consumes the `Data`
     /// instance. This ensures that a `Data` type _always_ represents _all_ of
     /// the data in a request.
-    pub fn open(mut self) -> impl BufRead {
+    pub fn open(self) -> impl BufRead {
+        self.into_stream()
+    }
+
+    fn into_stream(mut self) -> DataStream {
         // Swap out the buffer and stream for empty ones so we can move.
         let mut buffer = vec![];
         let mut stream = EmptyReader(self.stream.get_ref().clone());
@@ -80,10 +101,29 @@ impl Data {
         }
     }
 
+    /// Creates the `Data` for the body `h_body`. On failure, returns the
+    /// status to respond with along with the reason.
     #[doc(hidden)]
-    pub fn from_hyp(mut h_body: BodyReader) -> Result<Data, &'static str> {
+    pub fn from_hyp(mut h_body: BodyReader, timeout: Option<Duration>, limit: ByteUnit)
+            -> Result<Data, (Status, &'static str)> {
         // FIXME: This is asolutely terrible, thanks to Hyper.
 
+        // The bytes Hyper buffered of a chunked body are still encoded, so
+        // decode them through Hyper's reader, which then knows where in the
+        // encoding the rest of the network stream begins. No more than `limit`
+        // decoded bytes are kept in memory.
+        let mut decoded = vec![];
+        if let ChunkedReader(..) = h_body {
+            if let Err(e) = decode_buffered(&mut h_body, &mut decoded, limit.as_u64()) {
+                if decoded.len() as u64 > limit.as_u64() {
+                    return Err((Status::PayloadTooLarge, "Chunked body is too large."));
+                }
+
+                warn_!("Failed to decode chunked body: {:?}.", e);
+                return Err((Status::BadRequest, "Bad chunked body."));
+            }
+        }
+
         // Retrieve the underlying HTTPStream from Hyper.
         let mut stream = match h_body.get_ref().get_ref()
                                      .downcast_ref::<HttpStream>() {
@@ -94,24 +134,34 @@ impl Data {
                     SizedReader(_, n) => SizedReader(owned_stream, n - buf_len),
                     EofReader(_) => EofReader(owned_stream),
                     EmptyReader(_) => EmptyReader(owned_stream),
-                    ChunkedReader(_, n) =>
-                        ChunkedReader(owned_stream, n.map(|k| k - buf_len)),
+                    ChunkedReader(_, n) => ChunkedReader(owned_stream, n),
                 }
             },
-            None => return Err("Stream is not an HTTP stream!"),
+            None => {
+                return Err((Status::InternalServerError, "Stream is not an HTTP stream!"));
+            }
         };
 
-        // Set the read timeout to 5 seconds.
-        stream.get_mut().set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        // Set the read timeout to the configured `timeout`, if any.
+        if let Err(e) = stream.get_mut().set_read_timeout(timeout) {
+            warn_!("Failed to set the read timeout: {:?}.", e);
+        }
 
-        // Create the Data object from hyper's buffer.
-        let (vec, pos, cap) = h_body.get_mut().take_buf();
-        Ok(Data::new(vec, pos, cap, stream))
+        // Create the Data object from hyper's buffer or the decoded bytes.
+        let chunked = match stream { ChunkedReader(..) => true, _ => false };
+        if chunked {
+            let cap = decoded.len();
+            Ok(Data::new(decoded, 0, cap, stream))
+        } else {
+            let (vec, pos, cap) = h_body.get_mut().take_buf();
+            Ok(Data::new(vec, pos, cap, stream))
+        }
     }
 
     /// Retrieve the `peek` buffer.
     ///
-    /// The peek buffer contains at most 4096 bytes of the body of the request.
+    /// The peek buffer contains at most 4096 bytes of the body of the request,
+    /// unless more have been read into it via [fill_peek](#method.fill_peek).
     /// The actual size of the returned buffer varies by web request. The
     /// [peek_complete](#method.peek_complete) can be used to determine if this
     /// buffer contains _all_ of the data in the body of the request.
@@ -120,6 +170,46 @@ impl Data {
         &self.buffer[self.position..self.capacity]
     }
 
+    /// Reads from the body into the `peek` buffer until the buffer contains at
+    /// least `num` bytes or the end of the body is reached, then returns the
+    /// `peek` buffer.
+    ///
+    /// This allows a handler or data guard to inspect as much of the start of
+    /// the body as it needs, for instance to sniff its format, without reading
+    /// the rest of it. The bytes read remain part of the body: they are
+    /// returned first by [open](#method.open), so the remainder of the body can
+    /// still be streamed incrementally. Peeking `num` bytes buffers `num` bytes
+    /// in memory, so `num` should be small.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Read;
+    /// use rocket::Data;
+    ///
+    /// fn is_png(mut data: Data) -> std::io::Result<bool> {
+    ///     let is_png = data.fill_peek(8)?.starts_with(b"\x89PNG\r\n\x1a\n");
+    ///     let mut body = vec![];
+    ///     data.open().read_to_end(&mut body)?;
+    ///     Ok(is_png)
+    /// }
+    /// ```
+    pub fn fill_peek(&mut self, num: usize) -> io::Result<&[u8]> {
+        let wanted = self.position + num;
+        if !self.is_done && self.capacity < wanted {
+            if self.buffer.len() < wanted {
+                trace_!("Resizing peek buffer from {} to {}.", self.buffer.len(), wanted);
+                self.buffer.resize(wanted, 0);
+            }
+
+            let n = self.stream.read_max(&mut self.buffer[self.capacity..wanted])?;
+            self.capacity += n;
+            self.is_done = self.capacity < wanted;
+        }
+
+        Ok(self.peek())
+    }
+
     /// Returns true if the `peek` buffer contains all of the data in the body
     /// of the request. Returns `false` if it does not or if it is not known if
     /// it does.
@@ -130,7 +220,9 @@ impl Data {
 
     /// A helper method to write the body of the request to any `Write` type.
     ///
-    /// This method is identical to `io::copy(&mut data.open(), writer)`.
+    /// This method is identical to `io::copy(&mut data.open(), writer)`. Use
+    /// [stream_to_limited](#method.stream_to_limited) to bound the number of
+    /// bytes written.
     #[inline(always)]
     pub fn stream_to<W: Write>(self, writer: &mut W) -> io::Result<u64> {
         io::copy(&mut self.open(), writer)
@@ -140,12 +232,71 @@ impl Data {
     /// determined by `path`.
     ///
     /// This method is identical to
-    /// `io::copy(&mut self.open(), &mut File::create(path)?)`.
+    /// `io::copy(&mut self.open(), &mut File::create(path)?)`. Use
+    /// [persist_to](#method.persist_to) to write the file atomically and with
+    /// a limit.
     #[inline(always)]
     pub fn stream_to_file<P: AsRef<Path>>(self, path: P) -> io::Result<u64> {
         io::copy(&mut self.open(), &mut File::create(path)?)
     }
 
+    /// Writes the body of the request to `writer`, failing if the body is
+    /// larger than `limit`.
+    ///
+    /// If the body exceeds the limit, an error of kind `InvalidData` is
+    /// returned once `limit` bytes, and then one more, have been written to
+    /// `writer`. Otherwise, returns the number of bytes written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::Data;
+    /// use rocket::config::ByteUnit;
+    ///
+    /// fn echo(data: Data) -> io::Result<u64> {
+    ///     data.stream_to_limited(&mut io::stdout(), ByteUnit::new(64 * 1024))
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn stream_to_limited<W: Write>(self, writer: &mut W, limit: ByteUnit)
+            -> io::Result<u64> {
+        persist::copy_limited(self.open(), writer, limit)
+    }
+
+    /// Atomically writes the body of the request to a file at `path`, failing
+    /// if the body is larger than `limit`.
+    ///
+    /// The body is first written to a temporary file in the same directory as
+    /// `path`, which is then renamed to `path`, replacing any existing file.
+    /// As such, a file at `path` always has complete contents: if the body is
+    /// too large or an I/O error occurs, the temporary file is removed and
+    /// any existing file at `path` is left untouched. When the body is too
+    /// large, the error's kind is `InvalidData`. Returns the number of bytes
+    /// written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #![feature(plugin)]
+    /// # #![plugin(rocket_codegen)]
+    /// # extern crate rocket;
+    /// use std::io;
+    /// use rocket::Data;
+    /// use rocket::config::ByteUnit;
+    ///
+    /// #[post("/upload", data = "<data>")]
+    /// fn upload(data: Data) -> io::Result<String> {
+    ///     let n = data.persist_to("/srv/uploads/latest", ByteUnit::new(1 << 20))?;
+    ///     Ok(format!("stored {} bytes", n))
+    /// }
+    /// # fn main() { }
+    /// ```
+    #[inline(always)]
+    pub fn persist_to<P: AsRef<Path>>(self, path: P, limit: ByteUnit) -> io::Result<u64> {
+        persist::persist(self.open(), path.as_ref(), limit)
+    }
+
     // Creates a new data object with an internal buffer `buf`, where the cursor
     // in the buffer is at `pos` and the buffer has `cap` valid bytes. The
     // remainder of the data bytes can be read from `stream`.
@@ -189,6 +340,58 @@ impl Data {
     }
 }
 
+/// Reads the decoded bytes of the chunked body `body` into `decoded` until the
+/// bytes Hyper buffered while reading the request's head have been consumed or
+/// the body ends. Fails if more than `limit` bytes would be decoded.
+///
+/// Hyper refills its buffer from the network when a read finds it empty, so
+/// each read asks for no more than is buffered. The buffer is then only
+/// refilled when chunk framing is split across its end, and since every
+/// refilled byte must be decoded too, `limit` bounds how much a client can
+/// make this buffer by splitting its framing over and over.
+fn decode_buffered<R: Read>(body: &mut HttpReader<&mut buffer::BufReader<R>>,
+                            decoded: &mut Vec<u8>,
+                            limit: u64) -> io::Result<()> {
+    let mut buf = [0; 4096];
+    loop {
+        let buffered = min(buf.len(), body.get_ref().get_buf().len());
+        if buffered == 0 {
+            return Ok(());
+        }
+
+        match body.read(&mut buf[..buffered])? {
+            0 => return Ok(()),
+            n => decoded.extend_from_slice(&buf[..n])
+        }
+
+        if decoded.len() as u64 > limit {
+            return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                      "the buffered chunked body exceeds the limit"));
+        }
+    }
+}
+
+/// The length of the body is known if the request has a `Content-Length` or
+/// the whole body has been read into the `peek` buffer.
+impl MessageBody for Data {
+    type Reader = DataStream;
+
+    fn length_hint(&self) -> Option<u64> {
+        let buffered = (self.capacity - self.position) as u64;
+        match self.stream {
+            SizedReader(_, remaining) => Some(buffered + remaining),
+            EmptyReader(_) => Some(buffered),
+            _ if self.is_done => Some(buffered),
+            _ => None
+        }
+    }
+
+    #[inline(always)]
+    fn open(self) -> DataStream {
+        self.into_stream()
+    }
+}
+
 impl Drop for Data {
     fn drop(&mut self) {
         // This is okay since the network stream expects to be shared mutably.
@@ -199,3 +402,49 @@ impl Drop for Data {
     }
 }
 
+
+#[cfg(test)]
+mod test {
+    use std::io::{self, Read};
+    use super::decode_buffered;
+    use hyper::buffer::BufReader;
+    use hyper::http::h1::HttpReader::ChunkedReader;
+
+    /// An endless chunked body of one-byte chunks.
+    struct Endless(usize);
+
+    impl Read for Endless {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            const CHUNK: &'static [u8] = b"1\r\na\r\n";
+            for byte in buf.iter_mut() {
+                *byte = CHUNK[self.0 % CHUNK.len()];
+                self.0 += 1;
+            }
+
+            Ok(buf.len())
+        }
+    }
+
+    #[test]
+    fn test_decode_buffered() {
+        let body = &b"3\r\nabc\r\n2\r\nde\r\n0\r\n\r\n"[..];
+        let mut reader = BufReader::with_capacity(body, 8);
+        reader.read_into_buf().unwrap();
+
+        let mut decoded = vec![];
+        decode_buffered(&mut ChunkedReader(&mut reader, None), &mut decoded, 1024).unwrap();
+        assert_eq!(decoded, b"abc");
+        assert!(reader.get_buf().is_empty());
+    }
+
+    #[test]
+    fn test_decode_buffered_is_bounded() {
+        let mut reader = BufReader::with_capacity(Endless(0), 4);
+        reader.read_into_buf().unwrap();
+
+        let mut decoded = vec![];
+        let result = decode_buffered(&mut ChunkedReader(&mut reader, None), &mut decoded, 64);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(decoded.len(), 65);
+    }
+}
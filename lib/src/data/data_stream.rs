@@ -2,7 +2,7 @@ use std::io::{self, BufRead, Read, Cursor, BufReader, Chain, Take};
 use std::net::Shutdown;
 
 use http::hyper::net::{HttpStream, NetworkStream};
-use http::hyper::h1::HttpReader;
+use hyper::http::h1::HttpReader;
 
 pub type StreamReader = HttpReader<HttpStream>;
 pub type InnerStream = Chain<Take<Cursor<Vec<u8>>>, BufReader<StreamReader>>;
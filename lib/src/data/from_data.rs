@@ -1,8 +1,10 @@
+use std::io::{self, Read};
+
 use outcome::{self, IntoOutcome};
 use outcome::Outcome::*;
 use http::Status;
 use request::Request;
-use data::Data;
+use data::{Data, Charset};
 
 /// Type alias for the `Outcome` of a `FromData` conversion.
 pub type Outcome<S, E> = outcome::Outcome<S, (Status, E), Data>;
@@ -103,10 +105,14 @@ impl<'a, S, E> IntoOutcome<S, (Status, E), Data> for Result<S, E> {
 ///             return Outcome::Forward(data);
 ///         }
 ///
-///         // Read the data into a String.
+///         // Read the data into a String, reading one byte past the limit to
+///         // find out if the limit is exceeded.
+///         let limit = req.limits().get("default").as_u64();
 ///         let mut string = String::new();
-///         if let Err(e) = data.open().read_to_string(&mut string) {
+///         if let Err(e) = data.open().take(limit + 1).read_to_string(&mut string) {
 ///             return Failure((Status::InternalServerError, format!("{:?}", e)));
+///         } else if string.len() as u64 > limit {
+///             return Failure((Status::PayloadTooLarge, "Too large.".into()));
 ///         }
 ///
 ///         // Split the string into two pieces at ':'.
@@ -137,6 +143,11 @@ impl<'a, S, E> IntoOutcome<S, (Status, E), Data> for Result<S, E> {
 /// ```
 pub trait FromData: Sized {
     /// The associated error to be returned when parsing fails.
+    ///
+    /// When a route's data guard fails, the error is recorded as the request's
+    /// [guard_failure](/rocket/struct.Request.html#method.guard_failure), so
+    /// the errors of data guards used by routes must also be `Debug` and
+    /// `'static`.
     type Error;
 
     /// Parses an instance of `Self` from the incoming request body data.
@@ -155,6 +166,45 @@ impl FromData for Data {
     }
 }
 
+/// Reads the data as text, decoding it from the charset declared by its
+/// Content-Type, as described in [Charset](struct.Charset.html); a leading
+/// UTF-8 byte order mark is removed. The data is limited by the **string**
+/// limit, or the **default** limit if there is no **string** limit, described
+/// in [Limits](/rocket/config/struct.Limits.html).
+///
+/// If the data exceeds the limit, returns a `Failure` with a status of `413
+/// Payload Too Large`. If the data can't be read or decoded, returns a
+/// `Failure` with a status of `400 Bad Request`. In either case, when the guard
+/// fails a route, the error is recorded as the request's
+/// [guard_failure](/rocket/struct.Request.html#method.guard_failure).
+impl FromData for String {
+    type Error = io::Error;
+
+    fn from_data(request: &Request, data: Data) -> Outcome<Self, Self::Error> {
+        let limit = request.limits().get("string");
+
+        // Read one byte past the limit to find out if the limit is exceeded.
+        let mut bytes = vec![];
+        let result = data.open().take(limit.as_u64() + 1).read_to_end(&mut bytes)
+            .and_then(|_| Charset::of(&request.content_type()));
+
+        let (status, error) = match result {
+            Ok(_) if bytes.len() as u64 > limit.as_u64() => {
+                let message = format!("data exceeds the limit of {}", limit);
+                (Status::PayloadTooLarge, io::Error::new(io::ErrorKind::InvalidData, message))
+            }
+            Ok(charset) => match charset.decode(bytes) {
+                Ok(string) => return Success(string),
+                Err(e) => (Status::BadRequest, e)
+            },
+            Err(e) => (Status::BadRequest, e)
+        };
+
+        error_!("Couldn't read data as text: {}", error);
+        Failure((status, error))
+    }
+}
+
 impl<T: FromData> FromData for Result<T, T::Error> {
     type Error = ();
 
@@ -4,8 +4,11 @@
 #[cfg(not(any(test, feature = "testing")))] mod data;
 #[cfg(not(any(test, feature = "testing")))] mod data_stream;
 mod from_data;
+mod persist;
+mod charset;
 
 pub use self::from_data::{FromData, Outcome};
+pub use self::charset::Charset;
 
 #[cfg(any(test, feature = "testing"))] pub use self::test_data::Data;
 #[cfg(not(any(test, feature = "testing")))] pub use self::data::Data;
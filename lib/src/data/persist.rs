@@ -0,0 +1,102 @@
+use std::io::{self, Read, Write};
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use config::ByteUnit;
+
+/// Copies `reader` to `writer`, failing with an `InvalidData` error once more
+/// than `limit` bytes have been read. Returns the number of bytes copied.
+pub fn copy_limited<R, W>(reader: R, writer: &mut W, limit: ByteUnit) -> io::Result<u64>
+    where R: Read, W: Write
+{
+    // Copy one byte past the limit to find out if the limit is exceeded.
+    let copied = io::copy(&mut reader.take(limit.as_u64() + 1), writer)?;
+    if copied > limit.as_u64() {
+        let message = format!("data exceeds the limit of {}", limit);
+        return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+    }
+
+    Ok(copied)
+}
+
+/// Writes `reader`, up to `limit` bytes, to a temporary file next to `path`,
+/// then renames it to `path`. Nothing is ever written at `path` if copying
+/// fails; the temporary file is removed instead.
+pub fn persist<R: Read>(reader: R, path: &Path, limit: ByteUnit) -> io::Result<u64> {
+    static COUNTER: AtomicUsize = ATOMIC_USIZE_INIT;
+
+    let file_name = path.file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+
+    let n = COUNTER.fetch_add(1, Ordering::SeqCst);
+    let temp_name = format!(".{}.{}-{}.tmp", file_name.to_string_lossy(), n, nanos);
+    let temp_path = path.with_file_name(temp_name);
+
+    let result = OpenOptions::new().write(true).create_new(true).open(&temp_path)
+        .and_then(|mut file| {
+            let copied = copy_limited(reader, &mut file, limit)?;
+            file.sync_all()?;
+            Ok(copied)
+        })
+        .and_then(|copied| fs::rename(&temp_path, path).map(|_| copied));
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+    use std::fs::{self, File};
+    use std::io::{Read, ErrorKind};
+
+    use super::{copy_limited, persist};
+    use config::ByteUnit;
+
+    #[test]
+    fn test_copy_limited() {
+        let mut out = vec![];
+        assert_eq!(copy_limited(&b"hello"[..], &mut out, ByteUnit::new(5)).unwrap(), 5);
+        assert_eq!(out, b"hello");
+
+        out.clear();
+        let error = copy_limited(&b"hello!"[..], &mut out, ByteUnit::new(5)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_persist() {
+        let dir = env::temp_dir();
+        let path = dir.join("rocket-persist-test.txt");
+        assert_eq!(persist(&b"hello"[..], &path, ByteUnit::new(5)).unwrap(), 5);
+
+        let mut contents = String::new();
+        File::open(&path).unwrap().read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "hello");
+
+        // A failed write leaves the existing file, and no temporary files.
+        assert!(persist(&b"goodbye"[..], &path, ByteUnit::new(5)).is_err());
+        contents.clear();
+        File::open(&path).unwrap().read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "hello");
+
+        let leftovers = fs::read_dir(&dir).unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry.file_name().to_string_lossy().starts_with(".rocket-persist-test.txt.")
+            })
+            .count();
+
+        assert_eq!(leftovers, 0);
+        fs::remove_file(&path).unwrap();
+    }
+}
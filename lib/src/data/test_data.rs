@@ -1,10 +1,16 @@
 use std::io::{self, BufRead, Write, Cursor, BufReader};
 use std::path::Path;
 use std::fs::File;
+use std::time::Duration;
 
-use http::hyper::h1::HttpReader;
+use hyper::http::h1::HttpReader;
 use http::hyper::net::NetworkStream;
-use http::hyper::buffer;
+use hyper::buffer;
+
+use super::persist;
+use config::ByteUnit;
+use http::Status;
+use body::MessageBody;
 
 pub type BodyReader<'a, 'b> =
     self::HttpReader<&'a mut self::buffer::BufReader<&'b mut NetworkStream>>;
@@ -13,6 +19,7 @@ const PEEK_BYTES: usize = 4096;
 
 pub struct Data {
     data: Vec<u8>,
+    peeked: usize,
 }
 
 impl Data {
@@ -22,12 +29,17 @@ impl Data {
 
     #[inline(always)]
     pub fn peek(&self) -> &[u8] {
-        &self.data[..::std::cmp::min(PEEK_BYTES, self.data.len())]
+        &self.data[..::std::cmp::min(self.peeked, self.data.len())]
+    }
+
+    pub fn fill_peek(&mut self, num: usize) -> io::Result<&[u8]> {
+        self.peeked = ::std::cmp::max(self.peeked, num);
+        Ok(self.peek())
     }
 
     #[inline(always)]
     pub fn peek_complete(&self) -> bool {
-        self.data.len() <= PEEK_BYTES
+        self.data.len() <= self.peeked
     }
 
     #[inline(always)]
@@ -40,11 +52,23 @@ impl Data {
         io::copy(&mut self.open(), &mut File::create(path)?)
     }
 
+    #[inline(always)]
+    pub fn stream_to_limited<W: Write>(self, writer: &mut W, limit: ByteUnit)
+            -> io::Result<u64> {
+        persist::copy_limited(self.open(), writer, limit)
+    }
+
+    #[inline(always)]
+    pub fn persist_to<P: AsRef<Path>>(self, path: P, limit: ByteUnit) -> io::Result<u64> {
+        persist::persist(self.open(), path.as_ref(), limit)
+    }
+
     #[doc(hidden)]
-    pub fn from_hyp(mut h_body: BodyReader) -> Result<Data, &'static str> {
+    pub fn from_hyp(mut h_body: BodyReader, _timeout: Duration)
+            -> Result<Data, (Status, &'static str)> {
         let mut vec = Vec::new();
         if let Err(_) = io::copy(&mut h_body, &mut vec) {
-            return Err("Reading from body failed.");
+            return Err((Status::BadRequest, "Reading from body failed."));
         };
 
         Ok(Data::new(vec))
@@ -52,6 +76,20 @@ impl Data {
 
     #[doc(hidden)]
     pub fn new(data: Vec<u8>) -> Data {
-        Data { data: data }
+        Data { data: data, peeked: PEEK_BYTES }
+    }
+}
+
+impl MessageBody for Data {
+    type Reader = BufReader<Cursor<Vec<u8>>>;
+
+    #[inline(always)]
+    fn length_hint(&self) -> Option<u64> {
+        Some(self.data.len() as u64)
+    }
+
+    #[inline(always)]
+    fn open(self) -> BufReader<Cursor<Vec<u8>>> {
+        BufReader::new(Cursor::new(self.data))
     }
 }
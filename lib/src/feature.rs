@@ -0,0 +1,117 @@
+//! Feature flags that enable and disable routes at dispatch time.
+//!
+//! A route is gated behind a feature flag via the `feature` route attribute
+//! parameter:
+//!
+//! ```rust,ignore
+//! #[get("/checkout", feature = "new_checkout")]
+//! fn checkout() -> ... { ... }
+//! ```
+//!
+//! Whether a flag is enabled is decided, for every request the route matches,
+//! by the application's [FeatureFlags](trait.FeatureFlags.html), which are
+//! managed state installed via
+//! [Rocket::feature_flags](/rocket/struct.Rocket.html#method.feature_flags).
+//! Flags can thus be read from a file, a database, or a remote service and be
+//! toggled while the application runs, letting incomplete endpoints ship dark.
+//! If an application has no feature flags, every gated route is disabled.
+//!
+//! A disabled route is skipped as if it had forwarded, so a lower ranked route
+//! for the same path, such as the current version of an endpoint, handles the
+//! request instead. Feature flags can instead have requests to a disabled
+//! route answered with `404 Not Found` right away; see
+//! [FeatureFlags::when_disabled](trait.FeatureFlags.html#method.when_disabled).
+//!
+//! # Example
+//!
+//! ```rust
+//! # #![feature(plugin)]
+//! # #![plugin(rocket_codegen)]
+//! # extern crate rocket;
+//! #
+//! use std::collections::HashSet;
+//!
+//! #[get("/checkout", feature = "new_checkout")]
+//! fn new_checkout() -> &'static str { "the new checkout" }
+//!
+//! #[get("/checkout", rank = 2)]
+//! fn checkout() -> &'static str { "the checkout" }
+//!
+//! fn main() {
+//!     let mut enabled = HashSet::new();
+//!     enabled.insert("new_checkout".to_string());
+//!
+//! # if false {
+//!     rocket::ignite()
+//!         .mount("/", routes![new_checkout, checkout])
+//!         .feature_flags(enabled)
+//!         .launch()
+//! # }
+//! }
+//! ```
+
+use std::collections::HashSet;
+
+use request::Request;
+
+/// What happens to requests matched by a route whose feature is disabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Disabled {
+    /// The route is skipped, as if it had forwarded, and the request is
+    /// routed to the next matching route.
+    Forward,
+    /// The request is answered with `404 Not Found` without trying any other
+    /// route.
+    NotFound,
+}
+
+/// Decides whether the feature flags gating routes are enabled.
+///
+/// Feature flags are managed state, installed via
+/// [Rocket::feature_flags](/rocket/struct.Rocket.html#method.feature_flags),
+/// and consulted whenever a request matches a route with a `feature`. Since
+/// they're asked for every such request, flags can change while the
+/// application runs. Sets of feature names, `HashSet<String>` and
+/// `HashSet<&'static str>`, are feature flags that enable exactly the features
+/// they contain.
+///
+/// # Example
+///
+/// Enable the `beta` feature for requests with an `X-Beta` header:
+///
+/// ```rust
+/// use rocket::Request;
+/// use rocket::feature::FeatureFlags;
+///
+/// struct BetaHeader;
+///
+/// impl FeatureFlags for BetaHeader {
+///     fn is_enabled(&self, feature: &str, request: &Request) -> bool {
+///         feature == "beta" && request.headers().contains("X-Beta")
+///     }
+/// }
+/// ```
+pub trait FeatureFlags: Send + Sync + 'static {
+    /// Returns `true` if `feature` is enabled for `request`.
+    fn is_enabled(&self, feature: &str, request: &Request) -> bool;
+
+    /// Returns what happens to requests matched by a route whose `feature` is
+    /// disabled. By default, the route is skipped via `Disabled::Forward`.
+    fn when_disabled(&self, _feature: &str) -> Disabled {
+        Disabled::Forward
+    }
+}
+
+impl FeatureFlags for HashSet<String> {
+    #[inline(always)]
+    fn is_enabled(&self, feature: &str, _: &Request) -> bool {
+        self.contains(feature)
+    }
+}
+
+impl FeatureFlags for HashSet<&'static str> {
+    #[inline(always)]
+    fn is_enabled(&self, feature: &str, _: &Request) -> bool {
+        self.contains(feature)
+    }
+}
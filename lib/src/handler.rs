@@ -35,3 +35,14 @@ pub type Handler = for<'r> fn(&'r Request, Data) -> Outcome<'r>;
 
 /// The type of an error handler.
 pub type ErrorHandler = for<'r> fn(Error, &'r Request) -> response::Result<'r>;
+
+/// The type of a route guard: a precondition checked against a request before
+/// the route's handler is called. Route guards are attached to routes via
+/// [Routes::guard](/rocket/struct.Routes.html#method.guard).
+pub type Guard = for<'a, 'r> fn(&'a Request<'r>) -> outcome::Outcome<(), Status, ()>;
+
+/// The type of a route's parameter check: parses the dynamic path segments of
+/// a request the route matches, returning the name of the first parameter
+/// that fails to parse, if any. Generated by codegen for each route.
+#[doc(hidden)]
+pub type ParamCheck = for<'a, 'r> fn(&'a Request<'r>) -> Result<(), &'static str>;
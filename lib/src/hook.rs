@@ -0,0 +1,440 @@
+//! Request and response hooks and the predicates that gate them.
+//!
+//! Hooks run for every request that satisfies their [Predicate](struct.Predicate.html):
+//! a _request hook_ runs before the request is routed and may modify it, while
+//! a _response hook_ runs after a response has been generated and may modify
+//! the response. Hooks are attached to an application via
+//! [Rocket::on_request](/rocket/struct.Rocket.html#method.on_request) and
+//! [Rocket::on_response](/rocket/struct.Rocket.html#method.on_response).
+//!
+//! Predicates are evaluated by Rocket before a hook is invoked, so expensive
+//! hooks, such as those that capture bodies or inspect credentials, cost almost
+//! nothing for requests they aren't interested in.
+//!
+//! # Example
+//!
+//! Add a `Cache-Control` header to all responses to `GET` requests under
+//! `/static`:
+//!
+//! ```rust
+//! use rocket::hook::Predicate;
+//! use rocket::http::{Header, Method};
+//!
+//! # if false {
+//! rocket::ignite()
+//!     .on_response(Predicate::path("/static").method(Method::Get), |_, response| {
+//!         response.set_header(Header::new("Cache-Control", "max-age=3600"));
+//!     })
+//!     .launch()
+//! # }
+//! ```
+
+use std::ascii::AsciiExt;
+use std::str::FromStr;
+use std::io::{Read, Cursor};
+
+use request::Request;
+use response::{Body, Response};
+use data::Data;
+use http::{Method, Status, ETag, LastModified, Digest, ContentMd5};
+
+/// The type of a request hook: a function called with the incoming request and
+/// its data before the request is routed.
+pub type RequestHook = Box<Fn(&mut Request, &Data) + Send + Sync>;
+
+/// The type of a response hook: a function called with the request and the
+/// response generated for it before the response is written out.
+pub type ResponseHook = Box<Fn(&Request, &mut Response) + Send + Sync>;
+
+/// A predicate over incoming requests that determines whether a hook runs.
+///
+/// A predicate is a conjunction of a path prefix, a set of methods, and a set
+/// of required headers. A request satisfies a predicate when its path begins
+/// with the prefix's segments, its method is one of the methods, and each of
+/// the headers is present. An unset prefix or an empty set of methods matches
+/// every request.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::Request;
+/// use rocket::hook::Predicate;
+/// use rocket::http::{Header, Method};
+///
+/// let predicate = Predicate::path("/api")
+///     .method(Method::Post)
+///     .method(Method::Put)
+///     .header("Authorization");
+///
+/// let mut request = Request::new(Method::Post, "/api/users");
+/// assert!(!predicate.matches(&request));
+///
+/// request.add_header(Header::new("Authorization", "Bearer x"));
+/// assert!(predicate.matches(&request));
+///
+/// request.set_uri("/apiary");
+/// assert!(!predicate.matches(&request));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Predicate {
+    prefix: Vec<String>,
+    methods: Vec<Method>,
+    headers: Vec<String>,
+}
+
+impl Predicate {
+    /// Returns a predicate that every request satisfies.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::hook::Predicate;
+    /// use rocket::http::Method;
+    ///
+    /// assert!(Predicate::any().matches(&Request::new(Method::Delete, "/a/b")));
+    /// ```
+    #[inline(always)]
+    pub fn any() -> Predicate {
+        Predicate { prefix: vec![], methods: vec![], headers: vec![] }
+    }
+
+    /// Returns a predicate satisfied by requests whose path begins with the
+    /// segments of `prefix`. Segments are compared whole, so the prefix
+    /// `/api` matches `/api` and `/api/users` but not `/apiary`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::hook::Predicate;
+    /// use rocket::http::Method;
+    ///
+    /// let predicate = Predicate::path("/api/v1");
+    /// assert!(predicate.matches(&Request::new(Method::Get, "/api/v1/users")));
+    /// assert!(!predicate.matches(&Request::new(Method::Get, "/api/v2")));
+    /// ```
+    pub fn path<S: AsRef<str>>(prefix: S) -> Predicate {
+        let prefix = prefix.as_ref().split('/')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect();
+
+        Predicate { prefix: prefix, methods: vec![], headers: vec![] }
+    }
+
+    /// Additionally allows requests with method `method`. Once a method has
+    /// been added, requests with methods that haven't been added are rejected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::hook::Predicate;
+    /// use rocket::http::Method;
+    ///
+    /// let predicate = Predicate::any().method(Method::Post);
+    /// assert!(predicate.matches(&Request::new(Method::Post, "/")));
+    /// assert!(!predicate.matches(&Request::new(Method::Get, "/")));
+    /// ```
+    #[inline]
+    pub fn method(mut self, method: Method) -> Predicate {
+        if !self.methods.contains(&method) {
+            self.methods.push(method);
+        }
+
+        self
+    }
+
+    /// Additionally requires that a header named `name` be present. Header
+    /// names are compared case-insensitively.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::hook::Predicate;
+    /// use rocket::http::{Method, Header};
+    ///
+    /// let predicate = Predicate::any().header("X-Trace");
+    ///
+    /// let mut request = Request::new(Method::Get, "/");
+    /// assert!(!predicate.matches(&request));
+    ///
+    /// request.add_header(Header::new("x-trace", "1"));
+    /// assert!(predicate.matches(&request));
+    /// ```
+    #[inline]
+    pub fn header<S: Into<String>>(mut self, name: S) -> Predicate {
+        self.headers.push(name.into());
+        self
+    }
+
+    /// Returns `true` if `request` satisfies `self`.
+    pub fn matches(&self, request: &Request) -> bool {
+        if !self.methods.is_empty() && !self.methods.contains(&request.method()) {
+            return false;
+        }
+
+        let mut segments = request.uri().segments();
+        if !self.prefix.iter().all(|prefix| segments.next() == Some(prefix.as_str())) {
+            return false;
+        }
+
+        self.headers.iter().all(|name| {
+            request.headers().iter().any(|header| header.name.eq_ignore_ascii_case(name))
+        })
+    }
+}
+
+/// A response hook that adds the request headers recorded via
+/// [Request::vary_on](/rocket/struct.Request.html#method.vary_on) to the
+/// response's `Vary` header. Request guards that negotiate, such as
+/// [AcceptLanguage](/rocket/http/struct.AcceptLanguage.html), and routes with a
+/// `format` record the headers they depend on, so attaching this hook keeps
+/// caches from serving a negotiated response to the wrong client.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::hook::{self, Predicate};
+///
+/// # if false {
+/// rocket::ignite()
+///     .on_response(Predicate::any(), hook::vary)
+///     .launch()
+/// # }
+/// ```
+pub fn vary(request: &Request, response: &mut Response) {
+    for name in request.varies_on() {
+        response.vary(&name);
+    }
+}
+
+/// A response hook that answers conditional `GET` and `HEAD` requests with
+/// `304 Not Modified` when the client's cached copy is still current.
+///
+/// The hook only considers `200 OK` responses that carry validators. If the
+/// request has an `If-None-Match` header, the response's `ETag` is compared
+/// against it as described in
+/// [ETag::precondition](/rocket/http/struct.ETag.html#method.precondition).
+/// Otherwise, if the request has an `If-Modified-Since` header, the response's
+/// `Last-Modified` time is compared against it. When the validators match, the
+/// response's status is set to `304 Not Modified` and its body and
+/// `Content-Type` are removed; all other headers, including the validators,
+/// are kept.
+///
+/// Since the hook runs after the response has been generated, it saves
+/// bandwidth, not work. Handlers that can cheaply determine a resource's
+/// validators should evaluate preconditions themselves.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::hook::{self, Predicate};
+/// use rocket::http::Method;
+///
+/// # if false {
+/// rocket::ignite()
+///     .on_response(Predicate::any().method(Method::Get).method(Method::Head),
+///                  hook::conditional)
+///     .launch()
+/// # }
+/// ```
+pub fn conditional(request: &Request, response: &mut Response) {
+    match request.method() {
+        Method::Get | Method::Head if response.status() == Status::Ok => {},
+        _ => return
+    }
+
+    let not_modified = if request.headers().contains("If-None-Match") {
+        response.header_values("ETag").next()
+            .and_then(|value| ETag::from_str(value).ok())
+            .and_then(|etag| etag.precondition(request.method(), request.headers()))
+            == Some(Status::NotModified)
+    } else {
+        let since = request.headers().get_one("If-Modified-Since")
+            .and_then(|value| LastModified::from_str(value).ok());
+        let modified = response.header_values("Last-Modified").next()
+            .and_then(|value| LastModified::from_str(value).ok());
+
+        match (since, modified) {
+            (Some(since), Some(modified)) => modified.0 <= since.0,
+            _ => false
+        }
+    };
+
+    if not_modified {
+        response.set_status(Status::NotModified);
+        response.remove_header("Content-Type");
+        response.take_body();
+    }
+}
+
+/// A response hook that adds `Content-MD5` and `Digest` headers to responses
+/// with sized bodies so that clients can verify the integrity of the bodies
+/// they receive. The `Digest` header carries the body's `SHA-256` digest.
+///
+/// The body is read into memory to compute its digests, so the hook should be
+/// restricted, via its predicate, to requests whose responses are small enough
+/// to buffer. Chunked bodies are left untouched. The
+/// [Verified](/rocket/request/struct.Verified.html) data guard verifies these
+/// headers on uploads.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::hook::{self, Predicate};
+///
+/// # if false {
+/// rocket::ignite()
+///     .on_response(Predicate::path("/objects"), hook::digest)
+///     .launch()
+/// # }
+/// ```
+pub fn digest(_: &Request, response: &mut Response) {
+    let mut body = match response.take_body() {
+        Some(Body::Sized(body, size)) => body.take(size),
+        Some(body) => {
+            response.set_raw_body(body);
+            return;
+        }
+        None => return
+    };
+
+    let mut bytes = vec![];
+    if let Err(e) = body.read_to_end(&mut bytes) {
+        error_!("Failed to read the response body to digest it: {:?}", e);
+        response.set_status(Status::InternalServerError);
+        return;
+    }
+
+    response.set_header(ContentMd5::of(&bytes));
+    response.set_header(Digest::sha256(&bytes));
+    response.set_sized_body(Cursor::new(bytes));
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{Predicate, conditional, digest};
+
+    use request::Request;
+    use response::Response;
+    use http::{Header, Status, ContentType, ETag, LastModified, Digest, ContentMd5};
+    use http::Method::*;
+
+    #[test]
+    fn test_path_prefix() {
+        let predicate = Predicate::path("/a/b/");
+        assert!(predicate.matches(&Request::new(Get, "/a/b")));
+        assert!(predicate.matches(&Request::new(Get, "/a//b/c?x=y")));
+        assert!(!predicate.matches(&Request::new(Get, "/a")));
+        assert!(!predicate.matches(&Request::new(Get, "/a/bc")));
+        assert!(!predicate.matches(&Request::new(Get, "/b/a")));
+
+        let root = Predicate::path("/");
+        assert!(root.matches(&Request::new(Get, "/")));
+        assert!(root.matches(&Request::new(Get, "/anything")));
+    }
+
+    #[test]
+    fn test_methods_and_headers() {
+        let predicate = Predicate::path("/x").method(Get).method(Head).header("A").header("B");
+
+        let mut request = Request::new(Get, "/x");
+        request.add_header(Header::new("A", "1"));
+        assert!(!predicate.matches(&request));
+
+        request.add_header(Header::new("B", "2"));
+        assert!(predicate.matches(&request));
+
+        request.set_method(Head);
+        assert!(predicate.matches(&request));
+
+        request.set_method(Post);
+        assert!(!predicate.matches(&request));
+    }
+
+    fn validated_response<'r>() -> Response<'r> {
+        let modified = UNIX_EPOCH + Duration::from_secs(784111777);
+        Response::build()
+            .header(ContentType::HTML)
+            .header(ETag::strong("v1"))
+            .header(LastModified(modified))
+            .sized_body(Cursor::new("<h1>Hi!</h1>"))
+            .finalize()
+    }
+
+    #[test]
+    fn test_conditional_etag() {
+        let mut request = Request::new(Get, "/");
+        request.add_header(Header::new("If-None-Match", r#"W/"v0", "v1""#));
+        let mut response = validated_response();
+        conditional(&request, &mut response);
+        assert_eq!(response.status(), Status::NotModified);
+        assert!(response.body().is_none());
+        assert!(response.header_values("Content-Type").next().is_none());
+        assert_eq!(response.header_values("ETag").next(), Some("\"v1\""));
+
+        request.replace_header(Header::new("If-None-Match", r#""v2""#));
+        let mut response = validated_response();
+        conditional(&request, &mut response);
+        assert_eq!(response.status(), Status::Ok);
+        assert!(response.body().is_some());
+
+        // `If-None-Match` takes precedence over `If-Modified-Since`.
+        request.add_header(Header::new("If-Modified-Since", "Sun, 06 Nov 1994 08:49:37 GMT"));
+        let mut response = validated_response();
+        conditional(&request, &mut response);
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn test_conditional_modified_since() {
+        let mut request = Request::new(Head, "/");
+        request.add_header(Header::new("If-Modified-Since", "Sun, 06 Nov 1994 08:49:37 GMT"));
+        let mut response = validated_response();
+        conditional(&request, &mut response);
+        assert_eq!(response.status(), Status::NotModified);
+
+        request.replace_header(Header::new("If-Modified-Since", "Sun, 06 Nov 1994 08:49:36 GMT"));
+        let mut response = validated_response();
+        conditional(&request, &mut response);
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn test_conditional_ignores_other_requests() {
+        let mut request = Request::new(Post, "/");
+        request.add_header(Header::new("If-None-Match", "*"));
+        let mut response = validated_response();
+        conditional(&request, &mut response);
+        assert_eq!(response.status(), Status::Ok);
+
+        request.set_method(Get);
+        let mut response = validated_response();
+        response.set_status(Status::NotFound);
+        conditional(&request, &mut response);
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_digest() {
+        let request = Request::new(Get, "/");
+        let mut response = validated_response();
+        digest(&request, &mut response);
+
+        let md5: ContentMd5 = response.header_values("Content-MD5").next().unwrap()
+            .parse().unwrap();
+        let sha256: Digest = response.header_values("Digest").next().unwrap()
+            .parse().unwrap();
+        assert!(md5.verify(b"<h1>Hi!</h1>"));
+        assert_eq!(sha256.verify(b"<h1>Hi!</h1>"), Some(true));
+
+        let body = response.body().and_then(|b| b.into_string());
+        assert_eq!(body, Some("<h1>Hi!</h1>".to_string()));
+    }
+}
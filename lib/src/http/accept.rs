@@ -0,0 +1,313 @@
+use std::ascii::AsciiExt;
+use std::str::FromStr;
+use std::fmt;
+
+use http::ContentType;
+use http::accept_language::parse_quality;
+
+/// The value of an `Accept` request header: a list of media ranges ordered by
+/// preference.
+///
+/// Each media range, such as `text/html`, `image/*`, or `*/*`, has a quality
+/// value between `0` and `1`, defaulting to `1`. Ranges are ordered by
+/// descending quality; ranges with equal quality keep the order they were sent
+/// in. A range with quality `0` marks matching media types as unacceptable.
+/// See [RFC 7231 §5.3.2] for details.
+///
+/// [RFC 7231 §5.3.2]: https://tools.ietf.org/html/rfc7231#section-5.3.2
+///
+/// The `Accept` header of a request is parsed once and retrieved via
+/// [Request::accept](/rocket/struct.Request.html#method.accept).
+///
+/// # Request Guard
+///
+/// `Accept` is a request guard that always succeeds. A missing or empty header
+/// results in an `Accept` that accepts any media type. Malformed elements in
+/// the header are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use rocket::http::{Accept, ContentType};
+///
+/// let accept = Accept::from_str("text/html, application/json;q=0.9, */*;q=0.1")
+///     .unwrap();
+///
+/// assert_eq!(accept.preferred(), &ContentType::new("text", "html"));
+/// assert_eq!(accept.negotiate(&[ContentType::JSON, ContentType::HTML]),
+///            Some(&ContentType::HTML));
+/// assert_eq!(accept.negotiate(&[ContentType::PNG]), Some(&ContentType::PNG));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Accept {
+    ranges: Vec<(ContentType, f32)>,
+}
+
+/// Returns `true` if the media type `media` is matched by the media range
+/// `range`, ignoring parameters.
+fn matches(range: &ContentType, media: &ContentType) -> bool {
+    (range.ttype == "*" || range.ttype.eq_ignore_ascii_case(&media.ttype))
+        && (range.subtype == "*" || range.subtype.eq_ignore_ascii_case(&media.subtype))
+}
+
+/// Parses a single element of an `Accept` header: a media range optionally
+/// followed by parameters, one of which may be a quality value. Parameters
+/// other than the quality value are kept with the media range.
+fn parse_element(element: &str) -> Result<(ContentType, f32), &'static str> {
+    let mut parts = element.split(';');
+    let range = parts.next().unwrap_or("").trim();
+    let mut params = vec![];
+    let mut quality = 1.0;
+    for param in parts.map(|p| p.trim()) {
+        let mut kv = param.splitn(2, '=');
+        match (kv.next(), kv.next()) {
+            (Some("q"), Some(q)) | (Some("Q"), Some(q)) => {
+                quality = parse_quality(q).ok_or("Invalid quality value.")?;
+            }
+            (Some(_), Some(_)) => params.push(param),
+            _ => return Err("Invalid media range parameter.")
+        }
+    }
+
+    let media = if params.is_empty() {
+        ContentType::from_str(range)?
+    } else {
+        ContentType::from_str(&format!("{}; {}", range, params.join("; ")))?
+    };
+
+    if media.ttype == "*" && media.subtype != "*" {
+        return Err("Invalid media range.");
+    }
+
+    Ok((media, quality))
+}
+
+impl Accept {
+    /// Orders `ranges` by preference. Returns `Accept::any()` if `ranges` is
+    /// empty.
+    fn from_ranges(mut ranges: Vec<(ContentType, f32)>) -> Accept {
+        if ranges.is_empty() {
+            return Accept::any();
+        }
+
+        // `sort_by` is stable, so ranges of equal quality keep their order.
+        ranges.sort_by(|a, b| b.1.partial_cmp(&a.1).expect("qualities are numbers"));
+        Accept { ranges: ranges }
+    }
+
+    /// Parses the value of an `Accept` header, ignoring malformed elements,
+    /// so that a single bad element doesn't cost the client its other
+    /// preferences.
+    #[doc(hidden)]
+    pub fn parse_lossy(string: &str) -> Accept {
+        let ranges = string.split(',')
+            .filter(|e| !e.trim().is_empty())
+            .filter_map(|e| parse_element(e).ok())
+            .collect();
+
+        Accept::from_ranges(ranges)
+    }
+
+    /// Returns an `Accept` that accepts any media type. This is the meaning of
+    /// a missing `Accept` header.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::{Accept, ContentType};
+    ///
+    /// let any = Accept::any();
+    /// assert_eq!(any.negotiate(&[ContentType::XML]), Some(&ContentType::XML));
+    /// ```
+    #[inline(always)]
+    pub fn any() -> Accept {
+        Accept { ranges: vec![(ContentType::Any, 1.0)] }
+    }
+
+    /// Returns the media ranges in `self` and their quality values, in order
+    /// of preference.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use rocket::http::Accept;
+    ///
+    /// let accept = Accept::from_str("text/*;q=0.5, text/plain").unwrap();
+    /// let ranges: Vec<_> = accept.media_types()
+    ///     .map(|(media, q)| (media.to_string(), q))
+    ///     .collect();
+    ///
+    /// assert_eq!(ranges, vec![("text/plain".to_string(), 1.0),
+    ///                         ("text/*".to_string(), 0.5)]);
+    /// ```
+    pub fn media_types<'a>(&'a self) -> impl Iterator<Item=(&'a ContentType, f32)> + 'a {
+        self.ranges.iter().map(|&(ref media, q)| (media, q))
+    }
+
+    /// Returns the most preferred media range in `self`.
+    #[inline]
+    pub fn preferred(&self) -> &ContentType {
+        &self.ranges[0].0
+    }
+
+    /// Returns the quality value `self` assigns to the media type `media`: that
+    /// of the most specific range matching `media`, or `0` if no range does.
+    /// An exact range, like `text/html`, is more specific than a subtype
+    /// wildcard, like `text/*`, which is more specific than `*/*`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use rocket::http::{Accept, ContentType};
+    ///
+    /// let accept = Accept::from_str("text/*;q=0.5, text/html, */*;q=0.1").unwrap();
+    /// assert_eq!(accept.quality_of(&ContentType::HTML), 1.0);
+    /// assert_eq!(accept.quality_of(&ContentType::Plain), 0.5);
+    /// assert_eq!(accept.quality_of(&ContentType::JSON), 0.1);
+    /// ```
+    pub fn quality_of(&self, media: &ContentType) -> f32 {
+        let specificity = |range: &ContentType| {
+            (range.ttype != "*") as u8 + (range.subtype != "*") as u8
+        };
+
+        let mut best: Option<(u8, f32)> = None;
+        for &(ref range, q) in &self.ranges {
+            if matches(range, media) {
+                let rank = specificity(range);
+                if best.map_or(true, |(best_rank, _)| rank > best_rank) {
+                    best = Some((rank, q));
+                }
+            }
+        }
+
+        best.map_or(0.0, |(_, q)| q)
+    }
+
+    /// Returns `true` if `self` accepts the media type `media`: its
+    /// [quality](#method.quality_of) is greater than `0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use rocket::http::{Accept, ContentType};
+    ///
+    /// let accept = Accept::from_str("application/*, application/xml;q=0").unwrap();
+    /// assert!(accept.accepts(&ContentType::JSON));
+    /// assert!(!accept.accepts(&ContentType::new("application", "xml")));
+    /// assert!(!accept.accepts(&ContentType::HTML));
+    /// ```
+    #[inline]
+    pub fn accepts(&self, media: &ContentType) -> bool {
+        self.quality_of(media) > 0.0
+    }
+
+    /// Chooses the most preferred media type among those in `supported`,
+    /// returning `None` if none of them is acceptable.
+    ///
+    /// Ranges are considered in order of preference, and the first supported
+    /// media type matched by a range is chosen, so ties are broken by the
+    /// order of `supported`. Media types matched by a range with quality `0`
+    /// are never chosen.
+    pub fn negotiate<'s>(&self, supported: &'s [ContentType]) -> Option<&'s ContentType> {
+        let excluded = |media: &ContentType| {
+            self.ranges.iter().any(|&(ref range, q)| q == 0.0 && matches(range, media))
+        };
+
+        for &(ref range, q) in &self.ranges {
+            if q == 0.0 {
+                continue;
+            }
+
+            let found = supported.iter().find(|media| matches(range, media) && !excluded(media));
+            if found.is_some() {
+                return found;
+            }
+        }
+
+        None
+    }
+}
+
+impl FromStr for Accept {
+    type Err = &'static str;
+
+    /// Parses the value of an `Accept` header. Returns an `Err` if any element
+    /// is malformed. An empty value is equivalent to a missing header and
+    /// accepts any media type.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use rocket::http::Accept;
+    ///
+    /// assert!(Accept::from_str("text/html, */*;q=0.8").is_ok());
+    /// assert!(Accept::from_str("text/html;q=2").is_err());
+    /// assert!(Accept::from_str("html").is_err());
+    /// ```
+    fn from_str(string: &str) -> Result<Accept, &'static str> {
+        let mut ranges = vec![];
+        for element in string.split(',').filter(|e| !e.trim().is_empty()) {
+            ranges.push(parse_element(element)?);
+        }
+
+        Ok(Accept::from_ranges(ranges))
+    }
+}
+
+impl fmt::Display for Accept {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, &(ref media, q)) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{}", media)?;
+            if q != 1.0 {
+                write!(f, ";q={}", q)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::Accept;
+    use http::ContentType;
+
+    #[test]
+    fn test_parse() {
+        let accept = Accept::from_str("text/*;q=0.3, text/html;q=0.7, text/html;level=1, \
+                                       */*;q=0.5").unwrap();
+        let ranges: Vec<_> = accept.media_types().map(|(m, q)| (m.to_string(), q)).collect();
+        assert_eq!(ranges, vec![("text/html; level=1".to_string(), 1.0),
+                                ("text/html".to_string(), 0.7),
+                                ("*/*".to_string(), 0.5),
+                                ("text/*".to_string(), 0.3)]);
+
+        assert!(Accept::from_str("*/html").is_err());
+        assert!(Accept::from_str("text/html;q=0.1234").is_err());
+        assert_eq!(Accept::from_str("").unwrap(), Accept::any());
+        assert_eq!(Accept::parse_lossy("bad, text/plain").preferred(),
+                   &ContentType::new("text", "plain"));
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let supported = [ContentType::JSON, ContentType::HTML];
+        let accept = Accept::from_str("application/*;q=0.5, text/html").unwrap();
+        assert_eq!(accept.negotiate(&supported), Some(&ContentType::HTML));
+
+        let accept = Accept::from_str("*/*, text/html;q=0").unwrap();
+        assert_eq!(accept.negotiate(&supported[1..]), None);
+        assert_eq!(accept.negotiate(&supported), Some(&ContentType::JSON));
+    }
+}
@@ -0,0 +1,343 @@
+use std::ascii::AsciiExt;
+use std::str::FromStr;
+use std::fmt;
+
+/// The value of an `Accept-Language` request header: a list of language
+/// ranges ordered by preference.
+///
+/// Each language range, such as `en-US`, `fr`, or `*`, has a quality value
+/// between `0` and `1`, defaulting to `1`. Ranges are ordered by descending
+/// quality; ranges with equal quality keep the order they were sent in. A
+/// range with quality `0` marks matching languages as unacceptable. See [RFC
+/// 7231 §5.3.5] for details.
+///
+/// [RFC 7231 §5.3.5]: https://tools.ietf.org/html/rfc7231#section-5.3.5
+///
+/// # Request Guard
+///
+/// `AcceptLanguage` is a request guard that always succeeds. A missing or
+/// empty header results in an `AcceptLanguage` that accepts any language.
+/// Malformed elements in the header are ignored.
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// #
+/// use rocket::http::AcceptLanguage;
+///
+/// #[get("/")]
+/// fn index(languages: AcceptLanguage) -> &'static str {
+///     match languages.negotiate(&["en", "de", "fr"]) {
+///         Some("de") => "Hallo, Welt!",
+///         Some("fr") => "Bonjour, le monde !",
+///         _ => "Hello, world!"
+///     }
+/// }
+/// #
+/// # fn main() { }
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use rocket::http::AcceptLanguage;
+///
+/// let accept = AcceptLanguage::from_str("fr-CH, fr;q=0.9, en;q=0.8, de;q=0.7, *;q=0.5")
+///     .unwrap();
+///
+/// assert_eq!(accept.negotiate(&["en", "fr"]), Some("fr"));
+/// assert_eq!(accept.negotiate(&["de", "en-GB"]), Some("en-GB"));
+/// assert_eq!(accept.negotiate(&["ja"]), Some("ja"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcceptLanguage {
+    ranges: Vec<(String, f32)>,
+}
+
+/// Returns `true` if `range` is a syntactically valid language range: `*` or
+/// one to eight letters followed by any number of `-`-prefixed subtags of one
+/// to eight letters or digits.
+fn is_valid_range(range: &str) -> bool {
+    if range == "*" {
+        return true;
+    }
+
+    range.split('-').enumerate().all(|(i, subtag)| {
+        !subtag.is_empty() && subtag.len() <= 8 && subtag.chars().all(|c: char| {
+            c.is_ascii() && (c.is_alphabetic() || (i > 0 && c.is_digit(10)))
+        })
+    })
+}
+
+/// Parses a quality value: `0` or `1` followed by up to three decimal digits.
+pub fn parse_quality(string: &str) -> Option<f32> {
+    let valid = match string.as_bytes().first() {
+        Some(&b'0') | Some(&b'1') => string.len() <= 5,
+        _ => false
+    };
+
+    match string.parse::<f32>() {
+        Ok(q) if valid && q >= 0.0 && q <= 1.0 => Some(q),
+        _ => None
+    }
+}
+
+/// Returns `true` if the language tag `tag` is matched by the language range
+/// `range` under basic filtering: the range is `*`, equals the tag, or is a
+/// prefix of the tag ending at a `-`.
+fn filters(range: &str, tag: &str) -> bool {
+    range == "*" || tag.eq_ignore_ascii_case(range)
+        || (tag.len() > range.len() && tag.as_bytes()[range.len()] == b'-'
+            && tag[..range.len()].eq_ignore_ascii_case(range))
+}
+
+/// Parses a single element of an `Accept-Language` header: a language range
+/// optionally followed by a quality value.
+fn parse_element(element: &str) -> Result<(String, f32), &'static str> {
+    let mut parts = element.split(';');
+    let range = parts.next().unwrap_or("").trim();
+    if !is_valid_range(range) {
+        return Err("Invalid language range.");
+    }
+
+    let mut quality = 1.0;
+    for param in parts {
+        let mut kv = param.splitn(2, '=');
+        match (kv.next().map(|k| k.trim()), kv.next().map(|v| v.trim())) {
+            (Some("q"), Some(q)) | (Some("Q"), Some(q)) => {
+                quality = parse_quality(q).ok_or("Invalid quality value.")?;
+            }
+            _ => return Err("Invalid language range parameter.")
+        }
+    }
+
+    Ok((range.to_string(), quality))
+}
+
+impl AcceptLanguage {
+    /// Orders `ranges` by preference. Returns `AcceptLanguage::any()` if
+    /// `ranges` is empty.
+    fn from_ranges(mut ranges: Vec<(String, f32)>) -> AcceptLanguage {
+        if ranges.is_empty() {
+            return AcceptLanguage::any();
+        }
+
+        // `sort_by` is stable, so ranges of equal quality keep their order.
+        ranges.sort_by(|a, b| b.1.partial_cmp(&a.1).expect("qualities are numbers"));
+        AcceptLanguage { ranges: ranges }
+    }
+
+    /// Parses the value of an `Accept-Language` header, ignoring malformed
+    /// elements. Used by the request guard, where a single bad element
+    /// shouldn't cost the client its other preferences.
+    #[doc(hidden)]
+    pub fn parse_lossy(string: &str) -> AcceptLanguage {
+        let ranges = string.split(',')
+            .filter(|e| !e.trim().is_empty())
+            .filter_map(|e| parse_element(e).ok())
+            .collect();
+
+        AcceptLanguage::from_ranges(ranges)
+    }
+
+    /// Returns an `AcceptLanguage` that accepts any language. This is the
+    /// meaning of a missing `Accept-Language` header.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::AcceptLanguage;
+    ///
+    /// let any = AcceptLanguage::any();
+    /// assert_eq!(any.negotiate(&["de", "en"]), Some("de"));
+    /// ```
+    #[inline(always)]
+    pub fn any() -> AcceptLanguage {
+        AcceptLanguage { ranges: vec![("*".to_string(), 1.0)] }
+    }
+
+    /// Returns the language ranges in `self` and their quality values, in
+    /// order of preference.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use rocket::http::AcceptLanguage;
+    ///
+    /// let accept = AcceptLanguage::from_str("da, en-GB;q=0.8, en;q=0.9").unwrap();
+    /// let ranges: Vec<_> = accept.ranges().collect();
+    /// assert_eq!(ranges, vec![("da", 1.0), ("en", 0.9), ("en-GB", 0.8)]);
+    /// ```
+    pub fn ranges<'a>(&'a self) -> impl Iterator<Item=(&'a str, f32)> + 'a {
+        self.ranges.iter().map(|&(ref range, q)| (range.as_str(), q))
+    }
+
+    /// Chooses the most preferred language among the language tags in
+    /// `supported`, returning `None` if none of them is acceptable.
+    ///
+    /// Ranges are considered in order of preference. For each range, a
+    /// supported tag that the range matches exactly is chosen first, followed
+    /// by the first supported tag that begins with the range, such as `en-GB`
+    /// for the range `en`. Failing that, the range is truncated, subtag by
+    /// subtag, and compared again, so that the range `de-AT` falls back to the
+    /// supported tag `de` before less preferred ranges are considered. Tags
+    /// matched by a range with quality `0` are never chosen. Ties between
+    /// supported tags are broken by their order in `supported`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use rocket::http::AcceptLanguage;
+    ///
+    /// let accept = AcceptLanguage::from_str("de-AT, en;q=0.5").unwrap();
+    /// assert_eq!(accept.negotiate(&["en", "de"]), Some("de"));
+    /// assert_eq!(accept.negotiate(&["en-US", "fr"]), Some("en-US"));
+    /// assert_eq!(accept.negotiate(&["fr"]), None);
+    ///
+    /// let accept = AcceptLanguage::from_str("*, fr;q=0").unwrap();
+    /// assert_eq!(accept.negotiate(&["fr", "it"]), Some("it"));
+    /// ```
+    pub fn negotiate<'s>(&self, supported: &[&'s str]) -> Option<&'s str> {
+        let excluded = |tag: &str| {
+            self.ranges.iter()
+                .filter(|&&(ref range, q)| q == 0.0 && range != "*")
+                .any(|&(ref range, _)| filters(range, tag))
+        };
+
+        let acceptable: Vec<&'s str> = supported.iter()
+            .map(|tag| *tag)
+            .filter(|tag| !excluded(tag))
+            .collect();
+
+        let eq = |range: &str| acceptable.iter().find(|tag| tag.eq_ignore_ascii_case(range));
+        for &(ref range, q) in &self.ranges {
+            if q == 0.0 {
+                continue;
+            }
+
+            let prefix = acceptable.iter().find(|tag| filters(range, tag));
+            if let Some(tag) = eq(range).or(prefix) {
+                return Some(*tag);
+            }
+
+            let mut truncated = range.as_str();
+            while let Some(i) = truncated.rfind('-') {
+                truncated = &truncated[..i];
+                if let Some(tag) = eq(truncated) {
+                    return Some(*tag);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl FromStr for AcceptLanguage {
+    type Err = &'static str;
+
+    /// Parses the value of an `Accept-Language` header. Returns an `Err` if
+    /// any element is malformed. An empty value is equivalent to a missing
+    /// header and accepts any language.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use rocket::http::AcceptLanguage;
+    ///
+    /// assert!(AcceptLanguage::from_str("en-US, en;q=0.5").is_ok());
+    /// assert!(AcceptLanguage::from_str("en;q=2").is_err());
+    /// assert!(AcceptLanguage::from_str("en_US").is_err());
+    /// ```
+    fn from_str(string: &str) -> Result<AcceptLanguage, &'static str> {
+        let mut ranges = vec![];
+        for element in string.split(',').filter(|e| !e.trim().is_empty()) {
+            ranges.push(parse_element(element)?);
+        }
+
+        Ok(AcceptLanguage::from_ranges(ranges))
+    }
+}
+
+impl fmt::Display for AcceptLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, &(ref range, q)) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{}", range)?;
+            if q != 1.0 {
+                write!(f, ";q={}", q)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AcceptLanguage;
+    use std::str::FromStr;
+
+    fn accept(string: &str) -> AcceptLanguage {
+        AcceptLanguage::from_str(string).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let ranges: Vec<_> = accept("en;q=0.5, de, *;q=0.1, fr;Q=0.5").ranges()
+            .map(|(r, q)| (r.to_string(), q))
+            .collect();
+
+        assert_eq!(ranges, vec![("de".to_string(), 1.0), ("en".to_string(), 0.5),
+                                ("fr".to_string(), 0.5), ("*".to_string(), 0.1)]);
+
+        assert_eq!(accept(""), AcceptLanguage::any());
+        assert_eq!(accept(" , "), AcceptLanguage::any());
+        assert!(AcceptLanguage::from_str("zh-Hant-TW, sr-Latn-RS-1994").is_ok());
+
+        assert!(AcceptLanguage::from_str("en;q=").is_err());
+        assert!(AcceptLanguage::from_str("en;q=1.5").is_err());
+        assert!(AcceptLanguage::from_str("en;q=0.12345").is_err());
+        assert!(AcceptLanguage::from_str("en;level=1").is_err());
+        assert!(AcceptLanguage::from_str("toolongtag").is_err());
+        assert!(AcceptLanguage::from_str("1en").is_err());
+        assert!(AcceptLanguage::from_str("en--US").is_err());
+    }
+
+    #[test]
+    fn test_parse_lossy() {
+        assert_eq!(AcceptLanguage::parse_lossy("en_US, de;q=0.5, fr;q=2"), accept("de;q=0.5"));
+        assert_eq!(AcceptLanguage::parse_lossy("??"), AcceptLanguage::any());
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(accept("en-US").negotiate(&["en", "en-US"]), Some("en-US"));
+        assert_eq!(accept("EN-us").negotiate(&["en-US"]), Some("en-US"));
+        assert_eq!(accept("en").negotiate(&["en-GB", "en-US"]), Some("en-GB"));
+        assert_eq!(accept("en-US").negotiate(&["en-GB", "en"]), Some("en"));
+        assert_eq!(accept("fr, en;q=0.1").negotiate(&["en-US"]), Some("en-US"));
+        assert_eq!(accept("fr").negotiate(&["en"]), None);
+        assert_eq!(accept("fr").negotiate(&[]), None);
+    }
+
+    #[test]
+    fn test_negotiate_exclusions() {
+        assert_eq!(accept("*;q=0.5, en;q=0").negotiate(&["en-US", "de"]), Some("de"));
+        assert_eq!(accept("en, en-GB;q=0").negotiate(&["en-GB", "en-US"]), Some("en-US"));
+        assert_eq!(accept("*;q=0").negotiate(&["en"]), None);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(accept("en;q=0.5, de").to_string(), "de, en;q=0.5");
+    }
+}
@@ -0,0 +1,299 @@
+use std::ascii::AsciiExt;
+use std::str::FromStr;
+use std::fmt;
+
+use http::Header;
+use http::base64;
+
+/// The credentials in an `Authorization` header.
+///
+/// Two authentication schemes are supported: `Basic`, defined in [RFC 7617],
+/// whose credentials are a base64 encoded username and password, and
+/// `Bearer`, defined in [RFC 6750], whose credentials are an opaque token.
+/// Scheme names are case-insensitive.
+///
+/// [RFC 7617]: https://tools.ietf.org/html/rfc7617
+/// [RFC 6750]: https://tools.ietf.org/html/rfc6750
+///
+/// # Request Guard
+///
+/// `Authorization` is a request guard. If the request has no `Authorization`
+/// header, the guard fails with a status of `401 Unauthorized`. If the header
+/// is malformed or uses an unsupported scheme, the guard fails with a status
+/// of `400 Bad Request`. Use `Option<Authorization>` to accept requests with
+/// and without credentials.
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// #
+/// use rocket::http::Authorization;
+///
+/// #[get("/whoami")]
+/// fn whoami(auth: Authorization) -> String {
+///     match auth {
+///         Authorization::Basic { username, .. } => username,
+///         Authorization::Bearer(_) => "a token holder".to_string()
+///     }
+/// }
+/// #
+/// # fn main() { }
+/// ```
+///
+/// # Header
+///
+/// `Authorization` implements `Into<Header>`, producing an `Authorization`
+/// header, which is useful when testing:
+///
+/// ```rust
+/// use rocket::http::{Authorization, Header};
+///
+/// let header: Header = Authorization::basic("Aladdin", "open sesame").into();
+/// assert_eq!(header.value, "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==");
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum Authorization {
+    /// `Basic` credentials: a username and a password.
+    Basic {
+        /// The username. Never contains a `:`.
+        username: String,
+        /// The password.
+        password: String,
+    },
+    /// `Bearer` credentials: an opaque token.
+    Bearer(String),
+}
+
+/// Returns `true` if `token` is a valid `token68`: one or more letters,
+/// digits, or any of `-._~+/`, followed by any number of `=`.
+fn is_valid_token68(token: &str) -> bool {
+    let body = token.trim_right_matches('=');
+    !body.is_empty() && body.chars().all(|c: char| {
+        c.is_ascii() && (c.is_alphanumeric() || "-._~+/".contains(c))
+    })
+}
+
+impl Authorization {
+    /// Returns `Basic` credentials for the username `username` and password
+    /// `password`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `username` contains a `:`, as such a username can't be
+    /// represented in `Basic` credentials.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Authorization;
+    ///
+    /// let auth = Authorization::basic("user", "pass");
+    /// assert_eq!(auth.username(), Some("user"));
+    /// ```
+    pub fn basic<U: Into<String>, P: Into<String>>(username: U, password: P) -> Authorization {
+        let username = username.into();
+        if username.contains(':') {
+            panic!("Basic credentials' usernames cannot contain ':'.");
+        }
+
+        Authorization::Basic { username: username, password: password.into() }
+    }
+
+    /// Returns `Bearer` credentials with the token `token`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Authorization;
+    ///
+    /// let auth = Authorization::bearer("mF_9.B5f-4.1JqM");
+    /// assert_eq!(auth.token(), Some("mF_9.B5f-4.1JqM"));
+    /// ```
+    #[inline(always)]
+    pub fn bearer<T: Into<String>>(token: T) -> Authorization {
+        Authorization::Bearer(token.into())
+    }
+
+    /// Returns the username in `self` if `self` holds `Basic` credentials.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Authorization;
+    ///
+    /// assert_eq!(Authorization::basic("me", "pw").username(), Some("me"));
+    /// assert_eq!(Authorization::bearer("x").username(), None);
+    /// ```
+    #[inline]
+    pub fn username(&self) -> Option<&str> {
+        match *self {
+            Authorization::Basic { ref username, .. } => Some(username),
+            Authorization::Bearer(_) => None
+        }
+    }
+
+    /// Returns the password in `self` if `self` holds `Basic` credentials.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Authorization;
+    ///
+    /// assert_eq!(Authorization::basic("me", "pw").password(), Some("pw"));
+    /// assert_eq!(Authorization::bearer("x").password(), None);
+    /// ```
+    #[inline]
+    pub fn password(&self) -> Option<&str> {
+        match *self {
+            Authorization::Basic { ref password, .. } => Some(password),
+            Authorization::Bearer(_) => None
+        }
+    }
+
+    /// Returns the token in `self` if `self` holds `Bearer` credentials.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Authorization;
+    ///
+    /// assert_eq!(Authorization::bearer("x").token(), Some("x"));
+    /// assert_eq!(Authorization::basic("me", "pw").token(), None);
+    /// ```
+    #[inline]
+    pub fn token(&self) -> Option<&str> {
+        match *self {
+            Authorization::Bearer(ref token) => Some(token),
+            Authorization::Basic { .. } => None
+        }
+    }
+}
+
+impl FromStr for Authorization {
+    type Err = &'static str;
+
+    /// Parses the value of an `Authorization` header. Returns an `Err` if the
+    /// scheme is unsupported or the credentials are malformed. The decoded
+    /// `Basic` credentials must be valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use rocket::http::Authorization;
+    ///
+    /// let auth = Authorization::from_str("basic dXNlcjpwYTpzcw==").unwrap();
+    /// assert_eq!(auth, Authorization::basic("user", "pa:ss"));
+    ///
+    /// let auth = Authorization::from_str("Bearer mF_9.B5f-4.1JqM").unwrap();
+    /// assert_eq!(auth, Authorization::bearer("mF_9.B5f-4.1JqM"));
+    ///
+    /// assert!(Authorization::from_str("Basic !!!").is_err());
+    /// assert!(Authorization::from_str("Digest username=x").is_err());
+    /// ```
+    fn from_str(string: &str) -> Result<Authorization, &'static str> {
+        let string = string.trim();
+        let (scheme, credentials) = match string.find(' ') {
+            Some(i) => (&string[..i], string[i..].trim_left()),
+            None => return Err("Missing credentials."),
+        };
+
+        if scheme.eq_ignore_ascii_case("Basic") {
+            let decoded = base64::decode(credentials)?;
+            let decoded = String::from_utf8(decoded).map_err(|_| "Credentials aren't UTF-8.")?;
+            let colon = decoded.find(':').ok_or("Missing ':' in Basic credentials.")?;
+            Ok(Authorization::Basic {
+                username: decoded[..colon].to_string(),
+                password: decoded[(colon + 1)..].to_string(),
+            })
+        } else if scheme.eq_ignore_ascii_case("Bearer") {
+            if !is_valid_token68(credentials) {
+                return Err("Invalid Bearer token.");
+            }
+
+            Ok(Authorization::Bearer(credentials.to_string()))
+        } else {
+            Err("Unsupported authorization scheme.")
+        }
+    }
+}
+
+impl fmt::Display for Authorization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Authorization::Basic { ref username, ref password } => {
+                let credentials = format!("{}:{}", username, password);
+                write!(f, "Basic {}", base64::encode(credentials.as_bytes()))
+            }
+            Authorization::Bearer(ref token) => write!(f, "Bearer {}", token),
+        }
+    }
+}
+
+/// Credentials are secrets, so they're never printed in full.
+impl fmt::Debug for Authorization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Authorization::Basic { ref username, .. } => {
+                write!(f, "Basic {{ username: {:?}, password: \"..\" }}", username)
+            }
+            Authorization::Bearer(_) => write!(f, "Bearer(\"..\")"),
+        }
+    }
+}
+
+impl Into<Header<'static>> for Authorization {
+    #[inline(always)]
+    fn into(self) -> Header<'static> {
+        Header::new("Authorization", self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Authorization;
+    use std::str::FromStr;
+
+    fn parse(string: &str) -> Result<Authorization, &'static str> {
+        Authorization::from_str(string)
+    }
+
+    #[test]
+    fn test_basic() {
+        assert_eq!(parse("Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="),
+                   Ok(Authorization::basic("Aladdin", "open sesame")));
+        assert_eq!(parse("  BASIC   QWxhZGRpbjpvcGVuIHNlc2FtZQ  "),
+                   Ok(Authorization::basic("Aladdin", "open sesame")));
+        assert_eq!(parse("Basic Og=="), Ok(Authorization::basic("", "")));
+        assert_eq!(parse("Basic w7w6w7Y="), Ok(Authorization::basic("ü", "ö")));
+
+        assert!(parse("Basic").is_err());
+        assert!(parse("Basic dXNlcg==").is_err());
+        assert!(parse("Basic /w==").is_err());
+        assert!(parse("Basic QWxh ZGRp").is_err());
+    }
+
+    #[test]
+    fn test_bearer() {
+        assert_eq!(parse("bearer abc.def=="), Ok(Authorization::bearer("abc.def==")));
+        assert!(parse("Bearer").is_err());
+        assert!(parse("Bearer =").is_err());
+        assert!(parse("Bearer a b").is_err());
+        assert!(parse("Bearer a,b").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for auth in vec![Authorization::basic("a", "b:c"), Authorization::bearer("t0k3n")] {
+            assert_eq!(parse(&auth.to_string()), Ok(auth));
+        }
+    }
+
+    #[test]
+    fn test_debug_hides_secrets() {
+        let debug = format!("{:?}", Authorization::basic("user", "hunter2"));
+        assert!(debug.contains("user") && !debug.contains("hunter2"));
+        assert!(!format!("{:?}", Authorization::bearer("secret")).contains("secret"));
+    }
+}
@@ -0,0 +1,87 @@
+//! Standard (RFC 4648 §4) base64 encoding and decoding.
+
+const ALPHABET: &'static [u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Returns the 6-bit value of the base64 character `c`, if it is one.
+#[inline]
+fn value_of(c: u8) -> Option<u8> {
+    match c {
+        b'A'...b'Z' => Some(c - b'A'),
+        b'a'...b'z' => Some(c - b'a' + 26),
+        b'0'...b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None
+    }
+}
+
+/// Encodes `bytes` as padded base64.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = ((b[0] as u32) << 16) | ((b[1] as u32) << 8) | (b[2] as u32);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
+
+/// Decodes the base64 string `string`. Padding is optional, but if present,
+/// must be correct. Returns an `Err` if `string` isn't valid base64.
+pub fn decode(string: &str) -> Result<Vec<u8>, &'static str> {
+    let data = string.trim_right_matches('=').as_bytes();
+    let padding = string.len() - data.len();
+    if padding > 2 || (padding > 0 && string.len() % 4 != 0) || data.len() % 4 == 1 {
+        return Err("Invalid base64 length.");
+    }
+
+    let mut out = Vec::with_capacity(data.len() * 3 / 4);
+    for chunk in data.chunks(4) {
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let value = value_of(c).ok_or("Invalid base64 character.")?;
+            n |= (value as u32) << (18 - 6 * i);
+        }
+
+        let bytes = [(n >> 16) as u8, (n >> 8) as u8, n as u8];
+        out.extend_from_slice(&bytes[..(chunk.len() - 1)]);
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{encode, decode};
+
+    #[test]
+    fn test_round_trip() {
+        let cases: &[(&str, &str)] = &[
+            ("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="), ("fooba", "Zm9vYmE="), ("foobar", "Zm9vYmFy"),
+        ];
+
+        for &(plain, encoded) in cases {
+            assert_eq!(encode(plain.as_bytes()), encoded);
+            assert_eq!(decode(encoded), Ok(plain.as_bytes().to_vec()));
+            assert_eq!(decode(encoded.trim_right_matches('=')), Ok(plain.as_bytes().to_vec()));
+        }
+    }
+
+    #[test]
+    fn test_bad_decode() {
+        assert!(decode("Zm9v!").is_err());
+        assert!(decode("Z").is_err());
+        assert!(decode("Zg=").is_err());
+        assert!(decode("Zg===").is_err());
+        assert!(decode("Zm 9v").is_err());
+    }
+}
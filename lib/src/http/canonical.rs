@@ -0,0 +1,257 @@
+use std::ascii::AsciiExt;
+use std::borrow::Cow;
+use std::mem;
+
+use http::HeaderMap;
+
+/// Header names with a canonical spelling. Names matching one of these, in any
+/// case, are renamed to it.
+const KNOWN_NAMES: &'static [&'static str] = &[
+    "Accept", "Accept-Charset", "Accept-Encoding", "Accept-Language",
+    "Authorization", "Cache-Control", "Connection", "Content-Disposition",
+    "Content-Encoding", "Content-Language", "Content-Length", "Content-MD5",
+    "Content-Type", "Cookie", "Date", "Digest", "DNT", "Expect", "Forwarded",
+    "From", "Host", "If-Match", "If-Modified-Since", "If-None-Match", "If-Range",
+    "If-Unmodified-Since", "Keep-Alive", "Max-Forwards", "Origin", "Pragma",
+    "Proxy-Authorization", "Proxy-Connection", "Range", "Referer", "TE",
+    "Trailer", "Transfer-Encoding", "Upgrade", "User-Agent", "Via", "Warning",
+    "X-Forwarded-For", "X-Forwarded-Host", "X-Forwarded-Proto", "X-Real-IP",
+    "X-Requested-With",
+];
+
+/// Headers that may appear at most once in a request by default.
+const SINGLETONS: &'static [&'static str] = &[
+    "Authorization", "Content-Length", "Content-MD5", "Content-Type", "From",
+    "Host", "If-Modified-Since", "If-Range", "If-Unmodified-Since",
+    "Max-Forwards", "Proxy-Authorization", "Range", "Referer", "User-Agent",
+];
+
+/// Headers that are never stripped, even when listed in `Connection`: the
+/// request can't be understood without them.
+const END_TO_END: &'static [&'static str] = &[
+    "Connection", "Content-Length", "Content-Type", "Host",
+];
+
+/// How a [HeaderPolicy](struct.HeaderPolicy.html) treats singleton headers
+/// that appear more than once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Duplicates {
+    /// Keep the first value and discard the rest.
+    First,
+    /// Reject the request with `400 Bad Request`.
+    Reject,
+}
+
+/// A policy for canonicalizing the headers of incoming requests.
+///
+/// Clients and proxies spell, repeat, and annotate headers in many ways. When
+/// a policy is installed via
+/// [Rocket::canonicalize_headers](/rocket/struct.Rocket.html#method.canonicalize_headers),
+/// every request's headers are canonicalized before anything else sees them,
+/// so request guards see a single, canonical view:
+///
+///   * Well-known header names are renamed to their canonical spelling, such
+///     as `Content-Type` for `content-type`, merging their values.
+///   * Singleton headers, such as `Host` and `Content-Type`, that appear more
+///     than once are reduced to their first value or cause the request to be
+///     rejected, as set via [duplicates](#method.duplicates).
+///   * Headers named in the `Connection` header are hop-by-hop and are
+///     stripped. `Host`, `Content-Type`, and `Content-Length` are never
+///     stripped.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::{HeaderMap, HeaderPolicy, Duplicates};
+///
+/// let mut headers = HeaderMap::new();
+/// headers.add_raw("content-type", "text/plain");
+/// headers.add_raw("Host", "rocket.rs");
+/// headers.add_raw("Host", "example.com");
+/// headers.add_raw("Connection", "close, X-Trace");
+/// headers.add_raw("X-Trace", "1");
+///
+/// let policy = HeaderPolicy::new();
+/// assert!(policy.apply(&mut headers.clone()).is_ok());
+///
+/// let policy = HeaderPolicy::new().duplicates(Duplicates::Reject);
+/// assert!(policy.apply(&mut headers.clone()).is_err());
+///
+/// HeaderPolicy::new().apply(&mut headers).unwrap();
+/// assert_eq!(headers.get_one("Content-Type"), Some("text/plain"));
+/// assert_eq!(headers.get_one("Host"), Some("rocket.rs"));
+/// assert!(!headers.contains("X-Trace"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderPolicy {
+    duplicates: Duplicates,
+    singletons: Vec<Cow<'static, str>>,
+    strip_hop_by_hop: bool,
+}
+
+impl HeaderPolicy {
+    /// Returns the default policy: duplicate singletons are reduced to their
+    /// first value, and headers named in `Connection` are stripped.
+    pub fn new() -> HeaderPolicy {
+        HeaderPolicy {
+            duplicates: Duplicates::First,
+            singletons: SINGLETONS.iter().map(|&name| Cow::Borrowed(name)).collect(),
+            strip_hop_by_hop: true,
+        }
+    }
+
+    /// Sets how singleton headers that appear more than once are treated.
+    #[inline]
+    pub fn duplicates(mut self, duplicates: Duplicates) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Adds the header named `name` to the headers that may appear at most
+    /// once. Its name is canonicalized to `name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::{HeaderMap, HeaderPolicy, Duplicates};
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.add_raw("x-api-key", "one");
+    /// headers.add_raw("X-API-Key", "two");
+    ///
+    /// let policy = HeaderPolicy::new().singleton("X-API-Key");
+    /// policy.apply(&mut headers).unwrap();
+    /// assert_eq!(headers.get("X-API-Key").count(), 1);
+    /// ```
+    pub fn singleton<N: Into<Cow<'static, str>>>(mut self, name: N) -> Self {
+        self.singletons.push(name.into());
+        self
+    }
+
+    /// Sets whether headers named in the `Connection` header are stripped.
+    #[inline]
+    pub fn strip_hop_by_hop(mut self, strip: bool) -> Self {
+        self.strip_hop_by_hop = strip;
+        self
+    }
+
+    /// Returns the canonical spelling of `name`, if it has one.
+    fn canonical_name(&self, name: &str) -> Option<Cow<'static, str>> {
+        KNOWN_NAMES.iter()
+            .find(|known| known.eq_ignore_ascii_case(name))
+            .map(|&known| Cow::Borrowed(known))
+            .or_else(|| {
+                self.singletons.iter()
+                    .find(|singleton| singleton.eq_ignore_ascii_case(name))
+                    .cloned()
+            })
+    }
+
+    /// Canonicalizes `headers` according to this policy. Returns an error
+    /// describing the problem if the headers are rejected, in which case
+    /// `headers` may be partially canonicalized.
+    pub fn apply<'h>(&self, headers: &mut HeaderMap<'h>) -> Result<(), String> {
+        let entries: Vec<_> = mem::replace(headers, HeaderMap::new())
+            .into_iter_raw()
+            .collect();
+
+        // The names listed in `Connection`, which are hop-by-hop.
+        let hop_by_hop: Vec<String> = if self.strip_hop_by_hop {
+            entries.iter()
+                .filter(|&&(ref name, _)| name.eq_ignore_ascii_case("Connection"))
+                .flat_map(|&(_, ref values)| values.iter())
+                .flat_map(|value| value.split(','))
+                .map(|token| token.trim().to_string())
+                .filter(|token| !token.is_empty())
+                .filter(|token| !END_TO_END.iter().any(|n| n.eq_ignore_ascii_case(token)))
+                .collect()
+        } else {
+            vec![]
+        };
+
+        for (name, mut values) in entries {
+            if hop_by_hop.iter().any(|token| token.eq_ignore_ascii_case(&name)) {
+                continue;
+            }
+
+            match self.canonical_name(&name) {
+                Some(canonical) => headers.add_all(canonical, &mut values),
+                None => headers.add_all(name, &mut values),
+            }
+        }
+
+        for singleton in &self.singletons {
+            let mut values = headers.take(singleton);
+            if values.len() > 1 && self.duplicates == Duplicates::Reject {
+                return Err(format!("'{}' header appears {} times", singleton, values.len()));
+            }
+
+            if !values.is_empty() {
+                values.truncate(1);
+                headers.add_all(singleton.clone(), &mut values);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for HeaderPolicy {
+    #[inline]
+    fn default() -> HeaderPolicy {
+        HeaderPolicy::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{HeaderPolicy, Duplicates};
+    use http::HeaderMap;
+
+    #[test]
+    fn test_canonical_names() {
+        let mut headers = HeaderMap::new();
+        headers.add_raw("dnt", "1");
+        headers.add_raw("accept", "text/html");
+        headers.add_raw("ACCEPT", "text/plain");
+        headers.add_raw("x-custom", "value");
+
+        HeaderPolicy::new().apply(&mut headers).unwrap();
+        assert_eq!(headers.get("Accept").count(), 2);
+        assert!(!headers.contains("accept"));
+        assert_eq!(headers.get_one("DNT"), Some("1"));
+        assert!(headers.contains("x-custom"));
+        assert_eq!(headers.len(), 4);
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let mut headers = HeaderMap::new();
+        headers.add_raw("Content-Length", "10");
+        headers.add_raw("content-length", "20");
+
+        let policy = HeaderPolicy::new().duplicates(Duplicates::Reject);
+        assert!(policy.apply(&mut headers.clone()).is_err());
+
+        HeaderPolicy::new().apply(&mut headers).unwrap();
+        assert_eq!(headers.get("Content-Length").count(), 1);
+    }
+
+    #[test]
+    fn test_hop_by_hop() {
+        let mut headers = HeaderMap::new();
+        headers.add_raw("Connection", "keep-alive, x-hop, Host");
+        headers.add_raw("Keep-Alive", "timeout=5");
+        headers.add_raw("X-Hop", "1");
+        headers.add_raw("Host", "rocket.rs");
+
+        let mut kept = headers.clone();
+        HeaderPolicy::new().strip_hop_by_hop(false).apply(&mut kept).unwrap();
+        assert!(kept.contains("X-Hop") && kept.contains("Keep-Alive"));
+
+        HeaderPolicy::new().apply(&mut headers).unwrap();
+        assert!(!headers.contains("X-Hop") && !headers.contains("Keep-Alive"));
+        assert_eq!(headers.get_one("Host"), Some("rocket.rs"));
+        assert!(headers.contains("Connection"));
+    }
+}
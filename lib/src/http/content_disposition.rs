@@ -0,0 +1,228 @@
+use std::fmt;
+
+use http::Header;
+
+/// The value of a `Content-Disposition` response header.
+///
+/// A `Content-Disposition` header indicates whether a response should be
+/// displayed `inline` or downloaded as an `attachment`, optionally with a
+/// suggested filename. Filenames are always escaped correctly: ASCII filenames
+/// are sent as a quoted `filename` parameter, while filenames with non-ASCII
+/// characters are additionally sent as a UTF-8 encoded `filename*` parameter,
+/// as described in [RFC 6266] and [RFC 5987], with an ASCII approximation in
+/// `filename` for older clients.
+///
+/// [RFC 6266]: https://tools.ietf.org/html/rfc6266
+/// [RFC 5987]: https://tools.ietf.org/html/rfc5987
+///
+/// # Header
+///
+/// `ContentDisposition` implements `Into<Header>`, producing a
+/// `Content-Disposition` header:
+///
+/// ```rust
+/// use rocket::Response;
+/// use rocket::http::ContentDisposition;
+///
+/// let disposition = ContentDisposition::attachment().filename("résumé.pdf");
+/// # #[allow(unused_variables)]
+/// let response = Response::build().header(disposition).finalize();
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::ContentDisposition;
+///
+/// let disposition = ContentDisposition::attachment().filename("report \"Q3\".csv");
+/// assert_eq!(disposition.to_string(), r#"attachment; filename="report \"Q3\".csv""#);
+///
+/// let disposition = ContentDisposition::attachment().filename("€ rates.txt");
+/// assert_eq!(disposition.to_string(),
+///            "attachment; filename=\"_ rates.txt\"; filename*=UTF-8''%E2%82%AC%20rates.txt");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ContentDisposition {
+    attachment: bool,
+    filename: Option<String>,
+}
+
+/// Returns `true` if `b` is an `attr-char` as defined in RFC 5987 and may thus
+/// appear unencoded in an extended parameter value.
+#[inline]
+fn is_attr_char(b: u8) -> bool {
+    match b {
+        b'a'...b'z' | b'A'...b'Z' | b'0'...b'9' => true,
+        b'!' | b'#' | b'$' | b'&' | b'+' | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~' => true,
+        _ => false
+    }
+}
+
+/// Writes `name` as a quoted string, escaping quotes and backslashes. Control
+/// and non-ASCII characters are replaced with `_`.
+fn write_quoted(f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in name.chars() {
+        match c {
+            '"' | '\\' => write!(f, "\\{}", c)?,
+            ' '...'~' => write!(f, "{}", c)?,
+            _ => write!(f, "_")?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
+/// Writes `name` as an RFC 5987 extended value in the UTF-8 charset.
+fn write_extended(f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+    write!(f, "UTF-8''")?;
+    for b in name.bytes() {
+        if is_attr_char(b) {
+            write!(f, "{}", b as char)?;
+        } else {
+            write!(f, "%{:02X}", b)?;
+        }
+    }
+
+    Ok(())
+}
+
+impl ContentDisposition {
+    /// Creates a new `ContentDisposition` indicating that the response should
+    /// be displayed inline.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ContentDisposition;
+    ///
+    /// assert_eq!(ContentDisposition::inline().to_string(), "inline");
+    /// ```
+    #[inline(always)]
+    pub fn inline() -> ContentDisposition {
+        ContentDisposition { attachment: false, filename: None }
+    }
+
+    /// Creates a new `ContentDisposition` indicating that the response should
+    /// be downloaded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ContentDisposition;
+    ///
+    /// assert_eq!(ContentDisposition::attachment().to_string(), "attachment");
+    /// ```
+    #[inline(always)]
+    pub fn attachment() -> ContentDisposition {
+        ContentDisposition { attachment: true, filename: None }
+    }
+
+    /// Sets the suggested filename to `name`. Any directory components should
+    /// already have been removed from `name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ContentDisposition;
+    ///
+    /// let disposition = ContentDisposition::attachment().filename("data.json");
+    /// assert_eq!(disposition.to_string(), "attachment; filename=\"data.json\"");
+    /// ```
+    #[inline]
+    pub fn filename<S: Into<String>>(mut self, name: S) -> ContentDisposition {
+        self.filename = Some(name.into());
+        self
+    }
+
+    /// Returns `true` if `self` indicates that the response should be
+    /// downloaded.
+    #[inline(always)]
+    pub fn is_attachment(&self) -> bool {
+        self.attachment
+    }
+
+    /// Returns the suggested filename, if any, as it was set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ContentDisposition;
+    ///
+    /// let disposition = ContentDisposition::inline().filename("ünïcødé.txt");
+    /// assert_eq!(disposition.get_filename(), Some("ünïcødé.txt"));
+    /// ```
+    #[inline]
+    pub fn get_filename(&self) -> Option<&str> {
+        self.filename.as_ref().map(|s| s.as_str())
+    }
+}
+
+impl fmt::Display for ContentDisposition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", if self.attachment { "attachment" } else { "inline" })?;
+        if let Some(ref name) = self.filename {
+            write!(f, "; filename=")?;
+            write_quoted(f, name)?;
+
+            if name.chars().any(|c| c < ' ' || c > '~') {
+                write!(f, "; filename*=")?;
+                write_extended(f, name)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates a new `Header` with name `Content-Disposition` and the value set to
+/// the HTTP rendering of this disposition.
+impl Into<Header<'static>> for ContentDisposition {
+    #[inline]
+    fn into(self) -> Header<'static> {
+        Header::new("Content-Disposition", self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ContentDisposition;
+    use http::Header;
+
+    #[test]
+    fn test_plain() {
+        assert_eq!(ContentDisposition::inline().to_string(), "inline");
+        assert_eq!(ContentDisposition::inline().filename("a.txt").to_string(),
+                   "inline; filename=\"a.txt\"");
+        assert_eq!(ContentDisposition::attachment().filename("").to_string(),
+                   "attachment; filename=\"\"");
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(ContentDisposition::attachment().filename("a\\b\"c").to_string(),
+                   r#"attachment; filename="a\\b\"c""#);
+        assert_eq!(ContentDisposition::attachment().filename("a;b=c").to_string(),
+                   "attachment; filename=\"a;b=c\"");
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        assert_eq!(ContentDisposition::attachment().filename("naïve.txt").to_string(),
+                   "attachment; filename=\"na_ve.txt\"; filename*=UTF-8''na%C3%AFve.txt");
+        assert_eq!(ContentDisposition::attachment().filename("日本.txt").to_string(),
+                   "attachment; filename=\"__.txt\"; \
+                   filename*=UTF-8''%E6%97%A5%E6%9C%AC.txt");
+        assert_eq!(ContentDisposition::inline().filename("a\r\nb").to_string(),
+                   "inline; filename=\"a__b\"; filename*=UTF-8''a%0D%0Ab");
+        assert_eq!(ContentDisposition::inline().filename("'%\"").to_string(),
+                   "inline; filename=\"'%\\\"\"");
+    }
+
+    #[test]
+    fn test_header() {
+        let header: Header = ContentDisposition::attachment().filename("x").into();
+        assert_eq!(header.name, "Content-Disposition");
+        assert_eq!(header.value, "attachment; filename=\"x\"");
+    }
+}
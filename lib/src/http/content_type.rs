@@ -1,3 +1,4 @@
+use std::ascii::AsciiExt;
 use std::borrow::{Borrow, Cow};
 use std::str::FromStr;
 use std::fmt;
@@ -215,6 +216,44 @@ impl ContentType {
             params: params.map(|p| p.into())
         }
     }
+
+    /// Returns the value of the parameter named `name`, compared
+    /// case-insensitively, with any surrounding quotes removed, or `None` if
+    /// `self` has no such parameter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ContentType;
+    ///
+    /// let plain = ContentType::with_params("text", "plain", Some("Charset=\"latin1\""));
+    /// assert_eq!(plain.param("charset"), Some("latin1"));
+    /// assert_eq!(ContentType::HTML.param("charset"), Some("utf-8"));
+    /// assert_eq!(ContentType::JSON.param("charset"), None);
+    /// ```
+    pub fn param(&self, name: &str) -> Option<&str> {
+        let params = match self.params {
+            Some(ref params) => params,
+            None => return None
+        };
+
+        for param in params.split(';') {
+            let mut parts = param.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) if key.trim().eq_ignore_ascii_case(name) => {
+                    let value = value.trim();
+                    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+                        return Some(&value[1..(value.len() - 1)]);
+                    }
+
+                    return Some(value);
+                }
+                _ => continue
+            }
+        }
+
+        None
+    }
 }
 
 impl Default for ContentType {
@@ -462,6 +501,19 @@ mod test {
             ContentType::with_params("*", "*", Some("charset=\"utf-8\";else=1")));
     }
 
+    #[test]
+    fn test_param_lookup() {
+        let ct = ContentType::from_str("text/plain; charset=\"ISO-8859-1\"; else=1").unwrap();
+        assert_eq!(ct.param("charset"), Some("ISO-8859-1"));
+        assert_eq!(ct.param("CHARSET"), Some("ISO-8859-1"));
+        assert_eq!(ct.param("else"), Some("1"));
+        assert_eq!(ct.param("els"), None);
+
+        let ct = ContentType::with_params("text", "plain", Some("a=1; b=\"\""));
+        assert_eq!(ct.param("b"), Some(""));
+        assert_eq!(ContentType::Plain.param("a"), None);
+    }
+
     #[test]
     fn test_bad_parses() {
         assert_no_parse!("application//json");
@@ -0,0 +1,369 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::str::FromStr;
+use std::fmt;
+
+use http::Header;
+
+const DAYS: [&'static str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const LONG_DAYS: [&'static str; 7] = [
+    "Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"
+];
+
+const MONTHS: [&'static str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"
+];
+
+/// Returns the (year, month, day) of the day `days` days after 1970-01-01.
+/// Months and days are 1-based.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = (if z >= 0 { z } else { z - 146096 }) / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + (if month <= 2 { 1 } else { 0 });
+    (year, month, day)
+}
+
+/// Returns the number of days between 1970-01-01 and the given date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = (if year >= 0 { year } else { year - 399 }) / 400;
+    let yoe = year - era * 400;
+    let month = month as i64;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Formats `time` as an IMF-fixdate, such as `Sun, 06 Nov 1994 08:49:37 GMT`.
+/// Times before the epoch are formatted as the epoch; fractions of a second
+/// are truncated.
+pub fn format(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0) as i64;
+    let (days, secs_of_day) = (secs / 86400, secs % 86400);
+    let (year, month, day) = civil_from_days(days);
+    format!("{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+            DAYS[((days + 4) % 7) as usize], day, MONTHS[(month - 1) as usize], year,
+            secs_of_day / 3600, (secs_of_day % 3600) / 60, secs_of_day % 60)
+}
+
+/// Parses `HH:MM:SS`, returning the number of seconds since midnight.
+fn parse_time(string: &str) -> Option<u64> {
+    let parts: Vec<_> = string.split(':').collect();
+    if parts.len() != 3 || parts.iter().any(|p| p.len() != 2) {
+        return None;
+    }
+
+    let (h, m, s) = match (parts[0].parse::<u64>(), parts[1].parse::<u64>(), parts[2].parse()) {
+        (Ok(h), Ok(m), Ok(s)) if h < 24 && m < 60 && s <= 60 => (h, m, s),
+        _ => return None
+    };
+
+    Some(h * 3600 + m * 60 + s)
+}
+
+/// Returns the 1-based index of the month abbreviated as `name`.
+fn parse_month(name: &str) -> Option<u32> {
+    MONTHS.iter().position(|m| *m == name).map(|i| i as u32 + 1)
+}
+
+/// Converts a date and time of day to a `SystemTime`, if the date is valid
+/// and not before the epoch.
+fn to_system_time(year: i64, month: u32, day: u32, secs: u64) -> Option<SystemTime> {
+    let days_in_month = match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    };
+
+    if year < 1970 || day < 1 || day > days_in_month {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day) as u64;
+    Some(UNIX_EPOCH + Duration::from_secs(days * 86400 + secs))
+}
+
+/// Parses an HTTP-date in any of the three formats recipients must accept:
+/// IMF-fixdate (`Sun, 06 Nov 1994 08:49:37 GMT`), the obsolete RFC 850 format
+/// (`Sunday, 06-Nov-94 08:49:37 GMT`), and the obsolete asctime format (`Sun
+/// Nov  6 08:49:37 1994`). See [RFC 7231 §7.1.1.1] for details.
+///
+/// [RFC 7231 §7.1.1.1]: https://tools.ietf.org/html/rfc7231#section-7.1.1.1
+pub fn parse(string: &str) -> Result<SystemTime, &'static str> {
+    let parts: Vec<_> = string.split_whitespace().collect();
+    let (year, month, day, time) = match parts.len() {
+        // IMF-fixdate: `Sun, 06 Nov 1994 08:49:37 GMT`.
+        6 if DAYS.iter().any(|d| parts[0] == format!("{},", d)) && parts[5] == "GMT" => {
+            if parts[1].len() != 2 || parts[3].len() != 4 {
+                return Err("Invalid IMF-fixdate.");
+            }
+
+            (parts[3].parse().ok(), parse_month(parts[2]), parts[1].parse().ok(), parts[4])
+        }
+        // RFC 850: `Sunday, 06-Nov-94 08:49:37 GMT`.
+        4 if LONG_DAYS.iter().any(|d| parts[0] == format!("{},", d)) && parts[3] == "GMT" => {
+            let date: Vec<_> = parts[1].split('-').collect();
+            if date.len() != 3 || date[0].len() != 2 || date[2].len() != 2 {
+                return Err("Invalid RFC 850 date.");
+            }
+
+            // Two digit years that appear to be more than 50 years in the
+            // future are in the past; with the epoch as a floor, 70-99 are in
+            // the 1900s and 00-69 are in the 2000s.
+            let year = date[2].parse::<i64>().ok()
+                .map(|y| if y >= 70 { 1900 + y } else { 2000 + y });
+            (year, parse_month(date[1]), date[0].parse().ok(), parts[2])
+        }
+        // asctime: `Sun Nov  6 08:49:37 1994`.
+        5 if DAYS.contains(&parts[0]) => {
+            if parts[2].len() > 2 || parts[4].len() != 4 {
+                return Err("Invalid asctime date.");
+            }
+
+            (parts[4].parse().ok(), parse_month(parts[1]), parts[2].parse().ok(), parts[3])
+        }
+        _ => return Err("Unrecognized HTTP-date format.")
+    };
+
+    match (year, month, day, parse_time(time)) {
+        (Some(year), Some(month), Some(day), Some(secs)) => {
+            to_system_time(year, month, day, secs).ok_or("Invalid or pre-epoch date.")
+        }
+        _ => Err("Invalid HTTP-date.")
+    }
+}
+
+macro_rules! date_header {
+    ($(#[$attr:meta])* struct $T:ident => $name:expr) => (
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $T(pub SystemTime);
+
+        impl FromStr for $T {
+            type Err = &'static str;
+
+            #[inline]
+            fn from_str(string: &str) -> Result<$T, &'static str> {
+                parse(string).map($T)
+            }
+        }
+
+        impl fmt::Display for $T {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&format(self.0))
+            }
+        }
+
+        impl Into<Header<'static>> for $T {
+            #[inline]
+            fn into(self) -> Header<'static> {
+                Header::new($name, format(self.0))
+            }
+        }
+    )
+}
+
+date_header! {
+    /// The `Date` header: the time at which a message was originated.
+    ///
+    /// The time is formatted as an IMF-fixdate, with fractions of a second
+    /// truncated. All three HTTP-date formats are accepted when parsing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use rocket::http::{Date, Header};
+    ///
+    /// let date = Date(UNIX_EPOCH + Duration::from_secs(784111777));
+    /// let header: Header = date.into();
+    /// assert_eq!(header.to_string(), "Date: Sun, 06 Nov 1994 08:49:37 GMT");
+    /// ```
+    struct Date => "Date"
+}
+
+date_header! {
+    /// The `Last-Modified` header: the time at which the origin server believes
+    /// a representation was last modified.
+    ///
+    /// The time is formatted as an IMF-fixdate, with fractions of a second
+    /// truncated. All three HTTP-date formats are accepted when parsing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use rocket::http::LastModified;
+    ///
+    /// let modified = LastModified::from_str("Sunday, 06-Nov-94 08:49:37 GMT").unwrap();
+    /// assert_eq!(modified, LastModified(UNIX_EPOCH + Duration::from_secs(784111777)));
+    /// ```
+    struct LastModified => "Last-Modified"
+}
+
+date_header! {
+    /// The `Expires` header: the time after which a response is considered
+    /// stale.
+    ///
+    /// The time is formatted as an IMF-fixdate, with fractions of a second
+    /// truncated. All three HTTP-date formats are accepted when parsing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use rocket::http::Expires;
+    ///
+    /// let expires = Expires(UNIX_EPOCH + Duration::from_secs(1500));
+    /// assert_eq!(expires.to_string(), "Thu, 01 Jan 1970 00:25:00 GMT");
+    /// ```
+    struct Expires => "Expires"
+}
+
+/// The `Retry-After` header: how long a client ought to wait before making a
+/// follow-up request, as a delay or an HTTP-date. It's sent with `429 Too Many
+/// Requests` and `503 Service Unavailable` responses, as set by the
+/// [TooManyRequests](/rocket/response/status/struct.TooManyRequests.html) and
+/// [ServiceUnavailable](/rocket/response/status/struct.ServiceUnavailable.html)
+/// responders.
+///
+/// A delay is formatted in whole seconds, with fractions of a second rounded
+/// up so that a client never retries too early. A date is formatted as an
+/// IMF-fixdate. All three HTTP-date formats are accepted when parsing.
+///
+/// # Example
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use rocket::http::{RetryAfter, Header};
+///
+/// let header: Header = RetryAfter::from(Duration::from_millis(1500)).into();
+/// assert_eq!(header.to_string(), "Retry-After: 2");
+///
+/// let retry = RetryAfter::from_str("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+/// assert_eq!(retry, RetryAfter::Date(UNIX_EPOCH + Duration::from_secs(784111777)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RetryAfter {
+    /// Retry after the delay, sent as a number of seconds.
+    Delay(Duration),
+    /// Retry after the time, sent as an HTTP-date.
+    Date(SystemTime),
+}
+
+impl From<Duration> for RetryAfter {
+    #[inline(always)]
+    fn from(delay: Duration) -> RetryAfter {
+        RetryAfter::Delay(delay)
+    }
+}
+
+impl From<SystemTime> for RetryAfter {
+    #[inline(always)]
+    fn from(time: SystemTime) -> RetryAfter {
+        RetryAfter::Date(time)
+    }
+}
+
+impl FromStr for RetryAfter {
+    type Err = &'static str;
+
+    fn from_str(string: &str) -> Result<RetryAfter, &'static str> {
+        let string = string.trim();
+        if !string.is_empty() && string.bytes().all(|b| b >= b'0' && b <= b'9') {
+            return string.parse()
+                .map(|secs| RetryAfter::Delay(Duration::from_secs(secs)))
+                .map_err(|_| "Retry-After delay is too large.");
+        }
+
+        parse(string).map(RetryAfter::Date)
+    }
+}
+
+impl fmt::Display for RetryAfter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RetryAfter::Delay(delay) => {
+                let round_up = if delay.subsec_nanos() > 0 { 1 } else { 0 };
+                write!(f, "{}", delay.as_secs() + round_up)
+            }
+            RetryAfter::Date(time) => f.write_str(&format(time)),
+        }
+    }
+}
+
+impl Into<Header<'static>> for RetryAfter {
+    #[inline]
+    fn into(self) -> Header<'static> {
+        Header::new("Retry-After", self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format, parse, civil_from_days, days_from_civil, RetryAfter};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn test_civil_round_trip() {
+        for &days in &[-1000000, -1, 0, 1, 59, 60, 10956, 11016, 2932896] {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+        }
+
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+    }
+
+    #[test]
+    fn test_format() {
+        let time = UNIX_EPOCH + Duration::new(784111777, 999_999_999);
+        assert_eq!(format(time), "Sun, 06 Nov 1994 08:49:37 GMT");
+        assert_eq!(format(UNIX_EPOCH), "Thu, 01 Jan 1970 00:00:00 GMT");
+        assert_eq!(format(UNIX_EPOCH - Duration::from_secs(1)), "Thu, 01 Jan 1970 00:00:00 GMT");
+    }
+
+    #[test]
+    fn test_parse() {
+        let time = UNIX_EPOCH + Duration::from_secs(784111777);
+        assert_eq!(parse("Sun, 06 Nov 1994 08:49:37 GMT"), Ok(time));
+        assert_eq!(parse("Sunday, 06-Nov-94 08:49:37 GMT"), Ok(time));
+        assert_eq!(parse("Sun Nov  6 08:49:37 1994"), Ok(time));
+        assert_eq!(parse("Tue, 29 Feb 2000 00:00:00 GMT").map(format),
+                   Ok("Tue, 29 Feb 2000 00:00:00 GMT".into()));
+
+        assert!(parse("Sun, 06 Nov 1994 08:49:37 UTC").is_err());
+        assert!(parse("Sun, 6 Nov 1994 08:49:37 GMT").is_err());
+        assert!(parse("Sun, 06 Foo 1994 08:49:37 GMT").is_err());
+        assert!(parse("Sun, 06 Nov 1994 24:00:00 GMT").is_err());
+        assert!(parse("Thu, 29 Feb 2001 00:00:00 GMT").is_err());
+        assert!(parse("Wed, 31 Dec 1969 23:59:59 GMT").is_err());
+        assert!(parse("1994-11-06T08:49:37Z").is_err());
+    }
+
+    #[test]
+    fn test_retry_after() {
+        use std::str::FromStr;
+
+        assert_eq!(RetryAfter::from_str("120"), Ok(RetryAfter::Delay(Duration::from_secs(120))));
+        assert_eq!(RetryAfter::from_str("Sun, 06 Nov 1994 08:49:37 GMT"),
+                   Ok(RetryAfter::Date(UNIX_EPOCH + Duration::from_secs(784111777))));
+        assert!(RetryAfter::from_str("-1").is_err());
+        assert!(RetryAfter::from_str("soon").is_err());
+        assert!(RetryAfter::from_str("99999999999999999999999").is_err());
+
+        assert_eq!(RetryAfter::from(Duration::from_secs(0)).to_string(), "0");
+        assert_eq!(RetryAfter::from(Duration::new(5, 1)).to_string(), "6");
+        assert_eq!(RetryAfter::from(UNIX_EPOCH).to_string(), "Thu, 01 Jan 1970 00:00:00 GMT");
+    }
+}
@@ -0,0 +1,240 @@
+use std::ascii::AsciiExt;
+use std::io::{self, Read};
+use std::str::FromStr;
+use std::fmt;
+
+use http::{base64, hash, Header};
+
+/// The value of a `Digest` header: a list of digests of a body, each naming
+/// its algorithm, as described in [RFC 3230].
+///
+/// The `SHA-256` and `MD5` algorithms are understood. Digests with other
+/// algorithms are kept, but can't be verified.
+///
+/// [RFC 3230]: https://tools.ietf.org/html/rfc3230
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::Digest;
+///
+/// let digest = Digest::sha256(b"hello");
+/// assert_eq!(digest.to_string(),
+///            "SHA-256=LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ=");
+/// assert_eq!(digest.verify(b"hello"), Some(true));
+/// assert_eq!(digest.verify(b"hullo"), Some(false));
+///
+/// let digest: Digest = "UNIXsum=30637".parse().unwrap();
+/// assert_eq!(digest.verify(b"hello"), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Digest {
+    values: Vec<(String, String)>,
+}
+
+impl Digest {
+    /// Returns a `Digest` with the `SHA-256` digest of `bytes`.
+    pub fn sha256(bytes: &[u8]) -> Digest {
+        Digest { values: vec![("SHA-256".into(), base64::encode(&hash::sha256(bytes)))] }
+    }
+
+    /// Returns a `Digest` with the `SHA-256` digest of the bytes read from
+    /// `reader` until EOF, along with the number of bytes read. The bytes are
+    /// digested as they're read, so only a small buffer is held in memory.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Digest;
+    ///
+    /// let (digest, read) = Digest::sha256_reader(&b"hello"[..]).unwrap();
+    /// assert_eq!(digest, Digest::sha256(b"hello"));
+    /// assert_eq!(read, 5);
+    /// ```
+    pub fn sha256_reader<R: Read>(mut reader: R) -> io::Result<(Digest, u64)> {
+        let mut hasher = hash::Sha256::new();
+        let mut buffer = [0u8; 8192];
+        let mut read = 0;
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => {
+                    hasher.update(&buffer[..n]);
+                    read += n as u64;
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e)
+            }
+        }
+
+        let value = base64::encode(&hasher.finish());
+        Ok((Digest { values: vec![("SHA-256".into(), value)] }, read))
+    }
+
+    /// Returns a `Digest` with the `MD5` digest of `bytes`.
+    pub fn md5(bytes: &[u8]) -> Digest {
+        Digest { values: vec![("MD5".into(), base64::encode(&hash::md5(bytes)))] }
+    }
+
+    /// Returns the algorithms of the digests in `self`, in order.
+    pub fn algorithms<'a>(&'a self) -> impl Iterator<Item = &'a str> + 'a {
+        self.values.iter().map(|&(ref algorithm, _)| algorithm.as_str())
+    }
+
+    /// Verifies the digests in `self` with understood algorithms against
+    /// `bytes`. Returns `Some(true)` if all of them match, `Some(false)` if any
+    /// doesn't, and `None` if there are none to verify.
+    pub fn verify(&self, bytes: &[u8]) -> Option<bool> {
+        let mut verified = None;
+        for &(ref algorithm, ref value) in &self.values {
+            let expected = if algorithm.eq_ignore_ascii_case("SHA-256") {
+                base64::encode(&hash::sha256(bytes))
+            } else if algorithm.eq_ignore_ascii_case("MD5") {
+                base64::encode(&hash::md5(bytes))
+            } else {
+                continue
+            };
+
+            if *value != expected {
+                return Some(false);
+            }
+
+            verified = Some(true);
+        }
+
+        verified
+    }
+}
+
+impl FromStr for Digest {
+    type Err = &'static str;
+
+    fn from_str(string: &str) -> Result<Digest, &'static str> {
+        let mut values = vec![];
+        for item in string.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            match item.find('=') {
+                Some(i) if i > 0 => {
+                    values.push((item[..i].to_string(), item[(i + 1)..].to_string()))
+                }
+                _ => return Err("Digest values must be of the form algorithm=value."),
+            }
+        }
+
+        if values.is_empty() {
+            return Err("Digest header is empty.");
+        }
+
+        Ok(Digest { values: values })
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, &(ref algorithm, ref value)) in self.values.iter().enumerate() {
+            if i != 0 {
+                f.write_str(", ")?;
+            }
+
+            write!(f, "{}={}", algorithm, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Into<Header<'static>> for Digest {
+    #[inline]
+    fn into(self) -> Header<'static> {
+        Header::new("Digest", self.to_string())
+    }
+}
+
+/// The `Content-MD5` header: the base64 encoded MD5 digest of a body, as
+/// described in [RFC 1864].
+///
+/// [RFC 1864]: https://tools.ietf.org/html/rfc1864
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::{ContentMd5, Header};
+///
+/// let header: Header = ContentMd5::of(b"hello").into();
+/// assert_eq!(header.to_string(), "Content-MD5: XUFAKrxLKna5cZ2REBfFkg==");
+///
+/// let md5: ContentMd5 = "XUFAKrxLKna5cZ2REBfFkg==".parse().unwrap();
+/// assert!(md5.verify(b"hello"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContentMd5(pub [u8; 16]);
+
+impl ContentMd5 {
+    /// Returns the `ContentMd5` of `bytes`.
+    #[inline]
+    pub fn of(bytes: &[u8]) -> ContentMd5 {
+        ContentMd5(hash::md5(bytes))
+    }
+
+    /// Returns `true` if `self` is the MD5 digest of `bytes`.
+    #[inline]
+    pub fn verify(&self, bytes: &[u8]) -> bool {
+        self.0 == hash::md5(bytes)
+    }
+}
+
+impl FromStr for ContentMd5 {
+    type Err = &'static str;
+
+    fn from_str(string: &str) -> Result<ContentMd5, &'static str> {
+        let bytes = base64::decode(string.trim())?;
+        if bytes.len() != 16 {
+            return Err("Content-MD5 must be a 16 byte digest.");
+        }
+
+        let mut digest = [0; 16];
+        digest.copy_from_slice(&bytes);
+        Ok(ContentMd5(digest))
+    }
+}
+
+impl fmt::Display for ContentMd5 {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&base64::encode(&self.0))
+    }
+}
+
+impl Into<Header<'static>> for ContentMd5 {
+    #[inline]
+    fn into(self) -> Header<'static> {
+        Header::new("Content-MD5", self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Digest, ContentMd5};
+
+    #[test]
+    fn test_parse_digest() {
+        let digest: Digest = "sha-256=LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ=, \
+                              MD5=XUFAKrxLKna5cZ2REBfFkg==".parse().unwrap();
+        assert_eq!(digest.algorithms().collect::<Vec<_>>(), vec!["sha-256", "MD5"]);
+        assert_eq!(digest.verify(b"hello"), Some(true));
+        assert_eq!(digest.verify(b""), Some(false));
+
+        let digest: Digest = "MD5=XUFAKrxLKna5cZ2REBfFkg==, SHA-256=AAAA".parse().unwrap();
+        assert_eq!(digest.verify(b"hello"), Some(false));
+
+        assert!("".parse::<Digest>().is_err());
+        assert!("=abc".parse::<Digest>().is_err());
+        assert!("MD5".parse::<Digest>().is_err());
+    }
+
+    #[test]
+    fn test_parse_content_md5() {
+        assert_eq!("XUFAKrxLKna5cZ2REBfFkg==".parse(), Ok(ContentMd5::of(b"hello")));
+        assert!("XUFAKrxLKna5cZ2R".parse::<ContentMd5>().is_err());
+        assert!("not base64!".parse::<ContentMd5>().is_err());
+    }
+}
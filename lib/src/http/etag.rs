@@ -0,0 +1,421 @@
+use std::str::FromStr;
+use std::fmt;
+
+use http::{Header, HeaderMap, Method, Status};
+
+/// An entity tag, as used in the `ETag`, `If-Match`, and `If-None-Match`
+/// headers.
+///
+/// An entity tag is an opaque validator for a particular representation of a
+/// resource. Entity tags are either _strong_ or _weak_. Two entity tags are
+/// equal under _strong comparison_ if neither is weak and their opaque tags
+/// are identical; they are equal under _weak comparison_ if their opaque tags
+/// are identical, regardless of weakness. See [RFC 7232 §2.3.2] for details.
+///
+/// [RFC 7232 §2.3.2]: https://tools.ietf.org/html/rfc7232#section-2.3.2
+///
+/// # Header
+///
+/// `ETag` implements `Into<Header>`, producing an `ETag` header:
+///
+/// ```rust
+/// use rocket::Response;
+/// use rocket::http::ETag;
+///
+/// # #[allow(unused_variables)]
+/// let response = Response::build().header(ETag::strong("xyzzy")).finalize();
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::ETag;
+///
+/// let strong = ETag::strong("1");
+/// let weak = ETag::weak("1");
+///
+/// assert!(strong.weak_eq(&weak));
+/// assert!(!strong.strong_eq(&weak));
+/// assert_eq!(weak.to_string(), "W/\"1\"");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ETag {
+    tag: String,
+    weak: bool,
+}
+
+/// Returns `true` if `c` is a valid `etagc` character, excluding obs-text.
+#[inline]
+fn is_valid_tag_char(c: char) -> bool {
+    c == '!' || (c >= '#' && c <= '~')
+}
+
+/// Parses a single entity tag from the start of `string`, returning the tag
+/// and the remainder of the string.
+fn parse_one(string: &str) -> Result<(ETag, &str), &'static str> {
+    let (weak, rest) = if string.starts_with("W/") {
+        (true, &string[2..])
+    } else {
+        (false, string)
+    };
+
+    if !rest.starts_with('"') {
+        return Err("Entity tags must be quoted.");
+    }
+
+    let end = match rest[1..].find('"') {
+        Some(i) => i + 1,
+        None => return Err("Unterminated entity tag."),
+    };
+
+    let tag = &rest[1..end];
+    if !tag.chars().all(is_valid_tag_char) {
+        return Err("Invalid characters in entity tag.");
+    }
+
+    Ok((ETag { tag: tag.to_string(), weak: weak }, &rest[(end + 1)..]))
+}
+
+/// The value of an `If-Match` or `If-None-Match` header.
+enum Condition {
+    Any,
+    Tags(Vec<ETag>),
+}
+
+impl Condition {
+    fn parse(string: &str) -> Result<Condition, &'static str> {
+        if string.trim() == "*" {
+            return Ok(Condition::Any);
+        }
+
+        Ok(Condition::Tags(ETag::parse_list(string)?))
+    }
+
+    fn matches<F: Fn(&ETag) -> bool>(&self, f: F) -> bool {
+        match *self {
+            Condition::Any => true,
+            Condition::Tags(ref tags) => tags.iter().any(f),
+        }
+    }
+}
+
+impl ETag {
+    /// Creates a new strong entity tag with the opaque tag `tag`. The tag is
+    /// not validated.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ETag;
+    ///
+    /// let etag = ETag::strong("xyzzy");
+    /// assert!(!etag.is_weak());
+    /// assert_eq!(etag.to_string(), "\"xyzzy\"");
+    /// ```
+    #[inline(always)]
+    pub fn strong<T: Into<String>>(tag: T) -> ETag {
+        ETag { tag: tag.into(), weak: false }
+    }
+
+    /// Creates a new weak entity tag with the opaque tag `tag`. The tag is not
+    /// validated.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ETag;
+    ///
+    /// let etag = ETag::weak("xyzzy");
+    /// assert!(etag.is_weak());
+    /// assert_eq!(etag.to_string(), "W/\"xyzzy\"");
+    /// ```
+    #[inline(always)]
+    pub fn weak<T: Into<String>>(tag: T) -> ETag {
+        ETag { tag: tag.into(), weak: true }
+    }
+
+    /// Returns the opaque tag of `self`, without quotes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ETag;
+    ///
+    /// let etag = ETag::weak("xyzzy");
+    /// assert_eq!(etag.tag(), "xyzzy");
+    /// ```
+    #[inline(always)]
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Returns `true` if `self` is a weak entity tag.
+    #[inline(always)]
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    /// Returns `self` as a weak entity tag with the same opaque tag.
+    ///
+    /// A strong entity tag promises byte-for-byte equality, so it must be
+    /// weakened when the representation it was computed for is transformed,
+    /// for instance by a content coding such as `gzip`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ETag;
+    ///
+    /// let etag = ETag::strong("xyzzy").into_weak();
+    /// assert_eq!(etag, ETag::weak("xyzzy"));
+    /// ```
+    #[inline(always)]
+    pub fn into_weak(self) -> ETag {
+        ETag { tag: self.tag, weak: true }
+    }
+
+    /// Compares `self` and `other` using the strong comparison function: both
+    /// must be strong and have identical opaque tags.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ETag;
+    ///
+    /// assert!(ETag::strong("1").strong_eq(&ETag::strong("1")));
+    /// assert!(!ETag::strong("1").strong_eq(&ETag::weak("1")));
+    /// assert!(!ETag::weak("1").strong_eq(&ETag::weak("1")));
+    /// ```
+    #[inline]
+    pub fn strong_eq(&self, other: &ETag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    /// Compares `self` and `other` using the weak comparison function: the
+    /// opaque tags must be identical.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ETag;
+    ///
+    /// assert!(ETag::strong("1").weak_eq(&ETag::weak("1")));
+    /// assert!(ETag::weak("1").weak_eq(&ETag::weak("1")));
+    /// assert!(!ETag::weak("1").weak_eq(&ETag::weak("2")));
+    /// ```
+    #[inline]
+    pub fn weak_eq(&self, other: &ETag) -> bool {
+        self.tag == other.tag
+    }
+
+    /// Parses a comma-separated list of entity tags, as found in the
+    /// `If-Match` and `If-None-Match` headers. Empty list elements are
+    /// ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ETag;
+    ///
+    /// let tags = ETag::parse_list(r#""a", W/"b",,"c""#).unwrap();
+    /// assert_eq!(tags, vec![ETag::strong("a"), ETag::weak("b"), ETag::strong("c")]);
+    ///
+    /// assert!(ETag::parse_list("a, b").is_err());
+    /// ```
+    pub fn parse_list(string: &str) -> Result<Vec<ETag>, &'static str> {
+        let mut tags = vec![];
+        let mut rest = string.trim_left_matches(|c: char| c == ',' || c == ' ' || c == '\t');
+        while !rest.is_empty() {
+            let (tag, remaining) = parse_one(rest)?;
+            tags.push(tag);
+
+            let remaining = remaining.trim_left_matches(|c: char| c == ' ' || c == '\t');
+            if !remaining.is_empty() && !remaining.starts_with(',') {
+                return Err("Entity tags must be separated by commas.");
+            }
+
+            rest = remaining.trim_left_matches(|c: char| c == ',' || c == ' ' || c == '\t');
+        }
+
+        Ok(tags)
+    }
+
+    /// Evaluates the `If-Match` and `If-None-Match` preconditions in `headers`
+    /// for a request with method `method` against `self`, the current entity
+    /// tag of the target resource, as described in [RFC 7232 §6].
+    ///
+    /// Returns `Some(Status::PreconditionFailed)` if `If-Match` is present and
+    /// no listed tag strongly matches `self`. Returns `Some(Status::NotModified)`
+    /// for `GET` and `HEAD` requests, or `Some(Status::PreconditionFailed)` for
+    /// all others, if `If-None-Match` is present and any listed tag weakly
+    /// matches `self`. Otherwise, returns `None`, and the request should be
+    /// processed normally. Malformed headers are ignored.
+    ///
+    /// [RFC 7232 §6]: https://tools.ietf.org/html/rfc7232#section-6
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::{ETag, Header, HeaderMap, Method, Status};
+    ///
+    /// let etag = ETag::strong("v2");
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.add(Header::new("If-None-Match", r#"W/"v1", W/"v2""#));
+    /// assert_eq!(etag.precondition(Method::Get, &headers), Some(Status::NotModified));
+    /// assert_eq!(etag.precondition(Method::Put, &headers),
+    ///            Some(Status::PreconditionFailed));
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.add(Header::new("If-Match", r#""v1""#));
+    /// assert_eq!(etag.precondition(Method::Put, &headers),
+    ///            Some(Status::PreconditionFailed));
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.add(Header::new("If-Match", r#""v2""#));
+    /// assert_eq!(etag.precondition(Method::Put, &headers), None);
+    /// ```
+    pub fn precondition(&self, method: Method, headers: &HeaderMap) -> Option<Status> {
+        let if_match = headers.get_one("If-Match").and_then(|v| Condition::parse(v).ok());
+        if let Some(condition) = if_match {
+            if !condition.matches(|tag| tag.strong_eq(self)) {
+                return Some(Status::PreconditionFailed);
+            }
+        }
+
+        let if_none_match = headers.get_one("If-None-Match")
+            .and_then(|v| Condition::parse(v).ok());
+
+        if let Some(condition) = if_none_match {
+            if condition.matches(|tag| tag.weak_eq(self)) {
+                return match method {
+                    Method::Get | Method::Head => Some(Status::NotModified),
+                    _ => Some(Status::PreconditionFailed)
+                };
+            }
+        }
+
+        None
+    }
+}
+
+impl FromStr for ETag {
+    type Err = &'static str;
+
+    /// Parses a single entity tag, as found in the `ETag` header.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use rocket::http::ETag;
+    ///
+    /// let etag = ETag::from_str("W/\"xyzzy\"").unwrap();
+    /// assert_eq!(etag, ETag::weak("xyzzy"));
+    ///
+    /// assert!(ETag::from_str("xyzzy").is_err());
+    /// ```
+    fn from_str(string: &str) -> Result<ETag, &'static str> {
+        let (tag, rest) = parse_one(string.trim())?;
+        if !rest.is_empty() {
+            return Err("Trailing characters after entity tag.");
+        }
+
+        Ok(tag)
+    }
+}
+
+impl fmt::Display for ETag {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.weak {
+            write!(f, "W/\"{}\"", self.tag)
+        } else {
+            write!(f, "\"{}\"", self.tag)
+        }
+    }
+}
+
+/// Creates a new `Header` with name `ETag` and the value set to the HTTP
+/// rendering of this entity tag.
+impl Into<Header<'static>> for ETag {
+    #[inline]
+    fn into(self) -> Header<'static> {
+        Header::new("ETag", self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ETag;
+    use std::str::FromStr;
+
+    use http::{Header, HeaderMap, Status};
+    use http::Method::*;
+
+    fn headers(name: &'static str, value: &'static str) -> HeaderMap<'static> {
+        let mut map = HeaderMap::new();
+        map.add(Header::new(name, value));
+        map
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(ETag::from_str("\"\"").unwrap(), ETag::strong(""));
+        assert_eq!(ETag::from_str(" \"abc\" ").unwrap(), ETag::strong("abc"));
+        assert_eq!(ETag::from_str("W/\"a,b\"").unwrap(), ETag::weak("a,b"));
+
+        assert!(ETag::from_str("").is_err());
+        assert!(ETag::from_str("w/\"abc\"").is_err());
+        assert!(ETag::from_str("\"abc").is_err());
+        assert!(ETag::from_str("\"a\"b\"").is_err());
+        assert!(ETag::from_str("\"a b\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(ETag::parse_list("").unwrap(), vec![]);
+        assert_eq!(ETag::parse_list(" , \"a\" ,").unwrap(), vec![ETag::strong("a")]);
+        assert_eq!(ETag::parse_list("\"a,b\", W/\"c\"").unwrap(),
+                   vec![ETag::strong("a,b"), ETag::weak("c")]);
+
+        assert!(ETag::parse_list("\"a\" \"b\"").is_err());
+        assert!(ETag::parse_list("*").is_err());
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = ETag::strong("abc");
+        let any = headers("If-None-Match", "*");
+        assert_eq!(etag.precondition(Get, &any), Some(Status::NotModified));
+        assert_eq!(etag.precondition(Head, &any), Some(Status::NotModified));
+        assert_eq!(etag.precondition(Post, &any), Some(Status::PreconditionFailed));
+
+        let weak = headers("If-None-Match", "W/\"abc\"");
+        assert_eq!(etag.precondition(Get, &weak), Some(Status::NotModified));
+
+        let other = headers("If-None-Match", "\"xyz\"");
+        assert_eq!(etag.precondition(Get, &other), None);
+
+        let malformed = headers("If-None-Match", "abc");
+        assert_eq!(etag.precondition(Get, &malformed), None);
+    }
+
+    #[test]
+    fn test_if_match() {
+        let etag = ETag::strong("abc");
+        assert_eq!(etag.precondition(Put, &headers("If-Match", "*")), None);
+        assert_eq!(etag.precondition(Put, &headers("If-Match", "\"x\", \"abc\"")), None);
+        assert_eq!(etag.precondition(Put, &headers("If-Match", "W/\"abc\"")),
+                   Some(Status::PreconditionFailed));
+
+        let weak = ETag::weak("abc");
+        assert_eq!(weak.precondition(Put, &headers("If-Match", "\"abc\"")),
+                   Some(Status::PreconditionFailed));
+    }
+
+    #[test]
+    fn test_no_conditions() {
+        assert_eq!(ETag::strong("abc").precondition(Get, &HeaderMap::new()), None);
+    }
+}
@@ -0,0 +1,84 @@
+//! The MD5 (RFC 1321) and SHA-256 (FIPS 180-4) message digests, as computed by
+//! the `md5` and `sha2` crates.
+//!
+//! These are used to compute and verify the `Content-MD5` and `Digest` headers
+//! of bodies. MD5 is not collision resistant; it only detects corruption.
+
+use sha2::{self, Digest};
+
+/// Returns the MD5 digest of `bytes`.
+pub fn md5(bytes: &[u8]) -> [u8; 16] {
+    ::md5::compute(bytes).0
+}
+
+/// Returns the SHA-256 digest of `bytes`.
+pub fn sha256(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    hasher.finish()
+}
+
+/// An incremental SHA-256 hasher, for digesting bodies as they're read
+/// instead of all at once.
+#[derive(Clone)]
+pub struct Sha256(sha2::Sha256);
+
+impl Sha256 {
+    /// Returns a hasher that has yet to be fed any bytes.
+    pub fn new() -> Sha256 {
+        Sha256(sha2::Sha256::default())
+    }
+
+    /// Feeds `bytes` to the hasher.
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.0.input(bytes);
+    }
+
+    /// Returns the digest of the bytes fed to the hasher.
+    pub fn finish(self) -> [u8; 32] {
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(&self.0.result());
+        digest
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{md5, sha256, Sha256};
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_md5() {
+        assert_eq!(hex(&md5(b"")), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(hex(&md5(b"abc")), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(hex(&md5(b"The quick brown fox jumps over the lazy dog")),
+                   "9e107d9d372bb6826bd81d3542a419d6");
+        assert_eq!(hex(&md5(&[b'a'; 1000])), "cabe45dcc9ae5b66ba86600cca6b8ba8");
+    }
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(hex(&sha256(b"")),
+                   "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(hex(&sha256(b"abc")),
+                   "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(hex(&sha256(&[b'a'; 1000])),
+                   "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3");
+    }
+
+    #[test]
+    fn test_incremental_sha256() {
+        let data: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+        for &step in &[1, 7, 63, 64, 65, 500] {
+            let mut hasher = Sha256::new();
+            for chunk in data.chunks(step) {
+                hasher.update(chunk);
+            }
+
+            assert_eq!(hasher.finish(), sha256(&data));
+        }
+    }
+}
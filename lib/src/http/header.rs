@@ -1,8 +1,10 @@
+use std::ascii::AsciiExt;
 use std::collections::HashMap;
+use std::collections::hash_map;
 use std::borrow::{Borrow, Cow};
 use std::fmt;
 
-use http::hyper::header as hyper;
+use hyper::header as hyper;
 
 /// Simple representation of an HTTP header.
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -17,8 +19,12 @@ impl<'h> Header<'h> {
     /// Constructs a new header. This method should be used rarely and only for
     /// non-standard headers. Instead, prefer to use the `Into<Header>`
     /// implementations of many types, including
-    /// [ContentType](struct.ContentType.html) and all of the headers in
-    /// [http::hyper::header](hyper/header/index.html).
+    /// [ContentType](struct.ContentType.html) and the other typed headers in
+    /// this module, such as [Location](struct.Location.html).
+    ///
+    /// Neither the name nor the value is validated. Use
+    /// [checked](#method.checked) when either is derived from untrusted input.
+    /// Invalid headers are dropped when a response is written out.
     ///
     /// # Examples
     ///
@@ -50,6 +56,77 @@ impl<'h> Header<'h> {
             value: value.into()
         }
     }
+
+    /// Constructs a new header, validating its name and value. Returns an
+    /// `Err` if `name` is not a valid header name or if `value` contains
+    /// illegal bytes such as CR, LF, or NUL. This should be used in favor of
+    /// [new](#method.new) when either the name or value is derived from
+    /// untrusted input, as an unchecked `\r\n` in a header value allows
+    /// clients to inject headers into a response.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Header;
+    ///
+    /// let header = Header::checked("X-Echo", "hello");
+    /// assert_eq!(header, Ok(Header::new("X-Echo", "hello")));
+    ///
+    /// assert!(Header::checked("X-Echo", "hello\r\nSet-Cookie: evil").is_err());
+    /// assert!(Header::checked("X Echo", "hello").is_err());
+    /// ```
+    #[inline]
+    pub fn checked<'a: 'h, 'b: 'h, N, V>(name: N, value: V)
+            -> Result<Header<'h>, &'static str>
+        where N: Into<Cow<'a, str>>, V: Into<Cow<'b, str>>
+    {
+        let header = Header::new(name, value);
+        header.validate()?;
+        Ok(header)
+    }
+
+    /// Returns `Ok` if `self` has a valid name and value, and an `Err`
+    /// describing the problem otherwise. A valid name is a non-empty token; a
+    /// valid value contains no control characters other than horizontal tab.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Header;
+    ///
+    /// assert!(Header::new("X-Ok", "a\tb").validate().is_ok());
+    /// assert!(Header::new("X-Bad", "a\nb").validate().is_err());
+    /// assert!(Header::new("", "value").validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.name.is_empty() || !self.name.bytes().all(is_token_byte) {
+            return Err("Header names must be non-empty tokens.");
+        }
+
+        if !self.value.bytes().all(is_value_byte) {
+            return Err("Header values cannot contain control characters.");
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns `true` if `b` may appear in a header name: a `tchar`.
+#[inline]
+fn is_token_byte(b: u8) -> bool {
+    match b {
+        b'a'...b'z' | b'A'...b'Z' | b'0'...b'9' => true,
+        b'!' | b'#' | b'$' | b'%' | b'&' | b'\'' | b'*' | b'+' | b'-' | b'.' | b'^' | b'_'
+            | b'`' | b'|' | b'~' => true,
+        _ => false
+    }
+}
+
+/// Returns `true` if `b` may appear in a header value: anything but control
+/// characters, save for horizontal tab.
+#[inline]
+fn is_value_byte(b: u8) -> bool {
+    b == b'\t' || (b >= 0x20 && b != 0x7F)
 }
 
 impl<'h> fmt::Display for Header<'h> {
@@ -59,6 +136,10 @@ impl<'h> fmt::Display for Header<'h> {
     }
 }
 
+/// Deprecated: converts a hyper typed header into a `Header`. Prefer the typed
+/// headers in [http](/rocket/http/index.html), which don't depend on hyper.
+/// This conversion will be removed along with hyper.
+#[deprecated(since = "0.2.0", note = "use Rocket's typed headers instead")]
 impl<T> From<T> for Header<'static> where T: hyper::Header + hyper::HeaderFormat {
     fn from(hyper_header: T) -> Header<'static> {
         let formatter = hyper::HeaderFormatter(&hyper_header);
@@ -294,18 +375,16 @@ impl<'h> HeaderMap<'h> {
     /// previously added, that header will have one more value.
     ///
     /// ```rust
-    /// use rocket::http::{Cookie, HeaderMap};
-    /// use rocket::http::hyper::header;
+    /// use rocket::http::{Allow, HeaderMap};
+    /// use rocket::http::Method::*;
     ///
     /// let mut map = HeaderMap::new();
     ///
-    /// let cookie = vec![Cookie::new("a".to_string(), "b".to_string())];
-    /// map.add(header::SetCookie(cookie));
-    /// assert_eq!(map.get("Set-Cookie").count(), 1);
+    /// map.add(Allow(vec![Get, Head]));
+    /// assert_eq!(map.get("Allow").count(), 1);
     ///
-    /// let cookie = vec![Cookie::new("c".to_string(), "d".to_string())];
-    /// map.add(header::SetCookie(cookie));
-    /// assert_eq!(map.get("Set-Cookie").count(), 2);
+    /// map.add(Allow(vec![Post]));
+    /// assert_eq!(map.get("Allow").count(), 2);
     /// ```
     #[inline(always)]
     pub fn add<'p: 'h, H: Into<Header<'p>>>(&mut self, header: H) {
@@ -314,7 +393,8 @@ impl<'h> HeaderMap<'h> {
     }
 
     /// A convenience method to add a header using a raw name and value.
-    /// Aliases `add(Header::new(name, value))`. Should be used rarely.
+    /// Aliases `add(Header::new(name, value))`. Should be used rarely. The name
+    /// and value are not validated; see [Header::checked](struct.Header.html#method.checked).
     ///
     /// # Example
     ///
@@ -384,11 +464,89 @@ impl<'h> HeaderMap<'h> {
     ///
     /// map.remove("X-Custom");
     /// assert_eq!(map.len(), 1);
+    /// ```
     #[inline(always)]
     pub fn remove(&mut self, name: &str) {
         self.headers.remove(name);
     }
 
+    /// Removes all of the values for the header with name `name` and returns
+    /// them in FIFO order. Returns an empty vector if there is no such header.
+    /// Header names are compared case-insensitively, so values added under
+    /// differently cased names are all removed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::HeaderMap;
+    ///
+    /// let mut map = HeaderMap::new();
+    /// map.add_raw("X-Custom", "value_1");
+    /// map.add_raw("X-Custom", "value_2");
+    /// map.add_raw("X-Other", "other");
+    ///
+    /// let values = map.take("X-Custom");
+    /// assert_eq!(values, vec!["value_1", "value_2"]);
+    /// assert_eq!(map.len(), 1);
+    ///
+    /// assert!(map.take("X-Custom").is_empty());
+    ///
+    /// map.add_raw("X-Custom", "value_3");
+    /// assert_eq!(map.take("x-custom"), vec!["value_3"]);
+    /// ```
+    pub fn take(&mut self, name: &str) -> Vec<Cow<'h, str>> {
+        let keys: Vec<_> = self.headers.keys()
+            .filter(|key| key.eq_ignore_ascii_case(name))
+            .cloned()
+            .collect();
+
+        let mut values = vec![];
+        for key in keys {
+            values.extend(self.headers.remove(&key).unwrap_or(vec![]));
+        }
+
+        values
+    }
+
+    /// Returns the entry for the header with name `name`, which can be used to
+    /// read, insert, or modify its values with a single lookup.
+    ///
+    /// # Example
+    ///
+    /// Add a header only if it isn't already present:
+    ///
+    /// ```rust
+    /// use rocket::http::HeaderMap;
+    ///
+    /// let mut map = HeaderMap::new();
+    /// map.add_raw("Cache-Control", "no-cache");
+    ///
+    /// map.entry("Cache-Control").or_insert("max-age=60");
+    /// map.entry("X-Frame-Options").or_insert("DENY");
+    ///
+    /// assert_eq!(map.get_one("Cache-Control"), Some("no-cache"));
+    /// assert_eq!(map.get_one("X-Frame-Options"), Some("DENY"));
+    /// ```
+    ///
+    /// Rewrite the values of a header in place:
+    ///
+    /// ```rust
+    /// use rocket::http::HeaderMap;
+    ///
+    /// let mut map = HeaderMap::new();
+    /// map.add_raw("Vary", "Accept");
+    ///
+    /// map.entry("Vary").values().push("Accept-Encoding".into());
+    /// let values: Vec<_> = map.get("Vary").collect();
+    /// assert_eq!(values, vec!["Accept", "Accept-Encoding"]);
+    /// ```
+    #[inline(always)]
+    pub fn entry<'a, 'n: 'h, N>(&'a mut self, name: N) -> HeaderEntry<'a, 'h>
+        where N: Into<Cow<'n, str>>
+    {
+        HeaderEntry { inner: self.headers.entry(name.into()) }
+    }
+
     /// Removes all of the headers stored in this map and returns a vector
     /// containing them. Header names are returned in no specific order, but all
     /// values for a given header name are grouped together, and values are in
@@ -468,3 +626,161 @@ impl<'h> HeaderMap<'h> {
         self.headers.into_iter()
     }
 }
+
+/// A view into the values of a single header in a [HeaderMap](struct.HeaderMap.html),
+/// which may or may not be present. Returned by
+/// [HeaderMap::entry](struct.HeaderMap.html#method.entry).
+pub struct HeaderEntry<'a, 'h: 'a> {
+    inner: hash_map::Entry<'a, Cow<'h, str>, Vec<Cow<'h, str>>>
+}
+
+impl<'a, 'h: 'a> HeaderEntry<'a, 'h> {
+    /// Returns the name of the header this entry refers to.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::HeaderMap;
+    ///
+    /// let mut map = HeaderMap::new();
+    /// assert_eq!(map.entry("X-Custom").name(), "X-Custom");
+    /// ```
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        self.inner.key()
+    }
+
+    /// Returns `true` if the header has at least one value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::HeaderMap;
+    ///
+    /// let mut map = HeaderMap::new();
+    /// map.add_raw("X-Custom", "value");
+    ///
+    /// assert!(map.entry("X-Custom").is_present());
+    /// assert!(!map.entry("X-Other").is_present());
+    /// ```
+    #[inline]
+    pub fn is_present(&self) -> bool {
+        match self.inner {
+            hash_map::Entry::Occupied(ref entry) => !entry.get().is_empty(),
+            hash_map::Entry::Vacant(_) => false
+        }
+    }
+
+    /// Returns a mutable borrow of the header's values, in FIFO order. If the
+    /// header isn't present, the returned vector is empty; values pushed into
+    /// it are added to the map.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::HeaderMap;
+    ///
+    /// let mut map = HeaderMap::new();
+    /// map.add_raw("X-Custom", "value_1");
+    ///
+    /// map.entry("X-Custom").values()[0] = "value_2".into();
+    /// assert_eq!(map.get_one("X-Custom"), Some("value_2"));
+    /// ```
+    #[inline]
+    pub fn values(self) -> &'a mut Vec<Cow<'h, str>> {
+        self.inner.or_insert(vec![])
+    }
+
+    /// Adds `value` as the header's only value if the header isn't present.
+    /// Returns a mutable borrow of the header's values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::HeaderMap;
+    ///
+    /// let mut map = HeaderMap::new();
+    /// map.entry("X-Custom").or_insert("value_1");
+    /// map.entry("X-Custom").or_insert("value_2");
+    ///
+    /// let values: Vec<_> = map.get("X-Custom").collect();
+    /// assert_eq!(values, vec!["value_1"]);
+    /// ```
+    #[inline]
+    pub fn or_insert<'v: 'h, V>(self, value: V) -> &'a mut Vec<Cow<'h, str>>
+        where V: Into<Cow<'v, str>>
+    {
+        self.or_insert_with(|| value)
+    }
+
+    /// Adds the value returned by `f` as the header's only value if the header
+    /// isn't present. `f` is only called if the header isn't present. Returns
+    /// a mutable borrow of the header's values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::HeaderMap;
+    ///
+    /// let mut map = HeaderMap::new();
+    /// map.add_raw("X-Request-Id", "1234");
+    ///
+    /// map.entry("X-Request-Id").or_insert_with(|| "5678");
+    /// assert_eq!(map.get_one("X-Request-Id"), Some("1234"));
+    /// ```
+    pub fn or_insert_with<'v: 'h, V, F>(self, f: F) -> &'a mut Vec<Cow<'h, str>>
+        where V: Into<Cow<'v, str>>, F: FnOnce() -> V
+    {
+        let values = self.values();
+        if values.is_empty() {
+            values.push(f().into());
+        }
+
+        values
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Header, HeaderMap};
+
+    #[test]
+    fn test_checked() {
+        assert!(Header::checked("Content-Type", "text/html; charset=utf-8").is_ok());
+        assert!(Header::checked("X-Unicode", "héllo wörld").is_ok());
+        assert!(Header::checked("X-Empty", "").is_ok());
+        assert!(Header::checked("X-Tab", "a\tb").is_ok());
+
+        assert!(Header::checked("X-Split", "a\r\nSet-Cookie: x=y").is_err());
+        assert!(Header::checked("X-Split", "a\nb").is_err());
+        assert!(Header::checked("X-Split", "a\rb").is_err());
+        assert!(Header::checked("X-Nul", "a\0b").is_err());
+        assert!(Header::checked("X-Del", "a\x7Fb").is_err());
+
+        assert!(Header::checked("", "a").is_err());
+        assert!(Header::checked("X-Name:", "a").is_err());
+        assert!(Header::checked("X-Na\r\nme", "a").is_err());
+        assert!(Header::checked("X-Név", "a").is_err());
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut map = HeaderMap::new();
+        map.replace_all("X-Empty", vec![]);
+        assert!(!map.entry("X-Empty").is_present());
+        map.entry("X-Empty").or_insert("filled");
+        assert_eq!(map.get_one("X-Empty"), Some("filled"));
+
+        map.entry("X-New").values().push("a".into());
+        map.entry("X-New").or_insert("b").push("c".into());
+        assert_eq!(map.get("X-New").collect::<Vec<_>>(), vec!["a", "c"]);
+
+        let mut called = false;
+        map.entry("X-New").or_insert_with(|| { called = true; "d" });
+        assert!(!called);
+
+        assert_eq!(map.take("X-New"), vec!["a", "c"]);
+        assert!(!map.contains("X-New"));
+        assert!(map.take("X-New").is_empty());
+    }
+}
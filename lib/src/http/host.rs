@@ -0,0 +1,244 @@
+use std::borrow::Cow;
+use std::fmt;
+
+/// The authority of a request: a host name and an optional port.
+///
+/// A `Host` is typically parsed from the value of the `Host` request header.
+/// The host name is stored as it was received; comparisons between hosts via
+/// [matches](#method.matches) are case-insensitive.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::Host;
+///
+/// let host = Host::parse("rocket.rs:8000").unwrap();
+/// assert_eq!(host.name(), "rocket.rs");
+/// assert_eq!(host.port(), Some(8000));
+/// assert_eq!(host.to_string(), "rocket.rs:8000");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Host<'h> {
+    name: Cow<'h, str>,
+    port: Option<u16>,
+}
+
+#[inline]
+fn is_valid_name_char(c: char) -> bool {
+    match c {
+        'a'...'z' | 'A'...'Z' | '0'...'9' | '-' | '.' | '_' => true,
+        _ => false
+    }
+}
+
+#[inline]
+fn is_valid_ipv6_char(c: char) -> bool {
+    match c {
+        'a'...'f' | 'A'...'F' | '0'...'9' | ':' | '.' => true,
+        _ => false
+    }
+}
+
+impl<'h> Host<'h> {
+    /// Constructs a new `Host` with the given `name` and `port`. The `name` is
+    /// not validated.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Host;
+    ///
+    /// let host = Host::new("localhost", Some(8000));
+    /// assert_eq!(host.to_string(), "localhost:8000");
+    /// ```
+    #[inline(always)]
+    pub fn new<N: Into<Cow<'h, str>>>(name: N, port: Option<u16>) -> Host<'h> {
+        Host { name: name.into(), port: port }
+    }
+
+    /// Parses `string` as a host name with an optional port, as found in the
+    /// `Host` header. IPv6 literals must be enclosed in brackets.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `Err` if the name is empty, contains characters that aren't
+    /// valid in a host name, or if the port isn't a valid 16-bit integer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Host;
+    ///
+    /// let host = Host::parse("[::1]:80").unwrap();
+    /// assert_eq!(host.name(), "[::1]");
+    /// assert_eq!(host.port(), Some(80));
+    ///
+    /// assert!(Host::parse("evil.com/path").is_err());
+    /// assert!(Host::parse("rocket.rs:http").is_err());
+    /// ```
+    pub fn parse(string: &'h str) -> Result<Host<'h>, &'static str> {
+        let string = string.trim();
+        let (name, port_str) = if string.starts_with('[') {
+            let end = match string.find(']') {
+                Some(i) => i,
+                None => return Err("Unterminated IPv6 literal."),
+            };
+
+            if !string[1..end].chars().all(is_valid_ipv6_char) {
+                return Err("Invalid characters in IPv6 literal.");
+            }
+
+            match &string[(end + 1)..] {
+                "" => (&string[..(end + 1)], None),
+                rest if rest.starts_with(':') => (&string[..(end + 1)], Some(&rest[1..])),
+                _ => return Err("Unexpected characters after IPv6 literal."),
+            }
+        } else {
+            match string.rfind(':') {
+                Some(i) => (&string[..i], Some(&string[(i + 1)..])),
+                None => (string, None),
+            }
+        };
+
+        if name.is_empty() || name == "[]" {
+            return Err("Empty host name.");
+        }
+
+        if !name.starts_with('[') && !name.chars().all(is_valid_name_char) {
+            return Err("Invalid characters in host name.");
+        }
+
+        let port = match port_str {
+            Some(port) => match port.parse::<u16>() {
+                Ok(port) => Some(port),
+                Err(_) => return Err("Invalid port."),
+            },
+            None => None,
+        };
+
+        Ok(Host::new(name, port))
+    }
+
+    /// Returns the host name of `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Host;
+    ///
+    /// let host = Host::parse("rocket.rs").unwrap();
+    /// assert_eq!(host.name(), "rocket.rs");
+    /// ```
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the port of `self`, if one was specified.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Host;
+    ///
+    /// let host = Host::parse("rocket.rs").unwrap();
+    /// assert_eq!(host.port(), None);
+    ///
+    /// let host = Host::parse("rocket.rs:443").unwrap();
+    /// assert_eq!(host.port(), Some(443));
+    /// ```
+    #[inline(always)]
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    /// Returns `true` if `self` is matched by the host `pattern`. Host names
+    /// are compared case-insensitively. If `pattern` has no port, any port in
+    /// `self` is accepted; otherwise, the ports must be equal.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Host;
+    ///
+    /// let host = Host::parse("Rocket.rs:8000").unwrap();
+    /// assert!(host.matches(&Host::parse("rocket.rs").unwrap()));
+    /// assert!(host.matches(&Host::parse("rocket.rs:8000").unwrap()));
+    /// assert!(!host.matches(&Host::parse("rocket.rs:80").unwrap()));
+    /// assert!(!host.matches(&Host::parse("api.rocket.rs").unwrap()));
+    /// ```
+    pub fn matches(&self, pattern: &Host) -> bool {
+        if self.name.to_lowercase() != pattern.name.to_lowercase() {
+            return false;
+        }
+
+        pattern.port.is_none() || pattern.port == self.port
+    }
+
+    /// Converts `self` into an owned `Host` with a static lifetime.
+    #[inline(always)]
+    pub fn into_owned(self) -> Host<'static> {
+        Host { name: Cow::Owned(self.name.into_owned()), port: self.port }
+    }
+}
+
+impl<'h> fmt::Display for Host<'h> {
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.port {
+            Some(port) => write!(f, "{}:{}", self.name, port),
+            None => write!(f, "{}", self.name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Host;
+
+    macro_rules! assert_parse {
+        ($string:expr, $name:expr, $port:expr) => ({
+            let host = Host::parse($string).expect($string);
+            assert_eq!(host.name(), $name);
+            assert_eq!(host.port(), $port);
+        })
+    }
+
+    #[test]
+    fn test_parse_valid() {
+        assert_parse!("localhost", "localhost", None);
+        assert_parse!("localhost:8000", "localhost", Some(8000));
+        assert_parse!("  rocket.rs  ", "rocket.rs", None);
+        assert_parse!("api.rocket.rs:443", "api.rocket.rs", Some(443));
+        assert_parse!("127.0.0.1:80", "127.0.0.1", Some(80));
+        assert_parse!("[::1]", "[::1]", None);
+        assert_parse!("[fe80::1]:8080", "[fe80::1]", Some(8080));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(Host::parse("").is_err());
+        assert!(Host::parse(":80").is_err());
+        assert!(Host::parse("localhost:").is_err());
+        assert!(Host::parse("localhost:99999").is_err());
+        assert!(Host::parse("user@localhost").is_err());
+        assert!(Host::parse("localhost/path").is_err());
+        assert!(Host::parse("[::1").is_err());
+        assert!(Host::parse("[::1]x").is_err());
+        assert!(Host::parse("[]:80").is_err());
+        assert!(Host::parse("local host").is_err());
+    }
+
+    #[test]
+    fn test_matches() {
+        let host = Host::parse("Example.com:8000").unwrap();
+        assert!(host.matches(&Host::new("example.com", None)));
+        assert!(host.matches(&Host::new("EXAMPLE.COM", Some(8000))));
+        assert!(!host.matches(&Host::new("example.com", Some(80))));
+        assert!(!host.matches(&Host::new("www.example.com", None)));
+
+        let host = Host::parse("example.com").unwrap();
+        assert!(host.matches(&Host::new("example.com", None)));
+        assert!(!host.matches(&Host::new("example.com", Some(80))));
+    }
+}
@@ -5,6 +5,15 @@
 //! All types that are re-exported from Hyper resides inside of this module.
 //! These types will, with certainty, be removed with time, but they reside here
 //! while necessary.
+//!
+//! ## Deprecated: Typed Headers
+//!
+//! The re-export of hyper's `header` module, and the conversion of its typed
+//! headers into [Header](/rocket/http/struct.Header.html)s, are deprecated.
+//! Use Rocket's typed headers instead, such as
+//! [ContentType](/rocket/http/struct.ContentType.html),
+//! [Location](/rocket/http/struct.Location.html), and
+//! [Date](/rocket/http/struct.Date.html).
 
 pub use hyper::server::Request as Request;
 pub use hyper::server::Response as Response;
@@ -18,8 +27,7 @@ pub use hyper::net;
 pub use hyper::method::Method;
 pub use hyper::status::StatusCode;
 pub use hyper::uri::RequestUri;
-pub use hyper::http::h1;
-pub use hyper::buffer;
+pub use hyper::version::HttpVersion;
 
 /// Type alias to `hyper::Response<'a, hyper::net::Fresh>`.
 pub type FreshResponse<'a> = self::Response<'a, self::net::Fresh>;
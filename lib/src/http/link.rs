@@ -0,0 +1,697 @@
+use std::ascii::AsciiExt;
+use std::fmt;
+use std::str::FromStr;
+
+use http::Header;
+
+/// The value of a `Link` header: a list of links, each with a target URI, a
+/// relation type, and optional target attributes.
+///
+/// `Link` headers are commonly used to paginate API responses by linking to
+/// the `next`, `prev`, `first`, and `last` pages of a collection, as described
+/// in [RFC 8288]. Target URIs are written between angle brackets, with any
+/// characters that would end the URI early percent-encoded. Attribute values
+/// are written as tokens when possible and as quoted strings otherwise.
+///
+/// [RFC 8288]: https://tools.ietf.org/html/rfc8288
+///
+/// # Header
+///
+/// `Link` implements `Into<Header>`, producing a `Link` header:
+///
+/// ```rust
+/// use rocket::Response;
+/// use rocket::http::Link;
+///
+/// let link = Link::new()
+///     .add("/items?page=3", "next")
+///     .add("/items?page=1", "prev");
+///
+/// # #[allow(unused_variables)]
+/// let response = Response::build().header(link).finalize();
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::Link;
+///
+/// let link = Link::new()
+///     .add("/items?page=3", "next")
+///     .add_with("/items?page=9", "last", &[("title", "Last page")]);
+///
+/// assert_eq!(link.to_string(),
+///            r#"</items?page=3>; rel=next, </items?page=9>; rel=last; title="Last page""#);
+/// ```
+///
+/// # Parsing
+///
+/// `Link` implements `FromStr`, parsing the value of a `Link` header, and
+/// `FromRequest`, parsing all of the request's `Link` headers: a request
+/// without any yields an empty `Link`, and a malformed one fails with a status
+/// of `400 Bad Request`. Each link is a [LinkValue](struct.LinkValue.html):
+///
+/// ```rust
+/// use rocket::http::Link;
+///
+/// let link: Link = r#"</style.css>; rel=preload; as=style, </>; rel="home start""#
+///     .parse()
+///     .unwrap();
+///
+/// let preload = link.get("preload").unwrap();
+/// assert_eq!(preload.uri(), "/style.css");
+/// assert_eq!(preload.param("as"), Some("style"));
+/// assert_eq!(link.get("start").map(|l| l.uri()), Some("/"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Link {
+    values: Vec<LinkValue>,
+}
+
+/// A single link in a `Link` header: a target URI, a relation type, and any
+/// target attributes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LinkValue {
+    uri: String,
+    rel: String,
+    params: Vec<(String, String)>,
+}
+
+/// Returns `true` if `c` is a `tchar` and may thus appear in a token.
+#[inline]
+fn is_token_char(c: char) -> bool {
+    match c {
+        'a'...'z' | 'A'...'Z' | '0'...'9' => true,
+        '!' | '#' | '$' | '%' | '&' | '\'' | '*' | '+' | '-' | '.' | '^' | '_' | '`' | '|'
+            | '~' => true,
+        _ => false
+    }
+}
+
+/// Returns `true` if `c` is an ASCII control character other than a tab, and
+/// thus can't appear in a header value.
+#[inline]
+fn is_forbidden_char(c: char) -> bool {
+    (c < ' ' && c != '\t') || c == '\x7f'
+}
+
+/// Writes `value` as a token if it is one, and as a quoted string otherwise.
+fn write_value(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    if !value.is_empty() && value.chars().all(is_token_char) {
+        return write!(f, "{}", value);
+    }
+
+    write!(f, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => write!(f, "\\{}", c)?,
+            _ => write!(f, "{}", c)?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
+/// Writes `uri` between angle brackets, percent-encoding characters that
+/// can't appear in a URI reference.
+fn write_uri(f: &mut fmt::Formatter, uri: &str) -> fmt::Result {
+    write!(f, "<")?;
+    for c in uri.chars() {
+        match c {
+            '<' | '>' | '"' | ' ' | '\t' => write!(f, "%{:02X}", c as u8)?,
+            c if is_forbidden_char(c) => write!(f, "%{:02X}", c as u8)?,
+            _ => write!(f, "{}", c)?,
+        }
+    }
+
+    write!(f, ">")
+}
+
+impl Link {
+    /// Creates a new, empty `Link` header value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Link;
+    ///
+    /// let link = Link::new();
+    /// assert!(link.is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn new() -> Link {
+        Link { values: vec![] }
+    }
+
+    /// Adds a link to `uri` with the relation type `rel`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Link;
+    ///
+    /// let link = Link::new().add("https://example.com/?page=2", "next");
+    /// assert_eq!(link.to_string(), "<https://example.com/?page=2>; rel=next");
+    /// ```
+    #[inline]
+    pub fn add<U, R>(self, uri: U, rel: R) -> Link
+        where U: Into<String>, R: Into<String>
+    {
+        self.add_with(uri, rel, &[])
+    }
+
+    /// Adds a link to `uri` with the relation type `rel` and the target
+    /// attributes `params`, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rel` or any attribute value contains a control character
+    /// other than a tab, or if any attribute name isn't a non-empty token.
+    /// Such links can't be written into a header.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Link;
+    ///
+    /// let link = Link::new().add_with("/a", "alternate", &[("type", "text/html")]);
+    /// assert_eq!(link.to_string(), r#"</a>; rel=alternate; type="text/html""#);
+    /// ```
+    pub fn add_with<U, R>(mut self, uri: U, rel: R, params: &[(&str, &str)]) -> Link
+        where U: Into<String>, R: Into<String>
+    {
+        let rel = rel.into();
+        assert!(!rel.chars().any(is_forbidden_char),
+                "link relation types can't contain control characters");
+
+        for &(name, value) in params {
+            assert!(!name.is_empty() && name.chars().all(is_token_char),
+                    "link attribute names must be tokens");
+            assert!(!value.chars().any(is_forbidden_char),
+                    "link attribute values can't contain control characters");
+        }
+
+        self.values.push(LinkValue {
+            uri: uri.into(),
+            rel: rel,
+            params: params.iter().map(|&(k, v)| (k.to_string(), v.to_string())).collect(),
+        });
+
+        self
+    }
+
+    /// Returns the number of links in `self`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Link;
+    ///
+    /// let link = Link::new().add("/1", "first").add("/9", "last");
+    /// assert_eq!(link.len(), 2);
+    /// ```
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if `self` contains no links.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns an iterator over the links in `self`, in order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Link;
+    ///
+    /// let link = Link::new().add("/1", "first").add("/9", "last");
+    /// let uris: Vec<_> = link.iter().map(|l| l.uri()).collect();
+    /// assert_eq!(uris, vec!["/1", "/9"]);
+    /// ```
+    #[inline]
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item=&'a LinkValue> + 'a {
+        self.values.iter()
+    }
+
+    /// Returns the first link in `self` with the relation type `rel`, if any.
+    /// Relation types are compared case-insensitively.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Link;
+    ///
+    /// let link = Link::new().add("/1", "first").add("/3", "next");
+    /// assert_eq!(link.get("Next").map(|l| l.uri()), Some("/3"));
+    /// assert!(link.get("prev").is_none());
+    /// ```
+    pub fn get(&self, rel: &str) -> Option<&LinkValue> {
+        self.values.iter().find(|value| value.has_rel(rel))
+    }
+}
+
+impl LinkValue {
+    /// Returns the target URI of this link, as written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Link;
+    ///
+    /// let link = Link::new().add("/items?page=2", "next");
+    /// assert_eq!(link.get("next").unwrap().uri(), "/items?page=2");
+    /// ```
+    #[inline(always)]
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Returns the relation type of this link, which may be a space-separated
+    /// list of relation types.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Link;
+    ///
+    /// let link = Link::new().add("/", "home start");
+    /// assert_eq!(link.get("home").unwrap().rel(), "home start");
+    /// ```
+    #[inline(always)]
+    pub fn rel(&self) -> &str {
+        &self.rel
+    }
+
+    /// Returns `true` if `rel` is one of the relation types of this link.
+    /// Relation types are compared case-insensitively.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Link;
+    ///
+    /// let link = Link::new().add("/", "home start");
+    /// let value = link.get("start").unwrap();
+    /// assert!(value.has_rel("HOME"));
+    /// assert!(!value.has_rel("next"));
+    /// ```
+    pub fn has_rel(&self, rel: &str) -> bool {
+        self.rel.split_whitespace().any(|r| r.eq_ignore_ascii_case(rel))
+    }
+
+    /// Returns the value of the first target attribute named `name`, if any.
+    /// Names are compared case-insensitively.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Link;
+    ///
+    /// let link = Link::new().add_with("/a", "alternate", &[("type", "text/html")]);
+    /// let value = link.get("alternate").unwrap();
+    /// assert_eq!(value.param("Type"), Some("text/html"));
+    /// assert_eq!(value.param("title"), None);
+    /// ```
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter()
+            .find(|&&(ref key, _)| key.eq_ignore_ascii_case(name))
+            .map(|&(_, ref value)| value.as_str())
+    }
+
+    /// Returns an iterator over the target attributes of this link, other
+    /// than `rel`, as `(name, value)` pairs in order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::Link;
+    ///
+    /// let link = Link::new().add_with("/", "x", &[("a", "1"), ("b", "2")]);
+    /// let params: Vec<_> = link.get("x").unwrap().params().collect();
+    /// assert_eq!(params, vec![("a", "1"), ("b", "2")]);
+    /// ```
+    #[inline]
+    pub fn params<'a>(&'a self) -> impl Iterator<Item=(&'a str, &'a str)> + 'a {
+        self.params.iter().map(|&(ref key, ref value)| (key.as_str(), value.as_str()))
+    }
+}
+
+/// A cursor over the value of a `Link` header being parsed.
+struct Parser<'a> {
+    string: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.string.as_bytes().get(self.pos).cloned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek() == Some(b' ') || self.peek() == Some(b'\t') {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips whitespace and then `byte`, returning `true`, if `byte` is next.
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            return true;
+        }
+
+        false
+    }
+
+    /// Parses a target URI between angle brackets, decoding the characters
+    /// that `write_uri` percent-encodes. Other percent-encoded characters are
+    /// left as they are.
+    fn uri(&mut self) -> Result<String, &'static str> {
+        if !self.eat(b'<') {
+            return Err("link target must be between angle brackets");
+        }
+
+        let string = self.string;
+        let raw = match string[self.pos..].find('>') {
+            Some(i) => {
+                let raw = &string[self.pos..(self.pos + i)];
+                self.pos += i + 1;
+                raw
+            }
+            None => return Err("unterminated link target")
+        };
+
+        let mut uri = String::with_capacity(raw.len());
+        let mut rest = raw;
+        while let Some(i) = rest.find('%') {
+            uri.push_str(&rest[..i]);
+            let is_hex = |b: &u8| match *b {
+                b'0'...b'9' | b'a'...b'f' | b'A'...b'F' => true,
+                _ => false
+            };
+
+            let bytes = rest.as_bytes();
+            let decoded = match (bytes.get(i + 1), bytes.get(i + 2)) {
+                (Some(a), Some(b)) if is_hex(a) && is_hex(b) => {
+                    let c = u8::from_str_radix(&rest[(i + 1)..(i + 3)], 16).unwrap() as char;
+                    match c {
+                        '<' | '>' | '"' | ' ' | '\t' => Some(c),
+                        c if is_forbidden_char(c) => Some(c),
+                        _ => None
+                    }
+                }
+                _ => None
+            };
+
+            match decoded {
+                Some(c) => {
+                    uri.push(c);
+                    rest = &rest[(i + 3)..];
+                }
+                None => {
+                    uri.push('%');
+                    rest = &rest[(i + 1)..];
+                }
+            }
+        }
+
+        uri.push_str(rest);
+        Ok(uri)
+    }
+
+    /// Parses a token, which may be empty.
+    fn token(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.peek().map_or(false, |b| is_token_char(b as char)) {
+            self.pos += 1;
+        }
+
+        &self.string[start..self.pos]
+    }
+
+    /// Parses a token or a quoted string.
+    fn value(&mut self) -> Result<String, &'static str> {
+        self.skip_whitespace();
+        if self.peek() != Some(b'"') {
+            return match self.token() {
+                "" => Err("malformed link attribute value"),
+                token => Ok(token.to_string())
+            };
+        }
+
+        let string = self.string;
+        let start = self.pos + 1;
+        let mut value = String::new();
+        let mut chars = string[start..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos = start + i + 1;
+                    return Ok(value);
+                }
+                '\\' => match chars.next() {
+                    Some((_, c)) if !is_forbidden_char(c) => value.push(c),
+                    _ => break
+                },
+                c if is_forbidden_char(c) => break,
+                c => value.push(c)
+            }
+        }
+
+        Err("unterminated or malformed quoted string")
+    }
+
+    /// Parses a single link: a target URI followed by its attributes.
+    fn link_value(&mut self) -> Result<LinkValue, &'static str> {
+        let uri = self.uri()?;
+        let (mut rel, mut params) = (None, vec![]);
+        while self.eat(b';') {
+            let name = self.token();
+            if name.is_empty() {
+                return Err("malformed link attribute");
+            }
+
+            let value = match self.eat(b'=') {
+                true => self.value()?,
+                false => String::new()
+            };
+
+            // Only the first `rel` attribute counts; later ones are ignored.
+            if name.eq_ignore_ascii_case("rel") {
+                if rel.is_none() {
+                    rel = Some(value);
+                }
+            } else {
+                params.push((name.to_string(), value));
+            }
+        }
+
+        match rel {
+            Some(rel) => Ok(LinkValue { uri: uri, rel: rel, params: params }),
+            None => Err("link is missing a `rel` attribute")
+        }
+    }
+}
+
+impl FromStr for Link {
+    type Err = &'static str;
+
+    /// Parses the value of a `Link` header: a comma-separated list of links,
+    /// each a target URI between angle brackets followed by `;`-separated
+    /// attributes, one of which must be `rel`. Attribute values may be tokens
+    /// or quoted strings. Returns an `Err` if any link is malformed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use rocket::http::Link;
+    ///
+    /// assert!(Link::from_str("</a>; rel=next").is_ok());
+    /// assert!(Link::from_str(r#"</a,b>; rel="next"; title="a, b""#).is_ok());
+    /// assert!(Link::from_str("</a>").is_err());
+    /// assert!(Link::from_str("/a; rel=next").is_err());
+    /// ```
+    fn from_str(string: &str) -> Result<Link, &'static str> {
+        let mut parser = Parser { string: string, pos: 0 };
+        let mut link = Link::new();
+        loop {
+            // Empty elements in the list are allowed and ignored.
+            while parser.eat(b',') {  }
+            if parser.peek().is_none() {
+                return Ok(link);
+            }
+
+            link.values.push(parser.link_value()?);
+            parser.skip_whitespace();
+            match parser.peek() {
+                None | Some(b',') => continue,
+                Some(_) => return Err("malformed link")
+            }
+        }
+    }
+}
+
+impl fmt::Display for LinkValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_uri(f, &self.uri)?;
+        write!(f, "; rel=")?;
+        write_value(f, &self.rel)?;
+        for &(ref name, ref value) in &self.params {
+            write!(f, "; {}=", name)?;
+            write_value(f, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, value) in self.values.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{}", value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates a new `Header` with name `Link` and the value set to the HTTP
+/// rendering of these links.
+impl Into<Header<'static>> for Link {
+    #[inline]
+    fn into(self) -> Header<'static> {
+        Header::new("Link", self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Link;
+
+    #[test]
+    fn test_pagination() {
+        let link = Link::new()
+            .add("/items?page=1", "first")
+            .add("/items?page=4", "prev")
+            .add("/items?page=6", "next")
+            .add("/items?page=10", "last");
+
+        assert_eq!(link.to_string(), "</items?page=1>; rel=first, \
+            </items?page=4>; rel=prev, </items?page=6>; rel=next, \
+            </items?page=10>; rel=last");
+    }
+
+    #[test]
+    fn test_quoting() {
+        assert_eq!(Link::new().add("/", "next prev").to_string(), "</>; rel=\"next prev\"");
+        assert_eq!(Link::new().add("/", "").to_string(), "</>; rel=\"\"");
+        assert_eq!(Link::new().add_with("/", "x", &[("title", "a \"b\" \\c")]).to_string(),
+                   r#"</>; rel=x; title="a \"b\" \\c""#);
+        assert_eq!(Link::new().add_with("/", "x", &[("a", "1"), ("b", "2")]).to_string(),
+                   "</>; rel=x; a=1; b=2");
+    }
+
+    #[test]
+    fn test_uri_escaping() {
+        assert_eq!(Link::new().add("/a b>c", "x").to_string(), "</a%20b%3Ec>; rel=x");
+        assert_eq!(Link::new().add("/<\"", "x").to_string(), "</%3C%22>; rel=x");
+    }
+
+    #[test]
+    fn test_control_characters_are_never_written() {
+        let link = Link::new().add("/a\r\nSet-Cookie: x=y", "x");
+        assert_eq!(link.to_string(), "</a%0D%0ASet-Cookie:%20x=y>; rel=x");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_control_characters_in_values_panic() {
+        Link::new().add_with("/", "x", &[("t", "a\r\nSet-Cookie: x=y")]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_control_characters_in_rel_panic() {
+        Link::new().add("/", "x\ny");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_token_names_panic() {
+        Link::new().add_with("/", "x", &[("a b", "1")]);
+    }
+
+    #[test]
+    fn test_parse() {
+        let link: Link = "</a>; rel=next, </b>;rel=\"prev first\";title=\"x, \\\"y\\\"\""
+            .parse().unwrap();
+
+        assert_eq!(link.len(), 2);
+        assert_eq!(link.get("next").unwrap().uri(), "/a");
+        assert_eq!(link.get("FIRST").unwrap().uri(), "/b");
+        assert_eq!(link.get("prev").unwrap().param("title"), Some("x, \"y\""));
+
+        let link: Link = " , </a,b>; REL=next; rel=prev; crossorigin ,".parse().unwrap();
+        let value = link.get("next").unwrap();
+        assert_eq!(value.uri(), "/a,b");
+        assert!(!value.has_rel("prev"));
+        assert_eq!(value.params().collect::<Vec<_>>(), vec![("crossorigin", "")]);
+
+        assert_eq!("".parse::<Link>(), Ok(Link::new()));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("</a>".parse::<Link>().is_err());
+        assert!("/a; rel=next".parse::<Link>().is_err());
+        assert!("</a; rel=next".parse::<Link>().is_err());
+        assert!("</a>; rel=\"next".parse::<Link>().is_err());
+        assert!("</a>; rel=".parse::<Link>().is_err());
+        assert!("</a>; =next".parse::<Link>().is_err());
+        assert!("</a>; rel=next </b>; rel=prev".parse::<Link>().is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let link = Link::new()
+            .add("/items?page=2", "next")
+            .add_with("/", "home start", &[("title", "a \"b\", c"), ("hreflang", "en")]);
+
+        assert_eq!(link.to_string().parse::<Link>(), Ok(link));
+    }
+
+    #[test]
+    fn test_round_trip_escaped_uri() {
+        let link = Link::new().add("/a b\t<c>\"d\"?q=%2F", "x");
+        assert_eq!(link.to_string(), "</a%20b%09%3Cc%3E%22d%22?q=%2F>; rel=x");
+        assert_eq!(link.to_string().parse::<Link>(), Ok(link));
+
+        let link: Link = "</100%25%2>; rel=x".parse().unwrap();
+        assert_eq!(link.get("x").unwrap().uri(), "/100%25%2");
+    }
+
+    #[test]
+    fn test_parse_rejects_control_characters() {
+        assert!("</a>; rel=\"a\x01b\"".parse::<Link>().is_err());
+        assert!("</a>; rel=\"a\\\x01b\"".parse::<Link>().is_err());
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(Link::new().is_empty());
+        assert_eq!(Link::new().to_string(), "");
+        assert_eq!(Link::new().add("/", "x").len(), 1);
+    }
+}
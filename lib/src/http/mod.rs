@@ -7,16 +7,44 @@
 //! [hyper](hyper/index.html) should be considered unstable.
 pub mod hyper;
 pub mod uri;
+#[doc(hidden)] pub mod base64;
+#[doc(hidden)] pub mod hash;
 
 mod cookies;
 mod method;
 mod content_type;
 mod status;
 mod header;
+mod host;
+mod etag;
+mod range;
+mod content_disposition;
+mod link;
+mod accept_language;
+mod accept;
+mod authorization;
+mod date;
+mod typed;
+mod raw_str;
+mod digest;
+mod canonical;
 
 pub use self::method::Method;
 pub use self::content_type::ContentType;
 pub use self::status::{Status, StatusClass};
-pub use self::header::{Header, HeaderMap};
+pub use self::header::{Header, HeaderMap, HeaderEntry};
+pub use self::host::Host;
+pub use self::etag::ETag;
+pub use self::range::{Range, ByteRange};
+pub use self::content_disposition::ContentDisposition;
+pub use self::link::{Link, LinkValue};
+pub use self::accept_language::AcceptLanguage;
+pub use self::accept::Accept;
+pub use self::authorization::Authorization;
+pub use self::date::{Date, LastModified, Expires, RetryAfter};
+pub use self::typed::{ContentLength, Location, Server, Allow};
+pub use self::raw_str::RawStr;
+pub use self::digest::{Digest, ContentMd5};
+pub use self::canonical::{HeaderPolicy, Duplicates};
 
 pub use self::cookies::{Cookie, Cookies};
@@ -0,0 +1,387 @@
+use std::ascii::AsciiExt;
+use std::str::FromStr;
+use std::cmp::{min, max};
+use std::fmt;
+
+/// A single byte range from a `Range` header.
+///
+/// Positions are zero-based and inclusive, as in the header itself. A byte
+/// range is resolved against the length of a representation with
+/// [resolve](#method.resolve).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ByteRange {
+    /// The bytes from the first position to the last position, inclusive:
+    /// `first-last`.
+    Bounded(u64, u64),
+    /// The bytes from the first position to the end: `first-`.
+    From(u64),
+    /// The last given number of bytes: `-length`.
+    Suffix(u64),
+}
+
+impl ByteRange {
+    /// Resolves `self` against a representation that is `len` bytes long,
+    /// returning the first and last positions, inclusive, of the bytes that
+    /// `self` selects. A last position past the end is clamped to the end.
+    /// Returns `None` if `self` is unsatisfiable: it starts past the end, or
+    /// it is an empty suffix.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::ByteRange;
+    ///
+    /// assert_eq!(ByteRange::Bounded(0, 499).resolve(10000), Some((0, 499)));
+    /// assert_eq!(ByteRange::Bounded(9500, 10500).resolve(10000), Some((9500, 9999)));
+    /// assert_eq!(ByteRange::From(9500).resolve(10000), Some((9500, 9999)));
+    /// assert_eq!(ByteRange::Suffix(500).resolve(10000), Some((9500, 9999)));
+    /// assert_eq!(ByteRange::Suffix(20000).resolve(10000), Some((0, 9999)));
+    ///
+    /// assert_eq!(ByteRange::From(10000).resolve(10000), None);
+    /// assert_eq!(ByteRange::Suffix(0).resolve(10000), None);
+    /// ```
+    pub fn resolve(&self, len: u64) -> Option<(u64, u64)> {
+        if len == 0 {
+            return None;
+        }
+
+        match *self {
+            ByteRange::Bounded(first, _) | ByteRange::From(first) if first >= len => None,
+            ByteRange::Bounded(first, last) => Some((first, min(last, len - 1))),
+            ByteRange::From(first) => Some((first, len - 1)),
+            ByteRange::Suffix(0) => None,
+            ByteRange::Suffix(n) if n >= len => Some((0, len - 1)),
+            ByteRange::Suffix(n) => Some((len - n, len - 1)),
+        }
+    }
+}
+
+impl FromStr for ByteRange {
+    type Err = &'static str;
+
+    /// Parses a single byte range specifier: `first-last`, `first-`, or
+    /// `-length`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use rocket::http::ByteRange;
+    ///
+    /// assert_eq!(ByteRange::from_str("0-499"), Ok(ByteRange::Bounded(0, 499)));
+    /// assert_eq!(ByteRange::from_str("500-"), Ok(ByteRange::From(500)));
+    /// assert_eq!(ByteRange::from_str("-500"), Ok(ByteRange::Suffix(500)));
+    ///
+    /// assert!(ByteRange::from_str("500-499").is_err());
+    /// assert!(ByteRange::from_str("-").is_err());
+    /// ```
+    fn from_str(string: &str) -> Result<ByteRange, &'static str> {
+        let string = string.trim();
+        let dash = match string.find('-') {
+            Some(i) => i,
+            None => return Err("Byte ranges must contain a '-'."),
+        };
+
+        let (first, last) = (&string[..dash], &string[(dash + 1)..]);
+        let parse = |s: &str| -> Result<u64, &'static str> {
+            if s.is_empty() || !s.bytes().all(|b| b >= b'0' && b <= b'9') {
+                return Err("Byte range positions must be non-negative integers.");
+            }
+
+            s.parse().map_err(|_| "Byte range position is too large.")
+        };
+
+        match (first.is_empty(), last.is_empty()) {
+            (true, true) => Err("Byte ranges must have a first position or a length."),
+            (true, false) => Ok(ByteRange::Suffix(parse(last)?)),
+            (false, true) => Ok(ByteRange::From(parse(first)?)),
+            (false, false) => {
+                let (first, last) = (parse(first)?, parse(last)?);
+                if last < first {
+                    return Err("The last position of a byte range precedes the first.");
+                }
+
+                Ok(ByteRange::Bounded(first, last))
+            }
+        }
+    }
+}
+
+impl fmt::Display for ByteRange {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ByteRange::Bounded(first, last) => write!(f, "{}-{}", first, last),
+            ByteRange::From(first) => write!(f, "{}-", first),
+            ByteRange::Suffix(n) => write!(f, "-{}", n),
+        }
+    }
+}
+
+/// The most byte ranges a `Range` header may contain. Headers with more are
+/// rejected when parsed, so the request is answered as if it had no `Range`.
+const MAX_RANGES: usize = 16;
+
+/// The value of a `Range` request header with the `bytes` unit.
+///
+/// A `Range` header requests one or more parts of a representation. Each part
+/// is a [ByteRange](enum.ByteRange.html). The ranges are resolved against the
+/// length of the representation with [resolve](#method.resolve), which
+/// coalesces overlapping and adjacent ranges. If none of them are satisfiable,
+/// the response should be `416 Range Not Satisfiable`. A header with more than
+/// 16 byte ranges doesn't parse, so that a handful of bytes can't request an
+/// enormous multipart response; such a header should be ignored and the full
+/// representation sent with `200 OK`. See [RFC 7233 §3.1] for details.
+///
+/// [RFC 7233 §3.1]: https://tools.ietf.org/html/rfc7233#section-3.1
+///
+/// # Example
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use rocket::http::{Range, ByteRange};
+///
+/// let range = Range::from_str("bytes=0-99, 200-, -50").unwrap();
+/// assert_eq!(range.ranges(), &[
+///     ByteRange::Bounded(0, 99), ByteRange::From(200), ByteRange::Suffix(50)
+/// ]);
+///
+/// assert_eq!(range.resolve(1000), Some(vec![(0, 99), (200, 999)]));
+/// assert_eq!(range.to_string(), "bytes=0-99,200-,-50");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Range {
+    ranges: Vec<ByteRange>,
+}
+
+impl Range {
+    /// Creates a new `Range` from the byte ranges `ranges`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ranges` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::{Range, ByteRange};
+    ///
+    /// let range = Range::new(vec![ByteRange::From(100)]);
+    /// assert_eq!(range.to_string(), "bytes=100-");
+    /// ```
+    #[inline]
+    pub fn new(ranges: Vec<ByteRange>) -> Range {
+        assert!(!ranges.is_empty(), "a range must contain at least one byte range");
+        Range { ranges: ranges }
+    }
+
+    /// Returns the byte ranges in `self`, in the order they were requested.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::{Range, ByteRange};
+    ///
+    /// let range = Range::new(vec![ByteRange::Suffix(10)]);
+    /// assert_eq!(range.ranges(), &[ByteRange::Suffix(10)]);
+    /// ```
+    #[inline(always)]
+    pub fn ranges(&self) -> &[ByteRange] {
+        &self.ranges
+    }
+
+    /// Resolves each byte range in `self` against a representation that is
+    /// `len` bytes long, returning the first and last positions, inclusive, of
+    /// the bytes selected, in ascending order. Unsatisfiable ranges are
+    /// dropped, and ranges that overlap or are adjacent are coalesced into
+    /// one. Returns `None` if no range is satisfiable.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use rocket::http::Range;
+    ///
+    /// let range = Range::from_str("bytes=0-9,5000-").unwrap();
+    /// assert_eq!(range.resolve(100), Some(vec![(0, 9)]));
+    /// assert_eq!(range.resolve(0), None);
+    ///
+    /// let range = Range::from_str("bytes=50-59,0-9,5-19,20-29").unwrap();
+    /// assert_eq!(range.resolve(100), Some(vec![(0, 29), (50, 59)]));
+    ///
+    /// let range = Range::from_str("bytes=100-").unwrap();
+    /// assert_eq!(range.resolve(100), None);
+    /// ```
+    pub fn resolve(&self, len: u64) -> Option<Vec<(u64, u64)>> {
+        let mut resolved: Vec<_> = self.ranges.iter()
+            .filter_map(|range| range.resolve(len))
+            .collect();
+
+        resolved.sort();
+        let mut coalesced: Vec<(u64, u64)> = Vec::with_capacity(resolved.len());
+        for (first, last) in resolved {
+            if let Some(previous) = coalesced.last_mut() {
+                if first <= previous.1.saturating_add(1) {
+                    previous.1 = max(previous.1, last);
+                    continue;
+                }
+            }
+
+            coalesced.push((first, last));
+        }
+
+        if coalesced.is_empty() {
+            None
+        } else {
+            Some(coalesced)
+        }
+    }
+}
+
+impl FromStr for Range {
+    type Err = &'static str;
+
+    /// Parses the value of a `Range` header. The unit must be `bytes`. Empty
+    /// list elements are ignored, but there must be at least one byte range
+    /// and at most 16.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use rocket::http::Range;
+    ///
+    /// assert!(Range::from_str("bytes=0-499").is_ok());
+    /// assert!(Range::from_str("bytes=0-499, , -5").is_ok());
+    ///
+    /// assert!(Range::from_str("bytes=").is_err());
+    /// assert!(Range::from_str("items=0-5").is_err());
+    /// assert!(Range::from_str("bytes=5-0").is_err());
+    ///
+    /// let many = vec!["0-0"; 17].join(",");
+    /// assert!(Range::from_str(&format!("bytes={}", many)).is_err());
+    /// ```
+    fn from_str(string: &str) -> Result<Range, &'static str> {
+        let string = string.trim();
+        let eq = match string.find('=') {
+            Some(i) => i,
+            None => return Err("Range must begin with a unit followed by '='."),
+        };
+
+        if !string[..eq].trim().eq_ignore_ascii_case("bytes") {
+            return Err("Only the 'bytes' range unit is supported.");
+        }
+
+        let mut ranges = vec![];
+        for spec in string[(eq + 1)..].split(',') {
+            if spec.trim().is_empty() {
+                continue;
+            }
+
+            if ranges.len() == MAX_RANGES {
+                return Err("Range contains too many byte ranges.");
+            }
+
+            ranges.push(ByteRange::from_str(spec)?);
+        }
+
+        if ranges.is_empty() {
+            return Err("Range must contain at least one byte range.");
+        }
+
+        Ok(Range { ranges: ranges })
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bytes=")?;
+        for (i, range) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+
+            write!(f, "{}", range)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Range, ByteRange};
+    use super::ByteRange::*;
+    use std::str::FromStr;
+
+    fn ranges(string: &str) -> Vec<ByteRange> {
+        Range::from_str(string).unwrap().ranges().to_vec()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(ranges("bytes=0-0"), vec![Bounded(0, 0)]);
+        assert_eq!(ranges(" BYTES = 1-2 ,3-, -4 "), vec![Bounded(1, 2), From(3), Suffix(4)]);
+        assert_eq!(ranges("bytes=,,0-1,"), vec![Bounded(0, 1)]);
+        assert_eq!(ranges("bytes=18446744073709551615-"), vec![From(::std::u64::MAX)]);
+
+        assert!(Range::from_str("").is_err());
+        assert!(Range::from_str("0-1").is_err());
+        assert!(Range::from_str("bytes=,").is_err());
+        assert!(Range::from_str("bytes=1").is_err());
+        assert!(Range::from_str("bytes=-").is_err());
+        assert!(Range::from_str("bytes=+1-2").is_err());
+        assert!(Range::from_str("bytes=1--2").is_err());
+        assert!(Range::from_str("bytes=a-b").is_err());
+        assert!(Range::from_str("bytes=2-1").is_err());
+        assert!(Range::from_str("bytes=0-1,2-1").is_err());
+        assert!(Range::from_str("bytes=18446744073709551616-").is_err());
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(Bounded(0, 0).resolve(1), Some((0, 0)));
+        assert_eq!(Bounded(0, 100).resolve(10), Some((0, 9)));
+        assert_eq!(Bounded(10, 100).resolve(10), None);
+        assert_eq!(From(0).resolve(0), None);
+        assert_eq!(From(9).resolve(10), Some((9, 9)));
+        assert_eq!(Suffix(1).resolve(10), Some((9, 9)));
+        assert_eq!(Suffix(10).resolve(10), Some((0, 9)));
+        assert_eq!(Suffix(1).resolve(0), None);
+    }
+
+    #[test]
+    fn test_resolve_multiple() {
+        let range = Range::from_str("bytes=0-4,100-200,-3").unwrap();
+        assert_eq!(range.resolve(10), Some(vec![(0, 4), (7, 9)]));
+        assert_eq!(range.resolve(150), Some(vec![(0, 4), (100, 149)]));
+        assert_eq!(range.resolve(0), None);
+
+        let range = Range::from_str("bytes=50-,60-70").unwrap();
+        assert_eq!(range.resolve(50), None);
+    }
+
+    #[test]
+    fn test_resolve_coalesces() {
+        let range = Range::from_str("bytes=0-0,0-0,0-0,1-1,3-").unwrap();
+        assert_eq!(range.resolve(10), Some(vec![(0, 1), (3, 9)]));
+
+        let range = Range::from_str("bytes=-5,0-,2-3").unwrap();
+        assert_eq!(range.resolve(10), Some(vec![(0, 9)]));
+    }
+
+    #[test]
+    fn test_range_count_is_capped() {
+        let ranges = |n: usize| format!("bytes={}", vec!["0-0"; n].join(","));
+        assert!(Range::from_str(&ranges(16)).is_ok());
+        assert!(Range::from_str(&ranges(17)).is_err());
+        assert!(Range::from_str(&format!("{},,,", ranges(16))).is_ok());
+    }
+
+    #[test]
+    fn test_display() {
+        for string in &["bytes=0-1", "bytes=5-", "bytes=-5", "bytes=0-1,5-,-5"] {
+            assert_eq!(&Range::from_str(string).unwrap().to_string(), string);
+        }
+    }
+}
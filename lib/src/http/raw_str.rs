@@ -0,0 +1,259 @@
+use std::borrow::{Borrow, Cow};
+use std::ops::Deref;
+use std::str::Utf8Error;
+use std::fmt;
+
+use http::uri::URI;
+
+/// A reference to a string that is still in its raw, possibly percent-encoded
+/// form, as it was received in a request.
+///
+/// Route parameters, query strings, and form values arrive percent-encoded.
+/// `RawStr` makes this explicit: to get at the string a user intended, the
+/// value must first be decoded via [url_decode](#method.url_decode),
+/// [percent_decode](#method.percent_decode), or
+/// [percent_decode_lossy](#method.percent_decode_lossy). Before a value is
+/// embedded in HTML, it should be escaped via
+/// [html_escape](#method.html_escape).
+///
+/// `RawStr` dereferences to `str`, so the raw string can always be inspected
+/// directly.
+///
+/// # Form Values
+///
+/// `&RawStr` implements [FromFormValue](/rocket/request/trait.FromFormValue.html),
+/// receiving the field's value exactly as it appeared in the form:
+///
+/// ```rust,ignore
+/// #[derive(FromForm)]
+/// struct Search<'r> {
+///     query: &'r RawStr,
+/// }
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::RawStr;
+///
+/// let raw = RawStr::from_str("Hello%2C+world%21");
+/// assert_eq!(raw.url_decode(), Ok("Hello, world!".to_string()));
+/// assert_eq!(raw.as_str(), "Hello%2C+world%21");
+/// ```
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RawStr(str);
+
+impl RawStr {
+    /// Constructs an `&RawStr` from an `&str` at no cost.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::RawStr;
+    ///
+    /// let raw = RawStr::from_str("Hello%21");
+    /// assert_eq!(raw, "Hello%21");
+    /// ```
+    #[inline(always)]
+    pub fn from_str<'a>(string: &'a str) -> &'a RawStr {
+        unsafe { &*(string as *const str as *const RawStr) }
+    }
+
+    /// Returns the raw, undecoded string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::RawStr;
+    ///
+    /// let raw = RawStr::from_str("a%20b");
+    /// assert_eq!(raw.as_str(), "a%20b");
+    /// ```
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the percent-decoded version of the string. Returns an `Err` if
+    /// the decoded string isn't valid UTF-8. `+` characters are left as they
+    /// are; use [url_decode](#method.url_decode) to decode form values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::RawStr;
+    ///
+    /// let raw = RawStr::from_str("Hello%2C%20world+%21");
+    /// assert_eq!(raw.percent_decode().unwrap(), "Hello, world+!");
+    ///
+    /// let bad = RawStr::from_str("%FF");
+    /// assert!(bad.percent_decode().is_err());
+    /// ```
+    #[inline(always)]
+    pub fn percent_decode(&self) -> Result<Cow<str>, Utf8Error> {
+        URI::percent_decode(self.0.as_bytes())
+    }
+
+    /// Returns the percent-decoded version of the string. Invalid UTF-8
+    /// sequences are replaced with � U+FFFD, the replacement character.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::RawStr;
+    ///
+    /// let raw = RawStr::from_str("caf%C3%A9%FF");
+    /// assert_eq!(raw.percent_decode_lossy(), "café\u{FFFD}");
+    /// ```
+    #[inline(always)]
+    pub fn percent_decode_lossy(&self) -> Cow<str> {
+        URI::percent_decode_lossy(self.0.as_bytes())
+    }
+
+    /// Returns the URL-decoded version of the string, as is appropriate for
+    /// query strings and form values: `+` characters are decoded as spaces
+    /// and the result is then percent-decoded. Returns an `Err` if the decoded
+    /// string isn't valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::RawStr;
+    ///
+    /// let raw = RawStr::from_str("1+%2B+1");
+    /// assert_eq!(raw.url_decode(), Ok("1 + 1".to_string()));
+    /// ```
+    pub fn url_decode(&self) -> Result<String, Utf8Error> {
+        let replaced = self.0.replace('+', " ");
+        URI::percent_decode(replaced.as_bytes()).map(|s| s.into_owned())
+    }
+
+    /// Returns an HTML-escaped version of the raw string: the characters `&`,
+    /// `<`, `>`, `"`, `'`, and `/` are replaced with character references.
+    /// The result is safe to embed in HTML text and quoted attribute values.
+    /// Note that the string isn't decoded first; decode it, then escape the
+    /// decoded string via `RawStr::from_str(&decoded).html_escape()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::RawStr;
+    ///
+    /// let raw = RawStr::from_str("<b>\"Tom\" & 'Jerry'</b>");
+    /// assert_eq!(raw.html_escape(),
+    ///            "&lt;b&gt;&quot;Tom&quot; &amp; &#x27;Jerry&#x27;&lt;&#x2F;b&gt;");
+    ///
+    /// let safe = RawStr::from_str("nothing to escape");
+    /// assert_eq!(safe.html_escape(), "nothing to escape");
+    /// ```
+    pub fn html_escape(&self) -> Cow<str> {
+        let is_special = |c: char| "&<>\"'/".contains(c);
+        if !self.0.contains(is_special) {
+            return Cow::Borrowed(&self.0);
+        }
+
+        let mut escaped = String::with_capacity(self.0.len() + 16);
+        for c in self.0.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&#x27;"),
+                '/' => escaped.push_str("&#x2F;"),
+                c => escaped.push(c)
+            }
+        }
+
+        Cow::Owned(escaped)
+    }
+}
+
+impl<'a> From<&'a str> for &'a RawStr {
+    #[inline(always)]
+    fn from(string: &'a str) -> &'a RawStr {
+        RawStr::from_str(string)
+    }
+}
+
+impl Deref for RawStr {
+    type Target = str;
+
+    #[inline(always)]
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for RawStr {
+    #[inline(always)]
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for RawStr {
+    #[inline(always)]
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for RawStr {
+    #[inline(always)]
+    fn eq(&self, other: &str) -> bool {
+        &self.0 == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for RawStr {
+    #[inline(always)]
+    fn eq(&self, other: &&'a str) -> bool {
+        &self.0 == *other
+    }
+}
+
+impl<'a, 'b> PartialEq<&'b str> for &'a RawStr {
+    #[inline(always)]
+    fn eq(&self, other: &&'b str) -> bool {
+        &self.0 == *other
+    }
+}
+
+impl fmt::Display for RawStr {
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Debug for RawStr {
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RawStr;
+
+    #[test]
+    fn test_url_decode() {
+        let decode = |s: &str| RawStr::from_str(s).url_decode();
+        assert_eq!(decode("a+b"), Ok("a b".to_string()));
+        assert_eq!(decode("a%2Bb"), Ok("a+b".to_string()));
+        assert_eq!(decode("%E2%9C%93+done"), Ok("✓ done".to_string()));
+        assert_eq!(decode(""), Ok("".to_string()));
+        assert!(decode("%C3%28").is_err());
+    }
+
+    #[test]
+    fn test_html_escape() {
+        let escape = |s: &'static str| RawStr::from_str(s).html_escape().into_owned();
+        assert_eq!(escape("<script>"), "&lt;script&gt;");
+        assert_eq!(escape("a&&b"), "a&amp;&amp;b");
+        assert_eq!(escape("héllo"), "héllo");
+        assert_eq!(escape(""), "");
+    }
+}
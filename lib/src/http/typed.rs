@@ -0,0 +1,165 @@
+use std::str::FromStr;
+use std::fmt;
+
+use http::{Header, Method};
+
+/// The `Content-Length` header: the size of a message body, in bytes.
+///
+/// Rocket sets this header itself when writing out a response with a sized
+/// body.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::{ContentLength, Header};
+///
+/// let header: Header = ContentLength(1024).into();
+/// assert_eq!(header.to_string(), "Content-Length: 1024");
+/// assert_eq!("1024".parse(), Ok(ContentLength(1024)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContentLength(pub u64);
+
+impl FromStr for ContentLength {
+    type Err = &'static str;
+
+    fn from_str(string: &str) -> Result<ContentLength, &'static str> {
+        let string = string.trim();
+        if string.is_empty() || !string.bytes().all(|b| b >= b'0' && b <= b'9') {
+            return Err("Content-Length must be a non-negative integer.");
+        }
+
+        string.parse().map(ContentLength).map_err(|_| "Content-Length is too large.")
+    }
+}
+
+impl fmt::Display for ContentLength {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Into<Header<'static>> for ContentLength {
+    #[inline]
+    fn into(self) -> Header<'static> {
+        Header::new("Content-Length", self.0.to_string())
+    }
+}
+
+macro_rules! string_header {
+    ($(#[$attr:meta])* struct $T:ident => $name:expr) => (
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub struct $T(pub String);
+
+        impl FromStr for $T {
+            type Err = &'static str;
+
+            #[inline]
+            fn from_str(string: &str) -> Result<$T, &'static str> {
+                Ok($T(string.trim().to_string()))
+            }
+        }
+
+        impl fmt::Display for $T {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl Into<Header<'static>> for $T {
+            #[inline]
+            fn into(self) -> Header<'static> {
+                Header::new($name, self.0)
+            }
+        }
+    )
+}
+
+string_header! {
+    /// The `Location` header: the URI to redirect to or of a newly created
+    /// resource.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    /// use rocket::http::{Location, Status};
+    ///
+    /// let response = Response::build()
+    ///     .status(Status::SeeOther)
+    ///     .header(Location("/login".into()))
+    ///     .finalize();
+    ///
+    /// assert_eq!(response.header_values("Location").next(), Some("/login"));
+    /// ```
+    struct Location => "Location"
+}
+
+string_header! {
+    /// The `Server` header: the software used by the origin server.
+    ///
+    /// Rocket sets this header to `rocket` on every response.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::http::{Header, Server};
+    ///
+    /// let header: Header = Server("rocket".into()).into();
+    /// assert_eq!(header.to_string(), "Server: rocket");
+    /// ```
+    struct Server => "Server"
+}
+
+/// The `Allow` header: the methods a resource supports.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::http::{Allow, Header, Method};
+///
+/// let header: Header = Allow(vec![Method::Get, Method::Head]).into();
+/// assert_eq!(header.to_string(), "Allow: GET, HEAD");
+///
+/// let allow: Allow = "get, POST".parse().unwrap();
+/// assert_eq!(allow, Allow(vec![Method::Get, Method::Post]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Allow(pub Vec<Method>);
+
+impl FromStr for Allow {
+    type Err = &'static str;
+
+    fn from_str(string: &str) -> Result<Allow, &'static str> {
+        let mut methods = vec![];
+        for method in string.split(',').map(|m| m.trim()).filter(|m| !m.is_empty()) {
+            methods.push(method.parse().map_err(|_| "Unknown method in Allow header.")?);
+        }
+
+        Ok(Allow(methods))
+    }
+}
+
+impl fmt::Display for Allow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, method) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+
+            write!(f, "{}", method)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Into<Header<'static>> for Allow {
+    #[inline]
+    fn into(self) -> Header<'static> {
+        Header::new("Allow", self.to_string())
+    }
+}
@@ -301,6 +301,11 @@ unsafe impl<'a> Sync for URI<'a> { /* It's safe! */ }
 
 impl<'a, 'b> Collider<URI<'b>> for URI<'a> {
     fn collides_with(&self, other: &URI<'b>) -> bool {
+        // The `*` target of `OPTIONS *` refers to the server, not to a path.
+        if self.as_str() == "*" || other.as_str() == "*" {
+            return false;
+        }
+
         for (seg_a, seg_b) in self.segments().zip(other.segments()) {
             if seg_a.ends_with("..>") || seg_b.ends_with("..>") {
                 return true;
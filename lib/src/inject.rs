@@ -0,0 +1,229 @@
+//! Dependency injection by type.
+//!
+//! A _provider_ is a function that constructs a value of some type, such as a
+//! database pool or an API client. Providers are registered with an
+//! application via [Rocket::provide](/rocket/struct.Rocket.html#method.provide)
+//! and [Rocket::provide_per_request](/rocket/struct.Rocket.html#method.provide_per_request),
+//! and the values they construct are injected into handlers that have an
+//! [Inject<T>](struct.Inject.html) argument, where `T` is the type of the
+//! value. There is at most one provider for any given type.
+//!
+//! Providers registered with `provide` are called once, when the application
+//! is launched, and the value they construct is shared by all requests.
+//! Providers registered with `provide_per_request` are called for every
+//! request that needs their value and are passed the request.
+//!
+//! # Testing
+//!
+//! Registering a provider for a type that already has one replaces the
+//! existing provider. Tests can thus substitute a service, say with a mock,
+//! without any global state by registering their own provider after building
+//! the application:
+//!
+//! ```rust
+//! # #![feature(plugin)]
+//! # #![plugin(rocket_codegen)]
+//! # extern crate rocket;
+//! #
+//! use rocket::Rocket;
+//! use rocket::inject::Inject;
+//!
+//! struct Mailer {
+//!     from: &'static str
+//! }
+//!
+//! #[get("/sender")]
+//! fn sender(mailer: Inject<Mailer>) -> &'static str {
+//!     mailer.from
+//! }
+//!
+//! fn app() -> Rocket {
+//!     rocket::ignite()
+//!         .mount("/", routes![sender])
+//!         .provide(|| Mailer { from: "hello@example.com" })
+//! }
+//!
+//! # fn main() {
+//! // In a test:
+//! let rocket = app().provide(|| Mailer { from: "test@localhost" });
+//! # }
+//! ```
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::fmt;
+
+use outcome::Outcome::*;
+use request::{self, Request, FromRequest};
+use http::Status;
+
+type LaunchConstructor = Box<Fn() -> Box<Any + Send> + Send + Sync>;
+type RequestConstructor = Box<Fn(&Request) -> Box<Any> + Send + Sync>;
+
+enum Provider {
+    /// The constructor and, once it has been called, the `Arc<T>` it produced.
+    Launch(LaunchConstructor, Mutex<Option<Box<Any + Send>>>),
+    /// The constructor, which produces a `T`.
+    Request(RequestConstructor),
+}
+
+/// The providers registered with an application, keyed by the type of the
+/// value they construct.
+#[doc(hidden)]
+pub struct Providers {
+    providers: HashMap<TypeId, Provider>
+}
+
+impl Providers {
+    #[doc(hidden)]
+    pub fn new() -> Providers {
+        Providers { providers: HashMap::new() }
+    }
+
+    /// Registers a provider called once for all requests. Returns `true` if a
+    /// provider for `T` was replaced.
+    #[doc(hidden)]
+    pub fn add_launch<T, F>(&mut self, constructor: F) -> bool
+        where T: Send + Sync + 'static, F: Fn() -> T + Send + Sync + 'static
+    {
+        let constructor: LaunchConstructor = Box::new(move || -> Box<Any + Send> {
+            Box::new(Arc::new(constructor()))
+        });
+
+        let provider = Provider::Launch(constructor, Mutex::new(None));
+        self.providers.insert(TypeId::of::<T>(), provider).is_some()
+    }
+
+    /// Registers a provider called for every request. Returns `true` if a
+    /// provider for `T` was replaced.
+    #[doc(hidden)]
+    pub fn add_request<T, F>(&mut self, constructor: F) -> bool
+        where T: 'static, F: Fn(&Request) -> T + Send + Sync + 'static
+    {
+        let constructor: RequestConstructor = Box::new(move |request: &Request| -> Box<Any> {
+            Box::new(constructor(request))
+        });
+
+        let provider = Provider::Request(constructor);
+        self.providers.insert(TypeId::of::<T>(), provider).is_some()
+    }
+
+    /// Returns the number of registered providers.
+    #[doc(hidden)]
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    /// Calls every provider that is called once and hasn't yet been called.
+    #[doc(hidden)]
+    pub fn initialize(&self) {
+        for provider in self.providers.values() {
+            if let Provider::Launch(ref constructor, ref value) = *provider {
+                let mut value = value.lock().expect("provider lock poisoned");
+                if value.is_none() {
+                    *value = Some(constructor());
+                }
+            }
+        }
+    }
+
+    /// Returns the value of type `T` for `request`, calling `T`'s provider if
+    /// necessary, or `None` if there is no provider for `T`.
+    fn get<T: 'static>(&self, request: &Request) -> Option<Inject<T>> {
+        match self.providers.get(&TypeId::of::<T>()) {
+            Some(&Provider::Launch(ref constructor, ref value)) => {
+                let mut value = value.lock().expect("provider lock poisoned");
+                if value.is_none() {
+                    *value = Some(constructor());
+                }
+
+                value.as_ref()
+                    .and_then(|value| value.downcast_ref::<Arc<T>>())
+                    .map(|value| Inject(Inner::Shared(value.clone())))
+            }
+            Some(&Provider::Request(ref constructor)) => {
+                constructor(request).downcast::<T>().ok()
+                    .map(|value| Inject(Inner::Owned(*value)))
+            }
+            None => None
+        }
+    }
+}
+
+enum Inner<T> {
+    Shared(Arc<T>),
+    Owned(T),
+}
+
+/// Request guard that injects the value of type `T` constructed by `T`'s
+/// provider. See the [module level documentation](index.html) for details.
+///
+/// `Inject<T>` dereferences to `T`. If no provider has been registered for
+/// `T`, the guard fails with a status of `500 Internal Server Error`.
+///
+/// # Example
+///
+/// A per-request provider can inspect the request. Below, every request is
+/// given a `RequestId`, taken from the `X-Request-Id` header if there is one:
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// #
+/// use rocket::inject::Inject;
+///
+/// struct RequestId(String);
+///
+/// #[get("/id")]
+/// fn id(request_id: Inject<RequestId>) -> String {
+///     request_id.0.clone()
+/// }
+///
+/// fn main() {
+/// # if false {
+///     rocket::ignite()
+///         .mount("/", routes![id])
+///         .provide_per_request(|request| {
+///             let id = request.headers().get_one("X-Request-Id").unwrap_or("none");
+///             RequestId(id.to_string())
+///         })
+///         .launch()
+/// # }
+/// }
+/// ```
+pub struct Inject<T: 'static>(Inner<T>);
+
+impl<T: 'static> Deref for Inject<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        match self.0 {
+            Inner::Shared(ref value) => &**value,
+            Inner::Owned(ref value) => value,
+        }
+    }
+}
+
+impl<T: fmt::Debug + 'static> fmt::Debug for Inject<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Inject").field(&**self).finish()
+    }
+}
+
+impl<'a, 'r, T: 'static> FromRequest<'a, 'r> for Inject<T> {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        match request.providers().and_then(|providers| providers.get::<T>(request)) {
+            Some(value) => Success(value),
+            None => {
+                error_!("A handler requested a type that has no provider.");
+                Failure((Status::InternalServerError, ()))
+            }
+        }
+    }
+}
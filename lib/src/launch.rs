@@ -0,0 +1,114 @@
+//! Information about a launching application and how it's reported.
+//!
+//! When an application launches, Rocket gathers what it's about to serve into
+//! a [LaunchInfo](struct.LaunchInfo.html) and hands it to the application's
+//! launch reporter. The default reporter, [log](fn.log.html), logs the familiar
+//! launch banner. A different reporter is installed via
+//! [Rocket::launch_reporter](/rocket/struct.Rocket.html#method.launch_reporter)
+//! to silence the banner, log it in another format, or display it elsewhere.
+//!
+//! # Example
+//!
+//! Log the address as a single line of JSON instead of the banner:
+//!
+//! ```rust
+//! # if false {
+//! rocket::ignite()
+//!     .launch_reporter(|info| {
+//!         println!("{{\"event\":\"launch\",\"url\":\"{}\",\"routes\":{}}}",
+//!                  info.url(), info.routes.len());
+//!     })
+//!     .launch()
+//! # }
+//! ```
+
+use term_painter::Color::*;
+use term_painter::ToStyle;
+
+use config::Environment;
+use router::Route;
+
+/// The type of a launch reporter: a function called with the launch
+/// information of an application once its server is listening, just before
+/// it begins serving requests.
+pub type LaunchReporter = Box<Fn(&LaunchInfo) + Send + Sync>;
+
+/// What an application serves, and where, as it launches.
+#[derive(Debug)]
+pub struct LaunchInfo<'a> {
+    /// The address the server is listening on.
+    pub address: String,
+    /// The port the server is listening on.
+    pub port: usize,
+    /// The protocol the server speaks, such as `http`.
+    pub protocol: &'static str,
+    /// The environment the application is configured for.
+    pub environment: Environment,
+    /// The mounted routes, ordered by path, then method, then rank.
+    pub routes: Vec<&'a Route>,
+    /// The status codes with a catcher registered by the application, in
+    /// ascending order.
+    pub catchers: Vec<u16>,
+    /// The number of request hooks.
+    pub request_hooks: usize,
+    /// The number of response hooks.
+    pub response_hooks: usize,
+    /// The number of values of managed state.
+    pub managed_state: usize,
+    /// The number of dependency providers.
+    pub providers: usize,
+    /// The names of the configuration parameters that are hot reloaded.
+    pub hot_reload: Vec<String>,
+}
+
+impl<'a> LaunchInfo<'a> {
+    /// Returns the URL the application is served from, such as
+    /// `http://localhost:8000`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::launch::LaunchInfo;
+    /// use rocket::config::Environment;
+    ///
+    /// let info = LaunchInfo {
+    ///     address: "localhost".into(),
+    ///     port: 8000,
+    ///     protocol: "http",
+    ///     environment: Environment::Development,
+    ///     routes: vec![],
+    ///     catchers: vec![],
+    ///     request_hooks: 0,
+    ///     response_hooks: 0,
+    ///     managed_state: 0,
+    ///     providers: 0,
+    ///     hot_reload: vec![],
+    /// };
+    ///
+    /// assert_eq!(info.url(), "http://localhost:8000");
+    /// ```
+    pub fn url(&self) -> String {
+        format!("{}://{}:{}", self.protocol, self.address, self.port)
+    }
+}
+
+/// The default launch reporter: logs the launch banner at the `normal` log
+/// level. Custom reporters can call this function to keep the banner.
+pub fn log(info: &LaunchInfo) {
+    if info.managed_state > 0 {
+        info!("📦  Managed state: {} values.", White.paint(info.managed_state));
+    }
+
+    if info.providers > 0 {
+        info!("💉  Providers: {}.", White.paint(info.providers));
+    }
+
+    if !info.hot_reload.is_empty() {
+        info!("🔧  Hot reloading: {}.", White.paint(info.hot_reload.join(", ")));
+    }
+
+    info!("🚀  {} {}{}...",
+          White.paint("Rocket has launched from"),
+          White.bold().paint(format!("{}://", info.protocol)),
+          White.bold().paint(format!("{}:{}", info.address, info.port)));
+}
@@ -94,6 +94,13 @@ extern crate term_painter;
 extern crate hyper;
 extern crate url;
 extern crate toml;
+extern crate libc;
+extern crate md5;
+extern crate sha2;
+#[cfg(feature = "encrypted_config")] extern crate ring;
+#[cfg(feature = "chrono")] extern crate chrono;
+#[cfg(feature = "uuid")] extern crate uuid;
+#[cfg(feature = "bigdecimal")] extern crate bigdecimal;
 
 #[cfg(test)] #[macro_use] extern crate lazy_static;
 
@@ -105,7 +112,19 @@ pub mod response;
 pub mod outcome;
 pub mod config;
 pub mod data;
+pub mod body;
 pub mod handler;
+pub mod hook;
+pub mod launch;
+pub mod report;
+pub mod shutdown;
+pub mod metrics;
+pub mod inject;
+pub mod tenant;
+pub mod feature;
+pub mod maintenance;
+pub mod canonical;
+pub mod blocking;
 
 mod error;
 mod router;
@@ -113,18 +132,21 @@ mod rocket;
 mod codegen;
 mod catcher;
 mod ext;
+mod state;
 
 #[doc(inline)] pub use response::Response;
-#[doc(inline)] pub use handler::{Handler, ErrorHandler};
+#[doc(inline)] pub use handler::{Handler, ErrorHandler, Guard};
 #[doc(inline)] pub use logger::LoggingLevel;
 #[doc(hidden)] pub use codegen::{StaticRouteInfo, StaticCatchInfo};
+#[doc(hidden)] pub use state::StateType;
 #[doc(inline)] pub use outcome::Outcome;
 #[doc(inline)] pub use data::Data;
-pub use router::Route;
+pub use router::{Route, Routes, Resource, Explanation, Candidate, Verdict};
 pub use request::Request;
 pub use error::Error;
 pub use catcher::Catcher;
 pub use rocket::Rocket;
+pub use state::State;
 
 /// Alias to [Rocket::ignite()](/rocket/struct.Rocket.html#method.ignite).
 /// Creates a new instance of `Rocket`.
@@ -1,12 +1,22 @@
 //! Rocket's logging infrastructure.
 
 use std::str::FromStr;
+use std::sync::{Once, ONCE_INIT};
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
 
-use log::{self, Log, LogLevel, LogRecord, LogMetadata};
+use log::{self, Log, LogLevel, LogRecord, LogMetadata, MaxLogLevelFilter};
 use term_painter::Color::*;
 use term_painter::ToStyle;
 
-struct RocketLogger(LoggingLevel);
+struct RocketLogger;
+
+/// The current logging level, as set by `init` or `set_level`.
+static LEVEL: AtomicUsize = ATOMIC_USIZE_INIT;
+
+/// The address of the `log` crate's max level handle, set once, when the
+/// logger is initialized, and `0` until then.
+static MAX_LEVEL_FILTER: AtomicUsize = ATOMIC_USIZE_INIT;
+static MAX_LEVEL_INIT: Once = ONCE_INIT;
 
 /// Defines the different levels for log messages.
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
@@ -20,6 +30,24 @@ pub enum LoggingLevel {
 }
 
 impl LoggingLevel {
+    #[inline(always)]
+    fn from_usize(value: usize) -> LoggingLevel {
+        match value {
+            0 => LoggingLevel::Critical,
+            1 => LoggingLevel::Normal,
+            _ => LoggingLevel::Debug,
+        }
+    }
+
+    #[inline(always)]
+    fn as_usize(&self) -> usize {
+        match *self {
+            LoggingLevel::Critical => 0,
+            LoggingLevel::Normal => 1,
+            LoggingLevel::Debug => 2,
+        }
+    }
+
     #[inline(always)]
     fn max_log_level(&self) -> LogLevel {
         match *self {
@@ -63,9 +91,16 @@ macro_rules! debug_ { ($($args:expr),+) => { log_!(debug: $($args),+); }; }
 #[doc(hidden)] #[macro_export]
 macro_rules! warn_ { ($($args:expr),+) => { log_!(warn: $($args),+); }; }
 
+impl RocketLogger {
+    #[inline(always)]
+    fn level(&self) -> LoggingLevel {
+        LoggingLevel::from_usize(LEVEL.load(Ordering::Relaxed))
+    }
+}
+
 impl Log for RocketLogger {
     fn enabled(&self, md: &LogMetadata) -> bool {
-        md.level() <= self.0.max_log_level()
+        md.level() <= self.level().max_log_level()
     }
 
     fn log(&self, record: &LogRecord) {
@@ -74,14 +109,16 @@ impl Log for RocketLogger {
             return;
         }
 
+        let level = self.level();
+
         // Don't print Hyper's messages unless Debug is enabled.
         let from_hyper = record.location().module_path().starts_with("hyper::");
-        if from_hyper && self.0 != LoggingLevel::Debug {
+        if from_hyper && level != LoggingLevel::Debug {
             return;
         }
 
         // In Rocket, we abuse target with value "_" to indicate indentation.
-        if record.target() == "_" && self.0 != LoggingLevel::Critical {
+        if record.target() == "_" && level != LoggingLevel::Critical {
             print!("    {} ", White.paint("=>"));
         }
 
@@ -112,11 +149,30 @@ impl Log for RocketLogger {
 #[doc(hidden)]
 pub fn init(level: LoggingLevel) {
     let result = log::set_logger(|max_log_level| {
+        LEVEL.store(level.as_usize(), Ordering::Relaxed);
         max_log_level.set(level.max_log_level().to_log_level_filter());
-        Box::new(RocketLogger(level))
+        MAX_LEVEL_INIT.call_once(|| {
+            let handle = Box::into_raw(Box::new(max_log_level));
+            MAX_LEVEL_FILTER.store(handle as usize, Ordering::SeqCst);
+        });
+
+        Box::new(RocketLogger)
     });
 
     if let Err(err) = result {
         println!("Logger failed to initialize: {}", err);
     }
 }
+
+/// Changes the level of the logger initialized by `init`. Does nothing if the
+/// logger hasn't been initialized.
+#[doc(hidden)]
+pub fn set_level(level: LoggingLevel) {
+    // The handle is never freed once set, so it's safe to use thereafter.
+    let address = MAX_LEVEL_FILTER.load(Ordering::SeqCst);
+    if address != 0 {
+        let max_log_level = unsafe { &*(address as *const MaxLogLevelFilter) };
+        LEVEL.store(level.as_usize(), Ordering::Relaxed);
+        max_log_level.set(level.max_log_level().to_log_level_filter());
+    }
+}
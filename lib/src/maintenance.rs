@@ -0,0 +1,267 @@
+//! A switch that takes an application down for maintenance while it runs.
+//!
+//! A [Maintenance](struct.Maintenance.html) switch is installed via
+//! [Rocket::maintenance](/rocket/struct.Rocket.html#method.maintenance). While
+//! the switch is on, every request is answered with `503 Service Unavailable`
+//! without being routed, except for requests to the allow-listed paths, such
+//! as a health check or an admin interface. The response comes from the
+//! application's `503` catcher, so the page shown during maintenance is
+//! customized, or rendered from a template, by registering an `#[error(503)]`
+//! catcher. When the switch has a [retry_after](struct.Maintenance.html#method.retry_after)
+//! hint, the response carries a `Retry-After` header as well.
+//!
+//! The switch can be flipped in two ways:
+//!
+//!   * **From code:** the switch is managed state, so a route can retrieve it
+//!     as `State<Maintenance>` and call [enable](struct.Maintenance.html#method.enable)
+//!     or [disable](struct.Maintenance.html#method.disable). Clones of a switch
+//!     share its state, so a clone kept elsewhere works as well.
+//!   * **From configuration:** the switch follows the `maintenance` boolean
+//!     extra. It's read at launch and, when `maintenance` is hot-reloaded via
+//!     [Rocket::hot_reload](/rocket/struct.Rocket.html#method.hot_reload),
+//!     whenever the configuration file changes.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #![feature(plugin)]
+//! # #![plugin(rocket_codegen)]
+//! # extern crate rocket;
+//! use std::time::Duration;
+//!
+//! use rocket::State;
+//! use rocket::maintenance::Maintenance;
+//!
+//! #[post("/admin/maintenance/<on>")]
+//! fn toggle(on: bool, maintenance: State<Maintenance>) -> &'static str {
+//!     maintenance.set(on);
+//!     if on { "Down for maintenance." } else { "Back up." }
+//! }
+//!
+//! #[error(503)]
+//! fn down() -> &'static str {
+//!     "We'll be right back!"
+//! }
+//!
+//! fn main() {
+//!     let maintenance = Maintenance::new()
+//!         .allow("/admin")
+//!         .retry_after(Duration::from_secs(600));
+//!
+//! # if false {
+//!     rocket::ignite()
+//!         .mount("/", routes![toggle])
+//!         .catch(errors![down])
+//!         .hot_reload(&["maintenance"])
+//!         .maintenance(maintenance)
+//!         .launch()
+//! # }
+//! }
+//! ```
+
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use request::Request;
+use http::RetryAfter;
+
+struct Inner {
+    enabled: AtomicBool,
+    allowed: Vec<String>,
+    retry_after: RwLock<Option<RetryAfter>>,
+}
+
+/// A switch that, when on, answers requests to all but the allow-listed
+/// paths with `503 Service Unavailable`. See the
+/// [maintenance](/rocket/maintenance/index.html) module for more.
+///
+/// Switches are cheap to clone, and all clones share the same state.
+#[derive(Clone)]
+pub struct Maintenance(Arc<Inner>);
+
+impl Maintenance {
+    /// Returns a new switch that's off and allows no paths.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::maintenance::Maintenance;
+    ///
+    /// let maintenance = Maintenance::new();
+    /// assert!(!maintenance.is_enabled());
+    /// ```
+    pub fn new() -> Maintenance {
+        Maintenance(Arc::new(Inner {
+            enabled: AtomicBool::new(false),
+            allowed: vec![],
+            retry_after: RwLock::new(None),
+        }))
+    }
+
+    /// Allows requests to `path`, and to the paths below it, while the switch
+    /// is on. Paths are matched by whole segments: allowing `/admin` allows
+    /// `/admin` and `/admin/users` but not `/administrator`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` doesn't begin with `/`, or if the switch has been
+    /// cloned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::maintenance::Maintenance;
+    ///
+    /// let maintenance = Maintenance::new().allow("/health").allow("/admin");
+    /// ```
+    pub fn allow<S: Into<String>>(mut self, path: S) -> Self {
+        let mut path = path.into();
+        if !path.starts_with('/') {
+            error!("Maintenance allow-list path '{}' must begin with '/'.", path);
+            panic!("Bad maintenance allow-list path.");
+        }
+
+        while path.len() > 1 && path.ends_with('/') {
+            path.pop();
+        }
+
+        Arc::get_mut(&mut self.0).expect("maintenance switch not yet shared").allowed.push(path);
+        self
+    }
+
+    /// Sets the `Retry-After` hint sent with responses while the switch is on,
+    /// or removes it if `retry_after` is `None`. The hint can be changed while
+    /// the application runs, as when the end of maintenance is postponed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rocket::maintenance::Maintenance;
+    ///
+    /// let maintenance = Maintenance::new().retry_after(Duration::from_secs(300));
+    /// maintenance.set_retry_after(None);
+    /// ```
+    pub fn retry_after<R: Into<RetryAfter>>(self, retry_after: R) -> Self {
+        self.set_retry_after(Some(retry_after.into()));
+        self
+    }
+
+    /// Sets or removes the `Retry-After` hint. See
+    /// [retry_after](#method.retry_after).
+    pub fn set_retry_after(&self, retry_after: Option<RetryAfter>) {
+        *self.0.retry_after.write().expect("maintenance lock") = retry_after;
+    }
+
+    /// Returns the `Retry-After` hint, if there is one.
+    pub fn retry_after_hint(&self) -> Option<RetryAfter> {
+        *self.0.retry_after.read().expect("maintenance lock")
+    }
+
+    /// Turns the switch on.
+    #[inline]
+    pub fn enable(&self) {
+        self.set(true);
+    }
+
+    /// Turns the switch off.
+    #[inline]
+    pub fn disable(&self) {
+        self.set(false);
+    }
+
+    /// Turns the switch on if `enabled` is `true` and off otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::maintenance::Maintenance;
+    ///
+    /// let maintenance = Maintenance::new();
+    /// let shared = maintenance.clone();
+    /// shared.set(true);
+    /// assert!(maintenance.is_enabled());
+    /// ```
+    pub fn set(&self, enabled: bool) {
+        let was = self.0.enabled.swap(enabled, Ordering::SeqCst);
+        if was != enabled {
+            match enabled {
+                true => warn!("Maintenance mode enabled."),
+                false => info!("Maintenance mode disabled."),
+            }
+        }
+    }
+
+    /// Returns `true` if the switch is on.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.0.enabled.load(Ordering::SeqCst)
+    }
+
+    /// Returns `true` if requests to `path` are allowed while the switch is
+    /// on.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::maintenance::Maintenance;
+    ///
+    /// let maintenance = Maintenance::new().allow("/admin");
+    /// assert!(maintenance.allows("/admin/users"));
+    /// assert!(!maintenance.allows("/administrator"));
+    /// ```
+    pub fn allows(&self, path: &str) -> bool {
+        self.0.allowed.iter().any(|prefix| {
+            prefix == "/" || path == prefix
+                || (path.starts_with(prefix.as_str())
+                    && path.as_bytes()[prefix.len()] == b'/')
+        })
+    }
+
+    /// Returns `true` if `request` should be answered with `503 Service
+    /// Unavailable`: the switch is on and the request's path isn't allowed.
+    #[doc(hidden)]
+    pub fn rejects(&self, request: &Request) -> bool {
+        self.is_enabled() && !self.allows(request.uri().path())
+    }
+}
+
+impl fmt::Debug for Maintenance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Maintenance")
+            .field("enabled", &self.is_enabled())
+            .field("allowed", &self.0.allowed)
+            .field("retry_after", &self.retry_after_hint())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Maintenance;
+
+    #[test]
+    fn test_allows_whole_segments() {
+        let maintenance = Maintenance::new().allow("/admin/").allow("/health");
+        assert!(maintenance.allows("/admin"));
+        assert!(maintenance.allows("/admin/users"));
+        assert!(maintenance.allows("/health"));
+        assert!(!maintenance.allows("/administrator"));
+        assert!(!maintenance.allows("/healthz"));
+        assert!(!maintenance.allows("/"));
+
+        assert!(Maintenance::new().allow("/").allows("/anything"));
+        assert!(!Maintenance::new().allows("/"));
+    }
+
+    #[test]
+    fn test_clones_share_state() {
+        let maintenance = Maintenance::new();
+        let other = maintenance.clone();
+        other.enable();
+        assert!(maintenance.is_enabled());
+        maintenance.disable();
+        assert!(!other.is_enabled());
+    }
+}
@@ -0,0 +1,380 @@
+//! Per-route request and response size metrics.
+//!
+//! Rocket records the size of the body of every request and response in a
+//! [Histogram](struct.Histogram.html) for the route that handled the request.
+//! Requests that aren't handled by any route aren't recorded. The recorded
+//! histograms are available via [Rocket::metrics](/rocket/struct.Rocket.html#method.metrics)
+//! and, inside of a handler, via the `&Metrics` request guard.
+//!
+//! Request body sizes are taken from the `Content-Length` header; requests
+//! without one aren't recorded. Response body sizes are recorded once the
+//! body has been written out, so the sizes of streamed bodies are known, too.
+//!
+//! # Metrics Endpoint
+//!
+//! [Metrics::render](struct.Metrics.html#method.render) formats the histograms
+//! in the Prometheus text exposition format, so exposing them to a metrics
+//! collector is a matter of adding a route:
+//!
+//! ```rust
+//! # #![feature(plugin)]
+//! # #![plugin(rocket_codegen)]
+//! # extern crate rocket;
+//! #
+//! use rocket::metrics::Metrics;
+//!
+//! #[get("/metrics")]
+//! fn metrics(metrics: &Metrics) -> String {
+//!     metrics.render()
+//! }
+//! #
+//! # fn main() { }
+//! ```
+
+use std::cmp::{min, max};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::io::{self, Read};
+use std::sync::Mutex;
+
+use outcome::Outcome::*;
+use request::{self, Request, FromRequest};
+use router::Route;
+
+/// The number of buckets in a `Histogram`: one for empty bodies and one for
+/// each power of two.
+const BUCKETS: usize = 65;
+
+/// A histogram of body sizes, in bytes.
+///
+/// Sizes are counted in buckets whose bounds are powers of two: the first
+/// bucket counts empty bodies, and the `i`th bucket counts sizes in `[2^(i -
+/// 1), 2^i)`. The histogram is thus accurate to within a factor of two, which
+/// is plenty for capacity planning, and uses a fixed amount of memory.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::metrics::Histogram;
+///
+/// let mut histogram = Histogram::new();
+/// histogram.record(0);
+/// histogram.record(700);
+/// histogram.record(1000);
+///
+/// assert_eq!(histogram.count(), 3);
+/// assert_eq!(histogram.sum(), 1700);
+/// assert_eq!(histogram.buckets(), vec![(0, 1), (1023, 2)]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    counts: Vec<u64>,
+    sum: u64,
+    max: u64,
+}
+
+/// Returns the index of the bucket that counts `size`.
+#[inline(always)]
+fn bucket_of(size: u64) -> usize {
+    64 - size.leading_zeros() as usize
+}
+
+/// Returns the largest size counted by the bucket at `index`.
+#[inline(always)]
+fn upper_bound(index: usize) -> u64 {
+    match index {
+        0 => 0,
+        64 => u64::max_value(),
+        i => (1 << i) - 1
+    }
+}
+
+impl Histogram {
+    /// Returns an empty histogram.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::metrics::Histogram;
+    ///
+    /// let histogram = Histogram::new();
+    /// assert_eq!(histogram.count(), 0);
+    /// ```
+    #[inline(always)]
+    pub fn new() -> Histogram {
+        Histogram { counts: vec![0; BUCKETS], sum: 0, max: 0 }
+    }
+
+    /// Records a body of `size` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::metrics::Histogram;
+    ///
+    /// let mut histogram = Histogram::new();
+    /// histogram.record(42);
+    /// assert_eq!(histogram.max(), 42);
+    /// ```
+    #[inline]
+    pub fn record(&mut self, size: u64) {
+        self.counts[bucket_of(size)] += 1;
+        self.sum = self.sum.saturating_add(size);
+        if size > self.max {
+            self.max = size;
+        }
+    }
+
+    /// Returns the number of bodies recorded.
+    #[inline]
+    pub fn count(&self) -> u64 {
+        self.counts.iter().fold(0, |sum, &count| sum + count)
+    }
+
+    /// Returns the total size, in bytes, of the bodies recorded, saturating at
+    /// `u64::max_value()`.
+    #[inline(always)]
+    pub fn sum(&self) -> u64 {
+        self.sum
+    }
+
+    /// Returns the size, in bytes, of the largest body recorded, or `0` if no
+    /// bodies have been recorded.
+    #[inline(always)]
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Returns the non-empty buckets of `self` in increasing order as pairs of
+    /// the largest size counted by the bucket and the number of bodies
+    /// counted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::metrics::Histogram;
+    ///
+    /// let mut histogram = Histogram::new();
+    /// histogram.record(3);
+    /// histogram.record(2);
+    /// histogram.record(4096);
+    ///
+    /// assert_eq!(histogram.buckets(), vec![(3, 2), (8191, 1)]);
+    /// ```
+    pub fn buckets(&self) -> Vec<(u64, u64)> {
+        self.counts.iter().enumerate()
+            .filter(|&(_, &count)| count > 0)
+            .map(|(i, &count)| (upper_bound(i), count))
+            .collect()
+    }
+
+    /// Returns an upper bound on the size below which a fraction `q` of the
+    /// recorded bodies fall, or `None` if no bodies have been recorded. `q` is
+    /// clamped to `[0, 1]`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::metrics::Histogram;
+    ///
+    /// let mut histogram = Histogram::new();
+    /// for size in 1..101 {
+    ///     histogram.record(size);
+    /// }
+    ///
+    /// assert_eq!(histogram.quantile(0.5), Some(63));
+    /// assert_eq!(histogram.quantile(1.0), Some(100));
+    /// ```
+    pub fn quantile(&self, q: f64) -> Option<u64> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+
+        let q = if q < 0.0 { 0.0 } else if q > 1.0 { 1.0 } else { q };
+        let rank = max((q * count as f64).ceil() as u64, 1);
+        let mut seen = 0;
+        for (i, &bucket_count) in self.counts.iter().enumerate() {
+            seen += bucket_count;
+            if seen >= rank {
+                return Some(min(upper_bound(i), self.max));
+            }
+        }
+
+        Some(self.max)
+    }
+}
+
+/// The request and response body size histograms for a single route.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteSizes {
+    /// The sizes of request bodies with a `Content-Length` header.
+    pub request: Histogram,
+    /// The sizes of response bodies.
+    pub response: Histogram,
+}
+
+/// The body size histograms of all of the routes in an application.
+///
+/// Routes are identified by their method and path, as in `GET /users/<id>`.
+/// See the [module level documentation](index.html) for more.
+pub struct Metrics {
+    routes: Mutex<HashMap<String, RouteSizes>>,
+}
+
+/// Returns the name `route` is identified by in `Metrics`.
+fn route_name(route: &Route) -> String {
+    format!("{} {}", route.method, route.path)
+}
+
+impl Metrics {
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn new() -> Metrics {
+        Metrics { routes: Mutex::new(HashMap::new()) }
+    }
+
+    fn with_route<F: FnOnce(&mut RouteSizes)>(&self, route: &Route, f: F) {
+        let mut routes = self.routes.lock().expect("metrics lock");
+        let sizes = routes.entry(route_name(route)).or_insert_with(|| RouteSizes {
+            request: Histogram::new(),
+            response: Histogram::new(),
+        });
+
+        f(sizes)
+    }
+
+    #[doc(hidden)]
+    pub fn record_request(&self, route: &Route, size: u64) {
+        self.with_route(route, |sizes| sizes.request.record(size));
+    }
+
+    #[doc(hidden)]
+    pub fn record_response(&self, route: &Route, size: u64) {
+        self.with_route(route, |sizes| sizes.response.record(size));
+    }
+
+    /// Returns the size histograms recorded for the route identified by
+    /// `name`, such as `GET /users/<id>`, if any requests to it have been
+    /// recorded.
+    pub fn get(&self, name: &str) -> Option<RouteSizes> {
+        self.routes.lock().expect("metrics lock").get(name).cloned()
+    }
+
+    /// Returns the size histograms of every route for which requests have been
+    /// recorded, ordered by route name.
+    pub fn snapshot(&self) -> Vec<(String, RouteSizes)> {
+        let routes = self.routes.lock().expect("metrics lock");
+        let mut snapshot: Vec<_> = routes.iter()
+            .map(|(name, sizes)| (name.clone(), sizes.clone()))
+            .collect();
+
+        snapshot.sort_by(|a, b| a.0.cmp(&b.0));
+        snapshot
+    }
+
+    /// Formats the histograms of every route in the Prometheus text exposition
+    /// format as the `rocket_request_body_bytes` and
+    /// `rocket_response_body_bytes` histogram metrics, labeled by route.
+    pub fn render(&self) -> String {
+        let snapshot = self.snapshot();
+        let mut out = String::new();
+        for &(metric, is_request) in &[("rocket_request_body_bytes", true),
+                                       ("rocket_response_body_bytes", false)] {
+            let _ = writeln!(out, "# TYPE {} histogram", metric);
+            for &(ref name, ref sizes) in &snapshot {
+                let histogram = if is_request { &sizes.request } else { &sizes.response };
+                let label = name.replace('\\', "\\\\").replace('"', "\\\"");
+
+                let mut cumulative = 0;
+                for (bound, count) in histogram.buckets() {
+                    cumulative += count;
+                    let _ = writeln!(out, "{}_bucket{{route=\"{}\",le=\"{}\"}} {}",
+                                     metric, label, bound, cumulative);
+                }
+
+                let _ = writeln!(out, "{}_bucket{{route=\"{}\",le=\"+Inf\"}} {}",
+                                 metric, label, cumulative);
+                let _ = writeln!(out, "{}_sum{{route=\"{}\"}} {}", metric, label, histogram.sum());
+                let _ = writeln!(out, "{}_count{{route=\"{}\"}} {}", metric, label, cumulative);
+            }
+        }
+
+        out
+    }
+}
+
+/// Wraps a response body, recording the number of bytes read from it once it
+/// is dropped.
+#[doc(hidden)]
+pub struct CountingBody<'r> {
+    inner: Box<Read + 'r>,
+    read: u64,
+    route: &'r Route,
+    metrics: &'r Metrics,
+}
+
+impl<'r> CountingBody<'r> {
+    #[doc(hidden)]
+    pub fn new(inner: Box<Read + 'r>, route: &'r Route, metrics: &'r Metrics) -> Self {
+        CountingBody { inner: inner, read: 0, route: route, metrics: metrics }
+    }
+}
+
+impl<'r> Read for CountingBody<'r> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+        Ok(n)
+    }
+}
+
+impl<'r> Drop for CountingBody<'r> {
+    fn drop(&mut self) {
+        self.metrics.record_response(self.route, self.read);
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for &'r Metrics {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        match request.metrics() {
+            Some(metrics) => Success(metrics),
+            None => Forward(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Histogram, bucket_of, upper_bound};
+
+    #[test]
+    fn test_buckets() {
+        assert_eq!(bucket_of(0), 0);
+        assert_eq!(bucket_of(1), 1);
+        assert_eq!(bucket_of(2), 2);
+        assert_eq!(bucket_of(3), 2);
+        assert_eq!(bucket_of(1024), 11);
+        assert_eq!(bucket_of(u64::max_value()), 64);
+
+        for size in &[0, 1, 2, 3, 1023, 1024, 1 << 40, u64::max_value()] {
+            assert!(*size <= upper_bound(bucket_of(*size)));
+        }
+    }
+
+    #[test]
+    fn test_histogram() {
+        let mut histogram = Histogram::new();
+        assert_eq!(histogram.quantile(0.5), None);
+
+        histogram.record(10);
+        histogram.record(u64::max_value());
+        assert_eq!(histogram.count(), 2);
+        assert_eq!(histogram.sum(), u64::max_value());
+        assert_eq!(histogram.quantile(0.0), Some(15));
+        assert_eq!(histogram.quantile(0.5), Some(15));
+        assert_eq!(histogram.quantile(2.0), Some(u64::max_value()));
+    }
+}
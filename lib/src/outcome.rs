@@ -351,6 +351,137 @@ impl<S, E, F> Outcome<S, E, F> {
         }
     }
 
+    /// Maps an `Outcome<S, E, F>` to an `Outcome<T, E, F>` by applying the
+    /// function `f` to the value of type `S` in `self` if `self` is an
+    /// `Outcome::Success`.
+    ///
+    /// ```rust
+    /// # use rocket::outcome::Outcome;
+    /// # use rocket::outcome::Outcome::*;
+    /// #
+    /// let x: Outcome<i32, &str, usize> = Success(10);
+    /// assert_eq!(x.map(|v| v * 2), Success(20));
+    ///
+    /// let x: Outcome<i32, &str, usize> = Forward(25);
+    /// assert_eq!(x.map(|v| v * 2), Forward(25));
+    /// ```
+    #[inline]
+    pub fn map<T, M: FnOnce(S) -> T>(self, f: M) -> Outcome<T, E, F> {
+        match self {
+            Success(val) => Success(f(val)),
+            Failure(val) => Failure(val),
+            Forward(val) => Forward(val),
+        }
+    }
+
+    /// Maps an `Outcome<S, E, F>` to an `Outcome<S, T, F>` by applying the
+    /// function `f` to the value of type `E` in `self` if `self` is an
+    /// `Outcome::Failure`.
+    ///
+    /// ```rust
+    /// # use rocket::outcome::Outcome;
+    /// # use rocket::outcome::Outcome::*;
+    /// #
+    /// let x: Outcome<i32, &str, usize> = Failure("Hi! I'm an error.");
+    /// assert_eq!(x.map_failure(|e| e.len()), Failure(17));
+    ///
+    /// let x: Outcome<i32, &str, usize> = Success(10);
+    /// assert_eq!(x.map_failure(|e| e.len()), Success(10));
+    /// ```
+    #[inline]
+    pub fn map_failure<T, M: FnOnce(E) -> T>(self, f: M) -> Outcome<S, T, F> {
+        match self {
+            Success(val) => Success(val),
+            Failure(val) => Failure(f(val)),
+            Forward(val) => Forward(val),
+        }
+    }
+
+    /// Calls `f` with the value of type `S` in `self` if `self` is an
+    /// `Outcome::Success`, returning its outcome. Otherwise, returns the
+    /// `Failure` or `Forward` in `self`.
+    ///
+    /// This chains steps that may each fail or forward without nesting
+    /// `match`es.
+    ///
+    /// ```rust
+    /// # use rocket::outcome::Outcome;
+    /// # use rocket::outcome::Outcome::*;
+    /// #
+    /// fn parse(string: &str) -> Outcome<i32, &'static str, ()> {
+    ///     string.parse().map(Success).unwrap_or(Failure("not a number"))
+    /// }
+    ///
+    /// let x: Outcome<&str, &str, ()> = Success("10");
+    /// assert_eq!(x.and_then(parse), Success(10));
+    ///
+    /// let x: Outcome<&str, &str, ()> = Success("ten");
+    /// assert_eq!(x.and_then(parse), Failure("not a number"));
+    ///
+    /// let x: Outcome<&str, &str, ()> = Forward(());
+    /// assert_eq!(x.and_then(parse), Forward(()));
+    /// ```
+    #[inline]
+    pub fn and_then<T, M>(self, f: M) -> Outcome<T, E, F>
+        where M: FnOnce(S) -> Outcome<T, E, F>
+    {
+        match self {
+            Success(val) => f(val),
+            Failure(val) => Failure(val),
+            Forward(val) => Forward(val),
+        }
+    }
+
+    /// Calls `f` with the value of type `F` in `self` if `self` is an
+    /// `Outcome::Forward`, returning its outcome. Otherwise, returns the
+    /// `Success` or `Failure` in `self`.
+    ///
+    /// This tries an alternative when a step forwards, such as looking for a
+    /// credential elsewhere when it's missing from where it's first looked for.
+    ///
+    /// ```rust
+    /// # use rocket::outcome::Outcome;
+    /// # use rocket::outcome::Outcome::*;
+    /// #
+    /// let x: Outcome<i32, &str, ()> = Forward(());
+    /// assert_eq!(x.or_forward(|_| Success(5)), Success::<i32, &str, ()>(5));
+    ///
+    /// let x: Outcome<i32, &str, ()> = Success(10);
+    /// assert_eq!(x.or_forward(|_| Success(5)), Success::<i32, &str, ()>(10));
+    /// ```
+    #[inline]
+    pub fn or_forward<T, M>(self, f: M) -> Outcome<S, E, T>
+        where M: FnOnce(F) -> Outcome<S, E, T>
+    {
+        match self {
+            Success(val) => Success(val),
+            Failure(val) => Failure(val),
+            Forward(val) => f(val),
+        }
+    }
+
+    /// Converts from `Outcome<S, E, F>` to `Result<S, T>`, mapping a
+    /// `Success(s)` to `Ok(s)` and both a `Failure` and a `Forward` to
+    /// `Err(value)`. `self` is consumed, and all other values are discarded.
+    ///
+    /// ```rust
+    /// # use rocket::outcome::Outcome;
+    /// # use rocket::outcome::Outcome::*;
+    /// #
+    /// let x: Outcome<i32, &str, usize> = Success(10);
+    /// assert_eq!(x.success_or("missing"), Ok(10));
+    ///
+    /// let x: Outcome<i32, &str, usize> = Forward(25);
+    /// assert_eq!(x.success_or("missing"), Err("missing"));
+    /// ```
+    #[inline]
+    pub fn success_or<T>(self, value: T) -> Result<S, T> {
+        match self {
+            Success(val) => Ok(val),
+            _ => Err(value)
+        }
+    }
+
     #[doc(hidden)]
     #[inline(always)]
     fn formatting(&self) -> (Color, &'static str) {
@@ -0,0 +1,164 @@
+//! Reporting server errors to an error tracker.
+//!
+//! Rocket hands each server error it encounters while handling a request to
+//! the application's [ErrorReporter](trait.ErrorReporter.html), along with the
+//! request, so that errors can be sent to an error tracking service without
+//! wrapping every handler. A [ServerError](enum.ServerError.html) is reported
+//! when:
+//!
+//!   * a handler panics: the request is answered with a
+//!     `500 Internal Server Error` instead of the connection being dropped;
+//!   * a request or data guard fails with a server error status, such as when
+//!     managed state is missing;
+//!   * a `500 Internal Server Error` response is produced for any other
+//!     reason, such as a responder failing.
+//!
+//! At most one error is reported per request: the first one. The default
+//! reporter, [ignore](fn.ignore.html), does nothing, since errors are already
+//! logged. A different reporter is installed via
+//! [Rocket::error_reporter](/rocket/struct.Rocket.html#method.error_reporter).
+//!
+//! # Example
+//!
+//! Print each error as a single line of JSON:
+//!
+//! ```rust
+//! # if false {
+//! rocket::ignite()
+//!     .error_reporter(|request: &rocket::Request, error: &rocket::report::ServerError| {
+//!         println!("{{\"event\":\"error\",\"uri\":\"{}\",\"status\":{}}}",
+//!                  request.uri(), error.status().code);
+//!     })
+//!     .launch()
+//! # }
+//! ```
+
+use std::any::Any;
+use std::fmt;
+
+use request::{Request, GuardFailure};
+use http::Status;
+
+/// A reporter of server errors, such as a client of an error tracking service.
+///
+/// `ErrorReporter` is implemented for all `Fn(&Request, &ServerError)`
+/// closures that are `Send + Sync`. Reporters are called on the thread
+/// handling the request, before the response is sent, so a reporter that does
+/// I/O should hand the error off to another thread.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Mutex;
+/// use std::sync::mpsc::Sender;
+/// use rocket::Request;
+/// use rocket::report::{ErrorReporter, ServerError};
+///
+/// struct Tracker(Mutex<Sender<String>>);
+///
+/// impl ErrorReporter for Tracker {
+///     fn report(&self, request: &Request, error: &ServerError) {
+///         let event = format!("{} {}: {}", request.method(), request.uri(), error);
+///         let _ = self.0.lock().unwrap().send(event);
+///     }
+/// }
+/// ```
+pub trait ErrorReporter: Send + Sync {
+    /// Reports `error`, which occurred while handling `request`.
+    fn report(&self, request: &Request, error: &ServerError);
+}
+
+impl<F> ErrorReporter for F where F: Fn(&Request, &ServerError) + Send + Sync {
+    fn report(&self, request: &Request, error: &ServerError) {
+        self(request, error)
+    }
+}
+
+/// The default error reporter: does nothing.
+pub fn ignore(_: &Request, _: &ServerError) {  }
+
+/// A server error that occurred while handling a request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerError {
+    /// The handler panicked. The field is the panic's message, if it had one.
+    Panic(Option<String>),
+    /// A request or data guard failed with a server error status.
+    GuardFailure(GuardFailure),
+    /// A `500 Internal Server Error` response was produced for another
+    /// reason, such as a responder or a catcher failing.
+    Response(Status),
+}
+
+impl ServerError {
+    /// Returns the status of the response to the request that failed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::report::ServerError;
+    /// use rocket::http::Status;
+    ///
+    /// let error = ServerError::Panic(Some("oh no".into()));
+    /// assert_eq!(error.status(), Status::InternalServerError);
+    /// ```
+    pub fn status(&self) -> Status {
+        match *self {
+            ServerError::Panic(_) => Status::InternalServerError,
+            ServerError::GuardFailure(ref failure) => failure.status(),
+            ServerError::Response(status) => status,
+        }
+    }
+}
+
+impl fmt::Display for ServerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ServerError::Panic(Some(ref message)) => write!(f, "handler panicked: {}", message),
+            ServerError::Panic(None) => write!(f, "handler panicked"),
+            ServerError::GuardFailure(ref failure) => write!(f, "guard failed: {}", failure),
+            ServerError::Response(status) => write!(f, "responded with {}", status),
+        }
+    }
+}
+
+/// Returns the message of a panic with the payload `payload`, if it has one.
+#[doc(hidden)]
+pub fn panic_message(payload: &Any) -> Option<String> {
+    match payload.downcast_ref::<&'static str>() {
+        Some(message) => Some(message.to_string()),
+        None => payload.downcast_ref::<String>().cloned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::panic;
+
+    use super::{ServerError, panic_message};
+    use request::GuardFailure;
+    use http::Status;
+
+    #[test]
+    fn test_panic_message() {
+        let payload = panic::catch_unwind(|| panic!("static")).unwrap_err();
+        assert_eq!(panic_message(&*payload), Some("static".to_string()));
+
+        let payload = panic::catch_unwind(|| panic!("formatted {}", 1)).unwrap_err();
+        assert_eq!(panic_message(&*payload), Some("formatted 1".to_string()));
+
+        let payload = panic::catch_unwind(|| panic!(7)).unwrap_err();
+        assert_eq!(panic_message(&*payload), None);
+    }
+
+    #[test]
+    fn test_status_and_display() {
+        let failure = GuardFailure::new(Status::ServiceUnavailable, "no database");
+        let error = ServerError::GuardFailure(failure);
+        assert_eq!(error.status(), Status::ServiceUnavailable);
+        assert!(error.to_string().starts_with("guard failed: "));
+
+        assert_eq!(ServerError::Panic(None).to_string(), "handler panicked");
+        assert_eq!(ServerError::Response(Status::InternalServerError).status(),
+                   Status::InternalServerError);
+    }
+}
@@ -0,0 +1,105 @@
+use std::ascii::AsciiExt;
+use std::fmt;
+
+use outcome::Outcome::*;
+use request::{self, Request, FromRequest};
+use http::{Status, Authorization};
+
+/// The realm named in the challenge issued to requests without credentials.
+const REALM: &'static str = "Restricted";
+
+/// Request guard for `Basic` credentials, as defined in [RFC 7617]: the
+/// username and password in a request's `Authorization` header.
+///
+/// [RFC 7617]: https://tools.ietf.org/html/rfc7617
+///
+/// The guard succeeds if the `Authorization` header holds well-formed `Basic`
+/// credentials, leaving it to the handler to check them. If the header is
+/// missing or holds credentials of another scheme, the guard records a
+/// `Basic` challenge via
+/// [Request::challenge](/rocket/struct.Request.html#method.challenge) and
+/// forwards. If no other route accepts the request, Rocket responds with
+/// `401 Unauthorized` and a `WWW-Authenticate: Basic realm="Restricted",
+/// charset="UTF-8"` header, prompting browsers for credentials. If the header
+/// is malformed, the guard fails with a status of `400 Bad Request`.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// #
+/// use rocket::request::BasicAuth;
+/// use rocket::http::Status;
+///
+/// #[get("/admin")]
+/// fn admin(auth: BasicAuth) -> Result<String, Status> {
+///     if auth.username == "admin" && auth.password == "hunter2" {
+///         Ok(format!("Welcome, {}.", auth.username))
+///     } else {
+///         Err(Status::Forbidden)
+///     }
+/// }
+/// #
+/// # fn main() { }
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BasicAuth {
+    /// The username. Never contains a `:`.
+    pub username: String,
+    /// The password.
+    pub password: String,
+}
+
+impl BasicAuth {
+    /// Returns the challenge `BasicAuth` records for requests without `Basic`
+    /// credentials.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::BasicAuth;
+    ///
+    /// assert_eq!(BasicAuth::challenge(), "Basic realm=\"Restricted\", charset=\"UTF-8\"");
+    /// ```
+    #[inline]
+    pub fn challenge() -> String {
+        format!("Basic realm=\"{}\", charset=\"UTF-8\"", REALM)
+    }
+}
+
+/// Credentials are secrets, so the password is never printed.
+impl fmt::Debug for BasicAuth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BasicAuth {{ username: {:?}, password: \"..\" }}", self.username)
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for BasicAuth {
+    type Error = &'static str;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        let header = match request.headers().get_one("Authorization") {
+            Some(header) => header,
+            None => {
+                request.challenge(BasicAuth::challenge());
+                return Forward(());
+            }
+        };
+
+        let scheme = header.trim_left().split(' ').next().unwrap_or("");
+        if !scheme.eq_ignore_ascii_case("Basic") {
+            request.challenge(BasicAuth::challenge());
+            return Forward(());
+        }
+
+        match header.parse::<Authorization>() {
+            Ok(Authorization::Basic { username, password }) => {
+                Success(BasicAuth { username: username, password: password })
+            }
+            Ok(Authorization::Bearer(_)) => unreachable!("scheme is Basic"),
+            Err(e) => Failure((Status::BadRequest, e))
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use std::any::TypeId;
+use std::ascii::AsciiExt;
+use std::ops::Deref;
+use std::fmt;
+
+use outcome::Outcome::*;
+use request::{self, Request, FromRequest};
+use http::{Status, Authorization};
+use state::{State, StateType};
+
+/// The challenge issued to requests without a bearer token.
+const CHALLENGE: &'static str = "Bearer realm=\"Restricted\"";
+
+/// The challenge issued to requests whose bearer token was rejected.
+const INVALID_CHALLENGE: &'static str = "Bearer realm=\"Restricted\", error=\"invalid_token\"";
+
+/// Validates bearer tokens on behalf of the
+/// [BearerToken](struct.BearerToken.html) request guard.
+///
+/// A validator maps a token to the principal it identifies, such as a user
+/// looked up in a database or the claims of a verified JWT. Validators are
+/// managed state: a validator is installed via
+/// [Rocket::manage](/rocket/struct.Rocket.html#method.manage), and a
+/// `BearerToken<V>` guard validates tokens with the managed `V`. Launching
+/// fails if a route uses `BearerToken<V>` but no `V` is managed.
+///
+/// # Example
+///
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// use rocket::Request;
+/// use rocket::request::TokenValidator;
+///
+/// struct User(String);
+///
+/// struct ApiKeys(HashMap<String, String>);
+///
+/// impl TokenValidator for ApiKeys {
+///     type Principal = User;
+///     type Error = &'static str;
+///
+///     fn validate(&self, token: &str, _: &Request) -> Result<User, &'static str> {
+///         self.0.get(token).map(|name| User(name.clone())).ok_or("unknown key")
+///     }
+/// }
+/// ```
+pub trait TokenValidator: Send + Sync + 'static {
+    /// The principal a valid token identifies.
+    type Principal;
+
+    /// The reason a token is invalid.
+    type Error: fmt::Debug + 'static;
+
+    /// Returns the principal `token`, the bearer token of `request`,
+    /// identifies, or an `Err` if the token is invalid.
+    fn validate(&self, token: &str, request: &Request) -> Result<Self::Principal, Self::Error>;
+}
+
+/// The error of a [BearerToken](struct.BearerToken.html) guard that failed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BearerError<E> {
+    /// The `Authorization` header was malformed.
+    Malformed(&'static str),
+    /// The validator rejected the token.
+    Invalid(E),
+    /// The validator isn't managed.
+    Unmanaged,
+}
+
+/// Request guard for a bearer token, as defined in [RFC 6750], validated by
+/// the managed [TokenValidator](trait.TokenValidator.html) `V`.
+///
+/// [RFC 6750]: https://tools.ietf.org/html/rfc6750
+///
+/// The guard succeeds with the principal the token identifies, to which it
+/// dereferences. Its outcome is as follows:
+///
+///   * If the request has no `Authorization` header or one with another
+///     scheme, the guard records a `Bearer` challenge via
+///     [Request::challenge](/rocket/struct.Request.html#method.challenge) and
+///     forwards. If no other route accepts the request, Rocket responds with
+///     `401 Unauthorized`.
+///   * If the header is malformed, the guard fails with a status of `400 Bad
+///     Request`.
+///   * If the validator rejects the token, the guard fails with a status of
+///     `401 Unauthorized`, and the response carries a `WWW-Authenticate`
+///     header with an `invalid_token` error.
+///   * If no `V` is managed, the guard fails with a status of `500 Internal
+///     Server Error`.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// #
+/// use rocket::Request;
+/// use rocket::request::{BearerToken, TokenValidator};
+///
+/// struct User(String);
+///
+/// struct StaticToken;
+///
+/// impl TokenValidator for StaticToken {
+///     type Principal = User;
+///     type Error = ();
+///
+///     fn validate(&self, token: &str, _: &Request) -> Result<User, ()> {
+///         if token == "s3cr3t" { Ok(User("admin".into())) } else { Err(()) }
+///     }
+/// }
+///
+/// #[get("/me")]
+/// fn me(user: BearerToken<StaticToken>) -> String {
+///     format!("Hello, {}!", user.0)
+/// }
+///
+/// fn main() {
+/// # if false {
+///     rocket::ignite()
+///         .mount("/", routes![me])
+///         .manage(StaticToken)
+///         .launch()
+/// # }
+/// }
+/// ```
+pub struct BearerToken<V: TokenValidator> {
+    token: String,
+    principal: V::Principal,
+}
+
+impl<V: TokenValidator> BearerToken<V> {
+    /// Returns the token presented by the request.
+    #[inline(always)]
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Returns the principal the token identifies.
+    #[inline(always)]
+    pub fn principal(&self) -> &V::Principal {
+        &self.principal
+    }
+
+    /// Consumes `self` and returns the principal the token identifies.
+    #[inline(always)]
+    pub fn into_principal(self) -> V::Principal {
+        self.principal
+    }
+}
+
+impl<V: TokenValidator> Deref for BearerToken<V> {
+    type Target = V::Principal;
+
+    #[inline(always)]
+    fn deref(&self) -> &V::Principal {
+        &self.principal
+    }
+}
+
+/// Tokens are secrets, so they're never printed.
+impl<V: TokenValidator> fmt::Debug for BearerToken<V> where V::Principal: fmt::Debug {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BearerToken")
+            .field("token", &"..")
+            .field("principal", &self.principal)
+            .finish()
+    }
+}
+
+impl<V: TokenValidator> StateType for BearerToken<V> {
+    fn state_type() -> Option<TypeId> {
+        Some(TypeId::of::<V>())
+    }
+}
+
+impl<'a, 'r, V: TokenValidator> FromRequest<'a, 'r> for BearerToken<V> {
+    type Error = BearerError<V::Error>;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        let header = match request.headers().get_one("Authorization") {
+            Some(header) => header,
+            None => {
+                request.challenge(CHALLENGE);
+                return Forward(());
+            }
+        };
+
+        let scheme = header.trim_left().split(' ').next().unwrap_or("");
+        if !scheme.eq_ignore_ascii_case("Bearer") {
+            request.challenge(CHALLENGE);
+            return Forward(());
+        }
+
+        let token = match header.parse::<Authorization>() {
+            Ok(Authorization::Bearer(token)) => token,
+            Ok(Authorization::Basic { .. }) => unreachable!("scheme is Bearer"),
+            Err(e) => return Failure((Status::BadRequest, BearerError::Malformed(e)))
+        };
+
+        let validator = match request.guard::<State<V>>() {
+            Success(validator) => validator.inner(),
+            _ => return Failure((Status::InternalServerError, BearerError::Unmanaged))
+        };
+
+        match validator.validate(&token, request) {
+            Ok(principal) => Success(BearerToken { token: token, principal: principal }),
+            Err(e) => {
+                request.challenge(INVALID_CHALLENGE);
+                Failure((Status::Unauthorized, BearerError::Invalid(e)))
+            }
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use std::ascii::AsciiExt;
+use std::net::{IpAddr, Ipv6Addr};
+
+use outcome::Outcome::*;
+use request::{self, Request, FromRequest};
+use http::HeaderMap;
+
+/// Information about the client that originated a request, as reported by
+/// trusted proxies.
+///
+/// When an application is deployed behind a reverse proxy, the remote peer of
+/// each request is the proxy, not the client. Proxies report the client's
+/// address and the scheme the client used via the `Forwarded` header or the
+/// `X-Forwarded-For` and `X-Forwarded-Proto` headers. Because these headers
+/// can be set by anyone, they are only believed when the request arrives from
+/// a proxy listed in the `trusted_proxies` configuration parameter.
+///
+/// The client is determined by walking the list of forwarded addresses from
+/// the nearest hop outwards, skipping trusted proxies, and choosing the first
+/// address that isn't a trusted proxy. If the `Forwarded` header is present,
+/// the `X-Forwarded-*` headers are ignored. If the remote peer isn't a trusted
+/// proxy, all forwarding headers are ignored and the client is the remote
+/// peer.
+///
+/// # Request Guard
+///
+/// `ClientInfo` is a request guard. It forwards if the address of the remote
+/// peer is unknown, which only happens for local requests.
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// #
+/// use rocket::request::ClientInfo;
+///
+/// #[get("/")]
+/// fn index(client: ClientInfo) -> String {
+///     format!("Hello, {} over {}!", client.ip(), client.scheme())
+/// }
+/// #
+/// # fn main() { }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClientInfo {
+    ip: IpAddr,
+    secure: bool,
+    proxied: bool,
+}
+
+/// A single hop in a forwarding chain: the address a proxy received the
+/// request from, if known, and the scheme it was received over, if reported.
+struct Hop {
+    ip: Option<IpAddr>,
+    proto: Option<String>,
+}
+
+/// Parses a node identifier from a `Forwarded` `for` parameter or an
+/// `X-Forwarded-For` element, discarding any port. Returns `None` for
+/// obfuscated or unknown identifiers.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if node.starts_with('[') {
+        return node[1..].find(']').and_then(|i| node[1..(i + 1)].parse::<Ipv6Addr>().ok())
+            .map(IpAddr::V6);
+    }
+
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(ip);
+    }
+
+    // An IPv4 address with a port.
+    match node.rfind(':') {
+        Some(i) => node[..i].parse::<IpAddr>().ok(),
+        None => None
+    }
+}
+
+/// Parses the hops in the `Forwarded` headers in `headers`, outermost first.
+fn forwarded_hops(headers: &HeaderMap) -> Vec<Hop> {
+    let mut hops = vec![];
+    for value in headers.get("Forwarded") {
+        for element in value.split(',') {
+            let mut hop = Hop { ip: None, proto: None };
+            for pair in element.split(';') {
+                let mut kv = pair.splitn(2, '=');
+                let (key, value) = match (kv.next(), kv.next()) {
+                    (Some(key), Some(value)) => (key.trim(), value.trim().trim_matches('"')),
+                    _ => continue
+                };
+
+                if key.eq_ignore_ascii_case("for") {
+                    hop.ip = parse_node(value);
+                } else if key.eq_ignore_ascii_case("proto") {
+                    hop.proto = Some(value.to_string());
+                }
+            }
+
+            hops.push(hop);
+        }
+    }
+
+    hops
+}
+
+/// Parses the hops in the `X-Forwarded-For` headers in `headers`, outermost
+/// first. The scheme of the innermost hop, the one appended by the proxy the
+/// request came from, is taken from the last `X-Forwarded-Proto` value; the
+/// outer hops may have been made up by the client.
+fn x_forwarded_hops(headers: &HeaderMap) -> Vec<Hop> {
+    let mut hops: Vec<Hop> = headers.get("X-Forwarded-For")
+        .flat_map(|value| value.split(','))
+        .map(|node| Hop { ip: parse_node(node), proto: None })
+        .collect();
+
+    let proto = headers.get("X-Forwarded-Proto")
+        .flat_map(|value| value.split(','))
+        .last()
+        .map(|proto| proto.trim().to_string());
+
+    if let Some(last) = hops.last_mut() {
+        last.proto = proto;
+    }
+
+    hops
+}
+
+impl ClientInfo {
+    /// Determines the client of a request received from `remote` with headers
+    /// `headers`, trusting the forwarding headers set by the proxies at the
+    /// addresses in `trusted`.
+    fn resolve(remote: IpAddr, headers: &HeaderMap, trusted: &[IpAddr]) -> ClientInfo {
+        let mut client = ClientInfo { ip: remote, secure: false, proxied: false };
+        if !trusted.contains(&remote) {
+            return client;
+        }
+
+        let hops = if headers.contains("Forwarded") {
+            forwarded_hops(headers)
+        } else {
+            x_forwarded_hops(headers)
+        };
+
+        for hop in hops.iter().rev() {
+            if let Some(ref proto) = hop.proto {
+                client.secure = proto.eq_ignore_ascii_case("https");
+            }
+
+            match hop.ip {
+                Some(ip) => {
+                    client.ip = ip;
+                    client.proxied = true;
+                    if !trusted.contains(&ip) {
+                        break;
+                    }
+                }
+                // The chain is broken; the last trusted proxy is all we know.
+                None => break
+            }
+        }
+
+        client
+    }
+
+    /// Returns the IP address of the client.
+    #[inline(always)]
+    pub fn ip(&self) -> IpAddr {
+        self.ip
+    }
+
+    /// Returns `true` if the client connected over HTTPS.
+    #[inline(always)]
+    pub fn is_secure(&self) -> bool {
+        self.secure
+    }
+
+    /// Returns the scheme the client connected with: `"https"` or `"http"`.
+    #[inline(always)]
+    pub fn scheme(&self) -> &'static str {
+        if self.secure { "https" } else { "http" }
+    }
+
+    /// Returns `true` if the client information was reported by a trusted
+    /// proxy, and `false` if the client is the remote peer itself.
+    #[inline(always)]
+    pub fn is_proxied(&self) -> bool {
+        self.proxied
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for ClientInfo {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        let remote = match request.remote() {
+            Some(remote) => remote.ip(),
+            None => return Forward(())
+        };
+
+        let trusted = match request.config() {
+            Some(config) => &config.trusted_proxies[..],
+            None => &[][..]
+        };
+
+        Success(ClientInfo::resolve(remote, request.headers(), trusted))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ClientInfo, parse_node};
+
+    use std::net::IpAddr;
+    use http::{Header, HeaderMap};
+
+    fn ip(string: &str) -> IpAddr {
+        string.parse().unwrap()
+    }
+
+    fn resolve(remote: &str, headers: &[(&'static str, &'static str)], trusted: &[&str])
+            -> ClientInfo {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.add(Header::new(name, value));
+        }
+
+        let trusted: Vec<_> = trusted.iter().map(|s| ip(s)).collect();
+        ClientInfo::resolve(ip(remote), &map, &trusted)
+    }
+
+    #[test]
+    fn test_parse_node() {
+        assert_eq!(parse_node("192.0.2.60"), Some(ip("192.0.2.60")));
+        assert_eq!(parse_node(" 192.0.2.60:4711 "), Some(ip("192.0.2.60")));
+        assert_eq!(parse_node("\"[2001:db8:cafe::17]:4711\""), Some(ip("2001:db8:cafe::17")));
+        assert_eq!(parse_node("[2001:db8:cafe::17]"), Some(ip("2001:db8:cafe::17")));
+        assert_eq!(parse_node("2001:db8:cafe::17"), Some(ip("2001:db8:cafe::17")));
+        assert_eq!(parse_node("unknown"), None);
+        assert_eq!(parse_node("_hidden"), None);
+        assert_eq!(parse_node("[::1"), None);
+    }
+
+    #[test]
+    fn test_untrusted_peer() {
+        let client = resolve("203.0.113.7", &[
+            ("X-Forwarded-For", "1.2.3.4"), ("X-Forwarded-Proto", "https")
+        ], &["10.0.0.1"]);
+
+        assert_eq!(client.ip(), ip("203.0.113.7"));
+        assert!(!client.is_secure());
+        assert!(!client.is_proxied());
+    }
+
+    #[test]
+    fn test_x_forwarded() {
+        let client = resolve("10.0.0.1", &[
+            ("X-Forwarded-For", "1.2.3.4, 10.0.0.2"), ("X-Forwarded-Proto", "https")
+        ], &["10.0.0.1", "10.0.0.2"]);
+
+        assert_eq!(client.ip(), ip("1.2.3.4"));
+        assert_eq!(client.scheme(), "https");
+        assert!(client.is_proxied());
+
+        // A spoofed address before an untrusted hop is ignored.
+        let client = resolve("10.0.0.1", &[
+            ("X-Forwarded-For", "6.6.6.6, 5.5.5.5")
+        ], &["10.0.0.1"]);
+
+        assert_eq!(client.ip(), ip("5.5.5.5"));
+        assert_eq!(client.scheme(), "http");
+
+        // The scheme reported by the trusted proxy survives a spoofed prefix.
+        let client = resolve("10.0.0.1", &[
+            ("X-Forwarded-For", "6.6.6.6, 5.5.5.5"), ("X-Forwarded-Proto", "https")
+        ], &["10.0.0.1"]);
+
+        assert_eq!(client.ip(), ip("5.5.5.5"));
+        assert_eq!(client.scheme(), "https");
+    }
+
+    #[test]
+    fn test_forwarded() {
+        let client = resolve("10.0.0.1", &[
+            ("Forwarded", "for=\"[2001:db8::1]:4711\";proto=https, for=10.0.0.2"),
+            ("X-Forwarded-For", "9.9.9.9"),
+        ], &["10.0.0.1", "10.0.0.2"]);
+
+        assert_eq!(client.ip(), ip("2001:db8::1"));
+        assert!(client.is_secure());
+
+        let client = resolve("10.0.0.1", &[
+            ("Forwarded", "For=192.0.2.43;Proto=http"),
+            ("Forwarded", "for=unknown"),
+        ], &["10.0.0.1"]);
+
+        assert_eq!(client.ip(), ip("10.0.0.1"));
+        assert!(!client.is_secure());
+    }
+
+    #[test]
+    fn test_all_trusted() {
+        let client = resolve("10.0.0.1", &[
+            ("X-Forwarded-For", "10.0.0.3, 10.0.0.2")
+        ], &["10.0.0.1", "10.0.0.2", "10.0.0.3"]);
+
+        assert_eq!(client.ip(), ip("10.0.0.3"));
+    }
+}
@@ -0,0 +1,169 @@
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::fmt;
+
+use outcome::Outcome::*;
+use request::{self, Request, FromRequest};
+
+struct Inner {
+    requests: Cell<usize>,
+    cache: RefCell<HashMap<TypeId, Box<Any>>>,
+}
+
+/// Storage scoped to the connection a request arrived on.
+///
+/// Rocket keeps connections open across requests when clients ask it to. A
+/// `Connection` lives as long as the underlying connection does, and every
+/// request that arrives on it sees the same `Connection`. This sits between
+/// request-local storage, via
+/// [Request::local_cache](/rocket/struct.Request.html#method.local_cache), and
+/// global [managed state](/rocket/struct.State.html): it's the right place for
+/// setup that is expensive to repeat per request but specific to one client,
+/// such as negotiated compression contexts or per-connection rate counters.
+///
+/// A request's connection is retrieved via
+/// [Request::connection](/rocket/struct.Request.html#method.connection) or the
+/// `&Connection` request guard. Values are stored with
+/// [local_cache](#method.local_cache); values that change across requests use
+/// interior mutability, such as a `Cell`.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use std::cell::Cell;
+/// use rocket::request::Connection;
+///
+/// struct Hits(Cell<usize>);
+///
+/// #[get("/")]
+/// fn index(connection: &Connection) -> String {
+///     let hits = connection.local_cache(|| Hits(Cell::new(0)));
+///     hits.0.set(hits.0.get() + 1);
+///     format!("{} hits on this connection", hits.0.get())
+/// }
+/// # fn main() { }
+/// ```
+#[derive(Clone)]
+pub struct Connection {
+    inner: Rc<Inner>,
+}
+
+impl Connection {
+    /// Returns storage for a new connection that has yet to serve a request.
+    /// Outside of tests, Rocket creates connections itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::Connection;
+    ///
+    /// let connection = Connection::new();
+    /// assert_eq!(connection.requests(), 0);
+    /// ```
+    pub fn new() -> Connection {
+        Connection {
+            inner: Rc::new(Inner {
+                requests: Cell::new(0),
+                cache: RefCell::new(HashMap::new()),
+            })
+        }
+    }
+
+    /// Returns the number of requests that have arrived on this connection,
+    /// including the one being handled.
+    #[inline(always)]
+    pub fn requests(&self) -> usize {
+        self.inner.requests.get()
+    }
+
+    /// Records that a request arrived on this connection. This should only be
+    /// used internally by `Rocket`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn record_request(&self) {
+        self.inner.requests.set(self.inner.requests.get() + 1);
+    }
+
+    /// Returns the value of type `T` stored for this connection, first storing
+    /// the value returned by `f` if there isn't one. There is at most one value
+    /// of any given type per connection, and `f` is called at most once per
+    /// type and connection unless it itself stores a value of type `T`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::cell::Cell;
+    /// use rocket::request::Connection;
+    ///
+    /// struct Counter(Cell<usize>);
+    ///
+    /// let connection = Connection::new();
+    /// connection.local_cache(|| Counter(Cell::new(1)));
+    ///
+    /// // The stored value is returned; the closure isn't called again.
+    /// let counter = connection.local_cache(|| Counter(Cell::new(10)));
+    /// assert_eq!(counter.0.get(), 1);
+    /// ```
+    pub fn local_cache<T, F>(&self, f: F) -> &T
+        where T: 'static, F: FnOnce() -> T
+    {
+        let key = TypeId::of::<T>();
+        if !self.inner.cache.borrow().contains_key(&key) {
+            // `f` may use the cache, so it's called without a borrow.
+            let value = f();
+            self.inner.cache.borrow_mut().entry(key).or_insert(Box::new(value));
+        }
+
+        let cache = self.inner.cache.borrow();
+        let value = cache.get(&key)
+            .and_then(|value| value.downcast_ref::<T>())
+            .expect("cached value of the requested type");
+
+        // Values are boxed and never replaced or removed while the connection
+        // is alive, so their addresses are stable even as the map grows, and
+        // the reference is valid for as long as `self` is.
+        unsafe { &*(value as *const T) }
+    }
+}
+
+impl fmt::Debug for Connection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Connection")
+            .field("requests", &self.requests())
+            .field("values", &self.inner.cache.borrow().len())
+            .finish()
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for &'a Connection {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        Success(request.connection())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use super::Connection;
+
+    struct Counter(Cell<usize>);
+
+    #[test]
+    fn test_shared_between_clones() {
+        let connection = Connection::new();
+        let clone = connection.clone();
+        clone.record_request();
+        clone.local_cache(|| Counter(Cell::new(0))).0.set(5);
+
+        assert_eq!(connection.requests(), 1);
+        assert_eq!(connection.local_cache(|| Counter(Cell::new(0))).0.get(), 5);
+        assert_eq!(Connection::new().local_cache(|| Counter(Cell::new(0))).0.get(), 0);
+    }
+}
@@ -0,0 +1,98 @@
+use chrono::{NaiveDate, NaiveTime, NaiveDateTime, DateTime, FixedOffset, UTC};
+
+use http::RawStr;
+use http::uri::URI;
+use request::{FromParam, FromFormValue};
+
+/// Parses a date in the `YYYY-MM-DD` format used by `<input type="date">`.
+fn naive_date(string: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(string, "%Y-%m-%d").ok()
+}
+
+/// Parses a time, with or without seconds, as used by `<input type="time">`.
+fn naive_time(string: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(string, "%H:%M:%S%.f")
+        .or_else(|_| NaiveTime::parse_from_str(string, "%H:%M"))
+        .ok()
+}
+
+/// Parses a date and time without an offset, with or without seconds, as used
+/// by `<input type="datetime-local">`.
+fn naive_date_time(string: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(string, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(string, "%Y-%m-%dT%H:%M"))
+        .ok()
+}
+
+/// Parses an RFC 3339 date and time, preserving its offset.
+fn fixed_date_time(string: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(string).ok()
+}
+
+/// Parses an RFC 3339 date and time, converting it to UTC.
+fn utc_date_time(string: &str) -> Option<DateTime<UTC>> {
+    fixed_date_time(string).map(|date_time| date_time.with_timezone(&UTC))
+}
+
+macro_rules! impl_with_parser {
+    ($($T:ty => $parse:ident),+) => ($(
+        impl<'v> FromFormValue<'v> for $T {
+            type Error = &'v str;
+
+            fn from_form_value(v: &'v str) -> Result<Self, Self::Error> {
+                let decoded = RawStr::from_str(v).url_decode().map_err(|_| v)?;
+                $parse(&decoded).ok_or(v)
+            }
+        }
+
+        impl<'a> FromParam<'a> for $T {
+            type Error = &'a str;
+
+            fn from_param(param: &'a str) -> Result<Self, Self::Error> {
+                let decoded = URI::percent_decode(param.as_bytes()).map_err(|_| param)?;
+                $parse(&decoded).ok_or(param)
+            }
+        }
+    )+)
+}
+
+impl_with_parser!(NaiveDate => naive_date, NaiveTime => naive_time,
+    NaiveDateTime => naive_date_time, DateTime<FixedOffset> => fixed_date_time,
+    DateTime<UTC> => utc_date_time);
+
+#[cfg(test)]
+mod test {
+    use chrono::{NaiveDate, NaiveTime, NaiveDateTime, DateTime, FixedOffset, UTC, TimeZone};
+    use request::{FromParam, FromFormValue};
+
+    #[test]
+    fn test_dates_and_times() {
+        let date = NaiveDate::from_ymd(2016, 12, 24);
+        assert_eq!(NaiveDate::from_form_value("2016-12-24"), Ok(date));
+        assert_eq!(NaiveDate::from_param("2016-12-24"), Ok(date));
+        assert!(NaiveDate::from_form_value("2016-13-24").is_err());
+        assert!(NaiveDate::from_param("yesterday").is_err());
+
+        let time = NaiveTime::from_hms(18, 30, 0);
+        assert_eq!(NaiveTime::from_form_value("18%3A30"), Ok(time));
+        assert_eq!(NaiveTime::from_form_value("18:30:00"), Ok(time));
+        assert_eq!(NaiveTime::from_param("18:30"), Ok(time));
+        assert!(NaiveTime::from_form_value("25:00").is_err());
+    }
+
+    #[test]
+    fn test_date_times() {
+        let local = NaiveDateTime::new(NaiveDate::from_ymd(2016, 12, 24),
+                                       NaiveTime::from_hms(18, 30, 0));
+        assert_eq!(NaiveDateTime::from_form_value("2016-12-24T18%3A30"), Ok(local));
+        assert_eq!(NaiveDateTime::from_param("2016-12-24T18:30:00"), Ok(local));
+        assert!(NaiveDateTime::from_form_value("2016-12-24").is_err());
+
+        let offset = FixedOffset::east(3600).ymd(2016, 12, 24).and_hms(18, 30, 0);
+        assert_eq!(DateTime::<FixedOffset>::from_form_value("2016-12-24T18%3A30%3A00%2B01%3A00"),
+                   Ok(offset));
+        assert_eq!(DateTime::<UTC>::from_param("2016-12-24T18:30:00+01:00"),
+                   Ok(UTC.ymd(2016, 12, 24).and_hms(17, 30, 0)));
+        assert!(DateTime::<UTC>::from_param("2016-12-24T18:30:00").is_err());
+    }
+}
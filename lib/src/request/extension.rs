@@ -0,0 +1,80 @@
+use std::ops::Deref;
+use std::fmt;
+
+use outcome::Outcome::*;
+use request::{self, Request, FromRequest};
+
+/// Request guard to retrieve an extension of a request: a value of type `T`
+/// stored via
+/// [Request::set_extension](/rocket/struct.Request.html#method.set_extension),
+/// typically by a request hook.
+///
+/// The guard forwards if the request has no extension of type `T`. Use
+/// `Option<Extension<T>>` to handle a missing extension in the handler.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::Extension;
+/// use rocket::hook::Predicate;
+///
+/// struct Principal(String);
+///
+/// #[get("/whoami")]
+/// fn whoami(principal: Extension<Principal>) -> String {
+///     principal.0.clone()
+/// }
+///
+/// fn main() {
+/// # if false {
+///     rocket::ignite()
+///         .mount("/", routes![whoami])
+///         .on_request(Predicate::any(), |request, _| {
+///             let user = request.headers().get_one("X-User").map(|u| u.to_string());
+///             if let Some(user) = user {
+///                 request.set_extension(Principal(user));
+///             }
+///         })
+///         .launch()
+/// # }
+/// }
+/// ```
+pub struct Extension<'a, T: 'static>(&'a T);
+
+impl<'a, T: 'static> Extension<'a, T> {
+    /// Returns a reference to the extension with the lifetime of the request
+    /// rather than that of the `Extension`.
+    #[inline(always)]
+    pub fn inner(&self) -> &'a T {
+        self.0
+    }
+}
+
+impl<'a, T: 'static> Deref for Extension<'a, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        self.0
+    }
+}
+
+impl<'a, T: fmt::Debug + 'static> fmt::Debug for Extension<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Extension").field(self.0).finish()
+    }
+}
+
+impl<'a, 'r, T: 'static> FromRequest<'a, 'r> for Extension<'a, T> {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        match request.extension::<T>() {
+            Some(value) => Success(Extension(value)),
+            None => Forward(())
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::any::Any;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use http::Status;
+
+/// The reason a request guard failed.
+///
+/// When a [FromRequest](trait.FromRequest.html) guard for a route fails, the
+/// request is failed with the guard's status, and the guard's error is
+/// recorded in the request alongside the status. The recorded failure can be
+/// retrieved via
+/// [Request::guard_failure](struct.Request.html#method.guard_failure), usually
+/// from an error catcher, to produce a more helpful response. It is also
+/// logged with the outcome of the request.
+///
+/// Data guards that parse the request body, such as [Form](struct.Form.html),
+/// record their failures in the same way, so catchers can report which part of
+/// the body was invalid.
+///
+/// The guard's error is recorded as is, alongside its `Debug` representation.
+/// A catcher that knows which guard failed can recover the typed error with
+/// [downcast_ref](#method.downcast_ref); any catcher can report the `Debug`
+/// representation via [error](#method.error). Because the error is kept as
+/// is, the errors of guards used by routes must be `'static`.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// #
+/// use rocket::Request;
+///
+/// #[error(401)]
+/// fn unauthorized(req: &Request) -> String {
+///     match req.guard_failure() {
+///         Some(failure) => match failure.downcast_ref::<ApiKeyError>() {
+///             Some(&ApiKeyError::Missing) => "Missing API key.".to_string(),
+///             Some(&ApiKeyError::Expired(ref key)) => format!("{} expired.", key),
+///             None => format!("Unauthorized: {}", failure.error())
+///         },
+///         None => "Unauthorized.".to_string()
+///     }
+/// }
+/// #
+/// # #[derive(Debug)]
+/// # enum ApiKeyError { Missing, Expired(String) }
+/// # fn main() { }
+/// ```
+#[derive(Clone)]
+pub struct GuardFailure {
+    status: Status,
+    error: String,
+    value: Rc<Any>,
+}
+
+impl GuardFailure {
+    /// Creates a new `GuardFailure` with status `status` and error `error`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::GuardFailure;
+    /// use rocket::http::Status;
+    ///
+    /// let failure = GuardFailure::new(Status::Unauthorized, "missing key");
+    /// assert_eq!(failure.error(), "\"missing key\"");
+    /// ```
+    #[inline]
+    pub fn new<E: fmt::Debug + Any>(status: Status, error: E) -> GuardFailure {
+        GuardFailure {
+            status: status,
+            error: format!("{:?}", error),
+            value: Rc::new(error),
+        }
+    }
+
+    /// Returns the status the guard failed with.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::GuardFailure;
+    /// use rocket::http::Status;
+    ///
+    /// let failure = GuardFailure::new(Status::Forbidden, ());
+    /// assert_eq!(failure.status(), Status::Forbidden);
+    /// ```
+    #[inline(always)]
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Returns the `Debug` representation of the guard's error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::GuardFailure;
+    /// use rocket::http::Status;
+    ///
+    /// let failure = GuardFailure::new(Status::BadRequest, Some(10));
+    /// assert_eq!(failure.error(), "Some(10)");
+    /// ```
+    #[inline(always)]
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+
+    /// Returns the guard's error if it is of type `E`. Otherwise, returns
+    /// `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::GuardFailure;
+    /// use rocket::http::Status;
+    ///
+    /// let failure = GuardFailure::new(Status::BadRequest, 10usize);
+    /// assert_eq!(failure.downcast_ref::<usize>(), Some(&10));
+    /// assert_eq!(failure.downcast_ref::<String>(), None);
+    /// ```
+    #[inline(always)]
+    pub fn downcast_ref<E: Any>(&self) -> Option<&E> {
+        self.value.downcast_ref()
+    }
+}
+
+impl fmt::Debug for GuardFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GuardFailure")
+            .field("status", &self.status)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+// Failures are compared by their status and the `Debug` representation of
+// their errors since the errors themselves needn't be comparable.
+impl PartialEq for GuardFailure {
+    fn eq(&self, other: &GuardFailure) -> bool {
+        self.status == other.status && self.error == other.error
+    }
+}
+
+impl Eq for GuardFailure { }
+
+impl Hash for GuardFailure {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.status.hash(state);
+        self.error.hash(state);
+    }
+}
+
+impl fmt::Display for GuardFailure {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.status, self.error)
+    }
+}
@@ -0,0 +1,395 @@
+use std::io;
+use std::fmt;
+
+use config::ByteUnit;
+
+/// The error returned by implementations of [FromForm](trait.FromForm.html)
+/// derived via `#[derive(FromForm)]`. It identifies the field that caused
+/// parsing to fail and why.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::request::FormError;
+///
+/// let error = FormError::BadValue {
+///     field: "age".to_string(),
+///     value: "ten".to_string(),
+///     expected: "usize"
+/// };
+///
+/// assert_eq!(error.field(), "age");
+/// assert_eq!(error.to_string(), "field 'age': expected usize, found 'ten'");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FormError {
+    /// The value of `field` could not be parsed as the type `expected`. The
+    /// raw, possibly URL-encoded, value is `value`.
+    BadValue {
+        field: String,
+        value: String,
+        expected: &'static str
+    },
+    /// The form has no value for `field`, which is of type `expected` and has
+    /// no default.
+    Missing {
+        field: String,
+        expected: &'static str
+    },
+    /// The form contains `field`, which isn't a field of the structure.
+    Unexpected {
+        field: String
+    },
+    /// The value of `field` parsed but was rejected by one of the field's
+    /// validators, which described why in `message`.
+    Invalid {
+        field: String,
+        message: String
+    },
+    /// The form has no value for `field`, and the default declared for it via
+    /// `#[form(default = "...")]` isn't a valid `expected`. The structure, not
+    /// the form, is at fault, so the `Form` guard fails with `500 Internal
+    /// Server Error`.
+    BadDefault {
+        field: String,
+        expected: &'static str
+    },
+}
+
+impl FormError {
+    /// Returns the name of the field that caused parsing to fail.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::FormError;
+    ///
+    /// let error = FormError::Unexpected { field: "admin".to_string() };
+    /// assert_eq!(error.field(), "admin");
+    /// ```
+    pub fn field(&self) -> &str {
+        match *self {
+            FormError::BadValue { ref field, .. } => field,
+            FormError::Missing { ref field, .. } => field,
+            FormError::Unexpected { ref field } => field,
+            FormError::Invalid { ref field, .. } => field,
+            FormError::BadDefault { ref field, .. } => field,
+        }
+    }
+
+    /// Prefixes the field name in `self` with `prefix`. Used to report errors
+    /// in nested forms and collections with the complete key, as in
+    /// `address.street` or `tags[2]`.
+    #[doc(hidden)]
+    pub fn prefixed(self, prefix: &str) -> FormError {
+        self.map_field(|field| format!("{}{}", prefix, field))
+    }
+
+    /// Replaces the field name in `self` with the result of calling `f` with
+    /// it.
+    #[doc(hidden)]
+    pub fn map_field<F: FnOnce(String) -> String>(self, f: F) -> FormError {
+        match self {
+            FormError::BadValue { field, value, expected } => FormError::BadValue {
+                field: f(field),
+                value: value,
+                expected: expected
+            },
+            FormError::Missing { field, expected } => FormError::Missing {
+                field: f(field),
+                expected: expected
+            },
+            FormError::Unexpected { field } => FormError::Unexpected {
+                field: f(field)
+            },
+            FormError::Invalid { field, message } => FormError::Invalid {
+                field: f(field),
+                message: message
+            },
+            FormError::BadDefault { field, expected } => FormError::BadDefault {
+                field: f(field),
+                expected: expected
+            },
+        }
+    }
+}
+
+impl fmt::Display for FormError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FormError::BadValue { ref field, ref value, expected } => {
+                write!(f, "field '{}': expected {}, found '{}'", field, expected, value)
+            }
+            FormError::Missing { ref field, expected } => {
+                write!(f, "field '{}': missing value of type {}", field, expected)
+            }
+            FormError::Unexpected { ref field } => {
+                write!(f, "field '{}': unexpected field", field)
+            }
+            FormError::Invalid { ref field, ref message } => {
+                write!(f, "field '{}': {}", field, message)
+            }
+            FormError::BadDefault { ref field, expected } => {
+                write!(f, "field '{}': declared default isn't a valid {}", field, expected)
+            }
+        }
+    }
+}
+
+/// Every error encountered while parsing a form via `#[derive(FromForm)]`, in
+/// the order the fields were parsed.
+///
+/// The derived `FromForm` implementation fails with the first of these errors.
+/// To receive all of them, as when re-rendering a form with an error message
+/// next to each invalid field, parse a
+/// [Validated](struct.Validated.html) form instead.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::request::{FormError, FormErrors};
+///
+/// let mut errors = FormErrors::new();
+/// errors.push(FormError::Missing { field: "name".to_string(), expected: "String" });
+/// errors.push(FormError::Invalid {
+///     field: "age".to_string(),
+///     message: "must be at least 18".to_string()
+/// });
+///
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors.first().map(|e| e.field()), Some("name"));
+/// assert_eq!(errors.for_field("age").len(), 1);
+/// assert_eq!(errors.to_string(),
+///            "field 'name': missing value of type String; field 'age': must be at least 18");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct FormErrors(Vec<FormError>);
+
+impl FormErrors {
+    /// Returns an empty collection of errors.
+    #[inline(always)]
+    pub fn new() -> FormErrors {
+        FormErrors(vec![])
+    }
+
+    /// Appends `error` to the errors.
+    #[inline(always)]
+    pub fn push(&mut self, error: FormError) {
+        self.0.push(error);
+    }
+
+    /// Appends all of the errors in `other`, in order.
+    #[inline(always)]
+    pub fn append(&mut self, other: FormErrors) {
+        self.0.extend(other.0);
+    }
+
+    /// Returns `true` if there are no errors.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of errors.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns an iterator over the errors, in order.
+    #[inline(always)]
+    pub fn iter(&self) -> ::std::slice::Iter<FormError> {
+        self.0.iter()
+    }
+
+    /// Returns the first error, if any. This is the error the derived
+    /// `FromForm` implementation fails with.
+    #[inline(always)]
+    pub fn first(&self) -> Option<&FormError> {
+        self.0.first()
+    }
+
+    /// Returns the errors for the field named `name`, the complete key of the
+    /// field, as in `age` or `address.city`.
+    pub fn for_field(&self, name: &str) -> Vec<&FormError> {
+        self.0.iter().filter(|e| e.field() == name).collect()
+    }
+
+    /// Prefixes the field name of every error with `prefix`.
+    #[doc(hidden)]
+    pub fn prefixed(self, prefix: &str) -> FormErrors {
+        self.map_field(|field| format!("{}{}", prefix, field))
+    }
+
+    /// Replaces the field name of every error with the result of calling `f`
+    /// with it.
+    #[doc(hidden)]
+    pub fn map_field<F: FnMut(String) -> String>(self, mut f: F) -> FormErrors {
+        FormErrors(self.0.into_iter().map(|e| e.map_field(&mut f)).collect())
+    }
+
+    /// Returns the first error. Panics if there are no errors.
+    #[doc(hidden)]
+    pub fn into_first(self) -> FormError {
+        self.0.into_iter().next().expect("an error")
+    }
+}
+
+impl From<FormError> for FormErrors {
+    fn from(error: FormError) -> FormErrors {
+        FormErrors(vec![error])
+    }
+}
+
+impl IntoIterator for FormErrors {
+    type Item = FormError;
+    type IntoIter = ::std::vec::IntoIter<FormError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a FormErrors {
+    type Item = &'a FormError;
+    type IntoIter = ::std::slice::Iter<'a, FormError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl fmt::Display for FormErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, error) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+
+            write!(f, "{}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The error returned by the [Form](struct.Form.html) data guard when the
+/// request's form data couldn't be read or parsed.
+///
+/// A handler can receive this error by accepting a `Result<Form<T>,
+/// FormDataError<T::Error>>`, where `T::Error` is a
+/// [FormError](enum.FormError.html) when `FromForm` is derived. This allows an
+/// API to respond with details about exactly which field was invalid:
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::{Form, FormError, FormDataError};
+/// use rocket::response::status;
+///
+/// #[derive(FromForm)]
+/// struct Signup {
+///     name: String,
+///     age: u8
+/// }
+///
+/// #[post("/signup", data = "<signup>")]
+/// fn signup(signup: Result<Form<Signup>, FormDataError<FormError>>)
+///         -> Result<String, status::Custom<String>> {
+///     use rocket::http::Status;
+///
+///     match signup {
+///         Ok(form) => Ok(format!("Welcome, {}!", form.get().name)),
+///         Err(FormDataError::Parse(_, e)) => {
+///             Err(status::Custom(Status::UnprocessableEntity, e.to_string()))
+///         }
+///         Err(FormDataError::Io(_)) => {
+///             Err(status::Custom(Status::BadRequest, "Unreadable form.".into()))
+///         }
+///         Err(FormDataError::TooLarge(_)) => {
+///             Err(status::Custom(Status::PayloadTooLarge, "Form too large.".into()))
+///         }
+///         Err(FormDataError::Malformed(_)) => {
+///             Err(status::Custom(Status::BadRequest, "Malformed form.".into()))
+///         }
+///         Err(FormDataError::TooMany(_)) => {
+///             Err(status::Custom(Status::PayloadTooLarge, "Too many fields.".into()))
+///         }
+///     }
+/// }
+/// # fn main() { }
+/// ```
+///
+/// When the guard fails instead, the error is recorded as the request's
+/// [guard_failure](struct.Request.html#method.guard_failure) so that error
+/// catchers can inspect it.
+#[derive(Debug)]
+pub enum FormDataError<E> {
+    /// Reading the form data failed. This includes data that can't be decoded
+    /// from its charset.
+    Io(io::Error),
+    /// The form string, the first field, couldn't be parsed; the error is the
+    /// second field.
+    Parse(String, E),
+    /// The form data exceeds the **forms** limit, or an uploaded file in
+    /// multipart form data exceeds the **file** limit, the field, described in
+    /// [Limits](/rocket/config/struct.Limits.html).
+    TooLarge(ByteUnit),
+    /// The form data is multipart form data that isn't well-formed; the field
+    /// describes why.
+    Malformed(&'static str),
+    /// The form data is multipart form data with more parts or files than the
+    /// limit with the given name, **parts** or **files**, allows.
+    TooMany(&'static str),
+}
+
+impl<E: fmt::Display> fmt::Display for FormDataError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FormDataError::Io(ref e) => write!(f, "failed to read form data: {}", e),
+            FormDataError::Parse(_, ref e) => fmt::Display::fmt(e, f),
+            FormDataError::TooLarge(limit) => {
+                write!(f, "form data exceeds the limit of {}", limit)
+            }
+            FormDataError::Malformed(reason) => write!(f, "malformed form data: {}", reason),
+            FormDataError::TooMany(limit) => write!(f, "form data exceeds the {} limit", limit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FormError, FormErrors, FormDataError};
+
+    #[test]
+    fn test_display() {
+        let missing = FormError::Missing { field: "name".into(), expected: "String" };
+        assert_eq!(missing.field(), "name");
+        assert_eq!(missing.to_string(), "field 'name': missing value of type String");
+
+        let unexpected = FormError::Unexpected { field: "x".into() };
+        assert_eq!(unexpected.to_string(), "field 'x': unexpected field");
+
+        let parse = FormDataError::Parse("x=1".into(), unexpected);
+        assert_eq!(parse.to_string(), "field 'x': unexpected field");
+
+        let invalid = FormError::Invalid { field: "age".into(), message: "too old".into() };
+        assert_eq!(invalid.to_string(), "field 'age': too old");
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut errors = FormErrors::from(FormError::Unexpected { field: "x".into() });
+        errors.append(FormErrors::from(FormError::Invalid {
+            field: "[0]".into(),
+            message: "bad".into()
+        }).prefixed("tags"));
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors.for_field("tags[0]").len(), 1);
+        assert!(errors.for_field("tags").is_empty());
+        assert_eq!(errors.to_string(), "field 'x': unexpected field; field 'tags[0]': bad");
+        assert_eq!(errors.into_first(), FormError::Unexpected { field: "x".into() });
+    }
+}
@@ -0,0 +1,631 @@
+//! Support for `#[derive(FromForm)]`. The derived implementation can't know
+//! whether a field's type is a form value, a nested form, or a collection, so
+//! it parses every field via `from_form_field`, which dispatches on the traits
+//! here. Each trait specializes on one of the three cases.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::{FromFormValue, FormError, FormErrors};
+
+/// The syntax of the keys of nested forms in a form string.
+///
+/// Collections use brackets in either syntax: `tags[0]=a` is an element of a
+/// `Vec` field named `tags`, and `attrs[color]=red` an entry of a `HashMap`
+/// field named `attrs`. The syntaxes differ in how the fields of nested
+/// structures are named:
+///
+///   * **Dots**, the default, reads them from keys like
+///     `user.name=x&user.address.city=y`.
+///   * **Brackets** reads them from keys like
+///     `user[name]=x&user[address][city]=y`, as produced by many JavaScript
+///     form serializers and Rails-style clients. Elements are also appended
+///     to a `Vec` field by keys with empty brackets, as in
+///     `user[emails][]=a&user[emails][]=b`.
+///
+/// The syntax of a structure deriving `FromForm` is chosen via the `syntax`
+/// parameter of the `form` attribute, as in `#[form(syntax = "brackets")]`,
+/// and applies to the structures nested within it as well.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::FromForm;
+///
+/// #[derive(FromForm)]
+/// struct User {
+///     name: String,
+///     emails: Vec<String>
+/// }
+///
+/// #[derive(FromForm)]
+/// #[form(syntax = "brackets")]
+/// struct Signup {
+///     user: User
+/// }
+///
+/// # fn main() {
+/// let form = "user[name]=Bob&user[emails][]=a%40b.c&user[emails][]=b%40c.d";
+/// let signup = Signup::from_form_string(form).unwrap();
+/// assert_eq!(signup.user.name, "Bob");
+/// assert_eq!(signup.user.emails.len(), 2);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FormSyntax {
+    /// Nested fields are named with dots, as in `user.name`.
+    Dots,
+    /// Nested fields are named with brackets, as in `user[name]`.
+    Brackets,
+}
+
+impl Default for FormSyntax {
+    fn default() -> FormSyntax {
+        FormSyntax::Dots
+    }
+}
+
+/// Splits `key`, the key of a form item, into the name of a field and the rest
+/// of the key, as in `user` and `.name`. With the `Brackets` syntax, the keys
+/// of the fields of nested forms are passed on whole, as in `[name][first]`,
+/// and the name is the one in the leading brackets.
+#[doc(hidden)]
+pub fn split_form_key(key: &str, syntax: FormSyntax) -> (&str, &str) {
+    if syntax == FormSyntax::Brackets {
+        if let Some(split) = split_index(key) {
+            return split;
+        }
+    }
+
+    match key.find(|c: char| c == '.' || c == '[' || c == '%') {
+        Some(i) => (&key[..i], &key[i..]),
+        None => (key, "")
+    }
+}
+
+/// Parses an instance of `Self` from form items whose keys may be nested, as in
+/// `address.street`. This trait is implemented by `#[derive(FromForm)]`; the
+/// derived `FromForm` implementation delegates to it.
+#[doc(hidden)]
+pub trait FromFormItems<'f>: Sized {
+    /// Parses `Self` from `items`, whose keys use `syntax`, failing on
+    /// unexpected fields only if `strict` is `true`. Fails with every error
+    /// encountered, in the order the fields were parsed.
+    fn from_form_items(items: Vec<(&'f str, &'f str)>, strict: bool, syntax: FormSyntax)
+        -> Result<Self, FormErrors>;
+
+    /// The syntax of the keys of `Self` when it isn't nested in another form.
+    fn form_syntax() -> FormSyntax {
+        FormSyntax::Dots
+    }
+}
+
+/// A type that can be a field of a derived `FromForm` structure: a form value,
+/// a structure that derives `FromForm`, or a `Vec` or `HashMap` of those. The
+/// derived implementation requires each field to implement this trait, so that
+/// a field that can't be parsed is a compile-time error rather than a form that
+/// never parses. `#[derive(FromForm)]` implements it for the structure.
+#[doc(hidden)]
+pub trait FormField<'f> { }
+
+impl<'f, T: FromFormValue<'f>> FormField<'f> for T { }
+
+impl<'f, T: FormField<'f>> FormField<'f> for Vec<T> { }
+
+impl<'f, K, V> FormField<'f> for HashMap<K, V>
+    where K: FromFormValue<'f> + Eq + Hash, V: FormField<'f> { }
+
+/// Does nothing, but fails to compile unless `T` can be a field of a derived
+/// `FromForm` structure.
+#[doc(hidden)]
+#[inline(always)]
+pub fn assert_form_field<'f, T: FormField<'f>>() { }
+
+/// A field of a derived `FromForm` structure that may be a form value.
+#[doc(hidden)]
+pub trait FormFieldValue<'f>: Sized {
+    /// Parses a field from the raw value `value`. Returns `None` if `Self`
+    /// isn't a form value and `Some(Err(()))` if `value` doesn't parse.
+    fn from_field_value(value: &'f str) -> Option<Result<Self, ()>>;
+
+    /// The value of the field when the form doesn't contain it, if any.
+    fn field_default() -> Option<Self>;
+}
+
+impl<'f, T> FormFieldValue<'f> for T {
+    default fn from_field_value(_: &'f str) -> Option<Result<Self, ()>> {
+        None
+    }
+
+    default fn field_default() -> Option<Self> {
+        None
+    }
+}
+
+impl<'f, T: FromFormValue<'f>> FormFieldValue<'f> for T {
+    fn from_field_value(value: &'f str) -> Option<Result<Self, ()>> {
+        Some(T::from_form_value(value).map_err(|_| ()))
+    }
+
+    fn field_default() -> Option<Self> {
+        T::default()
+    }
+}
+
+/// A field of a derived `FromForm` structure that has a value when it's missing
+/// from a form parsed leniently. Only `bool`, a checkbox, which isn't submitted
+/// when it's unchecked, does: it's `false`.
+trait FormFieldLenient: Sized {
+    /// The value of the field when a form parsed leniently doesn't contain it,
+    /// if any.
+    fn lenient_default() -> Option<Self>;
+}
+
+impl<T> FormFieldLenient for T {
+    default fn lenient_default() -> Option<Self> {
+        None
+    }
+}
+
+impl FormFieldLenient for bool {
+    fn lenient_default() -> Option<bool> {
+        Some(false)
+    }
+}
+
+/// A field of a derived `FromForm` structure that may be a nested form.
+#[doc(hidden)]
+pub trait FormFieldNested<'f>: Sized {
+    /// Parses a field from `items`, the items with keys prefixed by the field's
+    /// name, with the prefix removed. Returns `None` if `Self` isn't a form.
+    fn from_field_items(items: Vec<(&'f str, &'f str)>, strict: bool, syntax: FormSyntax)
+        -> Option<Result<Self, FormErrors>>;
+}
+
+impl<'f, T> FormFieldNested<'f> for T {
+    default fn from_field_items(_: Vec<(&'f str, &'f str)>, _: bool, _: FormSyntax)
+            -> Option<Result<Self, FormErrors>> {
+        None
+    }
+}
+
+impl<'f, T: FromFormItems<'f>> FormFieldNested<'f> for T {
+    fn from_field_items(items: Vec<(&'f str, &'f str)>, strict: bool, syntax: FormSyntax)
+            -> Option<Result<Self, FormErrors>> {
+        Some(T::from_form_items(items, strict, syntax))
+    }
+}
+
+/// A field of a derived `FromForm` structure that may be a collection: a `Vec`
+/// or a `HashMap`.
+#[doc(hidden)]
+pub trait FormFieldCollection<'f>: Sized {
+    /// Parses a field from `items`, pairs of the rest of a key after the
+    /// field's name, as in `[0]`, and a value. Returns `None` if `Self` isn't a
+    /// collection. `expected` names the type of the field.
+    fn from_field_collection(items: Vec<(&'f str, &'f str)>, strict: bool,
+                             syntax: FormSyntax, expected: &'static str)
+        -> Option<Result<Self, FormErrors>>;
+
+    /// The empty collection, used when the form contains no items for the
+    /// field. Returns `None` if `Self` isn't a collection.
+    fn empty_collection() -> Option<Self>;
+}
+
+impl<'f, T> FormFieldCollection<'f> for T {
+    default fn from_field_collection(_: Vec<(&'f str, &'f str)>, _: bool, _: FormSyntax,
+                                     _: &'static str)
+            -> Option<Result<Self, FormErrors>> {
+        None
+    }
+
+    default fn empty_collection() -> Option<Self> {
+        None
+    }
+}
+
+/// Repeated keys, as in `tag=a&tag=b`, are elements in the order they appear.
+/// Indexed keys, as in `tag[1]=b&tag[0]=a`, are elements in index order
+/// following those. Elements may be values or nested forms, as in
+/// `rows[0].name=a`. With the `Brackets` syntax, keys with empty brackets, as
+/// in `tag[]=a`, are repeated keys.
+impl<'f, T> FormFieldCollection<'f> for Vec<T> {
+    fn from_field_collection(items: Vec<(&'f str, &'f str)>, strict: bool,
+                             syntax: FormSyntax, expected: &'static str)
+            -> Option<Result<Self, FormErrors>> {
+        Some(from_vec_items(items, strict, syntax, expected))
+    }
+
+    fn empty_collection() -> Option<Self> {
+        Some(vec![])
+    }
+}
+
+/// Keyed items, as in `attrs[color]=red`, are entries with key `color`. The
+/// values may be values or nested forms, as in `users[bob].age=42`.
+impl<'f, K: FromFormValue<'f> + Eq + Hash, V> FormFieldCollection<'f> for HashMap<K, V> {
+    fn from_field_collection(items: Vec<(&'f str, &'f str)>, strict: bool,
+                             syntax: FormSyntax, expected: &'static str)
+            -> Option<Result<Self, FormErrors>> {
+        Some(from_map_items(items, strict, syntax, expected))
+    }
+
+    fn empty_collection() -> Option<Self> {
+        Some(HashMap::new())
+    }
+}
+
+/// Splits `rest`, the rest of a key after a field's name, of the form
+/// `[index]remainder` into `index` and `remainder`. Browsers percent-encode
+/// the brackets, so `%5B` and `%5D` are accepted as well.
+fn split_index(rest: &str) -> Option<(&str, &str)> {
+    let bytes = rest.as_bytes();
+    let start = if bytes.starts_with(b"[") {
+        1
+    } else if bytes.len() >= 3 && bytes[0] == b'%' && bytes[1] == b'5'
+            && (bytes[2] == b'B' || bytes[2] == b'b') {
+        3
+    } else {
+        return None;
+    };
+
+    for i in start..bytes.len() {
+        if bytes[i] == b']' {
+            return Some((&rest[start..i], &rest[(i + 1)..]));
+        }
+
+        if bytes[i] == b'%' && i + 2 < bytes.len() && bytes[i + 1] == b'5'
+                && (bytes[i + 2] == b'D' || bytes[i + 2] == b'd') {
+            return Some((&rest[start..i], &rest[(i + 3)..]));
+        }
+    }
+
+    None
+}
+
+/// Groups the items with keys of the form `[index]remainder` by `index`, in
+/// order of first appearance, as `(remainder, value)` pairs. Items with keys
+/// of any other form are passed to `other`, which may fail.
+fn group_indexed<'f, F>(items: Vec<(&'f str, &'f str)>, mut other: F)
+        -> Result<Vec<(&'f str, Vec<(&'f str, &'f str)>)>, FormError>
+    where F: FnMut(&'f str, &'f str) -> Result<(), FormError>
+{
+    let mut groups: Vec<(&'f str, Vec<(&'f str, &'f str)>)> = vec![];
+    for (rest, value) in items {
+        let (index, remainder) = match split_index(rest) {
+            Some(split) => split,
+            None => {
+                other(rest, value)?;
+                continue;
+            }
+        };
+
+        let position = groups.iter().position(|&(i, _)| i == index);
+        match position {
+            Some(position) => groups[position].1.push((remainder, value)),
+            None => groups.push((index, vec![(remainder, value)]))
+        }
+    }
+
+    Ok(groups)
+}
+
+fn from_vec_items<'f, T>(items: Vec<(&'f str, &'f str)>, strict: bool, syntax: FormSyntax,
+                         expected: &'static str) -> Result<Vec<T>, FormErrors> {
+    let mut repeated = vec![];
+    let groups = group_indexed(items, |rest, value| {
+        if rest.is_empty() {
+            repeated.push(value);
+        } else if strict {
+            return Err(FormError::Unexpected { field: rest.to_string() });
+        }
+
+        Ok(())
+    })?;
+
+    let mut indexed = vec![];
+    for (index, items) in groups {
+        if index.is_empty() && syntax == FormSyntax::Brackets {
+            for (remainder, value) in items {
+                if remainder.is_empty() {
+                    repeated.push(value);
+                } else if strict {
+                    let field = format!("[]{}", remainder);
+                    return Err(FormError::Unexpected { field: field }.into());
+                }
+            }
+
+            continue;
+        }
+
+        match index.parse::<usize>() {
+            Ok(i) => indexed.push((i, index, items)),
+            Err(_) if strict => {
+                return Err(FormError::Unexpected { field: format!("[{}]", index) }.into());
+            }
+            Err(_) => { /* Ignore the non-numeric index. */ }
+        }
+    }
+
+    // Every element is parsed so that all of their errors are reported.
+    let mut elements = vec![];
+    let mut errors = FormErrors::new();
+    for (i, value) in repeated.into_iter().enumerate() {
+        match from_form_element(vec![("", value)], strict, syntax, expected) {
+            Ok(element) => elements.push(element),
+            Err(e) => errors.append(e.prefixed(&format!("[{}]", i)))
+        }
+    }
+
+    indexed.sort_by_key(|&(i, _, _)| i);
+    for (_, index, items) in indexed {
+        match from_form_element(items, strict, syntax, expected) {
+            Ok(element) => elements.push(element),
+            Err(e) => errors.append(e.prefixed(&format!("[{}]", index)))
+        }
+    }
+
+    match errors.is_empty() {
+        true => Ok(elements),
+        false => Err(errors)
+    }
+}
+
+fn from_map_items<'f, K, V>(items: Vec<(&'f str, &'f str)>, strict: bool, syntax: FormSyntax,
+                            expected: &'static str) -> Result<HashMap<K, V>, FormErrors>
+    where K: FromFormValue<'f> + Eq + Hash
+{
+    let groups = group_indexed(items, |rest, _| {
+        if strict {
+            return Err(FormError::Unexpected { field: rest.to_string() });
+        }
+
+        Ok(())
+    })?;
+
+    let mut map = HashMap::new();
+    let mut errors = FormErrors::new();
+    for (index, items) in groups {
+        let prefix = format!("[{}]", index);
+        let key = match K::from_form_value(index) {
+            Ok(key) => key,
+            Err(_) => {
+                errors.push(FormError::BadValue {
+                    field: prefix,
+                    value: index.to_string(),
+                    expected: expected
+                });
+
+                continue;
+            }
+        };
+
+        match from_form_element(items, strict, syntax, expected) {
+            Ok(value) => { map.insert(key, value); }
+            Err(e) => errors.append(e.prefixed(&prefix))
+        }
+    }
+
+    match errors.is_empty() {
+        true => Ok(map),
+        false => Err(errors)
+    }
+}
+
+/// Parses an element of a collection from `items`, failing if the element
+/// has no value and no default.
+fn from_form_element<'f, T>(items: Vec<(&'f str, &'f str)>, strict: bool, syntax: FormSyntax,
+                            expected: &'static str) -> Result<T, FormErrors> {
+    match from_form_field(items, strict, syntax, expected)? {
+        Some(element) => Ok(element),
+        None => form_field_default(strict).ok_or_else(|| FormError::Missing {
+            field: String::new(),
+            expected: expected
+        }.into())
+    }
+}
+
+/// Parses a field of type `T` from `items`, pairs of the rest of a key after
+/// the field's name and a value, whose keys use `syntax`. `expected` names the
+/// type of the field.
+///
+/// Returns `Ok(None)` if the items were all ignored, which only happens when
+/// `strict` is `false`. Errors identify the field relative to the field's name,
+/// so that the complete key is recovered by prefixing it with the name.
+#[doc(hidden)]
+pub fn from_form_field<'f, T>(items: Vec<(&'f str, &'f str)>, strict: bool,
+                              syntax: FormSyntax, expected: &'static str)
+                              -> Result<Option<T>, FormErrors> {
+    let collection = <T as FormFieldCollection<'f>>::from_field_collection(items.clone(),
+                                                                          strict, syntax,
+                                                                          expected);
+    if let Some(result) = collection {
+        return result.map(Some);
+    }
+
+    // Repeated keys for a single value: the last value wins. The keys of
+    // nested items keep their leading brackets with the `Brackets` syntax.
+    let mut raw_value = None;
+    let mut nested = vec![];
+    let mut first_nested = None;
+    for (rest, value) in items {
+        if rest.is_empty() {
+            raw_value = Some(value);
+        } else if syntax == FormSyntax::Dots && rest.starts_with('.') {
+            first_nested = first_nested.or(Some(rest));
+            nested.push((&rest[1..], value));
+        } else if syntax == FormSyntax::Brackets && split_index(rest).is_some() {
+            first_nested = first_nested.or(Some(rest));
+            nested.push((rest, value));
+        } else if strict {
+            return Err(FormError::Unexpected { field: rest.to_string() }.into());
+        }
+    }
+
+    if let Some(key) = first_nested {
+        match <T as FormFieldNested<'f>>::from_field_items(nested.clone(), strict, syntax) {
+            Some(result) => return result.map(Some).map_err(|e| match syntax {
+                FormSyntax::Dots => e.prefixed("."),
+                FormSyntax::Brackets => e.map_field(|field| bracket_name(&field)),
+            }),
+            None if strict => {
+                return Err(FormError::Unexpected { field: key.to_string() }.into());
+            }
+            None => { /* Ignore the nested items. */ }
+        }
+    }
+
+    match raw_value {
+        Some(value) => match <T as FormFieldValue<'f>>::from_field_value(value) {
+            Some(Ok(parsed)) => Ok(Some(parsed)),
+            _ => Err(FormError::BadValue {
+                field: String::new(),
+                value: value.to_string(),
+                expected: expected
+            }.into())
+        },
+        None => Ok(None)
+    }
+}
+
+/// Encloses the name of the field at the start of `field`, the key of a field
+/// of a nested form, in brackets, as in `[address][city]` for `address[city]`.
+fn bracket_name(field: &str) -> String {
+    match field.find(|c: char| c == '[' || c == '.') {
+        Some(i) => format!("[{}]{}", &field[..i], &field[i..]),
+        None => format!("[{}]", field)
+    }
+}
+
+/// Returns the value of a field of type `T` when the form doesn't contain it,
+/// if any: the default form value, the lenient default if the form isn't parsed
+/// `strict`ly, or an empty collection.
+#[doc(hidden)]
+pub fn form_field_default<'f, T>(strict: bool) -> Option<T> {
+    <T as FormFieldValue<'f>>::field_default()
+        .or_else(|| match strict {
+            true => None,
+            false => <T as FormFieldLenient>::lenient_default()
+        })
+        .or_else(|| <T as FormFieldCollection<'f>>::empty_collection())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use super::{from_form_field, split_index, split_form_key, FormSyntax};
+    use request::{FormError, FormErrors};
+
+    fn parse<'f, T>(items: &[(&'f str, &'f str)]) -> Result<Option<T>, FormError> {
+        from_form_field(items.to_vec(), true, FormSyntax::Dots, "T")
+            .map_err(FormErrors::into_first)
+    }
+
+    fn parse_brackets<'f, T>(items: &[(&'f str, &'f str)]) -> Result<Option<T>, FormError> {
+        from_form_field(items.to_vec(), true, FormSyntax::Brackets, "T")
+            .map_err(FormErrors::into_first)
+    }
+
+    #[test]
+    fn test_split_index() {
+        assert_eq!(split_index("[0]"), Some(("0", "")));
+        assert_eq!(split_index("[a].b"), Some(("a", ".b")));
+        assert_eq!(split_index("%5B1%5d[2]"), Some(("1", "[2]")));
+        assert_eq!(split_index("[0"), None);
+        assert_eq!(split_index(".a"), None);
+    }
+
+    #[test]
+    fn test_vec_fields() {
+        assert_eq!(parse(&[("", "a"), ("", "b")]), Ok(Some(vec!["a", "b"])));
+        assert_eq!(parse(&[("[1]", "2"), ("%5B0%5D", "1"), ("", "0")]),
+                   Ok(Some(vec![0usize, 1, 2])));
+        assert_eq!(parse(&[("[0][1]", "b"), ("[0][0]", "a")]),
+                   Ok(Some(vec![vec!["a", "b"]])));
+
+        assert_eq!(parse::<Vec<usize>>(&[("", "1"), ("[3]", "x")]), Err(FormError::BadValue {
+            field: "[3]".to_string(),
+            value: "x".to_string(),
+            expected: "T"
+        }));
+
+        assert_eq!(parse::<Vec<usize>>(&[("[x]", "1")]),
+                   Err(FormError::Unexpected { field: "[x]".to_string() }));
+        assert_eq!(parse::<Vec<usize>>(&[(".x", "1")]),
+                   Err(FormError::Unexpected { field: ".x".to_string() }));
+    }
+
+    #[test]
+    fn test_collection_errors() {
+        let items = vec![("", "1"), ("", "x"), ("[5]", "y"), ("[4]", "2")];
+        let errors = from_form_field::<Vec<usize>>(items, true, FormSyntax::Dots, "T")
+            .unwrap_err();
+
+        let fields: Vec<_> = errors.iter().map(|e| e.field()).collect();
+        assert_eq!(fields, vec!["[1]", "[5]"]);
+
+        let items = vec![("[a]", "1"), ("[1]", "x"), ("[2]", "3")];
+        let errors = from_form_field::<HashMap<usize, usize>>(items, true, FormSyntax::Dots, "T")
+            .unwrap_err();
+
+        let fields: Vec<_> = errors.iter().map(|e| e.field()).collect();
+        assert_eq!(fields, vec!["[a]", "[1]"]);
+    }
+
+    #[test]
+    fn test_map_fields() {
+        let map: HashMap<String, usize> = parse(&[("[a]", "1"), ("[b]", "2")]).unwrap().unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["a"], 1);
+        assert_eq!(map["b"], 2);
+
+        assert_eq!(parse::<HashMap<usize, usize>>(&[("[a]", "1")]), Err(FormError::BadValue {
+            field: "[a]".to_string(),
+            value: "a".to_string(),
+            expected: "T"
+        }));
+
+        assert_eq!(parse::<HashMap<String, usize>>(&[("", "1")]),
+                   Err(FormError::Unexpected { field: "".to_string() }));
+    }
+
+    #[test]
+    fn test_value_fields() {
+        assert_eq!(parse(&[("", "1"), ("", "2")]), Ok(Some(2usize)));
+        assert_eq!(parse::<usize>(&[("[0]", "1")]),
+                   Err(FormError::Unexpected { field: "[0]".to_string() }));
+        assert_eq!(from_form_field::<usize>(vec![("[0]", "1")], false, FormSyntax::Dots, "T"),
+                   Ok(None));
+    }
+
+    #[test]
+    fn test_split_form_key() {
+        assert_eq!(split_form_key("user.name", FormSyntax::Dots), ("user", ".name"));
+        assert_eq!(split_form_key("tags[0]", FormSyntax::Dots), ("tags", "[0]"));
+        assert_eq!(split_form_key("[name]", FormSyntax::Dots), ("", "[name]"));
+        assert_eq!(split_form_key("user[name]", FormSyntax::Brackets), ("user", "[name]"));
+        assert_eq!(split_form_key("[name][first]", FormSyntax::Brackets), ("name", "[first]"));
+        assert_eq!(split_form_key("name", FormSyntax::Brackets), ("name", ""));
+    }
+
+    #[test]
+    fn test_bracket_fields() {
+        assert_eq!(parse_brackets(&[("[]", "a"), ("%5B%5D", "b")]), Ok(Some(vec!["a", "b"])));
+        assert_eq!(parse_brackets(&[("[1]", "b"), ("[0]", "a"), ("[]", "c")]),
+                   Ok(Some(vec!["c", "a", "b"])));
+        assert_eq!(parse_brackets(&[("[0][]", "a"), ("[0][]", "b")]),
+                   Ok(Some(vec![vec!["a", "b"]])));
+
+        assert_eq!(parse::<Vec<&str>>(&[("[]", "a")]),
+                   Err(FormError::Unexpected { field: "[]".to_string() }));
+        assert_eq!(parse_brackets::<Vec<&str>>(&[("[][x]", "a")]),
+                   Err(FormError::Unexpected { field: "[][x]".to_string() }));
+        assert_eq!(parse_brackets::<usize>(&[("[x]", "1")]),
+                   Err(FormError::Unexpected { field: "[x]".to_string() }));
+        assert_eq!(parse_brackets::<usize>(&[(".x", "1")]),
+                   Err(FormError::Unexpected { field: ".x".to_string() }));
+    }
+}
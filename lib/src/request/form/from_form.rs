@@ -38,7 +38,157 @@ use error::Error;
 /// ```
 ///
 /// When deriving `FromForm`, every field in the structure must implement
-/// [FromFormValue](trait.FromFormValue.html).
+/// [FromFormValue](trait.FromFormValue.html), itself derive `FromForm`, or be
+/// a `Vec` or `HashMap` of such types; any other field is a compile-time
+/// error. The fields of a nested structure are read from keys prefixed with the
+/// name of the field holding it and a `.`:
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::FromForm;
+///
+/// #[derive(FromForm)]
+/// struct Address {
+///     street: String,
+///     city: String
+/// }
+///
+/// #[derive(FromForm)]
+/// struct Order {
+///     item: String,
+///     shipping: Address
+/// }
+///
+/// # fn main() {
+/// let form = "item=Rocket&shipping.street=1+Main+St&shipping.city=Moon";
+/// let order = Order::from_form_string(form).unwrap();
+/// assert_eq!(order.shipping.city, "Moon");
+/// # }
+/// ```
+///
+/// Nested structures can't be optional: the derived implementation fails if
+/// none of a nested structure's fields are present.
+///
+/// Fields of type `Vec<T>` collect repeated keys, as in `tag=a&tag=b`, and
+/// indexed keys, as in `tag[0]=a&tag[1]=b`, into a vector. Fields of type
+/// `HashMap<K, V>` collect keyed items, as in `attrs[color]=red`. Elements may
+/// themselves be nested structures, as in `rows[0].name=a`. A collection field
+/// with no items in the form is empty.
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::FromForm;
+///
+/// #[derive(FromForm)]
+/// struct Post {
+///     title: String,
+///     tags: Vec<String>
+/// }
+///
+/// # fn main() {
+/// let post = Post::from_form_string("title=Hi&tags=news&tags=rust").unwrap();
+/// assert_eq!(post.tags, vec!["news".to_string(), "rust".to_string()]);
+///
+/// let post = Post::from_form_string("title=Hi").unwrap();
+/// assert!(post.tags.is_empty());
+/// # }
+/// ```
+///
+/// A field is read from the form field with its name unless it's annotated
+/// with `#[form(field = "name")]`, which allows form field names that aren't
+/// valid Rust identifiers, such as `type`. A field annotated with
+/// `#[form(skip)]` is never read from the form, so it can't be set by a user,
+/// and is instead the `Default` value of its type. Form fields with the name of
+/// a skipped field are unexpected.
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::FromForm;
+///
+/// #[derive(FromForm)]
+/// struct Account {
+///     #[form(field = "type")]
+///     kind: String,
+///     #[form(skip)]
+///     admin: bool
+/// }
+///
+/// # fn main() {
+/// let account = Account::from_form_string("type=personal").unwrap();
+/// assert_eq!(account.kind, "personal");
+/// assert!(!account.admin);
+///
+/// assert!(Account::from_form_string("type=personal&admin=true").is_err());
+/// # }
+/// ```
+///
+/// A field annotated with `#[form(default = "value")]` is parsed as if the
+/// form had `value` as its value when it's missing from the form, instead of
+/// failing to parse. A `value` that isn't a valid `bool` or number, for fields
+/// of those types, is a compile-time error; for fields of other types, parsing
+/// fails with a `FormError::BadDefault` if `value` isn't valid, and the `Form`
+/// guard fails with `500 Internal Server Error`, since the fault isn't the
+/// client's. A field annotated with `#[form(default)]` is its type's `Default`
+/// value when it's missing. Optional checkboxes and rarely sent fields thus
+/// need not be `Option`s:
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::FromForm;
+///
+/// #[derive(FromForm)]
+/// struct Search {
+///     query: String,
+///     #[form(default = "10")]
+///     limit: usize,
+///     #[form(default = "relevance")]
+///     sort: String,
+///     #[form(default)]
+///     exact: bool
+/// }
+///
+/// # fn main() {
+/// let search = Search::from_form_string("query=rocket").unwrap();
+/// assert_eq!((search.limit, search.sort.as_str(), search.exact), (10, "relevance", false));
+/// # }
+/// ```
+///
+/// A field annotated with `#[form(validate = "expr")]` is checked by the
+/// [Validator](validate/trait.Validator.html) `expr` after it parses, failing
+/// with a `FormError::Invalid` if the validator rejects it. The derived
+/// implementation fails with the first error encountered; parse a
+/// [Validated](struct.Validated.html) form to receive all of them:
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::{FromForm, Validated};
+///
+/// #[derive(FromForm)]
+/// struct Reservation {
+///     #[form(validate = "len(1..64)")]
+///     name: String,
+///     #[form(validate = "range(1..13)")]
+///     guests: u8
+/// }
+///
+/// # fn main() {
+/// let form = "name=&guests=20";
+/// assert_eq!(Reservation::from_form_string(form).unwrap_err().field(), "name");
+///
+/// let errors = Validated::<Reservation>::from_form_string(form).unwrap().0.unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// # }
+/// ```
 ///
 /// # Implementing
 ///
@@ -54,6 +204,101 @@ pub trait FromForm<'f>: Sized {
     /// (`application/x-www-form-urlencoded data`) or returns an `Error` if one
     /// cannot be parsed.
     fn from_form_string(form_string: &'f str) -> Result<Self, Self::Error>;
+
+    /// Parses an instance of `Self` from a raw HTTP form string like
+    /// [from_form_string](#tymethod.from_form_string), but ignores fields
+    /// that `Self` doesn't expect instead of returning an `Error`. Missing
+    /// fields are still errors. This method is used by
+    /// [LenientForm](struct.LenientForm.html).
+    ///
+    /// The default implementation simply calls `from_form_string`. Derived
+    /// implementations are lenient.
+    fn from_form_string_lenient(form_string: &'f str) -> Result<Self, Self::Error> {
+        Self::from_form_string(form_string)
+    }
+
+    /// Returns the value to use when there is no form at all, as when a route
+    /// declares a `query` parameter and the request has no query string. If
+    /// this returns `None`, the form is required and the request is
+    /// forwarded. The default implementation returns `None`.
+    fn default_form() -> Option<Self> {
+        None
+    }
+}
+
+/// A missing form is `None`, as is a form that fails to parse. This allows a
+/// route's `query` parameter to be optional:
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// #[derive(FromForm)]
+/// struct Page {
+///     number: usize
+/// }
+///
+/// #[get("/items?<page>")]
+/// fn items(page: Option<Page>) -> String {
+///     format!("page {}", page.map_or(1, |p| p.number))
+/// }
+/// # fn main() { }
+/// ```
+impl<'f, T: FromForm<'f>> FromForm<'f> for Option<T> {
+    type Error = T::Error;
+
+    fn from_form_string(form_string: &'f str) -> Result<Self, Self::Error> {
+        Ok(T::from_form_string(form_string).ok())
+    }
+
+    fn from_form_string_lenient(form_string: &'f str) -> Result<Self, Self::Error> {
+        Ok(T::from_form_string_lenient(form_string).ok())
+    }
+
+    fn default_form() -> Option<Option<T>> {
+        Some(None)
+    }
+}
+
+/// A form that fails to parse is an `Err` with the error. A missing form is
+/// parsed as an empty one, so it's `Ok` only if all of the fields of `T` are
+/// optional. This allows a route to examine why its `query` parameter is
+/// invalid instead of forwarding:
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::FormError;
+///
+/// #[derive(FromForm)]
+/// struct Page {
+///     number: usize
+/// }
+///
+/// #[get("/items?<page>")]
+/// fn items(page: Result<Page, FormError>) -> String {
+///     match page {
+///         Ok(page) => format!("page {}", page.number),
+///         Err(e) => format!("bad page: {}", e)
+///     }
+/// }
+/// # fn main() { }
+/// ```
+impl<'f, T: FromForm<'f>> FromForm<'f> for Result<T, T::Error> {
+    type Error = T::Error;
+
+    fn from_form_string(form_string: &'f str) -> Result<Self, Self::Error> {
+        Ok(T::from_form_string(form_string))
+    }
+
+    fn from_form_string_lenient(form_string: &'f str) -> Result<Self, Self::Error> {
+        Ok(T::from_form_string_lenient(form_string))
+    }
+
+    fn default_form() -> Option<Self> {
+        Some(T::from_form_string(""))
+    }
 }
 
 /// This implementation should only be used during debugging!
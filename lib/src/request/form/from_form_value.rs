@@ -1,8 +1,12 @@
+use std::ascii::AsciiExt;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6, SocketAddr};
 use std::str::FromStr;
 
+#[cfg(feature = "uuid")] use uuid::Uuid;
+#[cfg(feature = "bigdecimal")] use bigdecimal::BigDecimal;
+
 use error::Error;
-use http::uri::URI;
+use http::RawStr;
 
 /// Trait to create instance of some type from a form value; expected from field
 /// types in structs deriving `FromForm`.
@@ -40,6 +44,18 @@ use http::uri::URI;
 ///     ...
 /// }
 /// ```
+///
+/// # Optional Implementations
+///
+/// With the `uuid` feature enabled, `FromFormValue` is implemented for
+/// `uuid::Uuid`. With the `bigdecimal` feature enabled, it is implemented for
+/// `bigdecimal::BigDecimal`, which represents decimal values such as prices
+/// exactly, unlike `f64`. With the `chrono` feature enabled, it is implemented
+/// for chrono's `NaiveDate`, `NaiveTime`, and `NaiveDateTime`, which accept
+/// the values submitted by `date`, `time`, and `datetime-local` inputs, and
+/// for `DateTime<FixedOffset>` and `DateTime<UTC>`, which accept RFC 3339
+/// dates and times with an offset. The same types implement
+/// [FromParam](/rocket/request/trait.FromParam.html).
 pub trait FromFormValue<'v>: Sized {
     /// The associated error which can be returned from parsing. It is a good
     /// idea to have the return type be or contain an `&'v str` so that the
@@ -68,38 +84,51 @@ impl<'v> FromFormValue<'v> for &'v str {
     }
 }
 
+impl<'v> FromFormValue<'v> for &'v RawStr {
+    type Error = Error;
+
+    // This gives the raw string, explicitly marked as such.
+    #[inline(always)]
+    fn from_form_value(v: &'v str) -> Result<Self, Self::Error> {
+        Ok(RawStr::from_str(v))
+    }
+}
+
 impl<'v> FromFormValue<'v> for String {
     type Error = &'v str;
 
     // This actually parses the value according to the standard.
     fn from_form_value(v: &'v str) -> Result<Self, Self::Error> {
-        let result = URI::percent_decode(v.as_bytes());
-        match result {
-            Err(_) => Err(v),
-            Ok(mut string) => Ok({
-                // Entirely safe because we're changing the single-byte '+'.
-                unsafe {
-                    for c in string.to_mut().as_mut_vec() {
-                        if *c == b'+' {
-                            *c = b' ';
-                        }
-                    }
-                }
-
-                string.into_owned()
-            })
-        }
+        RawStr::from_str(v).url_decode().map_err(|_| v)
     }
 }
 
+/// A checkbox: `on`, `true`, `yes`, and `1` are `true`, and `off`, `false`,
+/// `no`, and `0` are `false`, ignoring case. A checked checkbox is submitted
+/// with the value `on` by default, while an unchecked one isn't submitted at
+/// all. A missing `bool` field is thus `false` in a form parsed leniently, as
+/// via [LenientForm](struct.LenientForm.html), but is an error in a form parsed
+/// strictly; use `Option<bool>` or `#[form(default)]` for an optional checkbox.
+///
+/// ```rust
+/// use rocket::request::FromFormValue;
+///
+/// assert_eq!(bool::from_form_value("on"), Ok(true));
+/// assert_eq!(bool::from_form_value("Yes"), Ok(true));
+/// assert_eq!(bool::from_form_value("0"), Ok(false));
+/// assert_eq!(<bool as FromFormValue>::default(), None);
+/// ```
 impl<'v> FromFormValue<'v> for bool {
     type Error = &'v str;
 
     fn from_form_value(v: &'v str) -> Result<Self, Self::Error> {
-        match v {
-            "on" | "true" => Ok(true),
-            "off" | "false" => Ok(false),
-            _ => Err(v),
+        let is = |value: &str| v.eq_ignore_ascii_case(value);
+        if is("on") || is("true") || is("yes") || v == "1" {
+            Ok(true)
+        } else if is("off") || is("false") || is("no") || v == "0" {
+            Ok(false)
+        } else {
+            Err(v)
         }
     }
 }
@@ -118,6 +147,9 @@ macro_rules! impl_with_fromstr {
 impl_with_fromstr!(f32, f64, isize, i8, i16, i32, i64, usize, u8, u16, u32, u64,
     IpAddr, Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6, SocketAddr);
 
+#[cfg(feature = "uuid")] impl_with_fromstr!(Uuid);
+#[cfg(feature = "bigdecimal")] impl_with_fromstr!(BigDecimal);
+
 impl<'v, T: FromFormValue<'v>> FromFormValue<'v> for Option<T> {
     type Error = Error;
 
@@ -0,0 +1,93 @@
+use std::fmt::{self, Debug};
+
+use request::Request;
+use data::{self, Data, FromData};
+use outcome::Outcome::*;
+use super::{Form, FromForm, FormDataError};
+
+/// A `FromData` type for parsing `FromForm` types leniently.
+///
+/// `LenientForm` is identical to [Form](struct.Form.html) except that fields
+/// in the form that `T` doesn't expect are ignored instead of causing parsing
+/// to fail, and missing `bool` fields, unchecked checkboxes, are `false`.
+/// Other fields that `T` requires must still be present. This makes it a
+/// deliberate choice, per route, to accept forms with extra fields, such as
+/// those submitted by third-party services or by a client newer than the
+/// server.
+///
+/// The parsed structure is retrieved in the same way as with `Form`: borrowed
+/// via [get](#method.get) or moved out via [into_inner](#method.into_inner).
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::LenientForm;
+///
+/// #[derive(FromForm)]
+/// struct Payment {
+///     amount: u64,
+///     currency: String
+/// }
+///
+/// // A payment provider's webhook may send more fields than we care about.
+/// #[post("/webhook", data = "<payment>")]
+/// fn webhook(payment: LenientForm<Payment>) -> String {
+///     let payment = payment.into_inner();
+///     format!("Received {} {}.", payment.amount, payment.currency)
+/// }
+/// # fn main() { }
+/// ```
+pub struct LenientForm<'f, T: FromForm<'f> + 'f>(Form<'f, T>);
+
+impl<'f, T: FromForm<'f> + 'f> LenientForm<'f, T> {
+    /// Immutably borrow the parsed type.
+    #[inline(always)]
+    pub fn get(&'f self) -> &'f T {
+        self.0.get()
+    }
+
+    /// Mutably borrow the parsed type.
+    #[inline(always)]
+    pub fn get_mut(&'f mut self) -> &'f mut T {
+        self.0.get_mut()
+    }
+
+    /// Returns the raw form string that was used to parse the encapsulated
+    /// object.
+    #[inline(always)]
+    pub fn raw_form_string(&self) -> &str {
+        self.0.raw_form_string()
+    }
+}
+
+impl<'f, T: FromForm<'f> + 'static> LenientForm<'f, T> {
+    /// Consume this object and move out the parsed object.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+}
+
+impl<'f, T: FromForm<'f> + Debug + 'f> Debug for LenientForm<'f, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+/// Parses a `LenientForm` from incoming form data. Behaves exactly like the
+/// `FromData` implementation of [Form](struct.Form.html) except that
+/// unexpected fields are ignored.
+impl<'f, T: FromForm<'f>> FromData for LenientForm<'f, T> where T::Error: Debug + 'static {
+    type Error = FormDataError<T::Error>;
+
+    fn from_data(request: &Request, data: Data) -> data::Outcome<Self, Self::Error> {
+        match Form::read(request, data, false) {
+            Success(form) => Success(LenientForm(form)),
+            Failure(failure) => Failure(failure),
+            Forward(data) => Forward(data),
+        }
+    }
+}
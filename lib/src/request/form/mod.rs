@@ -14,22 +14,41 @@
 //! [FromFormValue](trait.FormFormValue.html). See the
 //! [codegen](/rocket_codegen/) documentation or the [forms guide](/guide/forms)
 //! for more information on forms and on deriving `FromForm`.
+//!
+//! Forms submitted as multipart form data, such as those with file inputs, are
+//! parsed via [MultipartForm](struct.MultipartForm.html), with uploaded files
+//! received as [TempFile](struct.TempFile.html)s. [Form](struct.Form.html)
+//! parses the text fields of multipart form data just as it parses URL-encoded
+//! form data, skipping uploaded files.
 
 mod form_items;
 mod from_form;
 mod from_form_value;
+mod error;
+mod lenient;
+mod field;
+mod multipart;
+pub mod validate;
 
 pub use self::form_items::FormItems;
 pub use self::from_form::FromForm;
 pub use self::from_form_value::FromFormValue;
+pub use self::error::{FormError, FormErrors, FormDataError};
+pub use self::lenient::LenientForm;
+pub use self::multipart::{MultipartForm, MultipartError, TempFile};
+pub use self::field::FormSyntax;
+pub use self::validate::Validated;
+#[doc(hidden)] pub use self::field::{FromFormItems, from_form_field, form_field_default};
+#[doc(hidden)] pub use self::field::{FormField, assert_form_field};
+#[doc(hidden)] pub use self::field::split_form_key;
 
 use std::marker::PhantomData;
 use std::fmt::{self, Debug};
-use std::io::Read;
+use std::io::{self, Read};
 
 use http::Status;
 use request::Request;
-use data::{self, Data, FromData};
+use data::{self, Data, FromData, Charset};
 use outcome::Outcome::*;
 
 // TODO: This works and is safe, but the lifetime appears twice.
@@ -148,6 +167,43 @@ use outcome::Outcome::*;
 ///
 /// If your string values will not contain URL encoded characters, using `str`
 /// will result in fewer allocation and is thus spreferred.
+///
+/// ## Strict Parsing
+///
+/// `Form` parses strictly: a form with a field that `T` doesn't expect, other
+/// than `_method`, fails to parse, as does a form missing a field that `T`
+/// requires. To ignore unexpected fields instead, use
+/// [LenientForm](struct.LenientForm.html).
+///
+/// ## Multipart Form Data
+///
+/// `Form` parses both `application/x-www-form-urlencoded` and
+/// `multipart/form-data`, so a handler works whether or not the HTML form it
+/// handles includes a file input, which makes browsers send multipart form
+/// data. The text fields of multipart form data are parsed into `T` exactly as
+/// if they had been URL-encoded. Uploaded files are skipped without being
+/// stored. Text fields are limited by the **forms** limit, each skipped file
+/// by the **file** limit, and the number of parts by the **parts** limit, all
+/// described in [Limits](/rocket/config/struct.Limits.html). To receive
+/// uploaded files, use [MultipartForm](struct.MultipartForm.html) instead.
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::Form;
+///
+/// #[derive(FromForm)]
+/// struct Message {
+///     text: String
+/// }
+///
+/// #[post("/message", data = "<message>")]
+/// fn message(message: Form<Message>) -> String {
+///     message.into_inner().text
+/// }
+/// # fn main() {  }
+/// ```
 pub struct Form<'f, T: FromForm<'f> + 'f> {
     object: T,
     form_string: String,
@@ -191,12 +247,18 @@ impl<'f, T: FromForm<'f> + 'f> Form<'f, T> {
     // caller via `get()` and contrain everything to that lifetime. This is, in
     // reality a little coarser than necessary, but the user can simply move the
     // call to right after the creation of a Form object to get the same effect.
-    fn new(form_string: String) -> Result<Self, (String, T::Error)> {
+    fn new(form_string: String, strict: bool) -> Result<Self, (String, T::Error)> {
         let long_lived_string: &'f str = unsafe {
             ::std::mem::transmute(form_string.as_str())
         };
 
-        match T::from_form_string(long_lived_string) {
+        let result = if strict {
+            T::from_form_string(long_lived_string)
+        } else {
+            T::from_form_string_lenient(long_lived_string)
+        };
+
+        match result {
             Ok(obj) => Ok(Form {
                 form_string: form_string,
                 object: obj,
@@ -220,44 +282,197 @@ impl<'f, T: FromForm<'f> + Debug + 'f> Debug for Form<'f, T> {
     }
 }
 
+/// The status a form guard fails with when the form data couldn't be parsed
+/// into a structure, with an error of type `Self`: `422 Unprocessable Entity`
+/// if every field parsed but some were rejected by validators, `500 Internal
+/// Server Error` if a declared default is invalid, and `400 Bad Request`
+/// otherwise.
+trait FormErrorStatus {
+    fn form_status(&self) -> Status;
+}
+
+impl<E> FormErrorStatus for E {
+    default fn form_status(&self) -> Status {
+        Status::BadRequest
+    }
+}
+
+impl FormErrorStatus for FormError {
+    fn form_status(&self) -> Status {
+        match *self {
+            FormError::Invalid { .. } => Status::UnprocessableEntity,
+            FormError::BadDefault { .. } => Status::InternalServerError,
+            _ => Status::BadRequest
+        }
+    }
+}
+
+impl FormErrorStatus for FormErrors {
+    fn form_status(&self) -> Status {
+        let statuses: Vec<_> = self.iter().map(|e| e.form_status()).collect();
+        if statuses.contains(&Status::InternalServerError) {
+            Status::InternalServerError
+        } else if statuses.contains(&Status::BadRequest) || statuses.is_empty() {
+            Status::BadRequest
+        } else {
+            Status::UnprocessableEntity
+        }
+    }
+}
+
 /// Parses a `Form` from incoming form data.
 ///
-/// If the content type of the request data is not
-/// `application/x-www-form-urlencoded`, `Forward`s the request. If the form
-/// data cannot be parsed into a `T` or reading the incoming stream failed,
-/// returns a `Failure` with the raw form string (if avaialble).
+/// If the content type of the request data is neither
+/// `application/x-www-form-urlencoded` nor `multipart/form-data`, `Forward`s
+/// the request. If reading the incoming stream failed, returns a `Failure` of
+/// `FormDataError::Io` with a status of `500 Internal Server Error`, or of `400
+/// Bad Request` if the data can't be decoded from its charset. If the
+/// form data exceeds a size limit, returns a `Failure` of
+/// `FormDataError::TooLarge`, and if multipart form data has too many parts or
+/// files, a `Failure` of `FormDataError::TooMany`, both with a status of `413
+/// Payload Too Large`. If multipart form data is
+/// malformed, returns a `Failure` of `FormDataError::Malformed` with a status
+/// of `400 Bad Request`. If the form data cannot be parsed into a `T`, returns
+/// a `Failure` of `FormDataError::Parse`, holding the raw form string and
+/// `T`'s error, with a status of `400 Bad Request`. The status is instead `422
+/// Unprocessable Entity` if every field parsed but some were rejected by their
+/// validators, all errors being `FormError::Invalid`, and `500 Internal Server
+/// Error` if an error is a `FormError::BadDefault`. In any case, when the guard
+/// fails a route, the error is recorded as the request's
+/// [guard_failure](struct.Request.html#method.guard_failure).
 ///
 /// All relevant warnings and errors are written to the console in Rocket
 /// logging format.
-impl<'f, T: FromForm<'f>> FromData for Form<'f, T> where T::Error: Debug {
-    type Error = Option<String>;
+impl<'f, T: FromForm<'f>> FromData for Form<'f, T> where T::Error: Debug + 'static {
+    type Error = FormDataError<T::Error>;
 
     fn from_data(request: &Request, data: Data) -> data::Outcome<Self, Self::Error> {
-        if !request.content_type().is_form() {
+        Form::read(request, data, true)
+    }
+}
+
+impl<'f, T: FromForm<'f>> Form<'f, T> where T::Error: Debug + 'static {
+    /// Reads and parses the form in `data`, strictly if `strict` is `true`.
+    /// This is the implementation of `FromData` for both `Form` and
+    /// `LenientForm`.
+    fn read(request: &Request, data: Data, strict: bool)
+            -> data::Outcome<Self, FormDataError<T::Error>> {
+        let content_type = request.content_type();
+        let result = if content_type.is_form() {
+            Form::read_urlencoded(request, data)
+        } else if content_type.is_data_form() {
+            multipart::read_form_data(request, data, false)
+                .map(|(form_string, _)| form_string)
+                .map_err(|(e, field)| {
+                    error_!("Failed to read multipart form field '{}': {:?}", field, e);
+                    e.into_form_error()
+                })
+        } else {
             warn_!("Form data does not have form content type.");
             return Forward(data);
+        };
+
+        let result = result.and_then(|form_string| {
+            Form::new(form_string, strict).map_err(|(form_string, e)| {
+                error_!("Failed to parse value from form: {:?}", e);
+                FormDataError::Parse(form_string, e)
+            })
+        });
+
+        match result {
+            Ok(form) => Success(form),
+            Err(error) => {
+                let status = match error {
+                    FormDataError::Io(ref e) if e.kind() == io::ErrorKind::InvalidData => {
+                        Status::BadRequest
+                    }
+                    FormDataError::Io(_) => Status::InternalServerError,
+                    FormDataError::TooLarge(_) | FormDataError::TooMany(_) => {
+                        Status::PayloadTooLarge
+                    }
+                    FormDataError::Parse(_, ref e) => e.form_status(),
+                    _ => Status::BadRequest
+                };
+
+                Failure((status, error))
+            }
         }
+    }
 
-        let mut form_string = String::with_capacity(4096);
-        let mut stream = data.open().take(32768);
-        if let Err(e) = stream.read_to_string(&mut form_string) {
+    // Reads the URL-encoded form string in `data`, within the **forms** limit,
+    // decoding it from its charset.
+    fn read_urlencoded(request: &Request, data: Data)
+            -> Result<String, FormDataError<T::Error>> {
+        let mut bytes = Vec::with_capacity(4096);
+        let limit = request.limits().get("forms");
+
+        // Read one byte past the limit to find out if the limit is exceeded.
+        let mut stream = data.open().take(limit.as_u64() + 1);
+        if let Err(e) = stream.read_to_end(&mut bytes) {
             error_!("IO Error: {:?}", e);
-            Failure((Status::InternalServerError, None))
-        } else {
-            match Form::new(form_string) {
-                Ok(form) => Success(form),
-                Err((form_string, e)) => {
-                    error_!("Failed to parse value from form: {:?}", e);
-                    Failure((Status::BadRequest, Some(form_string)))
+            return Err(FormDataError::Io(e));
+        } else if bytes.len() as u64 > limit.as_u64() {
+            error_!("Form data exceeds the limit of {}.", limit);
+            return Err(FormDataError::TooLarge(limit));
+        }
+
+        let charset = Charset::of(&request.content_type());
+        match charset.and_then(|charset| charset.decode(bytes).map(|s| (charset, s))) {
+            Ok((Charset::Latin1, form_string)) => Ok(latin1_escapes_to_utf8(&form_string)),
+            Ok((_, form_string)) => Ok(form_string),
+            Err(e) => {
+                error_!("Form data couldn't be decoded: {}", e);
+                Err(FormDataError::Io(e))
+            }
+        }
+    }
+}
+
+/// Rewrites the percent-encoded bytes in `form_string`, a form string sent in
+/// Latin-1, as the percent-encoded UTF-8 bytes of the same characters, so that
+/// they decode to the characters the client meant.
+fn latin1_escapes_to_utf8(form_string: &str) -> String {
+    fn hex(b: u8) -> Option<u8> {
+        match b {
+            b'0'...b'9' => Some(b - b'0'),
+            b'a'...b'f' => Some(b - b'a' + 10),
+            b'A'...b'F' => Some(b - b'A' + 10),
+            _ => None
+        }
+    }
+
+    let bytes = form_string.as_bytes();
+    let mut output = String::with_capacity(form_string.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let (Some(hi), Some(lo)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                let byte = hi * 16 + lo;
+                if byte >= 0x80 {
+                    let mut utf8 = String::new();
+                    utf8.push(byte as char);
+                    for b in utf8.bytes() {
+                        output.push_str(&format!("%{:02X}", b));
+                    }
+
+                    i += 3;
+                    continue;
                 }
             }
         }
+
+        // Every character but an escape is ASCII or was decoded from Latin-1.
+        let c = form_string[i..].chars().next().unwrap();
+        output.push(c);
+        i += c.len_utf8();
     }
+
+    output
 }
 
 #[cfg(test)]
 mod test {
-    use super::Form;
+    use super::{Form, latin1_escapes_to_utf8};
     use ::request::FromForm;
 
     struct Simple<'s> {
@@ -287,7 +502,7 @@ mod test {
     #[test]
     fn test_lifetime() {
         let form_string = "hello=world".to_string();
-        let form: Form<Simple> = Form::new(form_string).unwrap();
+        let form: Form<Simple> = Form::new(form_string, true).unwrap();
 
         let string: &str = form.get().value;
         assert_eq!(string, "hello=world");
@@ -297,7 +512,7 @@ mod test {
     fn test_lifetime_2() {
         let form_string = "hello=world".to_string();
         let mut _y = "hi";
-        let _form: Form<Simple> = Form::new(form_string).unwrap();
+        let _form: Form<Simple> = Form::new(form_string, true).unwrap();
         // _y = form.get().value;
 
         // fn should_not_compile<'f>(form: Form<'f, &'f str>) -> &'f str {
@@ -314,7 +529,7 @@ mod test {
     #[test]
     fn test_lifetime_3() {
         let form_string = "hello=world".to_string();
-        let form: Form<Other> = Form::new(form_string).unwrap();
+        let form: Form<Other> = Form::new(form_string, true).unwrap();
 
         // Not bad.
         fn should_compile(form: Form<Other>) -> String {
@@ -327,12 +542,19 @@ mod test {
     #[test]
     fn test_lifetime_4() {
         let form_string = "hello=world".to_string();
-        let form: Form<Simple> = Form::new(form_string).unwrap();
+        let form: Form<Simple> = Form::new(form_string, true).unwrap();
 
         fn should_compile<'f>(_form: Form<'f, Simple<'f>>) {  }
 
         should_compile(form)
         // assert_eq!(should_not_compile(form), "hello=world");
     }
-}
 
+    #[test]
+    fn test_latin1_escapes() {
+        assert_eq!(latin1_escapes_to_utf8("a=caf%E9&b=%e9t%E9"), "a=caf%C3%A9&b=%C3%A9t%C3%A9");
+        assert_eq!(latin1_escapes_to_utf8("a=%20%2B+%7E"), "a=%20%2B+%7E");
+        assert_eq!(latin1_escapes_to_utf8("a=\u{e9}%E"), "a=\u{e9}%E");
+        assert_eq!(latin1_escapes_to_utf8("a=%ZZ%"), "a=%ZZ%");
+    }
+}
@@ -0,0 +1,913 @@
+use std::ascii::AsciiExt;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::cmp::min;
+use std::env;
+use std::fmt::{self, Debug};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use http::{Status, ContentType};
+use request::Request;
+use data::{self, Data, FromData};
+use config::ByteUnit;
+use outcome::Outcome::*;
+use super::{Form, FromForm, FromFormValue, FormDataError, FormErrorStatus};
+
+/// The maximum size of the headers of a part, and of the preamble.
+const HEAD_LIMIT: u64 = 8 * 1024;
+
+/// The number of bytes read from the body at a time.
+const CHUNK_SIZE: usize = 4096;
+
+/// A file uploaded in multipart form data, stored in a temporary file.
+///
+/// A `TempFile` is the type of a file field in a structure parsed from
+/// multipart form data via [MultipartForm](struct.MultipartForm.html). Each
+/// uploaded file is streamed to a new file in the system's temporary directory
+/// as it is received; the file is limited to the size of the **file** limit
+/// described in [Limits](/rocket/config/struct.Limits.html).
+///
+/// The temporary file is deleted when the `TempFile` is dropped. To keep it,
+/// move it elsewhere with [persist_to](#method.persist_to).
+///
+/// A `TempFile` can only be parsed from multipart form data: parsing one from
+/// any other form fails.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::{MultipartForm, TempFile};
+///
+/// #[derive(FromForm)]
+/// struct Upload {
+///     description: String,
+///     file: TempFile
+/// }
+///
+/// #[post("/upload", data = "<upload>")]
+/// fn upload(upload: MultipartForm<Upload>) -> std::io::Result<String> {
+///     let upload = upload.into_inner();
+///     let name = upload.file.file_name().unwrap_or("upload").to_string();
+///     upload.file.persist_to(format!("/srv/uploads/{}", name))?;
+///     Ok(format!("Saved {}: {}", name, upload.description))
+/// }
+/// # fn main() { }
+/// ```
+pub struct TempFile {
+    path: PathBuf,
+    file_name: Option<String>,
+    content_type: Option<ContentType>,
+    len: u64,
+    persisted: bool,
+}
+
+impl TempFile {
+    /// Creates a new, empty temporary file in the system's temporary directory.
+    fn create(file_name: Option<String>, content_type: Option<ContentType>)
+            -> io::Result<(TempFile, File)> {
+        static COUNTER: AtomicUsize = ATOMIC_USIZE_INIT;
+
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0);
+
+        let n = COUNTER.fetch_add(1, Ordering::SeqCst);
+        let path = env::temp_dir().join(format!("rocket-upload-{}-{}", n, nanos));
+        let file = OpenOptions::new().write(true).create_new(true).open(&path)?;
+        let temp_file = TempFile {
+            path: path,
+            file_name: file_name,
+            content_type: content_type,
+            len: 0,
+            persisted: false
+        };
+
+        Ok((temp_file, file))
+    }
+
+    /// Creates a new, empty temporary file in the system's temporary
+    /// directory, for a file that's received in pieces, such as a resumable
+    /// upload, rather than in a multipart form. `file_name` is reduced to its
+    /// final path component, as for uploaded files. Data is added to the file
+    /// via [append](#method.append).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::request::TempFile;
+    ///
+    /// let mut file = TempFile::new(Some("../notes.txt"), None).unwrap();
+    /// assert_eq!(file.file_name(), Some("notes.txt"));
+    ///
+    /// file.append(&b"hello, "[..]).unwrap();
+    /// file.append(&b"world"[..]).unwrap();
+    /// assert_eq!(file.len(), 12);
+    /// ```
+    pub fn new(file_name: Option<&str>, content_type: Option<ContentType>)
+            -> io::Result<TempFile> {
+        let file_name = file_name.and_then(sanitize_file_name);
+        TempFile::create(file_name, content_type).map(|(temp_file, _)| temp_file)
+    }
+
+    /// Appends the data read from `reader` to the end of the file, returning
+    /// the number of bytes appended. Reading stops at the end of `reader`; to
+    /// bound the size of the file, limit `reader` via `Read::take`.
+    ///
+    /// If reading or writing fails partway, as when a client disconnects, the
+    /// data read up until the failure is kept, [len](#method.len) reflects it,
+    /// and the error is returned.
+    pub fn append<R: Read>(&mut self, mut reader: R) -> io::Result<u64> {
+        let mut file = OpenOptions::new().append(true).open(&self.path)?;
+        let result = io::copy(&mut reader, &mut file);
+        let previous = self.len;
+        self.len = file.metadata()?.len();
+        result.map(|_| self.len - previous)
+    }
+
+    /// Returns the path of the temporary file.
+    #[inline(always)]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the name of the file as sent by the client, if any. The name is
+    /// reduced to its final path component, so it contains no path separators,
+    /// and is never `.` or `..`. It is otherwise untrusted.
+    #[inline(always)]
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_ref().map(|name| name.as_str())
+    }
+
+    /// Returns the Content-Type of the file as sent by the client, if any.
+    #[inline(always)]
+    pub fn content_type(&self) -> Option<&ContentType> {
+        self.content_type.as_ref()
+    }
+
+    /// Returns the size of the file in bytes.
+    #[inline(always)]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Opens the temporary file for reading.
+    #[inline(always)]
+    pub fn open(&self) -> io::Result<File> {
+        File::open(&self.path)
+    }
+
+    /// Moves the temporary file to `path`, replacing any file there, so that
+    /// it is kept after `self` is dropped.
+    pub fn persist_to<P: AsRef<Path>>(mut self, path: P) -> io::Result<()> {
+        // Renaming fails across file systems; fall back to copying.
+        if fs::rename(&self.path, path.as_ref()).is_err() {
+            fs::copy(&self.path, path.as_ref())?;
+            let _ = fs::remove_file(&self.path);
+        }
+
+        self.persisted = true;
+        Ok(())
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if !self.persisted {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+impl Debug for TempFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TempFile")
+            .field("path", &self.path)
+            .field("file_name", &self.file_name)
+            .field("content_type", &self.content_type)
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+thread_local! {
+    /// The files uploaded in the multipart form currently being parsed on this
+    /// thread, keyed by their index among the form's files.
+    static UPLOADS: RefCell<HashMap<usize, TempFile>> = RefCell::new(HashMap::new())
+}
+
+/// The prefix of the value given to a file field in the form string parsed by
+/// `MultipartForm`, followed by the index of the file in `UPLOADS`. Text field
+/// values are percent-encoded, so they never contain `%%`: a client can't
+/// make a text field refer to a file.
+const FILE_MARKER: &'static str = "%%";
+
+/// Removes the files registered in `UPLOADS` when dropped, deleting any that
+/// weren't claimed while parsing.
+struct UploadsGuard;
+
+impl Drop for UploadsGuard {
+    fn drop(&mut self) {
+        let unclaimed = UPLOADS.with(|uploads| {
+            ::std::mem::replace(&mut *uploads.borrow_mut(), HashMap::new())
+        });
+
+        drop(unclaimed);
+    }
+}
+
+/// Parses a `TempFile` from the value of a file field in a form being parsed
+/// by [MultipartForm](struct.MultipartForm.html). Each file can be parsed at
+/// most once. If the value isn't that of a file field, returns the value as
+/// the `Err`.
+impl<'v> FromFormValue<'v> for TempFile {
+    type Error = &'v str;
+
+    fn from_form_value(v: &'v str) -> Result<TempFile, &'v str> {
+        if !v.starts_with(FILE_MARKER) {
+            return Err(v);
+        }
+
+        let index = v[FILE_MARKER.len()..].parse::<usize>().map_err(|_| v)?;
+        UPLOADS.with(|uploads| uploads.borrow_mut().remove(&index)).ok_or(v)
+    }
+}
+
+/// A `FromData` type for parsing `FromForm` types from multipart form data
+/// (`multipart/form-data`), as sent by HTML forms with file inputs.
+///
+/// `MultipartForm` is used exactly like [Form](struct.Form.html). The parts of
+/// the form are read in order. Parts without a filename are text fields,
+/// parsed via `FromFormValue` just like the fields of any other form; their
+/// total size is limited by the **forms** limit. Parts with a filename are
+/// files, each streamed to a [TempFile](struct.TempFile.html) and limited by
+/// the **file** limit. The number of parts is limited by the **parts** limit
+/// and the number of files by the **files** limit. The limits are described in
+/// [Limits](/rocket/config/struct.Limits.html). Fields for files should be of
+/// type `TempFile`, or a collection or `Option` of `TempFile`s.
+///
+/// If the request isn't multipart form data, the guard forwards. If the form
+/// is malformed, the guard fails with `400 Bad Request`; if a field exceeds
+/// its limit or the form has too many parts or files, with `413 Payload Too
+/// Large`; and if the structure can't be parsed from the fields, with the
+/// status described for [Form](struct.Form.html): usually `400 Bad Request`,
+/// and `422 Unprocessable Entity` if fields were rejected by validators. Files
+/// are deleted if the guard fails.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::{MultipartForm, TempFile};
+///
+/// #[derive(FromForm)]
+/// struct Album {
+///     title: String,
+///     photos: Vec<TempFile>
+/// }
+///
+/// #[post("/albums", data = "<album>")]
+/// fn create(album: MultipartForm<Album>) -> String {
+///     let album = album.get();
+///     format!("Created '{}' with {} photos.", album.title, album.photos.len())
+/// }
+/// # fn main() { }
+/// ```
+pub struct MultipartForm<'f, T: FromForm<'f> + 'f>(Form<'f, T>);
+
+impl<'f, T: FromForm<'f> + 'f> MultipartForm<'f, T> {
+    /// Immutably borrow the parsed type.
+    #[inline(always)]
+    pub fn get(&'f self) -> &'f T {
+        self.0.get()
+    }
+
+    /// Mutably borrow the parsed type.
+    #[inline(always)]
+    pub fn get_mut(&'f mut self) -> &'f mut T {
+        self.0.get_mut()
+    }
+}
+
+impl<'f, T: FromForm<'f> + 'static> MultipartForm<'f, T> {
+    /// Consume this object and move out the parsed object.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.0.into_inner()
+    }
+}
+
+impl<'f, T: FromForm<'f> + Debug + 'f> Debug for MultipartForm<'f, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+/// The error returned by the [MultipartForm](struct.MultipartForm.html) data
+/// guard when the request's multipart form data couldn't be read or parsed.
+#[derive(Debug)]
+pub enum MultipartError<E> {
+    /// Reading the form data or writing a file failed.
+    Io(io::Error),
+    /// The form data isn't well-formed multipart form data.
+    Malformed(&'static str),
+    /// The field with the given name exceeds its size limit.
+    TooLarge(String),
+    /// The form has more parts or files than the limit with the given name,
+    /// **parts** or **files**, allows.
+    TooMany(&'static str),
+    /// The structure couldn't be parsed from the fields.
+    Parse(E),
+}
+
+impl<E: fmt::Display> fmt::Display for MultipartError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MultipartError::Io(ref e) => write!(f, "failed to read form data: {}", e),
+            MultipartError::Malformed(reason) => write!(f, "malformed form data: {}", reason),
+            MultipartError::TooLarge(ref field) => {
+                write!(f, "field '{}': exceeds the size limit", field)
+            }
+            MultipartError::TooMany(limit) => write!(f, "the form exceeds the {} limit", limit),
+            MultipartError::Parse(ref e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+/// An error encountered while reading a part of multipart form data.
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Malformed(&'static str),
+    /// The part exceeds the limit, the field, in bytes.
+    TooLarge(u64),
+    /// The form has more parts or files than the limit named by the first
+    /// field allows; the second field is the limit.
+    TooMany(&'static str, u64),
+}
+
+impl From<io::Error> for ReadError {
+    fn from(error: io::Error) -> ReadError {
+        ReadError::Io(error)
+    }
+}
+
+impl ReadError {
+    /// Converts `self` into a `MultipartError` for the field `field`.
+    fn into_error<E>(self, field: &str) -> MultipartError<E> {
+        match self {
+            ReadError::Io(e) => MultipartError::Io(e),
+            ReadError::Malformed(reason) => MultipartError::Malformed(reason),
+            ReadError::TooLarge(_) => MultipartError::TooLarge(field.to_string()),
+            ReadError::TooMany(limit, _) => MultipartError::TooMany(limit),
+        }
+    }
+
+    /// Converts `self` into a `FormDataError`.
+    pub fn into_form_error<E>(self) -> FormDataError<E> {
+        match self {
+            ReadError::Io(e) => FormDataError::Io(e),
+            ReadError::Malformed(reason) => FormDataError::Malformed(reason),
+            ReadError::TooLarge(limit) => FormDataError::TooLarge(ByteUnit::new(limit)),
+            ReadError::TooMany(limit, _) => FormDataError::TooMany(limit),
+        }
+    }
+}
+
+/// Returns the index of the first occurrence of `needle` in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Reads the parts of multipart form data from a stream.
+struct MultipartReader<R: Read> {
+    reader: R,
+    buffer: Vec<u8>,
+    delimiter: Vec<u8>,
+}
+
+impl<R: Read> MultipartReader<R> {
+    fn new(reader: R, boundary: &str) -> MultipartReader<R> {
+        // The first delimiter needn't be preceded by a line break, so one is
+        // assumed to be.
+        MultipartReader {
+            reader: reader,
+            buffer: b"\r\n".to_vec(),
+            delimiter: format!("\r\n--{}", boundary).into_bytes(),
+        }
+    }
+
+    /// Reads until the buffer contains at least `n` bytes. Returns `false` if
+    /// the stream ends first.
+    fn fill(&mut self, n: usize) -> io::Result<bool> {
+        let mut chunk = [0; CHUNK_SIZE];
+        while self.buffer.len() < n {
+            match self.reader.read(&mut chunk)? {
+                0 => return Ok(false),
+                read => self.buffer.extend_from_slice(&chunk[..read]),
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Reads up to and past the next occurrence of `delimiter`, writing the
+    /// bytes before it to `sink`. Fails if more than `limit` bytes would be
+    /// written or the stream ends first. Returns the number of bytes written.
+    fn read_until<W: Write>(&mut self, delimiter: &[u8], sink: &mut W, limit: u64)
+            -> Result<u64, ReadError> {
+        let mut written = 0;
+        loop {
+            // Everything but a possible prefix of the delimiter can be written.
+            let (end, found) = match find(&self.buffer, delimiter) {
+                Some(i) => (i, true),
+                None => (self.buffer.len() - min(self.buffer.len(), delimiter.len() - 1), false)
+            };
+
+            written += end as u64;
+            if written > limit {
+                return Err(ReadError::TooLarge(limit));
+            }
+
+            sink.write_all(&self.buffer[..end])?;
+            if found {
+                self.buffer.drain(..(end + delimiter.len()));
+                return Ok(written);
+            }
+
+            self.buffer.drain(..end);
+            let needed = self.buffer.len() + 1;
+            if !self.fill(needed)? {
+                return Err(ReadError::Malformed("the form data ended unexpectedly"));
+            }
+        }
+    }
+
+    /// Skips the preamble and reads the headers of the next part. Returns
+    /// `None` if there are no more parts.
+    fn next_part(&mut self) -> Result<Option<Vec<(String, String)>>, ReadError> {
+        if !self.fill(2)? {
+            return Err(ReadError::Malformed("the form data ended unexpectedly"));
+        }
+
+        if &self.buffer[..2] == b"--" {
+            return Ok(None);
+        }
+
+        // Skip any whitespace after the boundary, then read the headers. The
+        // line break ending the boundary line is kept so that a part without
+        // headers is handled like any other.
+        self.read_until(b"\r\n", &mut io::sink(), HEAD_LIMIT)?;
+        let rest = ::std::mem::replace(&mut self.buffer, b"\r\n".to_vec());
+        self.buffer.extend_from_slice(&rest);
+
+        let mut head = vec![];
+        self.read_until(b"\r\n\r\n", &mut head, HEAD_LIMIT)?;
+        let head = String::from_utf8(head)
+            .map_err(|_| ReadError::Malformed("part headers must be UTF-8"))?;
+
+        let mut headers = vec![];
+        for line in head.split("\r\n").filter(|line| !line.is_empty()) {
+            let mut parts = line.splitn(2, ':');
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(value)) => {
+                    headers.push((name.trim().to_string(), value.trim().to_string()));
+                }
+                _ => return Err(ReadError::Malformed("malformed part header"))
+            }
+        }
+
+        Ok(Some(headers))
+    }
+
+    /// Reads the body of the current part into `sink`, failing if it's larger
+    /// than `limit`.
+    fn read_body<W: Write>(&mut self, sink: &mut W, limit: u64) -> Result<u64, ReadError> {
+        let delimiter = self.delimiter.clone();
+        self.read_until(&delimiter, sink, limit)
+    }
+}
+
+/// Returns the value of the parameter `name` in the header value `value`, such
+/// as the `name` in `form-data; name="file"`, unquoting it if necessary.
+fn header_param(value: &str, name: &str) -> Option<String> {
+    let mut rest = value;
+    while let Some(i) = rest.find(';') {
+        rest = rest[(i + 1)..].trim_left();
+        let eq = match rest.find('=') {
+            Some(eq) => eq,
+            None => return None
+        };
+
+        let (key, after) = (rest[..eq].trim(), &rest[(eq + 1)..]);
+        let (param, remaining) = if after.starts_with('"') {
+            let mut param = String::new();
+            let mut chars = after[1..].char_indices();
+            let mut end = None;
+            while let Some((j, c)) = chars.next() {
+                match c {
+                    '\\' => if let Some((_, c)) = chars.next() { param.push(c) },
+                    '"' => { end = Some(j + 2); break; }
+                    c => param.push(c)
+                }
+            }
+
+            match end {
+                Some(end) => (param, &after[end..]),
+                None => return None
+            }
+        } else {
+            let end = after.find(';').unwrap_or(after.len());
+            (after[..end].trim().to_string(), &after[end..])
+        };
+
+        if key.eq_ignore_ascii_case(name) {
+            return Some(param);
+        }
+
+        rest = remaining;
+    }
+
+    None
+}
+
+/// Reduces the filename `name` sent by a client to its final path component.
+fn sanitize_file_name(name: &str) -> Option<String> {
+    let name = name.rsplit(|c| c == '/' || c == '\\').next().unwrap_or("").trim();
+    match name {
+        "" | "." | ".." => None,
+        name => Some(name.to_string())
+    }
+}
+
+/// Appends `string`, percent-encoded, to `out`. Brackets and periods are kept
+/// as is so that field names for nested forms and collections are preserved.
+fn encode_into(string: &str, out: &mut String) {
+    for &b in string.as_bytes() {
+        match b {
+            b'a'...b'z' | b'A'...b'Z' | b'0'...b'9' => out.push(b as char),
+            b'-' | b'_' | b'.' | b'~' | b'[' | b']' => out.push(b as char),
+            _ => out.push_str(&format!("%{:02X}", b))
+        }
+    }
+}
+
+/// The text fields of multipart form data, as a URL-encoded form string, and
+/// its uploaded files, each with the name of its field.
+pub type Parts = (String, Vec<(String, TempFile)>);
+
+/// The limits that apply while reading multipart form data.
+#[derive(Debug, Clone, Copy)]
+struct PartLimits {
+    /// The total size of the text fields, in bytes.
+    text: u64,
+    /// The size of each file, in bytes.
+    file: u64,
+    /// The number of parts.
+    parts: u64,
+    /// The number of files.
+    files: u64,
+}
+
+/// Reads the parts of the multipart form data in `reader`, delimited by
+/// `boundary`, within `limits`. Text fields are URL-encoded into a form string.
+/// Files are stored in temporary files if `keep_files` is `true` and skipped,
+/// without being stored, otherwise. On error, returns the name of the field
+/// whose part was being read along with the error.
+fn read_parts<R: Read>(reader: R, boundary: &str, limits: PartLimits, keep_files: bool)
+        -> Result<Parts, (ReadError, String)> {
+    let mut reader = MultipartReader::new(reader, boundary);
+    reader.read_body(&mut io::sink(), HEAD_LIMIT).map_err(|e| (e, "<preamble>".into()))?;
+
+    let mut form_string = String::new();
+    let mut files = vec![];
+    let (mut text_len, mut parts) = (0, 0);
+    while let Some(headers) = reader.next_part().map_err(|e| (e, "<headers>".into()))? {
+        parts += 1;
+        if parts > limits.parts {
+            return Err((ReadError::TooMany("parts", limits.parts), "<headers>".into()));
+        }
+
+        let (mut name, mut file_name, mut content_type) = (None, None, None);
+        for (key, value) in headers {
+            if key.eq_ignore_ascii_case("Content-Disposition") {
+                name = header_param(&value, "name");
+                file_name = header_param(&value, "filename");
+            } else if key.eq_ignore_ascii_case("Content-Type") {
+                content_type = value.parse::<ContentType>().ok();
+            }
+        }
+
+        let name = match name {
+            Some(name) => name,
+            None => {
+                let error = ReadError::Malformed("a part has no field name");
+                return Err((error, "<headers>".into()));
+            }
+        };
+
+        if file_name.is_some() && !keep_files {
+            reader.read_body(&mut io::sink(), limits.file).map_err(|e| (e, name))?;
+            continue;
+        }
+
+        if let Some(file_name) = file_name {
+            if files.len() as u64 >= limits.files {
+                return Err((ReadError::TooMany("files", limits.files), name));
+            }
+
+            let file_name = sanitize_file_name(&file_name);
+            let (mut temp_file, mut file) = TempFile::create(file_name, content_type)
+                .map_err(|e| (ReadError::Io(e), name.clone()))?;
+
+            temp_file.len = reader.read_body(&mut file, limits.file)
+                .map_err(|e| (e, name.clone()))?;
+
+            files.push((name, temp_file));
+            continue;
+        }
+
+        // The text fields share the limit, so the error reports all of it.
+        let mut value = vec![];
+        let limit = limits.text - min(limits.text, text_len);
+        text_len += reader.read_body(&mut value, limit).map_err(|e| match e {
+            ReadError::TooLarge(_) => (ReadError::TooLarge(limits.text), name.clone()),
+            e => (e, name.clone())
+        })?;
+
+        let value = match String::from_utf8(value) {
+            Ok(value) => value,
+            Err(_) => return Err((ReadError::Malformed("text fields must be UTF-8"), name))
+        };
+
+        if !form_string.is_empty() {
+            form_string.push('&');
+        }
+
+        encode_into(&name, &mut form_string);
+        form_string.push('=');
+        encode_into(&value, &mut form_string);
+    }
+
+    Ok((form_string, files))
+}
+
+/// Reads the parts of the multipart form data `data` of `request`, whose
+/// content type must be `multipart/form-data`, within the **forms**,
+/// **file**, **parts**, and **files** limits. Files are only stored if
+/// `keep_files` is `true`.
+pub fn read_form_data(request: &Request, data: Data, keep_files: bool)
+        -> Result<Parts, (ReadError, String)> {
+    let content_type = request.content_type();
+    let boundary = content_type.params.as_ref()
+        .and_then(|params| header_param(&format!(";{}", params), "boundary"));
+
+    match boundary {
+        Some(ref boundary) if !boundary.is_empty() && boundary.len() <= 70 => {
+            let limits = request.limits();
+            let limits = PartLimits {
+                text: limits.get("forms").as_u64(),
+                file: limits.get("file").as_u64(),
+                parts: limits.get("parts").as_u64(),
+                files: limits.get("files").as_u64(),
+            };
+
+            read_parts(data.open(), boundary, limits, keep_files)
+        }
+        _ => {
+            let error = ReadError::Malformed("the content type has no valid boundary");
+            Err((error, "<headers>".into()))
+        }
+    }
+}
+
+/// Parses a `MultipartForm` from incoming multipart form data.
+impl<'f, T: FromForm<'f>> FromData for MultipartForm<'f, T> where T::Error: Debug + 'static {
+    type Error = MultipartError<T::Error>;
+
+    fn from_data(request: &Request, data: Data) -> data::Outcome<Self, Self::Error> {
+        if !request.content_type().is_data_form() {
+            warn_!("Form data does not have multipart form content type.");
+            return Forward(data);
+        }
+
+        // Files not claimed while parsing are deleted by the guard. Each file
+        // is registered in `UPLOADS` under its index, and its field's value is
+        // `FILE_MARKER` followed by the index, so that a `TempFile` field can
+        // claim it.
+        let _guard = UploadsGuard;
+        let result = read_form_data(request, data, true)
+            .map_err(|(e, field)| e.into_error(&field))
+            .and_then(|(mut form_string, files)| {
+                for (i, (name, temp_file)) in files.into_iter().enumerate() {
+                    UPLOADS.with(|uploads| uploads.borrow_mut().insert(i, temp_file));
+
+                    if !form_string.is_empty() {
+                        form_string.push('&');
+                    }
+
+                    encode_into(&name, &mut form_string);
+                    form_string.push('=');
+                    form_string.push_str(FILE_MARKER);
+                    form_string.push_str(&i.to_string());
+                }
+
+                Form::new(form_string, true).map_err(|(_, e)| MultipartError::Parse(e))
+            });
+
+        match result {
+            Ok(form) => Success(MultipartForm(form)),
+            Err(error) => {
+                error_!("Failed to parse multipart form: {:?}", error);
+                let status = match error {
+                    MultipartError::Io(_) => Status::InternalServerError,
+                    MultipartError::TooLarge(_) | MultipartError::TooMany(_) => {
+                        Status::PayloadTooLarge
+                    }
+                    MultipartError::Parse(ref e) => e.form_status(),
+                    _ => Status::BadRequest
+                };
+
+                Failure((status, error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+    use super::{MultipartReader, ReadError, PartLimits, header_param, sanitize_file_name};
+
+    fn read_parts(body: &str, text: u64, file: u64)
+            -> Result<super::Parts, (ReadError, String)> {
+        let limits = PartLimits { text: text, file: file, parts: 16, files: 16 };
+        super::read_parts(body.as_bytes(), "XyZ", limits, true)
+    }
+
+    /// A reader that returns at most one byte per read.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> ::std::io::Result<usize> {
+            if self.0.is_empty() || buf.is_empty() {
+                return Ok(0);
+            }
+
+            buf[0] = self.0[0];
+            self.0 = &self.0[1..];
+            Ok(1)
+        }
+    }
+
+    const BODY: &'static str = "preamble\r\n\
+        --XyZ\r\n\
+        Content-Disposition: form-data; name=\"title\"\r\n\
+        \r\n\
+        Hello & welcome\r\n\
+        --XyZ  \r\n\
+        Content-Disposition: form-data; name=\"file\"; filename=\"C:\\\\docs\\\\a.txt\"\r\n\
+        Content-Type: text/plain\r\n\
+        \r\n\
+        line one\r\n--XY\r\nline two\r\n\
+        --XyZ--\r\n\
+        epilogue";
+
+    #[test]
+    fn test_reader() {
+        for trickle in &[false, true] {
+            let mut reader = if *trickle {
+                MultipartReader::new(Box::new(Trickle(BODY.as_bytes())) as Box<Read>, "XyZ")
+            } else {
+                MultipartReader::new(Box::new(BODY.as_bytes()) as Box<Read>, "XyZ")
+            };
+
+            reader.read_body(&mut ::std::io::sink(), 1024).unwrap();
+            let headers = reader.next_part().unwrap().unwrap();
+            assert_eq!(headers.len(), 1);
+            let mut body = vec![];
+            reader.read_body(&mut body, 1024).unwrap();
+            assert_eq!(body, b"Hello & welcome");
+
+            let headers = reader.next_part().unwrap().unwrap();
+            assert_eq!(headers[1], ("Content-Type".to_string(), "text/plain".to_string()));
+            let mut body = vec![];
+            reader.read_body(&mut body, 1024).unwrap();
+            assert_eq!(body, b"line one\r\n--XY\r\nline two");
+
+            assert!(reader.next_part().unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn test_limits_and_truncation() {
+        let mut reader = MultipartReader::new(BODY.as_bytes(), "XyZ");
+        reader.read_body(&mut ::std::io::sink(), 1024).unwrap();
+        reader.next_part().unwrap();
+        match reader.read_body(&mut vec![], 4) {
+            Err(ReadError::TooLarge(4)) => { /* expected */ }
+            other => panic!("expected TooLarge, got {:?}", other)
+        }
+
+        let truncated = &BODY[..70];
+        let mut reader = MultipartReader::new(truncated.as_bytes(), "XyZ");
+        reader.read_body(&mut ::std::io::sink(), 1024).unwrap();
+        reader.next_part().unwrap();
+        match reader.read_body(&mut vec![], 1024) {
+            Err(ReadError::Malformed(_)) => { /* expected */ }
+            other => panic!("expected Malformed, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn test_read_parts() {
+        let (form_string, mut files) = read_parts(BODY, 1024, 1024).unwrap();
+        assert_eq!(form_string, "title=Hello%20%26%20welcome");
+        assert_eq!(files.len(), 1);
+
+        let (name, file) = files.remove(0);
+        assert_eq!(name, "file");
+        assert_eq!(file.file_name(), Some("a.txt"));
+        assert_eq!(file.len(), 24);
+
+        let mut contents = String::new();
+        file.open().unwrap().read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "line one\r\n--XY\r\nline two");
+
+        let path = file.path().to_path_buf();
+        drop(file);
+        assert!(!path.exists());
+
+        match read_parts(BODY, 8, 1024) {
+            Err((ReadError::TooLarge(8), ref field)) if field == "title" => { /* expected */ }
+            other => panic!("expected TooLarge, got {:?}", other)
+        }
+
+        match read_parts(BODY, 1024, 8) {
+            Err((ReadError::TooLarge(8), ref field)) if field == "file" => { /* expected */ }
+            other => panic!("expected TooLarge, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn test_part_and_file_counts() {
+        let limits = PartLimits { text: 1024, file: 1024, parts: 1, files: 16 };
+        match super::read_parts(BODY.as_bytes(), "XyZ", limits, true) {
+            Err((ReadError::TooMany("parts", 1), _)) => { /* expected */ }
+            other => panic!("expected TooMany, got {:?}", other)
+        }
+
+        let limits = PartLimits { text: 1024, file: 1024, parts: 2, files: 0 };
+        match super::read_parts(BODY.as_bytes(), "XyZ", limits, true) {
+            Err((ReadError::TooMany("files", 0), ref field)) if field == "file" => { /* ok */ }
+            other => panic!("expected TooMany, got {:?}", other)
+        }
+
+        let limits = PartLimits { text: 1024, file: 1024, parts: 2, files: 1 };
+        assert!(super::read_parts(BODY.as_bytes(), "XyZ", limits, true).is_ok());
+    }
+
+    #[test]
+    fn test_skipped_files() {
+        let limits = PartLimits { text: 1024, file: 1024, parts: 16, files: 0 };
+        let (form_string, files) = super::read_parts(BODY.as_bytes(), "XyZ", limits, false)
+            .unwrap();
+
+        assert_eq!(form_string, "title=Hello%20%26%20welcome");
+        assert!(files.is_empty());
+
+        let limits = PartLimits { text: 1024, file: 8, parts: 16, files: 0 };
+        match super::read_parts(BODY.as_bytes(), "XyZ", limits, false) {
+            Err((ReadError::TooLarge(8), ref field)) if field == "file" => { /* expected */ }
+            other => panic!("expected TooLarge, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn test_header_params() {
+        let value = r#"form-data; name="a \"b\""; filename=c.txt"#;
+        assert_eq!(header_param(value, "name"), Some("a \"b\"".to_string()));
+        assert_eq!(header_param(value, "FILENAME"), Some("c.txt".to_string()));
+        assert_eq!(header_param(value, "size"), None);
+        assert_eq!(header_param("form-data", "name"), None);
+        assert_eq!(header_param(";boundary=\"a;b\"", "boundary"), Some("a;b".to_string()));
+    }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("a.txt"), Some("a.txt".to_string()));
+        assert_eq!(sanitize_file_name("../../etc/passwd"), Some("passwd".to_string()));
+        assert_eq!(sanitize_file_name("C:\\docs\\a.txt"), Some("a.txt".to_string()));
+        assert_eq!(sanitize_file_name(".."), None);
+        assert_eq!(sanitize_file_name("dir/"), None);
+        assert_eq!(sanitize_file_name(""), None);
+    }
+}
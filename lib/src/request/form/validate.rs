@@ -0,0 +1,256 @@
+//! Validators for the fields of forms deriving `FromForm`.
+//!
+//! A field of a structure deriving `FromForm` is validated by annotating it
+//! with `#[form(validate = "expr")]`, where `expr` is an expression evaluating
+//! to a [Validator](trait.Validator.html) of the field's type. After the field
+//! parses, the validator is called with its value, and the error message it
+//! returns, if any, becomes a [FormError::Invalid](../enum.FormError.html) for
+//! the field. A field may have any number of validators.
+//!
+//! The validators in this module, [range](fn.range.html) and
+//! [len](fn.len.html), are in scope in the expression. Any function or closure
+//! taking a reference to the field's value and returning a `Result<(),
+//! String>` is a validator as well.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #![feature(plugin, custom_derive)]
+//! # #![plugin(rocket_codegen)]
+//! # extern crate rocket;
+//! use rocket::request::{FromForm, FormError};
+//!
+//! fn is_even(n: &u8) -> Result<(), String> {
+//!     match n % 2 {
+//!         0 => Ok(()),
+//!         _ => Err("must be even".to_string())
+//!     }
+//! }
+//!
+//! #[derive(FromForm)]
+//! struct Signup {
+//!     #[form(validate = "len(1..32)")]
+//!     name: String,
+//!     #[form(validate = "range(18..150)", validate = "is_even")]
+//!     age: u8
+//! }
+//!
+//! # fn main() {
+//! assert!(Signup::from_form_string("name=Bob&age=42").is_ok());
+//! assert_eq!(Signup::from_form_string("name=Bob&age=43").err(), Some(FormError::Invalid {
+//!     field: "age".to_string(),
+//!     message: "must be even".to_string()
+//! }));
+//! # }
+//! ```
+
+use std::fmt::Display;
+use std::ops::Range;
+
+use super::{FromForm, FromFormItems, FormItems, FormErrors};
+
+/// Trait implemented by validators of values of type `T`.
+///
+/// This trait is implemented for all functions and closures of type `Fn(&T) ->
+/// Result<(), String>`, so a custom validator is usually just a function.
+pub trait Validator<T: ?Sized> {
+    /// Checks `value`, returning a message describing why it's invalid if it
+    /// is.
+    fn validate(&self, value: &T) -> Result<(), String>;
+}
+
+impl<T: ?Sized, F: Fn(&T) -> Result<(), String>> Validator<T> for F {
+    #[inline(always)]
+    fn validate(&self, value: &T) -> Result<(), String> {
+        self(value)
+    }
+}
+
+/// A validator of values in a range. See [range](fn.range.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct InRange<T>(Range<T>);
+
+/// Returns a validator checking that a value is in `range`: at least its start
+/// and less than its end. A missing optional value is valid.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::request::validate::{range, Validator};
+///
+/// assert!(range(1..10).validate(&1).is_ok());
+/// assert!(range(1..10).validate(&10).is_err());
+/// assert!(range(1..10).validate(&None).is_ok());
+/// ```
+pub fn range<T: PartialOrd + Display>(range: Range<T>) -> InRange<T> {
+    InRange(range)
+}
+
+impl<T: PartialOrd + Display> Validator<T> for InRange<T> {
+    fn validate(&self, value: &T) -> Result<(), String> {
+        if *value < self.0.start || *value >= self.0.end {
+            return Err(format!("must be at least {} and less than {}",
+                               self.0.start, self.0.end));
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: PartialOrd + Display> Validator<Option<T>> for InRange<T> {
+    fn validate(&self, value: &Option<T>) -> Result<(), String> {
+        value.as_ref().map_or(Ok(()), |v| self.validate(v))
+    }
+}
+
+/// A validator of lengths in a range. See [len](fn.len.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Len(Range<usize>);
+
+/// Returns a validator checking that the length of a value is in `range`: at
+/// least its start and less than its end. The length of a string is its
+/// number of characters, and that of a vector its number of elements. A
+/// missing optional value is valid.
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::request::validate::{len, Validator};
+///
+/// assert!(len(1..4).validate(&"abc").is_ok());
+/// assert!(len(1..4).validate(&String::new()).is_err());
+/// assert!(len(1..4).validate(&vec![1, 2, 3, 4]).is_err());
+/// ```
+pub fn len(range: Range<usize>) -> Len {
+    Len(range)
+}
+
+impl Len {
+    fn check(&self, len: usize) -> Result<(), String> {
+        if len < self.0.start || len >= self.0.end {
+            return Err(format!("length must be at least {} and less than {}",
+                               self.0.start, self.0.end));
+        }
+
+        Ok(())
+    }
+}
+
+impl Validator<str> for Len {
+    fn validate(&self, value: &str) -> Result<(), String> {
+        self.check(value.chars().count())
+    }
+}
+
+impl<'a> Validator<&'a str> for Len {
+    fn validate(&self, value: &&'a str) -> Result<(), String> {
+        self.check(value.chars().count())
+    }
+}
+
+impl Validator<String> for Len {
+    fn validate(&self, value: &String) -> Result<(), String> {
+        self.check(value.chars().count())
+    }
+}
+
+impl<T> Validator<Vec<T>> for Len {
+    fn validate(&self, value: &Vec<T>) -> Result<(), String> {
+        self.check(value.len())
+    }
+}
+
+impl<T> Validator<Option<T>> for Len where Len: Validator<T> {
+    fn validate(&self, value: &Option<T>) -> Result<(), String> {
+        value.as_ref().map_or(Ok(()), |v| self.validate(v))
+    }
+}
+
+/// A form deriving `FromForm` that's parsed with every error encountered,
+/// instead of just the first.
+///
+/// Parsing a `Validated<T>` never fails: it's `Ok` with the parsed `T` or
+/// `Err` with the [FormErrors](struct.FormErrors.html) for every field that
+/// failed to parse or validate. This allows a handler to report all of the
+/// problems with a submitted form at once, as when re-rendering the form with
+/// a message next to each invalid field.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::{Form, Validated};
+///
+/// #[derive(FromForm)]
+/// struct Signup {
+///     #[form(validate = "len(1..32)")]
+///     name: String,
+///     #[form(validate = "range(18..150)")]
+///     age: u8
+/// }
+///
+/// #[post("/signup", data = "<signup>")]
+/// fn signup(signup: Form<Validated<Signup>>) -> String {
+///     match signup.get().0 {
+///         Ok(ref signup) => format!("Welcome, {}!", signup.name),
+///         Err(ref errors) => {
+///             let name_errors = errors.for_field("name");
+///             format!("{} errors, {} with the name", errors.len(), name_errors.len())
+///         }
+///     }
+/// }
+/// # fn main() { }
+/// ```
+#[derive(Debug)]
+pub struct Validated<T>(pub Result<T, FormErrors>);
+
+impl<T> Validated<T> {
+    /// Consumes `self`, returning the parsed form or the errors.
+    #[inline(always)]
+    pub fn into_result(self) -> Result<T, FormErrors> {
+        self.0
+    }
+}
+
+impl<'f, T: FromFormItems<'f>> FromForm<'f> for Validated<T> {
+    type Error = FormErrors;
+
+    fn from_form_string(form_string: &'f str) -> Result<Self, Self::Error> {
+        let items = FormItems(form_string).collect();
+        Ok(Validated(T::from_form_items(items, true, T::form_syntax())))
+    }
+
+    fn from_form_string_lenient(form_string: &'f str) -> Result<Self, Self::Error> {
+        let items = FormItems(form_string).collect();
+        Ok(Validated(T::from_form_items(items, false, T::form_syntax())))
+    }
+
+    fn default_form() -> Option<Self> {
+        Validated::from_form_string("").ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{range, len, Validator};
+
+    #[test]
+    fn test_range() {
+        assert!(range(-1..1).validate(&0).is_ok());
+        assert!(range(0.5..1.0).validate(&0.5).is_ok());
+        assert_eq!(range(1..3).validate(&3),
+                   Err("must be at least 1 and less than 3".to_string()));
+        assert!(range(1..3).validate(&Some(0)).is_err());
+    }
+
+    #[test]
+    fn test_len() {
+        assert!(len(2..3).validate("éé").is_ok());
+        assert!(len(0..1).validate(&Some(String::new())).is_ok());
+        assert!(len(1..2).validate(&Vec::<u8>::new()).is_err());
+        assert_eq!(len(1..3).validate(&"abc"),
+                   Err("length must be at least 1 and less than 3".to_string()));
+    }
+}
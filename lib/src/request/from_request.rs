@@ -1,11 +1,14 @@
 use std::fmt::Debug;
+use std::net::SocketAddr;
 
 use outcome::{self, IntoOutcome};
 use request::Request;
 use outcome::Outcome::*;
 
-use http::{Status, ContentType, Method, Cookies};
+use http::{Status, ContentType, Method, Cookies, Host, Accept, AcceptLanguage, Authorization};
+use http::Link;
 use http::uri::URI;
+use config::{Environment, Limits};
 
 /// Type alias for the `Outcome` of a `FromRequest` conversion.
 pub type Outcome<S, E> = outcome::Outcome<S, (Status, E), ()>;
@@ -118,6 +121,10 @@ impl<S, E> IntoOutcome<S, (Status, E), ()> for Result<S, E> {
 /// ```
 pub trait FromRequest<'a, 'r>: Sized {
     /// The associated error to be returned if derivation fails.
+    ///
+    /// When a route's guard fails, the error is recorded as the request's
+    /// [guard_failure](/rocket/struct.Request.html#method.guard_failure), so
+    /// the errors of guards used by routes must also be `'static`.
     type Error: Debug;
 
     /// Derives an instance of `Self` from the incoming request metadata.
@@ -153,6 +160,14 @@ impl<'a, 'r> FromRequest<'a, 'r> for &'a Cookies {
     }
 }
 
+impl<'a, 'r> FromRequest<'a, 'r> for &'a Limits {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        Success(request.limits())
+    }
+}
+
 impl<'a, 'r> FromRequest<'a, 'r> for ContentType {
     type Error = ();
 
@@ -161,6 +176,90 @@ impl<'a, 'r> FromRequest<'a, 'r> for ContentType {
     }
 }
 
+impl<'a, 'r> FromRequest<'a, 'r> for Host<'a> {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        match request.host() {
+            Some(host) => Success(host),
+            None => Failure((Status::BadRequest, ()))
+        }
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for SocketAddr {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        match request.remote() {
+            Some(address) => Success(address),
+            None => Forward(())
+        }
+    }
+}
+
+/// Retrieves the environment the application is running in. If the request
+/// isn't being handled by an application, as when it was created directly, the
+/// environment is determined by the `ROCKET_ENV` environment variable. Fails
+/// with a status of `500 Internal Server Error` if that variable is invalid.
+impl<'a, 'r> FromRequest<'a, 'r> for Environment {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        if let Some(config) = request.config() {
+            return Success(config.env);
+        }
+
+        match Environment::active() {
+            Ok(env) => Success(env),
+            Err(_) => Failure((Status::InternalServerError, ()))
+        }
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for AcceptLanguage {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        request.vary_on("Accept-Language");
+        let values: Vec<_> = request.headers().get("Accept-Language").collect();
+        Success(AcceptLanguage::parse_lossy(&values.join(",")))
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for Accept {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        request.vary_on("Accept");
+        Success(request.accept().cloned().unwrap_or_else(Accept::any))
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for Link {
+    type Error = &'static str;
+
+    fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        let values: Vec<_> = request.headers().get("Link").collect();
+        match values.join(",").parse() {
+            Ok(link) => Success(link),
+            Err(e) => Failure((Status::BadRequest, e))
+        }
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for Authorization {
+    type Error = &'static str;
+
+    fn from_request(request: &'a Request<'r>) -> Outcome<Self, Self::Error> {
+        match request.headers().get_one("Authorization").map(|value| value.parse()) {
+            Some(Ok(auth)) => Success(auth),
+            Some(Err(e)) => Failure((Status::BadRequest, e)),
+            None => Failure((Status::Unauthorized, "Missing Authorization header."))
+        }
+    }
+}
+
 impl<'a, 'r, T: FromRequest<'a, 'r>> FromRequest<'a, 'r> for Result<T, T::Error> {
     type Error = ();
 
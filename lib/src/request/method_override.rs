@@ -0,0 +1,196 @@
+use std::str::from_utf8_unchecked;
+use std::cmp::min;
+
+use request::{Request, FormItems};
+use data::Data;
+use http::Method;
+use http::Method::*;
+
+/// The header carrying a method override.
+const HEADER: &'static str = "X-HTTP-Method-Override";
+
+/// A policy for reinterpreting `POST` requests as requests with another method
+/// during routing.
+///
+/// Browsers only submit `GET` and `POST` forms, and some clients and proxies
+/// only pass those methods through. A `POST` request can ask to be routed as a
+/// `PUT`, `DELETE`, or `PATCH` request in one of two ways:
+///
+///   * With a form body whose _first_ field is `_method`, as in
+///     `_method=delete&id=10`. This is enabled by default.
+///   * With an `X-HTTP-Method-Override` header, as in
+///     `X-HTTP-Method-Override: PATCH`. This is opt-in.
+///
+/// When both are present, the form field wins. Requests with methods other
+/// than `POST` and overrides naming other methods are left untouched, so an
+/// override can't turn a `POST` into a `GET`. A policy is installed via
+/// [Rocket::method_override](/rocket/struct.Rocket.html#method.method_override).
+///
+/// # Example
+///
+/// Honor the `X-HTTP-Method-Override` header as well as the `_method` field:
+///
+/// ```rust
+/// use rocket::request::MethodOverride;
+///
+/// # if false {
+/// rocket::ignite()
+///     .method_override(MethodOverride::new().header(true))
+///     .launch()
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MethodOverride {
+    form: bool,
+    header: bool,
+}
+
+impl MethodOverride {
+    /// Returns the default policy: the `_method` form field is honored, and
+    /// the `X-HTTP-Method-Override` header is not.
+    #[inline]
+    pub fn new() -> MethodOverride {
+        MethodOverride { form: true, header: false }
+    }
+
+    /// Returns a policy under which methods are never overridden.
+    #[inline]
+    pub fn disabled() -> MethodOverride {
+        MethodOverride { form: false, header: false }
+    }
+
+    /// Sets whether the `_method` form field is honored.
+    #[inline]
+    pub fn form(mut self, enabled: bool) -> Self {
+        self.form = enabled;
+        self
+    }
+
+    /// Sets whether the `X-HTTP-Method-Override` header is honored.
+    #[inline]
+    pub fn header(mut self, enabled: bool) -> Self {
+        self.header = enabled;
+        self
+    }
+
+    /// Returns the method `request`, with body `data`, asks to be routed as
+    /// under this policy, if any. Only the peeked portion of `data` is
+    /// inspected; the body isn't consumed. This should only be used
+    /// internally by `Rocket`.
+    #[doc(hidden)]
+    pub fn resolve(&self, request: &Request, data: &Data) -> Option<Method> {
+        if request.method() != Post {
+            return None;
+        }
+
+        let from_form = if self.form { form_method(request, data) } else { None };
+        from_form.or_else(|| {
+            if self.header {
+                request.headers().get_one(HEADER).and_then(|value| allowed(value.trim()))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl MethodOverride {
+    /// Returns `true` if `request` could be routed under another method once
+    /// its body is read. This should only be used internally by `Rocket`.
+    #[doc(hidden)]
+    pub fn may_override(&self, request: &Request) -> bool {
+        request.method() == Post && (self.form || self.header)
+    }
+}
+
+impl Default for MethodOverride {
+    #[inline]
+    fn default() -> MethodOverride {
+        MethodOverride::new()
+    }
+}
+
+/// Returns the method named by `value` if it's one a `POST` may be overridden
+/// to.
+fn allowed(value: &str) -> Option<Method> {
+    match value.parse::<Method>() {
+        Ok(method @ Put) | Ok(method @ Delete) | Ok(method @ Patch) => Some(method),
+        _ => None
+    }
+}
+
+/// Returns the method named by the `_method` field of a form body, if the
+/// field comes first. Keep this in-sync with derive_form when preprocessing
+/// form fields.
+fn form_method(request: &Request, data: &Data) -> Option<Method> {
+    let data_len = data.peek().len();
+    let (min_len, max_len) = ("_method=put".len(), "_method=delete".len());
+    if !request.content_type().is_form() || data_len < min_len {
+        return None;
+    }
+
+    let form = unsafe {
+        from_utf8_unchecked(&data.peek()[..min(data_len, max_len)])
+    };
+
+    match FormItems(form).next() {
+        Some(("_method", value)) => allowed(value),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MethodOverride;
+
+    use request::Request;
+    use data::Data;
+    use http::{Header, ContentType};
+    use http::Method::*;
+
+    fn form(method: ::http::Method, body: &str) -> (Request<'static>, Data) {
+        let mut request = Request::new(method, "/");
+        request.add_header(ContentType::Form);
+        (request, Data::new(body.as_bytes().to_vec()))
+    }
+
+    #[test]
+    fn test_form_field() {
+        let policy = MethodOverride::new();
+        let (request, data) = form(Post, "_method=delete&id=1");
+        assert_eq!(policy.resolve(&request, &data), Some(Delete));
+
+        let (request, data) = form(Post, "_method=PATCH");
+        assert_eq!(policy.resolve(&request, &data), Some(Patch));
+
+        let (request, data) = form(Post, "_method=get&id=1");
+        assert_eq!(policy.resolve(&request, &data), None);
+
+        let (request, data) = form(Post, "id=1&_method=put");
+        assert_eq!(policy.resolve(&request, &data), None);
+
+        let (request, data) = form(Put, "_method=delete");
+        assert_eq!(policy.resolve(&request, &data), None);
+
+        let (request, data) = form(Post, "_method=delete");
+        assert_eq!(MethodOverride::disabled().resolve(&request, &data), None);
+    }
+
+    #[test]
+    fn test_header() {
+        let mut request = Request::new(Post, "/");
+        request.add_header(Header::new("X-HTTP-Method-Override", "put"));
+        let data = Data::new(vec![]);
+
+        assert_eq!(MethodOverride::new().resolve(&request, &data), None);
+        assert_eq!(MethodOverride::new().header(true).resolve(&request, &data), Some(Put));
+
+        request.replace_header(Header::new("X-HTTP-Method-Override", "CONNECT"));
+        assert_eq!(MethodOverride::new().header(true).resolve(&request, &data), None);
+
+        // The form field takes precedence over the header.
+        let (mut request, data) = form(Post, "_method=patch");
+        request.add_header(Header::new("X-HTTP-Method-Override", "DELETE"));
+        assert_eq!(MethodOverride::new().header(true).resolve(&request, &data), Some(Patch));
+    }
+}
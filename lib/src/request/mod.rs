@@ -4,11 +4,36 @@ mod request;
 mod param;
 mod form;
 mod from_request;
+mod failure;
+mod client_info;
+mod verified;
+mod query;
+mod connection;
+mod method_override;
+mod extension;
+mod basic_auth;
+mod bearer;
+#[cfg(feature = "chrono")] mod datetime;
 
 pub use self::request::Request;
 pub use self::from_request::{FromRequest, Outcome};
-pub use self::param::{FromParam, FromSegments};
-pub use self::form::{Form, FromForm, FromFormValue, FormItems};
+pub use self::failure::GuardFailure;
+pub use self::client_info::ClientInfo;
+pub use self::verified::{Verified, VerifyError};
+pub use self::query::{Query, FromQuery};
+pub use self::connection::Connection;
+pub use self::method_override::MethodOverride;
+pub use self::extension::Extension;
+pub use self::basic_auth::BasicAuth;
+pub use self::bearer::{BearerToken, BearerError, TokenValidator};
+pub use self::param::{FromParam, FromSegments, SegmentError};
+pub use self::form::{Form, LenientForm, FromForm, FromFormValue, FormItems};
+pub use self::form::{FormError, FormErrors, FormDataError, FormSyntax, Validated};
+pub use self::form::validate;
+pub use self::form::{MultipartForm, MultipartError, TempFile};
+#[doc(hidden)] pub use self::form::{FromFormItems, from_form_field, form_field_default};
+#[doc(hidden)] pub use self::form::{FormField, assert_form_field};
+#[doc(hidden)] pub use self::form::split_form_key;
 
 /// Type alias to retrieve flash messages from a request.
 pub type FlashMessage = ::response::Flash<()>;
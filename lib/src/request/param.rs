@@ -1,8 +1,13 @@
 use std::str::{Utf8Error, FromStr};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6, SocketAddr};
 use std::path::PathBuf;
-use std::fmt::Debug;
+use std::ascii::AsciiExt;
+use std::fmt::{self, Debug};
 
+#[cfg(feature = "uuid")] use uuid::Uuid;
+#[cfg(feature = "bigdecimal")] use bigdecimal::BigDecimal;
+
+use http::RawStr;
 use http::uri::{URI, Segments};
 
 /// Trait to convert a dynamic path segment string to a concrete value.
@@ -80,12 +85,38 @@ use http::uri::{URI, Segments};
 ///     type returns successfully. Otherwise, the raw path segment is returned
 ///     in the `Err` value.
 ///
+///   * **Uuid** _with the `uuid` feature_
+///
+///     A value is parsed successfully if it is a hyphenated or simple UUID.
+///     Otherwise, the raw path segment is returned in the `Err` value.
+///
+///   * **BigDecimal** _with the `bigdecimal` feature_
+///
+///     A value is parsed successfully if it is a decimal number, such as
+///     `-12.50`, which is represented exactly. Otherwise, the raw path segment
+///     is returned in the `Err` value.
+///
+///   * **NaiveDate, NaiveTime, NaiveDateTime, DateTime&lt;FixedOffset>,
+///     DateTime&lt;UTC>** _with the `chrono` feature_
+///
+///     The path segment is percent decoded and parsed as a date (`YYYY-MM-DD`),
+///     a time (`HH:MM` or `HH:MM:SS`), a date and time separated by `T`, or an
+///     RFC 3339 date and time with an offset, respectively. If parsing fails,
+///     the raw path segment is returned in the `Err` value.
+///
 ///   * **str**
 ///
 ///     _This implementation always returns successfully._
 ///
 ///     The path segment is passed directly with no modification.
 ///
+///   * **&RawStr**
+///
+///     _This implementation always returns successfully._
+///
+///     The path segment is passed exactly as it appeared in the request URI,
+///     still percent-encoded, as a [RawStr](/rocket/http/struct.RawStr.html).
+///
 ///   * **String**
 ///
 ///     Percent decodes the path segment. If the decode is successful, the
@@ -115,6 +146,28 @@ use http::uri::{URI, Segments};
 /// to do so. This tradeoff is similiar to that of form values, and you should
 /// use whichever makes sense for your application.
 ///
+/// Decoding is lossy: a segment of `a%2Fb` and the two segments `a/b` decode
+/// to the same string. A route that must tell them apart, or that decodes
+/// segments itself, should declare the parameter as `&RawStr`, which makes
+/// receiving the raw segment explicit:
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::http::RawStr;
+///
+/// #[get("/packages/<name>")]
+/// fn package(name: &RawStr) -> String {
+///     // `/packages/%40scope%2Fpkg` names the package `@scope/pkg`.
+///     match name.percent_decode() {
+///         Ok(name) => format!("Package: {}", name),
+///         Err(_) => format!("Bad package name: {}", name.as_str())
+///     }
+/// }
+/// # fn main() {  }
+/// ```
+///
 /// # Example
 ///
 /// Say you want to parse a segment of the form:
@@ -203,6 +256,13 @@ impl<'a> FromParam<'a> for &'a str {
     }
 }
 
+impl<'a> FromParam<'a> for &'a RawStr {
+    type Error = ();
+    fn from_param(param: &'a str) -> Result<&'a RawStr, Self::Error> {
+        Ok(RawStr::from_str(param))
+    }
+}
+
 impl<'a> FromParam<'a> for String {
     type Error = &'a str;
     fn from_param(p: &'a str) -> Result<String, Self::Error> {
@@ -225,6 +285,9 @@ impl_with_fromstr!(f32, f64, isize, i8, i16, i32, i64, usize, u8, u16, u32, u64,
        bool, IpAddr, Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6,
        SocketAddr);
 
+#[cfg(feature = "uuid")] impl_with_fromstr!(Uuid);
+#[cfg(feature = "bigdecimal")] impl_with_fromstr!(BigDecimal);
+
 impl<'a, T: FromParam<'a>> FromParam<'a> for Result<T, T::Error> {
     type Error = ();
     fn from_param(p: &'a str) -> Result<Self, Self::Error> {
@@ -257,12 +320,13 @@ impl<'a, T: FromParam<'a>> FromParam<'a> for Option<T> {
 ///
 /// # Provided Implementations
 ///
-/// Rocket implements `FromParam` for `PathBuf`. The `PathBuf` implementation
-/// constructs a path from the segments iterator. Each segment is
-/// percent-decoded. If a segment equals ".." before or after decoding, the
-/// previous segment (if any) is omitted. For security purposes, any other
-/// segments that begin with "*" or "." are ignored.  If a percent-decoded
-/// segment results in invalid UTF8, an `Err` is returned with the `Utf8Error`.
+/// Rocket implements `FromSegments` for `PathBuf`. The `PathBuf`
+/// implementation constructs a path from the segments iterator. Each segment is
+/// percent-decoded, then checked so that the path can be safely joined to a
+/// directory: segments that are `..`, contain a path separator or NUL byte,
+/// begin with `.` or `*`, end with `.` or a space, or name a Windows device
+/// such as `CON` or `NUL.txt` are rejected with a
+/// [SegmentError](enum.SegmentError.html) describing the problem.
 pub trait FromSegments<'a>: Sized {
     /// The associated error to be returned when parsing fails.
     type Error: Debug;
@@ -279,23 +343,88 @@ impl<'a> FromSegments<'a> for Segments<'a> {
     }
 }
 
-/// Creates a `PathBuf` from a `Segments` iterator. The returned `PathBuf` is
-/// percent-decoded. If a segment is equal to "..", the previous segment (if
-/// any) is skipped. For security purposes, any other segments that begin with
-/// "*" or "." are ignored.  If a percent-decoded segment results in invalid
-/// UTF8, an `Err` is returned.
+/// The error returned when a `PathBuf` can't be safely created from a path's
+/// segments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SegmentError {
+    /// A percent-decoded segment isn't valid UTF-8.
+    Utf8(Utf8Error),
+    /// A segment is `..`, which would traverse to the parent directory.
+    Traversal,
+    /// A segment contains the given character: a path separator, a drive or
+    /// stream separator, or a NUL byte.
+    BadChar(char),
+    /// A segment begins with the given character.
+    BadStart(char),
+    /// A segment ends with the given character, which Windows ignores.
+    BadEnd(char),
+    /// A segment names a Windows device, such as `CON` or `NUL.txt`.
+    DeviceName,
+}
+
+impl fmt::Display for SegmentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SegmentError::Utf8(ref e) => write!(f, "invalid UTF-8: {}", e),
+            SegmentError::Traversal => write!(f, "segment traverses to a parent"),
+            SegmentError::BadChar(c) => write!(f, "segment contains {:?}", c),
+            SegmentError::BadStart(c) => write!(f, "segment begins with {:?}", c),
+            SegmentError::BadEnd(c) => write!(f, "segment ends with {:?}", c),
+            SegmentError::DeviceName => write!(f, "segment names a device"),
+        }
+    }
+}
+
+/// Windows device names, which refer to the device regardless of the directory
+/// or extension.
+const DEVICE_NAMES: &'static [&'static str] = &[
+    "CON", "PRN", "AUX", "NUL", "CONIN$", "CONOUT$",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Checks that the percent-decoded `segment` can be safely pushed to a path.
+fn check_segment(segment: &str) -> Result<(), SegmentError> {
+    if segment == ".." {
+        return Err(SegmentError::Traversal);
+    }
+
+    let separator = |c: char| c == '/' || c == '\\' || c == ':' || c == '\0';
+    if let Some(c) = segment.chars().find(|&c| separator(c)) {
+        return Err(SegmentError::BadChar(c));
+    }
+
+    match segment.chars().next() {
+        Some(c) if c == '.' || c == '*' => return Err(SegmentError::BadStart(c)),
+        _ => { /* the segment begins with a regular character */ }
+    }
+
+    match segment.chars().last() {
+        Some(c) if c == '.' || c == ' ' => return Err(SegmentError::BadEnd(c)),
+        _ => { /* the segment ends with a regular character */ }
+    }
+
+    let stem = segment.split('.').next().unwrap_or("").trim_right();
+    if DEVICE_NAMES.iter().any(|name| name.eq_ignore_ascii_case(stem)) {
+        return Err(SegmentError::DeviceName);
+    }
+
+    Ok(())
+}
+
+/// Creates a `PathBuf` from a `Segments` iterator. Each segment is
+/// percent-decoded and checked for traversal tricks; if any segment fails the
+/// checks, a `SegmentError` is returned. See
+/// [FromSegments](trait.FromSegments.html) for the rules.
 impl<'a> FromSegments<'a> for PathBuf {
-    type Error = Utf8Error;
+    type Error = SegmentError;
 
-    fn from_segments(segments: Segments<'a>) -> Result<PathBuf, Utf8Error> {
+    fn from_segments(segments: Segments<'a>) -> Result<PathBuf, SegmentError> {
         let mut buf = PathBuf::new();
         for segment in segments {
-            let decoded = URI::percent_decode(segment.as_bytes())?;
-            if decoded == ".." {
-                buf.pop();
-            } else if !(decoded.starts_with('.') || decoded.starts_with('*')) {
-                buf.push(&*decoded)
-            }
+            let decoded = URI::percent_decode(segment.as_bytes()).map_err(SegmentError::Utf8)?;
+            check_segment(&decoded)?;
+            buf.push(&*decoded)
         }
 
         Ok(buf)
@@ -321,3 +450,47 @@ impl<'a, T: FromSegments<'a>> FromSegments<'a> for Option<T> {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use http::uri::URI;
+    use super::{FromSegments, SegmentError};
+
+    fn path(uri: &str) -> Result<PathBuf, SegmentError> {
+        PathBuf::from_segments(URI::new(uri).segments())
+    }
+
+    #[test]
+    fn test_safe_paths() {
+        assert_eq!(path("/a/b/c.txt"), Ok(PathBuf::from("a/b/c.txt")));
+        assert_eq!(path("/a%20b/c..d.txt"), Ok(PathBuf::from("a b/c..d.txt")));
+        assert_eq!(path("/console/nullify.d/com10"),
+                   Ok(PathBuf::from("console/nullify.d/com10")));
+    }
+
+    #[test]
+    fn test_traversal() {
+        assert_eq!(path("/a/../b"), Err(SegmentError::Traversal));
+        assert_eq!(path("/a/%2e%2e/b"), Err(SegmentError::Traversal));
+        assert_eq!(path("/a/%2E%2e"), Err(SegmentError::Traversal));
+        assert_eq!(path("/%2e%2e%2fetc/passwd"), Err(SegmentError::BadChar('/')));
+        assert_eq!(path("/%2fetc/passwd"), Err(SegmentError::BadChar('/')));
+        assert_eq!(path("/..%5c..%5cwindows"), Err(SegmentError::BadChar('\\')));
+        assert_eq!(path("/c:/windows"), Err(SegmentError::BadChar(':')));
+        assert_eq!(path("/a%00.txt"), Err(SegmentError::BadChar('\0')));
+    }
+
+    #[test]
+    fn test_bad_names() {
+        assert_eq!(path("/.git/config"), Err(SegmentError::BadStart('.')));
+        assert_eq!(path("/*"), Err(SegmentError::BadStart('*')));
+        assert_eq!(path("/a./b"), Err(SegmentError::BadEnd('.')));
+        assert_eq!(path("/a/b%20"), Err(SegmentError::BadEnd(' ')));
+        assert_eq!(path("/con"), Err(SegmentError::DeviceName));
+        assert_eq!(path("/files/NUL.txt"), Err(SegmentError::DeviceName));
+        assert_eq!(path("/Com1%20.tar.gz"), Err(SegmentError::DeviceName));
+        assert!(path("/%ff").is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt::Debug;
+use std::ops::{Deref, DerefMut};
+
+use outcome::Outcome::*;
+use request::{self, Request, FromRequest, FromForm};
+use http::Status;
+
+/// Trait to create an instance of some type from a request's query string.
+///
+/// This is the trait used by the [Query](struct.Query.html) request guard. It
+/// is implemented for every [FromForm](trait.FromForm.html) type, including
+/// those that derive `FromForm`, by parsing the query string as a lenient
+/// form: parameters the type doesn't expect are ignored, since query strings
+/// commonly carry parameters meant for others, such as analytics tags. Types
+/// that need to parse query strings differently may implement `FromQuery`
+/// directly.
+///
+/// A request without a query string is parsed as an empty query string, so
+/// structures whose fields are all optional, or have defaults, parse from
+/// requests without one.
+pub trait FromQuery<'q>: Sized {
+    /// The associated error to be returned when parsing fails.
+    type Error;
+
+    /// Parses an instance of `Self` from the raw, URL-encoded, query string
+    /// `query`, or returns an `Error` if one cannot be parsed.
+    fn from_query(query: &'q str) -> Result<Self, Self::Error>;
+}
+
+impl<'q, T: FromForm<'q>> FromQuery<'q> for T {
+    type Error = T::Error;
+
+    default fn from_query(query: &'q str) -> Result<T, T::Error> {
+        T::from_form_string_lenient(query)
+    }
+}
+
+/// A request guard that parses a request's query string into a `T`.
+///
+/// `Query` is the query string counterpart of [Form](struct.Form.html): it
+/// parses any [FromQuery](trait.FromQuery.html) type, which includes all
+/// `FromForm` types, from the query string of a request. Unlike a route's
+/// `query` parameter, which forwards when the request has no query string,
+/// `Query` parses a missing query string as an empty one, so GET endpoints
+/// whose parameters are all optional work without one. If parsing fails, the
+/// request fails with `400 Bad Request`.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::Query;
+///
+/// #[derive(FromForm)]
+/// struct Search {
+///     q: Option<String>,
+///     page: Option<usize>
+/// }
+///
+/// #[get("/search")]
+/// fn search(search: Query<Search>) -> String {
+///     format!("'{}' page {}", search.q.as_ref().map_or("", |q| q.as_str()),
+///             search.page.unwrap_or(1))
+/// }
+/// # fn main() { }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query<T>(pub T);
+
+impl<T> Query<T> {
+    /// Consumes `self` and returns the parsed value.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Query<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Query<T> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<'a, 'r, T: FromQuery<'a>> FromRequest<'a, 'r> for Query<T>
+    where T::Error: Debug + 'static
+{
+    type Error = T::Error;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, Self::Error> {
+        match T::from_query(request.uri().query().unwrap_or("")) {
+            Ok(value) => Success(Query(value)),
+            Err(e) => {
+                error_!("Failed to parse the query string: {:?}", e);
+                Failure((Status::BadRequest, e))
+            }
+        }
+    }
+}
@@ -1,18 +1,67 @@
-use std::cell::RefCell;
+use std::any::{Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::ascii::AsciiExt;
 use std::fmt;
+use std::io;
 
 use term_painter::Color::*;
 use term_painter::ToStyle;
 
 use error::Error;
-use super::{FromParam, FromSegments};
+use config::{Config, Limits};
+use metrics::Metrics;
+use inject::Providers;
+use tenant::{Tenant, Tenants};
+use state::ManagedState;
+use super::{FromParam, FromSegments, FromRequest, Outcome, GuardFailure, Connection};
+use super::ClientInfo;
+use super::{FromFormValue, FormItems};
 
 use router::Route;
 use http::uri::{URI, Segments};
-use http::{Method, ContentType, Header, HeaderMap, Cookies};
+use http::{Method, ContentType, Accept, Header, HeaderMap, Cookies, Host, Status, RawStr};
 
 use http::hyper;
 
+/// A value derived from a request's headers, computed at most once while the
+/// headers are unchanged.
+struct Cached<T>(RefCell<Option<T>>);
+
+impl<T> Cached<T> {
+    #[inline(always)]
+    fn new() -> Cached<T> {
+        Cached(RefCell::new(None))
+    }
+
+    /// Returns the cached value, first caching the value returned by `f` if
+    /// there isn't one.
+    fn get_or_init<F: FnOnce() -> T>(&self, f: F) -> &T {
+        if self.0.borrow().is_none() {
+            // `f` may itself initialize the value, which references returned
+            // by that call point to, so a value is never overwritten.
+            let value = f();
+            let mut cached = self.0.borrow_mut();
+            if cached.is_none() {
+                *cached = Some(value);
+            }
+        }
+
+        // The value is only replaced via `reset`, which takes `&mut self`, so
+        // it lives at least as long as the borrow of `self`.
+        let value = self.0.borrow();
+        unsafe { &*(value.as_ref().expect("a cached value") as *const T) }
+    }
+
+    /// Forgets the cached value, if any.
+    #[inline(always)]
+    fn reset(&mut self) {
+        *self.0.get_mut() = None;
+    }
+}
+
 /// The type of an incoming web request.
 ///
 /// This should be used sparingly in Rocket applications. In particular, it
@@ -24,8 +73,27 @@ pub struct Request<'r> {
     method: Method,
     uri: URI<'r>,
     headers: HeaderMap<'r>,
+    content_type: Cached<ContentType>,
+    accept: Cached<Option<Accept>>,
     params: RefCell<Vec<(usize, usize)>>,
     cookies: Cookies,
+    config: Option<Arc<Config>>,
+    default_limits: Cached<Limits>,
+    remote: Option<SocketAddr>,
+    connection: Connection,
+    failure: RefCell<Option<GuardFailure>>,
+    error_reported: Cell<bool>,
+    route: Cell<Option<&'r Route>>,
+    metrics: Option<&'r Metrics>,
+    providers: Option<&'r Providers>,
+    varies: RefCell<Vec<String>>,
+    challenges: RefCell<Vec<String>>,
+    tenant: Option<Tenant>,
+    tenants: Option<&'r Tenants>,
+    cache: RefCell<HashMap<TypeId, Box<Any>>>,
+    extensions: RefCell<Vec<(TypeId, Box<Any>)>>,
+    managed: Option<&'r ManagedState>,
+    finalizers: RefCell<Vec<Box<FnMut(Result<(), &io::Error>)>>>,
 }
 
 impl<'r> Request<'r> {
@@ -46,8 +114,27 @@ impl<'r> Request<'r> {
             method: method,
             uri: uri.into(),
             headers: HeaderMap::new(),
+            content_type: Cached::new(),
+            accept: Cached::new(),
             params: RefCell::new(Vec::new()),
             cookies: Cookies::new(&[]),
+            config: None,
+            default_limits: Cached::new(),
+            remote: None,
+            connection: Connection::new(),
+            failure: RefCell::new(None),
+            error_reported: Cell::new(false),
+            route: Cell::new(None),
+            metrics: None,
+            providers: None,
+            varies: RefCell::new(vec![]),
+            challenges: RefCell::new(vec![]),
+            tenant: None,
+            tenants: None,
+            cache: RefCell::new(HashMap::new()),
+            extensions: RefCell::new(vec![]),
+            managed: None,
+            finalizers: RefCell::new(vec![]),
         }
     }
 
@@ -140,6 +227,22 @@ impl<'r> Request<'r> {
         &self.headers
     }
 
+    /// Returns a mutable borrow to the headers in `self`. This should only be
+    /// used internally by `Rocket`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn headers_mut(&mut self) -> &mut HeaderMap<'r> {
+        self.reset_cached_headers();
+        &mut self.headers
+    }
+
+    /// Forgets the values parsed from the headers, which are about to change.
+    #[inline(always)]
+    fn reset_cached_headers(&mut self) {
+        self.content_type.reset();
+        self.accept.reset();
+    }
+
     /// Add the `header` to `self`'s headers.
     ///
     /// # Example
@@ -157,6 +260,7 @@ impl<'r> Request<'r> {
     /// ```
     #[inline(always)]
     pub fn add_header(&mut self, header: Header<'r>) {
+        self.reset_cached_headers();
         self.headers.add(header);
     }
 
@@ -180,6 +284,7 @@ impl<'r> Request<'r> {
     /// ```
     #[inline(always)]
     pub fn replace_header(&mut self, header: Header<'r>) {
+        self.reset_cached_headers();
         self.headers.replace(header);
     }
 
@@ -224,7 +329,8 @@ impl<'r> Request<'r> {
     }
 
     /// Returns the Content-Type header of `self`. If the header is not present,
-    /// returns `ContentType::Any`.
+    /// or is malformed, returns `ContentType::Any`. The header is parsed at
+    /// most once while the headers of `self` are unchanged.
     ///
     /// # Example
     ///
@@ -240,9 +346,248 @@ impl<'r> Request<'r> {
     /// ```
     #[inline(always)]
     pub fn content_type(&self) -> ContentType {
-        self.headers().get_one("Content-Type")
-            .and_then(|value| value.parse().ok())
-            .unwrap_or(ContentType::Any)
+        self.content_type.get_or_init(|| {
+            self.headers().get_one("Content-Type")
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(ContentType::Any)
+        }).clone()
+    }
+
+    /// Returns the media types acceptable to the client, as indicated by the
+    /// `Accept` header of `self`, or `None` if the header is not present.
+    /// Malformed elements of the header are ignored. The header is parsed at
+    /// most once while the headers of `self` are unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::{Method, ContentType, Header};
+    ///
+    /// let mut request = Request::new(Method::Get, "/uri");
+    /// assert!(request.accept().is_none());
+    ///
+    /// request.add_header(Header::new("Accept", "application/json, text/html;q=0.5"));
+    /// let accept = request.accept().unwrap();
+    /// assert_eq!(accept.preferred(), &ContentType::new("application", "json"));
+    /// ```
+    pub fn accept(&self) -> Option<&Accept> {
+        self.accept.get_or_init(|| {
+            let values: Vec<_> = self.headers().get("Accept").collect();
+            if values.is_empty() {
+                None
+            } else {
+                Some(Accept::parse_lossy(&values.join(",")))
+            }
+        }).as_ref()
+    }
+
+    /// Returns the host, with an optional port, that `self` was addressed to
+    /// as indicated by the `Host` header. Returns `None` if the header is
+    /// missing or malformed.
+    ///
+    /// If the application was configured with a non-empty list of `hosts`, the
+    /// host must additionally be matched by one of the configured hosts;
+    /// `None` is returned otherwise. As such, the value returned from this
+    /// method can be safely used to construct URLs that point back to the
+    /// application.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::{Method, Header};
+    ///
+    /// let mut request = Request::new(Method::Get, "/uri");
+    /// assert!(request.host().is_none());
+    ///
+    /// request.add_header(Header::new("Host", "rocket.rs:8000"));
+    /// let host = request.host().unwrap();
+    /// assert_eq!(host.name(), "rocket.rs");
+    /// assert_eq!(host.port(), Some(8000));
+    /// ```
+    pub fn host(&self) -> Option<Host> {
+        let host = match self.headers().get_one("Host").map(Host::parse) {
+            Some(Ok(host)) => host,
+            _ => return None
+        };
+
+        let hosts = match self.config() {
+            Some(config) if !config.hosts.is_empty() => &config.hosts,
+            _ => return Some(host)
+        };
+
+        if hosts.iter().filter_map(|h| Host::parse(h).ok()).any(|h| host.matches(&h)) {
+            Some(host)
+        } else {
+            warn_!("Host '{}' is not an allowed host.", host);
+            None
+        }
+    }
+
+    /// Returns the scheme the client used to reach the application: `"https"`
+    /// if a trusted proxy reports that the client connected over HTTPS, and
+    /// `"http"` otherwise. See
+    /// [ClientInfo](/rocket/request/struct.ClientInfo.html) for how proxies
+    /// are trusted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// let request = Request::new(Method::Get, "/uri");
+    /// assert_eq!(request.scheme(), "http");
+    /// ```
+    pub fn scheme(&self) -> &'static str {
+        match ClientInfo::from_request(self) {
+            ::outcome::Outcome::Success(client) => client.scheme(),
+            _ => "http"
+        }
+    }
+
+    /// Returns an absolute URL for `path` using the scheme returned by
+    /// [scheme](#method.scheme) and the host that `self` was addressed to as
+    /// returned by [host](#method.host). Returns `None` if the host is unknown
+    /// or not allowed. This is useful for constructing the values of
+    /// `Location` headers for redirects.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::{Method, Header};
+    ///
+    /// let mut request = Request::new(Method::Get, "/uri");
+    /// assert_eq!(request.absolute_uri("/login"), None);
+    ///
+    /// request.add_header(Header::new("Host", "rocket.rs"));
+    /// let url = request.absolute_uri("/login");
+    /// assert_eq!(url, Some("http://rocket.rs/login".to_string()));
+    /// ```
+    pub fn absolute_uri(&self, path: &str) -> Option<String> {
+        self.host().map(|host| {
+            let separator = if path.starts_with('/') { "" } else { "/" };
+            format!("{}://{}{}{}", self.scheme(), host, separator, path)
+        })
+    }
+
+    /// Sets the configuration that `self` is being handled under. This should
+    /// only be used internally by `Rocket`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn set_config(&mut self, config: Arc<Config>) {
+        self.config = Some(config);
+    }
+
+    /// Returns the configuration the request is being handled under, if any.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn config(&self) -> Option<&Config> {
+        self.config.as_ref().map(|config| &**config)
+    }
+
+    /// Returns the limits on the sizes of request bodies that apply to this
+    /// request: those of the configuration the request is being handled
+    /// under, or the default limits if there is none. Data guards should read
+    /// no more of a body than the limit for their kind of data allows. See
+    /// [Limits](/rocket/config/struct.Limits.html) for the built-in limits.
+    ///
+    /// Request guards can retrieve the limits via the `&Limits` request guard
+    /// as well.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::config::ByteUnit;
+    /// use rocket::http::Method;
+    ///
+    /// let request = Request::new(Method::Post, "/upload");
+    /// assert_eq!(request.limits().get("forms"), ByteUnit::new(32 * 1024));
+    /// assert_eq!(request.limits().get("csv"), request.limits().get("default"));
+    /// ```
+    #[inline]
+    pub fn limits(&self) -> &Limits {
+        match self.config() {
+            Some(config) => &config.limits,
+            None => self.default_limits.get_or_init(Limits::default)
+        }
+    }
+
+    /// Returns the address of the remote peer that sent the request, if it is
+    /// known. When the application is deployed behind a reverse proxy, this is
+    /// the address of the proxy; use
+    /// [ClientInfo](/rocket/request/struct.ClientInfo.html) to find the
+    /// address of the client that the proxy is forwarding for.
+    ///
+    /// The remote address is also available to handlers via the `SocketAddr`
+    /// request guard, which forwards if the address is unknown.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// let mut request = Request::new(Method::Get, "/uri");
+    /// assert_eq!(request.remote(), None);
+    ///
+    /// let address = "8.8.8.8:80".parse().unwrap();
+    /// request.set_remote(address);
+    /// assert_eq!(request.remote(), Some(address));
+    /// ```
+    #[inline(always)]
+    pub fn remote(&self) -> Option<SocketAddr> {
+        self.remote
+    }
+
+    /// Sets the address of the remote peer that sent the request.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// let mut request = Request::new(Method::Get, "/uri");
+    /// request.set_remote("127.0.0.1:8000".parse().unwrap());
+    /// assert_eq!(request.remote().map(|a| a.port()), Some(8000));
+    /// ```
+    #[inline(always)]
+    pub fn set_remote(&mut self, address: SocketAddr) {
+        self.remote = Some(address);
+    }
+
+    /// Returns the storage for the connection this request arrived on. See
+    /// [Connection](/rocket/request/struct.Connection.html) for details.
+    /// Requests that weren't received by Rocket, such as those created
+    /// manually, each have a fresh connection.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// struct Compressor(&'static str);
+    ///
+    /// let request = Request::new(Method::Get, "/uri");
+    /// let compressor = request.connection().local_cache(|| Compressor("deflate"));
+    /// assert_eq!(compressor.0, "deflate");
+    /// ```
+    #[inline(always)]
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Sets the connection the request arrived on. This should only be used
+    /// internally by `Rocket`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn set_connection(&mut self, connection: Connection) {
+        self.connection = connection;
     }
 
     /// Retrieves and parses into `T` the `n`th dynamic parameter from the
@@ -271,6 +616,43 @@ impl<'r> Request<'r> {
         T::from_param(param).map_err(|_| Error::BadParse)
     }
 
+    /// Retrieves and parses into `T` the value of the query parameter named
+    /// `name`, as in `3` for `page` in `/items?page=3`. Returns `None` if the
+    /// request's query string has no such parameter, and otherwise the result
+    /// of parsing the raw, possibly URL-encoded, value via
+    /// [FromFormValue](/rocket/request/trait.FromFormValue.html). If the
+    /// parameter is repeated, the last value is used, as in forms.
+    ///
+    /// This allows request guards and error catchers to read a single query
+    /// parameter without a route declaring it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// let request = Request::new(Method::Get, "/items?page=3&q=rocket%20fuel&page=x");
+    /// assert_eq!(request.query_value::<String>("q"), Some(Ok("rocket fuel".to_string())));
+    /// assert_eq!(request.query_value::<usize>("page"), Some(Err("x")));
+    /// assert_eq!(request.query_value::<usize>("size"), None);
+    /// ```
+    pub fn query_value<'a, T>(&'a self, name: &str) -> Option<Result<T, T::Error>>
+        where T: FromFormValue<'a>
+    {
+        let query = match self.uri().query() {
+            Some(query) => query,
+            None => return None
+        };
+
+        FormItems(query)
+            .filter(|&(key, _)| {
+                key == name || RawStr::from_str(key).url_decode().map_or(false, |k| k == name)
+            })
+            .last()
+            .map(|(_, value)| T::from_form_value(value))
+    }
+
     /// Set `self`'s parameters given that the route used to reach this request
     /// was `route`. This should only be used internally by `Rocket` as improper
     /// use may result in out of bounds indexing.
@@ -280,6 +662,466 @@ impl<'r> Request<'r> {
         *self.params.borrow_mut() = route.get_param_indexes(self.uri());
     }
 
+    /// Returns the failure of the request guard that caused this request to
+    /// fail, if any. This is typically used by error catchers to explain why a
+    /// request failed. See [GuardFailure](/rocket/request/struct.GuardFailure.html)
+    /// for more information.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::{Method, Status};
+    ///
+    /// let request = Request::new(Method::Get, "/uri");
+    /// assert!(request.guard_failure().is_none());
+    ///
+    /// request.set_guard_failure(Status::Unauthorized, "bad key");
+    /// let failure = request.guard_failure().unwrap();
+    /// assert_eq!(failure.status(), Status::Unauthorized);
+    /// assert_eq!(failure.error(), "\"bad key\"");
+    /// assert_eq!(failure.downcast_ref::<&str>(), Some(&"bad key"));
+    /// ```
+    #[inline]
+    pub fn guard_failure(&self) -> Option<GuardFailure> {
+        self.failure.borrow().clone()
+    }
+
+    /// Records that a request guard failed with status `status` and error
+    /// `error`. This should only be used internally by `Rocket`.
+    #[doc(hidden)]
+    #[inline]
+    pub fn set_guard_failure<E: fmt::Debug + Any>(&self, status: Status, error: E) {
+        *self.failure.borrow_mut() = Some(GuardFailure::new(status, error));
+    }
+
+    /// Records that a server error was reported for this request, returning
+    /// `true` if none had been before. This should only be used internally by
+    /// `Rocket`.
+    #[doc(hidden)]
+    #[inline]
+    pub fn set_error_reported(&self) -> bool {
+        let first = !self.error_reported.get();
+        self.error_reported.set(true);
+        first
+    }
+
+    /// Records that the response to this request depends on the value of the
+    /// request header named `name`, as is the case when the response is the
+    /// result of content negotiation. Names are compared case-insensitively and
+    /// recorded at most once. Request guards that negotiate, such as
+    /// [AcceptLanguage](/rocket/http/struct.AcceptLanguage.html), call this
+    /// method. The recorded names are added to the response's `Vary` header by
+    /// the [vary](/rocket/hook/fn.vary.html) hook.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// let request = Request::new(Method::Get, "/uri");
+    /// request.vary_on("Accept-Encoding");
+    /// request.vary_on("accept-encoding");
+    /// assert_eq!(request.varies_on(), vec!["Accept-Encoding"]);
+    /// ```
+    pub fn vary_on(&self, name: &str) {
+        let mut varies = self.varies.borrow_mut();
+        if !varies.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            varies.push(name.to_string());
+        }
+    }
+
+    /// Returns the names of the request headers the response to this request
+    /// depends on, in the order they were recorded via
+    /// [vary_on](#method.vary_on).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// let request = Request::new(Method::Get, "/uri");
+    /// assert!(request.varies_on().is_empty());
+    /// ```
+    #[inline]
+    pub fn varies_on(&self) -> Vec<String> {
+        self.varies.borrow().clone()
+    }
+
+    /// Records `challenge` as an authentication challenge for this request,
+    /// such as `Basic realm="admin"`. Guards that forward because a request
+    /// lacks credentials, such as
+    /// [BasicAuth](/rocket/request/struct.BasicAuth.html), call this method.
+    /// Identical challenges are recorded once.
+    ///
+    /// If no route accepts a request with recorded challenges, Rocket responds
+    /// with `401 Unauthorized` instead of `404 Not Found`. Every `401` response
+    /// to such a request carries one `WWW-Authenticate` header per challenge.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// let request = Request::new(Method::Get, "/uri");
+    /// request.challenge("Basic realm=\"admin\"");
+    /// request.challenge("Basic realm=\"admin\"");
+    /// assert_eq!(request.challenges(), vec!["Basic realm=\"admin\""]);
+    /// ```
+    pub fn challenge<S: Into<String>>(&self, challenge: S) {
+        let challenge = challenge.into();
+        let mut challenges = self.challenges.borrow_mut();
+        if !challenges.contains(&challenge) {
+            challenges.push(challenge);
+        }
+    }
+
+    /// Returns the authentication challenges recorded for this request via
+    /// [challenge](#method.challenge), in the order they were recorded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// let request = Request::new(Method::Get, "/uri");
+    /// assert!(request.challenges().is_empty());
+    /// ```
+    #[inline]
+    pub fn challenges(&self) -> Vec<String> {
+        self.challenges.borrow().clone()
+    }
+
+    /// Returns the value of type `T` cached for this request, first caching
+    /// the value returned by `f` if there isn't one. There is at most one
+    /// cached value of any given type per request, and `f` is called at most
+    /// once per type unless it itself caches a value of type `T`.
+    ///
+    /// This allows request guards to share the results of expensive
+    /// computations. For instance, several guards that each need the current
+    /// user can look the user up in the database once per request instead of
+    /// once per guard.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// struct User(&'static str);
+    ///
+    /// let request = Request::new(Method::Get, "/uri");
+    /// let user = request.local_cache(|| User("bob"));
+    /// assert_eq!(user.0, "bob");
+    ///
+    /// // The first value is returned; the closure isn't called again.
+    /// let user = request.local_cache(|| User("alice"));
+    /// assert_eq!(user.0, "bob");
+    /// ```
+    pub fn local_cache<T, F>(&self, f: F) -> &T
+        where T: Send + Sync + 'static, F: FnOnce() -> T
+    {
+        let key = TypeId::of::<T>();
+        if !self.cache.borrow().contains_key(&key) {
+            // `f` may use the cache, so it's called without a borrow.
+            let value = f();
+            self.cache.borrow_mut().entry(key).or_insert(Box::new(value));
+        }
+
+        let cache = self.cache.borrow();
+        let value = cache.get(&key)
+            .and_then(|value| value.downcast_ref::<T>())
+            .expect("cached value of the requested type");
+
+        // Cached values are boxed and never replaced or removed while the
+        // request is alive, so their addresses are stable even as the map
+        // grows, and the reference is valid for as long as `self` is.
+        unsafe { &*(value as *const T) }
+    }
+
+    /// Stores `value` as the extension of type `T` of this request, replacing
+    /// any previous extension of that type.
+    ///
+    /// Extensions let request hooks and guards pass data they derive from a
+    /// request, such as an authenticated principal or a trace context, to
+    /// handlers without global state. Unlike
+    /// [local_cache](#method.local_cache), an extension can be set from
+    /// anywhere the request is reachable and replaced later. Handlers retrieve
+    /// extensions via [extension](#method.extension) or the
+    /// [Extension](/rocket/request/struct.Extension.html) request guard.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// struct TraceId(u64);
+    ///
+    /// let request = Request::new(Method::Get, "/uri");
+    /// request.set_extension(TraceId(1));
+    /// request.set_extension(TraceId(2));
+    /// assert_eq!(request.extension::<TraceId>().map(|t| t.0), Some(2));
+    /// ```
+    pub fn set_extension<T: Send + Sync + 'static>(&self, value: T) {
+        self.extensions.borrow_mut().push((TypeId::of::<T>(), Box::new(value)));
+    }
+
+    /// Returns the extension of type `T` of this request, if one was set via
+    /// [set_extension](#method.set_extension).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// struct Principal(&'static str);
+    ///
+    /// let request = Request::new(Method::Get, "/uri");
+    /// assert!(request.extension::<Principal>().is_none());
+    ///
+    /// request.set_extension(Principal("alice"));
+    /// assert_eq!(request.extension::<Principal>().map(|p| p.0), Some("alice"));
+    /// ```
+    pub fn extension<T: 'static>(&self) -> Option<&T> {
+        let key = TypeId::of::<T>();
+        let extensions = self.extensions.borrow();
+        let value = extensions.iter().rev()
+            .find(|&&(id, _)| id == key)
+            .and_then(|&(_, ref value)| value.downcast_ref::<T>());
+
+        // Replaced extensions are shadowed, not dropped, and every extension
+        // is boxed, so addresses are stable for as long as `self` is alive.
+        value.map(|value| unsafe { &*(value as *const T) })
+    }
+
+    /// Runs the request guard `T` against `self` and returns its outcome, just
+    /// as Rocket does for the guards in a handler's signature.
+    ///
+    /// This allows handlers to run guards conditionally and guards to build on
+    /// one another: a guard that needs the result of another guard can invoke
+    /// it instead of duplicating its logic. Guards that are invoked more than
+    /// once per request run each time; use
+    /// [local_cache](#method.local_cache) to share expensive work.
+    ///
+    /// # Example
+    ///
+    /// A guard for administrators that builds on a guard for users:
+    ///
+    /// ```rust
+    /// use rocket::{Request, Outcome};
+    /// use rocket::request::{self, FromRequest};
+    /// use rocket::http::Status;
+    ///
+    /// struct User(String);
+    /// struct Admin(User);
+    ///
+    /// impl<'a, 'r> FromRequest<'a, 'r> for User {
+    ///     type Error = ();
+    ///
+    ///     fn from_request(request: &'a Request<'r>) -> request::Outcome<User, ()> {
+    ///         match request.headers().get_one("X-User") {
+    ///             Some(name) => Outcome::Success(User(name.to_string())),
+    ///             None => Outcome::Forward(())
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// impl<'a, 'r> FromRequest<'a, 'r> for Admin {
+    ///     type Error = ();
+    ///
+    ///     fn from_request(request: &'a Request<'r>) -> request::Outcome<Admin, ()> {
+    ///         request.guard::<User>().and_then(|user| {
+    ///             if user.0 == "root" {
+    ///                 Outcome::Success(Admin(user))
+    ///             } else {
+    ///                 Outcome::Failure((Status::Forbidden, ()))
+    ///             }
+    ///         })
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Running guards imperatively:
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::{Method, ContentType};
+    ///
+    /// let request = Request::new(Method::Post, "/uri");
+    /// assert_eq!(request.guard::<Method>().succeeded(), Some(Method::Post));
+    /// assert_eq!(request.guard::<ContentType>().succeeded(), Some(ContentType::Any));
+    /// ```
+    #[inline(always)]
+    pub fn guard<'a, T: FromRequest<'a, 'r>>(&'a self) -> Outcome<T, T::Error> {
+        T::from_request(self)
+    }
+
+    /// Registers `f` to be called once the response to this request has been
+    /// written to the client. `f` is passed `Ok(())` if the response was
+    /// written in full and the `Err` that stopped it otherwise, so it is called
+    /// whether or not writing succeeded. Callbacks are called in the order they
+    /// were registered.
+    ///
+    /// This allows handlers and request guards to clean up after responses
+    /// whose bodies are written after the handler returns, such as deleting a
+    /// temporary file that was streamed, or to record that a response was
+    /// delivered.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::fs;
+    /// use std::path::PathBuf;
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// let request = Request::new(Method::Get, "/uri");
+    /// let export = PathBuf::from("/tmp/export.csv");
+    /// request.on_finish(move |result| {
+    ///     if let Err(e) = result {
+    ///         println!("Export wasn't delivered: {}", e);
+    ///     }
+    ///
+    ///     let _ = fs::remove_file(&export);
+    /// });
+    /// ```
+    pub fn on_finish<F>(&self, f: F)
+        where F: FnOnce(Result<(), &io::Error>) + 'static
+    {
+        let mut f = Some(f);
+        self.finalizers.borrow_mut().push(Box::new(move |result| {
+            if let Some(f) = f.take() {
+                f(result);
+            }
+        }));
+    }
+
+    /// Calls the callbacks registered via [on_finish](#method.on_finish) with
+    /// `result`, the result of writing the response. Each callback is called
+    /// at most once. This should only be used internally by `Rocket`.
+    #[doc(hidden)]
+    pub fn finish(&self, result: Result<(), &io::Error>) {
+        // Callbacks may register more callbacks, so they're run unborrowed.
+        loop {
+            let finalizers = ::std::mem::replace(&mut *self.finalizers.borrow_mut(), vec![]);
+            if finalizers.is_empty() {
+                break;
+            }
+
+            for mut finalizer in finalizers {
+                finalizer(result);
+            }
+        }
+    }
+
+    /// Returns the route that handled the request, if any.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn route(&self) -> Option<&'r Route> {
+        self.route.get()
+    }
+
+    /// Records that `route` handled the request. This should only be used
+    /// internally by `Rocket`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn set_route(&self, route: &'r Route) {
+        self.route.set(Some(route));
+    }
+
+    /// Returns the metrics of the application handling the request, if any.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn metrics(&self) -> Option<&'r Metrics> {
+        self.metrics
+    }
+
+    /// Sets the metrics of the application handling the request. This should
+    /// only be used internally by `Rocket`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn set_metrics(&mut self, metrics: &'r Metrics) {
+        self.metrics = Some(metrics);
+    }
+
+    /// Returns the providers of the application handling the request, if any.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn providers(&self) -> Option<&'r Providers> {
+        self.providers
+    }
+
+    /// Sets the providers of the application handling the request. This
+    /// should only be used internally by `Rocket`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn set_providers(&mut self, providers: &'r Providers) {
+        self.providers = Some(providers);
+    }
+
+    /// Returns the managed state of the application handling the request, if
+    /// any.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn managed(&self) -> Option<&'r ManagedState> {
+        self.managed
+    }
+
+    /// Sets the managed state of the application handling the request. This
+    /// should only be used internally by `Rocket`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn set_managed(&mut self, managed: &'r ManagedState) {
+        self.managed = Some(managed);
+    }
+
+    /// Returns the tenant of the request, if it was resolved. See the
+    /// [tenant](/rocket/tenant/index.html) module for more information.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// let request = Request::new(Method::Get, "/uri");
+    /// assert!(request.tenant().is_none());
+    /// ```
+    #[inline(always)]
+    pub fn tenant(&self) -> Option<&Tenant> {
+        self.tenant.as_ref()
+    }
+
+    /// Sets the tenant of the request. This should only be used internally by
+    /// `Rocket`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn set_tenant(&mut self, tenant: Tenant) {
+        self.tenant = Some(tenant);
+    }
+
+    /// Returns the tenant configuration of the application handling the
+    /// request, if any.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn tenants(&self) -> Option<&'r Tenants> {
+        self.tenants
+    }
+
+    /// Sets the tenant configuration of the application handling the request.
+    /// This should only be used internally by `Rocket`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn set_tenants(&mut self, tenants: &'r Tenants) {
+        self.tenants = Some(tenants);
+    }
+
     /// Get the `n`th path parameter as a string, if it exists.
     #[doc(hidden)]
     pub fn get_param_str(&self, n: usize) -> Option<&str> {
@@ -335,24 +1177,57 @@ impl<'r> Request<'r> {
         }
     }
 
+    /// Parses a `Request` from the head of a raw HTTP/1.x request in `bytes`:
+    /// the request line followed by headers and an empty line. Anything after
+    /// the head, such as the body, is ignored. Returns an error describing the
+    /// problem if `bytes` doesn't contain a valid request head.
+    ///
+    /// This is the same parsing Rocket performs on incoming connections, minus
+    /// the connection, which makes it suitable for testing and fuzzing.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::http::Method;
+    ///
+    /// let raw = b"GET /hello?name=Rocket HTTP/1.1\r\nHost: rocket.rs\r\n\r\n";
+    /// let request = Request::parse(raw).unwrap();
+    /// assert_eq!(request.method(), Method::Get);
+    /// assert_eq!(request.uri().path(), "/hello");
+    /// assert_eq!(request.headers().get_one("Host"), Some("rocket.rs"));
+    ///
+    /// assert!(Request::parse(b"GET\r\n\r\n").is_err());
+    /// ```
+    pub fn parse(bytes: &[u8]) -> Result<Request<'static>, String> {
+        let mut reader = ::hyper::buffer::BufReader::new(bytes);
+        let incoming = ::hyper::http::h1::parse_request(&mut reader)
+            .map_err(|e| format!("Bad request head: {}", e))?;
+
+        let (h_method, h_uri) = incoming.subject;
+        Request::from_hyp(h_method, incoming.headers, h_uri)
+    }
+
     /// Convert from Hyper types into a Rocket Request.
     #[doc(hidden)]
     pub fn from_hyp(h_method: hyper::Method,
                     h_headers: hyper::header::Headers,
                     h_uri: hyper::RequestUri)
                     -> Result<Request<'static>, String> {
-        // Get a copy of the URI for later use.
-        let uri = match h_uri {
-            hyper::RequestUri::AbsolutePath(s) => s,
-            _ => return Err(format!("Bad URI: {}", h_uri)),
-        };
-
         // Ensure that the method is known. TODO: Allow made-up methods?
         let method = match Method::from_hyp(&h_method) {
             Some(method) => method,
             None => return Err(format!("Invalid method: {}", h_method))
         };
 
+        // Get a copy of the URI for later use. The `*` target is only valid
+        // for `OPTIONS` requests, which ask about the server as a whole.
+        let uri = match h_uri {
+            hyper::RequestUri::AbsolutePath(s) => s,
+            hyper::RequestUri::Star if method == Method::Options => "*".to_string(),
+            _ => return Err(format!("Bad URI: {}", h_uri)),
+        };
+
         // Construct the request object.
         let mut request = Request::new(method, uri);
 
@@ -383,3 +1258,20 @@ impl<'r> fmt::Display for Request<'r> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::Cached;
+
+    #[test]
+    fn test_reentrant_cached_init() {
+        let cached = Cached::new();
+        let value = cached.get_or_init(|| {
+            // The value initialized here is kept; the one returned is dropped.
+            assert_eq!(cached.get_or_init(|| vec![1]), &vec![1]);
+            vec![2]
+        });
+
+        assert_eq!(value, &vec![1]);
+    }
+}
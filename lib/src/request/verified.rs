@@ -0,0 +1,147 @@
+use std::io::{self, Read};
+use std::ops::Deref;
+use std::fmt;
+
+use outcome::Outcome::*;
+use request::Request;
+use data::{self, Data, FromData};
+use config::ByteUnit;
+use http::{Status, Digest, ContentMd5};
+
+/// A data guard for request bodies that are checked against the request's
+/// `Content-MD5` and `Digest` headers.
+///
+/// The body is read into memory, up to the **default** limit, and each of the
+/// request's `Content-MD5` and `Digest` headers is verified against it. If a
+/// digest doesn't match, or a header is malformed, the request fails with
+/// `400 Bad Request`. A body larger than the limit fails with `413 Payload Too
+/// Large`. Requests without either header, and `Digest` headers with only
+/// algorithms Rocket doesn't understand, are accepted unchecked; use
+/// [is_checked](#method.is_checked) to require verification.
+///
+/// The [digest](/rocket/hook/fn.digest.html) response hook generates these
+/// headers for responses.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::request::Verified;
+/// use rocket::http::Status;
+///
+/// #[put("/objects/<name>", data = "<object>")]
+/// fn put(name: String, object: Verified) -> Result<String, Status> {
+///     if !object.is_checked() {
+///         return Err(Status::BadRequest);
+///     }
+///
+///     Ok(format!("stored {} bytes in {}", object.len(), name))
+/// }
+/// # fn main() { }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verified {
+    bytes: Vec<u8>,
+    checked: bool,
+}
+
+impl Verified {
+    /// Returns `true` if the body was checked against at least one digest.
+    #[inline(always)]
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    /// Consumes `self` and returns the body's bytes.
+    #[inline(always)]
+    pub fn into_inner(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl Deref for Verified {
+    type Target = [u8];
+
+    #[inline(always)]
+    fn deref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// The error returned by the [Verified](struct.Verified.html) data guard.
+#[derive(Debug)]
+pub enum VerifyError {
+    /// An I/O error occurred while reading the body.
+    Io(io::Error),
+    /// The body exceeds the limit, which is included.
+    TooLarge(ByteUnit),
+    /// The header with the given name is malformed.
+    Malformed(&'static str),
+    /// The body doesn't match the digest in the header with the given name.
+    Mismatch(&'static str),
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VerifyError::Io(ref e) => write!(f, "I/O error: {}", e),
+            VerifyError::TooLarge(limit) => write!(f, "body exceeds the limit of {}", limit),
+            VerifyError::Malformed(name) => write!(f, "malformed {} header", name),
+            VerifyError::Mismatch(name) => write!(f, "body doesn't match its {}", name),
+        }
+    }
+}
+
+/// Checks `bytes` against the `Content-MD5` and `Digest` headers of `request`,
+/// returning whether any digest was checked.
+fn verify(request: &Request, bytes: &[u8]) -> Result<bool, VerifyError> {
+    let mut checked = false;
+    for value in request.headers().get("Content-MD5") {
+        let md5: ContentMd5 = value.parse()
+            .map_err(|_| VerifyError::Malformed("Content-MD5"))?;
+
+        if !md5.verify(bytes) {
+            return Err(VerifyError::Mismatch("Content-MD5"));
+        }
+
+        checked = true;
+    }
+
+    for value in request.headers().get("Digest") {
+        let digest: Digest = value.parse().map_err(|_| VerifyError::Malformed("Digest"))?;
+        match digest.verify(bytes) {
+            Some(true) => checked = true,
+            Some(false) => return Err(VerifyError::Mismatch("Digest")),
+            None => { /* no digests Rocket understands */ }
+        }
+    }
+
+    Ok(checked)
+}
+
+impl FromData for Verified {
+    type Error = VerifyError;
+
+    fn from_data(request: &Request, data: Data) -> data::Outcome<Self, Self::Error> {
+        let limit = request.limits().get("default");
+
+        // Read one byte past the limit to find out if the limit is exceeded.
+        let mut bytes = vec![];
+        let result = data.open().take(limit.as_u64() + 1).read_to_end(&mut bytes);
+        let (status, error) = match result {
+            Err(e) => (Status::InternalServerError, VerifyError::Io(e)),
+            Ok(n) if n as u64 > limit.as_u64() => {
+                (Status::PayloadTooLarge, VerifyError::TooLarge(limit))
+            }
+            Ok(_) => match verify(request, &bytes) {
+                Ok(checked) => return Success(Verified { bytes: bytes, checked: checked }),
+                Err(e) => (Status::BadRequest, e)
+            }
+        };
+
+        error_!("Failed to verify the request body: {}", error);
+        Failure((status, error))
+    }
+}
@@ -0,0 +1,78 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use response::{self, Responder, Body};
+use http::{Digest, Status};
+
+/// Adds a `Digest` header carrying the `SHA-256` digest of the body of a
+/// response so that download clients can verify its integrity.
+///
+/// The wrapped value is both the `Responder` and the reader of the body it
+/// responds with, such as a `File` or a
+/// [NamedFile](/rocket/response/struct.NamedFile.html). Before responding, the
+/// reader is read from its current position to its end, digesting the bytes as
+/// they're read, and then rewound. The body is never buffered in memory, so
+/// this is suitable for large downloads. Since the length of the body is known
+/// once it has been digested, a streamed body is sent with a `Content-Length`
+/// instead.
+///
+/// The wrapped `Responder` must respond with the reader's remaining contents as
+/// its body, as `File` and `NamedFile` do; the status and other headers of the
+/// response are left untouched. If the reader can't be read or rewound, the
+/// response is a `500 Internal Server Error`.
+///
+/// Bodies that can't be rewound, such as a [Stream](struct.Stream.html) of a
+/// pipe, could only be digested as they're sent, with the digest following the
+/// body as a chunked trailer. Trailers aren't supported, so such bodies can't
+/// be checksummed; use the [digest](/rocket/hook/fn.digest.html) response hook
+/// for small bodies instead.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use std::io;
+/// use rocket::response::{NamedFile, Checksummed};
+///
+/// #[get("/download/latest.iso")]
+/// fn download() -> io::Result<Checksummed<NamedFile>> {
+///     NamedFile::open("static/latest.iso").map(Checksummed)
+/// }
+/// # fn main() { }
+/// ```
+#[derive(Debug)]
+pub struct Checksummed<R>(pub R);
+
+/// Digests the wrapped reader, rewinds it, and responds with the wrapped
+/// `Responder`, adding a `Digest` header and sizing its body.
+impl<'r, R: Read + Seek + Responder<'r>> Responder<'r> for Checksummed<R> {
+    fn respond(self) -> response::Result<'r> {
+        let mut reader = self.0;
+        let digested = reader.seek(SeekFrom::Current(0)).and_then(|start| {
+            let (digest, size) = Digest::sha256_reader(&mut reader)?;
+            reader.seek(SeekFrom::Start(start))?;
+            Ok((digest, size))
+        });
+
+        let (digest, size) = match digested {
+            Ok(digested) => digested,
+            Err(e) => {
+                error_!("Failed to digest the response body: {:?}", e);
+                return Err(Status::InternalServerError);
+            }
+        };
+
+        let mut response = reader.respond()?;
+        if let Some(body) = response.take_body() {
+            let body = match body {
+                Body::Sized(body, _) | Body::Chunked(body, _) => body,
+            };
+
+            response.set_raw_body(Body::Sized(body.take(size), size));
+            response.set_header(digest);
+        }
+
+        Ok(response)
+    }
+}
@@ -0,0 +1,144 @@
+use std::io::{self, Read};
+
+use response::{self, Response, Responder, DEFAULT_CHUNK_SIZE};
+
+/// Streams a response to the client from an iterator of byte chunks.
+///
+/// The wrapped value can be anything that turns into an iterator whose items
+/// are byte buffers, such as `Vec<u8>`, `String`, or `&[u8]`, including the
+/// `Receiver` half of a channel. The client is sent a "chunked" response, and
+/// each item is flushed to the client as soon as the iterator produces it, so
+/// long-running computations can send their output progressively. Items longer
+/// than [DEFAULT_CHUNK_SIZE](/rocket/response/constant.DEFAULT_CHUNK_SIZE.html)
+/// are sent in several chunks, and empty items are skipped. The response ends
+/// when the iterator does.
+///
+/// Note that the thread handling the request is occupied until the iterator
+/// ends.
+///
+/// # Example
+///
+/// Stream the progress of a long-running job, produced by another thread, as
+/// it's made:
+///
+/// ```rust
+/// use std::thread;
+/// use std::sync::mpsc::{channel, Receiver};
+/// use rocket::response::Chunks;
+///
+/// fn progress() -> Chunks<Receiver<String>> {
+///     let (sender, receiver) = channel();
+///     thread::spawn(move || {
+///         for step in 1..4 {
+///             // ... do some work ...
+///             sender.send(format!("step {} of 3 done\n", step)).unwrap();
+///         }
+///     });
+///
+///     Chunks(receiver)
+/// }
+/// # let _ = progress();
+/// ```
+#[derive(Debug)]
+pub struct Chunks<I>(pub I);
+
+/// A reader of the items of an iterator of byte buffers, reading at most one
+/// item per read.
+struct ChunksReader<I: Iterator> {
+    chunks: I,
+    chunk: Option<I::Item>,
+    position: usize,
+}
+
+impl<I: Iterator> Read for ChunksReader<I> where I::Item: AsRef<[u8]> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // An empty read signals the end of the body, so skip empty items.
+        while self.chunk.as_ref().map_or(true, |c| self.position == c.as_ref().len()) {
+            self.chunk = match self.chunks.next() {
+                Some(chunk) => Some(chunk),
+                None => return Ok(0)
+            };
+
+            self.position = 0;
+        }
+
+        let n = match self.chunk {
+            Some(ref chunk) => (&chunk.as_ref()[self.position..]).read(buf)?,
+            None => 0
+        };
+
+        self.position += n;
+        Ok(n)
+    }
+}
+
+/// Streams each item of the wrapped iterator, as it's produced, using chunked
+/// transfer encoding.
+impl<'r, I> Responder<'r> for Chunks<I>
+    where I: IntoIterator, I::IntoIter: 'r, I::Item: AsRef<[u8]>
+{
+    fn respond(self) -> response::Result<'r> {
+        let reader = ChunksReader { chunks: self.0.into_iter(), chunk: None, position: 0 };
+        Response::build().flushed_body(reader, DEFAULT_CHUNK_SIZE).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Chunks, ChunksReader};
+    use response::{Responder, Body, DEFAULT_CHUNK_SIZE};
+
+    use std::io::Read;
+    use std::sync::mpsc::channel;
+
+    fn reads<I>(chunks: I, size: usize) -> Vec<Vec<u8>>
+        where I: Iterator, I::Item: AsRef<[u8]>
+    {
+        let mut reader = ChunksReader { chunks: chunks, chunk: None, position: 0 };
+        let mut buf = vec![0; size];
+        let mut reads = vec![];
+        loop {
+            match reader.read(&mut buf).unwrap() {
+                0 => return reads,
+                n => reads.push(buf[..n].to_vec())
+            }
+        }
+    }
+
+    #[test]
+    fn test_one_chunk_per_read() {
+        let chunks = vec!["hello", "", "world", "!"].into_iter();
+        assert_eq!(reads(chunks, 1024),
+                   vec![b"hello".to_vec(), b"world".to_vec(), b"!".to_vec()]);
+
+        let chunks = vec![vec![1u8; 10], vec![], vec![2u8; 3]].into_iter();
+        assert_eq!(reads(chunks, 4), vec![vec![1; 4], vec![1; 4], vec![1; 2], vec![2; 3]]);
+
+        assert!(reads(Vec::<Vec<u8>>::new().into_iter(), 8).is_empty());
+        assert!(reads(vec!["", ""].into_iter(), 8).is_empty());
+    }
+
+    #[test]
+    fn test_receiver() {
+        let (sender, receiver) = channel();
+        sender.send(b"a".to_vec()).unwrap();
+        sender.send(b"bc".to_vec()).unwrap();
+        drop(sender);
+
+        let mut response = Chunks(receiver).respond().unwrap();
+        match response.body() {
+            Some(body) => {
+                assert!(body.is_chunked());
+                assert_eq!(body.into_string(), Some("abc".to_string()));
+            }
+            None => panic!("expected a body")
+        }
+
+        let mut response = Chunks(vec!["x"]).respond().unwrap();
+        assert!(response.flushes_body());
+        match response.take_body() {
+            Some(Body::Chunked(_, size)) => assert_eq!(size, DEFAULT_CHUNK_SIZE),
+            _ => panic!("expected a chunked body")
+        }
+    }
+}
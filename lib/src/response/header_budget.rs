@@ -0,0 +1,244 @@
+use std::ascii::AsciiExt;
+use std::borrow::Cow;
+
+use response::Response;
+
+/// The number of bytes a header line occupies beyond its name and value: the
+/// `: ` separator and the trailing `\r\n`.
+const LINE_OVERHEAD: usize = 4;
+
+/// Returns the number of bytes the header line `name: value` occupies in a
+/// response head.
+#[inline]
+fn line_size(name: &str, value: &str) -> usize {
+    name.len() + value.len() + LINE_OVERHEAD
+}
+
+/// A cap on the total size and number of the headers of outgoing responses.
+///
+/// A bug, such as a loop that sets a cookie per iteration, can produce a
+/// response head that's megabytes large, which clients and proxies reject or,
+/// worse, choke on. When a budget is installed via
+/// [Rocket::header_budget](/rocket/struct.Rocket.html#method.header_budget),
+/// every response's headers are measured after the response hooks run. A
+/// header line counts as one header, and its size is that of its name and
+/// value plus four bytes for the `: ` separator and line ending.
+///
+/// A response over budget is handled as follows:
+///
+///   1. The values of the headers marked as truncatable via
+///      [truncate](#method.truncate) are dropped, last value first, in the
+///      order the headers were marked, until the response is within budget.
+///      Each drop is logged.
+///   2. If the response is still over budget, it's discarded, an error is
+///      logged, and the `500` error catcher responds instead.
+///
+/// The default budget allows 100 headers totaling 16KiB, and no header is
+/// truncatable.
+///
+/// # Example
+///
+/// Allow 8KiB of headers, dropping surplus cookies and `Link` headers before
+/// giving up on a response:
+///
+/// ```rust
+/// use rocket::response::HeaderBudget;
+///
+/// # if false {
+/// rocket::ignite()
+///     .header_budget(HeaderBudget::new()
+///         .max_size(8 * 1024)
+///         .truncate("Set-Cookie")
+///         .truncate("Link"))
+///     .launch()
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderBudget {
+    max_size: usize,
+    max_count: usize,
+    truncatable: Vec<Cow<'static, str>>,
+}
+
+impl HeaderBudget {
+    /// Returns the default budget: 100 headers totaling 16KiB, with no
+    /// truncatable headers.
+    #[inline]
+    pub fn new() -> HeaderBudget {
+        HeaderBudget {
+            max_size: 16 * 1024,
+            max_count: 100,
+            truncatable: vec![],
+        }
+    }
+
+    /// Sets the maximum total size, in bytes, of a response's headers.
+    #[inline]
+    pub fn max_size(mut self, bytes: usize) -> Self {
+        self.max_size = bytes;
+        self
+    }
+
+    /// Sets the maximum number of header lines in a response.
+    #[inline]
+    pub fn max_count(mut self, count: usize) -> Self {
+        self.max_count = count;
+        self
+    }
+
+    /// Marks the header named `name` as truncatable: its values may be
+    /// dropped to bring a response within budget. Headers are truncated in
+    /// the order they're marked.
+    pub fn truncate<N: Into<Cow<'static, str>>>(mut self, name: N) -> Self {
+        let name = name.into();
+        if !self.truncatable.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+            self.truncatable.push(name);
+        }
+
+        self
+    }
+
+    /// Returns the total size and number of the header lines of `response`.
+    fn usage(response: &Response) -> (usize, usize) {
+        response.headers().fold((0, 0), |(size, count), header| {
+            (size + line_size(&header.name, &header.value), count + 1)
+        })
+    }
+
+    #[inline]
+    fn fits(&self, (size, count): (usize, usize)) -> bool {
+        size <= self.max_size && count <= self.max_count
+    }
+
+    /// Brings the headers of `response` within this budget by truncating the
+    /// truncatable headers. Returns an error describing the overrun if that
+    /// doesn't suffice, in which case `response` may have been truncated. This
+    /// should only be used internally by `Rocket`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    /// use rocket::response::HeaderBudget;
+    ///
+    /// let mut response = Response::new();
+    /// for i in 0..5 {
+    ///     response.adjoin_raw_header("Set-Cookie", format!("c{}=v", i));
+    /// }
+    ///
+    /// let budget = HeaderBudget::new().max_count(3);
+    /// assert!(budget.apply(&mut response).is_err());
+    ///
+    /// budget.truncate("Set-Cookie").apply(&mut response).unwrap();
+    /// let cookies: Vec<_> = response.header_values("Set-Cookie").collect();
+    /// assert_eq!(cookies, vec!["c0=v", "c1=v", "c2=v"]);
+    /// ```
+    #[doc(hidden)]
+    pub fn apply(&self, response: &mut Response) -> Result<(), String> {
+        let mut usage = HeaderBudget::usage(response);
+        for name in &self.truncatable {
+            if self.fits(usage) {
+                break;
+            }
+
+            // Header names are case-insensitive: keep the response's casing.
+            let actual_name = match response.headers().find(|h| h.name.eq_ignore_ascii_case(name)) {
+                Some(header) => header.name.into_owned(),
+                None => continue
+            };
+
+            let mut values = response.take_header(name);
+            while !values.is_empty() && !self.fits(usage) {
+                let value = values.pop().expect("non-empty values");
+                usage = (usage.0 - line_size(&actual_name, &value), usage.1 - 1);
+                warn_!("Dropped a '{}' header to stay within the header budget.", actual_name);
+            }
+
+            for value in values {
+                response.adjoin_raw_header(actual_name.clone(), value);
+            }
+        }
+
+        if self.fits(usage) {
+            Ok(())
+        } else {
+            Err(format!("{} headers totaling {} bytes exceed the budget of {} headers, {} bytes",
+                        usage.1, usage.0, self.max_count, self.max_size))
+        }
+    }
+}
+
+impl Default for HeaderBudget {
+    #[inline]
+    fn default() -> HeaderBudget {
+        HeaderBudget::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ascii::AsciiExt;
+
+    use super::HeaderBudget;
+    use response::Response;
+
+    fn response(cookies: usize) -> Response<'static> {
+        let mut response = Response::new();
+        response.set_raw_header("Content-Type", "text/plain");
+        for i in 0..cookies {
+            response.adjoin_raw_header("Set-Cookie", format!("cookie{}=value", i));
+        }
+
+        response
+    }
+
+    #[test]
+    fn test_within_budget() {
+        let mut response = response(10);
+        assert!(HeaderBudget::new().apply(&mut response).is_ok());
+        assert_eq!(response.headers().count(), 11);
+    }
+
+    #[test]
+    fn test_size_budget() {
+        // "Content-Type: text/plain\r\n" is 26 bytes; the first cookies' are 27.
+        let budget = HeaderBudget::new().max_size(26 + 2 * 27);
+        assert!(budget.apply(&mut response(2)).is_ok());
+        assert!(budget.apply(&mut response(3)).is_err());
+
+        let mut response = response(50);
+        budget.truncate("set-cookie").apply(&mut response).unwrap();
+        assert_eq!(response.header_values("Set-Cookie").count(), 2);
+        assert_eq!(response.header_values("Set-Cookie").last(), Some("cookie1=value"));
+    }
+
+    #[test]
+    fn test_truncation_is_case_insensitive() {
+        let mut response = response(2);
+        response.adjoin_raw_header("set-cookie", "lower=value");
+
+        let budget = HeaderBudget::new().max_count(2).truncate("SET-COOKIE");
+        budget.apply(&mut response).unwrap();
+        assert_eq!(response.headers().count(), 2);
+        let cookies = response.headers().filter(|h| h.name.eq_ignore_ascii_case("Set-Cookie"));
+        assert_eq!(cookies.count(), 1);
+    }
+
+    #[test]
+    fn test_truncation_order() {
+        let mut response = response(2);
+        response.adjoin_raw_header("Link", "</a>; rel=preload");
+        response.adjoin_raw_header("Link", "</b>; rel=preload");
+
+        let budget = HeaderBudget::new().max_count(3).truncate("Link").truncate("Set-Cookie");
+        budget.apply(&mut response).unwrap();
+        assert_eq!(response.header_values("Link").count(), 0);
+        assert_eq!(response.header_values("Set-Cookie").count(), 2);
+
+        // Truncating every truncatable header may not suffice.
+        let mut response = self::response(2);
+        let budget = HeaderBudget::new().max_count(0).truncate("Set-Cookie");
+        assert!(budget.apply(&mut response).is_err());
+        assert!(!response.headers().any(|h| h.name == "Set-Cookie"));
+    }
+}
@@ -24,19 +24,28 @@ mod redirect;
 mod flash;
 mod named_file;
 mod stream;
+mod chunks;
 mod response;
 mod failure;
+mod throttle;
+mod checksum;
+mod header_budget;
 
 pub mod content;
 pub mod status;
 
 pub use self::response::{Response, ResponseBuilder, Body, DEFAULT_CHUNK_SIZE};
 pub use self::responder::Responder;
+#[doc(hidden)] pub use self::responder::set_derived_header;
 pub use self::redirect::Redirect;
 pub use self::flash::Flash;
 pub use self::named_file::NamedFile;
 pub use self::stream::Stream;
+pub use self::chunks::Chunks;
 pub use self::failure::Failure;
+pub use self::throttle::Throttled;
+pub use self::checksum::Checksummed;
+pub use self::header_budget::HeaderBudget;
 #[doc(inline)] pub use self::content::Content;
 
 /// Type alias for the `Result` of a `Responder::respond` call.
@@ -1,6 +1,5 @@
 use response::{Response, Responder};
-use http::hyper::header;
-use http::Status;
+use http::{Status, ContentLength, Location};
 
 /// An empty redirect response to a given URL.
 ///
@@ -102,8 +101,8 @@ impl Responder<'static> for Redirect {
     fn respond(self) -> Result<Response<'static>, Status> {
         Response::build()
             .status(self.0)
-            .header(header::ContentLength(0))
-            .header(header::Location(self.1.clone()))
+            .header(ContentLength(0))
+            .header(Location(self.1.clone()))
             .ok()
     }
 }
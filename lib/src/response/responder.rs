@@ -2,7 +2,7 @@ use std::fs::File;
 use std::io::Cursor;
 use std::fmt;
 
-use http::{Status, ContentType};
+use http::{Status, ContentType, Header};
 use response::{Response, Stream};
 
 /// Trait implemented by types that generate responses for clients.
@@ -75,6 +75,56 @@ use response::{Response, Stream};
 ///     to the client. If the `Result` is `Err`, the wrapped `Err` responder is
 ///     used to respond to the client.
 ///
+/// # Deriving
+///
+/// `Responder` can be derived, via `rocket_codegen`, for structures and enums
+/// that wrap another responder, setting the status, Content-Type, and headers
+/// of its response. The field without a `#[response(header)]` attribute is
+/// the wrapped responder; every other field is sent as a header. The status
+/// and Content-Type are set via a `#[response(..)]` attribute on the structure
+/// or on each variant of the enum; one on an enum applies to the variants
+/// without their own. A unit variant responds with an empty body.
+///
+/// ```rust
+/// # #![feature(plugin, custom_derive)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// use rocket::http::ContentType;
+///
+/// #[derive(Responder)]
+/// enum ApiError {
+///     #[response(status = 404)]
+///     NotFound(String),
+///     #[response(status = 400, content_type = "application/json")]
+///     Invalid(String),
+///     #[response(status = 503)]
+///     Unavailable {
+///         message: &'static str,
+///         #[response(header = "Retry-After")]
+///         retry_after: u32,
+///     },
+///     #[response(status = 401)]
+///     Unauthorized,
+/// }
+///
+/// #[derive(Responder)]
+/// #[response(status = 201)]
+/// struct Created {
+///     body: String,
+///     #[response(header = "Location")]
+///     location: String,
+///     #[response(header)]
+///     format: ContentType,
+/// }
+/// # fn main() { }
+/// ```
+///
+/// A header whose name is given is set to the field's `Display` value; a
+/// header without a name is set from the field's `Into<Header>` conversion.
+/// Headers set by the wrapper replace those set by the wrapped responder. If a
+/// header's value is invalid, such as one with a line break in it, the
+/// response fails with `500 Internal Server Error`.
+///
 /// # Implementation Tips
 ///
 /// This section describes a few best practices to take into account when
@@ -161,6 +211,19 @@ pub trait Responder<'r> {
     fn respond(self) -> Result<Response<'r>, Status>;
 }
 
+/// Sets `header` in `response`, failing with `500 Internal Server Error` if
+/// it's invalid. This is used by the code generated for `#[derive(Responder)]`
+/// and should not be used directly.
+#[doc(hidden)]
+pub fn set_derived_header<'r, 'h: 'r, H>(response: &mut Response<'r>, header: H)
+        -> Result<(), Status> where H: Into<Header<'h>>
+{
+    response.try_set_header(header).map(|_| ()).map_err(|e| {
+        error_!("Responder header is invalid: {}.", e);
+        Status::InternalServerError
+    })
+}
+
 /// Returns a response with Content-Type `text/plain` and a fixed-size body
 /// containing the string `self`. Always returns `Ok`.
 ///
@@ -1,9 +1,11 @@
 use std::{io, fmt, str};
+use std::ascii::AsciiExt;
 use std::borrow::Cow;
 
 use http::{Header, HeaderMap};
 use response::Responder;
 use http::Status;
+use body::MessageBody;
 
 /// The default size, in bytes, of a chunk for streamed responses.
 pub const DEFAULT_CHUNK_SIZE: u64 = 4096;
@@ -71,6 +73,27 @@ impl<T: io::Read> Body<T> {
     }
 }
 
+/// A sized body reports its size as its length; a chunked body's length is
+/// unknown.
+impl<T: io::Read> MessageBody for Body<T> {
+    type Reader = T;
+
+    #[inline(always)]
+    fn length_hint(&self) -> Option<u64> {
+        match *self {
+            Body::Sized(_, n) => Some(n),
+            Body::Chunked(..) => None,
+        }
+    }
+
+    #[inline(always)]
+    fn open(self) -> T {
+        match self {
+            Body::Sized(b, _) | Body::Chunked(b, _) => b,
+        }
+    }
+}
+
 impl<T> fmt::Debug for Body<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -205,8 +228,8 @@ impl<'r> ResponseBuilder<'r> {
     ///
     /// The type of `header` can be any type that implements `Into<Header>`.
     /// This includes `Header` itself,
-    /// [ContentType](/rocket/http/struct.ContentType.html) and [hyper::header
-    /// types](/rocket/http/hyper/header/index.html).
+    /// [ContentType](/rocket/http/struct.ContentType.html) and the
+    /// other typed headers in [http](/rocket/http/index.html).
     ///
     /// # Example
     ///
@@ -236,21 +259,22 @@ impl<'r> ResponseBuilder<'r> {
     ///
     /// The type of `header` can be any type that implements `Into<Header>`.
     /// This includes `Header` itself,
-    /// [ContentType](/rocket/http/struct.ContentType.html) and [hyper::header
-    /// types](/rocket/http/hyper/header/index.html).
+    /// [ContentType](/rocket/http/struct.ContentType.html) and the
+    /// other typed headers in [http](/rocket/http/index.html).
     ///
     /// # Example
     ///
     /// ```rust
     /// use rocket::Response;
-    /// use rocket::http::hyper::header::Accept;
+    /// use rocket::http::Allow;
+    /// use rocket::http::Method::*;
     ///
     /// let response = Response::build()
-    ///     .header_adjoin(Accept::json())
-    ///     .header_adjoin(Accept::text())
+    ///     .header_adjoin(Allow(vec![Get, Head]))
+    ///     .header_adjoin(Allow(vec![Post]))
     ///     .finalize();
     ///
-    /// assert_eq!(response.header_values("Accept").count(), 2);
+    /// assert_eq!(response.header_values("Allow").count(), 2);
     /// ```
     #[inline(always)]
     pub fn header_adjoin<'h: 'r, H>(&mut self, header: H) -> &mut ResponseBuilder<'r>
@@ -387,6 +411,31 @@ impl<'r> ResponseBuilder<'r> {
         self
     }
 
+    /// Sets the body of the `Response` to be the streamed `body` with a custom
+    /// chunk size, in bytes, flushing each chunk to the client as soon as it's
+    /// read. See
+    /// [set_flushed_body](struct.Response.html#method.set_flushed_body).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{Read, repeat};
+    /// use rocket::Response;
+    ///
+    /// let response = Response::build()
+    ///     .flushed_body(repeat(97).take(5), 10)
+    ///     .finalize();
+    ///
+    /// assert!(response.flushes_body());
+    /// ```
+    #[inline(always)]
+    pub fn flushed_body<B: io::Read + 'r>(&mut self, body: B, chunk_size: u64)
+            -> &mut ResponseBuilder<'r>
+    {
+        self.response.set_flushed_body(body, chunk_size);
+        self
+    }
+
     /// Merges the `other` `Response` into `self` by setting any fields in
     /// `self` to the corresponding value in `other` if they are set in `other`.
     /// Fields in `self` are unchanged if they are not set in `other`. If a
@@ -515,6 +564,7 @@ pub struct Response<'r> {
     status: Option<Status>,
     headers: HeaderMap<'r>,
     body: Option<Body<Box<io::Read + 'r>>>,
+    flush: bool,
 }
 
 impl<'r> Response<'r> {
@@ -541,6 +591,7 @@ impl<'r> Response<'r> {
             status: None,
             headers: HeaderMap::new(),
             body: None,
+            flush: false,
         }
     }
 
@@ -677,8 +728,8 @@ impl<'r> Response<'r> {
     /// Sets the header `header` in `self`. Any existing headers with the name
     /// `header.name` will be lost, and only `header` will remain. The type of
     /// `header` can be any type that implements `Into<Header>`. This includes
-    /// `Header` itself, [ContentType](/rocket/http/struct.ContentType.html) and
-    /// [hyper::header types](/rocket/http/hyper/header/index.html).
+    /// `Header` itself, [ContentType](/rocket/http/struct.ContentType.html) and the
+    /// other typed headers in [http](/rocket/http/index.html).
     ///
     /// # Example
     ///
@@ -701,6 +752,39 @@ impl<'r> Response<'r> {
         self.headers.replace(header)
     }
 
+    /// Validates and then sets the header `header` in `self` as
+    /// [set_header](#method.set_header) does. If the header's name or value is
+    /// invalid, as determined by
+    /// [Header::validate](/rocket/http/struct.Header.html#method.validate),
+    /// `self` is left unchanged and an `Err` is returned. Otherwise, returns
+    /// whether a header was replaced.
+    ///
+    /// This is the preferred way to set typed headers whose values are derived
+    /// from user input, such as from the fields of a custom `Responder`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    /// use rocket::http::{Header, ContentType, Location};
+    ///
+    /// let mut response = Response::new();
+    /// assert_eq!(response.try_set_header(ContentType::HTML), Ok(false));
+    /// assert_eq!(response.try_set_header(Location("/".into())), Ok(false));
+    ///
+    /// let injected = Header::new("X-Name", "a\r\nSet-Cookie: b");
+    /// assert!(response.try_set_header(injected).is_err());
+    /// assert_eq!(response.headers().count(), 2);
+    /// ```
+    #[inline]
+    pub fn try_set_header<'h: 'r, H>(&mut self, header: H) -> Result<bool, &'static str>
+        where H: Into<Header<'h>>
+    {
+        let header = header.into();
+        header.validate()?;
+        Ok(self.headers.replace(header))
+    }
+
     /// Sets the custom header with name `name` and value `value` in `self`. Any
     /// existing headers with the same `name` will be lost, and the new custom
     /// header will remain. This method should be used sparingly; prefer to use
@@ -733,23 +817,24 @@ impl<'r> Response<'r> {
     /// name `header.name`, another header with the same name and value
     /// `header.value` is added. The type of `header` can be any type that
     /// implements `Into<Header>`. This includes `Header` itself,
-    /// [ContentType](/rocket/http/struct.ContentType.html) and [hyper::header
-    /// types](/rocket/http/hyper/header/index.html).
+    /// [ContentType](/rocket/http/struct.ContentType.html) and the
+    /// other typed headers in [http](/rocket/http/index.html).
     ///
     /// # Example
     ///
     /// ```rust
     /// use rocket::Response;
-    /// use rocket::http::hyper::header::Accept;
+    /// use rocket::http::Allow;
+    /// use rocket::http::Method::*;
     ///
     /// let mut response = Response::new();
-    /// response.adjoin_header(Accept::json());
-    /// response.adjoin_header(Accept::text());
+    /// response.adjoin_header(Allow(vec![Get]));
+    /// response.adjoin_header(Allow(vec![Post]));
     ///
-    /// let mut accept_headers = response.headers();
-    /// assert_eq!(accept_headers.next(), Some(Accept::json().into()));
-    /// assert_eq!(accept_headers.next(), Some(Accept::text().into()));
-    /// assert_eq!(accept_headers.next(), None);
+    /// let mut allow_headers = response.headers();
+    /// assert_eq!(allow_headers.next(), Some(Allow(vec![Get]).into()));
+    /// assert_eq!(allow_headers.next(), Some(Allow(vec![Post]).into()));
+    /// assert_eq!(allow_headers.next(), None);
     /// ```
     #[inline(always)]
     pub fn adjoin_header<'h: 'r, H: Into<Header<'h>>>(&mut self, header: H) {
@@ -760,8 +845,8 @@ impl<'r> Response<'r> {
     /// `self` already contains headers with the name `name`, another header
     /// with the same `name` and `value` is added. The type of `header` can be
     /// any type that implements `Into<Header>`. This includes `Header` itself,
-    /// [ContentType](/rocket/http/struct.ContentType.html) and [hyper::header
-    /// types](/rocket/http/hyper/header/index.html).
+    /// [ContentType](/rocket/http/struct.ContentType.html) and the
+    /// other typed headers in [http](/rocket/http/index.html).
     ///
     /// # Example
     ///
@@ -808,6 +893,68 @@ impl<'r> Response<'r> {
         self.headers.remove(name);
     }
 
+    /// Adds `name` to the `Vary` header of `self`, indicating that `self`
+    /// depends on the value of the request header named `name`. The existing
+    /// `Vary` headers, if any, are merged into a single header: names already
+    /// present, compared case-insensitively, aren't added again, and `*`
+    /// replaces all other names.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    ///
+    /// let mut response = Response::new();
+    /// response.adjoin_raw_header("Vary", "Accept");
+    /// response.vary("Accept-Language");
+    /// response.vary("accept");
+    ///
+    /// let vary: Vec<_> = response.header_values("Vary").collect();
+    /// assert_eq!(vary, vec!["Accept, Accept-Language"]);
+    ///
+    /// response.vary("*");
+    /// assert_eq!(response.header_values("Vary").next(), Some("*"));
+    /// ```
+    pub fn vary(&mut self, name: &str) {
+        let name = name.trim();
+        let mut names: Vec<String> = self.headers.get("Vary")
+            .flat_map(|value| value.split(','))
+            .map(|n| n.trim())
+            .filter(|n| !n.is_empty())
+            .map(|n| n.to_string())
+            .collect();
+
+        if name == "*" || names.iter().any(|n| n == "*") {
+            names = vec!["*".to_string()];
+        } else if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            names.push(name.to_string());
+        }
+
+        self.set_raw_header("Vary", names.join(", "));
+    }
+
+    /// Removes all headers with the name `name`, compared case-insensitively,
+    /// and returns their values in FIFO order, or an empty vector if there are
+    /// none. Useful for rewriting a header without cloning its values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::Response;
+    ///
+    /// let mut response = Response::new();
+    /// response.adjoin_raw_header("X-Custom", "one");
+    /// response.adjoin_raw_header("X-Custom", "two");
+    ///
+    /// let values = response.take_header("x-custom");
+    /// assert_eq!(values, vec!["one", "two"]);
+    /// assert_eq!(response.headers().count(), 0);
+    /// ```
+    #[inline(always)]
+    pub fn take_header(&mut self, name: &str) -> Vec<Cow<'r, str>> {
+        self.headers.take(name)
+    }
+
     /// Returns a mutable borrow of the body of `self`, if there is one. The
     /// body is borrowed mutably to allow for reading.
     ///
@@ -907,6 +1054,7 @@ impl<'r> Response<'r> {
         body.seek(io::SeekFrom::Start(0))
             .expect("Attempted to reset body by seeking after getting size.");
         self.body = Some(Body::Sized(Box::new(body.take(size)), size));
+        self.flush = false;
     }
 
     /// Sets the body of `self` to be `body`, which will be streamed. The chunk
@@ -932,7 +1080,8 @@ impl<'r> Response<'r> {
     }
 
     /// Sets the body of `self` to be `body`, which will be streamed with chunk
-    /// size `chunk_size`.
+    /// size `chunk_size`. A `chunk_size` of `0` is treated as
+    /// [DEFAULT_CHUNK_SIZE](/rocket/response/constant.DEFAULT_CHUNK_SIZE.html).
     ///
     /// # Example
     ///
@@ -949,12 +1098,71 @@ impl<'r> Response<'r> {
     #[inline(always)]
     pub fn set_chunked_body<B>(&mut self, body: B, chunk_size: u64)
             where B: io::Read + 'r {
+        // A zero-sized chunk would end the response before the body is sent.
+        let chunk_size = match chunk_size {
+            0 => DEFAULT_CHUNK_SIZE,
+            n => n
+        };
+
         self.body = Some(Body::Chunked(Box::new(body), chunk_size));
+        self.flush = false;
+    }
+
+    /// Sets the body of `self` to be `body`, which will be streamed with chunk
+    /// size `chunk_size`, like [set_chunked_body](#method.set_chunked_body),
+    /// but flushes each chunk to the client as soon as it's read instead of
+    /// filling the chunk first. Use this for bodies that are produced
+    /// incrementally, such as event or record streams, whose clients should
+    /// see each piece of the body promptly. Bodies that are read quickly, such
+    /// as files, should use `set_chunked_body`, which writes fewer, larger
+    /// chunks.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{Read, repeat};
+    /// use rocket::Response;
+    ///
+    /// let mut response = Response::new();
+    /// response.set_flushed_body(repeat(97).take(5), 10);
+    /// assert!(response.flushes_body());
+    ///
+    /// let body_string = response.body().and_then(|b| b.into_string());
+    /// assert_eq!(body_string, Some("aaaaa".to_string()));
+    /// ```
+    #[inline(always)]
+    pub fn set_flushed_body<B>(&mut self, body: B, chunk_size: u64)
+            where B: io::Read + 'r {
+        self.set_chunked_body(body, chunk_size);
+        self.flush = true;
+    }
+
+    /// Returns `true` if the body of `self` is streamed and each chunk is
+    /// flushed to the client as soon as it's read.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::{Read, repeat};
+    /// use rocket::Response;
+    ///
+    /// let mut response = Response::new();
+    /// response.set_chunked_body(repeat(97).take(5), 10);
+    /// assert!(!response.flushes_body());
+    ///
+    /// response.set_flushed_body(repeat(97).take(5), 10);
+    /// assert!(response.flushes_body());
+    /// ```
+    #[inline(always)]
+    pub fn flushes_body(&self) -> bool {
+        self.flush && self.body.as_ref().map_or(false, |body| body.is_chunked())
     }
 
     /// Sets the body of `self` to be `body`. This method should typically not
     /// be used, opting instead for one of `set_sized_body`,
-    /// `set_streamed_body`, or `set_chunked_body`.
+    /// `set_streamed_body`, or `set_chunked_body`. Whether chunks are flushed
+    /// is kept, so that a body taken from `self` and wrapped is written as the
+    /// original body would have been.
     ///
     /// # Example
     ///
@@ -978,6 +1186,34 @@ impl<'r> Response<'r> {
         });
     }
 
+    /// Sets the body of `self` to be `body`, a
+    /// [MessageBody](/rocket/body/trait.MessageBody.html) such as an adapted
+    /// body. The body is sized if its length is known and streamed otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use rocket::Response;
+    /// use rocket::body::MessageBody;
+    /// use rocket::response::Body;
+    /// use rocket::config::ByteUnit;
+    ///
+    /// let body = Body::Sized(Cursor::new("Hello!"), 6).limit(ByteUnit::new(1024));
+    ///
+    /// let mut response = Response::new();
+    /// response.set_message_body(body);
+    ///
+    /// let body_string = response.body().and_then(|b| b.into_string());
+    /// assert_eq!(body_string, Some("Hello!".to_string()));
+    /// ```
+    pub fn set_message_body<B: MessageBody>(&mut self, body: B) where B::Reader: 'r {
+        match body.length_hint() {
+            Some(n) => self.set_raw_body(Body::Sized(body.open(), n)),
+            None => self.set_streamed_body(body.open()),
+        }
+    }
+
     /// Replaces this response's status and body with that of `other`, if they
     /// exist in `other`. Any headers that exist in `other` replace the ones in
     /// `self`. Any in `self` that aren't in `other` remain in `self`.
@@ -1020,6 +1256,7 @@ impl<'r> Response<'r> {
 
         if let Some(body) = other.body {
             self.body = Some(body);
+            self.flush = other.flush;
         }
 
         for (name, values) in other.headers.into_iter_raw() {
@@ -1069,6 +1306,7 @@ impl<'r> Response<'r> {
 
         if self.body.is_none() {
             self.body = other.body;
+            self.flush = other.flush;
         }
 
         for (name, mut values) in other.headers.into_iter_raw() {
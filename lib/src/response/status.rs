@@ -11,8 +11,7 @@ use std::hash::{Hash, Hasher};
 use std::collections::hash_map::DefaultHasher;
 
 use response::{Responder, Response};
-use http::hyper::header;
-use http::Status;
+use http::{Status, ETag, Location, RetryAfter};
 
 /// Sets the status of the response to 201 (Created).
 ///
@@ -45,7 +44,7 @@ impl<'r, R: Responder<'r>> Responder<'r> for Created<R> {
             build.merge(responder.respond()?);
         }
 
-        build.status(Status::Created).header(header::Location(self.0)).ok()
+        build.status(Status::Created).header(Location(self.0)).ok()
     }
 }
 
@@ -62,10 +61,10 @@ impl<'r, R: Responder<'r> + Hash> Responder<'r> for Created<R> {
             let hash = hasher.finish().to_string();
 
             build.merge(responder.respond()?);
-            build.header(header::ETag(header::EntityTag::strong(hash)));
+            build.header(ETag::strong(hash));
         }
 
-        build.status(Status::Created).header(header::Location(self.0)).ok()
+        build.status(Status::Created).header(Location(self.0)).ok()
     }
 }
 
@@ -172,5 +171,71 @@ impl<'r, R: Responder<'r>> Responder<'r> for Custom<R> {
     }
 }
 
+/// Sets the status of the response to 429 (Too Many Requests).
+///
+/// The `RetryAfter` field is set as the value of the `Retry-After` header,
+/// telling the client how long to wait before making another request. The
+/// optional `Responder` field is used to finalize the response.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use rocket::response::status;
+///
+/// let delay = Duration::from_secs(30).into();
+/// let response = status::TooManyRequests(delay, Some("Slow down!"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TooManyRequests<R>(pub RetryAfter, pub Option<R>);
+
+/// Sets the status code of the response to 429 Too Many Requests and the
+/// `Retry-After` header to the `RetryAfter` parameter. If the responder is
+/// `Some`, it is used to finalize the response.
+impl<'r, R: Responder<'r>> Responder<'r> for TooManyRequests<R> {
+    fn respond(self) -> Result<Response<'r>, Status> {
+        let mut build = Response::build();
+        if let Some(responder) = self.1 {
+            build.merge(responder.respond()?);
+        }
+
+        build.status(Status::TooManyRequests).header(self.0).ok()
+    }
+}
+
+/// Sets the status of the response to 503 (Service Unavailable).
+///
+/// The `RetryAfter` field is set as the value of the `Retry-After` header,
+/// telling the client when the service is expected to be available again, as
+/// when it's down for maintenance. The optional `Responder` field is used to
+/// finalize the response.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::{Duration, SystemTime};
+/// use rocket::response::status;
+/// use rocket::http::RetryAfter;
+///
+/// let back_at = SystemTime::now() + Duration::from_secs(3600);
+/// let response = status::ServiceUnavailable(RetryAfter::Date(back_at), Some("Be right back."));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceUnavailable<R>(pub RetryAfter, pub Option<R>);
+
+/// Sets the status code of the response to 503 Service Unavailable and the
+/// `Retry-After` header to the `RetryAfter` parameter. If the responder is
+/// `Some`, it is used to finalize the response.
+impl<'r, R: Responder<'r>> Responder<'r> for ServiceUnavailable<R> {
+    fn respond(self) -> Result<Response<'r>, Status> {
+        let mut build = Response::build();
+        if let Some(responder) = self.1 {
+            build.merge(responder.respond()?);
+        }
+
+        build.status(Status::ServiceUnavailable).header(self.0).ok()
+    }
+}
+
 // The following are unimplemented.
 // 206 Partial Content (variant), 203 Non-Authoritative Information (headers).
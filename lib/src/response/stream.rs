@@ -7,10 +7,17 @@ use http::Status;
 /// Streams a response to a client from an arbitrary `Read`er type.
 ///
 /// The client is sent a "chunked" response, where the chunk size is at most
-/// 4KiB. This means that at most 4KiB are stored in memory while the response
-/// is being sent. This type should be used when sending responses that are
-/// arbitrarily large in size, such as when streaming from a local socket.
-pub struct Stream<T: Read>(T, u64);
+/// 4KiB by default, or the size given to [chunked](#method.chunked). This
+/// means that at most one chunk is stored in memory while the response is
+/// being sent: the reader is never buffered in full. This type should be used
+/// when sending responses that are arbitrarily large in size, such as when
+/// streaming from a local socket, proxying a large payload, or sending an
+/// export as it is generated.
+///
+/// Each chunk is filled before it's sent. Streams whose readers produce data
+/// slowly, a little at a time, should be created with
+/// [flushing](#method.flushing) so that the client sees each read promptly.
+pub struct Stream<T: Read>(T, u64, bool);
 
 impl<T: Read> Stream<T> {
     /// Create a new stream from the given `reader`.
@@ -27,11 +34,38 @@ impl<T: Read> Stream<T> {
     /// let response = Stream::from(io::stdin());
     /// ```
     pub fn from(reader: T) -> Stream<T> {
-        Stream(reader, DEFAULT_CHUNK_SIZE)
+        Stream(reader, DEFAULT_CHUNK_SIZE, false)
+    }
+
+    /// Create a new stream from the given `reader` that sends whatever each
+    /// read of `reader` returns to the client right away, as a chunk of its
+    /// own, instead of filling each chunk first. Chunks are at most
+    /// [DEFAULT_CHUNK_SIZE](/rocket/response/constant.DEFAULT_CHUNK_SIZE.html)
+    /// bytes.
+    ///
+    /// # Example
+    ///
+    /// Stream the output of a long-running command as it's produced.
+    ///
+    /// ```rust
+    /// # use std::io;
+    /// use std::process::{Command, Stdio};
+    /// use rocket::response::Stream;
+    ///
+    /// # fn test() -> io::Result<()> {
+    /// let child = Command::new("make").stdout(Stdio::piped()).spawn()?;
+    /// let response = Stream::flushing(child.stdout.unwrap());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn flushing(reader: T) -> Stream<T> {
+        Stream(reader, DEFAULT_CHUNK_SIZE, true)
     }
 
     /// Create a new stream from the given `reader` and sets the chunk size for
-    /// each streamed chunk to `chunk_size` bytes.
+    /// each streamed chunk to `chunk_size` bytes. A `chunk_size` of `0` is
+    /// treated as
+    /// [DEFAULT_CHUNK_SIZE](/rocket/response/constant.DEFAULT_CHUNK_SIZE.html).
     ///
     /// # Example
     ///
@@ -45,7 +79,42 @@ impl<T: Read> Stream<T> {
     /// let response = Stream::chunked(io::stdin(), 10);
     /// ```
     pub fn chunked(reader: T, chunk_size: u64) -> Stream<T> {
-        Stream(reader, chunk_size)
+        match chunk_size {
+            0 => Stream(reader, DEFAULT_CHUNK_SIZE, false),
+            n => Stream(reader, n, false)
+        }
+    }
+
+    /// Returns the size, in bytes, of each chunk streamed to the client.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io;
+    /// use rocket::response::{Stream, DEFAULT_CHUNK_SIZE};
+    ///
+    /// assert_eq!(Stream::chunked(io::empty(), 10).chunk_size(), 10);
+    /// assert_eq!(Stream::from(io::empty()).chunk_size(), DEFAULT_CHUNK_SIZE);
+    /// ```
+    #[inline(always)]
+    pub fn chunk_size(&self) -> u64 {
+        self.1
+    }
+
+    /// Consumes the stream and returns the wrapped reader.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::Cursor;
+    /// use rocket::response::Stream;
+    ///
+    /// let stream = Stream::from(Cursor::new("hello"));
+    /// assert_eq!(stream.into_inner().into_inner(), "hello");
+    /// ```
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.0
     }
 }
 
@@ -56,7 +125,9 @@ impl<T: Read + Debug> Debug for Stream<T> {
 }
 
 /// Sends a response to the client using the "Chunked" transfer encoding. The
-/// maximum chunk size is 4KiB.
+/// maximum chunk size is the stream's [chunk_size](#method.chunk_size). Chunks
+/// are flushed as they're read if the stream was created with
+/// [flushing](#method.flushing).
 ///
 /// # Failure
 ///
@@ -65,6 +136,10 @@ impl<T: Read + Debug> Debug for Stream<T> {
 /// to the console with an indication of what went wrong.
 impl<'r, T: Read + 'r> Responder<'r> for Stream<T> {
     fn respond(self) -> Result<Response<'r>, Status> {
-        Response::build().chunked_body(self.0, self.1).ok()
+        if self.2 {
+            Response::build().flushed_body(self.0, self.1).ok()
+        } else {
+            Response::build().chunked_body(self.0, self.1).ok()
+        }
     }
 }
@@ -0,0 +1,110 @@
+use std::io::{self, Read};
+use std::time::{Duration, Instant};
+use std::cmp::min;
+use std::thread;
+
+use response::{self, Responder};
+
+/// Caps the rate at which the body of a response is sent to the client.
+///
+/// The first field is the maximum number of bytes per second to send; the
+/// second is the wrapped `Responder`. Body writes are paced so that, averaged
+/// over the lifetime of the response, no more than the given number of bytes
+/// are sent per second. The status and headers of the wrapped response are
+/// left untouched. A rate of `0` disables throttling.
+///
+/// Throttling is useful for large downloads that would otherwise saturate an
+/// uplink shared with latency-sensitive traffic. Note that the thread handling
+/// the request is occupied for the duration of the response.
+///
+/// # Example
+///
+/// Serve a file at no more than 512KiB/s:
+///
+/// ```rust
+/// use rocket::response::{NamedFile, Throttled};
+///
+/// # #[allow(unused_variables)]
+/// let response = NamedFile::open("large.iso").map(|f| Throttled(512 * 1024, f));
+/// ```
+#[derive(Debug)]
+pub struct Throttled<R>(pub u64, pub R);
+
+/// A reader that sleeps as necessary to limit the rate at which it is read.
+struct ThrottledReader<R> {
+    inner: R,
+    rate: u64,
+    start: Option<Instant>,
+    read: u64,
+}
+
+impl<R: Read> ThrottledReader<R> {
+    fn new(inner: R, rate: u64) -> ThrottledReader<R> {
+        ThrottledReader { inner: inner, rate: rate, start: None, read: 0 }
+    }
+}
+
+impl<R: Read> Read for ThrottledReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let start = match self.start {
+            Some(start) => start,
+            None => {
+                let now = Instant::now();
+                self.start = Some(now);
+                now
+            }
+        };
+
+        // Never read more than a second's worth at once to keep pacing smooth.
+        let max = min(buf.len() as u64, self.rate) as usize;
+        let n = self.inner.read(&mut buf[..max])?;
+        self.read += n as u64;
+
+        // Sleep until the time at which `read` bytes may have been sent.
+        let expected = Duration::from_millis(self.read * 1000 / self.rate);
+        let elapsed = start.elapsed();
+        if expected > elapsed {
+            thread::sleep(expected - elapsed);
+        }
+
+        Ok(n)
+    }
+}
+
+/// Responds with the wrapped `Responder`, replacing its body, if any, with one
+/// that is throttled to the given number of bytes per second.
+impl<'r, R: Responder<'r>> Responder<'r> for Throttled<R> {
+    fn respond(self) -> response::Result<'r> {
+        let (rate, mut response) = (self.0, self.1.respond()?);
+        if rate == 0 {
+            return Ok(response);
+        }
+
+        if let Some(body) = response.take_body() {
+            response.set_raw_body(body.map(|body| ThrottledReader::new(body, rate)));
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ThrottledReader;
+
+    use std::io::Read;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_throttled_read() {
+        let data = vec![7u8; 300];
+        let start = Instant::now();
+
+        let mut reader = ThrottledReader::new(&data[..], 1000);
+        let mut contents = vec![];
+        reader.read_to_end(&mut contents).unwrap();
+
+        assert_eq!(contents, data);
+        assert!(start.elapsed() >= Duration::from_millis(300));
+    }
+}
@@ -1,34 +1,66 @@
 use std::collections::HashMap;
-use std::str::from_utf8_unchecked;
-use std::cmp::min;
-use std::io::{self, Write};
+use std::ascii::AsciiExt;
+use std::borrow::Cow;
+use std::io::{self, Read, Write, Cursor};
+use std::mem;
+use std::sync::Arc;
+use std::cell::RefCell;
+use std::any::TypeId;
+use std::panic::{self, AssertUnwindSafe};
 
 use term_painter::Color::*;
 use term_painter::ToStyle;
 
 use {logger, handler};
 use ext::ReadExt;
-use config::{self, Config};
-use request::{Request, FormItems};
+use config::{self, Config, Environment, ReloadHandler, RedirectRule, LiveConfig};
+use hook::{Predicate, RequestHook, ResponseHook};
+use launch::{self, LaunchInfo, LaunchReporter};
+use report::{self, ErrorReporter, ServerError};
+use shutdown::{self, ShutdownHandle, ShutdownReason, ShutdownListener};
+use metrics::{Metrics, CountingBody};
+use inject::Providers;
+use tenant::{Tenants, TenantSource};
+use state::{State, ManagedState};
+use feature::{FeatureFlags, Disabled};
+use maintenance::Maintenance;
+use request::{Request, Connection, MethodOverride, GuardFailure};
 use data::Data;
-use response::{Body, Response};
-use router::{Router, Route};
+use response::{Body, Response, HeaderBudget};
+use router::{Router, Route, Explanation};
 use catcher::{self, Catcher};
 use outcome::Outcome;
 use error::Error;
 
-use http::{Method, Status};
-use http::hyper::{self, header};
+use http::{Method, Status, StatusClass, Server, ETag, ContentType, Allow, HeaderPolicy, Header};
+use http::hyper::{self, header, HttpVersion};
 use http::uri::URI;
 
 /// The main `Rocket` type: used to mount routes and catchers and launch the
 /// application.
 pub struct Rocket {
-    address: String,
-    port: usize,
+    config: Arc<Config>,
     router: Router,
     default_catchers: HashMap<u16, Catcher>,
     catchers: HashMap<u16, Catcher>,
+    hot_reload: Vec<String>,
+    reload_handlers: Vec<ReloadHandler>,
+    live_config: LiveConfig,
+    request_hooks: Vec<(Predicate, RequestHook)>,
+    response_hooks: Vec<(Predicate, ResponseHook)>,
+    server_options: Option<ResponseHook>,
+    launch_reporter: LaunchReporter,
+    error_reporter: Box<ErrorReporter>,
+    shutdown: ShutdownHandle,
+    header_policy: Option<HeaderPolicy>,
+    header_budget: Option<HeaderBudget>,
+    method_not_allowed: Vec<String>,
+    method_override: MethodOverride,
+    metrics: Metrics,
+    providers: Providers,
+    tenants: Tenants,
+    maintenance: Option<Maintenance>,
+    managed: ManagedState,
 }
 
 #[doc(hidden)]
@@ -42,7 +74,14 @@ impl hyper::Handler for Rocket {
                       hyp_req: hyper::Request<'h, 'k>,
                       res: hyper::FreshResponse<'h>) {
         // Get all of the information from Hyper.
-        let (_, h_method, h_headers, h_uri, _, h_body) = hyp_req.deconstruct();
+        let (h_addr, h_method, h_headers, h_uri, h_version, h_body) = hyp_req.deconstruct();
+
+        // Determine whether the client wants the connection kept open. Once
+        // shutting down, connections are closed after their current response.
+        let mut persistence = Persistence::of(h_version, &h_headers);
+        if self.shutdown.reason().is_some() {
+            persistence.keep_alive = false;
+        }
 
         // Convert the Hyper request into a Rocket request.
         let mut request = match Request::from_hyp(h_method, h_headers, h_uri) {
@@ -51,48 +90,155 @@ impl hyper::Handler for Rocket {
                 error!("Bad incoming request: {}", e);
                 let dummy = Request::new(Method::Get, URI::new("<unknown>"));
                 let r = self.handle_error(Status::InternalServerError, &dummy);
-                return self.issue_response(r, res);
+                let _ = self.issue_response(r, res, persistence);
+                return;
             }
         };
 
+        // Remember the address of the peer that sent the request, and the
+        // connection it arrived on.
+        request.set_remote(h_addr);
+        request.set_connection(current_connection());
+
         // Retrieve the data from the hyper body.
-        let data = match Data::from_hyp(h_body) {
+        let limit = self.config.limits.get("default");
+        let data = match Data::from_hyp(h_body, self.config.read_timeout, limit) {
             Ok(data) => data,
-            Err(reason) => {
+            Err((status, reason)) => {
                 error_!("Bad data in request: {}", reason);
-                let r = self.handle_error(Status::InternalServerError, &request);
-                return self.issue_response(r, res);
+                let r = self.handle_error(status, &request);
+                let _ = self.issue_response(r, res, persistence);
+                return;
             }
         };
 
         // Dispatch the request to get a response, then write that response out.
-        let response = self.dispatch(&mut request, data);
-        self.issue_response(response, res)
+        let result = {
+            let response = self.dispatch(&mut request, data);
+            self.issue_response(response, res, persistence)
+        };
+
+        // Let the request know how writing the response went.
+        request.finish(result.as_ref().map(|_| ()));
+    }
+
+    // Hyper asks whether to send `100 Continue` to requests with an `Expect:
+    // 100-continue` header before the request is handled, and writes out the
+    // status it's given right away, so the decision is made from routing.
+    fn check_continue(&self, hyp_req: (&hyper::Method, &hyper::RequestUri, &header::Headers))
+            -> hyper::StatusCode {
+        let (h_method, h_uri, h_headers) = hyp_req;
+        let mut request = match Request::from_hyp(h_method.clone(), h_headers.clone(),
+                                                  h_uri.clone()) {
+            Ok(request) => request,
+            Err(_) => return hyper::StatusCode::BadRequest
+        };
+
+        let status = self.continue_status(&mut request);
+        hyper::StatusCode::from_u16(status.code)
+    }
+
+    // Hyper handles each connection, with all of its requests, on one thread,
+    // so the connection's storage is kept in a thread local while it's open.
+    fn on_connection_start(&self) {
+        CONNECTION.with(|connection| *connection.borrow_mut() = Some(Connection::new()));
+    }
+
+    fn on_connection_end(&self) {
+        CONNECTION.with(|connection| connection.borrow_mut().take());
+    }
+}
+
+thread_local!(static CONNECTION: RefCell<Option<Connection>> = RefCell::new(None));
+
+/// Returns the connection being handled on this thread, or a new connection if
+/// there isn't one.
+fn current_connection() -> Connection {
+    CONNECTION.with(|cell| {
+        let mut current = cell.borrow_mut();
+        if current.is_none() {
+            *current = Some(Connection::new());
+        }
+
+        current.as_ref().cloned().expect("a current connection")
+    })
+}
+
+/// The most bytes of a chunked body that are buffered to respond to an HTTP/1.0
+/// client. Larger bodies fail with a `500`.
+const HTTP10_BODY_LIMIT: u64 = 8 * 1024 * 1024;
+
+/// How the connection a request arrived on is to be treated when responding.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Persistence {
+    /// Whether the client speaks HTTP/1.0 and so can't receive chunked bodies.
+    http10: bool,
+    /// Whether the connection should be kept open after the response.
+    keep_alive: bool,
+}
+
+impl Persistence {
+    /// HTTP/1.1 connections persist unless the client sends `Connection:
+    /// close`. HTTP/1.0 connections persist only if the client sends
+    /// `Connection: keep-alive`.
+    fn of(version: HttpVersion, headers: &header::Headers) -> Persistence {
+        let http10 = version == HttpVersion::Http10;
+        let keep_alive = match headers.get::<header::Connection>() {
+            Some(c) if c.contains(&header::ConnectionOption::Close) => false,
+            Some(c) if c.contains(&header::ConnectionOption::KeepAlive) => true,
+            _ => !http10
+        };
+
+        Persistence { http10: http10, keep_alive: keep_alive }
     }
 }
 
 impl Rocket {
     #[inline]
-    fn issue_response(&self, mut response: Response, hyp_res: hyper::FreshResponse) {
+    fn issue_response(&self, mut response: Response, hyp_res: hyper::FreshResponse,
+                      persistence: Persistence) -> io::Result<()> {
         // Add the 'rocket' server header, and write out the response.
         // TODO: If removing Hyper, write out `Date` header too.
-        response.set_header(header::Server("rocket".to_string()));
+        response.set_header(Server("rocket".to_string()));
 
-        match self.write_response(response, hyp_res) {
+        let result = self.write_response(response, hyp_res, persistence);
+        match result {
             Ok(_) => info_!("{}", Green.paint("Response succeeded.")),
-            Err(e) => error_!("Failed to write response: {:?}.", e)
+            Err(ref e) => error_!("Failed to write response: {:?}.", e)
         }
+
+        result
     }
 
     fn write_response(&self, mut response: Response,
-                      mut hyp_res: hyper::FreshResponse) -> io::Result<()>
+                      mut hyp_res: hyper::FreshResponse,
+                      persistence: Persistence) -> io::Result<()>
     {
         *hyp_res.status_mut() = hyper::StatusCode::from_u16(response.status().code);
 
         for header in response.headers() {
+            // Never write out a header that could split the response.
+            if let Err(reason) = header.validate() {
+                error_!("Dropping invalid '{}' header: {}", header.name, reason);
+                continue;
+            }
+
+            // Write every value of a multi-valued header, each on its own line.
             let name = header.name.into_owned();
-            let value = vec![header.value.into_owned().into()];
-            hyp_res.headers_mut().set_raw(name, value);
+            let mut values = hyp_res.headers().get_raw(&name)
+                .map(|values| values.to_vec())
+                .unwrap_or(vec![]);
+
+            values.push(header.value.into_owned().into());
+            hyp_res.headers_mut().set_raw(name, values);
+        }
+
+        // Tell the client what will happen to the connection. Hyper closes the
+        // connection after the response when it sees `Connection: close`.
+        if !persistence.keep_alive {
+            hyp_res.headers_mut().set(header::Connection::close());
+        } else if persistence.http10 {
+            hyp_res.headers_mut().set(header::Connection::keep_alive());
         }
 
         if response.body().is_none() {
@@ -100,6 +246,7 @@ impl Rocket {
             return hyp_res.start()?.end();
         }
 
+        let flush = response.flushes_body();
         match response.body() {
             None => {
                 hyp_res.headers_mut().set(header::ContentLength(0));
@@ -111,6 +258,26 @@ impl Rocket {
                 io::copy(body, &mut stream)?;
                 stream.end()
             }
+            Some(Body::Chunked(body, _)) if persistence.http10 => {
+                // HTTP/1.0 clients don't understand chunked encoding, and Hyper
+                // can't close-delimit a body, so buffer it to find its length.
+                // Streamed bodies can be endless: only buffer up to a limit.
+                let mut buffer = vec![];
+                body.take(HTTP10_BODY_LIMIT + 1).read_to_end(&mut buffer)?;
+                if buffer.len() as u64 > HTTP10_BODY_LIMIT {
+                    error_!("Chunked body is too large to buffer for an HTTP/1.0 client.");
+                    *hyp_res.status_mut() = hyper::StatusCode::InternalServerError;
+                    hyp_res.headers_mut().clear();
+                    hyp_res.headers_mut().set(header::Connection::close());
+                    hyp_res.headers_mut().set(header::ContentLength(0));
+                    return hyp_res.start()?.end();
+                }
+
+                hyp_res.headers_mut().set(header::ContentLength(buffer.len() as u64));
+                let mut stream = hyp_res.start()?;
+                stream.write_all(&buffer)?;
+                stream.end()
+            }
             Some(Body::Chunked(mut body, chunk_size)) => {
                 // This _might_ happen on a 32-bit machine!
                 if chunk_size > (usize::max_value() as u64) {
@@ -121,10 +288,28 @@ impl Rocket {
                 // The buffer stores the current chunk being written out.
                 let mut buffer = vec![0; chunk_size as usize];
                 let mut stream = hyp_res.start()?;
+                if !flush {
+                    loop {
+                        match body.read_max(&mut buffer)? {
+                            0 => break,
+                            n => stream.write_all(&buffer[..n])?,
+                        }
+                    }
+
+                    return stream.end();
+                }
+
+                // Flushed bodies are produced incrementally, so each read is
+                // written out and flushed as a chunk of its own.
                 loop {
-                    match body.read_max(&mut buffer)? {
-                        0 => break,
-                        n => stream.write_all(&buffer[..n])?,
+                    match body.read(&mut buffer) {
+                        Ok(0) => break,
+                        Ok(n) => {
+                            stream.write_all(&buffer[..n])?;
+                            stream.flush()?;
+                        }
+                        Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                        Err(e) => return Err(e)
                     }
                 }
 
@@ -134,39 +319,310 @@ impl Rocket {
     }
 
     /// Preprocess the request for Rocket-specific things. At this time, we're
-    /// only checking for _method in forms. Keep this in-sync with derive_form
-    /// when preprocessing form fields.
+    /// only reinterpreting the request's method as the method override policy
+    /// asks.
     fn preprocess_request(&self, req: &mut Request, data: &Data) {
-        // Check if this is a form and if the form contains the special _method
-        // field which we use to reinterpret the request's method.
-        let data_len = data.peek().len();
-        let (min_len, max_len) = ("_method=get".len(), "_method=delete".len());
-        if req.content_type().is_form() && data_len >= min_len {
-            let form = unsafe {
-                from_utf8_unchecked(&data.peek()[..min(data_len, max_len)])
-            };
+        if let Some(method) = self.method_override.resolve(req, data) {
+            req.set_method(method);
+        }
+    }
 
-            let mut form_items = FormItems(form);
-            if let Some(("_method", value)) = form_items.next() {
-                if let Ok(method) = value.parse() {
-                    req.set_method(method);
-                }
+    /// Lets `request` know which configuration it's being handled under and
+    /// canonicalizes its headers as the header policy asks. Returns `false` if
+    /// the header policy rejected the request's headers.
+    fn prepare_request<'s>(&'s self, request: &mut Request<'s>) -> bool {
+        request.set_config(self.live_config.get().unwrap_or_else(|| self.config.clone()));
+        request.set_metrics(&self.metrics);
+        request.set_providers(&self.providers);
+        request.set_tenants(&self.tenants);
+        request.set_managed(&self.managed);
+
+        // Canonicalize the headers, if asked to, before anything reads them.
+        if let Some(ref policy) = self.header_policy {
+            if let Err(e) = policy.apply(request.headers_mut()) {
+                info!("{}:", request);
+                error_!("Headers rejected by the header policy: {}.", e);
+                return false;
             }
         }
+
+        true
     }
 
     #[doc(hidden)]
     #[inline(always)]
-    pub fn dispatch<'r>(&self, request: &'r mut Request, data: Data) -> Response<'r> {
+    pub fn dispatch<'s, 'r>(&'s self, request: &'r mut Request<'s>, data: Data)
+            -> Response<'r> {
+        let rejected = !self.prepare_request(request);
+        request.connection().record_request();
+
         // Do a bit of preprocessing before routing.
         self.preprocess_request(request, &data);
 
-        // Route the request to get a response.
+        // Resolve the request's tenant before any guards can ask for it, then
+        // run the request hooks whose predicates the request satisfies. Neither
+        // sees requests the header policy rejected.
+        if !rejected {
+            self.tenants.resolve(request);
+            for &(ref predicate, ref hook) in &self.request_hooks {
+                if predicate.matches(request) {
+                    hook(request, &data);
+                }
+            }
+        }
+
+        // `OPTIONS *` requests, requests asking to be explained, and requests
+        // to redirected paths are answered without being routed. They're
+        // checked against the URI the tenant and the request hooks left.
+        let redirect = if rejected {
+            None
+        } else {
+            self.server_options_response(request)
+                .or_else(|| self.explain_response(request))
+                .or_else(|| self.redirect_response(request))
+                .or_else(|| self.canonical_response(request))
+        };
+
+        // Respond to the request, then run the matching response hooks.
+        let request: &'r Request<'s> = request;
+        let mut response = match redirect {
+            Some(response) => response,
+            None if rejected => self.handle_error(Status::BadRequest, request),
+            None => match self.maintenance {
+                Some(ref maintenance) if maintenance.rejects(request) => {
+                    self.maintenance_response(maintenance, request)
+                }
+                _ => self.respond(request, data)
+            }
+        };
+
+        // Point successful responses at the canonical URL, if asked to.
+        if let Some(canonical) = request.route().and_then(|route| route.canonical.as_ref()) {
+            if response.status().code / 100 == 2 {
+                if let Some(link) = canonical.link_header(request) {
+                    response.adjoin_header(link);
+                }
+            }
+        }
+        for &(ref predicate, ref hook) in &self.response_hooks {
+            if predicate.matches(request) {
+                hook(request, &mut response);
+            }
+        }
+
+        // Keep validators correct if a hook or responder encoded the body.
+        adjust_for_encoding(&mut response);
+
+        // Replace responses whose headers can't be brought within budget.
+        if let Some(ref budget) = self.header_budget {
+            if let Err(e) = budget.apply(&mut response) {
+                error_!("Response headers exceed the header budget: {}.", e);
+                response = self.handle_error(Status::InternalServerError, request);
+            }
+        }
+
+        // Report internal server errors that weren't reported as they happened.
+        if response.status() == Status::InternalServerError {
+            self.report_error(request, ServerError::Response(response.status()));
+        }
+
+        // Record the body sizes for the route that handled the request.
+        if let Some(route) = request.route() {
+            self.record_sizes(route, request, &mut response);
+        }
+
+        response
+    }
+
+    /// Returns the response to `request` while the application is down for
+    /// maintenance: that of the `503` catcher, with a `Retry-After` header if
+    /// `maintenance` has a hint.
+    fn maintenance_response<'r>(&self, maintenance: &Maintenance, request: &'r Request)
+            -> Response<'r> {
+        info!("{}:", request);
+        info_!("Rejected: down for maintenance.");
+        let mut response = self.handle_error(Status::ServiceUnavailable, request);
+        if let Some(retry_after) = maintenance.retry_after_hint() {
+            response.set_header(retry_after);
+        }
+
+        response
+    }
+
+    /// Returns the interim status to send to `request`, which carries an
+    /// `Expect: 100-continue` header, before its body is read: `100 Continue`
+    /// if a route could handle it, and otherwise the final status the request
+    /// would be answered with, so that the client doesn't send a body that
+    /// would be discarded. When the final status is sent, the request isn't
+    /// handled and the connection is closed.
+    ///
+    /// The request is preprocessed as it is when it's dispatched: the header
+    /// policy is applied and its tenant is resolved before it's routed. Guards
+    /// can't be checked before the body is read, since data guards, request
+    /// hooks, and the method override policy need the body, so a request is
+    /// only turned away when its headers are rejected, when it's down for
+    /// maintenance, or when no route matches its method and path, no request
+    /// hook may rewrite it, and its method can't be overridden.
+    #[doc(hidden)]
+    pub fn continue_status<'s>(&'s self, request: &mut Request<'s>) -> Status {
+        if !self.prepare_request(request) {
+            info_!("Expectation declined: responding with {}.", Status::BadRequest);
+            return Status::BadRequest;
+        }
+
+        if let Some(ref maintenance) = self.maintenance {
+            if maintenance.rejects(request) {
+                return Status::ServiceUnavailable;
+            }
+        }
+
+        if request.method() == Method::Options && request.uri().as_str() == "*" {
+            return Status::Continue;
+        }
+
+        if self.config.redirects.iter().any(|rule| rule.apply(request.uri()).is_some()) {
+            return Status::Continue;
+        }
+
+        // Hooks may rewrite the request once its body is available.
+        self.tenants.resolve(request);
+        if self.request_hooks.iter().any(|&(ref predicate, _)| predicate.matches(request)) {
+            return Status::Continue;
+        }
+
+        if !self.router.route(request).is_empty() || self.method_override.may_override(request) {
+            return Status::Continue;
+        }
+
+        info!("{}:", request);
+        if self.is_method_not_allowed(request) {
+            info_!("Expectation declined: responding with {}.", Status::MethodNotAllowed);
+            Status::MethodNotAllowed
+        } else {
+            info_!("Expectation declined: responding with {}.", Status::NotFound);
+            Status::NotFound
+        }
+    }
+
+    /// Returns a redirect to the destination of the first redirect rule that
+    /// matches `request`'s URI, if any.
+    fn redirect_response(&self, request: &Request) -> Option<Response<'static>> {
+        for rule in &self.config.redirects {
+            if let Some(location) = rule.apply(request.uri()) {
+                info!("{}:", request);
+                info_!("Redirected by {}.", White.paint(rule));
+                return Some(Response::build()
+                    .status(rule.status())
+                    .raw_header("Location", location)
+                    .finalize());
+            }
+        }
+
+        None
+    }
+
+    /// Returns a permanent redirect to the canonical URL of the route that
+    /// `request` is for if `request`'s URL isn't canonical. See the
+    /// [canonical](/rocket/canonical/index.html) module.
+    fn canonical_response(&self, request: &Request) -> Option<Response<'static>> {
+        self.router.canonical_location(request).map(|location| {
+            info!("{}:", request);
+            info_!("Redirected to the canonical URL {}.", White.paint(&location));
+            let status = match request.method() {
+                Method::Get | Method::Head => Status::MovedPermanently,
+                _ => Status::PermanentRedirect
+            };
+
+            Response::build().status(status).raw_header("Location", location).finalize()
+        })
+    }
+
+    /// Returns the server-wide capabilities response if `request` is an
+    /// `OPTIONS *` request: an empty `200 OK` response with an `Allow` header
+    /// listing the methods of the mounted routes, as modified by the handler
+    /// registered via `server_options`.
+    fn server_options_response(&self, request: &Request) -> Option<Response<'static>> {
+        use http::Method::*;
+
+        if request.method() != Options || request.uri().as_str() != "*" {
+            return None;
+        }
+
+        // `HEAD` requests are handled automatically by `GET` routes.
+        let methods: Vec<Method> = self.router.routes().map(|route| route.method).collect();
+        let all = [Options, Get, Head, Post, Put, Patch, Delete, Trace, Connect];
+        let allowed: Vec<Method> = all.iter()
+            .filter(|&&m| m == Options || methods.contains(&m)
+                    || (m == Head && methods.contains(&Get)))
+            .cloned()
+            .collect();
+
+        info!("{}:", request);
+        info_!("Answering with the server-wide options.");
+        let mut response = Response::build().header(Allow(allowed)).finalize();
+
+        if let Some(ref handler) = self.server_options {
+            handler(request, &mut response);
+        }
+
+        Some(response)
+    }
+
+    /// In development, returns a plain text explanation of how `request` would
+    /// be routed if it has an `X-Rocket-Explain` header.
+    fn explain_response(&self, request: &Request) -> Option<Response<'static>> {
+        if self.config.env != Environment::Development
+                || !request.headers().contains("X-Rocket-Explain") {
+            return None;
+        }
+
+        info!("{}:", request);
+        info_!("Explaining how the request would be routed.");
+        let explanation = self.explain(request).to_string();
+        Some(Response::build()
+            .header(ContentType::Plain)
+            .sized_body(Cursor::new(explanation))
+            .finalize())
+    }
+
+    /// Records the sizes of the bodies of `request` and `response` in the
+    /// metrics for `route`. The size of a chunked response body is recorded
+    /// once the body has been read.
+    fn record_sizes<'r>(&'r self, route: &'r Route, request: &Request,
+                        response: &mut Response<'r>) {
+        let length = request.headers().get_one("Content-Length")
+            .and_then(|value| value.trim().parse().ok());
+
+        if let Some(length) = length {
+            self.metrics.record_request(route, length);
+        }
+
+        let size = match response.body() {
+            Some(Body::Sized(_, size)) => Some(size),
+            Some(Body::Chunked(..)) => None,
+            None => Some(0),
+        };
+
+        match size {
+            Some(size) => self.metrics.record_response(route, size),
+            None => {
+                if let Some(Body::Chunked(body, chunk_size)) = response.take_body() {
+                    let body = CountingBody::new(body, route, &self.metrics);
+                    response.set_raw_body(Body::Chunked(body, chunk_size));
+                }
+            }
+        }
+    }
+
+    /// Routes the request and generates a response for it, using the error
+    /// catchers if routing fails. `HEAD` requests that aren't routed are
+    /// retried as `GET` requests with the response body stripped.
+    fn respond<'s, 'r>(&'s self, request: &'r Request<'s>, data: Data) -> Response<'r> {
         match self.route(request, data) {
             Outcome::Success(mut response) => {
-                let cookie_delta = request.cookies().delta();
-                if !cookie_delta.is_empty() {
-                    response.adjoin_header(header::SetCookie(cookie_delta));
+                // One `Set-Cookie` header per cookie: cookies can't be joined.
+                for cookie in request.cookies().delta() {
+                    response.adjoin_raw_header("Set-Cookie", cookie.to_string());
                 }
 
                 response
@@ -178,16 +634,24 @@ impl Rocket {
                 // FIXME: Pay the cost to copy Request into UnsafeCell? Pay the
                 // cost to use RefCell? Move the call to `issue_response` here
                 // to move Request and move directly into an UnsafeCell?
-                let request: &'r mut Request = unsafe {
-                    &mut *(request as *const Request as *mut Request)
+                let request: &'r mut Request<'s> = unsafe {
+                    &mut *(request as *const Request<'s> as *mut Request<'s>)
                 };
 
                 if request.method() == Method::Head {
                     info_!("Autohandling {} request.", White.paint("HEAD"));
                     request.set_method(Method::Get);
-                    let mut response = self.dispatch(request, data);
+                    let mut response = self.respond(request, data);
                     response.strip_body();
                     response
+                } else if !request.challenges().is_empty() {
+                    info_!("Unauthenticated request: responding with {}.",
+                           Status::Unauthorized);
+                    self.handle_error(Status::Unauthorized, request)
+                } else if self.is_method_not_allowed(request) {
+                    info_!("Unsupported method: responding with {}.",
+                           Status::MethodNotAllowed);
+                    self.handle_error(Status::MethodNotAllowed, request)
                 } else {
                     self.handle_error(Status::NotFound, request)
                 }
@@ -203,7 +667,7 @@ impl Rocket {
     /// the request, this function returns an `Err` with the status code.
     #[doc(hidden)]
     #[inline(always)]
-    pub fn route<'r>(&self, request: &'r Request, mut data: Data)
+    pub fn route<'s, 'r>(&'s self, request: &'r Request<'s>, mut data: Data)
             -> handler::Outcome<'r> {
         // Go through the list of matching routes until we fail or succeed.
         info!("{}:", request);
@@ -214,14 +678,75 @@ impl Rocket {
             // FIXME: Users should not be able to use this.
             request.set_params(route);
 
-            // Dispatch the request to the handler.
-            let outcome = (route.handler)(request, data);
+            // Skip routes whose feature is disabled, or stop routing entirely.
+            if let Some(ref feature) = route.feature {
+                match self.feature_disabled(feature, request) {
+                    None => { /* continue to the guards */ }
+                    Some(Disabled::Forward) => {
+                        info_!("{} '{}' is disabled.", White.paint("Feature:"), feature);
+                        continue;
+                    }
+                    Some(Disabled::NotFound) => {
+                        info_!("{} '{}' is disabled.", White.paint("Feature:"), feature);
+                        return Outcome::Failure(Status::NotFound);
+                    }
+                }
+            }
+
+            // Check the route's guards; a forward moves on to the next route.
+            match route.check_guards(request) {
+                Outcome::Success(()) => { /* continue to the handler */ }
+                Outcome::Forward(()) => {
+                    info_!("{} {}", White.paint("Outcome:"), "Guard forwarded.");
+                    continue;
+                }
+                Outcome::Failure(status) => {
+                    info_!("{} {}", White.paint("Outcome:"), "Guard failed.");
+                    if let Some(failure) = request.guard_failure() {
+                        info_!("{} {}", White.paint("Guard failure:"), failure);
+                        self.report_guard_failure(request, failure);
+                    }
+                    return Outcome::Failure(status);
+                }
+            }
+
+            // Dispatch the request to the handler. A handler that panics fails
+            // with a `500` so that the client still gets a response.
+            let handled = panic::catch_unwind(AssertUnwindSafe(|| (route.handler)(request, data)));
+            let outcome = match handled {
+                Ok(outcome) => outcome,
+                Err(payload) => {
+                    let message = report::panic_message(&*payload);
+                    match message {
+                        Some(ref message) => error_!("Handler panicked: {}", message),
+                        None => error_!("Handler panicked.")
+                    }
+
+                    request.set_route(route);
+                    self.report_error(request, ServerError::Panic(message));
+                    return Outcome::Failure(Status::InternalServerError);
+                }
+            };
 
             // Check if the request processing completed or if the request needs
             // to be forwarded. If it does, continue the loop to try again.
             info_!("{} {}", White.paint("Outcome:"), outcome);
+            if outcome.is_failure() {
+                if let Some(failure) = request.guard_failure() {
+                    info_!("{} {}", White.paint("Guard failure:"), failure);
+                    self.report_guard_failure(request, failure);
+                }
+            }
+
             match outcome {
-                o@Outcome::Success(_) | o @Outcome::Failure(_) => return o,
+                o@Outcome::Success(_) | o @Outcome::Failure(_) => {
+                    request.set_route(route);
+                    if !route.content_type.is_any() {
+                        request.vary_on(route.format_header());
+                    }
+
+                    return o;
+                }
                 Outcome::Forward(unused_data) => data = unused_data,
             };
         }
@@ -230,6 +755,53 @@ impl Rocket {
         Outcome::Forward(data)
     }
 
+    /// Returns `true` if `request` is under a mount point that answers with
+    /// `405 Method Not Allowed` and routes match its path, but none for its
+    /// method.
+    fn is_method_not_allowed(&self, request: &Request) -> bool {
+        let under_mount = self.method_not_allowed.iter().any(|base| {
+            let mut segments = request.uri().segments();
+            URI::new(base.as_str()).segments().all(|s| segments.next() == Some(s))
+        });
+
+        if !under_mount {
+            return false;
+        }
+
+        let allowed = self.router.allowed_methods(request);
+        !allowed.is_empty() && !allowed.contains(&request.method())
+    }
+
+    /// Returns what happens to `request` if `feature` is disabled for it, or
+    /// `None` if it's enabled. Without feature flags, every feature is
+    /// disabled.
+    fn feature_disabled(&self, feature: &str, request: &Request) -> Option<Disabled> {
+        match request.guard::<State<Box<FeatureFlags>>>() {
+            Outcome::Success(flags) => if flags.is_enabled(feature, request) {
+                None
+            } else {
+                Some(flags.when_disabled(feature))
+            },
+            _ => Some(Disabled::Forward)
+        }
+    }
+
+    /// Reports `error`, which occurred while handling `request`, to the error
+    /// reporter, unless an error was already reported for `request`.
+    fn report_error(&self, request: &Request, error: ServerError) {
+        if request.set_error_reported() {
+            self.error_reporter.report(request, &error);
+        }
+    }
+
+    /// Reports the guard failure `failure` if its status is a server error.
+    /// Guards fail with client error statuses as a matter of course.
+    fn report_guard_failure(&self, request: &Request, failure: GuardFailure) {
+        if failure.status().class() == StatusClass::ServerError {
+            self.report_error(request, ServerError::GuardFailure(failure));
+        }
+    }
+
     // TODO: DOC.
     #[doc(hidden)]
     pub fn handle_error<'r>(&self, status: Status, req: &'r Request) -> Response<'r> {
@@ -243,12 +815,29 @@ impl Rocket {
 
         // Dispatch to the user's catcher. If it fails, use the default 500.
         let error = Error::NoRoute;
-        catcher.handle(error, req).unwrap_or_else(|err_status| {
+        let mut response = catcher.handle(error, req).unwrap_or_else(|err_status| {
             error_!("Catcher failed with status: {}!", err_status);
             warn_!("Using default 500 error catcher.");
             let default = self.default_catchers.get(&500).expect("Default 500");
             default.handle(error, req).expect("Default 500 response.")
-        })
+        });
+
+        // Challenge unauthenticated requests, as required by RFC 7235.
+        if response.status() == Status::Unauthorized {
+            for challenge in req.challenges() {
+                response.adjoin_header(Header::new("WWW-Authenticate", challenge));
+            }
+        }
+
+        // List the methods the resource supports, as required by RFC 7231.
+        if response.status() == Status::MethodNotAllowed {
+            let allowed = self.router.allowed_methods(req);
+            if !allowed.is_empty() {
+                response.set_header(Allow(allowed));
+            }
+        }
+
+        response
     }
 
     /// Create a new `Rocket` application using the configuration information in
@@ -321,12 +910,50 @@ impl Rocket {
             info_!("{} {}: {}", Yellow.paint("[extra]"), name, White.paint(value));
         }
 
+        if !config.hosts.is_empty() {
+            info_!("hosts: {}", White.paint(config.hosts.join(", ")));
+        }
+
+        if !config.trusted_proxies.is_empty() {
+            let proxies: Vec<_> = config.trusted_proxies.iter().map(|p| p.to_string()).collect();
+            info_!("trusted proxies: {}", White.paint(proxies.join(", ")));
+        }
+
+        for rule in &config.redirects {
+            info_!("{} {}", Yellow.paint("[redirect]"), White.paint(rule));
+        }
+
+        info_!("limits: {}", White.paint(&config.limits));
+        match config.read_timeout {
+            Some(timeout) => info_!("read timeout: {:?}", White.paint(timeout)),
+            None => info_!("read timeout: {}", White.paint("none")),
+        }
+        info_!("blocking pool: {} threads, queue of {}", White.paint(config.blocking_threads),
+               White.paint(config.blocking_queue));
+
         Rocket {
-            address: config.address.clone(),
-            port: config.port,
+            config: Arc::new(config.clone()),
             router: Router::new(),
             default_catchers: catcher::defaults::get(),
             catchers: catcher::defaults::get(),
+            hot_reload: vec![],
+            reload_handlers: vec![],
+            live_config: LiveConfig::new(),
+            request_hooks: vec![],
+            response_hooks: vec![],
+            server_options: None,
+            launch_reporter: Box::new(launch::log),
+            error_reporter: Box::new(report::ignore),
+            shutdown: ShutdownHandle::new(),
+            header_policy: None,
+            header_budget: None,
+            method_not_allowed: vec![],
+            method_override: MethodOverride::new(),
+            metrics: Metrics::new(),
+            providers: Providers::new(),
+            tenants: Tenants::new(),
+            maintenance: None,
+            managed: ManagedState::new(),
         }
     }
 
@@ -446,40 +1073,924 @@ impl Rocket {
         self
     }
 
-    /// Starts the application server and begins listening for and dispatching
-    /// requests to mounted routes and catchers.
+    /// Marks the configuration parameters named in `names` as hot-reloadable.
+    /// Once the application has launched, changes to these parameters in the
+    /// configuration file are applied without restarting the application.
+    ///
+    /// Only the `log` parameter and extras can be hot-reloaded. Changes to
+    /// `log` take effect immediately. Changes to extras are visible via the
+    /// configuration of requests that arrive afterwards and are delivered to
+    /// the functions registered via [on_reload](#method.on_reload). On Unix,
+    /// sending the process `SIGHUP` also reloads the configuration.
     ///
     /// # Panics
     ///
-    /// If the server could not be started, this method prints the reason and
-    /// then exits the process.
+    /// Panics if any of `names` is `address`, `port`, `session_key`, `hosts`,
+    /// or `trusted_proxies`.
     ///
     /// # Examples
     ///
     /// ```rust
     /// # if false {
-    /// rocket::ignite().launch()
+    /// rocket::ignite()
+    ///     .hot_reload(&["log", "feature_flags"])
+    ///     .launch()
     /// # }
     /// ```
-    pub fn launch(self) {
-        if self.router.has_collisions() {
-            warn!("Route collisions detected!");
-        }
+    pub fn hot_reload(mut self, names: &[&str]) -> Self {
+        for name in names {
+            if ["address", "port", "session_key", "hosts", "trusted_proxies"].contains(name) {
+                error!("The '{}' parameter can't be hot-reloaded.", name);
+                panic!("Bad hot-reloadable parameter.")
+            }
 
-        let full_addr = format!("{}:{}", self.address, self.port);
-        let server = match hyper::Server::http(full_addr.as_str()) {
-            Ok(hyper_server) => hyper_server,
-            Err(e) => {
-                error!("Failed to start server.");
-                panic!("{}", e);
+            if !self.hot_reload.iter().any(|n| n == name) {
+                self.hot_reload.push(name.to_string());
             }
-        };
+        }
+
+        self
+    }
+
+    /// Registers `handler` to be called when hot-reloadable configuration
+    /// parameters change. `handler` is called with the reloaded configuration
+    /// and the names of the parameters that changed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::config::Config;
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .hot_reload(&["feature_flags"])
+    ///     .on_reload(|config: &Config, changed: &[String]| {
+    ///         println!("Changed: {:?}", changed);
+    ///     })
+    ///     .launch()
+    /// # }
+    /// ```
+    pub fn on_reload<F>(mut self, handler: F) -> Self
+        where F: Fn(&Config, &[String]) + Send + Sync + 'static
+    {
+        self.reload_handlers.push(Box::new(handler));
+        self
+    }
+
+    /// Registers `hook` to be called with each incoming request that satisfies
+    /// `predicate`, and its data, before the request is routed. Hooks are
+    /// called in the order they were registered. See the
+    /// [hook](/rocket/hook/index.html) module for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::hook::Predicate;
+    /// use rocket::http::Method;
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .on_request(Predicate::path("/legacy").method(Method::Get), |req, _| {
+    ///         let path = format!("/v1{}", req.uri().as_str());
+    ///         req.set_uri(path);
+    ///     })
+    ///     .launch()
+    /// # }
+    /// ```
+    pub fn on_request<F>(mut self, predicate: Predicate, hook: F) -> Self
+        where F: Fn(&mut Request, &Data) + Send + Sync + 'static
+    {
+        self.request_hooks.push((predicate, Box::new(hook)));
+        self
+    }
+
+    /// Registers `hook` to be called with each request that satisfies
+    /// `predicate` and the response generated for it, before the response is
+    /// written out. Hooks are called in the order they were registered. See
+    /// the [hook](/rocket/hook/index.html) module for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::hook::Predicate;
+    /// use rocket::http::Header;
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .on_response(Predicate::path("/api"), |_, response| {
+    ///         response.set_header(Header::new("X-API-Version", "1"));
+    ///     })
+    ///     .launch()
+    /// # }
+    /// ```
+    pub fn on_response<F>(mut self, predicate: Predicate, hook: F) -> Self
+        where F: Fn(&Request, &mut Response) + Send + Sync + 'static
+    {
+        self.response_hooks.push((predicate, Box::new(hook)));
+        self
+    }
+
+    /// Registers `handler` to customize the response to `OPTIONS *` requests,
+    /// which ask about the capabilities of the server as a whole rather than
+    /// of a resource.
+    ///
+    /// `OPTIONS *` requests are never routed. Instead, Rocket answers them
+    /// with an empty `200 OK` response whose `Allow` header lists the methods
+    /// of the mounted routes. `handler` is called with the request and this
+    /// response before response hooks are run, and may modify it as it likes.
+    /// Registering a handler replaces any previously registered one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::http::Header;
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .server_options(|_, response| {
+    ///         response.set_header(Header::new("Accept-Patch", "application/json"));
+    ///     })
+    ///     .launch()
+    /// # }
+    /// ```
+    pub fn server_options<F>(mut self, handler: F) -> Self
+        where F: Fn(&Request, &mut Response) + Send + Sync + 'static
+    {
+        self.server_options = Some(Box::new(handler));
+        self
+    }
+
+    /// Canonicalizes the headers of every request according to `policy` before
+    /// it is routed, so that request guards and hooks see a canonical view of
+    /// them. Requests whose headers the policy rejects fail with `400 Bad
+    /// Request`. See [HeaderPolicy](/rocket/http/struct.HeaderPolicy.html) for
+    /// what canonicalization entails. Headers aren't canonicalized by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::http::{HeaderPolicy, Duplicates};
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .canonicalize_headers(HeaderPolicy::new().duplicates(Duplicates::Reject))
+    ///     .launch()
+    /// # }
+    /// ```
+    #[inline]
+    pub fn canonicalize_headers(mut self, policy: HeaderPolicy) -> Self {
+        self.header_policy = Some(policy);
+        self
+    }
+
+    /// Installs `budget` as the cap on the total size and number of the
+    /// headers of every response. Responses over budget are truncated or
+    /// replaced by the `500` error catcher's response. See
+    /// [HeaderBudget](/rocket/response/struct.HeaderBudget.html) for details.
+    /// Response headers aren't capped by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::response::HeaderBudget;
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .header_budget(HeaderBudget::new().max_count(50).truncate("Set-Cookie"))
+    ///     .launch()
+    /// # }
+    /// ```
+    #[inline]
+    pub fn header_budget(mut self, budget: HeaderBudget) -> Self {
+        self.header_budget = Some(budget);
+        self
+    }
+
+    /// Answers requests under the mount point `base` with `405 Method Not
+    /// Allowed` when routes match their path but none match their method.
+    ///
+    /// By default, such requests are answered with `404 Not Found`. Once
+    /// enabled for `base`, they're answered by the `405` catcher, so the body
+    /// of the response can be customized via
+    /// [catch](#method.catch), and the response carries an `Allow` header
+    /// listing the methods of the routes that match the request's path. A
+    /// `base` of `/` enables `405` responses for every request.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #![feature(plugin)]
+    /// # #![plugin(rocket_codegen)]
+    /// # extern crate rocket;
+    /// #[get("/items")]
+    /// fn items() -> &'static str { "[]" }
+    ///
+    /// fn main() {
+    /// # if false {
+    ///     rocket::ignite()
+    ///         .mount("/api", routes![items])
+    ///         .method_not_allowed("/api")
+    ///         .launch()
+    /// # }
+    /// }
+    /// ```
+    pub fn method_not_allowed(mut self, base: &str) -> Self {
+        self.method_not_allowed.push(base.to_string());
+        self
+    }
+
+    /// Registers the segment matcher `matcher` under `name`, for use in route
+    /// paths as `<param:name>`. A matcher receives the percent-decoded path
+    /// segment and decides whether it matches. Matching takes part in
+    /// routing: a route only matches a request if each of its matchers accepts
+    /// the corresponding segment, so requests with other values fall through
+    /// to other routes instead of failing after the route is chosen. Routes
+    /// whose matcher rejects a static segment of another route at the same
+    /// position don't collide with it.
+    ///
+    /// Besides registered matchers, route paths may use the built-in matchers
+    /// `int`, `uint`, `alpha`, `alnum`, and `uuid`, and inline patterns
+    /// consisting of a character class and an optional quantifier, such as
+    /// `<slug:[a-z0-9-]+>` or `<code:[A-Z]{3}>`. Registering a matcher with
+    /// the name of a built-in one replaces it. Launching fails if a route uses
+    /// an unknown matcher or an invalid pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #![feature(plugin)]
+    /// # #![plugin(rocket_codegen)]
+    /// # extern crate rocket;
+    /// #[get("/<lang:locale>/about")]
+    /// fn about(lang: String) -> String {
+    ///     format!("about ({})", lang)
+    /// }
+    ///
+    /// #[get("/<year:uint>/<slug:[a-z0-9-]+>")]
+    /// fn post(year: u16, slug: String) -> String {
+    ///     format!("{}: {}", year, slug)
+    /// }
+    ///
+    /// fn main() {
+    /// # if false {
+    ///     rocket::ignite()
+    ///         .matcher("locale", |s| s == "en" || s == "de")
+    ///         .mount("/", routes![about, post])
+    ///         .launch()
+    /// # }
+    /// }
+    /// ```
+    #[inline]
+    pub fn matcher<F>(mut self, name: &str, matcher: F) -> Self
+        where F: Fn(&str) -> bool + Send + Sync + 'static
+    {
+        self.router.add_matcher(name, Box::new(matcher));
+        self
+    }
+
+    /// Sets the policy for reinterpreting `POST` requests as `PUT`, `DELETE`,
+    /// or `PATCH` requests during routing. By default, the `_method` field of
+    /// form bodies is honored and the `X-HTTP-Method-Override` header is not.
+    /// See [MethodOverride](/rocket/request/struct.MethodOverride.html) for
+    /// details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::request::MethodOverride;
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .method_override(MethodOverride::disabled().header(true))
+    ///     .launch()
+    /// # }
+    /// ```
+    #[inline]
+    pub fn method_override(mut self, policy: MethodOverride) -> Self {
+        self.method_override = policy;
+        self
+    }
+
+    /// Redirects requests to paths matching `from` to `to` with status
+    /// `status` before they are routed. See
+    /// [RedirectRule](/rocket/config/struct.RedirectRule.html) for the syntax
+    /// of `from` and `to`. Redirects from the `redirects` configuration
+    /// parameter are tried first, followed by those added via this method in
+    /// the order they were added; the first matching redirect applies.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from`, `to`, or `status` is invalid, as described in
+    /// [RedirectRule::new](/rocket/config/struct.RedirectRule.html#method.new).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::http::Status;
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .redirect("/about-us", "/about", Status::MovedPermanently)
+    ///     .redirect("/blog/<path..>", "/posts/<path..>", Status::PermanentRedirect)
+    ///     .launch()
+    /// # }
+    /// ```
+    pub fn redirect(mut self, from: &str, to: &str, status: Status) -> Self {
+        match RedirectRule::new(from, to, status) {
+            Ok(rule) => {
+                info!("🔀  {} {}", Magenta.paint("Redirecting:"), rule);
+                Arc::make_mut(&mut self.config).redirects.push(rule);
+            }
+            Err(e) => {
+                error!("Invalid redirect from '{}' to '{}': {}", from, to, e);
+                panic!("Aborting due to invalid redirect.");
+            }
+        }
+
+        self
+    }
+
+    /// Returns the configuration environment the application is running in.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::config::{Config, Environment};
+    ///
+    /// let config = Config::default_for(Environment::Staging, "/custom").unwrap();
+    /// let rocket = rocket::custom(&config);
+    /// assert!(rocket.environment().is_stage());
+    /// ```
+    #[inline(always)]
+    pub fn environment(&self) -> Environment {
+        self.config.env
+    }
+
+    /// Returns the request and response body size histograms recorded for
+    /// each route so far. See the [metrics](/rocket/metrics/index.html) module
+    /// for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let rocket = rocket::ignite();
+    /// assert!(rocket.metrics().snapshot().is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Returns an explanation of how `request` would be routed, without
+    /// running any guards or handlers: the routes whose paths match the
+    /// request, in the order they would be tried, why each would be skipped,
+    /// and which would win. See [Explanation](/rocket/struct.Explanation.html)
+    /// for more information.
+    ///
+    /// In the development environment, the explanation of a request with an
+    /// `X-Rocket-Explain` header is also returned, as plain text, in place of
+    /// the request's response.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #![feature(plugin)]
+    /// # #![plugin(rocket_codegen)]
+    /// # extern crate rocket;
+    /// use rocket::{Request, Verdict};
+    /// use rocket::http::Method;
+    ///
+    /// #[get("/<id>")]
+    /// fn item(id: usize) -> String { id.to_string() }
+    ///
+    /// #[get("/<name>", rank = 2)]
+    /// fn named(name: String) -> String { name }
+    ///
+    /// fn main() {
+    ///     let rocket = rocket::ignite().mount("/", routes![item, named]);
+    ///     let request = Request::new(Method::Get, "/ten");
+    ///     let explanation = rocket.explain(&request);
+    ///
+    ///     let verdicts: Vec<_> = explanation.candidates().iter()
+    ///         .map(|c| c.verdict)
+    ///         .collect();
+    ///
+    ///     assert_eq!(verdicts, vec![Verdict::Param("id"), Verdict::Wins]);
+    ///     assert_eq!(explanation.winner().unwrap().rank, 2);
+    /// }
+    /// ```
+    pub fn explain<'a>(&'a self, request: &Request) -> Explanation<'a> {
+        self.router.explain(request)
+    }
+
+    /// Registers `provider` as the constructor of values of type `T`, which
+    /// are injected into handlers via the
+    /// [Inject<T>](/rocket/inject/struct.Inject.html) request guard.
+    /// `provider` is called once, when the application is launched, and the
+    /// value it returns is shared by all requests. Replaces any existing
+    /// provider for `T`. See the [inject](/rocket/inject/index.html) module
+    /// for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    ///
+    /// struct Settings(HashMap<String, String>);
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .provide(|| Settings(HashMap::new()))
+    ///     .launch()
+    /// # }
+    /// ```
+    pub fn provide<T, F>(mut self, provider: F) -> Self
+        where T: Send + Sync + 'static, F: Fn() -> T + Send + Sync + 'static
+    {
+        if self.providers.add_launch(provider) {
+            info!("💉  Replaced an existing provider.");
+        }
+
+        self
+    }
+
+    /// Registers `provider` as the constructor of values of type `T`, which
+    /// are injected into handlers via the
+    /// [Inject<T>](/rocket/inject/struct.Inject.html) request guard.
+    /// `provider` is called with the request for every request whose handler
+    /// needs a `T`. Replaces any existing provider for `T`. See the
+    /// [inject](/rocket/inject/index.html) module for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// struct UserAgent(Option<String>);
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .provide_per_request(|request| {
+    ///         UserAgent(request.headers().get_one("User-Agent").map(|s| s.into()))
+    ///     })
+    ///     .launch()
+    /// # }
+    /// ```
+    pub fn provide_per_request<T, F>(mut self, provider: F) -> Self
+        where T: 'static, F: Fn(&Request) -> T + Send + Sync + 'static
+    {
+        if self.providers.add_request(provider) {
+            info!("💉  Replaced an existing provider.");
+        }
+
+        self
+    }
+
+    /// Adds `state` to the state managed by this application. Managed state is
+    /// retrieved by handlers via the [State](/rocket/struct.State.html) request
+    /// guard. At most one value of any given type can be managed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a value of type `T` is already being managed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Mutex;
+    ///
+    /// struct Visitors(Mutex<Vec<String>>);
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .manage(Visitors(Mutex::new(vec![])))
+    ///     .launch()
+    /// # }
+    /// ```
+    pub fn manage<T: Send + Sync + 'static>(mut self, state: T) -> Self {
+        if !self.managed.set(state) {
+            error!("State for this type is already being managed!");
+            panic!("Aborting due to duplicately managed state.");
+        }
+
+        self
+    }
+
+    /// Manages `flags` as the feature flags that decide whether routes with a
+    /// `feature` are enabled. See the [feature](/rocket/feature/index.html)
+    /// module for more.
+    ///
+    /// # Panics
+    ///
+    /// Panics if feature flags are already being managed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::HashSet;
+    ///
+    /// let mut enabled = HashSet::new();
+    /// enabled.insert("new_checkout");
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .feature_flags(enabled)
+    ///     .launch()
+    /// # }
+    /// ```
+    pub fn feature_flags<F: FeatureFlags>(self, flags: F) -> Self {
+        self.manage(Box::new(flags) as Box<FeatureFlags>)
+    }
+
+    /// Installs `maintenance` as the application's maintenance mode switch:
+    /// while it's on, requests to paths it doesn't allow are answered by the
+    /// `503` catcher without being routed. The switch is also managed, so
+    /// routes can flip it via `State<Maintenance>`, and it follows the
+    /// `maintenance` boolean extra, if set, at launch and whenever the extra
+    /// is hot-reloaded. See the [maintenance](/rocket/maintenance/index.html)
+    /// module for more.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a maintenance switch is already installed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::maintenance::Maintenance;
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .hot_reload(&["maintenance"])
+    ///     .maintenance(Maintenance::new().allow("/health"))
+    ///     .launch()
+    /// # }
+    /// ```
+    pub fn maintenance(mut self, maintenance: Maintenance) -> Self {
+        if self.maintenance.is_some() {
+            error!("A maintenance switch is already installed!");
+            panic!("Aborting due to duplicate maintenance switch.");
+        }
+
+        if let Ok(enabled) = self.config.get_bool("maintenance") {
+            maintenance.set(enabled);
+        }
+
+        info!("🚧  {}: {:?}", Magenta.paint("Maintenance"), maintenance);
+        let switch = maintenance.clone();
+        self.maintenance = Some(maintenance.clone());
+        self.manage(maintenance).on_reload(move |config: &Config, changed: &[String]| {
+            if changed.iter().any(|name| name == "maintenance") {
+                switch.set(config.get_bool("maintenance").unwrap_or(false));
+            }
+        })
+    }
+
+    /// Resolves the tenant of every request from `source` before the request
+    /// is routed. See the [tenant](/rocket/tenant/index.html) module for more
+    /// information.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::tenant::TenantSource;
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .tenants(TenantSource::Header("X-Tenant".into()))
+    ///     .launch()
+    /// # }
+    /// ```
+    pub fn tenants(mut self, source: TenantSource) -> Self {
+        info!("🏢  {}: {:?}", Magenta.paint("Tenants"), source);
+        self.tenants.set_source(source);
+        self
+    }
+
+    /// Registers `state` as the value of type `T` for the tenant named
+    /// `tenant`, replacing any existing value of type `T` for that tenant. The
+    /// value is retrieved via the
+    /// [TenantState](/rocket/tenant/struct.TenantState.html) request guard.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::tenant::TenantSource;
+    ///
+    /// struct Theme(&'static str);
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .tenants(TenantSource::PathPrefix)
+    ///     .tenant_state("acme", Theme("red"))
+    ///     .tenant_state("globex", Theme("blue"))
+    ///     .launch()
+    /// # }
+    /// ```
+    pub fn tenant_state<S, T>(mut self, tenant: S, state: T) -> Self
+        where S: Into<String>, T: Send + Sync + 'static
+    {
+        let tenant = tenant.into();
+        if self.tenants.add_state(tenant.clone(), state) {
+            info!("🏢  Replaced existing state for tenant '{}'.", tenant);
+        }
+
+        self
+    }
+
+    /// Installs `reporter` as the launch reporter: the function called with
+    /// this application's [LaunchInfo](/rocket/launch/struct.LaunchInfo.html)
+    /// once its server is listening. The default reporter,
+    /// [launch::log](/rocket/launch/fn.log.html), logs the launch banner.
+    ///
+    /// # Examples
+    ///
+    /// Launch without the banner:
+    ///
+    /// ```rust
+    /// # if false {
+    /// rocket::ignite()
+    ///     .launch_reporter(|_| ())
+    ///     .launch()
+    /// # }
+    /// ```
+    #[inline]
+    pub fn launch_reporter<F>(mut self, reporter: F) -> Self
+        where F: Fn(&LaunchInfo) + Send + Sync + 'static
+    {
+        self.launch_reporter = Box::new(reporter);
+        self
+    }
+
+    /// Sets the reporter that server errors, such as panicking handlers, are
+    /// reported to, along with the request that caused them. The default
+    /// reporter, [report::ignore](/rocket/report/fn.ignore.html), does
+    /// nothing. See the [report](/rocket/report/index.html) module for more
+    /// information.
+    ///
+    /// # Examples
+    ///
+    /// Log each error along with the request's URI:
+    ///
+    /// ```rust
+    /// use rocket::Request;
+    /// use rocket::report::ServerError;
+    ///
+    /// # if false {
+    /// rocket::ignite()
+    ///     .error_reporter(|request: &Request, error: &ServerError| {
+    ///         println!("error at {}: {}", request.uri(), error);
+    ///     })
+    ///     .launch()
+    /// # }
+    /// ```
+    #[inline]
+    pub fn error_reporter<R: ErrorReporter + 'static>(mut self, reporter: R) -> Self {
+        self.error_reporter = Box::new(reporter);
+        self
+    }
+
+    /// Returns the launch information of this application: what would be
+    /// reported if it were launched now.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let info = rocket::ignite().launch_info();
+    /// assert_eq!(info.protocol, "http");
+    /// assert!(info.routes.is_empty());
+    /// ```
+    pub fn launch_info(&self) -> LaunchInfo {
+        let mut routes: Vec<&Route> = self.router.routes().collect();
+        routes.sort_by(|a, b| {
+            (a.path.as_str(), a.method.to_string(), a.rank)
+                .cmp(&(b.path.as_str(), b.method.to_string(), b.rank))
+        });
+
+        let mut catchers: Vec<u16> = self.catchers.iter()
+            .filter(|&(_, catcher)| !catcher.is_default())
+            .map(|(&code, _)| code)
+            .collect();
+        catchers.sort();
+
+        LaunchInfo {
+            address: self.config.address.clone(),
+            port: self.config.port,
+            protocol: "http",
+            environment: self.config.env,
+            routes: routes,
+            catchers: catchers,
+            request_hooks: self.request_hooks.len(),
+            response_hooks: self.response_hooks.len(),
+            managed_state: self.managed.len(),
+            providers: self.providers.len(),
+            hot_reload: self.hot_reload.clone(),
+        }
+    }
+
+    /// Returns a handle that shuts this application down once it's running
+    /// via [run](#method.run) or [launch](#method.launch).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # if false {
+    /// use std::thread;
+    /// use std::time::Duration;
+    ///
+    /// let rocket = rocket::ignite();
+    /// let handle = rocket.shutdown_handle();
+    /// thread::spawn(move || {
+    ///     thread::sleep(Duration::from_secs(60));
+    ///     handle.shutdown();
+    /// });
+    ///
+    /// let reason = rocket.run();
+    /// assert!(reason.is_clean());
+    /// # }
+    /// ```
+    #[inline]
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    /// Starts the application server and begins listening for and dispatching
+    /// requests to mounted routes and catchers, as [run](#method.run) does.
+    /// When the application shuts down, exits the process with the exit code
+    /// of the [ShutdownReason](/rocket/shutdown/enum.ShutdownReason.html).
+    ///
+    /// # Panics
+    ///
+    /// If the server could not be started, this method prints the reason and
+    /// then exits the process with the exit code of `ShutdownReason::Fatal`.
+    ///
+    /// If a mounted route has an invalid segment matcher or requests managed
+    /// state, via the [State](/rocket/struct.State.html) request guard, of a
+    /// type that isn't managed, this method prints the offending routes and
+    /// then exits the process with the exit code of `ShutdownReason::Config`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # if false {
+    /// rocket::ignite().launch()
+    /// # }
+    /// ```
+    pub fn launch(self) {
+        let reason = self.run();
+        match reason.is_clean() {
+            true => info!("🛑  Shut down: {}.", reason),
+            false => error!("Shut down: {}.", reason)
+        }
+
+        reason.exit();
+    }
+
+    /// Starts the application server and begins listening for and dispatching
+    /// requests to mounted routes and catchers. Blocks until the application
+    /// is shut down, via a [ShutdownHandle](#method.shutdown_handle) or by the
+    /// process receiving `SIGINT` or `SIGTERM`, or fails to start, and returns
+    /// why.
+    ///
+    /// The server may continue to accept connections until the process exits,
+    /// so the reason is typically passed to
+    /// [ShutdownReason::exit](/rocket/shutdown/enum.ShutdownReason.html#method.exit)
+    /// once any cleanup is done.
+    ///
+    /// If a mounted route has an invalid segment matcher or requests managed
+    /// state, via the [State](/rocket/struct.State.html) request guard, of a
+    /// type that isn't managed, this method prints the offending routes and
+    /// returns `ShutdownReason::Config` without starting the server.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # if false {
+    /// let reason = rocket::ignite().run();
+    /// println!("stopped: {}", reason);
+    /// reason.exit();
+    /// # }
+    /// ```
+    pub fn run(mut self) -> ShutdownReason {
+        if self.router.has_collisions() {
+            warn!("Route collisions detected!");
+        }
+
+        let matcher_errors = self.router.matcher_errors();
+        for e in &matcher_errors {
+            error!("{}", e);
+        }
+
+        if !matcher_errors.is_empty() {
+            return ShutdownReason::Config("invalid segment matchers".into());
+        }
+
+        let mut unmanaged = false;
+        for route in self.router.routes() {
+            for &(id, name) in route.state.iter() {
+                if !self.managed.contains(id) {
+                    error!("{} requests unmanaged state: {}", route, White.paint(name));
+                    unmanaged = true;
+                }
+            }
+        }
+
+        if unmanaged {
+            return ShutdownReason::Config("unmanaged state".into());
+        }
+
+        let flags = TypeId::of::<Box<FeatureFlags>>();
+        if !self.managed.contains(flags) {
+            for route in self.router.routes().filter(|r| r.feature.is_some()) {
+                warn!("{} is disabled: there are no feature flags.", route);
+            }
+        }
+
+        if self.providers.len() > 0 {
+            self.providers.initialize();
+        }
+
+        if !self.hot_reload.is_empty() {
+            let handlers = mem::replace(&mut self.reload_handlers, vec![]);
+            config::watch((*self.config).clone(), self.hot_reload.clone(), handlers,
+                          self.live_config.clone());
+        }
+
+        let handle = self.shutdown.clone();
+        let full_addr = format!("{}:{}", self.config.address, self.config.port);
+        let listener = match ShutdownListener::bind(full_addr.as_str(), handle.clone()) {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("Failed to start server.");
+                return ShutdownReason::Fatal(format!("{}: {}", full_addr, e));
+            }
+        };
+
+        (self.launch_reporter)(&self.launch_info());
+        let _signals = shutdown::handle_signals(handle.clone());
+        let server = hyper::Server::new(listener.clone());
+        let mut listening = match server.handle(self) {
+            Ok(listening) => listening,
+            Err(e) => return ShutdownReason::Fatal(e.to_string())
+        };
+
+        // Stop accepting connections once shut down. Hyper's acceptor threads
+        // never exit, so they're detached rather than joined.
+        let reason = handle.wait();
+        listener.close();
+        let _ = listening.close();
+        reason
+    }
+}
+
+/// Adjusts `response` if its body has a content coding other than `identity`:
+/// the body's bytes differ from those of the unencoded representation, so any
+/// strong `ETag` is weakened, keeping conditional requests correct, and
+/// `Accept-Encoding` is added to `Vary`, so caches keep encoded and unencoded
+/// variants apart.
+fn adjust_for_encoding(response: &mut Response) {
+    let encoded = response.header_values("Content-Encoding")
+        .flat_map(|value| value.split(','))
+        .map(|coding| coding.trim())
+        .any(|coding| !coding.is_empty() && !coding.eq_ignore_ascii_case("identity"));
+
+    if !encoded {
+        return;
+    }
+
+    for value in response.take_header("ETag") {
+        let value = match value.parse::<ETag>() {
+            Ok(etag) => Cow::Owned(etag.into_weak().to_string()),
+            Err(_) => value
+        };
+
+        response.adjoin_raw_header("ETag", value);
+    }
+
+    response.vary("Accept-Encoding");
+}
+
+#[cfg(test)]
+mod test {
+    use super::Persistence;
+    use http::hyper::{header, HttpVersion};
+
+    fn persistence(version: HttpVersion, connection: Option<&str>) -> Persistence {
+        let mut headers = header::Headers::new();
+        if let Some(value) = connection {
+            headers.set_raw("Connection", vec![value.as_bytes().to_vec()]);
+        }
+
+        Persistence::of(version, &headers)
+    }
 
-        info!("🚀  {} {}{}...",
-              White.paint("Rocket has launched from"),
-              White.bold().paint("http://"),
-              White.bold().paint(&full_addr));
+    #[test]
+    fn test_persistence() {
+        use self::HttpVersion::*;
 
-        server.handle(self).unwrap();
+        assert!(persistence(Http11, None).keep_alive);
+        assert!(!persistence(Http11, Some("close")).keep_alive);
+        assert!(!persistence(Http10, None).keep_alive);
+        assert!(persistence(Http10, Some("keep-alive")).keep_alive);
+        assert!(persistence(Http10, Some("Keep-Alive")).http10);
+        assert!(!persistence(Http11, Some("keep-alive")).http10);
     }
 }
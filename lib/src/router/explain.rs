@@ -0,0 +1,130 @@
+use std::fmt;
+
+use super::{Collider, Route};
+use request::Request;
+
+/// Why a candidate route would or would not handle a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// The route would be tried first, and so would win if its guards and
+    /// handler succeed.
+    Wins,
+    /// The route matches but is tried after a route with a lower rank. It
+    /// would only be reached if the routes before it forward.
+    Outranked,
+    /// The route's method differs from the request's.
+    Method,
+    /// The request's `Content-Type` or, for methods without a payload, its
+    /// `Accept` header doesn't match the route's format.
+    Format,
+    /// The path parameter with the given name doesn't parse.
+    Param(&'static str),
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Verdict::Wins => write!(f, "wins"),
+            Verdict::Outranked => write!(f, "outranked"),
+            Verdict::Method => write!(f, "skipped: method mismatch"),
+            Verdict::Format => write!(f, "skipped: format mismatch"),
+            Verdict::Param(name) => write!(f, "skipped: '{}' failed to parse", name),
+        }
+    }
+}
+
+/// A route whose path matches a request, and the verdict on whether it would
+/// handle the request.
+#[derive(Debug, Clone)]
+pub struct Candidate<'r> {
+    /// The candidate route.
+    pub route: &'r Route,
+    /// Whether, and if not why not, the route would handle the request.
+    pub verdict: Verdict,
+}
+
+/// An explanation of how a request would be routed, returned by
+/// [Rocket::explain](/rocket/struct.Rocket.html#method.explain).
+///
+/// The candidates of an explanation are the routes whose paths match the
+/// request's URI, in the order Rocket would consider them: by rank, with
+/// routes of other methods last. Only routes built by codegen can be checked
+/// for parameters that fail to parse. Neither guards nor handlers are run, so
+/// the winner may still forward or fail when the request is dispatched.
+#[derive(Debug, Clone)]
+pub struct Explanation<'r> {
+    candidates: Vec<Candidate<'r>>,
+}
+
+impl<'r> Explanation<'r> {
+    #[doc(hidden)]
+    pub fn new<I>(routes: I, request: &Request) -> Explanation<'r>
+        where I: Iterator<Item = &'r Route>
+    {
+        let mut routes: Vec<_> = routes.filter(|r| request.uri().collides_with(&r.path))
+            .collect();
+
+        routes.sort_by_key(|r| (r.method != request.method(), r.rank));
+
+        let mut winner = false;
+        let candidates = routes.into_iter().map(|route| {
+            let verdict = if route.method != request.method() {
+                Verdict::Method
+            } else if !route.format_matches(request) {
+                Verdict::Format
+            } else {
+                request.set_params(route);
+                let params = route.params.map_or(Ok(()), |check| check(request));
+                match params {
+                    Err(name) => Verdict::Param(name),
+                    Ok(()) if winner => Verdict::Outranked,
+                    Ok(()) => {
+                        winner = true;
+                        Verdict::Wins
+                    }
+                }
+            };
+
+            Candidate { route: route, verdict: verdict }
+        }).collect();
+
+        Explanation { candidates: candidates }
+    }
+
+    /// Returns the candidate routes, in the order they would be considered.
+    #[inline(always)]
+    pub fn candidates(&self) -> &[Candidate<'r>] {
+        &self.candidates
+    }
+
+    /// Returns the route that would be tried first, if any.
+    pub fn winner(&self) -> Option<&'r Route> {
+        self.candidates.iter()
+            .find(|c| c.verdict == Verdict::Wins)
+            .map(|c| c.route)
+    }
+}
+
+impl<'r> fmt::Display for Explanation<'r> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.candidates.is_empty() {
+            return write!(f, "no routes match");
+        }
+
+        for (i, candidate) in self.candidates.iter().enumerate() {
+            if i != 0 {
+                f.write_str("\n")?;
+            }
+
+            let route = candidate.route;
+            write!(f, "{} {} [{}]", route.method, route.path, route.rank)?;
+            if !route.content_type.is_any() {
+                write!(f, " {}", route.content_type)?;
+            }
+
+            write!(f, ": {}", candidate.verdict)?;
+        }
+
+        Ok(())
+    }
+}
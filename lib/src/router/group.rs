@@ -0,0 +1,250 @@
+use std::any::Any;
+
+use handler::Guard;
+use request::{Request, FromRequest};
+use outcome::Outcome;
+use http::{ContentType, Method, Status};
+
+use super::{Route, Resource};
+
+/// A builder for a group of routes that share a path prefix, a format, and
+/// route guards.
+///
+/// Large APIs often have many routes that live under a common prefix, accept
+/// the same format, and require the same request guard. Instead of repeating
+/// these on every route, a group can apply them to a vector of routes at once.
+/// The resulting vector is then mounted as usual.
+///
+/// A group's guards are checked, in the order they were added, before a
+/// route's handler is called. If a guard forwards, the request is forwarded to
+/// the next matching route. If a guard fails, the request fails with the
+/// guard's status.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// #
+/// use rocket::Routes;
+/// use rocket::http::ContentType;
+/// # use rocket::Outcome;
+/// # use rocket::request::{self, Request, FromRequest};
+/// # struct APIKey;
+/// # impl<'a, 'r> FromRequest<'a, 'r> for APIKey {
+/// #     type Error = ();
+/// #     fn from_request(_: &'a Request<'r>) -> request::Outcome<APIKey, ()> {
+/// #         Outcome::Success(APIKey)
+/// #     }
+/// # }
+///
+/// #[get("/users")]
+/// fn users() -> &'static str { "[]" }
+///
+/// #[post("/users")]
+/// fn new_user() -> &'static str { "{}" }
+///
+/// fn main() {
+///     let api = Routes::group("/api/v1")
+///         .format(ContentType::JSON)
+///         .guard::<APIKey>()
+///         .routes(routes![users, new_user]);
+///
+/// # if false { // We don't actually want to launch the server in an example.
+///     rocket::ignite().mount("/", api)
+/// #       .launch()
+/// # }
+/// }
+/// ```
+pub struct Routes {
+    prefix: String,
+    format: Option<ContentType>,
+    guards: Vec<Guard>,
+}
+
+/// Runs the `FromRequest` implementation for `T`, discarding the value and
+/// recording the error on failure.
+fn guard<T>(request: &Request) -> Outcome<(), Status, ()>
+    where T: for<'a, 'r> FromRequest<'a, 'r>,
+          for<'a, 'r> <T as FromRequest<'a, 'r>>::Error: Any
+{
+    match T::from_request(request) {
+        Outcome::Success(_) => Outcome::Success(()),
+        Outcome::Forward(_) => Outcome::Forward(()),
+        Outcome::Failure((status, e)) => {
+            request.set_guard_failure(status, e);
+            Outcome::Failure(status)
+        }
+    }
+}
+
+impl Routes {
+    /// Creates a new group of routes with the path prefix `prefix`.
+    ///
+    /// # Panics
+    ///
+    /// The prefix must be a static path. That is, it must _not_ contain
+    /// dynamic path parameters: `<param>`.
+    pub fn group<S: AsRef<str>>(prefix: S) -> Routes {
+        let prefix = prefix.as_ref();
+        if prefix.contains('<') {
+            error_!("Bad group prefix: '{}'.", prefix);
+            error_!("Group prefixes must be static paths!");
+            panic!("Bad group prefix.")
+        }
+
+        Routes {
+            prefix: prefix.to_string(),
+            format: None,
+            guards: vec![],
+        }
+    }
+
+    /// Sets the format of the routes in the group to `format`. Routes that
+    /// declare their own format keep it.
+    #[inline(always)]
+    pub fn format(mut self, format: ContentType) -> Routes {
+        self.format = Some(format);
+        self
+    }
+
+    /// Adds the request guard `T` to the routes in the group. Group guards are
+    /// checked before any guards a route already had.
+    #[inline(always)]
+    pub fn guard<T>(mut self) -> Routes
+        where T: for<'a, 'r> FromRequest<'a, 'r>,
+              for<'a, 'r> <T as FromRequest<'a, 'r>>::Error: Any
+    {
+        self.guards.push(guard::<T>);
+        self
+    }
+
+    /// Applies the group's prefix, format, and guards to each route in
+    /// `routes`, returning the modified routes.
+    pub fn routes(&self, routes: Vec<Route>) -> Vec<Route> {
+        routes.into_iter().map(|mut route| {
+            let path = format!("{}/{}", self.prefix, route.path);
+            route.set_path(path);
+
+            if let Some(ref format) = self.format {
+                if route.content_type.is_any() {
+                    route.content_type = format.clone();
+                }
+            }
+
+            let mut guards = self.guards.clone();
+            guards.extend(route.guards.drain(..));
+            route.guards = guards;
+            route
+        }).collect()
+    }
+    /// Creates a route for each of the methods of the resource `R` at `path`,
+    /// then applies the group's prefix, format, and guards to them as
+    /// [routes](#method.routes) does. See [Resource](/rocket/trait.Resource.html)
+    /// for more information.
+    pub fn resource<R: Resource>(&self, path: &str) -> Vec<Route> {
+        self.routes(vec![
+            Route::new(Method::Get, path, R::get),
+            Route::new(Method::Post, path, R::post),
+            Route::new(Method::Put, path, R::put),
+            Route::new(Method::Delete, path, R::delete),
+            Route::new(Method::Patch, path, R::patch),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Routes;
+
+    use outcome::Outcome;
+    use request::{self, Request, FromRequest};
+    use http::Method::*;
+    use http::{ContentType, Status};
+    use data::Data;
+    use handler;
+    use router::Route;
+
+    struct Pass;
+    struct Block;
+
+    impl<'a, 'r> FromRequest<'a, 'r> for Pass {
+        type Error = ();
+
+        fn from_request(_: &'a Request<'r>) -> request::Outcome<Pass, ()> {
+            Outcome::Success(Pass)
+        }
+    }
+
+    impl<'a, 'r> FromRequest<'a, 'r> for Block {
+        type Error = ();
+
+        fn from_request(_: &'a Request<'r>) -> request::Outcome<Block, ()> {
+            Outcome::Failure((Status::Unauthorized, ()))
+        }
+    }
+
+    fn dummy_handler(_req: &Request, _: Data) -> handler::Outcome<'static> {
+        handler::Outcome::of("hi")
+    }
+
+    #[test]
+    fn test_prefix_and_format() {
+        let routes = Routes::group("/api/v1").format(ContentType::JSON).routes(vec![
+            Route::new(Get, "/users", dummy_handler),
+            Route::new(Get, "/<id>", dummy_handler),
+        ]);
+
+        assert_eq!(routes[0].path.as_str(), "/api/v1//users");
+        assert_eq!(routes[1].path.as_str(), "/api/v1//<id>");
+        assert_eq!(routes[0].path.segments().collect::<Vec<_>>(),
+                   vec!["api", "v1", "users"]);
+
+        for route in &routes {
+            assert_eq!(route.content_type, ContentType::JSON);
+        }
+    }
+
+    #[test]
+    fn test_route_format_kept() {
+        let mut route = Route::new(Post, "/", dummy_handler);
+        route.content_type = ContentType::Form;
+
+        let routes = Routes::group("/").format(ContentType::JSON).routes(vec![route]);
+        assert_eq!(routes[0].content_type, ContentType::Form);
+    }
+
+    #[test]
+    fn test_guards() {
+        let request = Request::new(Get, "/");
+
+        let routes = Routes::group("/").guard::<Pass>().routes(vec![
+            Route::new(Get, "/", dummy_handler)
+        ]);
+
+        assert_eq!(routes[0].guards.len(), 1);
+        assert!(routes[0].check_guards(&request).is_success());
+
+        let routes = Routes::group("/").guard::<Pass>().guard::<Block>().routes(vec![
+            Route::new(Get, "/", dummy_handler)
+        ]);
+
+        assert_eq!(routes[0].guards.len(), 2);
+        match routes[0].check_guards(&request) {
+            Outcome::Failure(status) => assert_eq!(status, Status::Unauthorized),
+            _ => panic!("expected the guard to fail")
+        }
+
+        let failure = request.guard_failure().expect("recorded guard failure");
+        assert_eq!(failure.status(), Status::Unauthorized);
+        assert_eq!(failure.error(), "()");
+        assert_eq!(failure.downcast_ref::<()>(), Some(&()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_dynamic_prefix() {
+        Routes::group("/<name>");
+    }
+}
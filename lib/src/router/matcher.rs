@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use http::uri::URI;
+
+/// A custom segment matcher: decides whether a percent-decoded path segment
+/// matches a `<name:matcher>` placeholder.
+pub type Matcher = Box<Fn(&str) -> bool + Send + Sync + 'static>;
+
+/// Returns the matcher specification of the route path segment `segment`, if
+/// it's a single-segment placeholder with one: `uuid` for `<id:uuid>`.
+pub fn segment_spec(segment: &str) -> Option<&str> {
+    if !segment.starts_with('<') || !segment.ends_with('>') || segment.ends_with("..>") {
+        return None;
+    }
+
+    let inner = &segment[1..(segment.len() - 1)];
+    inner.find(':').map(|i| &inner[(i + 1)..])
+}
+
+/// A character class followed by a quantifier, such as `[a-z0-9-]+`. This is
+/// the only form of inline pattern supported. There's no `?` quantifier since
+/// `?` begins a route's query; `{0,1}` is equivalent.
+#[derive(Debug, Clone, PartialEq)]
+struct Pattern {
+    ranges: Vec<(char, char)>,
+    negated: bool,
+    min: usize,
+    max: Option<usize>,
+}
+
+impl Pattern {
+    fn parse(spec: &str) -> Result<Pattern, String> {
+        let err = |msg: &str| Err(format!("invalid pattern '{}': {}", spec, msg));
+        if !spec.starts_with('[') {
+            return err("expected '['");
+        }
+
+        let mut chars = spec[1..].chars().peekable();
+        let negated = chars.peek() == Some(&'^');
+        if negated {
+            chars.next();
+        }
+
+        // Parse the class up to the closing ']'. A `-` between two characters
+        // denotes a range; elsewhere, it's literal. `\` escapes a character.
+        let mut ranges = vec![];
+        let mut closed = false;
+        while let Some(c) = chars.next() {
+            let c = match c {
+                ']' => {
+                    closed = true;
+                    break;
+                }
+                '\\' => match chars.next() {
+                    Some(escaped) => escaped,
+                    None => return err("trailing '\\'")
+                },
+                c => c
+            };
+
+            let is_range = chars.peek() == Some(&'-')
+                && chars.clone().nth(1).map_or(false, |next| next != ']');
+
+            if is_range {
+                chars.next();
+                let end = match chars.next() {
+                    Some('\\') => chars.next().unwrap_or('\\'),
+                    Some(end) => end,
+                    None => return err("unterminated range")
+                };
+
+                if end < c {
+                    return err("range out of order");
+                }
+
+                ranges.push((c, end));
+            } else {
+                ranges.push((c, c));
+            }
+        }
+
+        if !closed {
+            return err("missing ']'");
+        } else if ranges.is_empty() {
+            return err("empty class");
+        }
+
+        let quantifier: String = chars.collect();
+        let (min, max) = match quantifier.as_str() {
+            "" => (1, Some(1)),
+            "+" => (1, None),
+            "*" => (0, None),
+            q if q.starts_with('{') && q.ends_with('}') => {
+                let bounds = &q[1..(q.len() - 1)];
+                let mut parts = bounds.splitn(2, ',');
+                let min = parts.next().unwrap_or("").trim().parse::<usize>();
+                let max = parts.next().map(|m| m.trim());
+                match (min, max) {
+                    (Ok(min), None) => (min, Some(min)),
+                    (Ok(min), Some("")) => (min, None),
+                    (Ok(min), Some(max)) => match max.parse::<usize>() {
+                        Ok(max) if max >= min => (min, Some(max)),
+                        _ => return err("invalid repetition bounds")
+                    },
+                    _ => return err("invalid repetition bounds")
+                }
+            }
+            _ => return err("unknown quantifier")
+        };
+
+        Ok(Pattern { ranges: ranges, negated: negated, min: min, max: max })
+    }
+
+    fn matches(&self, segment: &str) -> bool {
+        let mut count = 0;
+        for c in segment.chars() {
+            let in_class = self.ranges.iter().any(|&(lo, hi)| c >= lo && c <= hi);
+            if in_class == self.negated {
+                return false;
+            }
+
+            count += 1;
+        }
+
+        count >= self.min && self.max.map_or(true, |max| count <= max)
+    }
+}
+
+fn is_uuid(segment: &str) -> bool {
+    let groups: Vec<_> = segment.split('-').collect();
+    groups.len() == 5
+        && groups.iter().zip(&[8, 4, 4, 4, 12]).all(|(group, &len)| {
+            group.len() == len && group.chars().all(|c| c.is_digit(16))
+        })
+}
+
+/// The segment matchers known to a router: the built-in matchers, those
+/// registered by the application, and the inline patterns of its routes.
+pub struct Matchers {
+    named: HashMap<String, Matcher>,
+    patterns: HashMap<String, Result<Pattern, String>>,
+}
+
+impl Matchers {
+    pub fn new() -> Matchers {
+        let mut matchers = Matchers { named: HashMap::new(), patterns: HashMap::new() };
+        matchers.add("int", Box::new(|s: &str| s.parse::<i64>().is_ok()));
+        matchers.add("uint", Box::new(|s: &str| s.parse::<u64>().is_ok()));
+        matchers.add("alpha", Box::new(|s: &str| {
+            !s.is_empty() && s.chars().all(|c| c.is_alphabetic())
+        }));
+        matchers.add("alnum", Box::new(|s: &str| {
+            !s.is_empty() && s.chars().all(|c| c.is_alphanumeric())
+        }));
+        matchers.add("uuid", Box::new(is_uuid));
+        matchers
+    }
+
+    /// Registers `matcher` under `name`, replacing any matcher of that name.
+    pub fn add(&mut self, name: &str, matcher: Matcher) {
+        self.named.insert(name.to_string(), matcher);
+    }
+
+    /// Compiles the inline patterns in `path`, a route's path, so that they
+    /// needn't be compiled per request.
+    pub fn compile(&mut self, path: &URI) {
+        for spec in path.segments().filter_map(segment_spec) {
+            if spec.starts_with('[') && !self.patterns.contains_key(spec) {
+                self.patterns.insert(spec.to_string(), Pattern::parse(spec));
+            }
+        }
+    }
+
+    /// Checks that every matcher in `path` is known or is a valid pattern.
+    pub fn validate(&self, path: &URI) -> Result<(), String> {
+        for spec in path.segments().filter_map(segment_spec) {
+            if spec.starts_with('[') {
+                match self.patterns.get(spec) {
+                    Some(&Err(ref e)) => return Err(e.clone()),
+                    Some(&Ok(_)) => continue,
+                    None => Pattern::parse(spec).map(|_| ())?
+                }
+            } else if !self.named.contains_key(spec) {
+                return Err(format!("unknown segment matcher '{}'", spec));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether `segment`, a raw segment of a request's path, matches
+    /// the matcher `spec`. Unknown matchers and invalid patterns never match.
+    pub fn matches(&self, spec: &str, segment: &str) -> bool {
+        let decoded = match URI::percent_decode(segment.as_bytes()) {
+            Ok(decoded) => decoded,
+            Err(_) => return false
+        };
+
+        if spec.starts_with('[') {
+            match self.patterns.get(spec) {
+                Some(&Ok(ref pattern)) => pattern.matches(&decoded),
+                Some(&Err(_)) => false,
+                None => Pattern::parse(spec).map_or(false, |p| p.matches(&decoded))
+            }
+        } else {
+            self.named.get(spec).map_or(false, |matcher| matcher(&decoded))
+        }
+    }
+
+    /// Returns whether every segment matcher in `route`, a route's path,
+    /// matches the corresponding segment of `uri`, a request's path whose
+    /// segments are known to line up with the route's.
+    pub fn accept(&self, route: &URI, uri: &URI) -> bool {
+        for (route_seg, uri_seg) in route.segments().zip(uri.segments()) {
+            if route_seg.ends_with("..>") {
+                break;
+            }
+
+            if let Some(spec) = segment_spec(route_seg) {
+                if !self.matches(spec, uri_seg) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns `true` if no request path can match both `a` and `b`, two
+    /// route paths, because a segment matcher in one rejects a static segment
+    /// at the same position in the other.
+    pub fn separate(&self, a: &URI, b: &URI) -> bool {
+        for (seg_a, seg_b) in a.segments().zip(b.segments()) {
+            if seg_a.ends_with("..>") || seg_b.ends_with("..>") {
+                break;
+            }
+
+            let rejects = |spec: Option<&str>, other: &str| {
+                spec.map_or(false, |spec| !other.contains('<') && !self.matches(spec, other))
+            };
+
+            if rejects(segment_spec(seg_a), seg_b) || rejects(segment_spec(seg_b), seg_a) {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+impl fmt::Debug for Matchers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut names: Vec<_> = self.named.keys().collect();
+        names.sort();
+        f.debug_struct("Matchers").field("named", &names).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Matchers, Pattern, segment_spec};
+    use http::uri::URI;
+
+    #[test]
+    fn test_segment_spec() {
+        assert_eq!(segment_spec("<id:uuid>"), Some("uuid"));
+        assert_eq!(segment_spec("<slug:[a-z-]+>"), Some("[a-z-]+"));
+        assert_eq!(segment_spec("<id>"), None);
+        assert_eq!(segment_spec("<path..>"), None);
+        assert_eq!(segment_spec("static"), None);
+    }
+
+    #[test]
+    fn test_patterns() {
+        let slug = Pattern::parse("[a-z0-9-]+").unwrap();
+        assert!(slug.matches("hello-world-2"));
+        assert!(!slug.matches("Hello"));
+        assert!(!slug.matches(""));
+
+        let hex = Pattern::parse("[0-9a-fA-F]{2,4}").unwrap();
+        assert!(hex.matches("fF") && hex.matches("0a1b"));
+        assert!(!hex.matches("a") && !hex.matches("abcde"));
+
+        let not_digits = Pattern::parse("[^0-9]*").unwrap();
+        assert!(not_digits.matches("") && not_digits.matches("abc"));
+        assert!(!not_digits.matches("a1"));
+
+        assert!(Pattern::parse("[a-z").is_err());
+        assert!(Pattern::parse("[z-a]+").is_err());
+        assert!(Pattern::parse("[a-z]{3,1}").is_err());
+        assert!(Pattern::parse("[a-z]x").is_err());
+        assert!(Pattern::parse("a-z").is_err());
+    }
+
+    #[test]
+    fn test_builtin_matchers() {
+        let matchers = Matchers::new();
+        assert!(matchers.matches("int", "-12") && !matchers.matches("int", "1.5"));
+        assert!(matchers.matches("uint", "12") && !matchers.matches("uint", "-12"));
+        assert!(matchers.matches("uuid", "67e55044-10b1-426f-9247-bb680e5fe0c8"));
+        assert!(!matchers.matches("uuid", "67e55044-10b1-426f-9247-bb680e5fe0c"));
+        assert!(matchers.matches("alpha", "caf%C3%A9"));
+        assert!(!matchers.matches("unknown", "anything"));
+    }
+
+    #[test]
+    fn test_separate() {
+        let matchers = Matchers::new();
+        let (a, b) = (URI::new("/users/<id:uint>"), URI::new("/users/new"));
+        assert!(matchers.separate(&a, &b) && matchers.separate(&b, &a));
+
+        let c = URI::new("/users/10");
+        assert!(!matchers.separate(&a, &c));
+        assert!(!matchers.separate(&a, &URI::new("/users/<name>")));
+        assert!(!matchers.separate(&a, &URI::new("/users/<name:alpha>")));
+    }
+}
@@ -1,33 +1,59 @@
 mod collider;
 mod route;
+mod group;
+mod explain;
+mod resource;
+mod matcher;
 
 pub use self::collider::Collider;
 pub use self::route::Route;
+pub use self::group::Routes;
+pub use self::resource::Resource;
+pub use self::explain::{Explanation, Candidate, Verdict};
+pub use self::matcher::Matcher;
+
+use self::matcher::Matchers;
 
 use std::collections::hash_map::HashMap;
+use std::cmp::Ordering;
 
 use request::Request;
 use http::Method;
+use http::uri::URI;
 
 // type Selector = (Method, usize);
 type Selector = Method;
 
-#[derive(Default)]
 pub struct Router {
     routes: HashMap<Selector, Vec<Route>>, // using 'selector' for now
+    matchers: Matchers,
 }
 
 impl Router {
     pub fn new() -> Router {
-        Router { routes: HashMap::new() }
+        Router { routes: HashMap::new(), matchers: Matchers::new() }
     }
 
     pub fn add(&mut self, route: Route) {
         // let selector = (route.method, route.path.segment_count());
+        self.matchers.compile(&route.path);
         let selector = route.method;
         self.routes.entry(selector).or_insert_with(|| vec![]).push(route);
     }
 
+    /// Registers the segment matcher `matcher` under `name`.
+    pub fn add_matcher(&mut self, name: &str, matcher: Matcher) {
+        self.matchers.add(name, matcher);
+    }
+
+    /// Returns an error message for each route with an unknown segment
+    /// matcher or an invalid inline pattern.
+    pub fn matcher_errors(&self) -> Vec<String> {
+        self.routes().filter_map(|route| {
+            self.matchers.validate(&route.path).err().map(|e| format!("{}: {}", route, e))
+        }).collect()
+    }
+
     // TODO: Make a `Router` trait with this function. Rename this `Router`
     // struct to something like `RocketRouter`. If that happens, returning a
     // `Route` structure is inflexible. Have it be an associated type.
@@ -40,21 +66,94 @@ impl Router {
         self.routes.get(&req.method()).map_or(vec![], |routes| {
             let mut matches: Vec<_> = routes.iter()
                 .filter(|r| r.collides_with(req))
+                .filter(|r| self.matchers.accept(&r.path, req.uri()))
                 .collect();
 
-            // FIXME: Presort vector to avoid a sort on each route.
-            matches.sort_by(|a, b| a.rank.cmp(&b.rank));
+            // FIXME: Presort vector to avoid a sort on each route. Routes of
+            // equal rank are tried in the order the request prefers their
+            // formats; the sort is stable, so ties keep their order.
+            matches.sort_by(|a, b| match a.rank.cmp(&b.rank) {
+                Ordering::Equal => {
+                    let (q_a, q_b) = (a.format_quality(req), b.format_quality(req));
+                    q_b.partial_cmp(&q_a).unwrap_or(Ordering::Equal)
+                }
+                ordering => ordering
+            });
             trace_!("All matches: {:?}", matches);
             matches
         })
     }
 
+    /// Returns the methods of the routes whose path matches `req`'s, in a
+    /// canonical order. `HEAD` is included if `GET` is, since `HEAD` requests
+    /// are handled by `GET` routes.
+    pub fn allowed_methods(&self, req: &Request) -> Vec<Method> {
+        use http::Method::*;
+
+        let methods: Vec<Method> = self.routes()
+            .filter(|r| req.uri().collides_with(&r.path))
+            .filter(|r| self.matchers.accept(&r.path, req.uri()))
+            .map(|r| r.method)
+            .collect();
+
+        let all = [Options, Get, Head, Post, Put, Patch, Delete, Trace, Connect];
+        all.iter()
+            .filter(|&&m| methods.contains(&m) || (m == Head && methods.contains(&Get)))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns an iterator over all of the routes in the router.
+    pub fn routes<'a>(&'a self) -> impl Iterator<Item = &'a Route> + 'a {
+        self.routes.values().flat_map(|routes| routes.iter())
+    }
+
+    /// Returns the location to redirect `req` to if it's for a non-canonical
+    /// URL of a route: the first route `req` matches, if it matches any, or
+    /// else the first route with a canonical form that the canonical form of
+    /// `req`'s path matches. `HEAD` requests are also checked against `GET`
+    /// routes, since they're handled by them.
+    pub fn canonical_location(&self, req: &Request) -> Option<String> {
+        if let Some(route) = self.route(req).first() {
+            return route.canonical.as_ref().and_then(|c| c.redirect_location(req));
+        }
+
+        let methods = match req.method() {
+            Method::Head => vec![Method::Head, Method::Get],
+            method => vec![method]
+        };
+
+        methods.iter().filter_map(|method| self.routes.get(method)).filter_map(|routes| {
+            routes.iter().filter_map(|route| {
+                let canonical = match route.canonical {
+                    Some(ref canonical) => canonical,
+                    None => return None
+                };
+
+                let path = canonical.canonical_path(req.uri().path());
+                let uri = URI::new(path.as_ref());
+                if uri.collides_with(&route.path) && self.matchers.accept(&route.path, &uri) {
+                    canonical.redirect_location(req)
+                } else {
+                    None
+                }
+            }).next()
+        }).next()
+    }
+
+    /// Returns an explanation of how `req` would be routed.
+    pub fn explain<'b>(&'b self, req: &Request) -> Explanation<'b> {
+        let routes = self.routes().filter(move |r| self.matchers.accept(&r.path, req.uri()));
+        Explanation::new(routes, req)
+    }
+
     pub fn has_collisions(&self) -> bool {
         let mut result = false;
         for routes in self.routes.values() {
             for (i, a_route) in routes.iter().enumerate() {
                 for b_route in routes.iter().skip(i + 1) {
-                    if a_route.collides_with(b_route) {
+                    if a_route.collides_with(b_route)
+                        && !self.matchers.separate(&a_route.path, &b_route.path) {
                         result = true;
                         warn!("{} and {} collide!", a_route, b_route);
                     }
@@ -362,4 +461,27 @@ mod test {
         assert!(match_params(&router, "/hello/sergio/age", &["sergio"]));
         assert!(match_params(&router, "/hello/you/age", &["you"]));
     }
+
+    #[test]
+    fn test_matcher_routing() {
+        let mut router = router_with_routes(&["/<id:uint>", "/<slug:[a-z-]+>"]);
+        assert_eq!(route(&router, Get, "/12").unwrap().path.as_str(), "/<id:uint>");
+        assert_eq!(route(&router, Get, "/a-b").unwrap().path.as_str(), "/<slug:[a-z-]+>");
+        assert!(route(&router, Get, "/A").is_none());
+
+        router.add_matcher("upper", Box::new(|s: &str| s.chars().all(|c| c.is_uppercase())));
+        router.add(Route::new(Get, "/<name:upper>", dummy_handler));
+        assert_eq!(route(&router, Get, "/A").unwrap().path.as_str(), "/<name:upper>");
+        assert!(router.matcher_errors().is_empty());
+
+        router.add(Route::new(Get, "/x/<name:unknown>/<b:[b-a]>", dummy_handler));
+        assert_eq!(router.matcher_errors().len(), 1);
+    }
+
+    #[test]
+    fn test_matcher_collisions() {
+        assert!(!unranked_route_collisions(&["/users/<id:uint>", "/users/new"]));
+        assert!(unranked_route_collisions(&["/users/<id:uint>", "/users/10"]));
+        assert!(unranked_route_collisions(&["/users/<id:uint>", "/users/<name>"]));
+    }
 }
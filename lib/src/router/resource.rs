@@ -0,0 +1,73 @@
+use request::Request;
+use data::Data;
+use handler::Outcome;
+
+/// A resource: a set of handlers, one per method, for a single path.
+///
+/// Larger applications are often easier to organize around resources than
+/// around individual routes. A type implementing `Resource` provides handlers
+/// for whichever of the `GET`, `POST`, `PUT`, `DELETE`, and `PATCH` methods it
+/// supports; handlers that aren't implemented forward. The resource is then
+/// turned into routes, one per method, that share a path, format, and guards
+/// via [Routes::resource](/rocket/struct.Routes.html#method.resource).
+///
+/// Handlers receive the raw request and data, exactly like manually created
+/// routes. Dynamic path parameters are retrieved via
+/// [Request::get_param](/rocket/struct.Request.html#method.get_param).
+///
+/// # Example
+///
+/// ```rust
+/// use rocket::{Request, Data, Resource, Routes};
+/// use rocket::handler::Outcome;
+/// use rocket::http::ContentType;
+///
+/// struct User;
+///
+/// impl Resource for User {
+///     fn get<'r>(request: &'r Request, data: Data) -> Outcome<'r> {
+///         match request.get_param::<usize>(0) {
+///             Ok(id) => Outcome::of(format!("user {}", id)),
+///             Err(_) => Outcome::forward(data)
+///         }
+///     }
+///
+///     fn delete<'r>(_: &'r Request, _: Data) -> Outcome<'r> {
+///         Outcome::of("deleted")
+///     }
+/// }
+///
+/// let routes = Routes::group("/api")
+///     .format(ContentType::JSON)
+///     .resource::<User>("/users/<id>");
+///
+/// # if false {
+/// rocket::ignite().mount("/", routes).launch()
+/// # }
+/// ```
+pub trait Resource {
+    /// Handles `GET` requests, and `HEAD` requests automatically.
+    fn get<'r>(_: &'r Request, data: Data) -> Outcome<'r> {
+        Outcome::forward(data)
+    }
+
+    /// Handles `POST` requests.
+    fn post<'r>(_: &'r Request, data: Data) -> Outcome<'r> {
+        Outcome::forward(data)
+    }
+
+    /// Handles `PUT` requests.
+    fn put<'r>(_: &'r Request, data: Data) -> Outcome<'r> {
+        Outcome::forward(data)
+    }
+
+    /// Handles `DELETE` requests.
+    fn delete<'r>(_: &'r Request, data: Data) -> Outcome<'r> {
+        Outcome::forward(data)
+    }
+
+    /// Handles `PATCH` requests.
+    fn patch<'r>(_: &'r Request, data: Data) -> Outcome<'r> {
+        Outcome::forward(data)
+    }
+}
@@ -1,3 +1,4 @@
+use std::any::TypeId;
 use std::fmt;
 use std::convert::From;
 
@@ -7,9 +8,11 @@ use term_painter::ToStyle;
 use term_painter::Color::*;
 
 use codegen::StaticRouteInfo;
-use handler::Handler;
+use canonical::Canonical;
+use handler::{Handler, Guard, ParamCheck};
 use request::Request;
-use http::{Method, ContentType};
+use outcome::Outcome;
+use http::{Method, ContentType, Status};
 use http::uri::URI;
 
 /// A route: a method, its handler, path, rank, and format/content type.
@@ -22,8 +25,30 @@ pub struct Route {
     pub path: URI<'static>,
     /// The rank of this route. Lower ranks have higher priorities.
     pub rank: isize,
-    /// The Content-Type this route matches against.
+    /// The format this route matches against: the `Content-Type` of requests
+    /// with methods that support a payload, such as `POST`, and a media type
+    /// in the `Accept` header of other requests, such as `GET`.
     pub content_type: ContentType,
+    /// The name of the feature flag that enables this route, if any. See the
+    /// [feature](/rocket/feature/index.html) module.
+    pub feature: Option<String>,
+    /// The canonical form of the route's URLs, if it has one. See the
+    /// [canonical](/rocket/canonical/index.html) module.
+    pub canonical: Option<Canonical>,
+    /// Guards that must succeed, in order, before the handler is called.
+    pub guards: Vec<Guard>,
+    /// The types, and their names, of the managed state the handler requests.
+    pub state: Vec<(TypeId, &'static str)>,
+    /// Checks that the route's dynamic path segments parse, if known. Routes
+    /// generated by codegen always have one.
+    #[doc(hidden)]
+    pub params: Option<ParamCheck>,
+    /// The descriptions of the handler's parameters, as (name, description)
+    /// pairs in the order of the handler's arguments. Codegen captures these
+    /// from the handler's `#[param(name = "..", description = "..")]`
+    /// attributes and from the lines of its doc comment of the form ``*
+    /// `name` - description``.
+    pub param_docs: Vec<(&'static str, &'static str)>,
 }
 
 fn default_rank(path: &str) -> isize {
@@ -46,6 +71,12 @@ impl Route {
             rank: default_rank(path.as_ref()),
             path: URI::from(path.as_ref().to_string()),
             content_type: ContentType::Any,
+            feature: None,
+            canonical: None,
+            guards: vec![],
+            state: vec![],
+            params: None,
+            param_docs: vec![],
         }
     }
 
@@ -59,6 +90,12 @@ impl Route {
             handler: handler,
             rank: rank,
             content_type: ContentType::Any,
+            feature: None,
+            canonical: None,
+            guards: vec![],
+            state: vec![],
+            params: None,
+            param_docs: vec![],
         }
     }
 
@@ -68,6 +105,80 @@ impl Route {
         self.path = URI::from(path.as_ref().to_string());
     }
 
+    /// Returns the description of the handler's parameter named `name`, if it
+    /// has one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::{Route, Request, Data};
+    /// use rocket::handler::Outcome;
+    /// use rocket::http::Method;
+    ///
+    /// fn handler<'r>(request: &'r Request, _: Data) -> Outcome<'r> {
+    ///     Outcome::of(request.get_param::<&str>(0).unwrap_or("?"))
+    /// }
+    ///
+    /// let mut route = Route::new(Method::Get, "/<name>", handler);
+    /// route.param_docs.push(("name", "The name to greet."));
+    /// assert_eq!(route.param_description("name"), Some("The name to greet."));
+    /// assert_eq!(route.param_description("age"), None);
+    /// ```
+    pub fn param_description(&self, name: &str) -> Option<&'static str> {
+        self.param_docs.iter()
+            .find(|&&(param, _)| param == name)
+            .map(|&(_, description)| description)
+    }
+
+    /// Runs each of the guards for this route against `request` in order,
+    /// returning the outcome of the first guard that doesn't succeed. If all
+    /// of the guards succeed, or there are none, returns `Success`.
+    #[doc(hidden)]
+    pub fn check_guards(&self, request: &Request) -> Outcome<(), Status, ()> {
+        for guard in self.guards.iter() {
+            match guard(request) {
+                Outcome::Success(()) => continue,
+                outcome => return outcome
+            }
+        }
+
+        Outcome::Success(())
+    }
+
+    /// Returns the name of the request header this route's format is matched
+    /// against: `Content-Type` if the route's method supports a payload and
+    /// `Accept` otherwise.
+    #[doc(hidden)]
+    #[inline]
+    pub fn format_header(&self) -> &'static str {
+        if self.method.supports_payload() { "Content-Type" } else { "Accept" }
+    }
+
+    /// Returns the quality value of this route's format for `request`: how
+    /// much `request` prefers this route's format, between `0` and `1`. The
+    /// format of a route without one, or of a route whose method supports a
+    /// payload, always has quality `1` if it matches and `0` otherwise.
+    #[doc(hidden)]
+    pub fn format_quality(&self, request: &Request) -> f32 {
+        if self.content_type.is_any() {
+            return 1.0;
+        }
+
+        if self.method.supports_payload() {
+            let matches = request.content_type().collides_with(&self.content_type);
+            return if matches { 1.0 } else { 0.0 };
+        }
+
+        request.accept().map_or(1.0, |accept| accept.quality_of(&self.content_type))
+    }
+
+    /// Returns `true` if this route's format matches `request`.
+    #[doc(hidden)]
+    #[inline]
+    pub fn format_matches(&self, request: &Request) -> bool {
+        self.format_quality(request) > 0.0
+    }
+
     // FIXME: Decide whether a component has to be fully variable or not. That
     // is, whether you can have: /a<a>b/ or even /<a>:<b>/
     // TODO: Don't return a Vec...take in an &mut [&'a str] (no alloc!)
@@ -102,6 +213,12 @@ impl Clone for Route {
             rank: self.rank,
             path: self.path.clone(),
             content_type: self.content_type.clone(),
+            feature: self.feature.clone(),
+            canonical: self.canonical.clone(),
+            guards: self.guards.clone(),
+            state: self.state.clone(),
+            params: self.params,
+            param_docs: self.param_docs.clone(),
         }
     }
 }
@@ -114,6 +231,10 @@ impl fmt::Display for Route {
             write!(f, " [{}]", White.paint(&self.rank))?;
         }
 
+        if let Some(ref feature) = self.feature {
+            write!(f, " ({})", Magenta.paint(feature))?;
+        }
+
         if !self.content_type.is_any() {
             write!(f, " {}", Yellow.paint(&self.content_type))
         } else {
@@ -137,6 +258,13 @@ impl<'a> From<&'a StaticRouteInfo> for Route {
             route.rank = rank;
         }
 
+        route.feature = info.feature.map(|feature| feature.to_string());
+        route.canonical = info.canonical.map(|canonical| {
+            canonical.parse().expect("codegen validates canonical options")
+        });
+        route.state = (info.state)();
+        route.params = Some(info.params);
+        route.param_docs = info.param_docs.to_vec();
         route
     }
 }
@@ -154,6 +282,6 @@ impl<'r> Collider<Request<'r>> for Route {
     fn collides_with(&self, req: &Request<'r>) -> bool {
         self.method == req.method()
             && req.uri().collides_with(&self.path)
-            && req.content_type().collides_with(&self.content_type)
+            && self.format_matches(req)
     }
 }
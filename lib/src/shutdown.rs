@@ -0,0 +1,361 @@
+//! Why an application stopped serving and how that's reported to the process.
+//!
+//! [Rocket::run](/rocket/struct.Rocket.html#method.run) serves an application
+//! until it's shut down, then returns a [ShutdownReason](enum.ShutdownReason.html)
+//! describing why: shutdown was requested via a
+//! [ShutdownHandle](struct.ShutdownHandle.html), the process received `SIGINT`
+//! or `SIGTERM`, or the server couldn't start. Each reason maps to a distinct
+//! process exit code, so that a supervisor can tell a clean stop from a crash
+//! or a configuration error. [Rocket::launch](/rocket/struct.Rocket.html#method.launch)
+//! exits the process with that code.
+//!
+//! | Reason      | Exit code             |
+//! |-------------|-----------------------|
+//! | `Requested` | 0                     |
+//! | `Signal(n)` | 128 + `n`             |
+//! | `Fatal`     | 70 (`EX_SOFTWARE`)    |
+//! | `Config`    | 78 (`EX_CONFIG`)      |
+//!
+//! # Example
+//!
+//! Shut the application down from a route:
+//!
+//! ```rust
+//! # #![feature(plugin)]
+//! # #![plugin(rocket_codegen)]
+//! # extern crate rocket;
+//! use rocket::State;
+//! use rocket::shutdown::ShutdownHandle;
+//!
+//! #[post("/shutdown")]
+//! fn shutdown(handle: State<ShutdownHandle>) -> &'static str {
+//!     handle.shutdown();
+//!     "Shutting down..."
+//! }
+//!
+//! fn main() {
+//! # if false {
+//!     let rocket = rocket::ignite().mount("/", routes![shutdown]);
+//!     let handle = rocket.shutdown_handle();
+//!     let reason = rocket.manage(handle).run();
+//!     println!("stopped: {}", reason);
+//!     reason.exit();
+//! # }
+//! }
+//! ```
+
+use std::fmt;
+use std::io;
+use std::process;
+use std::thread;
+use std::time::Duration;
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+use std::sync::{Arc, Mutex, Condvar};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+
+use http::hyper::net::{HttpListener, HttpStream, NetworkListener};
+
+/// Why an application stopped serving.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ShutdownReason {
+    /// Shutdown was requested via a [ShutdownHandle](struct.ShutdownHandle.html).
+    Requested,
+    /// The process received the signal with the given number, such as `SIGINT`
+    /// or `SIGTERM`.
+    Signal(i32),
+    /// The server failed, as when it couldn't bind to its address; the field
+    /// describes why.
+    Fatal(String),
+    /// The configuration is invalid; the field describes why.
+    Config(String),
+}
+
+impl ShutdownReason {
+    /// Returns the process exit code for this reason: `0` for `Requested`,
+    /// `128` plus the signal number for `Signal`, `70` for `Fatal`, and `78`
+    /// for `Config`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shutdown::ShutdownReason;
+    ///
+    /// assert_eq!(ShutdownReason::Requested.exit_code(), 0);
+    /// assert_eq!(ShutdownReason::Signal(15).exit_code(), 143);
+    /// assert_eq!(ShutdownReason::Config("bad port".into()).exit_code(), 78);
+    /// ```
+    pub fn exit_code(&self) -> i32 {
+        match *self {
+            ShutdownReason::Requested => 0,
+            ShutdownReason::Signal(signal) => 128 + signal,
+            ShutdownReason::Fatal(_) => 70,
+            ShutdownReason::Config(_) => 78,
+        }
+    }
+
+    /// Returns `true` if the application stopped as it was asked to, via a
+    /// handle or a signal, and `false` if it failed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shutdown::ShutdownReason;
+    ///
+    /// assert!(ShutdownReason::Signal(2).is_clean());
+    /// assert!(!ShutdownReason::Fatal("address in use".into()).is_clean());
+    /// ```
+    pub fn is_clean(&self) -> bool {
+        match *self {
+            ShutdownReason::Requested | ShutdownReason::Signal(_) => true,
+            ShutdownReason::Fatal(_) | ShutdownReason::Config(_) => false,
+        }
+    }
+
+    /// Exits the process with this reason's
+    /// [exit_code](#method.exit_code).
+    pub fn exit(&self) -> ! {
+        process::exit(self.exit_code())
+    }
+}
+
+impl fmt::Display for ShutdownReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ShutdownReason::Requested => write!(f, "shutdown requested"),
+            ShutdownReason::Signal(signal) => write!(f, "received signal {}", signal),
+            ShutdownReason::Fatal(ref reason) => write!(f, "server failed: {}", reason),
+            ShutdownReason::Config(ref reason) => write!(f, "invalid configuration: {}", reason),
+        }
+    }
+}
+
+/// A handle to shut down a running application, retrieved via
+/// [Rocket::shutdown_handle](/rocket/struct.Rocket.html#method.shutdown_handle).
+///
+/// Handles are cheap to clone, and all clones refer to the same application.
+/// A handle can be managed, via
+/// [Rocket::manage](/rocket/struct.Rocket.html#method.manage), so that routes
+/// can retrieve it as `State<ShutdownHandle>`.
+#[derive(Clone)]
+pub struct ShutdownHandle(Arc<(Mutex<Option<ShutdownReason>>, Condvar)>);
+
+impl ShutdownHandle {
+    #[doc(hidden)]
+    pub fn new() -> ShutdownHandle {
+        ShutdownHandle(Arc::new((Mutex::new(None), Condvar::new())))
+    }
+
+    /// Requests that the application shut down.
+    /// [Rocket::run](/rocket/struct.Rocket.html#method.run) then returns
+    /// `ShutdownReason::Requested`. Does nothing if the application is already
+    /// shutting down.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rocket::shutdown::ShutdownReason;
+    ///
+    /// let handle = rocket::ignite().shutdown_handle();
+    /// handle.shutdown();
+    /// assert_eq!(handle.reason(), Some(ShutdownReason::Requested));
+    /// ```
+    pub fn shutdown(&self) {
+        self.trigger(ShutdownReason::Requested);
+    }
+
+    /// Returns why the application is shutting down, or `None` if it isn't.
+    pub fn reason(&self) -> Option<ShutdownReason> {
+        (self.0).0.lock().expect("shutdown lock").clone()
+    }
+
+    /// Blocks the current thread until the application is shut down, then
+    /// returns why.
+    pub fn wait(&self) -> ShutdownReason {
+        let &(ref lock, ref cvar) = &*self.0;
+        let mut reason = lock.lock().expect("shutdown lock");
+        loop {
+            if let Some(ref reason) = *reason {
+                return reason.clone();
+            }
+
+            reason = cvar.wait(reason).expect("shutdown lock");
+        }
+    }
+
+    /// Shuts the application down with `reason` unless it's already shutting
+    /// down, in which case the first reason stands.
+    #[doc(hidden)]
+    pub fn trigger(&self, reason: ShutdownReason) {
+        let &(ref lock, ref cvar) = &*self.0;
+        let mut current = lock.lock().expect("shutdown lock");
+        if current.is_none() {
+            *current = Some(reason);
+            cvar.notify_all();
+        }
+    }
+}
+
+impl fmt::Debug for ShutdownHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("ShutdownHandle").field(&self.reason()).finish()
+    }
+}
+
+/// A listener that stops accepting connections once its application shuts
+/// down. When the shutdown is triggered, the listening socket is shut down,
+/// waking every thread blocked accepting a connection, and those threads then
+/// park for good instead of accepting again. This should only be used
+/// internally by `Rocket`.
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct ShutdownListener {
+    listener: HttpListener,
+    handle: ShutdownHandle,
+}
+
+impl ShutdownListener {
+    #[doc(hidden)]
+    pub fn bind<A: ToSocketAddrs>(addr: A, handle: ShutdownHandle)
+            -> io::Result<ShutdownListener> {
+        Ok(ShutdownListener {
+            listener: HttpListener::from(TcpListener::bind(addr)?),
+            handle: handle
+        })
+    }
+
+    /// Shuts the listening socket down, so that no further connections are
+    /// accepted and threads blocked accepting one wake up.
+    #[doc(hidden)]
+    pub fn close(&self) {
+        #[cfg(unix)]
+        unsafe {
+            use std::os::unix::io::AsRawFd;
+            ::libc::shutdown(self.listener.as_raw_fd(), ::libc::SHUT_RDWR);
+        }
+    }
+}
+
+impl NetworkListener for ShutdownListener {
+    type Stream = HttpStream;
+
+    fn accept(&mut self) -> ::hyper::Result<HttpStream> {
+        let result = self.listener.accept();
+        if self.handle.reason().is_some() {
+            // Hyper's acceptor threads never exit, so keep this one idle.
+            drop(result);
+            loop { thread::park(); }
+        }
+
+        result
+    }
+
+    #[inline]
+    fn local_addr(&mut self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+}
+
+/// Shuts down the application of `handle` with `ShutdownReason::Signal` when
+/// the process receives `SIGINT` or `SIGTERM`, until the returned guard is
+/// dropped. The signal handler only records the signal; a thread polls for it,
+/// since little is safe in a handler. Dropping the guard restores the previous
+/// handlers and stops the polling thread.
+#[cfg(unix)]
+#[doc(hidden)]
+pub fn handle_signals(handle: ShutdownHandle) -> SignalGuard {
+    use libc::{c_int, sighandler_t, signal, SIGINT, SIGTERM};
+
+    extern "C" fn record(signum: c_int) {
+        RECEIVED.store(signum as usize, Ordering::SeqCst);
+    }
+
+    RECEIVED.store(0, Ordering::SeqCst);
+    let previous = unsafe {
+        let handler = record as extern "C" fn(c_int) as sighandler_t;
+        vec![(SIGINT, signal(SIGINT, handler)), (SIGTERM, signal(SIGTERM, handler))]
+    };
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let thread = thread::spawn(move || {
+        while !thread_stop.load(Ordering::SeqCst) {
+            match RECEIVED.swap(0, Ordering::SeqCst) {
+                0 => thread::sleep(Duration::from_millis(100)),
+                signum => handle.trigger(ShutdownReason::Signal(signum as i32))
+            }
+        }
+    });
+
+    SignalGuard { previous: previous, stop: stop, thread: Some(thread) }
+}
+
+#[cfg(unix)]
+static RECEIVED: AtomicUsize = ATOMIC_USIZE_INIT;
+
+/// Restores the signal handlers that were replaced by `handle_signals`, and
+/// stops its polling thread, when dropped.
+#[cfg(unix)]
+#[doc(hidden)]
+pub struct SignalGuard {
+    previous: Vec<(::libc::c_int, ::libc::sighandler_t)>,
+    stop: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+#[cfg(unix)]
+impl Drop for SignalGuard {
+    fn drop(&mut self) {
+        for &(signum, handler) in &self.previous {
+            unsafe { ::libc::signal(signum, handler); }
+        }
+
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(not(unix))]
+#[doc(hidden)]
+pub struct SignalGuard;
+
+#[cfg(not(unix))]
+#[doc(hidden)]
+pub fn handle_signals(_: ShutdownHandle) -> SignalGuard {
+    /* Signals are only handled on Unix. */
+    SignalGuard
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+    use super::{ShutdownHandle, ShutdownReason};
+
+    #[test]
+    fn test_exit_codes() {
+        let reasons = vec![
+            ShutdownReason::Requested,
+            ShutdownReason::Signal(2),
+            ShutdownReason::Fatal("crash".into()),
+            ShutdownReason::Config("bad".into()),
+        ];
+
+        let codes: Vec<_> = reasons.iter().map(|r| r.exit_code()).collect();
+        assert_eq!(codes, vec![0, 130, 70, 78]);
+    }
+
+    #[test]
+    fn test_first_reason_stands() {
+        let handle = ShutdownHandle::new();
+        assert_eq!(handle.reason(), None);
+
+        let other = handle.clone();
+        let waiter = thread::spawn(move || other.wait());
+        handle.trigger(ShutdownReason::Signal(15));
+        handle.shutdown();
+
+        assert_eq!(waiter.join().unwrap(), ShutdownReason::Signal(15));
+        assert_eq!(handle.reason(), Some(ShutdownReason::Signal(15)));
+    }
+}
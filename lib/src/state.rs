@@ -0,0 +1,164 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::fmt;
+
+use outcome::Outcome::*;
+use request::{self, Request, FromRequest};
+use http::Status;
+
+/// A value that can be shared across threads and downcast.
+#[doc(hidden)]
+pub trait AnySync: Any + Send + Sync {
+    fn as_any(&self) -> &Any;
+}
+
+impl<T: Any + Send + Sync> AnySync for T {
+    fn as_any(&self) -> &Any {
+        self
+    }
+}
+
+/// The values managed by an application, keyed by their type.
+#[doc(hidden)]
+pub struct ManagedState {
+    values: HashMap<TypeId, Box<AnySync>>
+}
+
+impl ManagedState {
+    #[doc(hidden)]
+    pub fn new() -> ManagedState {
+        ManagedState { values: HashMap::new() }
+    }
+
+    /// Manages `value`. Returns `false`, leaving the existing value in place,
+    /// if a value of type `T` is already managed.
+    #[doc(hidden)]
+    pub fn set<T: Send + Sync + 'static>(&mut self, value: T) -> bool {
+        let key = TypeId::of::<T>();
+        if self.values.contains_key(&key) {
+            return false;
+        }
+
+        self.values.insert(key, Box::new(value));
+        true
+    }
+
+    /// Returns `true` if a value with the type identified by `id` is managed.
+    #[doc(hidden)]
+    pub fn contains(&self, id: TypeId) -> bool {
+        self.values.contains_key(&id)
+    }
+
+    #[doc(hidden)]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn get<T: 'static>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>())
+            .and_then(|value| value.as_any().downcast_ref::<T>())
+    }
+}
+
+/// Identifies the managed state a request guard type retrieves, if any. Code
+/// generation uses this to record the state each route requires so that
+/// unmanaged state is detected at launch.
+#[doc(hidden)]
+pub trait StateType {
+    fn state_type() -> Option<TypeId>;
+}
+
+impl<T> StateType for T {
+    default fn state_type() -> Option<TypeId> {
+        None
+    }
+}
+
+impl<'r, T: Send + Sync + 'static> StateType for State<'r, T> {
+    fn state_type() -> Option<TypeId> {
+        Some(TypeId::of::<T>())
+    }
+}
+
+/// Request guard to retrieve managed state.
+///
+/// A value of type `T` is managed by an application via
+/// [Rocket::manage](/rocket/struct.Rocket.html#method.manage); at most one
+/// value of any given type can be managed. Managed state is shared by all
+/// requests, and so must be `Send + Sync`. It's a convenient place for
+/// database pools, configuration structures, channels, and the like.
+///
+/// `State<T>` dereferences to `T`. A reference with the lifetime of the
+/// request can be retrieved via [inner](#method.inner).
+///
+/// If a route requests state of a type that isn't managed, launching the
+/// application fails. When requests are dispatched without launching, as in
+/// tests, the guard fails with a status of `500 Internal Server Error`.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// #
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use rocket::State;
+///
+/// struct HitCount(AtomicUsize);
+///
+/// #[get("/")]
+/// fn index(hits: State<HitCount>) -> String {
+///     let count = hits.0.fetch_add(1, Ordering::Relaxed) + 1;
+///     format!("This page has been visited {} times.", count)
+/// }
+///
+/// fn main() {
+/// # if false {
+///     rocket::ignite()
+///         .mount("/", routes![index])
+///         .manage(HitCount(AtomicUsize::new(0)))
+///         .launch()
+/// # }
+/// }
+/// ```
+pub struct State<'r, T: Send + Sync + 'static>(&'r T);
+
+impl<'r, T: Send + Sync + 'static> State<'r, T> {
+    /// Returns a reference to the managed value with the lifetime of the
+    /// request rather than that of the `State`.
+    #[inline(always)]
+    pub fn inner(&self) -> &'r T {
+        self.0
+    }
+}
+
+impl<'r, T: Send + Sync + 'static> Deref for State<'r, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        self.0
+    }
+}
+
+impl<'r, T: Send + Sync + fmt::Debug + 'static> fmt::Debug for State<'r, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("State").field(self.0).finish()
+    }
+}
+
+impl<'a, 'r, T: Send + Sync + 'static> FromRequest<'a, 'r> for State<'r, T> {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        match request.managed().and_then(|managed| managed.get::<T>()) {
+            Some(value) => Success(State(value)),
+            None => {
+                error_!("Attempted to retrieve unmanaged state!");
+                Failure((Status::InternalServerError, ()))
+            }
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! Per-tenant request scoping for multi-tenant applications.
+//!
+//! When an application is configured with a [TenantSource](enum.TenantSource.html)
+//! via [Rocket::tenants](/rocket/struct.Rocket.html#method.tenants), Rocket
+//! resolves the tenant of every incoming request before the request is routed,
+//! and thus before any request guards run. The tenant is resolved from one of:
+//!
+//!   * **the subdomain**: `acme.example.com` is tenant `acme` when the base
+//!     domain is `example.com`
+//!   * **a header**: a request with `X-Tenant: acme` is tenant `acme` when the
+//!     header is `X-Tenant`
+//!   * **the path prefix**: `/acme/users` is tenant `acme`; the prefix is
+//!     removed from the request's URI, so the request is routed as `/users`
+//!
+//! Tenant names consist of 1 to 63 ASCII letters, digits, `-`, and `_`.
+//! Subdomains are lowercased. A request whose tenant can't be resolved is
+//! routed without one.
+//!
+//! The resolved tenant is available to handlers via the
+//! [Tenant](struct.Tenant.html) request guard. Values specific to a tenant,
+//! such as a database pool per tenant, can be registered via
+//! [Rocket::tenant_state](/rocket/struct.Rocket.html#method.tenant_state) and
+//! retrieved via the [TenantState](struct.TenantState.html) request guard.
+//!
+//! # Example
+//!
+//! ```rust
+//! # #![feature(plugin)]
+//! # #![plugin(rocket_codegen)]
+//! # extern crate rocket;
+//! #
+//! use rocket::tenant::{Tenant, TenantSource, TenantState};
+//!
+//! struct Pool {
+//!     url: &'static str
+//! }
+//!
+//! #[get("/db")]
+//! fn db(tenant: Tenant, pool: TenantState<Pool>) -> String {
+//!     format!("{} uses {}", tenant, pool.url)
+//! }
+//!
+//! fn main() {
+//! # if false {
+//!     rocket::ignite()
+//!         .mount("/", routes![db])
+//!         .tenants(TenantSource::Subdomain("example.com".into()))
+//!         .tenant_state("acme", Pool { url: "postgres://db/acme" })
+//!         .tenant_state("globex", Pool { url: "postgres://db/globex" })
+//!         .launch()
+//! # }
+//! }
+//! ```
+
+use std::any::TypeId;
+use std::ascii::AsciiExt;
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::fmt;
+
+use outcome::Outcome::*;
+use request::{self, Request, FromRequest};
+use state::AnySync;
+use http::Status;
+
+/// Where the tenant of a request is resolved from. See the [module level
+/// documentation](index.html) for details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TenantSource {
+    /// The subdomain of the given base domain in the request's `Host` header.
+    Subdomain(String),
+    /// The value of the given request header.
+    Header(String),
+    /// The first segment of the request's path, which is removed.
+    PathPrefix,
+}
+
+/// Returns `true` if `name` is a valid tenant name.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.len() <= 63 && name.chars().all(|c| {
+        c.is_ascii() && (c.is_alphanumeric() || c == '-' || c == '_')
+    })
+}
+
+impl TenantSource {
+    /// Returns the tenant of `request`, if it can be resolved, and, for
+    /// `PathPrefix`, the URI the request should be routed as.
+    fn resolve(&self, request: &Request) -> Option<(Tenant, Option<String>)> {
+        match *self {
+            TenantSource::Subdomain(ref base) => {
+                let name = match request.host() {
+                    Some(host) => host.name().to_ascii_lowercase(),
+                    None => return None
+                };
+
+                let base = base.trim_matches('.').to_ascii_lowercase();
+                if name.len() <= base.len() + 1 || !name.ends_with(&base) {
+                    return None;
+                }
+
+                let prefix = &name[..(name.len() - base.len())];
+                if !prefix.ends_with('.') {
+                    return None;
+                }
+
+                let subdomain = &prefix[..(prefix.len() - 1)];
+                if !is_valid_name(subdomain) {
+                    return None;
+                }
+
+                Some((Tenant(subdomain.to_string()), None))
+            }
+            TenantSource::Header(ref header) => {
+                match request.headers().get_one(header).map(|v| v.trim()) {
+                    Some(value) if is_valid_name(value) => {
+                        Some((Tenant(value.to_string()), None))
+                    }
+                    _ => None
+                }
+            }
+            TenantSource::PathPrefix => {
+                let path = request.uri().path();
+                let trimmed = path.trim_left_matches('/');
+                let (segment, rest) = match trimmed.find('/') {
+                    Some(i) => (&trimmed[..i], &trimmed[i..]),
+                    None => (trimmed, "/")
+                };
+
+                if !is_valid_name(segment) {
+                    return None;
+                }
+
+                let uri = match request.uri().query() {
+                    Some(query) => format!("{}?{}", rest, query),
+                    None => rest.to_string()
+                };
+
+                Some((Tenant(segment.to_string()), Some(uri)))
+            }
+        }
+    }
+}
+
+/// The tenant configuration of an application: the source tenants are
+/// resolved from and the state registered for each tenant.
+#[doc(hidden)]
+pub struct Tenants {
+    source: Option<TenantSource>,
+    states: HashMap<(String, TypeId), Box<AnySync>>,
+}
+
+impl Tenants {
+    #[doc(hidden)]
+    pub fn new() -> Tenants {
+        Tenants { source: None, states: HashMap::new() }
+    }
+
+    #[doc(hidden)]
+    pub fn set_source(&mut self, source: TenantSource) {
+        self.source = Some(source);
+    }
+
+    /// Registers `state` for `tenant`. Returns `true` if state of the same
+    /// type was already registered for `tenant` and has been replaced.
+    #[doc(hidden)]
+    pub fn add_state<T: Send + Sync + 'static>(&mut self, tenant: String, state: T) -> bool {
+        self.states.insert((tenant, TypeId::of::<T>()), Box::new(state)).is_some()
+    }
+
+    /// Resolves the tenant of `request`, recording it in the request and, if
+    /// the tenant was resolved from a path prefix, removing the prefix.
+    #[doc(hidden)]
+    pub fn resolve(&self, request: &mut Request) {
+        let resolved = self.source.as_ref().and_then(|source| source.resolve(request));
+        if let Some((tenant, uri)) = resolved {
+            if let Some(uri) = uri {
+                request.set_uri(uri);
+            }
+
+            request.set_tenant(tenant);
+        }
+    }
+
+    fn state<T: 'static>(&self, tenant: &Tenant) -> Option<&T> {
+        self.states.get(&(tenant.0.clone(), TypeId::of::<T>()))
+            .and_then(|state| state.as_any().downcast_ref::<T>())
+    }
+}
+
+/// Request guard for the tenant of the request. See the [module level
+/// documentation](index.html) for details.
+///
+/// If the request's tenant wasn't resolved, the guard forwards.
+///
+/// # Example
+///
+/// ```rust
+/// # #![feature(plugin)]
+/// # #![plugin(rocket_codegen)]
+/// # extern crate rocket;
+/// #
+/// use rocket::tenant::Tenant;
+///
+/// #[get("/whoami")]
+/// fn whoami(tenant: Tenant) -> String {
+///     format!("You are {}.", tenant.name())
+/// }
+/// #
+/// # fn main() { }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Tenant(String);
+
+impl Tenant {
+    /// Returns the name of the tenant.
+    #[inline(always)]
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Tenant {
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for Tenant {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        match request.tenant() {
+            Some(tenant) => Success(tenant.clone()),
+            None => Forward(())
+        }
+    }
+}
+
+/// Request guard for the value of type `T` registered for the tenant of the
+/// request via [Rocket::tenant_state](/rocket/struct.Rocket.html#method.tenant_state).
+///
+/// `TenantState<T>` dereferences to `T`. If the request's tenant wasn't
+/// resolved, the guard forwards. If no `T` was registered for the tenant, the
+/// tenant is unknown, and the guard fails with a status of `404 Not Found`.
+pub struct TenantState<'r, T: 'static>(&'r T);
+
+impl<'r, T: 'static> Deref for TenantState<'r, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        self.0
+    }
+}
+
+impl<'r, T: fmt::Debug + 'static> fmt::Debug for TenantState<'r, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("TenantState").field(self.0).finish()
+    }
+}
+
+impl<'a, 'r, T: 'static> FromRequest<'a, 'r> for TenantState<'r, T> {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Self, ()> {
+        let tenant = match request.tenant() {
+            Some(tenant) => tenant,
+            None => return Forward(())
+        };
+
+        match request.tenants().and_then(|tenants| tenants.state::<T>(tenant)) {
+            Some(state) => Success(TenantState(state)),
+            None => {
+                error_!("No state of the requested type for tenant '{}'.", tenant);
+                Failure((Status::NotFound, ()))
+            }
+        }
+    }
+}
@@ -105,16 +105,19 @@
 //! }
 //! ```
 
+use std::net::SocketAddr;
+
 use ::{Rocket, Request, Response, Data};
+use request::Connection;
 use http::{Method, Header, Cookie};
 
 /// A type for mocking requests for testing Rocket applications.
-pub struct MockRequest {
-    request: Request<'static>,
+pub struct MockRequest<'r> {
+    request: Request<'r>,
     data: Data
 }
 
-impl MockRequest {
+impl<'r> MockRequest<'r> {
     /// Constructs a new mocked request with the given `method` and `uri`.
     #[inline]
     pub fn new<S: AsRef<str>>(method: Method, uri: S) -> Self {
@@ -143,6 +146,44 @@ impl MockRequest {
         self
     }
 
+    /// Set the address of the remote peer that sent this request. By default,
+    /// the remote address of a mocked request is unknown.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::http::Method::*;
+    /// use rocket::testing::MockRequest;
+    ///
+    /// let req = MockRequest::new(Get, "/").remote("10.0.0.1:8000".parse().unwrap());
+    /// ```
+    #[inline]
+    pub fn remote(mut self, address: SocketAddr) -> Self {
+        self.request.set_remote(address);
+        self
+    }
+
+    /// Set the connection this request arrives on. By default, each mocked
+    /// request arrives on a new connection. Dispatching several requests with
+    /// the same connection simulates requests on a kept-alive connection.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rocket::http::Method::*;
+    /// use rocket::testing::MockRequest;
+    /// use rocket::request::Connection;
+    ///
+    /// let connection = Connection::new();
+    /// let first = MockRequest::new(Get, "/").connection(&connection);
+    /// let second = MockRequest::new(Get, "/").connection(&connection);
+    /// ```
+    #[inline]
+    pub fn connection(mut self, connection: &Connection) -> Self {
+        self.request.set_connection(connection.clone());
+        self
+    }
+
     /// Add a cookie to this request.
     ///
     /// # Examples
@@ -163,7 +204,7 @@ impl MockRequest {
         self
     }
 
-    /// Set the body (data) of the request.
+    /// Set the body (data) of the request, which may be text or raw bytes.
     ///
     /// # Examples
     ///
@@ -179,8 +220,8 @@ impl MockRequest {
     ///     .body(r#"{ "key": "value", "array": [1, 2, 3], }"#);
     /// ```
     #[inline]
-    pub fn body<S: AsRef<str>>(mut self, body: S) -> Self {
-        self.data = Data::new(body.as_ref().as_bytes().into());
+    pub fn body<S: AsRef<[u8]>>(mut self, body: S) -> Self {
+        self.data = Data::new(body.as_ref().into());
         self
     }
 
@@ -219,8 +260,17 @@ impl MockRequest {
     /// assert_eq!(body_str, Some("Hello, world!".to_string()));
     /// # }
     /// ```
-    pub fn dispatch_with<'r>(&'r mut self, rocket: &Rocket) -> Response<'r> {
+    pub fn dispatch_with<'s>(&'s mut self, rocket: &'r Rocket) -> Response<'s> {
         let data = ::std::mem::replace(&mut self.data, Data::new(vec![]));
         rocket.dispatch(&mut self.request, data)
     }
 }
+
+/// Calls the callbacks registered via
+/// [Request::on_finish](/rocket/struct.Request.html#method.on_finish) as if the
+/// response had been written successfully.
+impl<'r> Drop for MockRequest<'r> {
+    fn drop(&mut self) {
+        self.request.finish(Ok(()));
+    }
+}
@@ -0,0 +1,107 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::Request;
+use rocket::http::{Accept, ContentType};
+
+#[get("/")]
+fn index(accept: Accept) -> String {
+    let supported = [ContentType::JSON, ContentType::HTML];
+    match accept.negotiate(&supported) {
+        Some(media) => media.to_string(),
+        None => "none".to_string()
+    }
+}
+
+#[get("/preferred")]
+fn preferred(request: &Request) -> String {
+    request.accept().map_or("missing".to_string(), |a| a.preferred().to_string())
+}
+
+#[get("/item", format = "application/json")]
+fn item_json() -> &'static str { "json" }
+
+#[get("/item", format = "text/html")]
+fn item_html() -> &'static str { "html" }
+
+#[post("/item", format = "application/json")]
+fn create_json() -> &'static str { "created" }
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{Header, Status};
+
+fn get(uri: &str, accept: Option<&'static str>) -> Option<String> {
+    let rocket = rocket::ignite().mount("/", routes![index, preferred]);
+    let mut req = MockRequest::new(Get, uri);
+    if let Some(accept) = accept {
+        req = req.header(Header::new("Accept", accept));
+    }
+
+    let mut response = req.dispatch_with(&rocket);
+    response.body().and_then(|b| b.into_string())
+}
+
+#[test]
+fn accept_guard_negotiates() {
+    assert_eq!(get("/", None), Some(ContentType::JSON.to_string()));
+    assert_eq!(get("/", Some("text/html")), Some(ContentType::HTML.to_string()));
+    assert_eq!(get("/", Some("application/*;q=0.5, text/*")), Some(ContentType::HTML.to_string()));
+    assert_eq!(get("/", Some("image/png")), Some("none".to_string()));
+}
+
+#[test]
+fn accept_accessor() {
+    assert_eq!(get("/preferred", None), Some("missing".to_string()));
+    assert_eq!(get("/preferred", Some("bad, text/plain;q=0.5, image/*")),
+               Some("image/*".to_string()));
+}
+
+fn item(accept: Option<&'static str>) -> (Status, Option<String>) {
+    let rocket = rocket::ignite().mount("/", routes![item_json, item_html, create_json]);
+    let mut req = MockRequest::new(Get, "/item");
+    if let Some(accept) = accept {
+        req = req.header(Header::new("Accept", accept));
+    }
+
+    let mut response = req.dispatch_with(&rocket);
+    (response.status(), response.body().and_then(|b| b.into_string()))
+}
+
+#[test]
+fn get_formats_match_accept() {
+    assert_eq!(item(Some("application/json")).1, Some("json".into()));
+    assert_eq!(item(Some("text/html")).1, Some("html".into()));
+    assert_eq!(item(Some("text/*")).1, Some("html".into()));
+    assert_eq!(item(Some("image/png")).0, Status::NotFound);
+}
+
+#[test]
+fn get_formats_respect_quality() {
+    assert_eq!(item(Some("application/json;q=0.5, text/html")).1, Some("html".into()));
+    assert_eq!(item(Some("text/html;q=0.2, */*;q=0.4")).1, Some("json".into()));
+    assert_eq!(item(Some("*/*, application/json;q=0")).1, Some("html".into()));
+}
+
+#[test]
+fn get_formats_without_accept_match_in_order() {
+    let (status, body) = item(None);
+    assert_eq!(status, Status::Ok);
+    assert!(body == Some("json".into()) || body == Some("html".into()));
+}
+
+#[test]
+fn payload_formats_match_content_type() {
+    let rocket = rocket::ignite().mount("/", routes![item_json, item_html, create_json]);
+    let mut req = MockRequest::new(Post, "/item")
+        .header(Header::new("Accept", "text/html"))
+        .header(ContentType::JSON);
+
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.body().and_then(|b| b.into_string()), Some("created".into()));
+
+    let mut req = MockRequest::new(Post, "/item").header(ContentType::HTML);
+    assert_eq!(req.dispatch_with(&rocket).status(), Status::NotFound);
+}
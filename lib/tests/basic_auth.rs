@@ -0,0 +1,97 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::request::BasicAuth;
+
+#[get("/admin")]
+fn admin(auth: BasicAuth) -> String {
+    format!("{}:{}", auth.username, auth.password)
+}
+
+#[get("/public")]
+fn public(auth: Option<BasicAuth>) -> String {
+    auth.map_or("guest".to_string(), |auth| auth.username)
+}
+
+#[get("/login")]
+fn login(auth: BasicAuth) -> String {
+    auth.username
+}
+
+#[get("/login", rank = 2)]
+fn login_form() -> &'static str {
+    "login form"
+}
+
+use rocket::Rocket;
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{Status, Authorization, Header};
+
+fn rocket() -> Rocket {
+    rocket::ignite().mount("/", routes![admin, public, login, login_form])
+}
+
+#[test]
+fn credentials_are_decoded() {
+    let rocket = rocket();
+    let mut req = MockRequest::new(Get, "/admin")
+        .header(Authorization::basic("Aladdin", "open:sesame"));
+    let mut response = req.dispatch_with(&rocket);
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.body().and_then(|b| b.into_string()),
+               Some("Aladdin:open:sesame".into()));
+}
+
+#[test]
+fn missing_credentials_are_challenged() {
+    let rocket = rocket();
+    for auth in vec![None, Some("Bearer abc")] {
+        let mut req = MockRequest::new(Get, "/admin");
+        if let Some(auth) = auth {
+            req = req.header(Header::new("Authorization", auth));
+        }
+
+        let response = req.dispatch_with(&rocket);
+        assert_eq!(response.status(), Status::Unauthorized);
+        let challenges: Vec<_> = response.header_values("WWW-Authenticate").collect();
+        assert_eq!(challenges, vec![BasicAuth::challenge().as_str()]);
+    }
+}
+
+#[test]
+fn malformed_credentials_are_rejected() {
+    let rocket = rocket();
+    let mut req = MockRequest::new(Get, "/admin")
+        .header(Header::new("Authorization", "Basic !!!"));
+    let response = req.dispatch_with(&rocket);
+
+    assert_eq!(response.status(), Status::BadRequest);
+    assert!(response.header_values("WWW-Authenticate").next().is_none());
+}
+
+#[test]
+fn missing_credentials_forward() {
+    let rocket = rocket();
+    let mut req = MockRequest::new(Get, "/login");
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.body().and_then(|b| b.into_string()), Some("login form".into()));
+    assert!(response.header_values("WWW-Authenticate").next().is_none());
+
+    let mut req = MockRequest::new(Get, "/public");
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.body().and_then(|b| b.into_string()), Some("guest".into()));
+}
+
+#[test]
+fn unrouted_requests_are_not_challenged() {
+    let rocket = rocket();
+    let mut req = MockRequest::new(Get, "/nowhere");
+    let response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::NotFound);
+    assert!(response.header_values("WWW-Authenticate").next().is_none());
+}
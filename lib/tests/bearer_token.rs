@@ -0,0 +1,109 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::collections::HashMap;
+
+use rocket::Request;
+use rocket::request::{BearerToken, TokenValidator};
+
+struct User {
+    name: String,
+}
+
+struct Tokens(HashMap<&'static str, &'static str>);
+
+impl TokenValidator for Tokens {
+    type Principal = User;
+    type Error = &'static str;
+
+    fn validate(&self, token: &str, _: &Request) -> Result<User, &'static str> {
+        self.0.get(token).map(|name| User { name: name.to_string() }).ok_or("unknown token")
+    }
+}
+
+#[get("/me")]
+fn me(user: BearerToken<Tokens>) -> String {
+    format!("{} ({})", user.name, user.token())
+}
+
+#[get("/feed")]
+fn feed(user: BearerToken<Tokens>) -> String {
+    format!("feed for {}", user.into_principal().name)
+}
+
+#[get("/feed", rank = 2)]
+fn public_feed() -> &'static str {
+    "public feed"
+}
+
+use rocket::Rocket;
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{Status, Authorization, Header};
+use rocket::shutdown::ShutdownReason;
+
+fn rocket() -> Rocket {
+    let mut tokens = HashMap::new();
+    tokens.insert("t0k3n", "alice");
+    rocket::ignite().mount("/", routes![me, feed, public_feed]).manage(Tokens(tokens))
+}
+
+fn get(rocket: &Rocket, uri: &str, auth: Option<&str>) -> (Status, Option<String>, Vec<String>) {
+    let mut req = MockRequest::new(Get, uri);
+    if let Some(auth) = auth {
+        req = req.header(Header::new("Authorization", auth.to_string()));
+    }
+
+    let mut response = req.dispatch_with(rocket);
+    let challenges = response.header_values("WWW-Authenticate").map(|v| v.to_string()).collect();
+    let body = response.body().and_then(|b| b.into_string());
+    (response.status(), body, challenges)
+}
+
+#[test]
+fn valid_tokens_yield_principals() {
+    let rocket = rocket();
+    let (status, body, _) = get(&rocket, "/me", Some(&Authorization::bearer("t0k3n").to_string()));
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body, Some("alice (t0k3n)".into()));
+
+    let (_, body, _) = get(&rocket, "/feed", Some("bearer t0k3n"));
+    assert_eq!(body, Some("feed for alice".into()));
+}
+
+#[test]
+fn invalid_tokens_are_unauthorized() {
+    let (status, _, challenges) = get(&rocket(), "/me", Some("Bearer wrong"));
+    assert_eq!(status, Status::Unauthorized);
+    assert_eq!(challenges.len(), 1);
+    assert!(challenges[0].starts_with("Bearer") && challenges[0].contains("invalid_token"));
+}
+
+#[test]
+fn missing_tokens_are_challenged_or_forwarded() {
+    let rocket = rocket();
+    for auth in vec![None, Some("Basic dXNlcjpwYXNz")] {
+        let (status, _, challenges) = get(&rocket, "/me", auth);
+        assert_eq!(status, Status::Unauthorized);
+        assert_eq!(challenges, vec!["Bearer realm=\"Restricted\"".to_string()]);
+    }
+
+    let (status, body, challenges) = get(&rocket, "/feed", None);
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body, Some("public feed".into()));
+    assert!(challenges.is_empty());
+}
+
+#[test]
+fn malformed_tokens_are_rejected() {
+    let (status, _, _) = get(&rocket(), "/me", Some("Bearer a b"));
+    assert_eq!(status, Status::BadRequest);
+}
+
+#[test]
+fn unmanaged_validators_abort_launch() {
+    let reason = rocket::ignite().mount("/", routes![me]).run();
+    assert_eq!(reason, ShutdownReason::Config("unmanaged state".into()));
+}
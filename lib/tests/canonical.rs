@@ -0,0 +1,94 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[get("/about", canonical = "lowercase, no_trailing_slash, link")]
+fn about() -> &'static str { "about" }
+
+#[get("/users/<name>", canonical = "no_trailing_slash")]
+fn user(name: &str) -> String { name.to_string() }
+
+#[post("/users", canonical = "no_trailing_slash")]
+fn create() -> &'static str { "created" }
+
+#[get("/", canonical = "host = rocket.rs, link")]
+fn index() -> &'static str { "index" }
+
+#[get("/<site>/<page>", rank = 2, canonical = "lowercase, no_trailing_slash")]
+fn page(site: &str, page: &str) -> String { format!("{}/{}", site, page) }
+
+#[get("/plain")]
+fn plain() -> &'static str { "plain" }
+
+use rocket::Rocket;
+use rocket::testing::MockRequest;
+use rocket::http::{Method, Header, Status};
+use rocket::http::Method::*;
+
+fn rocket() -> Rocket {
+    rocket::ignite().mount("/", routes![about, user, create, index, page, plain])
+}
+
+fn redirect(method: Method, uri: &str, host: Option<&'static str>) -> (Status, Option<String>) {
+    let rocket = rocket();
+    let mut req = MockRequest::new(method, uri);
+    if let Some(host) = host {
+        req = req.header(Header::new("Host", host));
+    }
+
+    let response = req.dispatch_with(&rocket);
+    let location = response.header_values("Location").next().map(|l| l.to_string());
+    (response.status(), location)
+}
+
+#[test]
+fn canonical_urls_are_served() {
+    assert_eq!(redirect(Get, "/about", None), (Status::Ok, None));
+    assert_eq!(redirect(Get, "/users/Bob", None), (Status::Ok, None));
+    assert_eq!(redirect(Get, "/", Some("rocket.rs")), (Status::Ok, None));
+    assert_eq!(redirect(Get, "/PLAIN", None).0, Status::NotFound);
+}
+
+#[test]
+fn non_canonical_urls_are_redirected() {
+    assert_eq!(redirect(Get, "/About", None),
+               (Status::MovedPermanently, Some("/about".into())));
+    assert_eq!(redirect(Get, "/ABOUT/?q=Hi", None),
+               (Status::MovedPermanently, Some("/about?q=Hi".into())));
+    assert_eq!(redirect(Head, "/users/Bob/", None),
+               (Status::MovedPermanently, Some("/users/Bob".into())));
+    assert_eq!(redirect(Post, "/users/", None),
+               (Status::PermanentRedirect, Some("/users".into())));
+    assert_eq!(redirect(Get, "/", Some("www.rocket.rs")),
+               (Status::MovedPermanently, Some("http://rocket.rs/".into())));
+}
+
+#[test]
+fn redirects_stay_on_the_host() {
+    assert_eq!(redirect(Get, "//evil.com/x/", None),
+               (Status::MovedPermanently, Some("/evil.com/x".into())));
+    assert_eq!(redirect(Get, "//EVIL.com/x", None),
+               (Status::MovedPermanently, Some("/evil.com/x".into())));
+    assert_eq!(redirect(Get, "///users/Bob", None),
+               (Status::MovedPermanently, Some("/users/Bob".into())));
+    assert_eq!(redirect(Get, "/\\EVIL.com/x", None).1, None);
+}
+
+#[test]
+fn canonical_links_are_added() {
+    let rocket = rocket();
+    let mut req = MockRequest::new(Get, "/about");
+    let response = req.dispatch_with(&rocket);
+    assert_eq!(response.header_values("Link").collect::<Vec<_>>(),
+               vec!["</about>; rel=\"canonical\""]);
+
+    let mut req = MockRequest::new(Get, "/").header(Header::new("Host", "rocket.rs:8000"));
+    let response = req.dispatch_with(&rocket);
+    assert_eq!(response.header_values("Link").collect::<Vec<_>>(),
+               vec!["<http://rocket.rs/>; rel=\"canonical\""]);
+
+    let mut req = MockRequest::new(Get, "/users/Bob");
+    let response = req.dispatch_with(&rocket);
+    assert!(response.header_values("Link").next().is_none());
+}
@@ -0,0 +1,83 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::request::Form;
+
+#[post("/text", data = "<text>")]
+fn text(text: String) -> String {
+    text
+}
+
+#[derive(FromForm)]
+struct Message {
+    text: String,
+}
+
+#[post("/form", data = "<message>")]
+fn form(message: Form<Message>) -> String {
+    message.into_inner().text
+}
+
+use rocket::Rocket;
+use rocket::config::{Config, Environment, Limits, ByteUnit};
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{ContentType, Status};
+
+fn post(rocket: &Rocket, uri: &str, content_type: ContentType, body: &[u8])
+        -> (Status, Option<String>) {
+    let mut req = MockRequest::new(Post, uri).header(content_type).body(body);
+    let mut response = req.dispatch_with(rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    (response.status(), body_str)
+}
+
+fn plain(charset: &'static str) -> ContentType {
+    ContentType::with_params("text", "plain", Some(charset))
+}
+
+fn form_with(charset: &'static str) -> ContentType {
+    ContentType::with_params("application", "x-www-form-urlencoded", Some(charset))
+}
+
+#[test]
+fn text_is_decoded_from_its_charset() {
+    let rocket = rocket::ignite().mount("/", routes![text]);
+    let ok = |s: &str| (Status::Ok, Some(s.to_string()));
+
+    assert_eq!(post(&rocket, "/text", ContentType::Plain, b"caf\xC3\xA9"), ok("café"));
+    assert_eq!(post(&rocket, "/text", ContentType::Plain, b"\xEF\xBB\xBFcaf\xC3\xA9"), ok("café"));
+    assert_eq!(post(&rocket, "/text", plain("charset=ISO-8859-1"), b"caf\xE9"), ok("café"));
+    assert_eq!(post(&rocket, "/text", ContentType::Any, b"hi"), ok("hi"));
+
+    assert_eq!(post(&rocket, "/text", ContentType::Plain, b"caf\xE9").0, Status::BadRequest);
+    assert_eq!(post(&rocket, "/text", plain("charset=koi8-r"), b"hi").0, Status::BadRequest);
+}
+
+#[test]
+fn text_respects_the_string_limit() {
+    let config = Config::default_for(Environment::Development, "/custom").unwrap()
+        .limits(Limits::default().limit("string", ByteUnit::new(4)));
+
+    let rocket = rocket::custom(&config).mount("/", routes![text]);
+    assert_eq!(post(&rocket, "/text", ContentType::Plain, b"four").0, Status::Ok);
+    assert_eq!(post(&rocket, "/text", ContentType::Plain, b"five!").0, Status::PayloadTooLarge);
+}
+
+#[test]
+fn forms_are_decoded_from_their_charset() {
+    let rocket = rocket::ignite().mount("/", routes![form]);
+    let ok = |s: &str| (Status::Ok, Some(s.to_string()));
+
+    assert_eq!(post(&rocket, "/form", ContentType::Form, b"\xEF\xBB\xBFtext=hi"), ok("hi"));
+    assert_eq!(post(&rocket, "/form", ContentType::Form, b"text=caf%C3%A9"), ok("café"));
+
+    // Latin-1 forms, with raw and percent-encoded characters.
+    let latin1 = form_with("charset=latin1");
+    assert_eq!(post(&rocket, "/form", latin1.clone(), b"text=caf%E9"), ok("café"));
+    assert_eq!(post(&rocket, "/form", latin1.clone(), b"text=caf\xE9+%A3"), ok("café £"));
+
+    assert_eq!(post(&rocket, "/form", ContentType::Form, b"text=caf\xE9").0, Status::BadRequest);
+}
@@ -0,0 +1,64 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Write, Seek, SeekFrom};
+use std::path::PathBuf;
+
+use rocket::response::{NamedFile, Checksummed};
+
+fn path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("rocket-checksum-{}", name))
+}
+
+#[get("/<name>")]
+fn download(name: String) -> io::Result<Checksummed<NamedFile>> {
+    NamedFile::open(path(&name)).map(Checksummed)
+}
+
+#[get("/skip/<name>")]
+fn skip(name: String) -> io::Result<Checksummed<File>> {
+    let mut file = File::open(path(&name))?;
+    file.seek(SeekFrom::Start(6))?;
+    Ok(Checksummed(file))
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{Digest, ContentType};
+
+fn contents(size: usize) -> String {
+    (0..size).map(|i| (b'a' + (i % 26) as u8) as char).collect()
+}
+
+#[test]
+fn downloads_carry_sized_digests() {
+    let name = "large.txt";
+    let text = contents(100_000);
+    File::create(path(name)).unwrap().write_all(text.as_bytes()).unwrap();
+
+    let rocket = rocket::ignite().mount("/", routes![download, skip]);
+    let mut req = MockRequest::new(Get, format!("/{}", name));
+    let mut response = req.dispatch_with(&rocket);
+
+    let digest = response.header_values("Digest").next().map(|v| v.to_string());
+    assert_eq!(digest, Some(Digest::sha256(text.as_bytes()).to_string()));
+    let content_type = response.header_values("Content-Type").next().map(|v| v.to_string());
+    assert_eq!(content_type, Some(ContentType::Plain.to_string()));
+
+    let body = response.body().unwrap();
+    assert!(body.is_sized());
+    assert_eq!(body.into_string(), Some(text.clone()));
+
+    // Only the text after the reader's position are digested and sent.
+    let mut req = MockRequest::new(Get, format!("/skip/{}", name));
+    let mut response = req.dispatch_with(&rocket);
+    let digest = response.header_values("Digest").next().map(|v| v.to_string());
+    assert_eq!(digest, Some(Digest::sha256(text[6..].as_bytes()).to_string()));
+    assert_eq!(response.body().and_then(|b| b.into_string()), Some(text[6..].to_string()));
+
+    let _ = fs::remove_file(path(name));
+}
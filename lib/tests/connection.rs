@@ -0,0 +1,51 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::cell::Cell;
+use rocket::request::Connection;
+
+struct Hits(Cell<usize>);
+
+#[get("/")]
+fn index(connection: &Connection) -> String {
+    let hits = connection.local_cache(|| Hits(Cell::new(0)));
+    hits.0.set(hits.0.get() + 1);
+    format!("{}/{}", hits.0.get(), connection.requests())
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+
+fn dispatch(rocket: &rocket::Rocket, connection: Option<&Connection>) -> Option<String> {
+    let mut req = MockRequest::new(Get, "/");
+    if let Some(connection) = connection {
+        req = req.connection(connection);
+    }
+
+    let mut response = req.dispatch_with(rocket);
+    response.body().and_then(|b| b.into_string())
+}
+
+#[test]
+fn state_persists_across_requests_on_a_connection() {
+    let rocket = rocket::ignite().mount("/", routes![index]);
+    let connection = Connection::new();
+
+    assert_eq!(dispatch(&rocket, Some(&connection)), Some("1/1".to_string()));
+    assert_eq!(dispatch(&rocket, Some(&connection)), Some("2/2".to_string()));
+    assert_eq!(dispatch(&rocket, Some(&connection)), Some("3/3".to_string()));
+    assert_eq!(connection.requests(), 3);
+}
+
+#[test]
+fn state_is_not_shared_between_connections() {
+    let rocket = rocket::ignite().mount("/", routes![index]);
+    let (first, second) = (Connection::new(), Connection::new());
+
+    assert_eq!(dispatch(&rocket, Some(&first)), Some("1/1".to_string()));
+    assert_eq!(dispatch(&rocket, Some(&second)), Some("1/1".to_string()));
+    assert_eq!(dispatch(&rocket, Some(&first)), Some("2/2".to_string()));
+    assert_eq!(dispatch(&rocket, None), Some("1/1".to_string()));
+}
@@ -0,0 +1,45 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::io::Read;
+use std::iter::repeat;
+
+use rocket::Data;
+
+#[post("/sniff", data = "<data>")]
+fn sniff(mut data: Data) -> String {
+    let kind = match data.fill_peek(5000).unwrap() {
+        peeked if peeked.len() < 5000 => "short",
+        peeked if peeked.ends_with(b"b") => "long",
+        _ => "unknown"
+    };
+
+    let mut body = String::new();
+    data.open().read_to_string(&mut body).unwrap();
+    format!("{}:{}", kind, body.len())
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+
+fn sniff_body(body: String) -> Option<String> {
+    let rocket = rocket::ignite().mount("/", routes![sniff]);
+    let mut req = MockRequest::new(Post, "/sniff").body(body);
+    let mut response = req.dispatch_with(&rocket);
+    response.body().and_then(|b| b.into_string())
+}
+
+#[test]
+fn peeking_past_the_default_buffer() {
+    let mut body: String = repeat('a').take(4999).collect();
+    body.push('b');
+    body.extend(repeat('c').take(3000));
+    assert_eq!(sniff_body(body), Some("long:8000".to_string()));
+}
+
+#[test]
+fn peeking_short_bodies() {
+    assert_eq!(sniff_body("hello".to_string()), Some("short:5".to_string()));
+}
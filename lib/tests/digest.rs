@@ -0,0 +1,71 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::request::Verified;
+
+#[put("/objects", data = "<object>")]
+fn put(object: Verified) -> String {
+    format!("{}:{}", object.is_checked(), String::from_utf8_lossy(&object))
+}
+
+#[get("/objects")]
+fn get() -> &'static str {
+    "hello"
+}
+
+use rocket::Rocket;
+use rocket::hook::{self, Predicate};
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{Status, Header, Digest, ContentMd5};
+
+fn rocket() -> Rocket {
+    rocket::ignite()
+        .mount("/", routes![put, get])
+        .on_response(Predicate::any().method(Get), hook::digest)
+}
+
+fn upload(headers: &[Header<'static>]) -> (Status, Option<String>) {
+    let mut req = MockRequest::new(Put, "/objects").body("hello");
+    for header in headers {
+        req = req.header(header.clone());
+    }
+
+    let rocket = rocket();
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    (response.status(), body_str)
+}
+
+#[test]
+fn uploads_with_matching_digests_are_accepted() {
+    assert_eq!(upload(&[ContentMd5::of(b"hello").into()]),
+               (Status::Ok, Some("true:hello".into())));
+    assert_eq!(upload(&[Digest::sha256(b"hello").into(), Digest::md5(b"hello").into()]),
+               (Status::Ok, Some("true:hello".into())));
+    assert_eq!(upload(&[Header::new("Digest", "UNIXsum=30637")]),
+               (Status::Ok, Some("false:hello".into())));
+    assert_eq!(upload(&[]), (Status::Ok, Some("false:hello".into())));
+}
+
+#[test]
+fn uploads_with_mismatched_digests_are_rejected() {
+    assert_eq!(upload(&[ContentMd5::of(b"hullo").into()]).0, Status::BadRequest);
+    assert_eq!(upload(&[Digest::sha256(b"hello").into(), Digest::md5(b"hullo").into()]).0,
+               Status::BadRequest);
+    assert_eq!(upload(&[Header::new("Content-MD5", "not base64")]).0, Status::BadRequest);
+}
+
+#[test]
+fn responses_carry_digests() {
+    let rocket = rocket();
+    let mut req = MockRequest::new(Get, "/objects");
+    let response = req.dispatch_with(&rocket);
+
+    let md5 = response.header_values("Content-MD5").next().map(|v| v.to_string());
+    let digest = response.header_values("Digest").next().map(|v| v.to_string());
+    assert_eq!(md5, Some(ContentMd5::of(b"hello").to_string()));
+    assert_eq!(digest, Some(Digest::sha256(b"hello").to_string()));
+}
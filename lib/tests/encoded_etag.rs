@@ -0,0 +1,45 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::Response;
+use rocket::hook::Predicate;
+use rocket::http::{ETag, Header};
+
+#[get("/resource")]
+fn resource() -> Response<'static> {
+    Response::build().header(ETag::strong("v1")).finalize()
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+
+fn etag_and_vary(encoding: Option<&'static str>) -> (Option<String>, Option<String>) {
+    let rocket = rocket::ignite()
+        .mount("/", routes![resource])
+        .on_response(Predicate::any(), move |_, response| {
+            if let Some(coding) = encoding {
+                response.set_header(Header::new("Content-Encoding", coding));
+            }
+        });
+
+    let mut req = MockRequest::new(Get, "/resource");
+    let response = req.dispatch_with(&rocket);
+    let etag = response.header_values("ETag").next().map(|s| s.to_string());
+    let vary = response.header_values("Vary").next().map(|s| s.to_string());
+    (etag, vary)
+}
+
+#[test]
+fn encoded_responses_have_weak_etags() {
+    let (etag, vary) = etag_and_vary(Some("gzip"));
+    assert_eq!(etag, Some("W/\"v1\"".into()));
+    assert_eq!(vary, Some("Accept-Encoding".into()));
+}
+
+#[test]
+fn identity_responses_keep_strong_etags() {
+    assert_eq!(etag_and_vary(None), (Some("\"v1\"".into()), None));
+    assert_eq!(etag_and_vary(Some("identity")), (Some("\"v1\"".into()), None));
+}
@@ -0,0 +1,39 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::config::{Config, Environment};
+
+#[get("/")]
+fn env(env: Environment) -> String {
+    env.to_string()
+}
+
+#[get("/debug")]
+fn debug(env: Environment) -> Option<&'static str> {
+    if env.is_dev() { Some("debug") } else { None }
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::Status;
+
+#[test]
+fn guard_reports_configured_environment() {
+    for env in &Environment::all() {
+        let config = Config::default_for(*env, "/custom").unwrap();
+        let rocket = rocket::custom(&config).mount("/", routes![env, debug]);
+        assert_eq!(rocket.environment(), *env);
+
+        let mut req = MockRequest::new(Get, "/");
+        let mut response = req.dispatch_with(&rocket);
+        let body_str = response.body().and_then(|b| b.into_string());
+        assert_eq!(body_str, Some(env.to_string()));
+
+        let mut req = MockRequest::new(Get, "/debug");
+        let response = req.dispatch_with(&rocket);
+        let expected = if env.is_dev() { Status::Ok } else { Status::NotFound };
+        assert_eq!(response.status(), expected);
+    }
+}
@@ -0,0 +1,96 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::sync::{Arc, Mutex};
+
+use rocket::{Request, State, Rocket};
+use rocket::report::ServerError;
+use rocket::response::Failure;
+use rocket::http::Status;
+
+struct Unmanaged;
+
+#[get("/panic")]
+fn panics() -> &'static str {
+    panic!("the handler panicked")
+}
+
+#[get("/unmanaged")]
+fn unmanaged(_state: State<Unmanaged>) -> &'static str {
+    "unreachable"
+}
+
+#[get("/fail/<code>")]
+fn fail(code: u16) -> Result<&'static str, Failure> {
+    Err(Failure(Status::raw(code)))
+}
+
+#[get("/ok")]
+fn ok() -> &'static str {
+    "ok"
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+
+type Reports = Arc<Mutex<Vec<(String, ServerError)>>>;
+
+fn rocket() -> (Rocket, Reports) {
+    let reports: Reports = Arc::new(Mutex::new(vec![]));
+    let sink = reports.clone();
+    let rocket = rocket::ignite()
+        .mount("/", routes![panics, unmanaged, fail, ok])
+        .error_reporter(move |request: &Request, error: &ServerError| {
+            sink.lock().unwrap().push((request.uri().as_str().to_string(), error.clone()));
+        });
+
+    (rocket, reports)
+}
+
+fn get(rocket: &Rocket, uri: &str) -> Status {
+    let mut req = MockRequest::new(Get, uri);
+    req.dispatch_with(rocket).status()
+}
+
+#[test]
+fn panics_are_answered_and_reported() {
+    let (rocket, reports) = rocket();
+    assert_eq!(get(&rocket, "/panic"), Status::InternalServerError);
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(*reports, vec![
+        ("/panic".to_string(), ServerError::Panic(Some("the handler panicked".into())))
+    ]);
+}
+
+#[test]
+fn server_error_guard_failures_are_reported() {
+    let (rocket, reports) = rocket();
+    assert_eq!(get(&rocket, "/unmanaged"), Status::InternalServerError);
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.len(), 1);
+    match reports[0].1 {
+        ServerError::GuardFailure(ref failure) => {
+            assert_eq!(failure.status(), Status::InternalServerError);
+        }
+        ref error => panic!("unexpected error: {:?}", error)
+    }
+}
+
+#[test]
+fn internal_server_errors_are_reported() {
+    let (rocket, reports) = rocket();
+    assert_eq!(get(&rocket, "/fail/500"), Status::InternalServerError);
+    assert_eq!(get(&rocket, "/fail/503"), Status::ServiceUnavailable);
+    assert_eq!(get(&rocket, "/fail/404"), Status::NotFound);
+    assert_eq!(get(&rocket, "/ok"), Status::Ok);
+    assert_eq!(get(&rocket, "/missing"), Status::NotFound);
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(*reports, vec![
+        ("/fail/500".to_string(), ServerError::Response(Status::InternalServerError))
+    ]);
+}
@@ -0,0 +1,97 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::Data;
+
+#[put("/upload", data = "<data>")]
+fn upload(data: Data) -> &'static str {
+    let _ = data;
+    "uploaded"
+}
+
+use rocket::{Rocket, Request};
+use rocket::maintenance::Maintenance;
+use rocket::request::MethodOverride;
+use rocket::tenant::TenantSource;
+use rocket::hook::Predicate;
+use rocket::http::{Method, Header, HeaderPolicy, Duplicates};
+use rocket::http::Method::*;
+use rocket::http::Status;
+
+fn status(rocket: &Rocket, method: Method, uri: &str) -> Status {
+    rocket.continue_status(&mut Request::new(method, uri))
+}
+
+#[test]
+fn routed_requests_continue() {
+    let rocket = rocket::ignite().mount("/", routes![upload]);
+    assert_eq!(status(&rocket, Put, "/upload"), Status::Continue);
+}
+
+#[test]
+fn unrouted_requests_are_declined() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![upload])
+        .method_override(MethodOverride::disabled());
+
+    assert_eq!(status(&rocket, Put, "/elsewhere"), Status::NotFound);
+    assert_eq!(status(&rocket, Post, "/upload"), Status::NotFound);
+}
+
+#[test]
+fn other_methods_are_declined_as_not_allowed() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![upload])
+        .method_not_allowed("/")
+        .method_override(MethodOverride::disabled());
+
+    assert_eq!(status(&rocket, Post, "/upload"), Status::MethodNotAllowed);
+}
+
+#[test]
+fn overridable_posts_continue() {
+    // The body may contain a `_method=put` field, so the body is needed.
+    let rocket = rocket::ignite().mount("/", routes![upload]);
+    assert_eq!(status(&rocket, Post, "/upload"), Status::Continue);
+}
+
+#[test]
+fn requests_during_maintenance_are_declined() {
+    let maintenance = Maintenance::new();
+    maintenance.enable();
+    let rocket = rocket::ignite().mount("/", routes![upload]).maintenance(maintenance);
+    assert_eq!(status(&rocket, Put, "/upload"), Status::ServiceUnavailable);
+}
+
+#[test]
+fn tenant_prefixed_requests_continue() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![upload])
+        .tenants(TenantSource::PathPrefix);
+
+    assert_eq!(status(&rocket, Put, "/acme/upload"), Status::Continue);
+}
+
+#[test]
+fn hooked_requests_continue() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![upload])
+        .method_override(MethodOverride::disabled())
+        .on_request(Predicate::path("/legacy"), |req, _| req.set_uri("/upload"));
+
+    assert_eq!(status(&rocket, Put, "/legacy/upload"), Status::Continue);
+    assert_eq!(status(&rocket, Put, "/elsewhere"), Status::NotFound);
+}
+
+#[test]
+fn requests_with_rejected_headers_are_declined() {
+    let policy = HeaderPolicy::new().duplicates(Duplicates::Reject).singleton("X-Key");
+    let rocket = rocket::ignite().mount("/", routes![upload]).header_policy(policy);
+
+    let mut request = Request::new(Put, "/upload");
+    request.add_header(Header::new("X-Key", "one"));
+    request.add_header(Header::new("X-Key", "two"));
+    assert_eq!(rocket.continue_status(&mut request), Status::BadRequest);
+}
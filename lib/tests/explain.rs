@@ -0,0 +1,103 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[get("/items/<id>")]
+fn item(id: usize) -> String {
+    id.to_string()
+}
+
+#[get("/items/<name>", rank = 2)]
+fn named(name: String) -> String {
+    name
+}
+
+#[get("/items/<name>", rank = 3)]
+fn fallback(name: String) -> String {
+    name
+}
+
+#[post("/items/<id>", format = "application/json")]
+fn create(id: usize) -> String {
+    id.to_string()
+}
+
+#[get("/other")]
+fn other() -> &'static str {
+    "other"
+}
+
+use rocket::{Rocket, Request, Verdict};
+use rocket::config::{Config, Environment};
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{ContentType, Header};
+
+fn rocket() -> Rocket {
+    let config = Config::default_for(Environment::Development, "/custom").unwrap();
+    rocket::custom(&config).mount("/", routes![item, named, fallback, create, other])
+}
+
+fn verdicts(rocket: &Rocket, request: &Request) -> Vec<(isize, Verdict)> {
+    rocket.explain(request).candidates().iter()
+        .map(|c| (c.route.rank, c.verdict))
+        .collect()
+}
+
+#[test]
+fn explains_candidates_in_order() {
+    let rocket = rocket();
+
+    let request = Request::new(Get, "/items/10");
+    assert_eq!(verdicts(&rocket, &request), vec![
+        (1, Verdict::Wins), (2, Verdict::Outranked), (3, Verdict::Outranked),
+        (1, Verdict::Method)
+    ]);
+
+    let request = Request::new(Get, "/items/ten");
+    assert_eq!(verdicts(&rocket, &request), vec![
+        (1, Verdict::Param("id")), (2, Verdict::Wins), (3, Verdict::Outranked),
+        (1, Verdict::Method)
+    ]);
+    assert_eq!(rocket.explain(&request).winner().map(|r| r.rank), Some(2));
+}
+
+#[test]
+fn explains_format_mismatches() {
+    let rocket = rocket();
+
+    let mut request = Request::new(Post, "/items/10");
+    request.add_header(ContentType::HTML.into());
+    let explanation = rocket.explain(&request);
+    assert!(explanation.winner().is_none());
+    assert_eq!(explanation.candidates()[0].verdict, Verdict::Format);
+
+    let request = Request::new(Get, "/nothing/here");
+    assert!(rocket.explain(&request).candidates().is_empty());
+    assert_eq!(rocket.explain(&request).to_string(), "no routes match");
+}
+
+#[test]
+fn explain_header_in_development() {
+    let rocket = rocket();
+    let mut req = MockRequest::new(Get, "/other").header(Header::new("X-Rocket-Explain", "1"));
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("GET /other [0]: wins".to_string()));
+
+    let mut req = MockRequest::new(Get, "/other");
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("other".to_string()));
+}
+
+#[test]
+fn no_explain_header_in_production() {
+    let config = Config::default_for(Environment::Production, "/custom").unwrap();
+    let rocket = rocket::custom(&config).mount("/", routes![other]);
+    let mut req = MockRequest::new(Get, "/other").header(Header::new("X-Rocket-Explain", "1"));
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("other".to_string()));
+}
@@ -0,0 +1,68 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::request::Extension;
+
+struct Principal(String);
+
+struct TraceId(u32);
+
+#[get("/whoami")]
+fn whoami(principal: Extension<Principal>, trace: Option<Extension<TraceId>>) -> String {
+    match trace {
+        Some(trace) => format!("{} ({})", principal.0, trace.0),
+        None => principal.0.clone()
+    }
+}
+
+#[get("/whoami", rank = 2)]
+fn anonymous() -> &'static str {
+    "anonymous"
+}
+
+use rocket::Rocket;
+use rocket::hook::Predicate;
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::Header;
+
+fn rocket() -> Rocket {
+    rocket::ignite()
+        .mount("/", routes![whoami, anonymous])
+        .on_request(Predicate::any(), |request, _| {
+            let user = request.headers().get_one("X-User").map(|u| u.to_string());
+            if let Some(user) = user {
+                request.set_extension(Principal(user));
+            }
+
+            let trace = request.headers().get_one("X-Trace").and_then(|t| t.parse().ok());
+            if let Some(trace) = trace {
+                request.set_extension(TraceId(trace));
+            }
+        })
+}
+
+fn whoami_with(headers: &[(&'static str, &'static str)]) -> Option<String> {
+    let mut req = MockRequest::new(Get, "/whoami");
+    for &(name, value) in headers {
+        req = req.header(Header::new(name, value));
+    }
+
+    let rocket = rocket();
+    let mut response = req.dispatch_with(&rocket);
+    response.body().and_then(|b| b.into_string())
+}
+
+#[test]
+fn hooks_pass_extensions_to_handlers() {
+    assert_eq!(whoami_with(&[("X-User", "alice")]), Some("alice".into()));
+    assert_eq!(whoami_with(&[("X-User", "bob"), ("X-Trace", "7")]), Some("bob (7)".into()));
+}
+
+#[test]
+fn missing_extensions_forward() {
+    assert_eq!(whoami_with(&[]), Some("anonymous".into()));
+    assert_eq!(whoami_with(&[("X-Trace", "7")]), Some("anonymous".into()));
+}
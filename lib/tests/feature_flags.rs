@@ -0,0 +1,87 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rocket::Request;
+use rocket::feature::{FeatureFlags, Disabled};
+
+#[get("/checkout", feature = "new_checkout")]
+fn new_checkout() -> &'static str { "new" }
+
+#[get("/checkout", rank = 2)]
+fn checkout() -> &'static str { "old" }
+
+#[get("/preview", feature = "preview")]
+fn preview() -> &'static str { "preview" }
+
+/// Enables `preview` when toggled on; answers disabled routes with a 404.
+struct Toggle(Arc<AtomicBool>);
+
+impl FeatureFlags for Toggle {
+    fn is_enabled(&self, feature: &str, _: &Request) -> bool {
+        feature == "preview" && self.0.load(Ordering::SeqCst)
+    }
+
+    fn when_disabled(&self, _: &str) -> Disabled {
+        Disabled::NotFound
+    }
+}
+
+use rocket::Rocket;
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::Status;
+
+fn get(rocket: &Rocket, uri: &str) -> (Status, Option<String>) {
+    let mut req = MockRequest::new(Get, uri);
+    let mut response = req.dispatch_with(rocket);
+    (response.status(), response.body().and_then(|b| b.into_string()))
+}
+
+#[test]
+fn enabled_features_are_routed() {
+    let mut enabled = HashSet::new();
+    enabled.insert("new_checkout");
+
+    let rocket = rocket::ignite()
+        .mount("/", routes![new_checkout, checkout])
+        .feature_flags(enabled);
+
+    assert_eq!(get(&rocket, "/checkout"), (Status::Ok, Some("new".into())));
+}
+
+#[test]
+fn disabled_features_forward() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![new_checkout, checkout, preview])
+        .feature_flags(HashSet::<&'static str>::new());
+
+    assert_eq!(get(&rocket, "/checkout"), (Status::Ok, Some("old".into())));
+    assert_eq!(get(&rocket, "/preview").0, Status::NotFound);
+}
+
+#[test]
+fn features_are_disabled_without_flags() {
+    let rocket = rocket::ignite().mount("/", routes![new_checkout, checkout]);
+    assert_eq!(get(&rocket, "/checkout"), (Status::Ok, Some("old".into())));
+}
+
+#[test]
+fn flags_are_checked_for_every_request() {
+    let toggle = Arc::new(AtomicBool::new(false));
+    let rocket = rocket::ignite()
+        .mount("/", routes![new_checkout, checkout, preview])
+        .feature_flags(Toggle(toggle.clone()));
+
+    assert_eq!(get(&rocket, "/preview").0, Status::NotFound);
+    assert_eq!(get(&rocket, "/checkout").0, Status::NotFound);
+
+    toggle.store(true, Ordering::SeqCst);
+    assert_eq!(get(&rocket, "/preview"), (Status::Ok, Some("preview".into())));
+    assert_eq!(get(&rocket, "/checkout").0, Status::NotFound);
+}
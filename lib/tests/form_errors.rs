@@ -0,0 +1,87 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::Request;
+use rocket::request::{Form, FormError, FormDataError};
+
+#[derive(FromForm)]
+struct Signup {
+    name: String,
+    age: u8,
+}
+
+#[post("/result", data = "<signup>")]
+fn result(signup: Result<Form<Signup>, FormDataError<FormError>>) -> String {
+    match signup {
+        Ok(form) => format!("{} is {}", form.get().name, form.get().age),
+        Err(FormDataError::Parse(_, e)) => format!("{}: {}", e.field(), e),
+        Err(FormDataError::Io(_)) => "io".to_string(),
+        Err(FormDataError::TooLarge(_)) => "too large".to_string(),
+        Err(FormDataError::Malformed(_)) => "malformed".to_string(),
+        Err(FormDataError::TooMany(_)) => "too many".to_string()
+    }
+}
+
+#[post("/strict", data = "<signup>")]
+fn strict(signup: Form<Signup>) -> String {
+    signup.get().name.clone()
+}
+
+#[error(400)]
+fn bad_request(req: &Request) -> String {
+    let failure = match req.guard_failure() {
+        Some(failure) => failure,
+        None => return "no failure".to_string()
+    };
+
+    match failure.downcast_ref::<FormDataError<FormError>>() {
+        Some(&FormDataError::Parse(_, ref e)) => format!("{}: {}", e.field(), e),
+        _ => failure.error().to_string()
+    }
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{ContentType, Status};
+
+fn post(uri: &str, body: &str) -> (Status, Option<String>) {
+    let rocket = rocket::ignite()
+        .mount("/", routes![result, strict])
+        .catch(errors![bad_request]);
+
+    let mut req = MockRequest::new(Post, uri)
+        .header(ContentType::Form)
+        .body(body);
+
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    (response.status(), body_str)
+}
+
+#[test]
+fn handler_receives_form_error() {
+    let (_, body) = post("/result", "name=Bob&age=30");
+    assert_eq!(body, Some("Bob is 30".to_string()));
+
+    let (_, body) = post("/result", "name=Bob&age=old");
+    assert_eq!(body, Some("age: field 'age': expected u8, found 'old'".to_string()));
+
+    let (_, body) = post("/result", "age=30");
+    assert_eq!(body, Some("name: field 'name': missing value of type String".to_string()));
+
+    let (_, body) = post("/result", "name=Bob&age=30&admin=true");
+    assert_eq!(body, Some("admin: field 'admin': unexpected field".to_string()));
+}
+
+#[test]
+fn catcher_receives_form_error() {
+    let (status, body) = post("/strict", "name=Bob&age=300");
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body, Some("age: field 'age': expected u8, found '300'".to_string()));
+
+    let (status, body) = post("/strict", "age=30");
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body, Some("name: field 'name': missing value of type String".to_string()));
+}
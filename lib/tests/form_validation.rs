@@ -0,0 +1,72 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::request::{Form, Validated};
+
+#[derive(FromForm)]
+struct Signup {
+    #[form(validate = "len(1..16)")]
+    name: String,
+    #[form(validate = "range(18..150)")]
+    age: u8,
+}
+
+#[post("/signup", data = "<signup>")]
+fn signup(signup: Form<Validated<Signup>>) -> String {
+    match signup.into_inner().into_result() {
+        Ok(signup) => format!("{} is {}", signup.name, signup.age),
+        Err(errors) => {
+            let fields: Vec<_> = errors.iter().map(|e| e.field().to_string()).collect();
+            fields.join(",")
+        }
+    }
+}
+
+#[post("/strict", data = "<signup>")]
+fn strict(signup: Form<Signup>) -> String {
+    signup.get().name.clone()
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{ContentType, Status};
+
+fn post(uri: &str, body: &str) -> (Status, Option<String>) {
+    let rocket = rocket::ignite().mount("/", routes![signup, strict]);
+    let mut req = MockRequest::new(Post, uri)
+        .header(ContentType::Form)
+        .body(body);
+
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    (response.status(), body_str)
+}
+
+#[test]
+fn valid_form_is_ok() {
+    let (status, body) = post("/signup", "name=Bob&age=30");
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body, Some("Bob is 30".to_string()));
+}
+
+#[test]
+fn handler_receives_every_error() {
+    let (status, body) = post("/signup", "name=&age=12");
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body, Some("name,age".to_string()));
+
+    let (_, body) = post("/signup", "extra=1&age=old");
+    assert_eq!(body, Some("extra,age,name".to_string()));
+}
+
+#[test]
+fn invalid_form_fails_guard() {
+    let (status, _) = post("/strict", "name=&age=30");
+    assert_eq!(status, Status::UnprocessableEntity);
+
+    // Fields that don't parse fail the guard as a bad request.
+    let (status, _) = post("/strict", "name=Bob&age=x");
+    assert_eq!(status, Status::BadRequest);
+}
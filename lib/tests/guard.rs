@@ -0,0 +1,87 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::{Request, Outcome};
+use rocket::request::{self, FromRequest};
+use rocket::http::{Status, Method};
+
+struct User(String);
+struct Admin(User);
+
+impl<'a, 'r> FromRequest<'a, 'r> for User {
+    type Error = &'static str;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<User, Self::Error> {
+        match request.headers().get_one("X-User") {
+            Some("") => Outcome::Failure((Status::BadRequest, "empty user")),
+            Some(name) => Outcome::Success(User(name.to_string())),
+            None => Outcome::Forward(())
+        }
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for Admin {
+    type Error = &'static str;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Admin, Self::Error> {
+        let user = match request.guard::<User>() {
+            Outcome::Success(user) => user,
+            Outcome::Failure(e) => return Outcome::Failure(e),
+            Outcome::Forward(()) => return Outcome::Forward(())
+        };
+
+        if user.0 == "root" {
+            Outcome::Success(Admin(user))
+        } else {
+            Outcome::Failure((Status::Forbidden, "not an admin"))
+        }
+    }
+}
+
+#[get("/")]
+fn admin(admin: Admin) -> String {
+    format!("admin {}", (admin.0).0)
+}
+
+#[get("/", rank = 2)]
+fn anonymous(request: &Request) -> String {
+    // Guards can also be run imperatively in handlers.
+    match request.guard::<Option<User>>() {
+        Outcome::Success(None) => format!("anonymous {}", request.guard::<Method>().unwrap()),
+        _ => unreachable!("the user guard forwarded")
+    }
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::Header;
+
+fn dispatch(user: Option<&'static str>) -> (Status, Option<String>) {
+    let rocket = rocket::ignite().mount("/", routes![admin, anonymous]);
+    let mut req = MockRequest::new(Get, "/");
+    if let Some(user) = user {
+        req = req.header(Header::new("X-User", user));
+    }
+
+    let mut response = req.dispatch_with(&rocket);
+    let body = response.body().and_then(|b| b.into_string());
+    (response.status(), body)
+}
+
+#[test]
+fn composite_guard_succeeds() {
+    assert_eq!(dispatch(Some("root")), (Status::Ok, Some("admin root".to_string())));
+}
+
+#[test]
+fn composite_guard_fails() {
+    assert_eq!(dispatch(Some("bob")).0, Status::Forbidden);
+    assert_eq!(dispatch(Some("")).0, Status::BadRequest);
+}
+
+#[test]
+fn composite_guard_forwards() {
+    assert_eq!(dispatch(None), (Status::Ok, Some("anonymous GET".to_string())));
+}
@@ -0,0 +1,122 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::{Request, Outcome};
+use rocket::request::{self, FromRequest};
+use rocket::http::Status;
+
+struct ApiKey;
+
+impl<'a, 'r> FromRequest<'a, 'r> for ApiKey {
+    type Error = String;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<ApiKey, String> {
+        match request.headers().get_one("X-Api-Key") {
+            Some("secret") => Outcome::Success(ApiKey),
+            Some(key) => Outcome::Failure((Status::Unauthorized, format!("bad key {}", key))),
+            None => Outcome::Failure((Status::Unauthorized, "missing key".into()))
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum RoleError {
+    Missing,
+    Unknown(String),
+}
+
+struct Admin;
+
+impl<'a, 'r> FromRequest<'a, 'r> for Admin {
+    type Error = RoleError;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Admin, RoleError> {
+        match request.headers().get_one("X-Role") {
+            Some("admin") => Outcome::Success(Admin),
+            Some(role) => Outcome::Failure((Status::Forbidden, RoleError::Unknown(role.into()))),
+            None => Outcome::Failure((Status::Forbidden, RoleError::Missing))
+        }
+    }
+}
+
+#[get("/")]
+fn index(_key: ApiKey) -> &'static str {
+    "authorized"
+}
+
+#[get("/admin")]
+fn admin(_admin: Admin) -> &'static str {
+    "admin"
+}
+
+#[error(401)]
+fn unauthorized(req: &Request) -> String {
+    match req.guard_failure() {
+        Some(failure) => format!("{}: {}", failure.status().code, failure.error()),
+        None => "no failure".to_string()
+    }
+}
+
+#[error(403)]
+fn forbidden(req: &Request) -> String {
+    let failure = match req.guard_failure() {
+        Some(failure) => failure,
+        None => return "no failure".to_string()
+    };
+
+    match failure.downcast_ref::<RoleError>() {
+        Some(&RoleError::Missing) => "missing role".to_string(),
+        Some(&RoleError::Unknown(ref role)) => format!("unknown role {}", role),
+        None => "untyped failure".to_string()
+    }
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::Header;
+
+#[test]
+fn guard_failure_reaches_catcher() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![index])
+        .catch(errors![unauthorized]);
+
+    let mut req = MockRequest::new(Get, "/");
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::Unauthorized);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("401: \"missing key\"".to_string()));
+
+    let mut req = MockRequest::new(Get, "/").header(Header::new("X-Api-Key", "nope"));
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::Unauthorized);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("401: \"bad key nope\"".to_string()));
+
+    let mut req = MockRequest::new(Get, "/").header(Header::new("X-Api-Key", "secret"));
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::Ok);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("authorized".to_string()));
+}
+
+#[test]
+fn typed_guard_failure_reaches_catcher() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![admin])
+        .catch(errors![forbidden]);
+
+    let mut req = MockRequest::new(Get, "/admin");
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::Forbidden);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("missing role".to_string()));
+
+    let mut req = MockRequest::new(Get, "/admin").header(Header::new("X-Role", "guest"));
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::Forbidden);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("unknown role guest".to_string()));
+}
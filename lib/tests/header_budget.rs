@@ -0,0 +1,68 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::io::Cursor;
+
+use rocket::response::{self, Responder, Response};
+
+/// Responds with `self.0` `Link` headers, as a buggy loop might.
+struct Links(usize);
+
+impl<'r> Responder<'r> for Links {
+    fn respond(self) -> response::Result<'r> {
+        let mut response = Response::build();
+        response.sized_body(Cursor::new("links"));
+        for i in 0..self.0 {
+            response.raw_header_adjoin("Link", format!("</asset/{}>; rel=preload", i));
+        }
+
+        response.ok()
+    }
+}
+
+#[get("/links/<n>")]
+fn links(n: usize) -> Links {
+    Links(n)
+}
+
+use rocket::Rocket;
+use rocket::response::HeaderBudget;
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::Status;
+
+fn rocket(budget: HeaderBudget) -> Rocket {
+    rocket::ignite().mount("/", routes![links]).header_budget(budget)
+}
+
+#[test]
+fn responses_within_budget_are_untouched() {
+    let rocket = rocket(HeaderBudget::new());
+    let mut req = MockRequest::new(Get, "/links/20");
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.header_values("Link").count(), 20);
+    assert_eq!(response.body().and_then(|b| b.into_string()), Some("links".into()));
+}
+
+#[test]
+fn truncatable_headers_are_truncated() {
+    let rocket = rocket(HeaderBudget::new().max_count(10).truncate("Link"));
+    let mut req = MockRequest::new(Get, "/links/1000");
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.headers().count() <= 10);
+    assert!(response.header_values("Link").count() > 0);
+    assert_eq!(response.body().and_then(|b| b.into_string()), Some("links".into()));
+}
+
+#[test]
+fn responses_over_budget_are_replaced() {
+    let rocket = rocket(HeaderBudget::new().max_size(1024));
+    let mut req = MockRequest::new(Get, "/links/1000");
+    let response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::InternalServerError);
+    assert_eq!(response.header_values("Link").count(), 0);
+}
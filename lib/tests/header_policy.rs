@@ -0,0 +1,86 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::Request;
+use rocket::http::ContentType;
+
+#[get("/")]
+fn index(request: &Request) -> String {
+    let mut names: Vec<_> = request.headers().iter().map(|h| h.name.to_string()).collect();
+    names.sort();
+    format!("{}: {}", request.content_type(), names.join(", "))
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{Status, Header, HeaderPolicy, Duplicates};
+
+#[test]
+fn headers_are_left_alone_by_default() {
+    let rocket = rocket::ignite().mount("/", routes![index]);
+    let mut req = MockRequest::new(Get, "/").header(Header::new("content-type", "text/html"));
+    let mut response = req.dispatch_with(&rocket);
+
+    let body = response.body().and_then(|b| b.into_string());
+    assert_eq!(body, Some(format!("{}: content-type", ContentType::Any)));
+}
+
+#[test]
+fn headers_are_canonicalized() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![index])
+        .canonicalize_headers(HeaderPolicy::new());
+
+    let mut req = MockRequest::new(Get, "/")
+        .header(Header::new("content-type", "text/html"))
+        .header(Header::new("Connection", "keep-alive, X-Hop"))
+        .header(Header::new("X-Hop", "1"))
+        .header(Header::new("X-Custom", "1"));
+
+    let mut response = req.dispatch_with(&rocket);
+    let body = response.body().and_then(|b| b.into_string());
+    assert_eq!(body, Some(format!("{}: Connection, Content-Type, X-Custom", ContentType::HTML)));
+}
+
+#[test]
+fn duplicate_singletons_can_be_rejected() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![index])
+        .canonicalize_headers(HeaderPolicy::new().duplicates(Duplicates::Reject));
+
+    let mut req = MockRequest::new(Get, "/")
+        .header(Header::new("Host", "rocket.rs"))
+        .header(Header::new("host", "example.com"));
+
+    let response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn request_hooks_skip_rejected_requests() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use rocket::hook::Predicate;
+
+    let hooked = Arc::new(AtomicUsize::new(0));
+    let counter = hooked.clone();
+    let rocket = rocket::ignite()
+        .mount("/", routes![index])
+        .canonicalize_headers(HeaderPolicy::new().duplicates(Duplicates::Reject))
+        .on_request(Predicate::path("/"), move |_, _| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+    let mut req = MockRequest::new(Get, "/")
+        .header(Header::new("Host", "rocket.rs"))
+        .header(Header::new("host", "example.com"));
+
+    assert_eq!(req.dispatch_with(&rocket).status(), Status::BadRequest);
+    assert_eq!(hooked.load(Ordering::SeqCst), 0);
+
+    let mut req = MockRequest::new(Get, "/");
+    assert_eq!(req.dispatch_with(&rocket).status(), Status::Ok);
+    assert_eq!(hooked.load(Ordering::SeqCst), 1);
+}
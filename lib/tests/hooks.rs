@@ -0,0 +1,63 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::hook::Predicate;
+use rocket::http::{Header, Method};
+
+#[get("/api/users")]
+fn users() -> &'static str {
+    "users"
+}
+
+#[get("/v2/users")]
+fn users_v2() -> &'static str {
+    "users v2"
+}
+
+#[get("/about")]
+fn about() -> &'static str {
+    "about"
+}
+
+fn rocket() -> rocket::Rocket {
+    rocket::ignite()
+        .mount("/", routes![users, users_v2, about])
+        .on_request(Predicate::path("/api").header("X-Version"), |req, _| {
+            let version = req.headers().get_one("X-Version").unwrap_or("1").to_string();
+            let path = format!("/v{}{}", version, &req.uri().path()["/api".len()..]);
+            req.set_uri(path);
+        })
+        .on_response(Predicate::path("/api").method(Method::Get), |_, response| {
+            response.set_header(Header::new("X-API", "true"));
+        })
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+
+#[test]
+fn response_hook_runs_when_predicate_matches() {
+    let rocket = rocket();
+
+    let mut req = MockRequest::new(Get, "/api/users");
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.header_values("X-API").next(), Some("true"));
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("users".to_string()));
+
+    let mut req = MockRequest::new(Get, "/about");
+    let response = req.dispatch_with(&rocket);
+    assert_eq!(response.header_values("X-API").next(), None);
+}
+
+#[test]
+fn request_hook_runs_when_predicate_matches() {
+    let rocket = rocket();
+
+    let mut req = MockRequest::new(Get, "/api/users").header(Header::new("X-Version", "2"));
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("users v2".to_string()));
+}
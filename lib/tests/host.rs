@@ -0,0 +1,109 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::{Request, Route, Data};
+use rocket::handler::Outcome;
+use rocket::http::Host;
+
+#[get("/")]
+fn host(host: Host) -> String {
+    host.to_string()
+}
+
+fn login<'r>(request: &'r Request, _: Data) -> Outcome<'r> {
+    Outcome::of(request.absolute_uri("/login"))
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{Header, Status};
+use rocket::config::{Config, Environment};
+
+fn rocket_with_hosts(hosts: Vec<String>) -> rocket::Rocket {
+    let config = Config::default_for(Environment::Development, "/custom")
+        .expect("config")
+        .hosts(hosts);
+
+    rocket::custom(&config)
+        .mount("/", routes![host])
+        .mount("/", vec![Route::new(Get, "/login", login)])
+}
+
+#[test]
+fn any_host_without_allowlist() {
+    let rocket = rocket_with_hosts(vec![]);
+
+    let mut req = MockRequest::new(Get, "/")
+        .header(Header::new("Host", "anything.example.com:8000"));
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("anything.example.com:8000".to_string()));
+
+    let mut req = MockRequest::new(Get, "/");
+    let response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn allowlisted_hosts() {
+    let rocket = rocket_with_hosts(vec!["rocket.rs".into(), "localhost:8000".into()]);
+
+    for good in &["rocket.rs", "ROCKET.rs:80", "localhost:8000"] {
+        let mut req = MockRequest::new(Get, "/").header(Header::new("Host", *good));
+        let mut response = req.dispatch_with(&rocket);
+        let body_str = response.body().and_then(|b| b.into_string());
+        assert_eq!(body_str, Some(good.to_string()));
+    }
+
+    for bad in &["evil.com", "localhost", "localhost:80", "rocket.rs/x"] {
+        let mut req = MockRequest::new(Get, "/").header(Header::new("Host", *bad));
+        let response = req.dispatch_with(&rocket);
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+}
+
+#[test]
+fn absolute_uri() {
+    let rocket = rocket_with_hosts(vec!["rocket.rs".into()]);
+
+    let mut req = MockRequest::new(Get, "/login")
+        .header(Header::new("Host", "rocket.rs:8000"));
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("http://rocket.rs:8000/login".to_string()));
+
+    let mut req = MockRequest::new(Get, "/login")
+        .header(Header::new("Host", "evil.com"));
+    let response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[test]
+fn absolute_uri_uses_forwarded_scheme() {
+    let config = Config::default_for(Environment::Development, "/custom")
+        .expect("config")
+        .trusted_proxies(vec!["10.0.0.1".parse().unwrap()]);
+
+    let rocket = rocket::custom(&config)
+        .mount("/", vec![Route::new(Get, "/login", login)]);
+
+    let mut req = MockRequest::new(Get, "/login")
+        .remote("10.0.0.1:4000".parse().unwrap())
+        .header(Header::new("Host", "rocket.rs"))
+        .header(Header::new("X-Forwarded-For", "203.0.113.5"))
+        .header(Header::new("X-Forwarded-Proto", "https"));
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("https://rocket.rs/login".to_string()));
+
+    let mut req = MockRequest::new(Get, "/login")
+        .remote("10.0.0.2:4000".parse().unwrap())
+        .header(Header::new("Host", "rocket.rs"))
+        .header(Header::new("X-Forwarded-For", "203.0.113.5"))
+        .header(Header::new("X-Forwarded-Proto", "https"));
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("http://rocket.rs/login".to_string()));
+}
@@ -0,0 +1,91 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+
+use rocket::Rocket;
+use rocket::inject::Inject;
+
+static CONSTRUCTED: AtomicUsize = ATOMIC_USIZE_INIT;
+
+struct Greeter(&'static str);
+
+struct Counter;
+
+impl Counter {
+    fn new() -> Counter {
+        CONSTRUCTED.fetch_add(1, Ordering::SeqCst);
+        Counter
+    }
+}
+
+struct Agent(String);
+
+#[get("/greet")]
+fn greet(greeter: Inject<Greeter>) -> &'static str {
+    greeter.0
+}
+
+#[get("/count")]
+fn count(_counter: Inject<Counter>) -> String {
+    CONSTRUCTED.load(Ordering::SeqCst).to_string()
+}
+
+#[get("/agent")]
+fn agent(agent: Inject<Agent>) -> String {
+    agent.0.clone()
+}
+
+fn app() -> Rocket {
+    rocket::ignite()
+        .mount("/", routes![greet, count, agent])
+        .provide(|| Greeter("hello"))
+        .provide(Counter::new)
+        .provide_per_request(|request| {
+            Agent(request.headers().get_one("User-Agent").unwrap_or("unknown").to_string())
+        })
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::{Header, Status};
+use rocket::http::Method::*;
+
+fn body_of(rocket: &Rocket, mut req: MockRequest) -> Option<String> {
+    let mut response = req.dispatch_with(rocket);
+    response.body().and_then(|b| b.into_string())
+}
+
+#[test]
+fn values_are_injected_by_type() {
+    let rocket = app();
+    assert_eq!(body_of(&rocket, MockRequest::new(Get, "/greet")), Some("hello".into()));
+
+    let req = MockRequest::new(Get, "/agent").header(Header::new("User-Agent", "curl"));
+    assert_eq!(body_of(&rocket, req), Some("curl".into()));
+    assert_eq!(body_of(&rocket, MockRequest::new(Get, "/agent")), Some("unknown".into()));
+}
+
+#[test]
+fn launch_providers_are_called_once() {
+    let rocket = app();
+    let first = body_of(&rocket, MockRequest::new(Get, "/count"));
+    let second = body_of(&rocket, MockRequest::new(Get, "/count"));
+    assert!(first.is_some());
+    assert_eq!(first, second);
+}
+
+#[test]
+fn providers_can_be_substituted() {
+    let rocket = app().provide(|| Greeter("mocked"));
+    assert_eq!(body_of(&rocket, MockRequest::new(Get, "/greet")), Some("mocked".into()));
+}
+
+#[test]
+fn missing_provider_fails() {
+    let rocket = rocket::ignite().mount("/", routes![greet]);
+    let mut req = MockRequest::new(Get, "/greet");
+    let response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::InternalServerError);
+}
@@ -0,0 +1,45 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::Request;
+
+#[get("/")]
+fn index() -> &'static str { "index" }
+
+#[post("/")]
+fn create() -> &'static str { "create" }
+
+#[get("/about")]
+fn about() -> &'static str { "about" }
+
+#[error(404)]
+fn not_found(_: &Request) -> &'static str { "not found" }
+
+use rocket::config::{Config, Environment};
+use rocket::hook::Predicate;
+use rocket::http::Method::*;
+
+#[test]
+fn launch_info_describes_the_application() {
+    let config = Config::default_for(Environment::Staging, "/custom").unwrap()
+        .address("127.0.0.1".into())
+        .port(9123);
+
+    let rocket = rocket::custom(&config)
+        .mount("/", routes![about, create, index])
+        .catch(errors![not_found])
+        .on_response(Predicate::any(), |_, _| ())
+        .manage(5usize);
+
+    let info = rocket.launch_info();
+    assert_eq!(info.url(), "http://127.0.0.1:9123");
+    assert_eq!(info.environment, Environment::Staging);
+    assert_eq!(info.catchers, vec![404]);
+    assert_eq!((info.request_hooks, info.response_hooks), (0, 1));
+    assert_eq!(info.managed_state, 1);
+
+    let routes: Vec<_> = info.routes.iter().map(|r| (r.method, r.path.as_str())).collect();
+    assert_eq!(routes, vec![(Get, "/"), (Post, "/"), (Get, "/about")]);
+}
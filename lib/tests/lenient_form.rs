@@ -0,0 +1,49 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::request::{Form, LenientForm};
+
+#[derive(FromForm)]
+struct Payment {
+    amount: u64,
+}
+
+#[post("/strict", data = "<payment>")]
+fn strict(payment: Form<Payment>) -> String {
+    payment.get().amount.to_string()
+}
+
+#[post("/lenient", data = "<payment>")]
+fn lenient(payment: LenientForm<Payment>) -> String {
+    payment.into_inner().amount.to_string()
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{ContentType, Status};
+
+fn post(uri: &str, body: &str) -> (Status, Option<String>) {
+    let rocket = rocket::ignite().mount("/", routes![strict, lenient]);
+    let mut req = MockRequest::new(Post, uri)
+        .header(ContentType::Form)
+        .body(body);
+
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    (response.status(), body_str)
+}
+
+#[test]
+fn strict_form_rejects_extra_fields() {
+    assert_eq!(post("/strict", "amount=10"), (Status::Ok, Some("10".to_string())));
+    assert_eq!(post("/strict", "amount=10&extra=1").0, Status::BadRequest);
+}
+
+#[test]
+fn lenient_form_ignores_extra_fields() {
+    assert_eq!(post("/lenient", "amount=10"), (Status::Ok, Some("10".to_string())));
+    assert_eq!(post("/lenient", "extra=1&amount=10&more=2"), (Status::Ok, Some("10".to_string())));
+    assert_eq!(post("/lenient", "extra=1").0, Status::BadRequest);
+}
@@ -0,0 +1,108 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::io::Read;
+
+use rocket::{Request, Data, Outcome, Rocket};
+use rocket::config::{Config, Environment, Limits, ByteUnit};
+use rocket::data::{self, FromData};
+use rocket::request::Form;
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{ContentType, Status};
+
+#[derive(FromForm)]
+struct Message {
+    text: String,
+}
+
+#[post("/", data = "<message>")]
+fn post(message: Form<Message>) -> String {
+    message.get().text.clone()
+}
+
+/// A custom data guard that respects the `csv` limit.
+struct Csv(Vec<String>);
+
+impl FromData for Csv {
+    type Error = ();
+
+    fn from_data(request: &Request, data: Data) -> data::Outcome<Self, ()> {
+        let limit = request.limits().get("csv").as_u64();
+        let mut body = String::new();
+        if data.open().take(limit + 1).read_to_string(&mut body).is_err() {
+            return Outcome::Failure((Status::BadRequest, ()));
+        }
+
+        if body.len() as u64 > limit {
+            return Outcome::Failure((Status::PayloadTooLarge, ()));
+        }
+
+        Outcome::Success(Csv(body.split(',').map(|s| s.to_string()).collect()))
+    }
+}
+
+#[post("/csv", data = "<csv>")]
+fn csv(csv: Csv) -> String {
+    csv.0.len().to_string()
+}
+
+#[get("/limits/<name>")]
+fn limit(limits: &Limits, name: &str) -> String {
+    limits.get(name).to_string()
+}
+
+fn rocket(limits: Limits) -> Rocket {
+    let config = Config::default_for(Environment::Development, "/custom").unwrap()
+        .limits(limits);
+
+    rocket::custom(&config).mount("/", routes![post, csv, limit])
+}
+
+fn dispatch(limits: Limits, body: &str) -> Status {
+    let rocket = rocket(limits);
+    let mut req = MockRequest::new(Post, "/").header(ContentType::Form).body(body);
+    req.dispatch_with(&rocket).status()
+}
+
+#[test]
+fn forms_within_the_limit_are_accepted() {
+    let limits = Limits::default().limit("forms", ByteUnit::new(10));
+    assert_eq!(dispatch(limits.clone(), "text=hello"), Status::Ok);
+    assert_eq!(dispatch(limits, "text=hi"), Status::Ok);
+}
+
+#[test]
+fn forms_over_the_limit_are_rejected() {
+    let limits = Limits::default().limit("forms", ByteUnit::new(10));
+    assert_eq!(dispatch(limits, "text=hello!"), Status::PayloadTooLarge);
+
+    // Other limits don't apply to forms.
+    let limits = Limits::default().limit("default", ByteUnit::new(1));
+    assert_eq!(dispatch(limits, "text=hello!"), Status::Ok);
+}
+
+#[test]
+fn custom_data_guards_see_configured_limits() {
+    let rocket = rocket(Limits::default().limit("csv", ByteUnit::new(5)));
+    let mut req = MockRequest::new(Post, "/csv").body("a,b,c");
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.body().and_then(|b| b.into_string()), Some("3".into()));
+
+    let mut req = MockRequest::new(Post, "/csv").body("a,b,c,d");
+    let response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::PayloadTooLarge);
+}
+
+#[test]
+fn limits_are_a_request_guard() {
+    let rocket = rocket(Limits::default().limit("csv", ByteUnit::new(2048)));
+    for &(name, expected) in &[("csv", "2KiB"), ("forms", "32KiB"), ("xml", "1MiB")] {
+        let uri = format!("/limits/{}", name);
+        let mut req = MockRequest::new(Get, &uri);
+        let mut response = req.dispatch_with(&rocket);
+        assert_eq!(response.body().and_then(|b| b.into_string()), Some(expected.into()));
+    }
+}
@@ -0,0 +1,53 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::http::Link;
+
+#[get("/")]
+fn links(link: Link) -> String {
+    link.iter().map(|l| format!("{} {}", l.rel(), l.uri())).collect::<Vec<_>>().join("\n")
+}
+
+#[get("/pages")]
+fn pages() -> rocket::Response<'static> {
+    rocket::Response::build()
+        .header(Link::new().add("/pages?n=2", "next").add("/pages?n=9", "last"))
+        .finalize()
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::{Header, Status};
+use rocket::http::Method::*;
+
+#[test]
+fn link_headers_are_parsed() {
+    let rocket = rocket::ignite().mount("/", routes![links]);
+
+    let mut req = MockRequest::new(Get, "/")
+        .header(Header::new("Link", "</a>; rel=next"))
+        .header(Header::new("Link", "</b>; rel=\"webmention\""));
+    let mut response = req.dispatch_with(&rocket);
+    let body = response.body().and_then(|b| b.into_string());
+    assert_eq!(body, Some("next /a\nwebmention /b".into()));
+
+    let mut req = MockRequest::new(Get, "/");
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.body().and_then(|b| b.into_string()), Some("".into()));
+
+    let mut req = MockRequest::new(Get, "/").header(Header::new("Link", "/a; rel=next"));
+    let response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[test]
+fn link_headers_round_trip() {
+    let rocket = rocket::ignite().mount("/", routes![pages]);
+    let mut req = MockRequest::new(Get, "/pages");
+    let response = req.dispatch_with(&rocket);
+
+    let link: Link = response.header_values("Link").next().unwrap().parse().unwrap();
+    assert_eq!(link.get("next").map(|l| l.uri()), Some("/pages?n=2"));
+    assert_eq!(link.get("last").map(|l| l.uri()), Some("/pages?n=9"));
+}
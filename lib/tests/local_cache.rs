@@ -0,0 +1,71 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+
+use rocket::{Request, Outcome};
+use rocket::request::{self, FromRequest};
+
+static LOOKUPS: AtomicUsize = ATOMIC_USIZE_INIT;
+
+struct User(String);
+
+fn lookup_user(request: &Request) -> Option<User> {
+    LOOKUPS.fetch_add(1, Ordering::SeqCst);
+    request.headers().get_one("X-User").map(|name| User(name.to_string()))
+}
+
+struct Name(String);
+
+impl<'a, 'r> FromRequest<'a, 'r> for Name {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Name, ()> {
+        match *request.local_cache(|| lookup_user(request)) {
+            Some(ref user) => Outcome::Success(Name(user.0.clone())),
+            None => Outcome::Forward(())
+        }
+    }
+}
+
+struct Initial(char);
+
+impl<'a, 'r> FromRequest<'a, 'r> for Initial {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Initial, ()> {
+        match request.local_cache(|| lookup_user(request)).as_ref() {
+            Some(user) => Outcome::Success(Initial(user.0.chars().next().unwrap_or('?'))),
+            None => Outcome::Forward(())
+        }
+    }
+}
+
+#[get("/")]
+fn index(name: Name, initial: Initial) -> String {
+    format!("{} ({})", name.0, initial.0)
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::Header;
+
+#[test]
+fn guards_share_cached_value() {
+    let rocket = rocket::ignite().mount("/", routes![index]);
+
+    let mut req = MockRequest::new(Get, "/").header(Header::new("X-User", "bob"));
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("bob (b)".to_string()));
+    assert_eq!(LOOKUPS.load(Ordering::SeqCst), 1);
+
+    // The cache is per request.
+    let mut req = MockRequest::new(Get, "/").header(Header::new("X-User", "ann"));
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("ann (a)".to_string()));
+    assert_eq!(LOOKUPS.load(Ordering::SeqCst), 2);
+}
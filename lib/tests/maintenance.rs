@@ -0,0 +1,89 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::time::Duration;
+
+use rocket::State;
+use rocket::maintenance::Maintenance;
+
+#[get("/")]
+fn index() -> &'static str { "index" }
+
+#[get("/health")]
+fn health() -> &'static str { "ok" }
+
+#[post("/admin/maintenance/<on>")]
+fn toggle(on: bool, maintenance: State<Maintenance>) -> &'static str {
+    maintenance.set(on);
+    "toggled"
+}
+
+#[error(503)]
+fn down() -> &'static str { "down for maintenance" }
+
+use rocket::Rocket;
+use rocket::testing::MockRequest;
+use rocket::http::Method;
+use rocket::http::Method::*;
+use rocket::http::Status;
+
+fn rocket(maintenance: Maintenance) -> Rocket {
+    rocket::ignite()
+        .mount("/", routes![index, health, toggle])
+        .catch(errors![down])
+        .maintenance(maintenance)
+}
+
+fn request(rocket: &Rocket, method: Method, uri: &str) -> (Status, Option<String>) {
+    let mut req = MockRequest::new(method, uri);
+    let mut response = req.dispatch_with(rocket);
+    (response.status(), response.body().and_then(|b| b.into_string()))
+}
+
+#[test]
+fn requests_are_routed_when_off() {
+    let rocket = rocket(Maintenance::new());
+    assert_eq!(request(&rocket, Get, "/"), (Status::Ok, Some("index".into())));
+}
+
+#[test]
+fn requests_are_rejected_by_the_503_catcher_when_on() {
+    let maintenance = Maintenance::new().retry_after(Duration::from_secs(120));
+    maintenance.enable();
+    let rocket = rocket(maintenance);
+
+    let mut req = MockRequest::new(Get, "/");
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::ServiceUnavailable);
+    assert_eq!(response.header_values("Retry-After").collect::<Vec<_>>(), vec!["120"]);
+    assert_eq!(response.body().and_then(|b| b.into_string()),
+               Some("down for maintenance".into()));
+
+    // Unknown paths are unavailable too, rather than not found.
+    assert_eq!(request(&rocket, Get, "/nope").0, Status::ServiceUnavailable);
+}
+
+#[test]
+fn allow_listed_paths_are_routed_when_on() {
+    let maintenance = Maintenance::new().allow("/health").allow("/admin");
+    maintenance.enable();
+    let rocket = rocket(maintenance);
+
+    assert_eq!(request(&rocket, Get, "/health"), (Status::Ok, Some("ok".into())));
+    assert_eq!(request(&rocket, Get, "/").0, Status::ServiceUnavailable);
+}
+
+#[test]
+fn switch_can_be_flipped_from_a_route() {
+    let maintenance = Maintenance::new().allow("/admin");
+    let rocket = rocket(maintenance.clone());
+
+    assert_eq!(request(&rocket, Post, "/admin/maintenance/true").0, Status::Ok);
+    assert!(maintenance.is_enabled());
+    assert_eq!(request(&rocket, Get, "/").0, Status::ServiceUnavailable);
+
+    assert_eq!(request(&rocket, Post, "/admin/maintenance/false").0, Status::Ok);
+    assert_eq!(request(&rocket, Get, "/"), (Status::Ok, Some("index".into())));
+}
@@ -0,0 +1,69 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rocket::State;
+
+struct Counter(AtomicUsize);
+
+struct Unmanaged;
+
+#[get("/count")]
+fn count(counter: State<Counter>) -> String {
+    (counter.0.fetch_add(1, Ordering::SeqCst) + 1).to_string()
+}
+
+#[get("/unmanaged")]
+fn unmanaged(_state: State<Unmanaged>) -> &'static str {
+    "unreachable"
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::Status;
+use rocket::shutdown::ShutdownReason;
+
+#[test]
+fn state_is_shared_across_requests() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![count])
+        .manage(Counter(AtomicUsize::new(0)));
+
+    for expected in 1..4 {
+        let mut req = MockRequest::new(Get, "/count");
+        let mut response = req.dispatch_with(&rocket);
+        let body_str = response.body().and_then(|b| b.into_string());
+        assert_eq!(body_str, Some(expected.to_string()));
+    }
+}
+
+#[test]
+fn unmanaged_state_fails_request() {
+    let rocket = rocket::ignite().mount("/", routes![unmanaged]);
+
+    let mut req = MockRequest::new(Get, "/unmanaged");
+    let response = req.dispatch_with(&rocket);
+    assert_eq!(response.status(), Status::InternalServerError);
+}
+
+#[test]
+fn unmanaged_state_aborts_launch() {
+    let reason = rocket::ignite()
+        .mount("/", routes![count, unmanaged])
+        .manage(Counter(AtomicUsize::new(0)))
+        .run();
+
+    assert_eq!(reason, ShutdownReason::Config("unmanaged state".into()));
+    assert_eq!(reason.exit_code(), 78);
+}
+
+#[test]
+#[should_panic(expected = "duplicately managed state")]
+fn state_is_managed_once() {
+    rocket::ignite()
+        .manage(Counter(AtomicUsize::new(0)))
+        .manage(Counter(AtomicUsize::new(1)));
+}
@@ -0,0 +1,74 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::Request;
+
+#[get("/items/<id>")]
+fn get_item(id: usize) -> String { format!("item {}", id) }
+
+#[put("/items/<id>")]
+fn put_item(id: usize) -> String { format!("put {}", id) }
+
+#[get("/pages")]
+fn pages() -> &'static str { "pages" }
+
+#[error(405)]
+fn not_allowed(request: &Request) -> String {
+    format!("{} is not allowed", request.method())
+}
+
+use rocket::Rocket;
+use rocket::testing::MockRequest;
+use rocket::http::Method::{self, *};
+use rocket::http::Status;
+
+fn rocket() -> Rocket {
+    rocket::ignite()
+        .mount("/api", routes![get_item, put_item])
+        .mount("/", routes![pages])
+        .method_not_allowed("/api")
+}
+
+fn dispatch(rocket: &Rocket, method: Method, uri: &str)
+    -> (Status, Option<String>, Option<String>)
+{
+    let mut req = MockRequest::new(method, uri);
+    let mut response = req.dispatch_with(rocket);
+    let allow = response.header_values("Allow").next().map(|v| v.to_string());
+    (response.status(), allow, response.body().and_then(|b| b.into_string()))
+}
+
+#[test]
+fn unsupported_methods_are_not_allowed() {
+    let rocket = rocket();
+    let (status, allow, _) = dispatch(&rocket, Delete, "/api/items/7");
+    assert_eq!(status, Status::MethodNotAllowed);
+    assert_eq!(allow, Some("GET, HEAD, PUT".into()));
+
+    let (status, _, body) = dispatch(&rocket, Put, "/api/items/7");
+    assert_eq!((status, body), (Status::Ok, Some("put 7".into())));
+}
+
+#[test]
+fn not_allowed_responses_use_catchers() {
+    let rocket = rocket().catch(errors![not_allowed]);
+    let (status, allow, body) = dispatch(&rocket, Post, "/api/items/7");
+    assert_eq!(status, Status::MethodNotAllowed);
+    assert_eq!(allow, Some("GET, HEAD, PUT".into()));
+    assert_eq!(body, Some("POST is not allowed".into()));
+}
+
+#[test]
+fn unknown_paths_are_not_found() {
+    let rocket = rocket();
+    assert_eq!(dispatch(&rocket, Delete, "/api/users").0, Status::NotFound);
+    assert_eq!(dispatch(&rocket, Get, "/api/items/seven").0, Status::NotFound);
+}
+
+#[test]
+fn other_mounts_are_not_found() {
+    let (status, allow, _) = dispatch(&rocket(), Post, "/pages");
+    assert_eq!((status, allow), (Status::NotFound, None));
+}
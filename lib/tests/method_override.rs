@@ -0,0 +1,69 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[post("/")]
+fn post() -> &'static str { "post" }
+
+#[put("/")]
+fn put() -> &'static str { "put" }
+
+#[delete("/")]
+fn delete() -> &'static str { "delete" }
+
+#[get("/")]
+fn get() -> &'static str { "get" }
+
+use rocket::Rocket;
+use rocket::request::MethodOverride;
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{Method, Header, ContentType};
+
+fn rocket(policy: MethodOverride) -> Rocket {
+    rocket::ignite()
+        .mount("/", routes![post, put, delete, get])
+        .method_override(policy)
+}
+
+fn dispatch(rocket: &Rocket, method: Method, form: Option<&str>, header: Option<&str>)
+    -> Option<String>
+{
+    let mut req = MockRequest::new(method, "/");
+    if let Some(form) = form {
+        req = req.header(ContentType::Form).body(form);
+    }
+
+    if let Some(header) = header {
+        req = req.header(Header::new("X-HTTP-Method-Override", header.to_string()));
+    }
+
+    let mut response = req.dispatch_with(rocket);
+    response.body().and_then(|b| b.into_string())
+}
+
+#[test]
+fn form_field_overrides_by_default() {
+    let rocket = rocket(MethodOverride::new());
+    assert_eq!(dispatch(&rocket, Post, Some("_method=put&a=b"), None), Some("put".into()));
+    assert_eq!(dispatch(&rocket, Post, Some("_method=delete"), None), Some("delete".into()));
+    assert_eq!(dispatch(&rocket, Post, Some("_method=get"), None), Some("post".into()));
+    assert_eq!(dispatch(&rocket, Post, None, Some("PUT")), Some("post".into()));
+}
+
+#[test]
+fn header_overrides_when_enabled() {
+    let rocket = rocket(MethodOverride::new().header(true));
+    assert_eq!(dispatch(&rocket, Post, None, Some("PUT")), Some("put".into()));
+    assert_eq!(dispatch(&rocket, Post, None, Some("GET")), Some("post".into()));
+    assert_eq!(dispatch(&rocket, Get, None, Some("DELETE")), Some("get".into()));
+    assert_eq!(dispatch(&rocket, Post, Some("_method=delete"), Some("PUT")),
+               Some("delete".into()));
+}
+
+#[test]
+fn overrides_can_be_disabled() {
+    let rocket = rocket(MethodOverride::disabled());
+    assert_eq!(dispatch(&rocket, Post, Some("_method=put"), None), Some("post".into()));
+}
@@ -0,0 +1,79 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::io::Cursor;
+
+use rocket::metrics::Metrics;
+use rocket::response::Stream;
+
+#[post("/echo", data = "<body>")]
+fn echo(body: String) -> String {
+    body
+}
+
+#[get("/stream")]
+fn stream() -> Stream<Cursor<Vec<u8>>> {
+    Stream::chunked(Cursor::new(vec![0; 3000]), 1024)
+}
+
+#[get("/metrics")]
+fn metrics(metrics: &Metrics) -> String {
+    metrics.render()
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Header;
+use rocket::http::Method::*;
+
+#[test]
+fn sizes_are_recorded_per_route() {
+    let rocket = rocket::ignite().mount("/", routes![echo, stream]);
+
+    let mut req = MockRequest::new(Post, "/echo")
+        .header(Header::new("Content-Length", "5"))
+        .body("hello");
+    req.dispatch_with(&rocket);
+
+    let mut req = MockRequest::new(Get, "/unknown");
+    req.dispatch_with(&rocket);
+
+    let sizes = rocket.metrics().get("POST /echo").expect("echo sizes");
+    assert_eq!(sizes.request.buckets(), vec![(7, 1)]);
+    assert_eq!(sizes.response.sum(), 5);
+    assert_eq!(rocket.metrics().snapshot().len(), 1);
+}
+
+#[test]
+fn streamed_sizes_are_recorded_once_read() {
+    let rocket = rocket::ignite().mount("/", routes![stream]);
+
+    let mut req = MockRequest::new(Get, "/stream");
+    {
+        let mut response = req.dispatch_with(&rocket);
+        assert!(rocket.metrics().get("GET /stream").is_none());
+        let body = response.body().and_then(|b| b.into_string());
+        assert_eq!(body.map(|b| b.len()), Some(3000));
+    }
+
+    let sizes = rocket.metrics().get("GET /stream").unwrap();
+    assert_eq!(sizes.request.count(), 0);
+    assert_eq!(sizes.response.max(), 3000);
+}
+
+#[test]
+fn metrics_endpoint_renders_histograms() {
+    let rocket = rocket::ignite().mount("/", routes![echo, metrics]);
+
+    let mut req = MockRequest::new(Post, "/echo").body("hi");
+    req.dispatch_with(&rocket);
+
+    let mut req = MockRequest::new(Get, "/metrics");
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap();
+    let lines: Vec<_> = body_str.lines().collect();
+    assert!(lines.contains(&"rocket_response_body_bytes_bucket{route=\"POST /echo\",le=\"3\"} 1"));
+    assert!(lines.contains(&"rocket_response_body_bytes_count{route=\"POST /echo\"} 1"));
+    assert!(lines.contains(&"rocket_request_body_bytes_count{route=\"POST /echo\"} 0"));
+}
@@ -0,0 +1,235 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::io::Read;
+
+use rocket::request::{Form, MultipartForm, TempFile};
+
+#[derive(FromForm)]
+struct Upload {
+    description: String,
+    files: Vec<TempFile>,
+    note: Option<TempFile>,
+}
+
+#[post("/upload", data = "<upload>")]
+fn upload(upload: MultipartForm<Upload>) -> String {
+    let upload = upload.into_inner();
+    let mut summary = upload.description.clone();
+    for file in upload.files.iter().chain(upload.note.iter()) {
+        let mut contents = String::new();
+        file.open().unwrap().read_to_string(&mut contents).unwrap();
+        summary.push_str(&format!(";{}={}", file.file_name().unwrap_or("?"), contents));
+    }
+
+    summary
+}
+
+#[derive(FromForm)]
+struct Avatar {
+    file: TempFile,
+}
+
+#[post("/avatar", data = "<avatar>")]
+fn avatar(avatar: Form<Avatar>) -> String {
+    avatar.get().file.path().display().to_string()
+}
+
+#[derive(FromForm)]
+struct Message {
+    text: String,
+    urgent: bool,
+}
+
+#[post("/message", data = "<message>")]
+fn message(message: Form<Message>) -> String {
+    let message = message.get();
+    format!("{} ({})", message.text, message.urgent)
+}
+
+use rocket::Rocket;
+use rocket::config::{Config, Environment, Limits, ByteUnit};
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{ContentType, Status};
+
+fn multipart(rocket: &Rocket, body: &str) -> (Status, Option<String>) {
+    let content_type = ContentType::with_params("multipart", "form-data", Some("boundary=XyZ"));
+    let mut req = MockRequest::new(Post, "/upload").header(content_type).body(body);
+    let mut response = req.dispatch_with(rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    (response.status(), body_str)
+}
+
+const BODY: &'static str = "--XyZ\r\n\
+    Content-Disposition: form-data; name=\"description\"\r\n\
+    \r\n\
+    holiday photos\r\n\
+    --XyZ\r\n\
+    Content-Disposition: form-data; name=\"files\"; filename=\"one.txt\"\r\n\
+    Content-Type: text/plain\r\n\
+    \r\n\
+    first\r\n\
+    --XyZ\r\n\
+    Content-Disposition: form-data; name=\"files\"; filename=\"../two.txt\"\r\n\
+    \r\n\
+    second file\r\n\
+    --XyZ--\r\n";
+
+#[test]
+fn multipart_form_with_files() {
+    let rocket = rocket::ignite().mount("/", routes![upload]);
+    assert_eq!(multipart(&rocket, BODY),
+               (Status::Ok, Some("holiday photos;one.txt=first;two.txt=second file".into())));
+}
+
+#[test]
+fn multipart_form_limits() {
+    let config = Config::default_for(Environment::Development, "/custom").unwrap()
+        .limits(Limits::default().limit("file", ByteUnit::new(8)));
+
+    let rocket = rocket::custom(&config).mount("/", routes![upload]);
+    assert_eq!(multipart(&rocket, BODY).0, Status::PayloadTooLarge);
+
+    let config = Config::default_for(Environment::Development, "/custom").unwrap()
+        .limits(Limits::default().limit("forms", ByteUnit::new(8)));
+
+    let rocket = rocket::custom(&config).mount("/", routes![upload]);
+    assert_eq!(multipart(&rocket, BODY).0, Status::PayloadTooLarge);
+}
+
+#[test]
+fn multipart_form_part_and_file_counts() {
+    let config = Config::default_for(Environment::Development, "/custom").unwrap()
+        .limits(Limits::default().limit("files", ByteUnit::new(1)));
+
+    let rocket = rocket::custom(&config).mount("/", routes![upload]);
+    assert_eq!(multipart(&rocket, BODY).0, Status::PayloadTooLarge);
+
+    let config = Config::default_for(Environment::Development, "/custom").unwrap()
+        .limits(Limits::default().limit("parts", ByteUnit::new(2)));
+
+    let rocket = rocket::custom(&config).mount("/", routes![upload]);
+    assert_eq!(multipart(&rocket, BODY).0, Status::PayloadTooLarge);
+
+    let config = Config::default_for(Environment::Development, "/custom").unwrap()
+        .limits(Limits::default()
+            .limit("parts", ByteUnit::new(3))
+            .limit("files", ByteUnit::new(2)));
+
+    let rocket = rocket::custom(&config).mount("/", routes![upload]);
+    assert_eq!(multipart(&rocket, BODY).0, Status::Ok);
+}
+
+#[test]
+fn text_fields_cannot_claim_files() {
+    let rocket = rocket::ignite().mount("/", routes![upload]);
+    let body = "--XyZ\r\n\
+        Content-Disposition: form-data; name=\"description\"\r\n\
+        \r\n\
+        d\r\n\
+        --XyZ\r\n\
+        Content-Disposition: form-data; name=\"note\"\r\n\
+        \r\n\
+        %%0\r\n\
+        --XyZ\r\n\
+        Content-Disposition: form-data; name=\"files\"; filename=\"one.txt\"\r\n\
+        \r\n\
+        first\r\n\
+        --XyZ--\r\n";
+
+    assert_eq!(multipart(&rocket, body), (Status::Ok, Some("d;one.txt=first".into())));
+}
+
+#[test]
+fn malformed_multipart_form() {
+    let rocket = rocket::ignite().mount("/", routes![upload]);
+    assert_eq!(multipart(&rocket, &BODY[..60]).0, Status::BadRequest);
+    assert_eq!(multipart(&rocket, "--XyZ\r\n\r\nno name\r\n--XyZ--").0, Status::BadRequest);
+
+    let mut req = MockRequest::new(Post, "/upload")
+        .header(ContentType::DataForm)
+        .body(BODY);
+
+    assert_eq!(req.dispatch_with(&rocket).status(), Status::BadRequest);
+}
+
+#[test]
+fn temp_files_only_come_from_multipart_forms() {
+    let rocket = rocket::ignite().mount("/", routes![avatar]);
+    let mut req = MockRequest::new(Post, "/avatar")
+        .header(ContentType::Form)
+        .body("file=%2Fetc%2Fpasswd");
+
+    assert_eq!(req.dispatch_with(&rocket).status(), Status::BadRequest);
+}
+
+const MESSAGE: &'static str = "--XyZ\r\n\
+    Content-Disposition: form-data; name=\"text\"\r\n\
+    \r\n\
+    hi & bye\r\n\
+    --XyZ\r\n\
+    Content-Disposition: form-data; name=\"attachment\"; filename=\"a.txt\"\r\n\
+    \r\n\
+    hello\r\n\
+    --XyZ\r\n\
+    Content-Disposition: form-data; name=\"urgent\"\r\n\
+    \r\n\
+    true\r\n\
+    --XyZ--\r\n";
+
+fn message_with(rocket: &Rocket, content_type: ContentType, body: &str) -> (Status, String) {
+    let mut req = MockRequest::new(Post, "/message").header(content_type).body(body);
+    let mut response = req.dispatch_with(rocket);
+    let body_str = response.body().and_then(|b| b.into_string()).unwrap_or(String::new());
+    (response.status(), body_str)
+}
+
+#[test]
+fn forms_parse_urlencoded_and_multipart_text_fields() {
+    let rocket = rocket::ignite().mount("/", routes![message]);
+    let multipart = ContentType::with_params("multipart", "form-data", Some("boundary=XyZ"));
+
+    assert_eq!(message_with(&rocket, ContentType::Form, "text=hi+%26+bye&urgent=true"),
+               (Status::Ok, "hi & bye (true)".into()));
+
+    // The file input's part is skipped.
+    assert_eq!(message_with(&rocket, multipart.clone(), MESSAGE),
+               (Status::Ok, "hi & bye (true)".into()));
+
+    // Without the file input's part.
+    let start = MESSAGE.find("--XyZ\r\nContent-Disposition: form-data; name=\"attachment").unwrap();
+    let end = MESSAGE.find("--XyZ\r\nContent-Disposition: form-data; name=\"urgent").unwrap();
+    let without_file = format!("{}{}", &MESSAGE[..start], &MESSAGE[end..]);
+    assert_eq!(message_with(&rocket, multipart.clone(), &without_file),
+               (Status::Ok, "hi & bye (true)".into()));
+
+    assert_eq!(message_with(&rocket, multipart.clone(), &MESSAGE[..60]).0, Status::BadRequest);
+    assert_eq!(message_with(&rocket, ContentType::DataForm, MESSAGE).0, Status::BadRequest);
+    assert_eq!(message_with(&rocket, ContentType::JSON, MESSAGE).0, Status::NotFound);
+}
+
+#[test]
+fn forms_do_not_store_files() {
+    let multipart = ContentType::with_params("multipart", "form-data", Some("boundary=XyZ"));
+    let config = Config::default_for(Environment::Development, "/custom").unwrap()
+        .limits(Limits::default().limit("files", ByteUnit::new(0)));
+
+    let rocket = rocket::custom(&config).mount("/", routes![message]);
+    assert_eq!(message_with(&rocket, multipart, MESSAGE), (Status::Ok, "hi & bye (true)".into()));
+}
+
+#[test]
+fn forms_limit_multipart_text_fields_and_files() {
+    let multipart = ContentType::with_params("multipart", "form-data", Some("boundary=XyZ"));
+    for &(limit, size) in &[("forms", 6), ("file", 4), ("parts", 2)] {
+        let config = Config::default_for(Environment::Development, "/custom").unwrap()
+            .limits(Limits::default().limit(limit, ByteUnit::new(size)));
+
+        let rocket = rocket::custom(&config).mount("/", routes![message]);
+        let (status, _) = message_with(&rocket, multipart.clone(), MESSAGE);
+        assert_eq!(status, Status::PayloadTooLarge);
+    }
+}
@@ -0,0 +1,52 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
+
+use rocket::{Request, Outcome};
+use rocket::request::{self, FromRequest};
+
+static DELIVERED: AtomicUsize = ATOMIC_USIZE_INIT;
+
+struct Delivery;
+
+impl<'a, 'r> FromRequest<'a, 'r> for Delivery {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Delivery, ()> {
+        request.on_finish(|result| {
+            if result.is_ok() {
+                DELIVERED.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        Outcome::Success(Delivery)
+    }
+}
+
+#[get("/job")]
+fn job(_delivery: Delivery) -> &'static str {
+    "job"
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+
+#[test]
+fn callbacks_run_after_the_response() {
+    let rocket = rocket::ignite().mount("/", routes![job]);
+
+    {
+        let mut req = MockRequest::new(Get, "/job");
+        let mut response = req.dispatch_with(&rocket);
+        let body_str = response.body().and_then(|b| b.into_string());
+        assert_eq!(body_str, Some("job".to_string()));
+
+        // The response hasn't been delivered until the request is done.
+        assert_eq!(DELIVERED.load(Ordering::SeqCst), 0);
+    }
+
+    assert_eq!(DELIVERED.load(Ordering::SeqCst), 1);
+}
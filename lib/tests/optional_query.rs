@@ -0,0 +1,62 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::request::FormError;
+
+#[derive(FromForm)]
+struct Page {
+    number: usize,
+}
+
+#[get("/optional?<page>")]
+fn optional(page: Option<Page>) -> String {
+    page.map_or("none".to_string(), |p| p.number.to_string())
+}
+
+#[get("/result?<page>")]
+fn result(page: Result<Page, FormError>) -> String {
+    match page {
+        Ok(page) => page.number.to_string(),
+        Err(e) => format!("error: {}", e.field())
+    }
+}
+
+#[get("/required?<page>")]
+fn required(page: Page) -> String {
+    page.number.to_string()
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::Status;
+
+fn get(uri: &str) -> (Status, Option<String>) {
+    let rocket = rocket::ignite().mount("/", routes![optional, result, required]);
+    let mut req = MockRequest::new(Get, uri);
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    (response.status(), body_str)
+}
+
+#[test]
+fn optional_query_params() {
+    assert_eq!(get("/optional?number=3"), (Status::Ok, Some("3".into())));
+    assert_eq!(get("/optional"), (Status::Ok, Some("none".into())));
+    assert_eq!(get("/optional?number=three"), (Status::Ok, Some("none".into())));
+}
+
+#[test]
+fn result_query_params() {
+    assert_eq!(get("/result?number=3"), (Status::Ok, Some("3".into())));
+    assert_eq!(get("/result?number=three"), (Status::Ok, Some("error: number".into())));
+    assert_eq!(get("/result"), (Status::Ok, Some("error: number".into())));
+}
+
+#[test]
+fn required_query_params_forward() {
+    assert_eq!(get("/required?number=3"), (Status::Ok, Some("3".into())));
+    assert_eq!(get("/required").0, Status::NotFound);
+    assert_eq!(get("/required?number=three").0, Status::NotFound);
+}
@@ -0,0 +1,45 @@
+extern crate rocket;
+
+use rocket::Request;
+use rocket::http::{Method, ContentType};
+
+#[test]
+fn parses_request_heads() {
+    let raw = b"POST /items?page=2 HTTP/1.1\r\n\
+                Host: rocket.rs\r\n\
+                Content-Type: application/json\r\n\
+                Cookie: name=value\r\n\
+                \r\n\
+                {\"body\": \"ignored\"}";
+
+    let request = Request::parse(raw).unwrap();
+    assert_eq!(request.method(), Method::Post);
+    assert_eq!(request.uri().path(), "/items");
+    assert_eq!(request.uri().query(), Some("page=2"));
+    assert_eq!(request.content_type(), ContentType::JSON);
+    assert_eq!(request.cookies().find("name").map(|c| c.value),
+               Some("value".to_string()));
+}
+
+#[test]
+fn parses_options_asterisk() {
+    let request = Request::parse(b"OPTIONS * HTTP/1.1\r\n\r\n").unwrap();
+    assert_eq!(request.uri().as_str(), "*");
+    assert!(Request::parse(b"GET * HTTP/1.1\r\n\r\n").is_err());
+}
+
+#[test]
+fn rejects_malformed_heads() {
+    let malformed: &[&[u8]] = &[
+        b"",
+        b"GET",
+        b"GET / HTTP/1.1\r\n",
+        b"GET / HTTP/1.1\r\nHost rocket.rs\r\n\r\n",
+        b"GET http://rocket.rs/ HTTP/1.1\r\n\r\n",
+        b"\xff\xfe / HTTP/1.1\r\n\r\n",
+    ];
+
+    for raw in malformed {
+        assert!(Request::parse(raw).is_err(), "{:?} should fail", raw);
+    }
+}
@@ -0,0 +1,53 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Read};
+
+use rocket::Data;
+use rocket::config::ByteUnit;
+use rocket::http::Status;
+
+#[post("/<name>", data = "<data>")]
+fn upload(name: String, data: Data) -> Result<String, Status> {
+    let path = env::temp_dir().join(format!("rocket-persist-{}", name));
+    match data.persist_to(&path, ByteUnit::new(8)) {
+        Ok(n) => Ok(n.to_string()),
+        Err(ref e) if e.kind() == io::ErrorKind::InvalidData => Err(Status::PayloadTooLarge),
+        Err(_) => Err(Status::InternalServerError)
+    }
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+
+fn contents(name: &str) -> Option<String> {
+    let mut contents = String::new();
+    let path = env::temp_dir().join(format!("rocket-persist-{}", name));
+    File::open(path).ok().map(|mut f| {
+        f.read_to_string(&mut contents).unwrap();
+        contents
+    })
+}
+
+#[test]
+fn persist_within_limit() {
+    let rocket = rocket::ignite().mount("/", routes![upload]);
+    let mut req = MockRequest::new(Post, "/small").body("12345678");
+    let mut response = req.dispatch_with(&rocket);
+    assert_eq!(response.body().and_then(|b| b.into_string()), Some("8".to_string()));
+    assert_eq!(contents("small"), Some("12345678".to_string()));
+
+    let _ = fs::remove_file(env::temp_dir().join("rocket-persist-small"));
+}
+
+#[test]
+fn persist_over_limit() {
+    let rocket = rocket::ignite().mount("/", routes![upload]);
+    let mut req = MockRequest::new(Post, "/large").body("123456789");
+    assert_eq!(req.dispatch_with(&rocket).status(), Status::PayloadTooLarge);
+    assert_eq!(contents("large"), None);
+}
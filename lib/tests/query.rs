@@ -0,0 +1,90 @@
+#![feature(plugin, custom_derive)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::{Request, Outcome};
+use rocket::request::{self, Query, FromRequest};
+
+#[derive(FromForm)]
+struct Search {
+    q: String,
+    page: Option<usize>,
+}
+
+#[derive(FromForm)]
+struct Filters {
+    tag: Option<String>,
+}
+
+#[get("/search")]
+fn search(search: Query<Search>) -> String {
+    format!("{}:{}", search.q, search.page.unwrap_or(1))
+}
+
+#[get("/items")]
+fn items(filters: Query<Filters>) -> String {
+    filters.into_inner().tag.unwrap_or("all".to_string())
+}
+
+struct Page(usize);
+
+impl<'a, 'r> FromRequest<'a, 'r> for Page {
+    type Error = String;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Page, String> {
+        match request.query_value::<usize>("page") {
+            Some(Ok(page)) => Outcome::Success(Page(page)),
+            Some(Err(value)) => Outcome::Failure((Status::BadRequest, value.to_string())),
+            None => Outcome::Success(Page(1))
+        }
+    }
+}
+
+#[get("/pages")]
+fn pages(page: Page) -> String {
+    page.0.to_string()
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::Status;
+
+fn get(uri: &str) -> (Status, Option<String>) {
+    let rocket = rocket::ignite().mount("/", routes![search, items, pages]);
+    let mut req = MockRequest::new(Get, uri);
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    (response.status(), body_str)
+}
+
+#[test]
+fn query_guards_parse_query_strings() {
+    assert_eq!(get("/search?q=rocket&page=2"), (Status::Ok, Some("rocket:2".into())));
+    assert_eq!(get("/search?q=rocket"), (Status::Ok, Some("rocket:1".into())));
+    assert_eq!(get("/search?utm_source=x&q=hi%20there"), (Status::Ok, Some("hi there:1".into())));
+}
+
+#[test]
+fn query_guards_with_optional_fields() {
+    assert_eq!(get("/items"), (Status::Ok, Some("all".into())));
+    assert_eq!(get("/items?tag=new"), (Status::Ok, Some("new".into())));
+}
+
+#[test]
+fn bad_query_strings_are_rejected() {
+    assert_eq!(get("/search").0, Status::BadRequest);
+    assert_eq!(get("/search?page=2").0, Status::BadRequest);
+
+    // Optional fields that fail to parse are `None`.
+    assert_eq!(get("/search?q=x&page=two"), (Status::Ok, Some("x:1".into())));
+}
+
+#[test]
+fn guards_read_single_query_values() {
+    assert_eq!(get("/pages?page=3"), (Status::Ok, Some("3".into())));
+    assert_eq!(get("/pages?q=x&page=1&page=7"), (Status::Ok, Some("7".into())));
+    assert_eq!(get("/pages?%70age=5"), (Status::Ok, Some("5".into())));
+    assert_eq!(get("/pages"), (Status::Ok, Some("1".into())));
+    assert_eq!(get("/pages?page=last").0, Status::BadRequest);
+}
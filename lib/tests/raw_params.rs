@@ -0,0 +1,53 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::http::RawStr;
+
+#[get("/raw/<name>")]
+fn raw(name: &RawStr) -> String {
+    name.as_str().to_string()
+}
+
+#[get("/decoded/<name>")]
+fn decoded(name: String) -> String {
+    name
+}
+
+#[get("/raw/<dir>/<file>")]
+fn raw_two(dir: &RawStr, file: &RawStr) -> String {
+    format!("{} then {}", dir, file)
+}
+
+use rocket::Rocket;
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::Status;
+
+fn get(rocket: &Rocket, uri: &str) -> Option<String> {
+    let mut req = MockRequest::new(Get, uri);
+    let mut response = req.dispatch_with(rocket);
+    response.body().and_then(|b| b.into_string())
+}
+
+#[test]
+fn raw_params_are_not_decoded() {
+    let rocket = rocket::ignite().mount("/", routes![raw, decoded, raw_two]);
+
+    assert_eq!(get(&rocket, "/raw/a%2Fb"), Some("a%2Fb".into()));
+    assert_eq!(get(&rocket, "/raw/a/b"), Some("a then b".into()));
+    assert_eq!(get(&rocket, "/raw/hello%20world%21"), Some("hello%20world%21".into()));
+    assert_eq!(get(&rocket, "/raw/a+b"), Some("a+b".into()));
+}
+
+#[test]
+fn raw_params_keep_invalid_encodings() {
+    let rocket = rocket::ignite().mount("/", routes![raw, decoded]);
+
+    // `%ff` isn't valid UTF-8 once decoded, so a `String` parameter forwards.
+    assert_eq!(get(&rocket, "/raw/%ff"), Some("%ff".into()));
+    let mut req = MockRequest::new(Get, "/decoded/%ff");
+    assert_eq!(req.dispatch_with(&rocket).status(), Status::NotFound);
+    assert_eq!(get(&rocket, "/decoded/a%2Fb"), Some("a/b".into()));
+}
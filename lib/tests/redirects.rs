@@ -0,0 +1,80 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::config::{Config, Environment, RedirectRule};
+
+#[get("/about")]
+fn about() -> &'static str {
+    "about"
+}
+
+#[get("/old")]
+fn old() -> &'static str {
+    "shadowed"
+}
+
+use rocket::Rocket;
+use rocket::hook::Predicate;
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::Status;
+
+fn location(rocket: &Rocket, uri: &str) -> (Status, Option<String>) {
+    let mut req = MockRequest::new(Get, uri);
+    let response = req.dispatch_with(rocket);
+    let location = response.headers()
+        .find(|h| h.name == "Location")
+        .map(|h| h.value.to_string());
+
+    (response.status(), location)
+}
+
+#[test]
+fn builder_redirects_before_routing() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![about, old])
+        .redirect("/old", "/about", Status::MovedPermanently)
+        .redirect("/blog/<path..>", "/posts/<path..>", Status::PermanentRedirect);
+
+    assert_eq!(location(&rocket, "/old"),
+               (Status::MovedPermanently, Some("/about".to_string())));
+    assert_eq!(location(&rocket, "/blog/2016/hello?draft=1"),
+               (Status::PermanentRedirect, Some("/posts/2016/hello?draft=1".to_string())));
+    assert_eq!(location(&rocket, "/about"), (Status::Ok, None));
+    assert_eq!(location(&rocket, "/unknown"), (Status::NotFound, None));
+}
+
+#[test]
+fn config_redirects_come_first() {
+    let rule = RedirectRule::new("/old", "/about", Status::Found).unwrap();
+    let config = Config::default_for(Environment::Development, "/custom").unwrap()
+        .redirects(vec![rule]);
+
+    let rocket = rocket::custom(&config)
+        .mount("/", routes![about, old])
+        .redirect("/old", "/elsewhere", Status::MovedPermanently);
+
+    assert_eq!(location(&rocket, "/old"), (Status::Found, Some("/about".to_string())));
+}
+
+#[test]
+fn redirects_see_rewritten_uris() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![about])
+        .on_request(Predicate::path("/v1"), |req, _| {
+            let path = req.uri().path()["/v1".len()..].to_string();
+            req.set_uri(path);
+        })
+        .redirect("/old", "/about", Status::MovedPermanently);
+
+    assert_eq!(location(&rocket, "/v1/old"),
+               (Status::MovedPermanently, Some("/about".to_string())));
+}
+
+#[test]
+#[should_panic(expected = "invalid redirect")]
+fn invalid_redirects_abort() {
+    rocket::ignite().redirect("/<a>", "/<b>", Status::Found);
+}
@@ -0,0 +1,34 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::net::SocketAddr;
+
+#[get("/")]
+fn remote(address: SocketAddr) -> String {
+    address.to_string()
+}
+
+#[get("/", rank = 2)]
+fn unknown() -> &'static str {
+    "unknown"
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+
+#[test]
+fn remote_address_is_injected() {
+    let rocket = rocket::ignite().mount("/", routes![remote, unknown]);
+
+    let mut req = MockRequest::new(Get, "/").remote("192.168.1.7:4321".parse().unwrap());
+    let mut response = req.dispatch_with(&rocket);
+    let body = response.body().and_then(|b| b.into_string());
+    assert_eq!(body, Some("192.168.1.7:4321".into()));
+
+    let mut req = MockRequest::new(Get, "/");
+    let mut response = req.dispatch_with(&rocket);
+    let body = response.body().and_then(|b| b.into_string());
+    assert_eq!(body, Some("unknown".into()));
+}
@@ -0,0 +1,45 @@
+extern crate rocket;
+
+use rocket::{Request, Data, Resource, Routes};
+use rocket::handler::Outcome;
+
+struct Item;
+
+impl Resource for Item {
+    fn get<'r>(request: &'r Request, data: Data) -> Outcome<'r> {
+        match request.get_param::<usize>(0) {
+            Ok(id) => Outcome::of(format!("item {}", id)),
+            Err(_) => Outcome::forward(data)
+        }
+    }
+
+    fn put<'r>(request: &'r Request, _: Data) -> Outcome<'r> {
+        Outcome::of(format!("put {}", request.get_param::<&str>(0).unwrap_or("?")))
+    }
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::{self, *};
+use rocket::http::Status;
+
+fn dispatch(method: Method, uri: &str) -> (Status, Option<String>) {
+    let rocket = rocket::ignite().mount("/", Routes::group("/api").resource::<Item>("/items/<id>"));
+    let mut req = MockRequest::new(method, uri);
+    let mut response = req.dispatch_with(&rocket);
+    let body_str = response.body().and_then(|b| b.into_string());
+    (response.status(), body_str)
+}
+
+#[test]
+fn resource_methods_are_routed() {
+    assert_eq!(dispatch(Get, "/api/items/7"), (Status::Ok, Some("item 7".into())));
+    assert_eq!(dispatch(Put, "/api/items/7"), (Status::Ok, Some("put 7".into())));
+    assert_eq!(dispatch(Head, "/api/items/7").0, Status::Ok);
+}
+
+#[test]
+fn unimplemented_methods_forward() {
+    assert_eq!(dispatch(Delete, "/api/items/7").0, Status::NotFound);
+    assert_eq!(dispatch(Post, "/api/items/7").0, Status::NotFound);
+    assert_eq!(dispatch(Get, "/api/items/seven").0, Status::NotFound);
+}
@@ -0,0 +1,46 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use rocket::http::RetryAfter;
+use rocket::response::status::{TooManyRequests, ServiceUnavailable};
+
+#[get("/limited")]
+fn limited() -> TooManyRequests<&'static str> {
+    TooManyRequests(Duration::from_secs(30).into(), Some("slow down"))
+}
+
+#[get("/maintenance")]
+fn maintenance() -> ServiceUnavailable<()> {
+    ServiceUnavailable(RetryAfter::Date(UNIX_EPOCH + Duration::from_secs(784111777)), None)
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::Status;
+
+#[test]
+fn too_many_requests_sets_retry_after() {
+    let rocket = rocket::ignite().mount("/", routes![limited, maintenance]);
+    let mut req = MockRequest::new(Get, "/limited");
+    let mut response = req.dispatch_with(&rocket);
+
+    assert_eq!(response.status(), Status::TooManyRequests);
+    assert_eq!(response.headers().get_one("Retry-After"), Some("30"));
+    let body_str = response.body().and_then(|b| b.into_string());
+    assert_eq!(body_str, Some("slow down".to_string()));
+}
+
+#[test]
+fn service_unavailable_sets_retry_after_date() {
+    let rocket = rocket::ignite().mount("/", routes![limited, maintenance]);
+    let mut req = MockRequest::new(Get, "/maintenance");
+    let response = req.dispatch_with(&rocket);
+
+    assert_eq!(response.status(), Status::ServiceUnavailable);
+    assert_eq!(response.headers().get_one("Retry-After"),
+               Some("Sun, 06 Nov 1994 08:49:37 GMT"));
+}
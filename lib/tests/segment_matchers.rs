@@ -0,0 +1,58 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[get("/users/<id:uint>")]
+fn user(id: u64) -> String {
+    format!("user {}", id)
+}
+
+#[get("/users/<name:[a-z-]+>")]
+fn named(name: String) -> String {
+    format!("named {}", name)
+}
+
+#[get("/users/new")]
+fn new_user() -> &'static str {
+    "new"
+}
+
+#[get("/<lang:locale>/about")]
+fn about(lang: String) -> String {
+    format!("about {}", lang)
+}
+
+use rocket::Rocket;
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::Status;
+
+fn rocket() -> Rocket {
+    rocket::ignite()
+        .matcher("locale", |s| s == "en" || s == "de")
+        .mount("/", routes![user, named, new_user, about])
+}
+
+fn get(rocket: &Rocket, uri: &str) -> (Status, Option<String>) {
+    let mut req = MockRequest::new(Get, uri);
+    let mut response = req.dispatch_with(rocket);
+    let body = response.body().and_then(|b| b.into_string());
+    (response.status(), body)
+}
+
+#[test]
+fn matchers_select_routes() {
+    let rocket = rocket();
+    assert_eq!(get(&rocket, "/users/42").1, Some("user 42".into()));
+    assert_eq!(get(&rocket, "/users/jane-doe").1, Some("named jane-doe".into()));
+    assert_eq!(get(&rocket, "/users/new").1, Some("new".into()));
+    assert_eq!(get(&rocket, "/de/about").1, Some("about de".into()));
+}
+
+#[test]
+fn rejected_segments_fall_through() {
+    let rocket = rocket();
+    assert_eq!(get(&rocket, "/users/Jane").0, Status::NotFound);
+    assert_eq!(get(&rocket, "/fr/about").0, Status::NotFound);
+}
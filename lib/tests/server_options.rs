@@ -0,0 +1,51 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+#[get("/<path>")]
+fn get(path: String) -> String {
+    path
+}
+
+#[post("/items")]
+fn post() -> &'static str {
+    "posted"
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+use rocket::http::{Status, Header};
+
+#[test]
+fn options_asterisk_lists_server_methods() {
+    let rocket = rocket::ignite().mount("/", routes![get, post]);
+    let mut req = MockRequest::new(Options, "*");
+    let mut response = req.dispatch_with(&rocket);
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.header_values("Allow").collect::<Vec<_>>(),
+               vec!["OPTIONS, GET, HEAD, POST"]);
+    assert!(response.body().is_none());
+}
+
+#[test]
+fn options_asterisk_can_be_customized() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![get])
+        .server_options(|_, response| {
+            response.set_header(Header::new("Accept-Patch", "application/json"));
+        });
+
+    let mut req = MockRequest::new(Options, "*");
+    let response = req.dispatch_with(&rocket);
+    assert_eq!(response.header_values("Accept-Patch").collect::<Vec<_>>(),
+               vec!["application/json"]);
+}
+
+#[test]
+fn asterisk_is_not_routed() {
+    let rocket = rocket::ignite().mount("/", routes![get]);
+    let mut req = MockRequest::new(Get, "*");
+    assert_eq!(req.dispatch_with(&rocket).status(), Status::NotFound);
+}
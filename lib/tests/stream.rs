@@ -0,0 +1,79 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use std::io::{self, Read, Cursor};
+
+use rocket::response::{Stream, Body, DEFAULT_CHUNK_SIZE};
+
+// A reader that yields `n` bytes, at most `max` at a time, so that the stream
+// can't be read to its end in one go.
+struct Trickle {
+    remaining: usize,
+    max: usize,
+}
+
+impl Read for Trickle {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = ::std::cmp::min(::std::cmp::min(self.remaining, self.max), buf.len());
+        for byte in &mut buf[..n] {
+            *byte = b'a';
+        }
+
+        self.remaining -= n;
+        Ok(n)
+    }
+}
+
+#[get("/trickle")]
+fn trickle() -> Stream<Trickle> {
+    Stream::chunked(Trickle { remaining: 10000, max: 7 }, 512)
+}
+
+#[get("/zero")]
+fn zero() -> Stream<Cursor<&'static str>> {
+    Stream::chunked(Cursor::new("hello, world!"), 0)
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::Method::*;
+
+#[test]
+fn stream_is_sent_chunked_with_its_chunk_size() {
+    let rocket = rocket::ignite().mount("/", routes![trickle]);
+    let mut req = MockRequest::new(Get, "/trickle");
+    let mut response = req.dispatch_with(&rocket);
+
+    let body = response.take_body().expect("a body");
+    assert!(body.is_chunked());
+    match body {
+        Body::Chunked(mut reader, chunk_size) => {
+            assert_eq!(chunk_size, 512);
+
+            let mut string = String::new();
+            reader.read_to_string(&mut string).unwrap();
+            assert_eq!(string.len(), 10000);
+            assert!(string.bytes().all(|b| b == b'a'));
+        }
+        _ => unreachable!()
+    }
+}
+
+#[test]
+fn zero_chunk_size_uses_the_default() {
+    let rocket = rocket::ignite().mount("/", routes![zero]);
+    let mut req = MockRequest::new(Get, "/zero");
+    let mut response = req.dispatch_with(&rocket);
+
+    match response.take_body() {
+        Some(Body::Chunked(mut reader, chunk_size)) => {
+            assert_eq!(chunk_size, DEFAULT_CHUNK_SIZE);
+
+            let mut string = String::new();
+            reader.read_to_string(&mut string).unwrap();
+            assert_eq!(string, "hello, world!");
+        }
+        _ => panic!("expected a chunked body")
+    }
+}
@@ -0,0 +1,86 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::Rocket;
+use rocket::tenant::{Tenant, TenantSource, TenantState};
+
+struct Pool(&'static str);
+
+#[get("/whoami")]
+fn whoami(tenant: Tenant) -> String {
+    tenant.name().to_string()
+}
+
+#[get("/whoami", rank = 2)]
+fn anonymous() -> &'static str {
+    "nobody"
+}
+
+#[get("/db?<query>")]
+fn db(pool: TenantState<Pool>, query: &str) -> String {
+    format!("{}: {}", pool.0, query)
+}
+
+fn app(source: TenantSource) -> Rocket {
+    rocket::ignite()
+        .mount("/", routes![whoami, anonymous, db])
+        .tenants(source)
+        .tenant_state("acme", Pool("acme-db"))
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::{Header, Status};
+use rocket::http::Method::*;
+
+fn get(rocket: &Rocket, uri: &str, header: Option<Header<'static>>) -> (Status, Option<String>) {
+    let mut req = MockRequest::new(Get, uri);
+    if let Some(header) = header {
+        req = req.header(header);
+    }
+
+    let mut response = req.dispatch_with(rocket);
+    (response.status(), response.body().and_then(|b| b.into_string()))
+}
+
+#[test]
+fn tenant_from_subdomain() {
+    let rocket = app(TenantSource::Subdomain("example.com".into()));
+    let host = |name: &'static str| Some(Header::new("Host", name));
+
+    assert_eq!(get(&rocket, "/whoami", host("ACME.example.com")).1, Some("acme".into()));
+    assert_eq!(get(&rocket, "/whoami", host("example.com")).1, Some("nobody".into()));
+    assert_eq!(get(&rocket, "/whoami", host("a.b.example.com")).1, Some("nobody".into()));
+    assert_eq!(get(&rocket, "/whoami", host("acme.example.org")).1, Some("nobody".into()));
+    assert_eq!(get(&rocket, "/whoami", host("xexample.com")).1, Some("nobody".into()));
+}
+
+#[test]
+fn tenant_from_header() {
+    let rocket = app(TenantSource::Header("X-Tenant".into()));
+    let tenant = |name: &'static str| Some(Header::new("X-Tenant", name));
+
+    assert_eq!(get(&rocket, "/whoami", tenant("globex")).1, Some("globex".into()));
+    assert_eq!(get(&rocket, "/whoami", tenant("../etc")).1, Some("nobody".into()));
+    assert_eq!(get(&rocket, "/whoami", None).1, Some("nobody".into()));
+}
+
+#[test]
+fn tenant_from_path_prefix() {
+    let rocket = app(TenantSource::PathPrefix);
+
+    assert_eq!(get(&rocket, "/acme/whoami", None).1, Some("acme".into()));
+    assert_eq!(get(&rocket, "/acme/db?query=x", None).1, Some("acme-db: query=x".into()));
+    assert_eq!(get(&rocket, "/whoami", None).0, Status::NotFound);
+}
+
+#[test]
+fn tenant_state_is_per_tenant() {
+    let rocket = app(TenantSource::Header("X-Tenant".into()));
+    let tenant = |name: &'static str| Some(Header::new("X-Tenant", name));
+
+    assert_eq!(get(&rocket, "/db?query=y", tenant("acme")).1, Some("acme-db: query=y".into()));
+    assert_eq!(get(&rocket, "/db?query=y", tenant("globex")).0, Status::NotFound);
+    assert_eq!(get(&rocket, "/db?query=y", None).0, Status::NotFound);
+}
@@ -0,0 +1,55 @@
+#![feature(plugin)]
+#![plugin(rocket_codegen)]
+
+extern crate rocket;
+
+use rocket::Response;
+use rocket::hook::{self, Predicate};
+use rocket::http::{AcceptLanguage, Header};
+
+#[get("/greeting")]
+fn greeting(languages: AcceptLanguage) -> &'static str {
+    match languages.negotiate(&["en", "fr"]) {
+        Some("fr") => "bonjour",
+        _ => "hello"
+    }
+}
+
+#[post("/", format = "application/json")]
+fn json() -> &'static str {
+    "json"
+}
+
+#[get("/cached")]
+fn cached() -> Response<'static> {
+    Response::build().header(Header::new("Vary", "Accept-Encoding")).finalize()
+}
+
+use rocket::testing::MockRequest;
+use rocket::http::{ContentType, Method};
+use rocket::http::Method::*;
+
+fn vary_of(rocket: &rocket::Rocket, method: Method, uri: &str) -> Option<String> {
+    let mut req = MockRequest::new(method, uri).header(ContentType::JSON);
+    let response = req.dispatch_with(rocket);
+    let values: Vec<_> = response.header_values("Vary").collect();
+    if values.is_empty() { None } else { Some(values.join(", ")) }
+}
+
+#[test]
+fn negotiated_headers_are_added_to_vary() {
+    let rocket = rocket::ignite()
+        .mount("/", routes![greeting, json, cached])
+        .on_response(Predicate::any(), hook::vary);
+
+    assert_eq!(vary_of(&rocket, Get, "/greeting"), Some("Accept-Language".into()));
+    assert_eq!(vary_of(&rocket, Post, "/"), Some("Content-Type".into()));
+    assert_eq!(vary_of(&rocket, Get, "/cached"), Some("Accept-Encoding".into()));
+}
+
+#[test]
+fn vary_is_opt_in() {
+    let rocket = rocket::ignite().mount("/", routes![greeting, json]);
+    assert_eq!(vary_of(&rocket, Get, "/greeting"), None);
+    assert_eq!(vary_of(&rocket, Post, "/"), None);
+}